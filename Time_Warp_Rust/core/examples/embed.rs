@@ -0,0 +1,37 @@
+//! Run a Time Warp program headlessly and save its drawing
+//!
+//! ```text
+//! cargo run -p time_warp_core --example embed [out.png]
+//! ```
+
+use time_warp_core::{Language, Session};
+
+const PROGRAM: &str = "\
+10 INPUT \"How many sides\"; N
+20 PRINT \"Drawing the shape\"
+30 FOR I = 1 TO N
+40 FORWARD 80
+50 RIGHT 360 / N
+60 NEXT I
+";
+
+fn main() -> anyhow::Result<()> {
+    let out = std::env::args().nth(1).unwrap_or_else(|| "embed.png".to_string());
+
+    let mut session = Session::new(Language::TempleCode);
+    session.run(PROGRAM)?;
+    while session.waiting_for_input() {
+        println!("{} 6", session.input_prompt().unwrap_or("?").trim_end());
+        session.provide_input("6")?;
+    }
+
+    for line in session.output() {
+        println!("{}", line);
+    }
+    let report = session.report();
+    println!("Drew {} lines; turtle ended at {:?}", report.lines_drawn, report.turtle_position);
+
+    session.canvas().save_png(&out)?;
+    println!("Saved {}", out);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Quick charts for recorded series (BASIC `PLOT`)
+//!
+//! A chart is drawn as ordinary turtle lines and text labels, scaled to fit
+//! the canvas, so it appears in PNG export like any other drawing. The turtle
+//! itself does not move.
+
+use super::{pos2, vec2, Pos2, Rect, Rgba, TurtleLine, TurtleState};
+use crate::i18n::trf;
+
+/// Space left between the plot area and the canvas edge (room for labels)
+const MARGIN: f32 = 24.0;
+/// Matches the default size used by `TurtleState::draw_text`
+const LABEL_SIZE: f32 = 12.0;
+
+/// Plot `ys` against `xs` (or against the sample index when `xs` is `None`)
+///
+/// Points are joined in order with the current pen color; axes use the
+/// turtle's accent color and the min/max of each axis are labelled.
+/// Returns the plot area in turtle coordinates.
+pub fn plot(turtle: &mut TurtleState, ys: &[f64], xs: Option<&[f64]>, title: &str) -> anyhow::Result<Rect> {
+    if ys.is_empty() {
+        return Err(anyhow::anyhow!(trf("basic.plot_empty_series", &[&title])));
+    }
+    let points: Vec<(f64, f64)> = match xs {
+        Some(xs) => xs.iter().copied().zip(ys.iter().copied()).collect(),
+        None => ys.iter().enumerate().map(|(i, &y)| ((i + 1) as f64, y)).collect(),
+    };
+
+    let (x_min, x_max) = min_max(points.iter().map(|p| p.0));
+    let (y_min, y_max) = min_max(points.iter().map(|p| p.1));
+
+    // Plot area in turtle coordinates (negative y is up)
+    let half_w = turtle.canvas_width / 2.0;
+    let half_h = turtle.canvas_height / 2.0;
+    let area = Rect::from_min_max(
+        pos2(-half_w + MARGIN, -half_h + MARGIN),
+        pos2(half_w - MARGIN, half_h - MARGIN),
+    );
+    let to_canvas = |x: f64, y: f64| {
+        pos2(
+            area.left() + (scale(x, x_min, x_max) * area.width() as f64) as f32,
+            area.bottom() - (scale(y, y_min, y_max) * area.height() as f64) as f32,
+        )
+    };
+
+    // Axes along the left and bottom of the plot area
+    let accent = turtle.accent_color;
+    push_line(turtle, area.left_bottom(), area.right_bottom(), accent, 1.0);
+    push_line(turtle, area.left_bottom(), area.left_top(), accent, 1.0);
+
+    let color = turtle.pen_color;
+    let width = turtle.pen_width;
+    let canvas_points: Vec<Pos2> = points.iter().map(|&(x, y)| to_canvas(x, y)).collect();
+    if canvas_points.len() == 1 {
+        // A lone sample still gets a visible dot
+        let p = canvas_points[0];
+        push_line(turtle, p, p + vec2(1.0, 0.0), color, width);
+    }
+    for pair in canvas_points.windows(2) {
+        push_line(turtle, pair[0], pair[1], color, width);
+    }
+
+    // Min/max labels inside the plot area, title along the top
+    let text_h = LABEL_SIZE + 2.0;
+    turtle.draw_text(area.left() + 4.0, area.top(), &format_value(y_max), accent);
+    turtle.draw_text(area.left() + 4.0, area.bottom() - text_h, &format_value(y_min), accent);
+    turtle.draw_text(area.left(), area.bottom() + 4.0, &format_value(x_min), accent);
+    let x_max_label = format_value(x_max);
+    turtle.draw_text(area.right() - label_width(&x_max_label), area.bottom() + 4.0, &x_max_label, accent);
+    turtle.draw_text(area.center().x - label_width(title) / 2.0, area.top() - text_h - 4.0, title, accent);
+
+    Ok(area)
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+/// Map `v` into 0..=1 across `lo..=hi`; a flat range sits in the middle
+fn scale(v: f64, lo: f64, hi: f64) -> f64 {
+    if hi > lo { (v - lo) / (hi - lo) } else { 0.5 }
+}
+
+fn push_line(turtle: &mut TurtleState, start: Pos2, end: Pos2, color: Rgba, width: f32) {
+    turtle.lines.push(TurtleLine { start, end, color, width });
+}
+
+/// Approximate rendered width of a label, matching the export font's advance
+fn label_width(text: &str) -> f32 {
+    let scale = (LABEL_SIZE / super::font::GLYPH_HEIGHT as f32).round().max(1.0);
+    text.chars().count() as f32 * (super::font::GLYPH_WIDTH + 1) as f32 * scale
+}
+
+fn format_value(v: f64) -> String {
+    if v.fract() == 0.0 && v.abs() < 1e9 {
+        format!("{}", v as i64)
+    } else {
+        let s = format!("{:.2}", v);
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
@@ -0,0 +1,68 @@
+//! Colors for the headless canvas
+//!
+//! The core has no GUI dependencies, so turtle colors use this small type
+//! instead of egui's `Color32`. Front ends convert at their boundary (the
+//! Time Warp app does it in `graphics::color32` / `graphics::rgba`).
+
+/// An opaque-or-translucent sRGB color with 8-bit channels, alpha not premultiplied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba([u8; 4]);
+
+impl Rgba {
+    pub const BLACK: Rgba = Rgba::from_rgb(0, 0, 0);
+    pub const WHITE: Rgba = Rgba::from_rgb(255, 255, 255);
+    pub const GRAY: Rgba = Rgba::from_rgb(160, 160, 160);
+    pub const RED: Rgba = Rgba::from_rgb(255, 0, 0);
+
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba([r, g, b, 255])
+    }
+
+    pub const fn from_rgba_unmultiplied(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba([r, g, b, a])
+    }
+
+    pub const fn r(self) -> u8 {
+        self.0[0]
+    }
+
+    pub const fn g(self) -> u8 {
+        self.0[1]
+    }
+
+    pub const fn b(self) -> u8 {
+        self.0[2]
+    }
+
+    pub const fn a(self) -> u8 {
+        self.0[3]
+    }
+
+    /// `[r, g, b, a]`
+    pub const fn to_array(self) -> [u8; 4] {
+        self.0
+    }
+
+    /// `#RRGGBB` (alpha is dropped)
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.r(), self.g(), self.b())
+    }
+}
+
+impl Default for Rgba {
+    fn default() -> Self {
+        Rgba::BLACK
+    }
+}
+
+impl From<[u8; 4]> for Rgba {
+    fn from(rgba: [u8; 4]) -> Self {
+        Rgba(rgba)
+    }
+}
+
+impl From<Rgba> for [u8; 4] {
+    fn from(color: Rgba) -> Self {
+        color.0
+    }
+}
@@ -0,0 +1,187 @@
+//! Ruler and protractor measurements on the turtle canvas
+//!
+//! The Output panel's measuring tools collect clicks in turtle coordinates:
+//! two for the ruler, three for the protractor, named as in angle ABC (a
+//! point on one arm, the vertex, a point on the other arm). A finished set of
+//! clicks becomes a [`Measurement`], whose [`Measurement::segments`] are
+//! drawn over the canvas and, when asked for, into a saved image. They never
+//! become turtle lines, so a program's drawing is left as it was.
+
+use super::{Pos2, Vec2};
+use crate::i18n::{tr, trf};
+
+/// Radius of the protractor's arc, unless an arm is shorter than twice this
+const ARC_RADIUS: f32 = 24.0;
+
+/// Straight pieces the protractor's arc is drawn with
+const ARC_STEPS: usize = 24;
+
+/// Distance between two points, in turtle steps
+pub fn distance(a: Pos2, b: Pos2) -> f32 {
+    a.distance(b)
+}
+
+/// Angle ABC at `vertex` between the arms to `a` and `b`, in degrees (0–180)
+///
+/// `None` when either arm has no length, so there is no angle to measure.
+pub fn angle_at(vertex: Pos2, a: Pos2, b: Pos2) -> Option<f32> {
+    let (arm_a, arm_b) = (a - vertex, b - vertex);
+    if arm_a.length_sq() == 0.0 || arm_b.length_sq() == 0.0 {
+        return None;
+    }
+    let cross = arm_a.x * arm_b.y - arm_a.y * arm_b.x;
+    Some(cross.abs().atan2(arm_a.dot(arm_b)).to_degrees())
+}
+
+/// Which measuring tool the canvas clicks go to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MeasureTool {
+    #[default]
+    Off,
+    Ruler,
+    Protractor,
+}
+
+impl MeasureTool {
+    /// Clicks one measurement takes
+    pub fn clicks(self) -> usize {
+        match self {
+            MeasureTool::Off => 0,
+            MeasureTool::Ruler => 2,
+            MeasureTool::Protractor => 3,
+        }
+    }
+
+    /// What to click next, with `placed` clicks made so far
+    pub fn hint(self, placed: usize) -> &'static str {
+        match (self, placed) {
+            (MeasureTool::Off, _) => "",
+            (MeasureTool::Ruler, 0) => tr("measure.hint.ruler_start"),
+            (MeasureTool::Ruler, _) => tr("measure.hint.ruler_end"),
+            (MeasureTool::Protractor, 0) => tr("measure.hint.first_arm"),
+            (MeasureTool::Protractor, 1) => tr("measure.hint.vertex"),
+            (MeasureTool::Protractor, _) => tr("measure.hint.second_arm"),
+        }
+    }
+
+    /// The measurement `points` make, once the tool has all its clicks
+    pub fn measure(self, points: &[Pos2]) -> Option<Measurement> {
+        match (self, points) {
+            (MeasureTool::Ruler, &[from, to]) => Some(Measurement::Distance { from, to }),
+            (MeasureTool::Protractor, &[a, vertex, b]) => Some(Measurement::Angle { a, vertex, b }),
+            _ => None,
+        }
+    }
+}
+
+/// A finished ruler or protractor measurement, in turtle coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Measurement {
+    Distance { from: Pos2, to: Pos2 },
+    Angle { a: Pos2, vertex: Pos2, b: Pos2 },
+}
+
+impl Measurement {
+    /// The readout, as shown beside the canvas and copied to the clipboard
+    pub fn readout(&self) -> String {
+        match *self {
+            Measurement::Distance { from, to } => trf("measure.distance", &[&format!("{:.1}", distance(from, to))]),
+            Measurement::Angle { a, vertex, b } => match angle_at(vertex, a, b) {
+                Some(degrees) => trf("measure.angle", &[&format!("{:.1}", degrees)]),
+                None => tr("measure.angle_undefined").to_string(),
+            },
+        }
+    }
+
+    /// Where the readout is labelled on the canvas
+    pub fn anchor(&self) -> Pos2 {
+        match *self {
+            Measurement::Distance { from, to } => from.lerp(to, 0.5),
+            Measurement::Angle { vertex, .. } => vertex,
+        }
+    }
+
+    /// Straight segments that draw the measurement: the ruler, or the
+    /// protractor's two arms and the arc between them
+    pub fn segments(&self) -> Vec<(Pos2, Pos2)> {
+        match *self {
+            Measurement::Distance { from, to } => vec![(from, to)],
+            Measurement::Angle { a, vertex, b } => {
+                let mut segments = vec![(vertex, a), (vertex, b)];
+                let arc = arc_points(vertex, a, b);
+                segments.extend(arc.windows(2).map(|pair| (pair[0], pair[1])));
+                segments
+            }
+        }
+    }
+}
+
+/// Points along the arc at `vertex` from the arm to `a` to the arm to `b`,
+/// the short way round; empty when there is no angle
+fn arc_points(vertex: Pos2, a: Pos2, b: Pos2) -> Vec<Pos2> {
+    let (arm_a, arm_b) = (a - vertex, b - vertex);
+    let Some(sweep) = angle_at(vertex, a, b) else {
+        return Vec::new();
+    };
+    let radius = ARC_RADIUS.min(arm_a.length().min(arm_b.length()) / 2.0);
+    let start = arm_a.angle();
+    // Turn toward the second arm: the sign of the cross product says which way
+    let turn = if arm_a.x * arm_b.y - arm_a.y * arm_b.x < 0.0 { -sweep } else { sweep }.to_radians();
+    (0..=ARC_STEPS)
+        .map(|i| vertex + Vec2::angled(start + turn * i as f32 / ARC_STEPS as f32) * radius)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::pos2;
+
+    #[test]
+    fn test_distance_and_angle_from_clicks() {
+        assert_eq!(distance(pos2(0.0, 0.0), pos2(30.0, 40.0)), 50.0);
+        let vertex = pos2(10.0, 10.0);
+        let right = angle_at(vertex, pos2(60.0, 10.0), pos2(10.0, -40.0)).unwrap();
+        assert!((right - 90.0).abs() < 1e-4, "{}", right);
+        // The same angle whichever arm is clicked first
+        let acute = angle_at(vertex, pos2(20.0, 20.0), pos2(20.0, 10.0)).unwrap();
+        assert!((acute - 45.0).abs() < 1e-4, "{}", acute);
+        assert!((angle_at(vertex, pos2(20.0, 10.0), pos2(20.0, 20.0)).unwrap() - acute).abs() < 1e-4);
+        let straight = angle_at(vertex, pos2(0.0, 10.0), pos2(20.0, 10.0)).unwrap();
+        assert!((straight - 180.0).abs() < 1e-4, "{}", straight);
+        assert_eq!(angle_at(vertex, vertex, pos2(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_tools_measure_once_all_clicks_are_in() {
+        let points = [pos2(0.0, 0.0), pos2(3.0, 4.0), pos2(3.0, 0.0)];
+        assert_eq!(MeasureTool::Ruler.measure(&points[..1]), None);
+        let ruler = MeasureTool::Ruler.measure(&points[..2]).unwrap();
+        assert_eq!(ruler.readout(), "Distance: 5.0");
+        assert_eq!(ruler.anchor(), pos2(1.5, 2.0));
+        assert_eq!(MeasureTool::Protractor.measure(&points[..2]), None);
+        let protractor = MeasureTool::Protractor.measure(&points).unwrap();
+        assert_eq!(protractor.readout(), "Angle: 36.9°");
+        assert_eq!(MeasureTool::Off.measure(&points), None);
+        let flat = MeasureTool::Protractor.measure(&[points[0], points[0], points[1]]).unwrap();
+        assert_eq!(flat.readout(), "Angle: undefined (an arm has no length)");
+    }
+
+    #[test]
+    fn test_protractor_arc_runs_between_the_arms() {
+        let vertex = pos2(0.0, 0.0);
+        let measurement = Measurement::Angle { a: pos2(100.0, 0.0), vertex, b: pos2(0.0, -100.0) };
+        let segments = measurement.segments();
+        assert_eq!(segments.len(), 2 + ARC_STEPS);
+        let arc: Vec<Pos2> = segments[2..].iter().map(|&(start, _)| start).collect();
+        assert!((arc[0] - pos2(ARC_RADIUS, 0.0)).length() < 1e-3);
+        let end = segments.last().unwrap().1;
+        assert!((end - pos2(0.0, -ARC_RADIUS)).length() < 1e-3, "{:?}", end);
+        // Every arc point stays inside the quarter between the arms
+        assert!(arc.iter().all(|p| p.x >= -1e-3 && p.y <= 1e-3 && (p.to_vec2().length() - ARC_RADIUS).abs() < 1e-3));
+
+        // A short arm pulls the arc in so it stays between the arms
+        let short = Measurement::Angle { a: pos2(10.0, 0.0), vertex, b: pos2(0.0, 100.0) };
+        assert!(short.segments()[2..].iter().all(|&(p, _)| (p.to_vec2().length() - 5.0).abs() < 1e-3));
+    }
+}
@@ -0,0 +1,749 @@
+// Canvas geometry uses egui's math types (the `emath` crate has no GUI parts)
+pub use emath::{pos2, vec2, Pos2, Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, trf};
+
+pub mod chart;
+pub mod color;
+pub mod font;
+pub mod measure;
+pub mod raster;
+pub mod retention;
+pub mod text_screen;
+
+pub use color::Rgba;
+pub use measure::{MeasureTool, Measurement};
+pub use raster::PngOptions;
+pub use retention::Backdrop;
+
+/// A line segment drawn by the turtle
+/// 
+/// Represents a single draw operation with start/end points, color, and width.
+/// Used for rendering and export to image formats.
+#[derive(Debug, Clone)]
+pub struct TurtleLine {
+    pub start: Pos2,
+    pub end: Pos2,
+    pub color: Rgba,
+    pub width: f32,
+}
+
+/// Segments Logo `FLASH n` lights up on the canvas for a moment
+///
+/// Only the canvas shows it; exports and the drawing itself are unchanged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flash {
+    /// Segments counted from the first since the last clear, folded ones included (see `TurtleState::flashed_lines`)
+    pub lines: std::ops::Range<usize>,
+    /// Counts the FLASHes, so the canvas can tell a new one from the one it already showed
+    pub serial: u64,
+}
+
+/// A text label placed on the canvas
+/// 
+/// `pos` is the top-left corner in turtle coordinates. Exported to PNG with
+/// the built-in bitmap font, so labels survive image export.
+#[derive(Debug, Clone)]
+pub struct TurtleText {
+    pub pos: Pos2,
+    pub text: String,
+    pub color: Rgba,
+    pub size: f32,
+}
+
+/// How headings are written in programs (Settings → Canvas, Logo `SETANGLEMODE`)
+///
+/// `TurtleState::heading` is always stored as a compass bearing; the mode only
+/// changes how SETHEADING, HEADING, TOWARDS, and ARC read and report angles.
+/// LEFT and RIGHT turn the same way in both modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AngleMode {
+    /// 0 = north, angles grow clockwise
+    #[default]
+    Compass,
+    /// 0 = east, angles grow counter-clockwise
+    Math,
+}
+
+impl AngleMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            AngleMode::Compass => "Compass",
+            AngleMode::Math => "Math",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().trim_start_matches('"').to_uppercase().as_str() {
+            "COMPASS" => Some(AngleMode::Compass),
+            "MATH" | "MATHEMATICAL" => Some(AngleMode::Math),
+            _ => None,
+        }
+    }
+
+    /// Compass bearing for a heading written in this mode
+    pub fn bearing(self, heading: f32) -> f32 {
+        match self {
+            AngleMode::Compass => heading.rem_euclid(360.0),
+            AngleMode::Math => (90.0 - heading).rem_euclid(360.0),
+        }
+    }
+
+    /// Heading in this mode for a compass bearing
+    pub fn heading(self, bearing: f32) -> f32 {
+        // The conversion is its own inverse
+        self.bearing(bearing)
+    }
+
+    /// Clockwise sweep for an angle measured in this mode's positive direction
+    pub fn clockwise(self, angle: f32) -> f32 {
+        match self {
+            AngleMode::Compass => angle,
+            AngleMode::Math => -angle,
+        }
+    }
+}
+
+/// Where BASIC's LINE and CIRCLE put (0, 0)
+///
+/// Drawing is stored in the centered canvas space either way, so the canvas
+/// and the PNG and SVG exports need not know which mode drew a line. Logo's
+/// turtle commands always use the centered space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CoordinateMode {
+    /// (0, 0) at the centre of the canvas
+    #[default]
+    Centered,
+    /// (0, 0) at the top-left corner, as in GW-BASIC listings
+    ScreenTopLeft,
+}
+
+impl CoordinateMode {
+    /// Canvas position of the point (`x`, `y`) on a `width` × `height` canvas
+    pub fn to_canvas(self, x: f32, y: f32, width: f32, height: f32) -> (f32, f32) {
+        match self {
+            CoordinateMode::Centered => (x, y),
+            // The canvas already grows y downward, so only the origin moves
+            CoordinateMode::ScreenTopLeft => (x - width / 2.0, y - height / 2.0),
+        }
+    }
+}
+
+/// Outline the turtle is drawn with (Settings → Canvas, Logo `SETSHAPE`)
+///
+/// Outlines are points around (0, 0) in canvas orientation (y grows
+/// downward) with the turtle facing up, reaching about 1 unit from the
+/// centre; [`place_shape`] turns and scales them into place.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum TurtleShape {
+    /// A disc with a heading tick (no outline)
+    #[default]
+    Circle,
+    Triangle,
+    Turtle,
+    Arrow,
+    Rocket,
+    /// Loaded from a points file (see [`TurtleShape::from_points_text`])
+    Custom(Vec<[f32; 2]>),
+}
+
+const TRIANGLE_POINTS: &[[f32; 2]] = &[[0.0, -1.0], [0.8, 0.7], [-0.8, 0.7]];
+
+const ARROW_POINTS: &[[f32; 2]] = &[
+    [0.0, -1.0], [0.7, -0.2], [0.25, -0.2], [0.25, 1.0], [-0.25, 1.0], [-0.25, -0.2], [-0.7, -0.2],
+];
+
+const ROCKET_POINTS: &[[f32; 2]] = &[
+    [0.0, -1.2], [0.3, -0.7], [0.3, 0.4], [0.65, 0.9], [0.3, 0.8], [0.15, 1.0],
+    [-0.15, 1.0], [-0.3, 0.8], [-0.65, 0.9], [-0.3, 0.4], [-0.3, -0.7],
+];
+
+/// Head, four legs, and a tail around the shell
+const TURTLE_POINTS: &[[f32; 2]] = &[
+    [0.0, -1.15], [0.18, -1.0], [0.15, -0.72], [0.45, -0.9], [0.62, -0.7], [0.45, -0.5],
+    [0.55, -0.1], [0.5, 0.35], [0.68, 0.62], [0.5, 0.8], [0.3, 0.62], [0.1, 0.72], [0.0, 0.98],
+    [-0.1, 0.72], [-0.3, 0.62], [-0.5, 0.8], [-0.68, 0.62], [-0.5, 0.35], [-0.55, -0.1],
+    [-0.45, -0.5], [-0.62, -0.7], [-0.45, -0.9], [-0.15, -0.72], [-0.18, -1.0],
+];
+
+impl TurtleShape {
+    /// Shapes offered by name (Settings and `SETSHAPE`)
+    pub const BUILT_IN: [TurtleShape; 5] =
+        [TurtleShape::Circle, TurtleShape::Triangle, TurtleShape::Turtle, TurtleShape::Arrow, TurtleShape::Rocket];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TurtleShape::Circle => "Circle",
+            TurtleShape::Triangle => "Triangle",
+            TurtleShape::Turtle => "Turtle",
+            TurtleShape::Arrow => "Arrow",
+            TurtleShape::Rocket => "Rocket",
+            TurtleShape::Custom(_) => "Custom",
+        }
+    }
+
+    /// A built-in shape by name, as in `SETSHAPE "TURTLE`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().trim_start_matches('"');
+        Self::BUILT_IN.into_iter().find(|shape| shape.name().eq_ignore_ascii_case(name))
+    }
+
+    /// The outline in shape units; `None` for the circle
+    pub fn points(&self) -> Option<&[[f32; 2]]> {
+        match self {
+            TurtleShape::Circle => None,
+            TurtleShape::Triangle => Some(TRIANGLE_POINTS),
+            TurtleShape::Turtle => Some(TURTLE_POINTS),
+            TurtleShape::Arrow => Some(ARROW_POINTS),
+            TurtleShape::Rocket => Some(ROCKET_POINTS),
+            TurtleShape::Custom(points) => Some(points),
+        }
+    }
+
+    /// A custom shape from a points file: one `x y` (or `x, y`) pair per line, `#` comments
+    ///
+    /// Points are written the way a drawing on paper reads (y grows upward)
+    /// with the turtle facing up; the outline is scaled so its farthest point
+    /// is 1 unit from (0, 0).
+    pub fn from_points_text(text: &str) -> anyhow::Result<Self> {
+        let mut points = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let coords: Vec<f32> = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|w| !w.is_empty())
+                .map(|w| w.parse::<f32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| anyhow::anyhow!(trf("logo.shape_bad_line", &[&(number + 1), &line])))?;
+            match coords[..] {
+                [x, y] if x.is_finite() && y.is_finite() => points.push([x, -y]),
+                _ => anyhow::bail!(trf("logo.shape_bad_line", &[&(number + 1), &line])),
+            }
+        }
+        if points.len() < 3 {
+            anyhow::bail!(trf("logo.shape_too_few", &[&points.len()]));
+        }
+        let reach = points.iter().map(|[x, y]| x.hypot(*y)).fold(0.0, f32::max);
+        if reach == 0.0 {
+            anyhow::bail!(tr("logo.shape_at_origin"));
+        }
+        Ok(TurtleShape::Custom(points.into_iter().map(|[x, y]| [x / reach, y / reach]).collect()))
+    }
+}
+
+/// How far a shape reaches, in turtle marker radii: as far as the circle's heading tick
+pub const SHAPE_REACH: f32 = 1.5;
+
+/// Shape `points` scaled by `size`, turned to compass `heading`, and moved to `at`
+///
+/// Works in any coordinates whose y grows downward: turtle (canvas) space,
+/// or screen space for a marker of fixed pixel size.
+pub fn place_shape(points: &[[f32; 2]], at: Pos2, heading: f32, size: f32) -> Vec<Pos2> {
+    let (sin, cos) = heading.to_radians().sin_cos();
+    points
+        .iter()
+        .map(|&[x, y]| at + vec2(x * cos - y * sin, x * sin + y * cos) * size)
+        .collect()
+}
+
+/// Twice the signed area of a polygon (positive when clockwise on screen)
+fn doubled_area(points: &[Pos2]) -> f32 {
+    let n = points.len();
+    (0..n).map(|i| {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        a.x * b.y - b.x * a.y
+    }).sum()
+}
+
+fn cross(o: Pos2, a: Pos2, b: Pos2) -> f32 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+/// Triangles covering a simple polygon, as index triples into `points` (ear clipping)
+///
+/// Painters fill only convex pieces, and the turtle shapes are not convex.
+pub fn triangulate(points: &[Pos2]) -> Vec<[usize; 3]> {
+    let orientation = doubled_area(points).signum();
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::new();
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let corner = |i: usize| (remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]);
+        let ear = (0..m).find(|&i| {
+            let (a, b, c) = corner(i);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+            // Convex corner with no other vertex inside, or on the edges of, the triangle it cuts off
+            cross(pa, pb, pc) * orientation > 0.0
+                && !remaining.iter().any(|&p| {
+                    let q = points[p];
+                    ![a, b, c].contains(&p)
+                        && cross(pa, pb, q) * orientation >= 0.0
+                        && cross(pb, pc, q) * orientation >= 0.0
+                        && cross(pc, pa, q) * orientation >= 0.0
+                })
+        });
+        if let Some(i) = ear {
+            let (a, b, c) = corner(i);
+            triangles.push([a, b, c]);
+            remaining.remove(i);
+        } else if let Some(i) = (0..m).find(|&i| {
+            let (a, b, c) = corner(i);
+            cross(points[a], points[b], points[c]) == 0.0
+        }) {
+            // A vertex in the middle of a straight edge covers nothing
+            remaining.remove(i);
+        } else {
+            break;
+        }
+    }
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+    triangles
+}
+
+/// Turtle graphics state for Logo-style drawing
+/// 
+/// Maintains turtle position, heading, pen state, and drawing history.
+/// Canvas coordinates: (0,0) is center, Y-axis inverted (up is negative).
+/// 
+/// # Example
+/// ```ignore
+/// let mut turtle = TurtleState::new();
+/// turtle.forward(100.0);  // Move forward 100 pixels
+/// turtle.right(90.0);     // Turn right 90 degrees
+/// turtle.forward(100.0);  // Draw an L shape
+/// ```
+#[derive(Clone)]
+pub struct TurtleState {
+    pub x: f32,
+    pub y: f32,
+    pub heading: f32, // degrees, 0 = up
+    pub pen_down: bool,
+    pub pen_color: Rgba,
+    pub pen_width: f32,
+    pub canvas_width: f32,
+    pub canvas_height: f32,
+    pub lines: Vec<TurtleLine>,
+    pub texts: Vec<TurtleText>,
+    pub visible: bool,
+    pub bg_color: Rgba,
+    pub accent_color: Rgba, // chart axes; synced from the UI theme
+    /// Bumped by `clear`, so a copy fed by streamed lines knows to start over
+    pub generation: u64,
+    /// How the turtle is drawn
+    pub shape: TurtleShape,
+    /// Where BASIC drawing statements put (0, 0)
+    pub coordinate_mode: CoordinateMode,
+    /// Colors drawn segments take in turn (Logo `SETCOLORCYCLE`); empty for the pen color
+    pub color_cycle: Vec<Rgba>,
+    /// Entry of `color_cycle` the next segment takes
+    pub cycle_position: usize,
+    /// The last `FLASH`, until the canvas is cleared
+    pub flash: Option<Flash>,
+    /// FLASHes so far, for `Flash::serial`
+    pub flashes: u64,
+    /// The oldest lines, folded into an image to stay within `line_budget`
+    pub backdrop: Option<Backdrop>,
+    /// Most lines kept before the oldest are folded into `backdrop` (see [`retention`]); `None` keeps every line
+    pub line_budget: Option<usize>,
+}
+
+/// The headless canvas an embedding program reads (`Session::canvas`)
+pub type TurtleCanvas = TurtleState;
+
+impl TurtleState {
+    pub fn new() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            heading: 0.0,
+            pen_down: true,
+            pen_color: Rgba::WHITE,
+            pen_width: 2.0,
+            canvas_width: 800.0,
+            canvas_height: 600.0,
+            lines: Vec::new(),
+            texts: Vec::new(),
+            visible: true,
+            bg_color: Rgba::from_rgb(10, 10, 20),
+            accent_color: Rgba::from_rgb(100, 150, 255),
+            generation: 0,
+            shape: TurtleShape::default(),
+            coordinate_mode: CoordinateMode::default(),
+            color_cycle: Vec::new(),
+            cycle_position: 0,
+            flash: None,
+            flashes: 0,
+            backdrop: None,
+            line_budget: None,
+        }
+    }
+    
+    /// Unit vector the turtle faces, in canvas coordinates (also used to draw the turtle icon)
+    pub fn direction(&self) -> Vec2 {
+        let rad = self.heading.to_radians();
+        vec2(rad.sin(), -rad.cos()) // Y is inverted in screen coordinates
+    }
+    
+    /// The turtle's outline in canvas coordinates, `size` units from its centre; `None` for the circle
+    pub fn shape_outline(&self, size: f32) -> Option<Vec<Pos2>> {
+        self.shape.points().map(|points| place_shape(points, pos2(self.x, self.y), self.heading, size))
+    }
+    
+    pub fn forward(&mut self, distance: f32) {
+        let old_x = self.x;
+        let old_y = self.y;
+        let step = self.direction() * distance;
+        
+        self.x += step.x;
+        self.y += step.y;
+        
+        if self.pen_down {
+            self.draw_segment(pos2(old_x, old_y), pos2(self.x, self.y));
+        }
+    }
+    
+    /// Add a segment in the pen's width and the next segment color
+    fn draw_segment(&mut self, start: Pos2, end: Pos2) {
+        let color = self.next_segment_color();
+        self.lines.push(TurtleLine { start, end, color, width: self.pen_width });
+    }
+    
+    /// The next color of the cycle (moving it on), else the pen color
+    fn next_segment_color(&mut self) -> Rgba {
+        if self.color_cycle.is_empty() {
+            return self.pen_color;
+        }
+        let color = self.color_cycle[self.cycle_position % self.color_cycle.len()];
+        self.cycle_position = (self.cycle_position + 1) % self.color_cycle.len();
+        color
+    }
+    
+    /// Draw each later segment in the next of `colors`, from the first; no colors turns cycling off
+    ///
+    /// The color is fixed when the segment is drawn, so exports show the same colors as the canvas.
+    pub fn set_color_cycle(&mut self, colors: Vec<Rgba>) {
+        self.color_cycle = colors;
+        self.cycle_position = 0;
+    }
+    
+    /// Light up the last `count` segments on the canvas (Logo `FLASH`)
+    pub fn flash(&mut self, count: usize) {
+        self.flashes += 1;
+        let end = self.total_lines();
+        self.flash = Some(Flash { lines: end.saturating_sub(count)..end, serial: self.flashes });
+    }
+    
+    pub fn back(&mut self, distance: f32) {
+        self.forward(-distance);
+    }
+    
+    pub fn left(&mut self, angle: f32) {
+        self.heading -= angle;
+        self.heading = self.heading.rem_euclid(360.0);
+    }
+    
+    pub fn right(&mut self, angle: f32) {
+        self.heading += angle;
+        self.heading = self.heading.rem_euclid(360.0);
+    }
+    
+    /// Compass bearing from the turtle to (x, y); the current heading if already there
+    pub fn towards(&self, x: f32, y: f32) -> f32 {
+        let (dx, dy) = (x - self.x, y - self.y);
+        if dx == 0.0 && dy == 0.0 {
+            return self.heading;
+        }
+        dx.atan2(-dy).to_degrees().rem_euclid(360.0)
+    }
+    
+    /// Draw an arc of `radius` centred on the turtle, starting at its heading
+    ///
+    /// Positive `angle` sweeps clockwise. The turtle does not move; nothing is
+    /// drawn with the pen up.
+    pub fn arc(&mut self, angle: f32, radius: f32) {
+        if !self.pen_down || angle == 0.0 {
+            return;
+        }
+        let center = pos2(self.x, self.y);
+        let steps = (angle.abs() / 5.0).ceil().max(1.0) as usize;
+        let point = |bearing: f32| {
+            let rad = bearing.to_radians();
+            center + vec2(rad.sin(), -rad.cos()) * radius
+        };
+        let mut prev = point(self.heading);
+        for i in 1..=steps {
+            let next = point(self.heading + angle * i as f32 / steps as f32);
+            self.draw_segment(prev, next);
+            prev = next;
+        }
+    }
+    
+    pub fn goto(&mut self, x: f32, y: f32) {
+        if self.pen_down {
+            self.draw_segment(pos2(self.x, self.y), pos2(x, y));
+        }
+        self.x = x;
+        self.y = y;
+    }
+    
+    /// Canvas position of a BASIC drawing statement's (`x`, `y`), in the current coordinate mode
+    pub fn basic_point(&self, x: f32, y: f32) -> (f32, f32) {
+        self.coordinate_mode.to_canvas(x, y, self.canvas_width, self.canvas_height)
+    }
+    
+    pub fn home(&mut self) {
+        self.goto(0.0, 0.0);
+        self.heading = 0.0;
+    }
+    
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.texts.clear();
+        self.backdrop = None;
+        self.flash = None;
+        self.generation += 1;
+    }
+    
+    /// Every segment drawn since the last clear and not folded into the backdrop, oldest first
+    pub fn lines(&self) -> &[TurtleLine] {
+        &self.lines
+    }
+    
+    /// Smallest rectangle containing every drawn segment, or `None` before anything is drawn
+    pub fn bounds(&self) -> Option<Rect> {
+        let folded = self.backdrop.as_ref().map(|backdrop| backdrop.bounds);
+        let mut points = self.lines.iter().flat_map(|l| [l.start, l.end]);
+        let Some(first) = points.next() else { return folded };
+        let drawn = points.fold(Rect::from_min_max(first, first), |rect, p| rect.union(Rect::from_min_max(p, p)));
+        Some(folded.map_or(drawn, |folded| folded.union(drawn)))
+    }
+    
+    /// Place a text label with its top-left corner at (x, y)
+    pub fn draw_text(&mut self, x: f32, y: f32, text: &str, color: Rgba) {
+        self.texts.push(TurtleText {
+            pos: pos2(x, y),
+            text: text.to_string(),
+            color,
+            size: 12.0,
+        });
+    }
+    
+    #[allow(dead_code)]
+    pub fn reset(&mut self) {
+        self.x = 0.0;
+        self.y = 0.0;
+        self.heading = 0.0;
+        self.pen_down = true;
+        self.pen_color = Rgba::WHITE;
+        self.pen_width = 2.0;
+        self.set_color_cycle(Vec::new());
+        self.lines.clear();
+        self.texts.clear();
+        self.backdrop = None;
+        self.flash = None;
+        self.visible = true;
+        self.bg_color = Rgba::from_rgb(10, 10, 20);
+        self.coordinate_mode = CoordinateMode::default();
+    }
+}
+
+/// Merge consecutive collinear segments, returning the simplified path and how many were merged
+/// 
+/// Two neighbours merge when they share color and width, connect (within
+/// `tolerance`), point the same way, and their joint lies within `tolerance`
+/// of the combined segment. Turns, pen changes, and jumps are preserved.
+pub fn simplify_path(lines: &[TurtleLine], tolerance: f32) -> (Vec<TurtleLine>, usize) {
+    let mut out: Vec<TurtleLine> = Vec::with_capacity(lines.len());
+    for line in lines {
+        if let Some(last) = out.last_mut() {
+            if can_merge(last, line, tolerance) {
+                last.end = line.end;
+                continue;
+            }
+        }
+        out.push(line.clone());
+    }
+    let merged = lines.len() - out.len();
+    (out, merged)
+}
+
+fn can_merge(a: &TurtleLine, b: &TurtleLine, tolerance: f32) -> bool {
+    if a.color != b.color || a.width != b.width || a.end.distance(b.start) > tolerance {
+        return false;
+    }
+    let da = a.end - a.start;
+    let db = b.end - b.start;
+    // A zero-length piece adds nothing to the drawing
+    if da.length() <= tolerance || db.length() <= tolerance {
+        return true;
+    }
+    if da.dot(db) <= 0.0 {
+        return false;
+    }
+    // Distance from the joint to the chord a.start -> b.end
+    let chord = b.end - a.start;
+    let len = chord.length();
+    if len == 0.0 {
+        return false;
+    }
+    let cross = chord.x * (a.end - a.start).y - chord.y * (a.end - a.start).x;
+    (cross / len).abs() <= tolerance
+}
+
+impl Default for TurtleState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(x0: f32, y0: f32, x1: f32, y1: f32) -> TurtleLine {
+        TurtleLine { start: pos2(x0, y0), end: pos2(x1, y1), color: Rgba::WHITE, width: 2.0 }
+    }
+
+    #[test]
+    fn test_angle_mode_conversions() {
+        assert_eq!(AngleMode::Compass.bearing(-90.0), 270.0);
+        assert_eq!(AngleMode::Math.bearing(0.0), 90.0);
+        assert_eq!(AngleMode::Math.bearing(90.0), 0.0);
+        assert_eq!(AngleMode::Math.heading(180.0), 270.0);
+        assert_eq!(AngleMode::Math.clockwise(30.0), -30.0);
+        assert_eq!(AngleMode::from_name("\"math"), Some(AngleMode::Math));
+        assert_eq!(AngleMode::from_name("degrees"), None);
+
+        let turtle = TurtleState::new();
+        assert_eq!(turtle.towards(10.0, 0.0), 90.0);
+        assert_eq!(turtle.towards(0.0, 10.0), 180.0);
+    }
+
+    fn close(a: Pos2, b: Pos2) -> bool {
+        a.distance(b) < 1e-4
+    }
+
+    #[test]
+    fn test_place_shape_turns_scales_and_moves() {
+        let nose = [[0.0, -1.0]];
+        assert!(close(place_shape(&nose, pos2(0.0, 0.0), 0.0, 10.0)[0], pos2(0.0, -10.0)));
+        // Compass headings: the nose points where FORWARD would go
+        let mut turtle = TurtleState::new();
+        for heading in [0.0, 90.0, 135.0, 270.0] {
+            turtle.heading = heading;
+            let placed = place_shape(&nose, pos2(5.0, 5.0), heading, 2.0)[0];
+            assert!(close(placed, pos2(5.0, 5.0) + turtle.direction() * 2.0), "{}: {:?}", heading, placed);
+        }
+        turtle.shape = TurtleShape::Triangle;
+        turtle.heading = 90.0;
+        let outline = turtle.shape_outline(10.0).unwrap();
+        assert!(close(outline[0], pos2(10.0, 0.0)));
+        assert!(close(outline[1], pos2(-7.0, 8.0)));
+        assert!(TurtleShape::Circle.points().is_none());
+    }
+
+    #[test]
+    fn test_triangulation_covers_each_shape() {
+        for shape in TurtleShape::BUILT_IN.iter().skip(1) {
+            let outline = place_shape(shape.points().unwrap(), pos2(0.0, 0.0), 30.0, 10.0);
+            let triangles = triangulate(&outline);
+            assert_eq!(triangles.len(), outline.len() - 2, "{}", shape.name());
+            let covered: f32 = triangles.iter().map(|&[a, b, c]| cross(outline[a], outline[b], outline[c]).abs()).sum();
+            let area = doubled_area(&outline).abs();
+            assert!((covered - area).abs() < area * 1e-4, "{}: {} vs {}", shape.name(), covered, area);
+        }
+    }
+
+    #[test]
+    fn test_shapes_by_name_and_from_points() {
+        assert_eq!(TurtleShape::from_name("\"turtle"), Some(TurtleShape::Turtle));
+        assert_eq!(TurtleShape::from_name("Custom"), None);
+        let shape = TurtleShape::from_points_text("# kite\n0 2\n1, 0\n0 -1  # tail\n-1 0\n").unwrap();
+        assert_eq!(shape, TurtleShape::Custom(vec![[0.0, -1.0], [0.5, 0.0], [0.0, 0.5], [-0.5, 0.0]]));
+        assert!(TurtleShape::from_points_text("0 1\n1 0").unwrap_err().to_string().contains("at least 3 points"));
+        assert!(TurtleShape::from_points_text("0 1\n1\n2 2").unwrap_err().to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_color_cycle_colors_each_segment_in_turn() {
+        let (green, blue) = (Rgba::from_rgb(0, 255, 0), Rgba::from_rgb(0, 0, 255));
+        let mut turtle = TurtleState::new();
+        turtle.set_color_cycle(vec![Rgba::RED, green, blue]);
+        turtle.forward(10.0);
+        turtle.goto(20.0, 20.0);
+        turtle.pen_down = false;
+        turtle.forward(5.0); // Moving with the pen up takes no color
+        turtle.pen_down = true;
+        turtle.arc(10.0, 5.0); // Two pieces
+        turtle.back(5.0);
+        let colors: Vec<Rgba> = turtle.lines.iter().map(|l| l.color).collect();
+        assert_eq!(colors, [Rgba::RED, green, blue, Rgba::RED, green]);
+
+        turtle.set_color_cycle(Vec::new());
+        turtle.forward(1.0);
+        assert_eq!(turtle.lines.last().unwrap().color, turtle.pen_color);
+    }
+
+    #[test]
+    fn test_flash_marks_the_last_segments_until_cleared() {
+        let mut turtle = TurtleState::new();
+        for _ in 0..4 {
+            turtle.forward(1.0);
+        }
+        turtle.flash(3);
+        assert_eq!(turtle.flash, Some(Flash { lines: 1..4, serial: 1 }));
+        turtle.flash(10);
+        assert_eq!(turtle.flash, Some(Flash { lines: 0..4, serial: 2 }));
+        turtle.clear();
+        assert_eq!(turtle.flash, None);
+        turtle.flash(1);
+        assert_eq!(turtle.flash.as_ref().map(|f| f.serial), Some(3), "a new flash after a clear is still new");
+    }
+
+    #[test]
+    fn test_simplify_collapses_collinear_chain() {
+        let mut turtle = TurtleState::new();
+        for _ in 0..10 {
+            turtle.forward(5.0);
+        }
+        let (simplified, merged) = simplify_path(&turtle.lines, 0.01);
+        assert_eq!(merged, 9);
+        assert_eq!(simplified.len(), 1);
+        assert!(simplified[0].start.distance(pos2(0.0, 0.0)) < 0.01);
+        assert!(simplified[0].end.distance(pos2(0.0, -50.0)) < 0.01);
+    }
+
+    #[test]
+    fn test_simplify_preserves_corners_and_reversals() {
+        let square: Vec<TurtleLine> = vec![
+            seg(0.0, 0.0, 10.0, 0.0), seg(10.0, 0.0, 20.0, 0.0),
+            seg(20.0, 0.0, 20.0, 20.0),
+            seg(20.0, 20.0, 0.0, 20.0),
+            seg(0.0, 20.0, 0.0, 30.0), seg(0.0, 30.0, 0.0, 25.0),
+        ];
+        let (simplified, merged) = simplify_path(&square, 0.01);
+        assert_eq!(merged, 1);
+        assert_eq!(simplified.len(), 5);
+        assert_eq!(simplified[0].end, pos2(20.0, 0.0));
+    }
+
+    #[test]
+    fn test_simplify_respects_tolerance_gaps_and_style() {
+        let bent = vec![seg(0.0, 0.0, 10.0, 0.0), seg(10.0, 0.0, 20.0, 0.5)];
+        assert_eq!(simplify_path(&bent, 0.1).1, 0);
+        assert_eq!(simplify_path(&bent, 0.5).1, 1);
+
+        let gap = vec![seg(0.0, 0.0, 10.0, 0.0), seg(12.0, 0.0, 20.0, 0.0)];
+        assert_eq!(simplify_path(&gap, 0.1).1, 0);
+
+        let mut recolored = vec![seg(0.0, 0.0, 10.0, 0.0), seg(10.0, 0.0, 20.0, 0.0)];
+        recolored[1].color = Rgba::RED;
+        assert_eq!(simplify_path(&recolored, 0.1).1, 0);
+    }
+}
@@ -0,0 +1,489 @@
+//! PNG rendering of the turtle canvas
+//!
+//! Points go through the same `RectTransform` the IDE's canvas uses (turtle
+//! (0,0) at the image centre, y growing downward), so an export is never
+//! mirrored relative to the display. Lines are anti-aliased strokes of their
+//! own width with round ends, which also closes the joints of a turtle path;
+//! a zero-length line comes out as a dot. Text uses the bitmap font in `font`.
+//!
+//! An export can be larger than the canvas ([`ExportSize`]): the transform
+//! itself is scaled, so lines, labels, and the turtle are drawn thicker and
+//! larger at the new size rather than a small bitmap being blown up, and the
+//! background can be left transparent.
+//!
+//! A thumbnail is rendered differently: the drawing, not the whole canvas, is
+//! fitted into the image with a small margin, so a small drawing in the
+//! middle of a large canvas still fills its preview.
+
+use emath::RectTransform;
+use image::{Rgba as Pixel, RgbaImage};
+
+use super::{font, pos2, triangulate, vec2, Backdrop, Measurement, Pos2, Rect, Rgba, TurtleLine, TurtleState, TurtleText, SHAPE_REACH};
+
+/// Radius of the exported turtle marker, as drawn on screen
+const TURTLE_RADIUS: f32 = 8.0;
+
+/// Color of an exported ruler or protractor, as drawn on screen
+pub const MEASURE_COLOR: Rgba = Rgba::from_rgb(255, 140, 0);
+
+/// Space left around a drawing in a thumbnail, as a fraction of its size
+const THUMBNAIL_MARGIN: f32 = 0.08;
+
+/// Smallest area of the canvas a thumbnail shows, so a dot or a short line is not blown up
+const THUMBNAIL_MIN_VIEW: f32 = 40.0;
+
+/// Largest side of an exported image, in pixels
+pub const MAX_EXPORT_SIDE: u32 = 16_384;
+
+/// How large an exported image is
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ExportSize {
+    /// The canvas's own size, a pixel per turtle step
+    #[default]
+    Canvas,
+    /// `n` pixels per turtle step
+    Scale(u32),
+    /// Exactly `width` x `height` pixels, the canvas as large as fits and centred
+    Pixels(u32, u32),
+}
+
+/// Extras drawn into an exported image, and its size
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PngOptions {
+    /// Draw the turtle (its shape, or a disc with a heading tick) where it stands, if it is visible
+    pub show_turtle: bool,
+    /// Ruler or protractor overlay to draw, with its readout, over everything else
+    pub measurement: Option<Measurement>,
+    pub size: ExportSize,
+    /// Leave the background out, so only the drawing is opaque
+    pub transparent: bool,
+}
+
+/// An image being painted in turtle coordinates
+struct Raster {
+    img: RgbaImage,
+    to_image: RectTransform,
+    /// Image pixels per turtle step; line widths and labels grow with it
+    scale: f32,
+}
+
+impl Raster {
+    /// A `width` x `height` image of the `world` rectangle (turtle coordinates)
+    fn showing(world: Rect, width: u32, height: u32, background: Pixel<u8>) -> Self {
+        Self::over(RgbaImage::from_pixel(width, height, background), world)
+    }
+
+    /// Paint over `img`, which shows the `world` rectangle
+    fn over(img: RgbaImage, world: Rect) -> Self {
+        let size = vec2(img.width() as f32, img.height() as f32);
+        let to_image = RectTransform::from_to(world, Rect::from_min_size(Pos2::ZERO, size));
+        Self { img, to_image, scale: to_image.scale().x }
+    }
+
+    /// Mix `color` into a pixel, `coverage` (0..1) of the way
+    fn blend(&mut self, x: i32, y: i32, color: Rgba, coverage: f32) {
+        if x < 0 || y < 0 || x as u32 >= self.img.width() || y as u32 >= self.img.height() {
+            return;
+        }
+        let alpha = (coverage * color.a() as f32 / 255.0).clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        // Over a transparent background the pixel takes on the color's opacity
+        let dst = self.img.get_pixel_mut(x as u32, y as u32);
+        let below = dst[3] as f32 / 255.0 * (1.0 - alpha);
+        let total = alpha + below;
+        for (channel, src) in dst.0.iter_mut().zip([color.r(), color.g(), color.b()]) {
+            *channel = ((src as f32 * alpha + *channel as f32 * below) / total).round() as u8;
+        }
+        dst[3] = (total * 255.0).round() as u8;
+    }
+
+    /// Anti-aliased stroke from `start` to `end` (turtle coordinates), `width` turtle steps wide
+    fn stroke(&mut self, start: Pos2, end: Pos2, width: f32, color: Rgba) {
+        let (a, b) = (self.to_image * start, self.to_image * end);
+        let radius = (width * self.scale).max(1.0) / 2.0;
+        let reach = radius + 1.0;
+        let (x0, x1) = ((a.x.min(b.x) - reach).floor() as i32, (a.x.max(b.x) + reach).ceil() as i32);
+        let (y0, y1) = ((a.y.min(b.y) - reach).floor() as i32, (a.y.max(b.y) + reach).ceil() as i32);
+        let ab = b - a;
+        let len_sq = ab.length_sq();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                // Distance from the pixel centre to the segment
+                let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+                let t = if len_sq > 0.0 { ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+                let distance = p.distance(a + ab * t);
+                self.blend(x, y, color, radius + 0.5 - distance);
+            }
+        }
+    }
+
+    /// Fill a polygon (turtle coordinates) with anti-aliased edges
+    fn fill(&mut self, outline: &[Pos2], color: Rgba) {
+        let points: Vec<Pos2> = outline.iter().map(|&p| self.to_image * p).collect();
+        for [a, b, c] in triangulate(&points).into_iter().map(|t| t.map(|i| points[i])) {
+            let (x0, x1) = (a.x.min(b.x).min(c.x).floor() as i32, a.x.max(b.x).max(c.x).ceil() as i32);
+            let (y0, y1) = (a.y.min(b.y).min(c.y).floor() as i32, a.y.max(b.y).max(c.y).ceil() as i32);
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    // Inside when the pixel centre is on the same side of all three edges
+                    let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+                    let sides = [(a, b), (b, c), (c, a)].map(|(s, e)| (e - s).x * (p - s).y - (e - s).y * (p - s).x);
+                    if sides.iter().all(|&d| d >= 0.0) || sides.iter().all(|&d| d <= 0.0) {
+                        self.blend(x, y, color, 1.0);
+                    }
+                }
+            }
+        }
+        for (i, &start) in outline.iter().enumerate() {
+            self.stroke(start, outline[(i + 1) % outline.len()], 1.0, color);
+        }
+    }
+
+    /// Draw the backdrop's image where it lies, each pixel's color through `color_map`
+    fn backdrop(&mut self, backdrop: &Backdrop, color_map: impl Fn(Rgba) -> Rgba) {
+        let image = &backdrop.image;
+        let area = Rect::from_two_pos(self.to_image * backdrop.world.min, self.to_image * backdrop.world.max);
+        if image.width() == 0 || image.height() == 0 || area.width() <= 0.0 || area.height() <= 0.0 {
+            return;
+        }
+        let (x0, x1) = (area.min.x.floor().max(0.0) as i32, area.max.x.ceil().min(self.img.width() as f32) as i32);
+        let (y0, y1) = (area.min.y.floor().max(0.0) as i32, area.max.y.ceil().min(self.img.height() as f32) as i32);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                // The backdrop pixel under this pixel's centre
+                let u = (x as f32 + 0.5 - area.min.x) / area.width() * image.width() as f32;
+                let v = (y as f32 + 0.5 - area.min.y) / area.height() * image.height() as f32;
+                if u < 0.0 || v < 0.0 || u >= image.width() as f32 || v >= image.height() as f32 {
+                    continue;
+                }
+                let pixel = image.get_pixel(u as u32, v as u32);
+                if pixel[3] > 0 {
+                    let color = color_map(Rgba::from_rgb(pixel[0], pixel[1], pixel[2]));
+                    self.blend(x, y, Rgba::from_rgba_unmultiplied(color.r(), color.g(), color.b(), pixel[3]), 1.0);
+                }
+            }
+        }
+    }
+
+    fn text(&mut self, text: &TurtleText, color: Rgba) {
+        // Font pixels scaled to the label size, top-left corner at `pos` as on screen
+        let scale = (text.size * self.scale / font::GLYPH_HEIGHT as f32).round().max(1.0) as i32;
+        let origin = self.to_image * text.pos;
+        let (x0, y0) = (origin.x.round() as i32, origin.y.round() as i32);
+        for (fx, fy) in font::text_pixels(&text.text) {
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    self.blend(x0 + fx as i32 * scale + dx, y0 + fy as i32 * scale + dy, color, 1.0);
+                }
+            }
+        }
+    }
+}
+
+impl TurtleState {
+    /// Draw the canvas into an image, passing every color through `color_map`
+    pub fn render_image(&self, options: PngOptions, color_map: impl Fn(Rgba) -> Rgba) -> RgbaImage {
+        let (width, height) = self.export_size(options.size);
+        let canvas = Rect::from_center_size(pos2(0.0, 0.0), vec2(self.canvas_width, self.canvas_height));
+        let background = if options.transparent { Pixel([0, 0, 0, 0]) } else { opaque(color_map(self.bg_color)) };
+        let mut raster = Raster::showing(export_view(canvas, width, height), width, height, background);
+        if let Some(backdrop) = &self.backdrop {
+            raster.backdrop(backdrop, &color_map);
+        }
+        for line in &self.lines {
+            raster.stroke(line.start, line.end, line.width, color_map(line.color));
+        }
+        for text in &self.texts {
+            raster.text(text, color_map(text.color));
+        }
+        if options.show_turtle && self.visible {
+            let pos = pos2(self.x, self.y);
+            if let Some(outline) = self.shape_outline(TURTLE_RADIUS * SHAPE_REACH) {
+                raster.fill(&outline, color_map(self.accent_color));
+            } else {
+                raster.stroke(pos, pos, TURTLE_RADIUS * 2.0, color_map(self.accent_color));
+                let tick = if luminance(self.accent_color) < 128.0 { Rgba::WHITE } else { Rgba::BLACK };
+                raster.stroke(pos, pos + self.direction() * TURTLE_RADIUS * SHAPE_REACH, 2.0, color_map(tick));
+            }
+        }
+        if let Some(measurement) = options.measurement {
+            let color = color_map(MEASURE_COLOR);
+            for (start, end) in measurement.segments() {
+                raster.stroke(start, end, 2.0, color);
+            }
+            let label = TurtleText { pos: measurement.anchor() + vec2(6.0, 6.0), text: measurement.readout(), color, size: 14.0 };
+            raster.text(&label, color);
+        }
+        raster.img
+    }
+
+    /// Pixel size of an export at `size`, each side at least 1 and at most [`MAX_EXPORT_SIDE`]
+    pub fn export_size(&self, size: ExportSize) -> (u32, u32) {
+        let scaled = |scale: u32| {
+            let side = |length: f32| ((length * scale as f32).round() as u32).clamp(1, MAX_EXPORT_SIDE);
+            (side(self.canvas_width), side(self.canvas_height))
+        };
+        match size {
+            ExportSize::Canvas => scaled(1),
+            ExportSize::Scale(scale) => scaled(scale.max(1)),
+            ExportSize::Pixels(width, height) => (width.clamp(1, MAX_EXPORT_SIDE), height.clamp(1, MAX_EXPORT_SIDE)),
+        }
+    }
+
+    /// A `width` x `height` preview of the drawing, fitted by [`thumbnail_view`]
+    ///
+    /// Lines are thinned in proportion, down to one pixel. Labels and the
+    /// turtle are left out, as they would be too small to read; a canvas with
+    /// nothing drawn comes out as its background.
+    pub fn render_thumbnail(&self, width: u32, height: u32) -> RgbaImage {
+        let canvas = Rect::from_center_size(pos2(0.0, 0.0), vec2(self.canvas_width, self.canvas_height));
+        let view = thumbnail_view(self.bounds().unwrap_or(canvas), width, height);
+        let mut raster = Raster::showing(view, width, height, opaque(self.bg_color));
+        if let Some(backdrop) = &self.backdrop {
+            raster.backdrop(backdrop, |c| c);
+        }
+        for line in &self.lines {
+            raster.stroke(line.start, line.end, line.width, line.color);
+        }
+        raster.img
+    }
+
+    /// Save canvas as PNG image
+    pub fn save_png(&self, path: &str) -> anyhow::Result<()> {
+        self.save_png_with(path, PngOptions::default(), |c| c)
+    }
+
+    /// Save canvas as PNG with `options`, passing every color through `color_map` first
+    ///
+    /// The color map is used for "export as displayed" when the canvas is
+    /// rendered with a remapped palette (e.g. CVD-safe colors).
+    pub fn save_png_with(&self, path: &str, options: PngOptions, color_map: impl Fn(Rgba) -> Rgba) -> anyhow::Result<()> {
+        self.render_image(options, color_map).save(path)?;
+        Ok(())
+    }
+}
+
+/// `img` (showing `world`) with `lines` stroked into it, as an export strokes them
+pub(super) fn fold_lines(img: RgbaImage, world: Rect, lines: &[TurtleLine]) -> RgbaImage {
+    let mut raster = Raster::over(img, world);
+    for line in lines {
+        raster.stroke(line.start, line.end, line.width, line.color);
+    }
+    raster.img
+}
+
+/// The part of the turtle plane a `width` x `height` export of `canvas` shows
+///
+/// The canvas grows to the image's aspect ratio around its centre, so it is
+/// never stretched; the extra space is background.
+pub fn export_view(canvas: Rect, width: u32, height: u32) -> Rect {
+    let aspect = width.max(1) as f32 / height.max(1) as f32;
+    let mut size = canvas.size();
+    if size.x / size.y < aspect {
+        size.x = size.y * aspect;
+    } else {
+        size.y = size.x / aspect;
+    }
+    Rect::from_center_size(canvas.center(), size)
+}
+
+/// The part of the canvas a `width` x `height` thumbnail of a drawing within `bounds` shows
+///
+/// `bounds` grows by the margin on every side and then to the thumbnail's
+/// aspect ratio, keeping its centre, so the drawing is as large as fits
+/// without being stretched.
+pub fn thumbnail_view(bounds: Rect, width: u32, height: u32) -> Rect {
+    let padded = bounds.size() * (1.0 + 2.0 * THUMBNAIL_MARGIN);
+    let size = padded.max(vec2(THUMBNAIL_MIN_VIEW, THUMBNAIL_MIN_VIEW));
+    export_view(Rect::from_center_size(bounds.center(), size), width, height)
+}
+
+fn opaque(color: Rgba) -> Pixel<u8> {
+    let [r, g, b, _] = color.to_array();
+    Pixel([r, g, b, 255])
+}
+
+fn luminance(color: Rgba) -> f32 {
+    0.299 * color.r() as f32 + 0.587 * color.g() as f32 + 0.114 * color.b() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleShape;
+
+    fn small_canvas() -> TurtleState {
+        let mut turtle = TurtleState::new();
+        turtle.canvas_width = 200.0;
+        turtle.canvas_height = 160.0;
+        turtle.bg_color = Rgba::BLACK;
+        turtle.pen_color = Rgba::RED;
+        turtle
+    }
+
+    fn is_pen(img: &RgbaImage, x: f32, y: f32) -> bool {
+        let p = img.get_pixel(x as u32, y as u32);
+        p[0] > 200 && p[1] < 50 && p[2] < 50
+    }
+
+    #[test]
+    fn test_thick_line_covers_its_width() {
+        let mut turtle = small_canvas();
+        turtle.pen_width = 6.0;
+        turtle.right(90.0);
+        turtle.forward(50.0);
+        let img = turtle.render_image(PngOptions::default(), |c| c);
+
+        // Horizontal line along y = 0, which is image row 80 (the centre)
+        let rows: Vec<u32> = (0..img.height()).filter(|&y| is_pen(&img, 125.0, y as f32)).collect();
+        assert_eq!(rows, (77..83).collect::<Vec<_>>(), "6 solid rows centred on the line");
+        assert!(!is_pen(&img, 125.0, 75.0));
+
+        turtle.pen_width = 1.0;
+        turtle.clear();
+        turtle.forward(-50.0);
+        let img = turtle.render_image(PngOptions::default(), |c| c);
+        assert!(!is_pen(&img, 125.0, 78.0), "a thin line stays thin");
+    }
+
+    #[test]
+    fn test_square_matches_the_screen_transform() {
+        let mut turtle = small_canvas();
+        for _ in 0..4 {
+            turtle.forward(40.0);
+            turtle.right(90.0);
+        }
+        let img = turtle.render_image(PngOptions::default(), |c| c);
+
+        // The IDE canvas maps the centred world rect onto the widget rect
+        let size = vec2(turtle.canvas_width, turtle.canvas_height);
+        let screen = RectTransform::from_to(
+            Rect::from_center_size(pos2(0.0, 0.0), size),
+            Rect::from_min_size(Pos2::ZERO, size),
+        );
+        for line in &turtle.lines {
+            let mid = screen * line.start.lerp(line.end, 0.5);
+            assert!(is_pen(&img, mid.x, mid.y), "edge at {:?}", mid);
+        }
+        // Forward from heading 0 goes up on screen: the top edge is above the centre, not below
+        let top = screen * pos2(20.0, -40.0);
+        let mirrored = screen * pos2(20.0, 40.0);
+        assert!(top.y < 80.0);
+        assert!(is_pen(&img, top.x, top.y));
+        assert!(!is_pen(&img, mirrored.x, mirrored.y), "not mirrored");
+    }
+
+    #[test]
+    fn test_diagonals_are_antialiased_and_turtle_is_optional() {
+        let mut turtle = small_canvas();
+        turtle.right(30.0);
+        turtle.forward(60.0);
+        let plain = turtle.render_image(PngOptions::default(), |c| c);
+        let partial = plain.pixels().filter(|p| p[0] > 0 && p[0] < 255).count();
+        assert!(partial > 0, "edge pixels are blended");
+
+        let with_turtle = turtle.render_image(PngOptions { show_turtle: true, ..PngOptions::default() }, |c| c);
+        let at = |img: &RgbaImage| *img.get_pixel((turtle.x + 100.0) as u32, (turtle.y + 80.0) as u32);
+        assert_ne!(at(&with_turtle), at(&plain));
+        turtle.visible = false;
+        assert_eq!(turtle.render_image(PngOptions { show_turtle: true, ..PngOptions::default() }, |c| c), plain);
+    }
+
+    #[test]
+    fn test_turtle_shape_points_where_it_heads() {
+        let mut turtle = small_canvas();
+        turtle.shape = TurtleShape::Arrow;
+        turtle.right(90.0);
+        let img = turtle.render_image(PngOptions { show_turtle: true, ..PngOptions::default() }, |c| c);
+        let accent = |x: f32, y: f32| *img.get_pixel((x + 100.0) as u32, (y + 80.0) as u32) == Pixel([100, 150, 255, 255]);
+        // Facing east: the head is wide just behind the nose, the shaft narrow behind that
+        assert!(accent(4.0, 5.0) && accent(4.0, -5.0), "head");
+        assert!(accent(-6.0, 0.0) && !accent(-6.0, 5.0), "shaft");
+        assert!(!accent(0.0, -9.0), "no disc");
+    }
+
+    #[test]
+    fn test_measurements_are_exported_only_when_asked() {
+        let turtle = small_canvas();
+        let plain = turtle.render_image(PngOptions::default(), |c| c);
+        let ruler = Measurement::Distance { from: pos2(-50.0, 30.0), to: pos2(50.0, 30.0) };
+        let img = turtle.render_image(PngOptions { measurement: Some(ruler), ..PngOptions::default() }, |c| c);
+        assert_ne!(img, plain);
+        let [r, g, b, _] = MEASURE_COLOR.to_array();
+        assert_eq!(*img.get_pixel(100, 110), Pixel([r, g, b, 255]), "ruler drawn");
+        assert_eq!(*plain.get_pixel(100, 110), Pixel([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_scaled_export_redraws_at_the_new_size() {
+        let mut turtle = small_canvas();
+        turtle.pen_width = 4.0;
+        turtle.right(90.0);
+        turtle.forward(50.0);
+        let options = |size| PngOptions { size, ..PngOptions::default() };
+        // The columns and rows of solid pen along and across the line
+        let extent = |img: &RgbaImage| {
+            let (mid_x, mid_y) = (img.width() as f32 * 0.6, img.height() as f32 / 2.0);
+            let columns: Vec<u32> = (0..img.width()).filter(|&x| is_pen(img, x as f32, mid_y)).collect();
+            let rows: Vec<u32> = (0..img.height()).filter(|&y| is_pen(img, mid_x, y as f32)).collect();
+            (columns[0], columns.last().unwrap() + 1, rows[0], rows.last().unwrap() + 1)
+        };
+
+        let single = turtle.render_image(options(ExportSize::Canvas), |c| c);
+        let double = turtle.render_image(options(ExportSize::Scale(2)), |c| c);
+        assert_eq!((single.dimensions(), double.dimensions()), ((200, 160), (400, 320)));
+        // From x 100 to 150 with round ends 2 px long, 4 px thick; all of it doubled
+        assert_eq!(extent(&single), (98, 152, 78, 82));
+        assert_eq!(extent(&double), (196, 304, 156, 164));
+        assert_eq!(turtle.render_image(options(ExportSize::Scale(1)), |c| c), single);
+
+        // A wider image than the canvas: the same scale, centred, background either side
+        let wide = turtle.render_image(options(ExportSize::Pixels(400, 160)), |c| c);
+        assert_eq!(extent(&wide), (198, 252, 78, 82));
+        assert_eq!(turtle.export_size(ExportSize::Pixels(0, 1 << 20)), (1, MAX_EXPORT_SIDE));
+    }
+
+    #[test]
+    fn test_transparent_background() {
+        let mut turtle = small_canvas();
+        turtle.pen_width = 4.0;
+        turtle.right(30.0);
+        turtle.forward(60.0);
+        let img = turtle.render_image(PngOptions { transparent: true, ..PngOptions::default() }, |c| c);
+        assert_eq!(*img.get_pixel(10, 10), Pixel([0, 0, 0, 0]));
+        assert_eq!(*img.get_pixel(100, 80), Pixel([255, 0, 0, 255]));
+        // Anti-aliased edges keep the pen's color and fade out
+        assert!(img.pixels().any(|p| p[3] > 0 && p[3] < 255));
+        assert!(img.pixels().filter(|p| p[3] > 0).all(|p| p.0[..3] == [255, 0, 0]));
+    }
+
+    #[test]
+    fn test_thumbnail_fits_the_drawing() {
+        // A 100 x 50 drawing, padded by 8% a side, then widened to 4:3
+        let bounds = Rect::from_min_max(pos2(0.0, -50.0), pos2(100.0, 0.0));
+        let view = thumbnail_view(bounds, 80, 60);
+        assert_eq!(view.center(), bounds.center());
+        assert!((view.width() - 116.0).abs() < 1e-3 && (view.height() - 87.0).abs() < 1e-3, "{:?}", view);
+        // A tall drawing is widened instead, and a dot still shows a patch of canvas
+        let tall = thumbnail_view(Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 200.0)), 80, 60);
+        assert!((tall.height() - 232.0).abs() < 1e-3 && (tall.width() / tall.height() - 4.0 / 3.0).abs() < 1e-4);
+        let dot = thumbnail_view(Rect::from_min_max(pos2(5.0, 5.0), pos2(5.0, 5.0)), 60, 60);
+        assert_eq!(dot.size(), vec2(THUMBNAIL_MIN_VIEW, THUMBNAIL_MIN_VIEW));
+
+        // The square's edges land the margin (7 px) in from the image's sides, 2 * 2.16 px wide
+        let mut turtle = small_canvas();
+        for _ in 0..4 {
+            turtle.forward(40.0);
+            turtle.right(90.0);
+        }
+        let img = turtle.render_thumbnail(100, 100);
+        assert_eq!(img.dimensions(), (100, 100));
+        let columns: Vec<u32> = (0..100).filter(|&x| is_pen(&img, x as f32, 50.0)).collect();
+        assert_eq!((columns[0], *columns.last().unwrap()), (5, 94), "{:?}", columns);
+        assert_eq!(columns.len(), 8);
+        assert!(!is_pen(&img, 50.0, 50.0));
+        assert_eq!(TurtleState::new().render_thumbnail(8, 8).get_pixel(4, 4), &Pixel(TurtleState::new().bg_color.to_array()));
+    }
+}
@@ -0,0 +1,191 @@
+//! Low-memory canvas: the oldest lines folded into a bitmap under a line budget
+//!
+//! A fractal can draw millions of segments, and every one is kept (twice,
+//! while the IDE mirrors a running program's drawing) so the canvas can be
+//! redrawn and exported. With a [`TurtleState::line_budget`], once more lines
+//! than that are kept the oldest are painted into a [`Backdrop`], an image of
+//! the canvas at a pixel per turtle step, and dropped, leaving half the
+//! budget as lines. The canvas, the PNG export, and the HTML export's SVG
+//! draw the backdrop first and the lines over it, so the picture stays the
+//! same; only zooming past 100% shows the folded part's pixels, and a folded
+//! line outside the canvas is gone. [`TurtleState::total_lines`] still counts
+//! every segment since the last clear, for PATHCOUNT and the run summary.
+
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use image::{ImageFormat, RgbaImage};
+
+use super::raster::fold_lines;
+use super::{pos2, vec2, Rect, Rgba, TurtleLine, TurtleState};
+
+/// Smallest budget Settings offers, so a slip does not fold on every segment
+pub const MIN_LINE_BUDGET: usize = 1_000;
+
+/// Budget Settings starts with: a few megabytes of lines
+pub const DEFAULT_LINE_BUDGET: usize = 100_000;
+
+/// Source of `Backdrop::serial`
+static SERIALS: AtomicU64 = AtomicU64::new(1);
+
+/// Lines folded into an image
+#[derive(Debug, Clone)]
+pub struct Backdrop {
+    /// The folded lines over transparency
+    pub image: Arc<RgbaImage>,
+    /// Part of the turtle plane the image covers: the canvas when lines were first folded
+    pub world: Rect,
+    /// Segments folded in
+    pub lines: usize,
+    /// Smallest rectangle containing every folded segment
+    pub bounds: Rect,
+    /// New after every fold, so a texture made from the image can tell it is stale
+    pub serial: u64,
+}
+
+impl Backdrop {
+    fn new(world: Rect) -> Self {
+        let side = |length: f32| length.round().max(1.0) as u32;
+        let image = RgbaImage::new(side(world.width()), side(world.height()));
+        Self { image: Arc::new(image), world, lines: 0, bounds: Rect::NOTHING, serial: 0 }
+    }
+
+    /// The image with every color passed through `color_map`, its coverage kept
+    pub fn mapped_image(&self, color_map: impl Fn(Rgba) -> Rgba) -> RgbaImage {
+        let mut image = (*self.image).clone();
+        for pixel in image.pixels_mut().filter(|p| p[3] > 0) {
+            let color = color_map(Rgba::from_rgb(pixel[0], pixel[1], pixel[2]));
+            pixel.0 = [color.r(), color.g(), color.b(), pixel[3]];
+        }
+        image
+    }
+
+    /// The mapped image as a PNG file's bytes
+    pub fn png(&self, color_map: impl Fn(Rgba) -> Rgba) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = Cursor::new(Vec::new());
+        self.mapped_image(color_map).write_to(&mut bytes, ImageFormat::Png)?;
+        Ok(bytes.into_inner())
+    }
+}
+
+impl TurtleState {
+    /// Segments folded into the backdrop since the last clear
+    pub fn folded_lines(&self) -> usize {
+        self.backdrop.as_ref().map_or(0, |backdrop| backdrop.lines)
+    }
+
+    /// Segments drawn since the last clear, folded ones included
+    pub fn total_lines(&self) -> usize {
+        self.folded_lines() + self.lines.len()
+    }
+
+    /// Whether more lines are kept than `line_budget` allows
+    pub fn over_budget(&self) -> bool {
+        self.line_budget.is_some_and(|budget| self.lines.len() > budget)
+    }
+
+    /// Fold the oldest lines into the backdrop when over budget, keeping half the budget as lines
+    ///
+    /// Returns how many were folded.
+    pub fn consolidate(&mut self) -> usize {
+        let Some(budget) = self.line_budget.filter(|_| self.over_budget()) else { return 0 };
+        let fold = self.lines.len() - budget / 2;
+        let canvas = Rect::from_center_size(pos2(0.0, 0.0), vec2(self.canvas_width, self.canvas_height));
+        let backdrop = self.backdrop.get_or_insert_with(|| Backdrop::new(canvas));
+        let folded = &self.lines[..fold];
+        let image = std::mem::replace(&mut backdrop.image, Arc::new(RgbaImage::new(0, 0)));
+        backdrop.image = Arc::new(fold_lines(Arc::unwrap_or_clone(image), backdrop.world, folded));
+        backdrop.bounds = folded.iter().fold(backdrop.bounds, |rect, l| rect.union(Rect::from_two_pos(l.start, l.end)));
+        backdrop.lines += fold;
+        backdrop.serial = SERIALS.fetch_add(1, Ordering::Relaxed);
+        self.lines.drain(..fold);
+        fold
+    }
+
+    /// Segments the last FLASH lights up that are still lines
+    pub fn flashed_lines(&self) -> &[TurtleLine] {
+        let Some(flash) = &self.flash else { return &[] };
+        let folded = self.folded_lines();
+        let range = flash.lines.start.saturating_sub(folded)..flash.lines.end.saturating_sub(folded);
+        self.lines.get(range).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::PngOptions;
+    use crate::interpreter::Interpreter;
+
+    /// `count` short segments walking round a circle
+    fn circle(turtle: &mut TurtleState, count: usize) {
+        for _ in 0..count {
+            turtle.forward(1.0);
+            turtle.right(0.5);
+        }
+    }
+
+    #[test]
+    fn test_consolidation_waits_for_the_budget() {
+        let mut turtle = TurtleState::new();
+        circle(&mut turtle, 1500);
+        assert_eq!(turtle.consolidate(), 0, "no budget, nothing folds");
+
+        turtle.line_budget = Some(1500);
+        assert!(!turtle.over_budget());
+        assert_eq!(turtle.consolidate(), 0);
+        circle(&mut turtle, 1);
+        assert!(turtle.over_budget());
+        assert_eq!(turtle.consolidate(), 751, "half the budget stays as lines");
+        assert_eq!((turtle.folded_lines(), turtle.lines.len(), turtle.total_lines()), (751, 750, 1501));
+        let serial = turtle.backdrop.as_ref().unwrap().serial;
+
+        circle(&mut turtle, 1000);
+        assert_eq!(turtle.consolidate(), 1000);
+        assert_eq!((turtle.folded_lines(), turtle.lines.len(), turtle.total_lines()), (1751, 750, 2501));
+        assert_ne!(turtle.backdrop.as_ref().unwrap().serial, serial, "a fold is a new image");
+
+        turtle.flash(800);
+        assert_eq!(turtle.flashed_lines().len(), 750, "folded segments no longer flash");
+        turtle.clear();
+        assert!(turtle.backdrop.is_none());
+        assert_eq!(turtle.total_lines(), 0);
+    }
+
+    #[test]
+    fn test_a_run_keeps_counting_folded_lines() {
+        let mut turtle = TurtleState::new();
+        turtle.line_budget = Some(1000);
+        let mut interp = Interpreter::new();
+        interp.load_program("REPEAT 3000 [FD 1 RT 0.5]\nPATHCOUNT").unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(turtle.total_lines(), 3000);
+        assert!(turtle.lines.len() <= 1000, "{}", turtle.lines.len());
+        assert_eq!(turtle.folded_lines() + turtle.lines.len(), 3000);
+        assert!(interp.output.iter().any(|l| l == "PATHCOUNT 3000"), "{:?}", interp.output);
+        let bounds = turtle.bounds().unwrap();
+        let mut whole = TurtleState::new();
+        circle(&mut whole, 3000);
+        assert_eq!(bounds, whole.bounds().unwrap(), "the bounds still cover the folded lines");
+    }
+
+    #[test]
+    fn test_exports_show_folded_lines() {
+        let mut whole = TurtleState::new();
+        whole.pen_color = Rgba::RED;
+        whole.pen_width = 3.0;
+        circle(&mut whole, 2000);
+        let mut folded = whole.clone();
+        folded.line_budget = Some(1000);
+        folded.consolidate();
+        assert_eq!(folded.lines.len(), 500);
+
+        let expected = whole.render_image(PngOptions::default(), |c| c);
+        let image = folded.render_image(PngOptions::default(), |c| c);
+        let difference = expected.pixels().zip(image.pixels()).flat_map(|(a, b)| a.0.iter().zip(b.0).map(|(&x, y)| x.abs_diff(y)));
+        assert!(difference.max().unwrap() <= 3, "the backdrop draws what the lines drew");
+        let png = folded.backdrop.as_ref().unwrap().png(|c| c).unwrap();
+        assert_eq!(image::load_from_memory(&png).unwrap().width(), 800);
+    }
+}
@@ -0,0 +1,144 @@
+//! The text screen (`SCREEN 0`) as a grid of character cells
+//!
+//! [`TextScreen`] is what the Output tab shows in text mode: `rows` lines of
+//! `cols` cells on the theme's background, each line in its own color. The
+//! IDE draws it from [`TextScreen::render_image`] at [`SCREEN_SCALE`], and
+//! File ▸ Export ▸ Text Screen and `--export-screen` save the same image at
+//! any scale, so art placed with `LOCATE` comes out exactly as it looked.
+//! Characters go through the bitmap font in [`font`]; a line longer than the
+//! screen is cut at its right edge, and lines past the last row are left out.
+
+use image::{Rgba as Pixel, RgbaImage};
+
+use super::{font, Rgba};
+
+/// Font pixels of border around the grid
+pub const MARGIN: u32 = 4;
+
+/// Screen pixels per font pixel in the IDE
+pub const SCREEN_SCALE: u32 = 2;
+
+/// A row of the screen and its color
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenLine {
+    pub text: String,
+    pub color: Rgba,
+}
+
+/// The text screen's size, rows, and background
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextScreen {
+    pub cols: u32,
+    pub rows: u32,
+    /// Top row first
+    pub lines: Vec<ScreenLine>,
+    pub background: Rgba,
+}
+
+impl TextScreen {
+    /// Image size at `scale` image pixels per font pixel
+    pub fn size(&self, scale: u32) -> (u32, u32) {
+        let width = 2 * MARGIN + self.cols * font::CELL_WIDTH;
+        let height = 2 * MARGIN + self.rows * font::CELL_HEIGHT;
+        (width * scale, height * scale)
+    }
+
+    /// Each character on the screen, other than spaces, as (row, column, character, color)
+    pub fn cells(&self) -> impl Iterator<Item = (u32, u32, char, Rgba)> + '_ {
+        self.lines.iter().take(self.rows as usize).enumerate().flat_map(move |(row, line)| {
+            line.text
+                .chars()
+                .take(self.cols as usize)
+                .enumerate()
+                .filter(|(_, ch)| !ch.is_whitespace())
+                .map(move |(col, ch)| (row as u32, col as u32, ch, line.color))
+        })
+    }
+
+    /// The screen as an image, `scale` pixels per font pixel
+    pub fn render_image(&self, scale: u32) -> RgbaImage {
+        let scale = scale.max(1);
+        let (width, height) = self.size(scale);
+        let mut img = RgbaImage::from_pixel(width, height, opaque(self.background));
+        for (row, col, ch, color) in self.cells() {
+            let (x0, y0) = cell_origin(row, col);
+            for (x, y) in font::cell_pixels(ch) {
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        img.put_pixel((x0 + x) * scale + dx, (y0 + y) * scale + dy, opaque(color));
+                    }
+                }
+            }
+        }
+        img
+    }
+
+    /// Save the screen as a PNG, `scale` pixels per font pixel
+    pub fn save_png(&self, path: &str, scale: u32) -> anyhow::Result<()> {
+        self.render_image(scale).save(path)?;
+        Ok(())
+    }
+}
+
+/// Top-left corner of cell (`row`, `col`) in font pixels
+pub fn cell_origin(row: u32, col: u32) -> (u32, u32) {
+    (MARGIN + col * font::CELL_WIDTH, MARGIN + row * font::CELL_HEIGHT)
+}
+
+fn opaque(color: Rgba) -> Pixel<u8> {
+    let [r, g, b, _] = color.to_array();
+    Pixel([r, g, b, 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::interpreter::{Interpreter, ScreenMode};
+
+    const INK: Rgba = Rgba::from_rgb(200, 255, 200);
+    const PAPER: Rgba = Rgba::from_rgb(10, 20, 30);
+
+    fn screen_of(interp: &Interpreter) -> TextScreen {
+        let ScreenMode::Text { cols, rows } = interp.screen_mode else { panic!("not in text mode") };
+        let lines = interp.text_lines.iter().map(|text| ScreenLine { text: text.clone(), color: INK }).collect();
+        TextScreen { cols, rows, lines, background: PAPER }
+    }
+
+    /// The color at font pixel (`x`, `y`) of cell (`row`, `col`)
+    fn pixel(img: &RgbaImage, row: u32, col: u32, x: u32, y: u32) -> Rgba {
+        let (x0, y0) = cell_origin(row, col);
+        Rgba::from(img.get_pixel((x0 + x) * 3, (y0 + y) * 3).0)
+    }
+
+    #[test]
+    fn test_locate_art_renders_at_its_cells() {
+        let mut interp = Interpreter::new();
+        let program = "10 SCREEN 0, 20, 5\n20 CLS\n30 LOCATE 3, 5\n40 PRINT \"█▀\"\n50 LOCATE 1, 1\n60 PRINT \"|\"";
+        interp.load_program(program).unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+        let screen = screen_of(&interp);
+        assert_eq!(screen.lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>(), ["|", "", "    █▀"]);
+
+        let img = screen.render_image(3);
+        assert_eq!(img.dimensions(), screen.size(3));
+        assert_eq!(screen.size(1), (2 * MARGIN + 20 * font::CELL_WIDTH, 2 * MARGIN + 5 * font::CELL_HEIGHT));
+        // █ fills row 3, column 5; ▀ only the top half of the next cell
+        assert_eq!(pixel(&img, 2, 4, 0, 0), INK);
+        assert_eq!(pixel(&img, 2, 4, font::CELL_WIDTH - 1, font::CELL_HEIGHT - 1), INK);
+        assert_eq!(pixel(&img, 2, 5, 2, 0), INK);
+        assert_eq!(pixel(&img, 2, 5, 2, font::CELL_HEIGHT - 1), PAPER);
+        // | is the middle column of the glyph at the top left; the cell beside it is empty
+        assert_eq!((pixel(&img, 0, 0, 2, 1), pixel(&img, 0, 0, 0, 1)), (INK, PAPER));
+        assert!((0..font::CELL_WIDTH).all(|x| pixel(&img, 0, 1, x, 3) == PAPER));
+        assert_eq!(Rgba::from(img.get_pixel(0, 0).0), PAPER);
+    }
+
+    #[test]
+    fn test_lines_are_cut_at_the_screen_edge() {
+        let line = |text: &str| ScreenLine { text: text.to_string(), color: INK };
+        let screen = TextScreen { cols: 3, rows: 1, lines: vec![line("AB DE"), line("X")], background: PAPER };
+        let cells: Vec<(u32, u32, char)> = screen.cells().map(|(row, col, ch, _)| (row, col, ch)).collect();
+        assert_eq!(cells, [(0, 0, 'A'), (0, 1, 'B')]);
+    }
+}
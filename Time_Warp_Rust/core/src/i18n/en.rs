@@ -0,0 +1,710 @@
+//! English: the default locale, and the fallback for ids another locale lacks
+
+pub const STRINGS: &[(&str, &str)] = &[
+    // Run results and errors
+    ("run.stopped", "⏹ Program stopped"),
+    ("error.plain", "❌ Error: {0}"),
+    ("error.at_line", "❌ Error at line {0}: {1}"),
+    ("error.hint", "Hint: {0}"),
+    ("hint.did_you_mean", "Did you mean '{0}'?"),
+    ("hint.unclosed_quote", "Unclosed string quote (\")"),
+    ("hint.missing_closing", "Missing {0} closing parenthesis"),
+    ("hint.missing_opening", "Missing {0} opening parenthesis"),
+    ("hint.let_needs_equals", "LET statement requires '=' (e.g., LET X = 10)"),
+    ("hint.if_needs_then", "IF statement requires THEN (e.g., IF X > 10 THEN PRINT \"Hi\")"),
+    ("hint.print_quote", "String literals in PRINT should be quoted (e.g., PRINT \"Hello\")"),
+    ("error.internal", "Internal interpreter error — please report: {0}, line {1}"),
+    ("error.internal_direct", "Internal interpreter error — please report: {0}"),
+    ("error.timeout", "Execution timeout ({0} seconds exceeded)"),
+    ("error.infinite_loop", "Infinite loop detected between lines {0}–{1} (no state change)"),
+    ("error.unknown_panic", "unknown panic"),
+    ("error.not_waiting", "The program is not waiting for input"),
+    ("output.truncated", "… [{0} more characters]"),
+    // Warnings
+    ("warning.prefix", "⚠️ Warning"),
+    ("warning.at_line", "{0} at line {1}: {2}"),
+    ("warning.strict", "{0} (strict mode)"),
+    ("warning.unset", "{0} is used before it is set"),
+    ("warning.reads_as_zero", "{0}, so it reads as 0"),
+    ("warning.near_limit", "{0}% of the {1} statement limit used; the run stops at the limit"),
+    ("warning.nan_condition", "The condition is not a number (NaN), so it is taken as false"),
+    ("warning.max_iterations", "Maximum iterations reached"),
+    ("warning.jump_into_loop", "GOTO {0} jumps into the body of `{1}`, which is not running"),
+    ("warning.string_for_number", "{0} gets the text \"{1}\"; name it {0}$ to hold text"),
+    ("warning.kind.undefined_variable", "Variable used before it is set"),
+    ("warning.kind.jump_into_loop", "GOTO into a FOR loop that is not running"),
+    ("warning.kind.string_for_number", "Text stored in a numeric variable"),
+    ("warning.kind.near_iteration_limit", "Run near its iteration limit"),
+    ("warning.kind.nan_condition", "Condition that is not a number (NaN)"),
+    // Limits
+    ("limit.exceeded", "{0} {1} exceeds the limit of {2} (raise \"{3}\" in Settings → Limits)"),
+    ("limit.program_bytes", "Program size (bytes)"),
+    ("limit.program_lines", "Program length (lines)"),
+    ("limit.output", "Output"),
+    ("limit.line_count", "line count"),
+    ("limit.repeat_count", "REPEAT count"),
+    ("limit.for_span", "FOR iteration span"),
+    ("limit.bracket_depth", "REPEAT bracket depth"),
+    ("limit.paren_depth", "Parenthesis depth"),
+    ("limit.output_discarded", "{0}; further output discarded"),
+    ("limit.clamped", "{0}; clamped to {1}"),
+    // Lessons: disabled statements
+    ("lesson.disabled", "{0} is disabled for this lesson"),
+    ("lesson.disabled_use", "{0} is disabled for this lesson — use {1} instead"),
+    // Reserved words
+    ("reserved.word", "{0} is a reserved word in {1}"),
+    // Languages
+    ("pilot.unknown_command", "Unknown PILOT command: {0}"),
+    ("pilot.ask_again_without_question", "AN: has no earlier A: to ask again"),
+    ("pilot.text_width_range", "TW: width must be between 0 and 1000"),
+    ("pilot.label_not_found", "Label not found: {0}"),
+    ("pilot.runtime_unimplemented", "Runtime command not yet implemented: R:{0}"),
+    ("pilot.match_option", "MC: unknown option '{0}' (use FOLD, PUNCT, SPACE, EXACT; -NAME turns one off)"),
+    ("pilot.quiz_data_expects", "D: needs question|answer1,answer2|feedback"),
+    ("pilot.quiz_option", "QZ: unknown option '{0}' (use QZ: or QZ:SHUFFLE)"),
+    ("pilot.quiz_seed", "QZ:SHUFFLE seed must be a whole number"),
+    ("pilot.quiz_no_questions", "QZ: no D: questions in this program"),
+    ("pilot.quiz_correct", "Correct!"),
+    ("pilot.quiz_wrong", "Not quite. The answer is {0}."),
+    ("pilot.quiz_score", "Score: {0} out of {1}"),
+    ("logo.too_deep", "{0} called procedures more than {1} deep (does it STOP?)"),
+    ("logo.unknown_command", "Unknown Logo command: {0}"),
+    ("logo.color_cycle_list", "SETCOLORCYCLE expects a list of colors, like [RED YELLOW GREEN], got '{0}'"),
+    ("logo.color_cycle_color", "SETCOLORCYCLE: '{0}' is not a color name or #RRGGBB"),
+    ("logo.unbalanced_brackets", "Unbalanced brackets in {0}"),
+    ("logo.program_stopped", "Program stopped"),
+    ("logo.angle_mode_expects", "SETANGLEMODE expects COMPASS or MATH, got '{0}'"),
+    ("logo.towards_expects", "TOWARDS needs x and y"),
+    ("logo.arc_expects", "ARC needs an angle and a radius"),
+    ("logo.shape_unreadable", "SETSHAPE cannot read {0}: {1}"),
+    ("logo.shape_expects", "SETSHAPE expects {0} or a points file, got '{1}'"),
+    ("logo.shape_bad_line", "line {0}: expected two numbers, got '{1}'"),
+    ("logo.shape_too_few", "a shape needs at least 3 points, found {0}"),
+    ("logo.shape_at_origin", "every point is at (0, 0)"),
+    ("logo.needs_xy", "{0} needs x and y, e.g. {0} 100 50 or {0} [100 50]"),
+    ("logo.repeat_missing_bracket", "REPEAT missing '['"),
+    ("logo.to_missing_name", "TO missing procedure name"),
+    ("logo.to_missing_end", "TO {0} missing END"),
+    ("logo.procedure_not_found", "Procedure {0} not found"),
+    ("basic.unknown_command", "Unknown BASIC command: {0}"),
+    ("basic.goto_failed", "GOTO {0} failed: line not found"),
+    ("basic.gosub_failed", "GOSUB {0} failed: line not found"),
+    ("basic.if_missing_then", "IF missing THEN"),
+    ("basic.loop_condition", "{0} expects nothing, or WHILE or UNTIL and a condition"),
+    ("basic.do_without_loop", "DO without LOOP"),
+    ("basic.loop_without_do", "LOOP without DO"),
+    ("basic.exit_expects", "EXIT expects FOR or DO"),
+    ("basic.exit_outside_loop", "EXIT {0} outside a {0} loop"),
+    ("basic.exit_unclosed", "EXIT {0} in a loop that is never closed"),
+    ("basic.on_expects", "ON needs GOTO or GOSUB"),
+    ("basic.on_range", "ON {0} is outside 0 to 255"),
+    ("basic.msgbox_expects", "MSGBOX expects a message, or message, YESNO, variable"),
+    ("basic.screen_missing_mode", "❌ SCREEN: Missing mode"),
+    ("basic.screen_unsupported", "❌ SCREEN: Unsupported mode {0}"),
+    ("basic.screen_set", "🎨 SCREEN set: {0}"),
+    ("basic.screen_cleared", "🎨 Screen cleared"),
+    ("basic.print_using_format", "PRINT USING needs a format string"),
+    ("basic.input_missing_variable", "INPUT missing variable name"),
+    ("basic.input_expects_clause", "INPUT expects AS NUMBER or RANGE lo TO hi after the variable"),
+    ("basic.input_range_missing_to", "INPUT RANGE missing TO"),
+    ("basic.input_expects_range", "INPUT expects RANGE lo TO hi after AS NUMBER"),
+    ("basic.input_between", "must be between {0} and {1}"),
+    ("basic.input_at_least", "must be a number of at least {0}"),
+    ("basic.input_at_most", "must be a number no more than {0}"),
+    ("basic.input_number", "must be a number"),
+    ("basic.input_gave_up", "INPUT {0}: no valid answer after {1} tries ({2})"),
+    ("basic.input_keys_range", "INPUT$ needs 1 to 255 keys, not {0}"),
+    ("basic.for_missing_equals", "FOR missing '='"),
+    ("basic.for_missing_to", "FOR missing TO"),
+    ("basic.for_step_zero", "FOR with STEP 0 never ends"),
+    ("basic.next_mismatch", "NEXT {0} does not match FOR {1}"),
+    ("basic.option_expects", "OPTION expects ERRORS CLASSIC, ERRORS MODERN, BASE 0, BASE 1, COORDS SCREEN or COORDS CENTERED"),
+    ("basic.read_not_number", "READ {0}: DATA value \"{1}\" is not a number"),
+    ("basic.merge_replaced_one", "⚠️ MERGE \"{0}\" replaced line {1}"),
+    ("basic.merge_replaced_many", "⚠️ MERGE \"{0}\" replaced lines {1}"),
+    ("basic.callfile_missing_name", "CALLFILE needs a file name"),
+    ("basic.callfile_too_deep", "CALLFILE \"{0}\": calls nested more than {1} files deep ({2} → {0})"),
+    ("basic.chain_missing_name", "CHAIN needs a file name"),
+    ("basic.merge_missing_name", "MERGE needs a file name"),
+    ("basic.merge_unnumbered", "MERGE \"{0}\" line {1}: only numbered lines can be merged"),
+    ("basic.plot_missing_series", "PLOT missing series name"),
+    ("basic.plot_no_series", "PLOT: no recorded series '{0}' (use LOGVAR {0} first)"),
+    ("basic.logvar_missing_name", "LOGVAR missing variable name"),
+    ("basic.plot_empty_series", "PLOT: series '{0}' has no values"),
+    ("basic.type.holds_numbers", "Type mismatch: {0} holds numbers"),
+    ("basic.type.holds_text", "Type mismatch: {0} holds text"),
+    ("basic.type.integer_range", "Overflow: {0} holds whole numbers from -32768 to 32767, not {1}"),
+    ("basic.type.range_backwards", "Letter range {0} runs backwards"),
+    ("basic.type.expected_letter", "Expected a letter or range like I-N, not '{0}'"),
+    ("basic.type.holds_strings", "Type mismatch: {0} holds strings"),
+    ("basic.type.needs_number", "Type mismatch: {0} needs a number"),
+    ("basic.dim.dimensions", "DIM {0} needs one or two bounds, like {0}(10) or {0}(3, 3)"),
+    ("basic.dim.below_base", "DIM {0}: bound {1} is below OPTION BASE {2}"),
+    ("basic.dim.too_many", "DIM {0} needs {1} elements; the most is {2}"),
+    ("basic.dim.expects", "DIM expects NAME(size), not '{0}'"),
+    ("basic.dim.duplicate", "Duplicate definition: {0} is already dimensioned"),
+    ("basic.dim.negative", "DIM {0}: bound {1} must be 0 or more"),
+    ("basic.dim.base_after_dim", "OPTION BASE must come before the first DIM"),
+    ("basic.dim.not_dimensioned", "{0} is not dimensioned (DIM {0}(size) first)"),
+    ("basic.dim.dimension_count", "Subscript out of range: {0} has {1} dimension(s)"),
+    ("basic.dim.subscript_range", "Subscript out of range: {0} (dimension {1} runs {2} to {3})"),
+    ("basic.error.next_without_for", "NEXT without FOR"),
+    ("basic.error.syntax", "Syntax error"),
+    ("basic.error.return_without_gosub", "RETURN without GOSUB"),
+    ("basic.error.out_of_data", "Out of DATA"),
+    ("basic.error.illegal_function_call", "Illegal function call"),
+    ("basic.error.overflow", "Overflow"),
+    ("basic.error.undefined_line_number", "Undefined line number"),
+    ("basic.error.subscript_out_of_range", "Subscript out of range"),
+    ("basic.error.duplicate_definition", "Duplicate definition"),
+    ("basic.error.division_by_zero", "Division by zero"),
+    ("basic.error.type_mismatch", "Type mismatch"),
+    ("basic.error.bad_input", "Bad input"),
+    ("basic.error.file_not_found", "File not found"),
+    // Security policy and shell commands
+    ("policy.shell_disabled", "{0} is disabled by policy (shell commands are off; your settings can turn them on with allow_shell and shell_allowlist)"),
+    ("policy.not_allowlisted", "{0} is disabled by policy ('{1}' is not in the shell allowlist)"),
+    ("policy.disabled", "{0} is disabled by policy ({1} is not allowed in Restricted mode)"),
+    ("policy.outside_workspace", "{0} is disabled by policy (path '{1}' is outside the workspace)"),
+    ("policy.run_in_progress", "The security policy cannot change while a program is running"),
+    ("policy.permission.file_io", "file access"),
+    ("policy.permission.network", "network access"),
+    ("policy.permission.plugins", "plugins"),
+    ("policy.permission.shell", "shell commands"),
+    ("shell.expects_command", "SHELL expects a command in quotes or a string variable"),
+    ("shell.start_failed", "SHELL could not start '{0}': {1}"),
+    ("shell.timed_out", "SHELL '{0}' was stopped after {1} seconds"),
+    // Expressions
+    ("expr.unknown_function", "Unknown function: {0}"),
+    ("expr.argument_count", "{0} takes {1} argument(s), got {2}"),
+    ("expr.asc_empty", "ASC of an empty string"),
+    ("expr.subscript_count", "{0} needs {1} subscript(s)"),
+    ("expr.host_argument_count", "{0} takes {1} argument(s)"),
+    ("expr.host_bad_name", "'{0}' is not a function name: a letter, then letters, digits, or _"),
+    ("expr.host_string_function", "{0} is a string function and cannot be replaced"),
+    ("expr.host_builtin", "{0} is a built-in function; register_function_shadowing replaces it"),
+    ("expr.missing_operand", "{0}: missing operand"),
+    ("expr.stack_underflow", "Stack underflow"),
+    ("expr.unknown_array", "Unknown array: {0}"),
+    ("expr.not_text", "'{0}' is not text"),
+    ("expr.takes_text", "{0} takes text: a \"literal\", a NAME$ variable, or a string expression, not '{1}'"),
+    ("expr.expected_close", "{0}: expected ')'"),
+    ("expr.negative_count", "a negative count"),
+    ("expr.start_before_first", "the start is before the first character"),
+    ("expr.missing_argument", "{0}: missing argument"),
+    ("expr.cint_range", "CINT({0}) is outside -32768 to 32767"),
+    ("expr.invalid", "Invalid expression '{0}': {1}"),
+    ("expr.evaluation_failed", "Evaluation failed for '{0}': {1}"),
+    ("expr.parse_failed", "Failed to parse expression '{0}': {1}"),
+    ("expr.too_complex", "Expression too complex (max {0} tokens)"),
+    ("expr.invalid_character", "Invalid character: {0}"),
+    ("expr.undefined_variable", "Undefined variable: {0}"),
+    ("expr.unknown_operator", "Unknown operator: {0}"),
+    ("expr.unknown_comparison", "Unknown comparison: {0}"),
+    ("expr.unexpected_token", "Unexpected token in RPN"),
+    ("expr.missing_operator", "Missing operator"),
+    ("expr.empty_stack", "Empty stack"),
+    // IDE: menus and files
+    ("menu.file", "File"),
+    ("menu.file.new", "📄 New"),
+    ("menu.file.new.file", "New File"),
+    ("menu.file.new.language", "New {0} File"),
+    ("menu.file.open", "📂 Open..."),
+    ("menu.file.save", "💾 Save"),
+    ("menu.file.save_as", "💾 Save As..."),
+    ("menu.file.export", "📤 Export"),
+    ("menu.file.export.text_screen", "🖥 Text Screen…"),
+    ("menu.file.export.text_screen.disabled", "Only in text mode (SCREEN 0)"),
+    ("menu.file.export.scale", "Scale"),
+    ("menu.file.package", "📦 Package Project..."),
+    ("menu.file.exit", "❌ Exit"),
+    ("menu.edit", "Edit"),
+    ("menu.edit.undo", "↶ Undo"),
+    ("menu.edit.redo", "↷ Redo"),
+    ("menu.edit.insert_file", "📥 Insert File at Cursor..."),
+    ("menu.edit.format", "🧹 Format Document"),
+    ("menu.edit.find_replace", "🔍 Find/Replace"),
+    ("menu.edit.find_in_files", "🗂 Find in Files"),
+    ("menu.edit.goto_symbol", "🧭 Go to Symbol..."),
+    ("menu.edit.rename", "✏ Rename Symbol..."),
+    ("menu.edit.evaluate", "Evaluate Selection"),
+    ("menu.run", "Run"),
+    ("menu.run.run", "▶️  Run Program"),
+    ("menu.run.step", "⏸️ Step"),
+    ("menu.run.continue", "⏯️ Continue"),
+    ("menu.run.stop", "⏹️ Stop"),
+    ("menu.view", "View"),
+    ("menu.view.theme", "🎨 Theme"),
+    ("menu.view.settings", "⚙️ Settings..."),
+    ("menu.view.outline", "☰ Outline"),
+    ("menu.view.diagnostics", "🩺 Diagnostics Log..."),
+    ("menu.view.clear_graphics", "🐢 Clear Graphics"),
+    ("menu.view.save_png", "💾 Save Canvas as PNG..."),
+    ("menu.tools", "Tools"),
+    ("menu.tools.share", "🔗 Share (copy link)"),
+    ("menu.tools.import_share", "📥 Import Share..."),
+    ("menu.tools.export_path", "📐 Export Path..."),
+    ("menu.tools.program_report", "📊 Program Report..."),
+    ("menu.tools.run_history", "🕘 Run History..."),
+    ("menu.tools.compare_model", "⚖ Compare With Model..."),
+    ("menu.help", "Help"),
+    ("menu.help.documentation", "📖 Documentation"),
+    ("menu.help.tutorials", "🎓 Tutorials..."),
+    ("menu.help.welcome", "👋 Welcome"),
+    ("menu.help.examples", "🖼 Example Gallery..."),
+    ("menu.help.about", "ℹ️ About"),
+    ("menu.edit.undo_named", "↶ Undo {0}"),
+    ("menu.edit.redo_named", "↷ Redo {0}"),
+    ("menu.view.restore_canvas", "↶ Restore Previous Canvas ({0})"),
+    ("file.kiosk_only", "Only programs in {0} can be opened on this computer"),
+    ("file.open_failed", "Failed to open file: {0}"),
+    ("file.filter.bundle", "Time Warp bundle"),
+    ("file.filter.all", "All"),
+    ("file.filter.png", "PNG Image"),
+    ("file.open_path_failed", "Could not open {0}: {1}"),
+    ("file.filter.programs", "Programs"),
+    ("file.filter.html", "HTML page"),
+    ("file.filter.svg", "SVG Image"),
+    ("file.filter.text", "Text"),
+    ("file.save_failed", "Failed to save {0}: {1}"),
+    ("project.set_by_file", "Set by this project's .timewarp.toml"),
+    ("about.title", "About Time Warp IDE"),
+    ("about.version", "Version {0}"),
+    ("about.tagline", "Educational programming environment for TempleCode"),
+    ("about.languages", "Combining BASIC, PILOT, and Logo"),
+    ("about.repository", "GitHub Repository"),
+    ("common.cancel", "Cancel"),
+    ("common.apply", "Apply"),
+    ("common.error", "Error"),
+    ("common.ok", "OK"),
+    ("common.close", "Close"),
+    ("common.dismiss", "Dismiss"),
+    ("common.copy", "📋 Copy"),
+    ("common.clear", "🗑 Clear"),
+    ("undo.format_document", "Format Document"),
+    ("undo.typing", "Typing"),
+    ("undo.restore_run", "Restore Run"),
+    ("undo.insert_file", "Insert File"),
+    ("undo.rename", "Rename Symbol"),
+    ("undo.replace_in_files", "Replace in Files"),
+    ("undo.move_lines", "Move Lines"),
+    ("undo.indent", "Indent"),
+    ("undo.unindent", "Unindent"),
+    ("undo.duplicate_lines", "Duplicate Lines"),
+    // IDE: editor
+    ("editor.folded_lines", "… {0} lines"),
+    ("editor.insert_failed", "Failed to insert {0}: {1}"),
+    ("editor.untrusted", "🔒 {0} was opened from outside the workspace, so it runs in Restricted mode: no files, network, or plugins."),
+    ("editor.trust", "Trust this file"),
+    ("editor.lossy", "⚠ {0} is not valid UTF-8 text: unreadable bytes were replaced with �, and saving keeps the replacements."),
+    ("editor.bom", "⚠ {0} began with a byte order mark (as Notepad saves files); it was removed so the first line reads correctly."),
+    ("tab.editor", "📝 Editor"),
+    ("tab.output", "📊 Output & Graphics"),
+    ("tab.debug", "🐛 Debug"),
+    ("tab.explorer", "📁 Explorer"),
+    ("tab.help", "❓ Help"),
+    ("tab.diff", "🔀 Diff"),
+    ("tab.welcome", "👋 Welcome"),
+    ("find.title", "Find/Replace"),
+    ("find.find", "Find:"),
+    ("find.replace_with", "Replace:"),
+    ("find.next", "Find Next"),
+    ("find.replace", "Replace"),
+    ("find.replace_all", "Replace All"),
+    ("find.replaced_one", "Replaced one occurrence"),
+    ("find.found", "Found '{0}' in code"),
+    ("find.not_found", "'{0}' not found"),
+    ("find.replaced_many", "Replaced {0} occurrence(s)"),
+    ("find.replaced_with_failures", "Replaced {0} occurrence(s); failed to write:\n{1}"),
+    ("find_in_files.title", "Find in Files"),
+    ("find_in_files.files", "Files:"),
+    ("find_in_files.match_case", "Match case"),
+    ("find_in_files.regex", "Regex"),
+    ("find_in_files.search", "🔍 Search"),
+    ("find_in_files.preview", "Preview Replace"),
+    ("find_in_files.preview_heading", "Replace preview (uncheck files to skip):"),
+    ("find_in_files.plan", "{0} — {1} occurrence(s)"),
+    ("find_in_files.apply", "✔ Apply"),
+    ("find_in_files.matches", "{0} match(es) in {1} file(s)"),
+    ("outline.line", "Line {0}"),
+    ("outline.title", "Outline"),
+    ("outline.empty", "No labels, procedures, or jump targets"),
+    ("outline.goto_title", "Go to Symbol"),
+    ("outline.no_match", "No matching symbols"),
+    ("rename.title", "Rename Symbol"),
+        ("assessment.start", "Start Assessment"),
+        ("assessment.title", "Assessment"),
+        ("assessment.intro", "You will have {0} minutes. The clock starts when you press Start; when it runs out, your program is stopped and the editor is locked."),
+        ("assessment.student", "Your name or student ID:"),
+        ("assessment.submit", "Submit"),
+        ("assessment.stop_first", "Stop the running program first"),
+        ("assessment.over", "⏱ Time is up"),
+        ("assessment.done", "✔ Submitted"),
+        ("assessment.time_up", "Time is up: the program was stopped and the editor is locked. Press Submit to hand in your work."),
+        ("assessment.locked", "The assessment is over; programs can no longer be changed or run."),
+        ("assessment.submitted", "Submitted: {0}"),
+        ("assessment.submit_failed", "Could not submit: {0}"),
+    ("rename.symbol", "Rename {0} to:"),
+    ("rename.all_tabs", "In every open tab"),
+    ("rename.count", "{0} occurrences in {1} tabs"),
+    ("rename.apply", "Rename"),
+    ("rename.no_symbol", "Put the cursor on a variable, label, line number, or procedure to rename it"),
+    ("rename.error.invalid", "'{0}' is not a valid name here"),
+    ("rename.error.reserved", "{0} is a keyword or built-in function"),
+    ("rename.error.taken", "{0} is already used in this program"),
+    ("rename.error.suffix", "The new name must keep the type suffix of {0}"),
+    ("explorer.title", "File Explorer"),
+    ("explorer.soon", "File explorer coming soon:"),
+    ("explorer.tree", "• Project tree view"),
+    ("explorer.operations", "• File operations"),
+    ("explorer.drag_drop", "• Drag and drop"),
+    ("language_switch.current", "Language: {0}"),
+    ("language_switch.locked", "Set by the program's @lang header"),
+    ("language_switch.title", "Switch language?"),
+    ("language_switch.mismatch", "This buffer looks like {0}. Running it as {1} will likely report errors."),
+    ("language_switch.keep", "Keep text, switch to {0}"),
+    ("language_switch.redraw", "Redraw as BASIC LINEs"),
+    ("language_switch.redraw.hover", "Runs the Logo program in a sandbox and writes what it draws as BASIC LINE statements in a new tab"),
+    ("language_switch.new_tab", "New {0} tab"),
+    ("language_switch.redraw_failed", "Cannot redraw as BASIC: {0}"),
+    // IDE: output, canvas and run results
+    ("output.heading", "Unified Screen"),
+    ("output.overlay_text", "Overlay text in graphics"),
+    ("output.highlight_variables", "🔍 Highlight variables"),
+    ("output.highlight_variables.hover", "Underline the parts of T: and PRINT lines that came from variables in the transcript"),
+    ("canvas.saved", "Canvas saved to {0}"),
+    ("canvas.save_failed", "Failed to save PNG: {0}"),
+    ("canvas_export.title", "Save Canvas as PNG"),
+    ("canvas_export.size", "Size"),
+    ("canvas_export.custom", "Custom"),
+    ("canvas_export.transparent", "Transparent background"),
+    ("canvas_export.pixels", "{0} × {1} pixels"),
+    ("canvas_export.save", "Save..."),
+    ("screen.exported", "Text screen saved to {0}"),
+    ("measure.ruler", "📏 Ruler"),
+    ("measure.ruler.hover", "Click two points on the canvas to measure the distance between them"),
+    ("measure.protractor", "📐 Protractor"),
+    ("measure.protractor.hover", "Click a point, the vertex, then another point to measure the angle"),
+    ("measure.hint.ruler_start", "Click where the ruler starts"),
+    ("measure.hint.ruler_end", "Click where the ruler ends"),
+    ("measure.hint.first_arm", "Click a point on the first arm"),
+    ("measure.hint.vertex", "Click the vertex"),
+    ("measure.hint.second_arm", "Click a point on the second arm"),
+    ("measure.distance", "Distance: {0}"),
+    ("measure.angle", "Angle: {0}°"),
+    ("measure.angle_undefined", "Angle: undefined (an arm has no length)"),
+    ("input.press_key", "⌨ Press a key"),
+    ("input.title", "Input Required"),
+    ("input.hint", "Type here and press Enter"),
+    ("input.submit", "Submit"),
+    ("input.press_keys", "⌨ Press {0} keys ({1} so far)"),
+    ("input.tries_left", "⚠️ {0} ({1} tries left)"),
+    ("dialog.title", "Message"),
+    ("dialog.yes", "Yes"),
+    ("dialog.no", "No"),
+    ("dialog.default", "[dialog] \"{0}\": no answer, chose {1}"),
+    ("status.file", "File: {0}"),
+    ("status.no_file", "None"),
+    ("status.theme", "Theme: {0}"),
+    ("status.restricted", "🔒 Restricted"),
+    ("status.restricted.hover", "Restricted mode: file, network, and plugin statements may be blocked"),
+    ("status.low_memory", "🗜 {0} lines flattened"),
+    ("status.low_memory.hover", "Low-memory canvas: the oldest lines are kept as a picture (Settings → Canvas)"),
+    ("status.executing", "Executing..."),
+    ("status.pace", "{0} statements/s"),
+    ("status.pace.hover", "How fast the program runs (or the last run ran), measured at the end of each slice sent to the IDE"),
+    ("status.ready", "Ready"),
+    ("status.program.hover", "Message from the running program (STATUS)"),
+    ("status.waiting_input", "⌨️ Waiting for input"),
+    ("status.paused", "⏸️ Paused"),
+    ("summary.finished", "✅ Finished in {0} — {1}, {2} drawn"),
+    ("summary.output_line", "{0} output line"),
+    ("summary.output_lines", "{0} output lines"),
+    ("summary.segment", "{0} segment"),
+    ("summary.segments", "{0} segments"),
+    ("summary.warning", "{0} warning"),
+    ("summary.warnings", "{0} warnings"),
+    ("summary.seed", "random seed {0}"),
+    ("history.title", "Run History"),
+    ("welcome.title", "Welcome to Time Warp"),
+    ("welcome.intro", "Write PILOT, BASIC, and Logo programs and watch them run. Pick a place to start:"),
+    ("welcome.new_basic", "📝 New BASIC program"),
+    ("welcome.new_basic.hover", "Open a new tab with a BASIC program to fill in"),
+    ("welcome.logo_tutorial", "🐢 Open the Logo tutorial"),
+    ("welcome.logo_tutorial.hover", "Learn to draw with the turtle, one checked step at a time"),
+    ("welcome.example", "📚 Load an example"),
+    ("welcome.example.hover", "Browse the Example Gallery and open a finished program"),
+    ("welcome.demo", "A Logo program drawing: 36 squares, each turned 10°"),
+    ("welcome.show_at_startup", "Show the Welcome tab at startup"),
+    ("welcome.close", "Close the Welcome tab (Help ▸ Welcome opens it again)"),
+    ("history.empty", "No runs yet. Every run that ends is kept here."),
+    ("history.count", "Last {0} runs"),
+    ("history.clear", "Clear"),
+    ("history.pick", "Pick a run to see its output."),
+    ("history.compare", "Compare with Current Output"),
+    ("history.compare.hover", "Show what changed between this run's output and the current one in the Diff tab."),
+    ("history.restore", "Restore This Program"),
+    ("history.restore.hover", "Put the program as it was for this run back into its tab (Undo takes it back)."),
+    ("history.restore.same", "The tab still holds the program this run started from."),
+    ("history.restore.closed", "The run's tab is closed."),
+    ("history.restore.too_long", "The program was too long to keep."),
+    ("history.use_seed", "Use This Seed"),
+    ("history.use_seed.hover", "Start every run's random numbers from this run's seed (Settings → Programs)."),
+    ("compare.title", "Compare With Model"),
+    ("compare.choose", "Choose Model..."),
+    ("compare.none_chosen", "No model answer chosen"),
+    ("compare.answers", "Answers both programs get, one per line:"),
+    ("compare.answers.hint", "e.g. 7"),
+    ("compare.run", "▶ Compare"),
+    ("compare.same", "✅ Same output and same drawing"),
+    ("compare.counts", "{0} line(s) only the model printed, {1} only the student; {2} segment(s) differ"),
+    ("compare.model", "Model answer"),
+    ("compare.student", "Student: {0}"),
+    ("compare.waiting", "Stopped at a question: no answers left"),
+    ("compare.read_failed", "Could not read {0}: {1}"),
+    ("summary.waiting", "⌨ Waiting for input — type your answer below"),
+    ("summary.breakpoint", "⏸ Stopped at a breakpoint before line {0}"),
+    ("summary.stopped", "⏹ Stopped after {0} — {1}"),
+    ("summary.reported_error", "The program reported an error"),
+    ("summary.go_to_line", "Go to line {0}"),
+    ("run.confirm_clear.title", "Clear the canvas?"),
+    ("run.confirm_clear.body", "This program starts with CLEARSCREEN and will erase the current drawing."),
+    ("run.confirm_clear.restore", "You can bring it back later with View → Restore Previous Canvas."),
+    ("run.confirm_clear.ask", "Ask me next time"),
+    ("run.confirm_clear.run", "▶️ Run"),
+    ("run.confirm_restart.title", "Restart the program?"),
+    ("run.confirm_restart.body", "A program is still running. Stop it and run the current tab from the start?"),
+    ("run.confirm_restart.restart", "🔄 Restart"),
+    ("run.load_failed", "Failed to load program: {0}"),
+    ("run.execution_error", "Execution error: {0}"),
+    ("run.step_error", "Step error: {0}"),
+    ("run.load_error", "Load error: {0}"),
+    ("transcript.title", "Transcript"),
+    ("transcript.line", "1 line"),
+    ("transcript.lines", "{0} lines"),
+    ("transcript.hidden", "({0} hidden by the warnings filter)"),
+    ("transcript.copy_all", "📋 Copy All"),
+    ("transcript.save", "💾 Save…"),
+    ("transcript.save_html", "🌐 Save HTML…"),
+    ("transcript.times", "🕑 Times"),
+    ("transcript.times.hover", "Show when each line appeared, from the start of the run"),
+    ("transcript.untimed", "Turn on Settings → Record output timing and run again"),
+    ("transcript.replay", "▶ Replay"),
+    ("transcript.replay_stop", "⏹ Stop replay"),
+    ("transcript.show_full", "Show full line"),
+    ("transcript.line_window", "Output Line {0}"),
+    ("transcript.characters", "{0} characters"),
+    ("diff.pin", "📌 Pin output"),
+    ("diff.pin.hover", "Keep this transcript to compare with the next run"),
+    ("diff.unpin", "Unpin"),
+    ("diff.title", "Output Diff"),
+    ("diff.empty", "Pin an output on the Output & Graphics tab, run again, and the differences appear here."),
+    ("diff.identical", "Transcripts are identical."),
+    ("diff.counts", "{0} line(s) added, {1} removed"),
+    ("export_path.title", "Export Path"),
+    ("export_path.about", "Writes each turtle line segment as x1, y1, x2, y2, color, width."),
+    ("export_path.simplify", "Simplify (merge collinear segments)"),
+    ("export_path.tolerance", "tolerance "),
+    ("export_path.simplified", "{0} segment(s) → {1} ({2} merged)"),
+    ("export_path.segments", "{0} segment(s)"),
+    ("report.title", "Program Report"),
+    ("report.statements", "Statements"),
+    ("report.nesting", "Deepest nesting"),
+    ("report.longest_line", "Longest line"),
+    ("report.line_chars", "line {0} ({1} characters)"),
+    ("report.variables", "Variables"),
+    ("report.unused", "Set but never read"),
+    ("report.unreachable", "Unreachable lines"),
+    ("report.none", "none"),
+    ("report.count_names", "{0}: {1}"),
+    ("report.constructs", "Statements per construct"),
+    ("report.procedure_call", "procedure call"),
+    // IDE: debugger, diagnostics and direct mode
+    ("debugger.title", "Debugger"),
+    ("debugger.variables", "Variables"),
+    ("debugger.export_csv", "Export CSV..."),
+    ("debugger.export_json", "Export JSON..."),
+    ("debugger.no_variables", "No variables yet. Run a program to inspect its variables."),
+    ("debugger.kept", "Kept for the next run (COMMON or Keep variables between runs)"),
+    ("debugger.set_at", "line {0}"),
+    ("debugger.set_at.hover", "Last assigned on line {0}; click to show it"),
+    ("debugger.inspect", "{0} = {1}"),
+    ("debugger.inspect.set_at", "{0} = {1}, set on line {2}"),
+    ("debugger.breakpoints", "Breakpoints ({0})"),
+    ("debugger.breakpoints.enabled", "Stop at breakpoints from the program"),
+    ("debugger.breakpoints.line", "{0} line {1}"),
+    ("debugger.breakpoints.from_source", "from source"),
+    ("debugger.answer_history", "Answer history ({0})"),
+    ("debugger.series", "Recorded Series"),
+    ("debugger.series_hint", "Use LOGVAR X in a loop to record a variable's value each time it runs."),
+    ("debugger.series_values", "{0} ({1} values)"),
+    ("debugger.max", "max {0}"),
+    ("debugger.min", "min {0}"),
+    ("debugger.export_failed", "Failed to export: {0}"),
+    ("diagnostics.title", "Diagnostics Log"),
+    ("diagnostics.off", "Logging is not set up in this session."),
+    ("diagnostics.count", "{0} event(s), filter: {1}"),
+    ("diagnostics.change_filter", "Change the filter in Settings → Diagnostics."),
+    ("direct.hint", "FD 50, RT 90, … then Enter"),
+    ("direct.copy_program", "📋 Copy as program"),
+    ("direct.copy_program.hover", "Open the recorded Logo statements in a new tab"),
+    ("direct.wrap", "in TO {0}"),
+    ("direct.copy_basic", "Copy as BASIC"),
+    ("direct.copy_basic.hover", "Open the recorded drawing as BASIC LINE statements"),
+    ("direct.clear", "Clear recording"),
+    ("direct.recorded_one", "1 statement recorded"),
+    ("direct.recorded", "{0} statements recorded"),
+    ("inline.ok", "ok"),
+    ("inline.busy", "A program is running; stop it to evaluate"),
+    // IDE: settings
+    ("settings.project_mark", "📁 project"),
+    ("settings.language.from_extension", "From the file extension"),
+    ("settings.language.templecode", "TempleCode (mixed)"),
+    ("settings.screen.as_program", "As the program sets it"),
+    ("settings.screen.text", "Text"),
+    ("settings.screen.graphics", "Graphics"),
+    ("settings.screen.columns", " columns"),
+    ("settings.screen.rows", " rows"),
+    ("settings.screen.px_wide", " px wide"),
+    ("settings.screen.px_high", " px high"),
+    ("settings.seed.same", "Same every run"),
+    ("settings.title", "Settings"),
+    ("settings.project", "Project"),
+    ("settings.project.marked", "Values marked 📁 project come from that file and replace yours while the project is open."),
+    ("settings.project.edit", "📝 Edit project file"),
+    ("settings.project.reload", "🔄 Reload"),
+    ("settings.project.none", "No project folder: open or save a file first."),
+    ("settings.project.save_defaults", "💾 Save as Project Defaults"),
+    ("settings.project.save_defaults.hover", "Write the language, screen, limits, theme, classroom, and tab settings in effect to the project folder"),
+    ("settings.project.replace", "This folder already has a {0}. Replace it?"),
+    ("settings.project.replace.confirm", "Replace"),
+    ("settings.appearance", "Appearance"),
+    ("settings.theme", "Theme"),
+    ("settings.ui_scale", "UI scale"),
+    ("settings.accessibility", "Accessibility"),
+    ("settings.cvd_safe", "CVD-safe colors (Okabe-Ito palette for named Logo colors)"),
+    ("settings.export_as_displayed", "Export as displayed"),
+    ("settings.export_as_displayed.note", "Saved images keep the authored colors unless \"Export as displayed\" is set."),
+    ("settings.tts", "Text-to-speech (speak every T: and PRINT line)"),
+    ("settings.tts.none", "No speech engine available: V: lines are shown as [voice] text."),
+    ("settings.canvas", "Canvas"),
+    ("settings.confirm_clearscreen", "Confirm before a program's CLEARSCREEN wipes the drawing"),
+    ("settings.export_turtle", "Include the turtle in saved images"),
+    ("settings.export_measurements", "Include the ruler or protractor in saved images"),
+    ("settings.canvas.restore_note", "View → Restore Previous Canvas brings back the drawing from before each run."),
+    ("settings.low_memory_canvas", "Low-memory canvas: keep at most"),
+    ("settings.low_memory_canvas.lines", "lines"),
+    ("settings.low_memory_canvas.note", "Beyond that, the oldest lines are flattened into a picture of the canvas. Saves memory on huge drawings; exports still show everything, but zooming in shows pixels. Takes effect from the next run."),
+    ("settings.logo_headings", "Logo headings"),
+    ("settings.angle.compass", "Compass (0 = north, clockwise)"),
+    ("settings.angle.math", "Math (0 = east, counter-clockwise)"),
+    ("settings.turtle_shape", "Turtle shape"),
+    ("settings.turtle_shape.load", "Load Points File…"),
+    ("settings.turtle_shape.load.hover", "One \"x y\" pair per line, the turtle facing up"),
+    ("settings.basic.classic_errors", "Classic error messages (?SYNTAX ERROR IN 20)"),
+    ("settings.basic.classic_errors.note", "Programs can also switch with OPTION ERRORS CLASSIC / OPTION ERRORS MODERN."),
+    ("settings.warnings", "Warnings"),
+    ("settings.warnings.show", "Show warnings in the output"),
+    ("settings.warnings.strict", "Strict mode: treat these as errors"),
+    ("settings.programs", "Programs"),
+    ("settings.language_mode", "Language mode"),
+    ("settings.language_mode.note", "Used for programs without an @lang header."),
+    ("settings.return_to_editor", "Return to the editor after a run finishes without errors"),
+    ("settings.keep_variables", "Keep variables between runs"),
+    ("settings.keep_variables.hover", "Without this, only BASIC COMMON variables carry over; CLEAR forgets them"),
+    ("settings.start_screen", "Start screen"),
+    ("settings.tab_width", "Tab width"),
+    ("settings.tab_width.spaces", " spaces"),
+    ("settings.line_ending", "Line endings on save"),
+    ("settings.line_ending.keep", "Keep each file's"),
+    ("settings.trim_trailing_whitespace", "Remove spaces at the ends of lines on save"),
+    ("settings.random_seed", "Random seed"),
+    ("settings.random_seed.note", "A run that uses RND or QZ:SHUFFLE shows its seed when it finishes; enter it here to repeat the run."),
+    ("settings.autorun_on_open", "Run the first program opened with --open at startup"),
+    ("settings.disabled_statements", "Disabled statements"),
+    ("settings.disabled_statements.hint", "e.g. GOTO, GOSUB"),
+    ("settings.disabled_statements.note", "Runs refuse these statements and the editor underlines them, to teach without them (--disable adds more for one session)."),
+    ("settings.honor_source_breakpoints", "Stop at breakpoints written in the program"),
+    ("settings.honor_source_breakpoints.hover", "A REM BREAK, 'BREAK, R:BREAK, or ;BREAK line stops the run before the next statement; Run ▸ Continue carries on."),
+    ("settings.classroom", "Classroom"),
+    ("settings.restricted", "Restricted mode (block file, network, and plugin statements)"),
+    ("settings.restricted.locked", "Restricted mode was turned on with --restricted and cannot be changed."),
+    ("settings.restricted.files", "Allow files"),
+    ("settings.restricted.network", "Allow network"),
+    ("settings.restricted.plugins", "Allow plugins"),
+    ("settings.restricted.workspace", "Workspace folder"),
+    ("settings.restricted.anywhere", "(anywhere)"),
+    ("settings.restricted.time_limit", "Time limit (seconds)"),
+    ("settings.restricted.max_statements", "Max statements per run"),
+    ("settings.restricted.next_run", "Changes apply from the next run."),
+    ("settings.record_output_timing", "Record output timing"),
+    ("settings.record_output_timing.hover", "Note when each output line appears and how long each prompt waits for an answer, for the run report, the Output panel's time gutter, and Replay."),
+    ("settings.slice_statements", "Statements per slice:"),
+    ("settings.slice_statements.hover", "A run is sent to the Output tab and canvas in slices. By default each slice runs as many statements as fit in about 4 ms; a fixed number makes every slice the same length however long its statements take."),
+    ("settings.slice_statements.fixed", "Fixed"),
+    ("settings.keep_run_history", "Keep run history"),
+    ("settings.keep_run_history.hover", "Save the last runs of each project folder, so Tools → Run History still has them next session."),
+    ("settings.diagnostics", "Diagnostics"),
+    ("settings.log_filter", "Log filter"),
+    ("settings.log_filter.note", "e.g. time_warp_core=debug for jumps and errors, time_warp_core=trace for every line."),
+    ("settings.diagnostics.open", "🩺 Open Diagnostics Log"),
+    ("settings.limits", "Limits"),
+    ("settings.limits.max_program_bytes", "Max program bytes"),
+    ("settings.limits.max_program_lines", "Max program lines"),
+    ("settings.limits.max_repeat_count", "Max REPEAT count"),
+    ("settings.limits.max_for_iterations", "Max FOR iterations"),
+    ("settings.limits.max_output_lines", "Max output lines"),
+    ("settings.limits.max_output_line_chars", "Max output line length"),
+    ("settings.limits.chars", " chars"),
+    ("settings.limits.input_retries", "Validated INPUT retries"),
+    ("settings.limits.answer_history", "PILOT answers kept (%ANS1…)"),
+    ("settings.limits.max_bracket_depth", "Max bracket nesting (REPEAT)"),
+    ("settings.limits.max_paren_depth", "Max parenthesis nesting"),
+    ("settings.limits.oversized_loops", "Oversized loops"),
+    ("settings.limits.warn_and_clamp", "Warn and clamp"),
+    ("settings.limits.error", "Error"),
+    ("settings.basic", "BASIC"),
+    ("settings.project_mark.hover", "Set by this project's {0}; edit that file to change it"),
+    ("settings.project.using", "Using {0}"),
+    ("settings.project.missing", "No {0} in {1}"),
+    ("settings.turtle_shape.failed", "Cannot use {0} as a turtle shape: {1}"),
+    ("settings.log_filter.invalid", "Invalid log filter: {0}"),
+    ("settings.project.save_failed", "Could not save project defaults: {0}"),
+    ("settings.project.no_folder", "open or save a file first; its folder is the project"),
+    ("settings.turtle_shape.points_filter", "Points"),
+    ("settings.language", "Language"),
+    ("settings.language.note", "Menus, panels, and the messages of runs. Keywords such as PRINT and FORWARD stay in English."),
+    ("settings.project.ignored", "Ignoring project settings: {0}"),
+    // IDE: sharing, packaging and tutorials
+    ("share.failed", "Share failed: {0}"),
+    ("share.title", "Share Program"),
+    ("share.copied", "Link copied to the clipboard. Paste it anywhere; open it with Tools → Import Share."),
+    ("share.import_title", "Import Share"),
+    ("share.paste", "Paste a timewarp://code/… link:"),
+    ("share.import", "📥 Import"),
+    ("share.import_failed", "Import failed: {0}"),
+    ("package.save_first", "Save the program before packaging it."),
+    ("package.title", "Package Project"),
+    ("package.entry", "Entry program: {0}"),
+    ("package.unsaved", "Unsaved changes are not included; save first."),
+    ("package.include", "Include these files (programs it loads are ticked):"),
+    ("package.no_files", "No other files in this folder."),
+    ("package.save", "📦 Save Bundle..."),
+    ("package.done", "Packaged {0} file(s) to {1}"),
+    ("package.failed", "Failed to package project: {0}"),
+    ("gallery.title", "Example Gallery"),
+    ("gallery.open", "📂 Open"),
+    ("tutorial.title", "Tutorial"),
+    ("tutorial.none", "No tutorials available."),
+    ("tutorial.step", "Step {0} of {1}"),
+    ("tutorial.completed", "({0} completed)"),
+    ("tutorial.hint", "💡 Hint"),
+    ("tutorial.check", "✅ Check"),
+    ("tutorial.reset", "↺ Reset"),
+    ("tutorial.show_solution", "Show solution"),
+    ("tutorial.previous", "◀ Previous"),
+    ("tutorial.next", "Next ▶"),
+    ("tutorial.complete", "✔ Correct! Tutorial complete."),
+    ("tutorial.correct", "✔ Correct! On to the next step."),
+    ("tutorial.output", "Program output"),
+];
@@ -0,0 +1,710 @@
+//! Spanish (español)
+
+pub const STRINGS: &[(&str, &str)] = &[
+    // Run results and errors
+    ("run.stopped", "⏹ Programa detenido"),
+    ("error.plain", "❌ Error: {0}"),
+    ("error.at_line", "❌ Error en la línea {0}: {1}"),
+    ("error.hint", "Pista: {0}"),
+    ("hint.did_you_mean", "¿Quisiste decir '{0}'?"),
+    ("hint.unclosed_quote", "Comilla de texto sin cerrar (\")"),
+    ("hint.missing_closing", "Faltan {0} paréntesis de cierre"),
+    ("hint.missing_opening", "Faltan {0} paréntesis de apertura"),
+    ("hint.let_needs_equals", "La instrucción LET necesita '=' (p. ej., LET X = 10)"),
+    ("hint.if_needs_then", "La instrucción IF necesita THEN (p. ej., IF X > 10 THEN PRINT \"Hola\")"),
+    ("hint.print_quote", "Los textos en PRINT van entre comillas (p. ej., PRINT \"Hola\")"),
+    ("error.internal", "Error interno del intérprete — por favor, infórmalo: {0}, línea {1}"),
+    ("error.internal_direct", "Error interno del intérprete — por favor, infórmalo: {0}"),
+    ("error.timeout", "Tiempo de ejecución agotado (se superaron {0} segundos)"),
+    ("error.infinite_loop", "Bucle infinito detectado entre las líneas {0}–{1} (el estado no cambia)"),
+    ("error.unknown_panic", "pánico desconocido"),
+    ("error.not_waiting", "El programa no está esperando una respuesta"),
+    ("output.truncated", "… [{0} caracteres más]"),
+    // Warnings
+    ("warning.prefix", "⚠️ Advertencia"),
+    ("warning.at_line", "{0} en la línea {1}: {2}"),
+    ("warning.strict", "{0} (modo estricto)"),
+    ("warning.unset", "{0} se usa antes de tener valor"),
+    ("warning.reads_as_zero", "{0}, así que vale 0"),
+    ("warning.near_limit", "Se ha usado el {0}% del límite de {1} instrucciones; la ejecución se detiene al llegar al límite"),
+    ("warning.nan_condition", "La condición no es un número (NaN), así que se toma como falsa"),
+    ("warning.max_iterations", "Se alcanzó el número máximo de iteraciones"),
+    ("warning.jump_into_loop", "GOTO {0} salta dentro del cuerpo de `{1}`, que no se está ejecutando"),
+    ("warning.string_for_number", "{0} recibe el texto \"{1}\"; llámala {0}$ para guardar texto"),
+    ("warning.kind.undefined_variable", "Variable usada antes de tener valor"),
+    ("warning.kind.jump_into_loop", "GOTO dentro de un bucle FOR que no se está ejecutando"),
+    ("warning.kind.string_for_number", "Texto guardado en una variable numérica"),
+    ("warning.kind.near_iteration_limit", "Ejecución cerca de su límite de iteraciones"),
+    ("warning.kind.nan_condition", "Condición que no es un número (NaN)"),
+    // Limits
+    ("limit.exceeded", "{0} {1} supera el límite de {2} (aumenta \"{3}\" en Ajustes → Límites)"),
+    ("limit.program_bytes", "Tamaño del programa (bytes)"),
+    ("limit.program_lines", "Longitud del programa (líneas)"),
+    ("limit.output", "Salida"),
+    ("limit.line_count", "número de líneas"),
+    ("limit.repeat_count", "Número de REPEAT"),
+    ("limit.for_span", "Recorrido de FOR"),
+    ("limit.bracket_depth", "Profundidad de corchetes de REPEAT"),
+    ("limit.paren_depth", "Profundidad de paréntesis"),
+    ("limit.output_discarded", "{0}; se descarta el resto de la salida"),
+    ("limit.clamped", "{0}; se reduce a {1}"),
+    // Lessons: disabled statements
+    ("lesson.disabled", "{0} está desactivado en esta lección"),
+    ("lesson.disabled_use", "{0} está desactivado en esta lección — usa {1} en su lugar"),
+    // Reserved words
+    ("reserved.word", "{0} es una palabra reservada en {1}"),
+    // Languages
+    ("pilot.unknown_command", "Comando PILOT desconocido: {0}"),
+    ("pilot.ask_again_without_question", "AN: no tiene un A: anterior que volver a preguntar"),
+    ("pilot.text_width_range", "TW: el ancho debe estar entre 0 y 1000"),
+    ("pilot.label_not_found", "Etiqueta no encontrada: {0}"),
+    ("pilot.runtime_unimplemented", "Comando de ejecución aún no implementado: R:{0}"),
+    ("pilot.match_option", "MC: opción desconocida '{0}' (usa FOLD, PUNCT, SPACE, EXACT; -NOMBRE desactiva una)"),
+    ("pilot.quiz_data_expects", "D: necesita pregunta|respuesta1,respuesta2|comentario"),
+    ("pilot.quiz_option", "QZ: opción desconocida '{0}' (usa QZ: o QZ:SHUFFLE)"),
+    ("pilot.quiz_seed", "La semilla de QZ:SHUFFLE debe ser un número entero"),
+    ("pilot.quiz_no_questions", "QZ: este programa no tiene preguntas D:"),
+    ("pilot.quiz_correct", "¡Correcto!"),
+    ("pilot.quiz_wrong", "No exactamente. La respuesta es {0}."),
+    ("pilot.quiz_score", "Puntuación: {0} de {1}"),
+    ("logo.too_deep", "{0} llamó a procedimientos con más de {1} niveles (¿usa STOP?)"),
+    ("logo.unknown_command", "Comando Logo desconocido: {0}"),
+    ("logo.color_cycle_list", "SETCOLORCYCLE espera una lista de colores, como [RED YELLOW GREEN], no '{0}'"),
+    ("logo.color_cycle_color", "SETCOLORCYCLE: '{0}' no es un nombre de color ni #RRGGBB"),
+    ("logo.unbalanced_brackets", "Corchetes desequilibrados en {0}"),
+    ("logo.program_stopped", "Programa detenido"),
+    ("logo.angle_mode_expects", "SETANGLEMODE espera COMPASS o MATH, no '{0}'"),
+    ("logo.towards_expects", "TOWARDS necesita x e y"),
+    ("logo.arc_expects", "ARC necesita un ángulo y un radio"),
+    ("logo.shape_unreadable", "SETSHAPE no puede leer {0}: {1}"),
+    ("logo.shape_expects", "SETSHAPE espera {0} o un archivo de puntos, no '{1}'"),
+    ("logo.shape_bad_line", "línea {0}: se esperaban dos números, se obtuvo '{1}'"),
+    ("logo.shape_too_few", "una forma necesita al menos 3 puntos, hay {0}"),
+    ("logo.shape_at_origin", "todos los puntos están en (0, 0)"),
+    ("logo.needs_xy", "{0} necesita x e y, p. ej., {0} 100 50 o {0} [100 50]"),
+    ("logo.repeat_missing_bracket", "Falta '[' en REPEAT"),
+    ("logo.to_missing_name", "Falta el nombre del procedimiento en TO"),
+    ("logo.to_missing_end", "Falta END en TO {0}"),
+    ("logo.procedure_not_found", "No se encontró el procedimiento {0}"),
+    ("basic.unknown_command", "Comando BASIC desconocido: {0}"),
+    ("basic.goto_failed", "GOTO {0} falló: no existe esa línea"),
+    ("basic.gosub_failed", "GOSUB {0} falló: no existe esa línea"),
+    ("basic.if_missing_then", "Falta THEN en IF"),
+    ("basic.loop_condition", "{0} no lleva nada, o WHILE o UNTIL y una condición"),
+    ("basic.do_without_loop", "DO sin LOOP"),
+    ("basic.loop_without_do", "LOOP sin DO"),
+    ("basic.exit_expects", "EXIT espera FOR o DO"),
+    ("basic.exit_outside_loop", "EXIT {0} fuera de un bucle {0}"),
+    ("basic.exit_unclosed", "EXIT {0} en un bucle que nunca se cierra"),
+    ("basic.on_expects", "ON necesita GOTO o GOSUB"),
+    ("basic.on_range", "ON {0} está fuera de 0 a 255"),
+    ("basic.msgbox_expects", "MSGBOX espera un mensaje, o mensaje, YESNO, variable"),
+    ("basic.screen_missing_mode", "❌ SCREEN: falta el modo"),
+    ("basic.screen_unsupported", "❌ SCREEN: modo {0} no admitido"),
+    ("basic.screen_set", "🎨 SCREEN establecido: {0}"),
+    ("basic.screen_cleared", "🎨 Pantalla borrada"),
+    ("basic.print_using_format", "PRINT USING necesita una cadena de formato"),
+    ("basic.input_missing_variable", "Falta el nombre de la variable en INPUT"),
+    ("basic.input_expects_clause", "INPUT espera AS NUMBER o RANGE mín TO máx después de la variable"),
+    ("basic.input_range_missing_to", "Falta TO en INPUT RANGE"),
+    ("basic.input_expects_range", "INPUT espera RANGE mín TO máx después de AS NUMBER"),
+    ("basic.input_between", "debe estar entre {0} y {1}"),
+    ("basic.input_at_least", "debe ser un número no menor que {0}"),
+    ("basic.input_at_most", "debe ser un número no mayor que {0}"),
+    ("basic.input_number", "debe ser un número"),
+    ("basic.input_gave_up", "INPUT {0}: ninguna respuesta válida tras {1} intentos ({2})"),
+    ("basic.input_keys_range", "INPUT$ necesita de 1 a 255 teclas, no {0}"),
+    ("basic.for_missing_equals", "Falta '=' en FOR"),
+    ("basic.for_missing_to", "Falta TO en FOR"),
+    ("basic.for_step_zero", "FOR con STEP 0 nunca termina"),
+    ("basic.next_mismatch", "NEXT {0} no corresponde a FOR {1}"),
+    ("basic.option_expects", "OPTION espera ERRORS CLASSIC, ERRORS MODERN, BASE 0, BASE 1, COORDS SCREEN o COORDS CENTERED"),
+    ("basic.read_not_number", "READ {0}: el valor DATA \"{1}\" no es un número"),
+    ("basic.merge_replaced_one", "⚠️ MERGE \"{0}\" reemplazó la línea {1}"),
+    ("basic.merge_replaced_many", "⚠️ MERGE \"{0}\" reemplazó las líneas {1}"),
+    ("basic.callfile_missing_name", "CALLFILE necesita un nombre de archivo"),
+    ("basic.callfile_too_deep", "CALLFILE \"{0}\": llamadas anidadas a más de {1} archivos de profundidad ({2} → {0})"),
+    ("basic.chain_missing_name", "CHAIN necesita un nombre de archivo"),
+    ("basic.merge_missing_name", "MERGE necesita un nombre de archivo"),
+    ("basic.merge_unnumbered", "MERGE \"{0}\" línea {1}: solo se pueden fusionar líneas numeradas"),
+    ("basic.plot_missing_series", "Falta el nombre de la serie en PLOT"),
+    ("basic.plot_no_series", "PLOT: no hay ninguna serie registrada '{0}' (usa LOGVAR {0} antes)"),
+    ("basic.logvar_missing_name", "A LOGVAR le falta el nombre de la variable"),
+    ("basic.plot_empty_series", "PLOT: la serie '{0}' no tiene valores"),
+    ("basic.type.holds_numbers", "Tipos incompatibles: {0} guarda números"),
+    ("basic.type.holds_text", "Tipos incompatibles: {0} guarda texto"),
+    ("basic.type.integer_range", "Desbordamiento: {0} guarda números enteros de -32768 a 32767, no {1}"),
+    ("basic.type.range_backwards", "El rango de letras {0} va al revés"),
+    ("basic.type.expected_letter", "Se esperaba una letra o un rango como I-N, no '{0}'"),
+    ("basic.type.holds_strings", "Tipos incompatibles: {0} guarda textos"),
+    ("basic.type.needs_number", "Tipos incompatibles: {0} necesita un número"),
+    ("basic.dim.dimensions", "DIM {0} necesita uno o dos límites, como {0}(10) o {0}(3, 3)"),
+    ("basic.dim.below_base", "DIM {0}: el límite {1} es menor que OPTION BASE {2}"),
+    ("basic.dim.too_many", "DIM {0} necesita {1} elementos; el máximo es {2}"),
+    ("basic.dim.expects", "DIM espera NOMBRE(tamaño), no '{0}'"),
+    ("basic.dim.duplicate", "Definición duplicada: {0} ya está dimensionada"),
+    ("basic.dim.negative", "DIM {0}: el límite {1} debe ser 0 o más"),
+    ("basic.dim.base_after_dim", "OPTION BASE debe ir antes del primer DIM"),
+    ("basic.dim.not_dimensioned", "{0} no está dimensionada (usa DIM {0}(tamaño) antes)"),
+    ("basic.dim.dimension_count", "Subíndice fuera de rango: {0} tiene {1} dimensión(es)"),
+    ("basic.dim.subscript_range", "Subíndice fuera de rango: {0} (la dimensión {1} va de {2} a {3})"),
+    ("basic.error.next_without_for", "NEXT sin FOR"),
+    ("basic.error.syntax", "Error de sintaxis"),
+    ("basic.error.return_without_gosub", "RETURN sin GOSUB"),
+    ("basic.error.out_of_data", "No quedan DATA"),
+    ("basic.error.illegal_function_call", "Llamada a función no válida"),
+    ("basic.error.overflow", "Desbordamiento"),
+    ("basic.error.undefined_line_number", "Número de línea no definido"),
+    ("basic.error.subscript_out_of_range", "Subíndice fuera de rango"),
+    ("basic.error.duplicate_definition", "Definición duplicada"),
+    ("basic.error.division_by_zero", "División por cero"),
+    ("basic.error.type_mismatch", "Tipos incompatibles"),
+    ("basic.error.bad_input", "Entrada no válida"),
+    ("basic.error.file_not_found", "Archivo no encontrado"),
+    // Security policy and shell commands
+    ("policy.shell_disabled", "{0} está desactivado por la política (los comandos del sistema están apagados; tu configuración puede activarlos con allow_shell y shell_allowlist)"),
+    ("policy.not_allowlisted", "{0} está desactivado por la política ('{1}' no está en la lista de programas permitidos)"),
+    ("policy.disabled", "{0} está desactivado por la política ({1} no se permite en el modo Restringido)"),
+    ("policy.outside_workspace", "{0} está desactivado por la política (la ruta '{1}' está fuera del espacio de trabajo)"),
+    ("policy.run_in_progress", "La política de seguridad no puede cambiar mientras se ejecuta un programa"),
+    ("policy.permission.file_io", "el acceso a archivos"),
+    ("policy.permission.network", "el acceso a la red"),
+    ("policy.permission.plugins", "los complementos"),
+    ("policy.permission.shell", "los comandos del sistema"),
+    ("shell.expects_command", "SHELL espera un comando entre comillas o una variable de texto"),
+    ("shell.start_failed", "SHELL no pudo iniciar '{0}': {1}"),
+    ("shell.timed_out", "SHELL '{0}' se detuvo tras {1} segundos"),
+    // Expressions
+    ("expr.unknown_function", "Función desconocida: {0}"),
+    ("expr.argument_count", "{0} lleva {1} argumento(s), no {2}"),
+    ("expr.asc_empty", "ASC de un texto vacío"),
+    ("expr.subscript_count", "{0} necesita {1} subíndice(s)"),
+    ("expr.host_argument_count", "{0} lleva {1} argumento(s)"),
+    ("expr.host_bad_name", "'{0}' no es un nombre de función: una letra y después letras, dígitos o _"),
+    ("expr.host_string_function", "{0} es una función de texto y no se puede reemplazar"),
+    ("expr.host_builtin", "{0} es una función predefinida; register_function_shadowing la reemplaza"),
+    ("expr.missing_operand", "{0}: falta un operando"),
+    ("expr.stack_underflow", "Faltan operandos en la pila"),
+    ("expr.unknown_array", "Matriz desconocida: {0}"),
+    ("expr.not_text", "'{0}' no es texto"),
+    ("expr.takes_text", "{0} lleva texto: un \"literal\", una variable NOMBRE$ o una expresión de texto, no '{1}'"),
+    ("expr.expected_close", "{0}: se esperaba ')'"),
+    ("expr.negative_count", "una cantidad negativa"),
+    ("expr.start_before_first", "el inicio está antes del primer carácter"),
+    ("expr.missing_argument", "{0}: falta un argumento"),
+    ("expr.cint_range", "CINT({0}) está fuera de -32768 a 32767"),
+    ("expr.invalid", "Expresión no válida '{0}': {1}"),
+    ("expr.evaluation_failed", "Falló la evaluación de '{0}': {1}"),
+    ("expr.parse_failed", "No se pudo analizar la expresión '{0}': {1}"),
+    ("expr.too_complex", "Expresión demasiado compleja (máximo {0} elementos)"),
+    ("expr.invalid_character", "Carácter no válido: {0}"),
+    ("expr.undefined_variable", "Variable no definida: {0}"),
+    ("expr.unknown_operator", "Operador desconocido: {0}"),
+    ("expr.unknown_comparison", "Comparación desconocida: {0}"),
+    ("expr.unexpected_token", "Elemento inesperado en la notación polaca inversa"),
+    ("expr.missing_operator", "Falta un operador"),
+    ("expr.empty_stack", "Pila vacía"),
+    // IDE: menus and files
+    ("menu.file", "Archivo"),
+    ("menu.file.new", "📄 Nuevo"),
+    ("menu.file.new.file", "Archivo nuevo"),
+    ("menu.file.new.language", "Nuevo archivo {0}"),
+    ("menu.file.open", "📂 Abrir..."),
+    ("menu.file.save", "💾 Guardar"),
+    ("menu.file.save_as", "💾 Guardar como..."),
+    ("menu.file.export", "📤 Exportar"),
+    ("menu.file.export.text_screen", "🖥 Pantalla de texto…"),
+    ("menu.file.export.text_screen.disabled", "Solo en modo texto (SCREEN 0)"),
+    ("menu.file.export.scale", "Escala"),
+    ("menu.file.package", "📦 Empaquetar proyecto..."),
+    ("menu.file.exit", "❌ Salir"),
+    ("menu.edit", "Editar"),
+    ("menu.edit.undo", "↶ Deshacer"),
+    ("menu.edit.redo", "↷ Rehacer"),
+    ("menu.edit.insert_file", "📥 Insertar archivo en el cursor..."),
+    ("menu.edit.format", "🧹 Dar formato al documento"),
+    ("menu.edit.find_replace", "🔍 Buscar/Reemplazar"),
+    ("menu.edit.find_in_files", "🗂 Buscar en archivos"),
+    ("menu.edit.goto_symbol", "🧭 Ir al símbolo..."),
+    ("menu.edit.rename", "✏ Renombrar símbolo..."),
+    ("menu.edit.evaluate", "Evaluar selección"),
+    ("menu.run", "Ejecutar"),
+    ("menu.run.run", "▶️  Ejecutar programa"),
+    ("menu.run.step", "⏸️ Paso a paso"),
+    ("menu.run.continue", "⏯️ Continuar"),
+    ("menu.run.stop", "⏹️ Detener"),
+    ("menu.view", "Ver"),
+    ("menu.view.theme", "🎨 Tema"),
+    ("menu.view.settings", "⚙️ Ajustes..."),
+    ("menu.view.outline", "☰ Esquema"),
+    ("menu.view.diagnostics", "🩺 Registro de diagnóstico..."),
+    ("menu.view.clear_graphics", "🐢 Borrar gráficos"),
+    ("menu.view.save_png", "💾 Guardar lienzo como PNG..."),
+    ("menu.tools", "Herramientas"),
+    ("menu.tools.share", "🔗 Compartir (copiar enlace)"),
+    ("menu.tools.import_share", "📥 Importar enlace compartido..."),
+    ("menu.tools.export_path", "📐 Exportar trayectoria..."),
+    ("menu.tools.program_report", "📊 Informe del programa..."),
+    ("menu.tools.run_history", "🕘 Historial de ejecuciones..."),
+    ("menu.tools.compare_model", "⚖ Comparar con el modelo..."),
+    ("menu.help", "Ayuda"),
+    ("menu.help.documentation", "📖 Documentación"),
+    ("menu.help.tutorials", "🎓 Tutoriales..."),
+    ("menu.help.welcome", "👋 Bienvenida"),
+    ("menu.help.examples", "🖼 Galería de ejemplos..."),
+    ("menu.help.about", "ℹ️ Acerca de"),
+    ("menu.edit.undo_named", "↶ Deshacer {0}"),
+    ("menu.edit.redo_named", "↷ Rehacer {0}"),
+    ("menu.view.restore_canvas", "↶ Restaurar lienzo anterior ({0})"),
+    ("file.kiosk_only", "En este equipo solo se pueden abrir programas de {0}"),
+    ("file.open_failed", "No se pudo abrir el archivo: {0}"),
+    ("file.filter.bundle", "Paquete de Time Warp"),
+    ("file.filter.all", "Todos"),
+    ("file.filter.png", "Imagen PNG"),
+    ("file.open_path_failed", "No se pudo abrir {0}: {1}"),
+    ("file.filter.programs", "Programas"),
+    ("file.filter.html", "Página HTML"),
+    ("file.filter.svg", "Imagen SVG"),
+    ("file.filter.text", "Texto"),
+    ("file.save_failed", "No se pudo guardar {0}: {1}"),
+    ("project.set_by_file", "Definido por el .timewarp.toml de este proyecto"),
+    ("about.title", "Acerca de Time Warp IDE"),
+    ("about.version", "Versión {0}"),
+    ("about.tagline", "Entorno educativo de programación para TempleCode"),
+    ("about.languages", "Combina BASIC, PILOT y Logo"),
+    ("about.repository", "Repositorio en GitHub"),
+    ("common.cancel", "Cancelar"),
+    ("common.apply", "Aplicar"),
+    ("common.error", "Error"),
+    ("common.ok", "Aceptar"),
+    ("common.close", "Cerrar"),
+    ("common.dismiss", "Descartar"),
+    ("common.copy", "📋 Copiar"),
+    ("common.clear", "🗑 Borrar"),
+    ("undo.format_document", "Dar formato al documento"),
+    ("undo.typing", "Escritura"),
+    ("undo.restore_run", "Restaurar ejecución"),
+    ("undo.insert_file", "Insertar archivo"),
+    ("undo.rename", "Renombrar símbolo"),
+    ("undo.replace_in_files", "Reemplazar en archivos"),
+    ("undo.move_lines", "Mover líneas"),
+    ("undo.indent", "Sangrar"),
+    ("undo.unindent", "Quitar sangría"),
+    ("undo.duplicate_lines", "Duplicar líneas"),
+    // IDE: editor
+    ("editor.folded_lines", "… {0} líneas"),
+    ("editor.insert_failed", "No se pudo insertar {0}: {1}"),
+    ("editor.untrusted", "🔒 {0} se abrió desde fuera del área de trabajo, así que se ejecuta en modo restringido: sin archivos, red ni complementos."),
+    ("editor.trust", "Confiar en este archivo"),
+    ("editor.lossy", "⚠ {0} no es texto UTF-8 válido: los bytes ilegibles se cambiaron por �, y al guardar se conservan los cambios."),
+    ("editor.bom", "⚠ {0} empezaba con una marca de orden de bytes (como guarda el Bloc de notas); se quitó para que la primera línea se lea bien."),
+    ("tab.editor", "📝 Editor"),
+    ("tab.output", "📊 Salida y gráficos"),
+    ("tab.debug", "🐛 Depuración"),
+    ("tab.explorer", "📁 Explorador"),
+    ("tab.help", "❓ Ayuda"),
+    ("tab.diff", "🔀 Diferencias"),
+    ("tab.welcome", "👋 Bienvenida"),
+    ("find.title", "Buscar/Reemplazar"),
+    ("find.find", "Buscar:"),
+    ("find.replace_with", "Reemplazar por:"),
+    ("find.next", "Buscar siguiente"),
+    ("find.replace", "Reemplazar"),
+    ("find.replace_all", "Reemplazar todo"),
+    ("find.replaced_one", "Se reemplazó una coincidencia"),
+    ("find.found", "Se encontró '{0}' en el código"),
+    ("find.not_found", "No se encontró '{0}'"),
+    ("find.replaced_many", "Se reemplazaron {0} coincidencia(s)"),
+    ("find.replaced_with_failures", "Se reemplazaron {0} coincidencia(s); no se pudo escribir:\n{1}"),
+    ("find_in_files.title", "Buscar en archivos"),
+    ("find_in_files.files", "Archivos:"),
+    ("find_in_files.match_case", "Distinguir mayúsculas"),
+    ("find_in_files.regex", "Expresión regular"),
+    ("find_in_files.search", "🔍 Buscar"),
+    ("find_in_files.preview", "Vista previa del reemplazo"),
+    ("find_in_files.preview_heading", "Vista previa del reemplazo (desmarca los archivos que quieras omitir):"),
+    ("find_in_files.plan", "{0} — {1} coincidencia(s)"),
+    ("find_in_files.apply", "✔ Aplicar"),
+    ("find_in_files.matches", "{0} coincidencia(s) en {1} archivo(s)"),
+    ("outline.line", "Línea {0}"),
+    ("outline.title", "Esquema"),
+    ("outline.empty", "No hay etiquetas, procedimientos ni destinos de salto"),
+    ("outline.goto_title", "Ir al símbolo"),
+    ("outline.no_match", "Ningún símbolo coincide"),
+    ("rename.title", "Renombrar símbolo"),
+        ("assessment.start", "Comenzar evaluación"),
+        ("assessment.title", "Evaluación"),
+        ("assessment.intro", "Tendrás {0} minutos. El reloj empieza al pulsar Comenzar; cuando se acabe, el programa se detendrá y el editor quedará bloqueado."),
+        ("assessment.student", "Tu nombre o número de estudiante:"),
+        ("assessment.submit", "Entregar"),
+        ("assessment.stop_first", "Detén primero el programa en ejecución"),
+        ("assessment.over", "⏱ Se acabó el tiempo"),
+        ("assessment.done", "✔ Entregado"),
+        ("assessment.time_up", "Se acabó el tiempo: el programa se detuvo y el editor está bloqueado. Pulsa Entregar para entregar tu trabajo."),
+        ("assessment.locked", "La evaluación terminó; los programas ya no se pueden cambiar ni ejecutar."),
+        ("assessment.submitted", "Entregado: {0}"),
+        ("assessment.submit_failed", "No se pudo entregar: {0}"),
+    ("rename.symbol", "Renombrar {0} como:"),
+    ("rename.all_tabs", "En todas las pestañas abiertas"),
+    ("rename.count", "{0} apariciones en {1} pestañas"),
+    ("rename.apply", "Renombrar"),
+    ("rename.no_symbol", "Coloca el cursor sobre una variable, etiqueta, número de línea o procedimiento para renombrarlo"),
+    ("rename.error.invalid", "'{0}' no es un nombre válido aquí"),
+    ("rename.error.reserved", "{0} es una palabra clave o función incorporada"),
+    ("rename.error.taken", "{0} ya se usa en este programa"),
+    ("rename.error.suffix", "El nuevo nombre debe conservar el sufijo de tipo de {0}"),
+    ("explorer.title", "Explorador de archivos"),
+    ("explorer.soon", "El explorador de archivos llegará pronto:"),
+    ("explorer.tree", "• Vista en árbol del proyecto"),
+    ("explorer.operations", "• Operaciones con archivos"),
+    ("explorer.drag_drop", "• Arrastrar y soltar"),
+    ("language_switch.current", "Lenguaje: {0}"),
+    ("language_switch.locked", "Lo fija la cabecera @lang del programa"),
+    ("language_switch.title", "¿Cambiar de lenguaje?"),
+    ("language_switch.mismatch", "Este texto parece {0}. Si lo ejecutas como {1}, lo más probable es que dé errores."),
+    ("language_switch.keep", "Conservar el texto y cambiar a {0}"),
+    ("language_switch.redraw", "Redibujar como LINE de BASIC"),
+    ("language_switch.redraw.hover", "Ejecuta el programa Logo en un entorno aislado y escribe lo que dibuja como instrucciones LINE de BASIC en una pestaña nueva"),
+    ("language_switch.new_tab", "Nueva pestaña de {0}"),
+    ("language_switch.redraw_failed", "No se puede redibujar como BASIC: {0}"),
+    // IDE: output, canvas and run results
+    ("output.heading", "Pantalla unificada"),
+    ("output.overlay_text", "Superponer el texto en los gráficos"),
+    ("output.highlight_variables", "🔍 Resaltar variables"),
+    ("output.highlight_variables.hover", "Subraya en la transcripción las partes de las líneas de T: y PRINT que vienen de variables"),
+    ("canvas.saved", "Lienzo guardado en {0}"),
+    ("canvas.save_failed", "No se pudo guardar el PNG: {0}"),
+    ("canvas_export.title", "Guardar lienzo como PNG"),
+    ("canvas_export.size", "Tamaño"),
+    ("canvas_export.custom", "Personalizado"),
+    ("canvas_export.transparent", "Fondo transparente"),
+    ("canvas_export.pixels", "{0} × {1} píxeles"),
+    ("canvas_export.save", "Guardar..."),
+    ("screen.exported", "Pantalla de texto guardada en {0}"),
+    ("measure.ruler", "📏 Regla"),
+    ("measure.ruler.hover", "Haz clic en dos puntos del lienzo para medir la distancia entre ellos"),
+    ("measure.protractor", "📐 Transportador"),
+    ("measure.protractor.hover", "Haz clic en un punto, en el vértice y luego en otro punto para medir el ángulo"),
+    ("measure.hint.ruler_start", "Haz clic donde empieza la regla"),
+    ("measure.hint.ruler_end", "Haz clic donde acaba la regla"),
+    ("measure.hint.first_arm", "Haz clic en un punto del primer lado"),
+    ("measure.hint.vertex", "Haz clic en el vértice"),
+    ("measure.hint.second_arm", "Haz clic en un punto del segundo lado"),
+    ("measure.distance", "Distancia: {0}"),
+    ("measure.angle", "Ángulo: {0}°"),
+    ("measure.angle_undefined", "Ángulo: indefinido (un lado no tiene longitud)"),
+    ("input.press_key", "⌨ Pulsa una tecla"),
+    ("input.title", "Se necesita una respuesta"),
+    ("input.hint", "Escribe aquí y pulsa Intro"),
+    ("input.submit", "Enviar"),
+    ("input.press_keys", "⌨ Pulsa {0} teclas (llevas {1})"),
+    ("input.tries_left", "⚠️ {0} (quedan {1} intentos)"),
+    ("dialog.title", "Mensaje"),
+    ("dialog.yes", "Sí"),
+    ("dialog.no", "No"),
+    ("dialog.default", "[diálogo] \"{0}\": sin respuesta, se eligió {1}"),
+    ("status.file", "Archivo: {0}"),
+    ("status.no_file", "Ninguno"),
+    ("status.theme", "Tema: {0}"),
+    ("status.restricted", "🔒 Restringido"),
+    ("status.restricted.hover", "Modo restringido: las instrucciones de archivos, red y complementos pueden estar bloqueadas"),
+    ("status.low_memory", "🗜 {0} líneas aplanadas"),
+    ("status.low_memory.hover", "Lienzo de poca memoria: las líneas más antiguas se guardan como imagen (Configuración → Lienzo)"),
+    ("status.executing", "Ejecutando..."),
+    ("status.pace", "{0} instrucciones/s"),
+    ("status.pace.hover", "Qué tan rápido se ejecuta el programa (o se ejecutó el último), medido al final de cada porción enviada al IDE"),
+    ("status.ready", "Listo"),
+    ("status.program.hover", "Mensaje del programa en ejecución (STATUS)"),
+    ("status.waiting_input", "⌨️ Esperando una respuesta"),
+    ("status.paused", "⏸️ En pausa"),
+    ("summary.finished", "✅ Terminado en {0} — {1}, {2} dibujados"),
+    ("summary.output_line", "{0} línea de salida"),
+    ("summary.output_lines", "{0} líneas de salida"),
+    ("summary.segment", "{0} segmento"),
+    ("summary.segments", "{0} segmentos"),
+    ("summary.warning", "{0} advertencia"),
+    ("summary.warnings", "{0} advertencias"),
+    ("summary.seed", "semilla aleatoria {0}"),
+    ("history.title", "Historial de ejecuciones"),
+    ("welcome.title", "Bienvenido a Time Warp"),
+    ("welcome.intro", "Escribe programas en PILOT, BASIC y Logo y míralos ejecutarse. Elige por dónde empezar:"),
+    ("welcome.new_basic", "📝 Nuevo programa BASIC"),
+    ("welcome.new_basic.hover", "Abre una pestaña nueva con un programa BASIC para completar"),
+    ("welcome.logo_tutorial", "🐢 Abrir el tutorial de Logo"),
+    ("welcome.logo_tutorial.hover", "Aprende a dibujar con la tortuga, paso a paso y con comprobación"),
+    ("welcome.example", "📚 Cargar un ejemplo"),
+    ("welcome.example.hover", "Recorre la Galería de ejemplos y abre un programa terminado"),
+    ("welcome.demo", "Un programa Logo dibujando: 36 cuadrados, cada uno girado 10°"),
+    ("welcome.show_at_startup", "Mostrar la pestaña de Bienvenida al iniciar"),
+    ("welcome.close", "Cerrar la pestaña de Bienvenida (Ayuda ▸ Bienvenida la vuelve a abrir)"),
+    ("history.empty", "Aún no hay ejecuciones. Aquí se guarda cada ejecución que termina."),
+    ("history.count", "Últimas {0} ejecuciones"),
+    ("history.clear", "Borrar"),
+    ("history.pick", "Elige una ejecución para ver su salida."),
+    ("history.compare", "Comparar con la salida actual"),
+    ("history.compare.hover", "Muestra en la pestaña Diferencias qué cambió entre la salida de esta ejecución y la actual."),
+    ("history.restore", "Restaurar este programa"),
+    ("history.restore.hover", "Vuelve a poner en su pestaña el programa tal como era en esta ejecución (Deshacer lo revierte)."),
+    ("history.restore.same", "La pestaña aún tiene el programa con el que empezó esta ejecución."),
+    ("history.restore.closed", "La pestaña de la ejecución está cerrada."),
+    ("history.restore.too_long", "El programa era demasiado largo para guardarlo."),
+    ("history.use_seed", "Usar esta semilla"),
+    ("history.use_seed.hover", "Empieza los números aleatorios de cada ejecución con la semilla de esta (Configuración → Programas)."),
+    ("compare.title", "Comparar con el modelo"),
+    ("compare.choose", "Elegir modelo..."),
+    ("compare.none_chosen", "No se eligió una respuesta modelo"),
+    ("compare.answers", "Respuestas para ambos programas, una por línea:"),
+    ("compare.answers.hint", "p. ej. 7"),
+    ("compare.run", "▶ Comparar"),
+    ("compare.same", "✅ Misma salida y mismo dibujo"),
+    ("compare.counts", "{0} línea(s) solo del modelo, {1} solo del estudiante; {2} segmento(s) distintos"),
+    ("compare.model", "Respuesta modelo"),
+    ("compare.student", "Estudiante: {0}"),
+    ("compare.waiting", "Se detuvo en una pregunta: no quedan respuestas"),
+    ("compare.read_failed", "No se pudo leer {0}: {1}"),
+    ("summary.waiting", "⌨ Esperando una respuesta — escríbela abajo"),
+    ("summary.breakpoint", "⏸ Detenido en un punto de interrupción antes de la línea {0}"),
+    ("summary.stopped", "⏹ Detenido tras {0} — {1}"),
+    ("summary.reported_error", "El programa informó de un error"),
+    ("summary.go_to_line", "Ir a la línea {0}"),
+    ("run.confirm_clear.title", "¿Borrar el lienzo?"),
+    ("run.confirm_clear.body", "Este programa empieza con CLEARSCREEN y borrará el dibujo actual."),
+    ("run.confirm_clear.restore", "Puedes recuperarlo después con Ver → Restaurar lienzo anterior."),
+    ("run.confirm_clear.ask", "Preguntarme la próxima vez"),
+    ("run.confirm_clear.run", "▶️ Ejecutar"),
+    ("run.confirm_restart.title", "¿Reiniciar el programa?"),
+    ("run.confirm_restart.body", "Un programa sigue en ejecución. ¿Detenerlo y ejecutar la pestaña actual desde el principio?"),
+    ("run.confirm_restart.restart", "🔄 Reiniciar"),
+    ("run.load_failed", "No se pudo cargar el programa: {0}"),
+    ("run.execution_error", "Error de ejecución: {0}"),
+    ("run.step_error", "Error al avanzar un paso: {0}"),
+    ("run.load_error", "Error de carga: {0}"),
+    ("transcript.title", "Transcripción"),
+    ("transcript.line", "1 línea"),
+    ("transcript.lines", "{0} líneas"),
+    ("transcript.hidden", "({0} ocultas por el filtro de advertencias)"),
+    ("transcript.copy_all", "📋 Copiar todo"),
+    ("transcript.save", "💾 Guardar…"),
+    ("transcript.save_html", "🌐 Guardar HTML…"),
+    ("transcript.times", "🕑 Tiempos"),
+    ("transcript.times.hover", "Mostrar cuándo apareció cada línea, desde el inicio de la ejecución"),
+    ("transcript.untimed", "Activa Ajustes → Registrar los tiempos de la salida y vuelve a ejecutar"),
+    ("transcript.replay", "▶ Repetir"),
+    ("transcript.replay_stop", "⏹ Detener repetición"),
+    ("transcript.show_full", "Mostrar la línea completa"),
+    ("transcript.line_window", "Línea de salida {0}"),
+    ("transcript.characters", "{0} caracteres"),
+    ("diff.pin", "📌 Fijar salida"),
+    ("diff.pin.hover", "Guarda esta transcripción para compararla con la próxima ejecución"),
+    ("diff.unpin", "Soltar"),
+    ("diff.title", "Diferencias de salida"),
+    ("diff.empty", "Fija una salida en la pestaña Salida y gráficos, vuelve a ejecutar y aquí aparecerán las diferencias."),
+    ("diff.identical", "Las transcripciones son idénticas."),
+    ("diff.counts", "{0} línea(s) añadidas, {1} eliminadas"),
+    ("export_path.title", "Exportar trayectoria"),
+    ("export_path.about", "Escribe cada segmento de la tortuga como x1, y1, x2, y2, color, grosor."),
+    ("export_path.simplify", "Simplificar (unir segmentos alineados)"),
+    ("export_path.tolerance", "tolerancia "),
+    ("export_path.simplified", "{0} segmento(s) → {1} ({2} unidos)"),
+    ("export_path.segments", "{0} segmento(s)"),
+    ("report.title", "Informe del programa"),
+    ("report.statements", "Instrucciones"),
+    ("report.nesting", "Anidamiento máximo"),
+    ("report.longest_line", "Línea más larga"),
+    ("report.line_chars", "línea {0} ({1} caracteres)"),
+    ("report.variables", "Variables"),
+    ("report.unused", "Asignadas y nunca leídas"),
+    ("report.unreachable", "Líneas inalcanzables"),
+    ("report.none", "ninguna"),
+    ("report.count_names", "{0}: {1}"),
+    ("report.constructs", "Instrucciones por construcción"),
+    ("report.procedure_call", "llamada a procedimiento"),
+    // IDE: debugger, diagnostics and direct mode
+    ("debugger.title", "Depurador"),
+    ("debugger.variables", "Variables"),
+    ("debugger.export_csv", "Exportar CSV..."),
+    ("debugger.export_json", "Exportar JSON..."),
+    ("debugger.no_variables", "Todavía no hay variables. Ejecuta un programa para ver sus variables."),
+    ("debugger.kept", "Se conserva para la próxima ejecución (COMMON o Conservar las variables entre ejecuciones)"),
+    ("debugger.set_at", "línea {0}"),
+    ("debugger.set_at.hover", "Asignada por última vez en la línea {0}; haz clic para verla"),
+    ("debugger.inspect", "{0} = {1}"),
+    ("debugger.inspect.set_at", "{0} = {1}, asignada en la línea {2}"),
+    ("debugger.breakpoints", "Puntos de interrupción ({0})"),
+    ("debugger.breakpoints.enabled", "Detenerse en los puntos de interrupción del programa"),
+    ("debugger.breakpoints.line", "{0} línea {1}"),
+    ("debugger.breakpoints.from_source", "del código"),
+    ("debugger.answer_history", "Historial de respuestas ({0})"),
+    ("debugger.series", "Series registradas"),
+    ("debugger.series_hint", "Usa LOGVAR X dentro de un bucle para registrar el valor de una variable en cada vuelta."),
+    ("debugger.series_values", "{0} ({1} valores)"),
+    ("debugger.max", "máx. {0}"),
+    ("debugger.min", "mín. {0}"),
+    ("debugger.export_failed", "No se pudo exportar: {0}"),
+    ("diagnostics.title", "Registro de diagnóstico"),
+    ("diagnostics.off", "El registro no está activo en esta sesión."),
+    ("diagnostics.count", "{0} evento(s), filtro: {1}"),
+    ("diagnostics.change_filter", "Cambia el filtro en Ajustes → Diagnóstico."),
+    ("direct.hint", "FD 50, RT 90, … y luego Intro"),
+    ("direct.copy_program", "📋 Copiar como programa"),
+    ("direct.copy_program.hover", "Abre en una pestaña nueva las instrucciones de Logo grabadas"),
+    ("direct.wrap", "dentro de TO {0}"),
+    ("direct.copy_basic", "Copiar como BASIC"),
+    ("direct.copy_basic.hover", "Abre el dibujo grabado como instrucciones LINE de BASIC"),
+    ("direct.clear", "Borrar grabación"),
+    ("direct.recorded_one", "1 instrucción grabada"),
+    ("direct.recorded", "{0} instrucciones grabadas"),
+    ("inline.ok", "ok"),
+    ("inline.busy", "Hay un programa en marcha; deténgalo para evaluar"),
+    // IDE: settings
+    ("settings.project_mark", "📁 proyecto"),
+    ("settings.language.from_extension", "Según la extensión del archivo"),
+    ("settings.language.templecode", "TempleCode (mixto)"),
+    ("settings.screen.as_program", "Como lo indique el programa"),
+    ("settings.screen.text", "Texto"),
+    ("settings.screen.graphics", "Gráficos"),
+    ("settings.screen.columns", " columnas"),
+    ("settings.screen.rows", " filas"),
+    ("settings.screen.px_wide", " px de ancho"),
+    ("settings.screen.px_high", " px de alto"),
+    ("settings.seed.same", "Igual en cada ejecución"),
+    ("settings.title", "Ajustes"),
+    ("settings.project", "Proyecto"),
+    ("settings.project.marked", "Los valores marcados con 📁 proyecto vienen de ese archivo y sustituyen a los tuyos mientras el proyecto está abierto."),
+    ("settings.project.edit", "📝 Editar archivo del proyecto"),
+    ("settings.project.reload", "🔄 Recargar"),
+    ("settings.project.none", "No hay carpeta de proyecto: abre o guarda un archivo primero."),
+    ("settings.project.save_defaults", "💾 Guardar como valores del proyecto"),
+    ("settings.project.save_defaults.hover", "Escribe en la carpeta del proyecto los ajustes de lenguaje, pantalla, límites, tema, aula y tabulación en uso"),
+    ("settings.project.replace", "Esta carpeta ya tiene un {0}. ¿Reemplazarlo?"),
+    ("settings.project.replace.confirm", "Reemplazar"),
+    ("settings.appearance", "Apariencia"),
+    ("settings.theme", "Tema"),
+    ("settings.ui_scale", "Escala de la interfaz"),
+    ("settings.accessibility", "Accesibilidad"),
+    ("settings.cvd_safe", "Colores aptos para daltonismo (paleta Okabe-Ito para los colores con nombre de Logo)"),
+    ("settings.export_as_displayed", "Exportar como se muestra"),
+    ("settings.export_as_displayed.note", "Las imágenes guardadas conservan los colores originales salvo que esté marcado \"Exportar como se muestra\"."),
+    ("settings.tts", "Texto a voz (leer en voz alta cada línea de T: y PRINT)"),
+    ("settings.tts.none", "No hay motor de voz disponible: las líneas V: se muestran como texto [voz]."),
+    ("settings.canvas", "Lienzo"),
+    ("settings.confirm_clearscreen", "Confirmar antes de que el CLEARSCREEN de un programa borre el dibujo"),
+    ("settings.export_turtle", "Incluir la tortuga en las imágenes guardadas"),
+    ("settings.export_measurements", "Incluir la regla o el transportador en las imágenes guardadas"),
+    ("settings.canvas.restore_note", "Ver → Restaurar lienzo anterior recupera el dibujo de antes de cada ejecución."),
+    ("settings.low_memory_canvas", "Lienzo de poca memoria: conservar como máximo"),
+    ("settings.low_memory_canvas.lines", "líneas"),
+    ("settings.low_memory_canvas.note", "Por encima, las líneas más antiguas se aplanan en una imagen del lienzo. Ahorra memoria en dibujos enormes; las exportaciones lo muestran todo, pero al ampliar se ven píxeles. Se aplica desde la próxima ejecución."),
+    ("settings.logo_headings", "Rumbos en Logo"),
+    ("settings.angle.compass", "Brújula (0 = norte, sentido horario)"),
+    ("settings.angle.math", "Matemático (0 = este, sentido antihorario)"),
+    ("settings.turtle_shape", "Forma de la tortuga"),
+    ("settings.turtle_shape.load", "Cargar archivo de puntos…"),
+    ("settings.turtle_shape.load.hover", "Un par \"x y\" por línea, con la tortuga mirando hacia arriba"),
+    ("settings.basic.classic_errors", "Mensajes de error clásicos (?SYNTAX ERROR IN 20)"),
+    ("settings.basic.classic_errors.note", "Los programas también pueden cambiarlo con OPTION ERRORS CLASSIC / OPTION ERRORS MODERN."),
+    ("settings.warnings", "Advertencias"),
+    ("settings.warnings.show", "Mostrar advertencias en la salida"),
+    ("settings.warnings.strict", "Modo estricto: tratar estas como errores"),
+    ("settings.programs", "Programas"),
+    ("settings.language_mode", "Modo de lenguaje"),
+    ("settings.language_mode.note", "Se usa en programas sin cabecera @lang."),
+    ("settings.return_to_editor", "Volver al editor cuando una ejecución termina sin errores"),
+    ("settings.keep_variables", "Conservar las variables entre ejecuciones"),
+    ("settings.keep_variables.hover", "Sin esto, solo se conservan las variables COMMON de BASIC; CLEAR las olvida"),
+    ("settings.start_screen", "Pantalla inicial"),
+    ("settings.tab_width", "Ancho de tabulación"),
+    ("settings.tab_width.spaces", " espacios"),
+    ("settings.line_ending", "Finales de línea al guardar"),
+    ("settings.line_ending.keep", "Los de cada archivo"),
+    ("settings.trim_trailing_whitespace", "Quitar espacios al final de las líneas al guardar"),
+    ("settings.random_seed", "Semilla aleatoria"),
+    ("settings.random_seed.note", "Una ejecución que usa RND o QZ:SHUFFLE muestra su semilla al terminar; escríbela aquí para repetir la ejecución."),
+    ("settings.autorun_on_open", "Ejecutar al iniciar el primer programa abierto con --open"),
+    ("settings.disabled_statements", "Instrucciones desactivadas"),
+    ("settings.disabled_statements.hint", "p. ej. GOTO, GOSUB"),
+    ("settings.disabled_statements.note", "Las ejecuciones rechazan estas instrucciones y el editor las subraya, para enseñar sin ellas (--disable añade más durante una sesión)."),
+    ("settings.honor_source_breakpoints", "Detenerse en los puntos de interrupción escritos en el programa"),
+    ("settings.honor_source_breakpoints.hover", "Una línea REM BREAK, 'BREAK, R:BREAK o ;BREAK detiene la ejecución antes de la instrucción siguiente; Ejecutar ▸ Continuar sigue."),
+    ("settings.classroom", "Aula"),
+    ("settings.restricted", "Modo restringido (bloquea instrucciones de archivos, red y complementos)"),
+    ("settings.restricted.locked", "El modo restringido se activó con --restricted y no se puede cambiar."),
+    ("settings.restricted.files", "Permitir archivos"),
+    ("settings.restricted.network", "Permitir red"),
+    ("settings.restricted.plugins", "Permitir complementos"),
+    ("settings.restricted.workspace", "Carpeta de trabajo"),
+    ("settings.restricted.anywhere", "(cualquiera)"),
+    ("settings.restricted.time_limit", "Límite de tiempo (segundos)"),
+    ("settings.restricted.max_statements", "Máximo de instrucciones por ejecución"),
+    ("settings.restricted.next_run", "Los cambios se aplican desde la próxima ejecución."),
+    ("settings.record_output_timing", "Registrar los tiempos de la salida"),
+    ("settings.record_output_timing.hover", "Anota cuándo aparece cada línea de salida y cuánto espera cada pregunta una respuesta, para el informe de ejecución, la columna de tiempos del panel de salida y la repetición."),
+    ("settings.slice_statements", "Instrucciones por porción:"),
+    ("settings.slice_statements.hover", "Una ejecución se envía a la pestaña Salida y al lienzo en porciones. Por defecto cada porción ejecuta las instrucciones que caben en unos 4 ms; un número fijo hace que todas las porciones tengan el mismo largo, tarden lo que tarden sus instrucciones."),
+    ("settings.slice_statements.fixed", "Fijo"),
+    ("settings.keep_run_history", "Guardar el historial de ejecuciones"),
+    ("settings.keep_run_history.hover", "Guarda las últimas ejecuciones de cada carpeta de proyecto, para que Herramientas → Historial de ejecuciones las tenga en la próxima sesión."),
+    ("settings.diagnostics", "Diagnóstico"),
+    ("settings.log_filter", "Filtro del registro"),
+    ("settings.log_filter.note", "p. ej. time_warp_core=debug para saltos y errores, time_warp_core=trace para cada línea."),
+    ("settings.diagnostics.open", "🩺 Abrir registro de diagnóstico"),
+    ("settings.limits", "Límites"),
+    ("settings.limits.max_program_bytes", "Máximo de bytes del programa"),
+    ("settings.limits.max_program_lines", "Máximo de líneas del programa"),
+    ("settings.limits.max_repeat_count", "Máximo de repeticiones de REPEAT"),
+    ("settings.limits.max_for_iterations", "Máximo de iteraciones de FOR"),
+    ("settings.limits.max_output_lines", "Máximo de líneas de salida"),
+    ("settings.limits.max_output_line_chars", "Longitud máxima de una línea de salida"),
+    ("settings.limits.chars", " caracteres"),
+    ("settings.limits.input_retries", "Reintentos de INPUT con validación"),
+    ("settings.limits.answer_history", "Respuestas PILOT guardadas (%ANS1…)"),
+    ("settings.limits.max_bracket_depth", "Anidamiento máximo de corchetes (REPEAT)"),
+    ("settings.limits.max_paren_depth", "Anidamiento máximo de paréntesis"),
+    ("settings.limits.oversized_loops", "Bucles demasiado grandes"),
+    ("settings.limits.warn_and_clamp", "Avisar y recortar"),
+    ("settings.limits.error", "Error"),
+    ("settings.basic", "BASIC"),
+    ("settings.project_mark.hover", "Definido por el {0} de este proyecto; edita ese archivo para cambiarlo"),
+    ("settings.project.using", "Usando {0}"),
+    ("settings.project.missing", "No hay {0} en {1}"),
+    ("settings.turtle_shape.failed", "No se puede usar {0} como forma de la tortuga: {1}"),
+    ("settings.log_filter.invalid", "Filtro de registro no válido: {0}"),
+    ("settings.project.save_failed", "No se pudieron guardar los valores del proyecto: {0}"),
+    ("settings.project.no_folder", "abre o guarda un archivo primero; su carpeta es el proyecto"),
+    ("settings.turtle_shape.points_filter", "Puntos"),
+    ("settings.language", "Idioma"),
+    ("settings.language.note", "Menús, paneles y mensajes de las ejecuciones. Las palabras clave como PRINT y FORWARD siguen en inglés."),
+    ("settings.project.ignored", "Se ignoran los ajustes del proyecto: {0}"),
+    // IDE: sharing, packaging and tutorials
+    ("share.failed", "No se pudo compartir: {0}"),
+    ("share.title", "Compartir programa"),
+    ("share.copied", "Enlace copiado al portapapeles. Pégalo donde quieras; ábrelo con Herramientas → Importar enlace compartido."),
+    ("share.import_title", "Importar enlace compartido"),
+    ("share.paste", "Pega un enlace timewarp://code/…:"),
+    ("share.import", "📥 Importar"),
+    ("share.import_failed", "No se pudo importar: {0}"),
+    ("package.save_first", "Guarda el programa antes de empaquetarlo."),
+    ("package.title", "Empaquetar proyecto"),
+    ("package.entry", "Programa principal: {0}"),
+    ("package.unsaved", "Los cambios sin guardar no se incluyen; guarda primero."),
+    ("package.include", "Incluir estos archivos (los programas que carga están marcados):"),
+    ("package.no_files", "No hay otros archivos en esta carpeta."),
+    ("package.save", "📦 Guardar paquete..."),
+    ("package.done", "Se empaquetaron {0} archivo(s) en {1}"),
+    ("package.failed", "No se pudo empaquetar el proyecto: {0}"),
+    ("gallery.title", "Galería de ejemplos"),
+    ("gallery.open", "📂 Abrir"),
+    ("tutorial.title", "Tutorial"),
+    ("tutorial.none", "No hay tutoriales disponibles."),
+    ("tutorial.step", "Paso {0} de {1}"),
+    ("tutorial.completed", "({0} completados)"),
+    ("tutorial.hint", "💡 Pista"),
+    ("tutorial.check", "✅ Comprobar"),
+    ("tutorial.reset", "↺ Reiniciar"),
+    ("tutorial.show_solution", "Ver la solución"),
+    ("tutorial.previous", "◀ Anterior"),
+    ("tutorial.next", "Siguiente ▶"),
+    ("tutorial.complete", "✔ ¡Correcto! Tutorial terminado."),
+    ("tutorial.correct", "✔ ¡Correcto! Pasa al siguiente paso."),
+    ("tutorial.output", "Salida del programa"),
+];
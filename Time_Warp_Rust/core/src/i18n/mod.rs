@@ -0,0 +1,230 @@
+//! Translations of the IDE's labels and the interpreter's messages
+//!
+//! Every user-visible string is looked up by a message id such as
+//! `"menu.file"` or `"basic.unknown_command"`: [`tr`] gives the text in the
+//! current [`Locale`], and [`trf`] fills its `{0}`, `{1}`, … placeholders.
+//! English is the default and the fallback for an id a locale lacks; an id
+//! no locale knows comes back as itself, so a typo shows up on screen rather
+//! than as a blank. Keyword names (PRINT, FORWARD, T:) stay English in every
+//! locale.
+//!
+//! The locale is process-wide, because the interpreter reports its messages
+//! from the run worker's thread as well as the UI's. Code that checks
+//! messages should compare kinds ([`BasicError`](crate::utils::error::BasicError),
+//! [`WarningKind`](crate::interpreter::warnings::WarningKind)), not their text.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+mod en;
+mod es;
+
+/// Language of the IDE and of the interpreter's messages (Settings → Language)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    /// The locale's own name for itself, for the Settings picker
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Spanish => "Español",
+        }
+    }
+
+    fn table(self) -> &'static HashMap<&'static str, &'static str> {
+        static ENGLISH: Lazy<HashMap<&str, &str>> = Lazy::new(|| en::STRINGS.iter().copied().collect());
+        static SPANISH: Lazy<HashMap<&str, &str>> = Lazy::new(|| es::STRINGS.iter().copied().collect());
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::Spanish => &SPANISH,
+        }
+    }
+
+    /// Whether this locale has its own text for `id`
+    pub fn has(self, id: &str) -> bool {
+        self.table().contains_key(id)
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switch every later lookup to `locale`
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+/// The locale lookups use now
+pub fn locale() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::Spanish,
+        _ => Locale::English,
+    }
+}
+
+/// Text for `id` in the current locale
+pub fn tr(id: &'static str) -> &'static str {
+    tr_in(locale(), id)
+}
+
+/// Text for `id` in `locale`, else in English, else the id itself
+pub fn tr_in(locale: Locale, id: &'static str) -> &'static str {
+    locale.table().get(id).or_else(|| Locale::English.table().get(id)).copied().unwrap_or(id)
+}
+
+/// Text for `id` in the current locale with `{0}`, `{1}`, … replaced by `args`
+pub fn trf(id: &'static str, args: &[&dyn Display]) -> String {
+    trf_in(locale(), id, args)
+}
+
+/// Text for `id` in `locale` with `{0}`, `{1}`, … replaced by `args`
+///
+/// One pass, so braces inside an argument (a program's own text) are left as
+/// they are. A placeholder without an argument is kept literally.
+pub fn trf_in(locale: Locale, id: &'static str, args: &[&dyn Display]) -> String {
+    let mut rest = tr_in(locale, id);
+    let mut text = String::with_capacity(rest.len());
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let placeholder = after.find('}').and_then(|close| Some((close, args.get(after[..close].parse::<usize>().ok()?)?)));
+        match placeholder {
+            Some((close, arg)) => {
+                text.push_str(&arg.to_string());
+                rest = &after[close + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Message ids looked up in Rust `source` with `tr`, `trf`, `tr_in`, or `trf_in`
+///
+/// For tests that check every id the code uses is in every locale.
+pub fn message_ids(source: &str) -> Vec<&str> {
+    static CALL: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\btrf?(?:_in)?\((?:[\w:]+,\s*)?"([a-z0-9_.]+)""#).unwrap());
+    CALL.captures_iter(source).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Ids in the `.rs` files under `dir` that some locale lacks
+    ///
+    /// This module is skipped: its tests look up ids that do not exist.
+    fn missing_ids(dir: &Path) -> Vec<String> {
+        let mut missing = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.ends_with("i18n") {
+                continue;
+            } else if path.is_dir() {
+                missing.extend(missing_ids(&path));
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for id in message_ids(&source) {
+                    for locale in Locale::ALL.into_iter().filter(|l| !l.has(id)) {
+                        missing.push(format!("{} ({:?}) in {}", id, locale, path.display()));
+                    }
+                }
+            }
+        }
+        missing
+    }
+
+    #[test]
+    fn test_every_message_id_used_in_code_exists_in_every_locale() {
+        let core = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        assert_eq!(missing_ids(&core), Vec::<String>::new());
+        // The IDE's sources, when the core is built inside the workspace
+        let ide = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src");
+        if ide.is_dir() {
+            assert_eq!(missing_ids(&ide), Vec::<String>::new());
+        }
+    }
+
+    /// Error messages in the `.rs` files under `path` written as English literals
+    ///
+    /// A literal with no lowercase word, such as `"SETSHAPE {}: {}"`, only
+    /// joins keywords and other messages, so it passes.
+    fn literal_messages(path: &Path) -> Vec<String> {
+        static LITERAL: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r#"(?:anyhow!|bail!|\.context|\.with_context|log_error)\(\s*(?:\|\|\s*)?(?:format!\(\s*)?"[^"]*[a-z]{2}"#).unwrap()
+        });
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path).unwrap().flatten();
+            return entries.flat_map(|entry| literal_messages(&entry.path())).collect();
+        }
+        if path.extension().is_none_or(|e| e != "rs") {
+            return Vec::new();
+        }
+        let source = std::fs::read_to_string(path).unwrap();
+        // Tests may build whatever errors they like
+        let code = source.split("#[cfg(test)]").next().unwrap_or("");
+        LITERAL.find_iter(code).map(|m| format!("{} in {}", m.as_str(), path.display())).collect()
+    }
+
+    #[test]
+    fn test_interpreter_messages_go_through_the_table() {
+        let core = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for part in ["languages", "interpreter", "graphics", "utils/expr_eval.rs"] {
+            assert_eq!(literal_messages(&core.join(part)), Vec::<String>::new());
+        }
+    }
+
+    #[test]
+    fn test_classify_reads_the_current_locale() {
+        use crate::utils::error::BasicError;
+        let spanish = trf_in(Locale::Spanish, "expr.missing_argument", &[&"SIN"]);
+        assert_eq!(BasicError::classify_in(Locale::Spanish, &spanish), BasicError::IllegalFunctionCall);
+        let spanish = trf_in(Locale::Spanish, "basic.goto_failed", &[&99]);
+        assert_eq!(BasicError::classify_in(Locale::Spanish, &spanish), BasicError::UndefinedLineNumber);
+        assert_eq!(BasicError::classify_in(Locale::Spanish, "División por cero"), BasicError::DivisionByZero);
+        assert_eq!(BasicError::classify_in(Locale::English, "NEXT J does not match FOR I"), BasicError::NextWithoutFor);
+    }
+
+    #[test]
+    fn test_every_locale_is_complete() {
+        for locale in Locale::ALL {
+            let extra: Vec<_> = locale.table().keys().filter(|id| !Locale::English.has(id)).collect();
+            assert!(extra.is_empty(), "{:?} has ids English lacks: {:?}", locale, extra);
+            let missing: Vec<_> = en::STRINGS.iter().map(|(id, _)| id).filter(|id| !locale.has(id)).collect();
+            assert!(missing.is_empty(), "{:?} lacks {:?}", locale, missing);
+        }
+        // No id is listed twice, which would hide one of the texts
+        assert_eq!(Locale::English.table().len(), en::STRINGS.len());
+        assert_eq!(Locale::Spanish.table().len(), es::STRINGS.len());
+    }
+
+    #[test]
+    fn test_lookup_and_placeholders() {
+        assert_eq!(trf_in(Locale::English, "basic.unknown_command", &[&"PRNT"]), "Unknown BASIC command: PRNT");
+        assert_eq!(trf_in(Locale::Spanish, "basic.unknown_command", &[&"PRNT"]), "Comando BASIC desconocido: PRNT");
+        assert_eq!(trf_in(Locale::English, "error.at_line", &[&3, &"oops"]), "❌ Error at line 3: oops");
+        assert_eq!(tr_in(Locale::Spanish, "no.such.id"), "no.such.id");
+        // A program's text is never read as a placeholder
+        assert_eq!(trf_in(Locale::English, "error.at_line", &[&"{1}", &"x{0}"]), "❌ Error at line {1}: x{0}");
+        assert_eq!(trf_in(Locale::English, "error.at_line", &[&7]), "❌ Error at line 7: {1}");
+        assert_eq!(
+            message_ids(r#"tr("menu.file"); trf("a.b", &[&x]); tr_in(Locale::Spanish, "c_d"); attr("x")"#),
+            ["menu.file", "a.b", "c_d"]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! BASIC arrays: `DIM SCORES(10)`, `DIM NAMES$(10)`, `DIM GRID(20, 20)`
+//!
+//! An array has one or two dimensions, each running from the lower bound set
+//! by `OPTION BASE` (0 unless the program asks for 1) up to the bound given to
+//! `DIM`, so `DIM A(10)` holds eleven numbers under `OPTION BASE 0` and ten
+//! under `OPTION BASE 1`. Elements are stored row by row and every subscript
+//! is checked against its own dimension. Names ending in `$` hold strings,
+//! starting empty; the others hold numbers, starting at 0.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use super::var_types::VarType;
+use super::Interpreter;
+use crate::i18n::{tr, trf};
+use crate::utils::error::BasicError;
+use crate::utils::expr_eval::ArrayElements;
+
+/// Most dimensions an array can have
+pub const MAX_DIMENSIONS: usize = 2;
+
+/// Most elements one array can hold
+pub const MAX_ELEMENTS: usize = 1_000_000;
+
+/// An array's elements, all numbers or all strings
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayValues {
+    Numbers(Vec<f64>),
+    Strings(Vec<String>),
+}
+
+/// One dimensioned array
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicArray {
+    pub name: String,
+    /// Lower bound of every dimension: the OPTION BASE in effect at DIM
+    pub base: usize,
+    /// Upper bound of each dimension, as given to DIM
+    pub bounds: Vec<usize>,
+    pub values: ArrayValues,
+}
+
+impl BasicArray {
+    /// Array `name` with the given upper bounds; a `$` name holds strings
+    pub fn new(name: &str, bounds: Vec<usize>, base: usize) -> Result<Self> {
+        if bounds.is_empty() || bounds.len() > MAX_DIMENSIONS {
+            return Err(anyhow::Error::new(BasicError::Syntax)
+                .context(trf("basic.dim.dimensions", &[&name])));
+        }
+        if let Some(bound) = bounds.iter().find(|b| **b < base) {
+            return Err(anyhow::Error::new(BasicError::IllegalFunctionCall)
+                .context(trf("basic.dim.below_base", &[&name, bound, &base])));
+        }
+        let len = bounds.iter().try_fold(1usize, |len, b| len.checked_mul(b + 1 - base)).unwrap_or(usize::MAX);
+        if len > MAX_ELEMENTS {
+            return Err(anyhow::Error::new(BasicError::IllegalFunctionCall)
+                .context(trf("basic.dim.too_many", &[&name, &len, &MAX_ELEMENTS])));
+        }
+        let values = if name.ends_with('$') {
+            ArrayValues::Strings(vec![String::new(); len])
+        } else {
+            ArrayValues::Numbers(vec![0.0; len])
+        };
+        Ok(Self { name: name.to_string(), base, bounds, values })
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self.values, ArrayValues::Strings(_))
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.values {
+            ArrayValues::Numbers(v) => v.len(),
+            ArrayValues::Strings(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Elements along each dimension
+    pub fn extents(&self) -> Vec<usize> {
+        self.bounds.iter().map(|b| b + 1 - self.base).collect()
+    }
+
+    /// Dimensions as declared with their lower bound: `GRID(0 TO 2, 0 TO 2)`
+    pub fn shape(&self) -> String {
+        let dims: Vec<String> = self.bounds.iter().map(|b| format!("{} TO {}", self.base, b)).collect();
+        format!("{}({})", self.name, dims.join(", "))
+    }
+
+    /// Storage position of the element at `subscripts`, checking each against its dimension
+    fn offset(&self, subscripts: &[f64]) -> Result<usize> {
+        let reference = || {
+            let shown: Vec<String> = subscripts.iter().map(|s| s.to_string()).collect();
+            format!("{}({})", self.name, shown.join(", "))
+        };
+        if subscripts.len() != self.bounds.len() {
+            return Err(anyhow::Error::new(BasicError::SubscriptOutOfRange)
+                .context(trf("basic.dim.dimension_count", &[&reference(), &self.bounds.len()])));
+        }
+        let mut offset = 0;
+        for (dimension, (subscript, bound)) in subscripts.iter().zip(&self.bounds).enumerate() {
+            let index = subscript.round();
+            if !(self.base as f64..=*bound as f64).contains(&index) {
+                return Err(anyhow::Error::new(BasicError::SubscriptOutOfRange).context(trf(
+                    "basic.dim.subscript_range",
+                    &[&reference(), &(dimension + 1), &self.base, bound],
+                )));
+            }
+            offset = offset * (bound + 1 - self.base) + (index as usize - self.base);
+        }
+        Ok(offset)
+    }
+
+    pub fn number_at(&self, subscripts: &[f64]) -> Result<f64> {
+        let offset = self.offset(subscripts)?;
+        match &self.values {
+            ArrayValues::Numbers(v) => Ok(v[offset]),
+            ArrayValues::Strings(_) => Err(self.type_mismatch(trf("basic.type.holds_strings", &[&self.name]))),
+        }
+    }
+
+    pub fn text_at(&self, subscripts: &[f64]) -> Result<String> {
+        let offset = self.offset(subscripts)?;
+        match &self.values {
+            ArrayValues::Strings(v) => Ok(v[offset].clone()),
+            ArrayValues::Numbers(_) => Err(self.type_mismatch(trf("basic.type.holds_numbers", &[&self.name]))),
+        }
+    }
+
+    pub fn set_number(&mut self, subscripts: &[f64], value: f64) -> Result<()> {
+        let offset = self.offset(subscripts)?;
+        match &mut self.values {
+            ArrayValues::Numbers(v) => v[offset] = value,
+            ArrayValues::Strings(_) => return Err(self.type_mismatch(trf("basic.type.holds_strings", &[&self.name]))),
+        }
+        Ok(())
+    }
+
+    pub fn set_text(&mut self, subscripts: &[f64], value: String) -> Result<()> {
+        let offset = self.offset(subscripts)?;
+        match &mut self.values {
+            ArrayValues::Strings(v) => v[offset] = value,
+            ArrayValues::Numbers(_) => return Err(self.type_mismatch(trf("basic.type.holds_numbers", &[&self.name]))),
+        }
+        Ok(())
+    }
+
+    fn type_mismatch(&self, message: String) -> anyhow::Error {
+        anyhow::Error::new(BasicError::TypeMismatch).context(message)
+    }
+
+    /// Element at storage position `offset` as the Variables panel shows it
+    pub fn display(&self, offset: usize) -> String {
+        match &self.values {
+            ArrayValues::Numbers(v) => v[offset].to_string(),
+            ArrayValues::Strings(v) => format!("\"{}\"", v[offset]),
+        }
+    }
+
+    /// The first `count` elements, with `…` when there are more
+    pub fn peek(&self, count: usize) -> String {
+        let shown: Vec<String> = (0..self.len().min(count)).map(|i| self.display(i)).collect();
+        let more = if self.len() > count { ", …" } else { "" };
+        format!("{}{}", shown.join(", "), more)
+    }
+}
+
+impl ArrayElements for HashMap<String, BasicArray> {
+    fn rank(&self, name: &str) -> Option<usize> {
+        self.get(name).map(|array| array.bounds.len())
+    }
+
+    fn element(&self, name: &str, subscripts: &[f64]) -> Result<f64> {
+        self.get(name).ok_or_else(|| anyhow!(trf("expr.unknown_array", &[&name])))?.number_at(subscripts)
+    }
+
+    fn text_element(&self, name: &str, subscripts: &[f64]) -> Result<String> {
+        self.get(name).ok_or_else(|| anyhow!(trf("expr.unknown_array", &[&name])))?.text_at(subscripts)
+    }
+}
+
+/// `text` split at commas outside parentheses and string literals
+pub fn split_top_level(text: &str) -> Vec<&str> {
+    split_top_level_on(text, &[','])
+}
+
+/// `text` split at any of `separators` outside parentheses and string literals
+pub fn split_top_level_on<'a>(text: &'a str, separators: &[char]) -> Vec<&'a str> {
+    let (mut parts, mut start, mut depth, mut in_string) = (Vec::new(), 0, 0i32, false);
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => depth -= 1,
+            c if !in_string && depth == 0 && separators.contains(&c) => {
+                parts.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// `GRID(I, J)` as its upper-case array name and subscript text
+pub fn element_reference(target: &str) -> Option<(String, &str)> {
+    let target = target.trim();
+    let open = target.find('(')?;
+    let name = target[..open].trim_end();
+    let inner = target[open + 1..].strip_suffix(')')?;
+    let mut chars = name.chars();
+    let valid_name = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name.trim_end_matches('$').chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    // The closing parenthesis must be the one that matches `(`
+    let mut depth = 0i32;
+    for c in inner.chars() {
+        depth += match c {
+            '(' => 1,
+            ')' => -1,
+            _ => 0,
+        };
+        if depth < 0 {
+            return None;
+        }
+    }
+    (valid_name && depth == 0).then(|| (name.to_uppercase(), inner))
+}
+
+/// Whether a variable or element named `target` holds strings
+pub fn is_string_target(target: &str) -> bool {
+    match element_reference(target) {
+        Some((name, _)) => name.ends_with('$'),
+        None => target.trim().ends_with('$'),
+    }
+}
+
+/// Whether `error` is an out-of-bounds subscript, even one reported through the expression evaluator
+pub fn is_subscript_error(error: &anyhow::Error) -> bool {
+    BasicError::classify(&error.to_string()) == BasicError::SubscriptOutOfRange
+}
+
+impl Interpreter {
+    /// `DIM NAME(bounds)`: make a new array under the current OPTION BASE
+    pub fn dim_array(&mut self, declaration: &str) -> Result<()> {
+        let (name, bounds) = element_reference(declaration)
+            .ok_or_else(|| anyhow::Error::new(BasicError::Syntax).context(trf("basic.dim.expects", &[&declaration])))?;
+        self.check_not_reserved(&name)?;
+        if self.arrays.contains_key(&name) {
+            return Err(anyhow::Error::new(BasicError::DuplicateDefinition).context(trf("basic.dim.duplicate", &[&name])));
+        }
+        let mut upper = Vec::new();
+        for bound in self.subscripts(bounds)? {
+            if !(bound.is_finite() && bound >= 0.0) {
+                return Err(anyhow::Error::new(BasicError::IllegalFunctionCall).context(trf("basic.dim.negative", &[&name, &bound])));
+            }
+            upper.push(bound.round() as usize);
+        }
+        let array = BasicArray::new(&name, upper, self.option_base)?;
+        self.arrays.insert(name, array);
+        Ok(())
+    }
+
+    /// `OPTION BASE 0|1`; only before the first DIM, as in GW-BASIC
+    pub fn set_option_base(&mut self, base: usize) -> Result<()> {
+        if !self.arrays.is_empty() {
+            return Err(anyhow::Error::new(BasicError::DuplicateDefinition).context(tr("basic.dim.base_after_dim")));
+        }
+        self.option_base = base;
+        Ok(())
+    }
+
+    fn subscripts(&self, text: &str) -> Result<Vec<f64>> {
+        split_top_level(text).into_iter().map(|s| self.evaluate_expression(s)).collect()
+    }
+
+    /// The dimensioned array `target` refers to, with its subscripts evaluated
+    fn array_target(&self, target: &str) -> Option<Result<(String, Vec<f64>)>> {
+        let (name, subscripts) = element_reference(target)?;
+        if !self.arrays.contains_key(&name) {
+            return Some(Err(anyhow!(trf("basic.dim.not_dimensioned", &[&name]))));
+        }
+        Some(self.subscripts(subscripts).map(|s| (name, s)))
+    }
+
+    /// Value of `expr` when it is a string array element like `NAMES$(I)`
+    pub fn element_text(&self, expr: &str) -> Option<Result<String>> {
+        let (name, _) = element_reference(expr)?;
+        if !self.arrays.get(&name).is_some_and(BasicArray::is_string) {
+            return None;
+        }
+        Some(self.array_target(expr)?.and_then(|(name, subscripts)| self.arrays[&name].text_at(&subscripts)))
+    }
+
+    /// Store a number in a variable or array element
+    pub fn assign_number(&mut self, target: &str, value: f64) -> Result<()> {
+        self.check_not_reserved(target)?;
+        match self.array_target(target) {
+            Some(element) => {
+                let (name, subscripts) = element?;
+                self.arrays.get_mut(&name).expect("array_target checks the name").set_number(&subscripts, value)
+            }
+            None => {
+                let value = self.coerce_number(target, value)?;
+                self.variables.insert(target.trim().to_string(), value);
+                self.note_assignment(target);
+                Ok(())
+            }
+        }
+    }
+
+    /// Store text in a variable or array element
+    pub fn assign_text(&mut self, target: &str, value: String) -> Result<()> {
+        self.check_not_reserved(target)?;
+        match self.array_target(target) {
+            Some(element) => {
+                let (name, subscripts) = element?;
+                self.arrays.get_mut(&name).expect("array_target checks the name").set_text(&subscripts, value)
+            }
+            None => {
+                // A name without a suffix that no DEF statement typed takes text too, as before
+                let numeric = match self.declare_variable(target) {
+                    VarType::Integer | VarType::Double => true,
+                    VarType::Single => VarType::from_suffix(target).is_some(),
+                    VarType::String => false,
+                };
+                if numeric {
+                    return Err(anyhow::Error::new(BasicError::TypeMismatch).context(trf("basic.type.holds_numbers", &[&target.trim()])));
+                }
+                self.string_variables.insert(target.trim().to_string(), value);
+                self.note_assignment(target);
+                Ok(())
+            }
+        }
+    }
+
+    /// Store an INPUT answer: a number if it reads as one, else text
+    ///
+    /// An array element takes its array's type, so a word typed for a
+    /// numeric element is a type mismatch.
+    pub fn assign_answer(&mut self, target: &str, answer: &str) -> Result<()> {
+        let number = answer.trim().parse::<f64>();
+        match element_reference(target) {
+            Some(_) if is_string_target(target) => self.assign_text(target, answer.to_string()),
+            Some(_) => {
+                let value = number.map_err(|_| {
+                    anyhow::Error::new(BasicError::TypeMismatch).context(trf("basic.type.needs_number", &[&target.trim()]))
+                })?;
+                self.assign_number(target, value)
+            }
+            None if self.holds_text(target) => self.assign_text(target, answer.to_string()),
+            None => match number {
+                Ok(value) => self.assign_number(target, value),
+                Err(_) => self.assign_text(target, answer.to_string()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_dimensional_storage_is_row_major() {
+        let mut grid = BasicArray::new("GRID", vec![2, 3], 0).unwrap();
+        assert_eq!((grid.len(), grid.extents()), (12, vec![3, 4]));
+        grid.set_number(&[1.0, 2.0], 7.0).unwrap();
+        assert_eq!(grid.values, ArrayValues::Numbers([vec![0.0; 6], vec![7.0], vec![0.0; 5]].concat()));
+        assert_eq!(grid.number_at(&[1.0, 2.0]).unwrap(), 7.0);
+        assert_eq!(grid.shape(), "GRID(0 TO 2, 0 TO 3)");
+    }
+
+    #[test]
+    fn test_each_dimension_is_checked() {
+        let grid = BasicArray::new("GRID", vec![2, 2], 1).unwrap();
+        let error = grid.number_at(&[1.0, 3.0]).unwrap_err();
+        assert_eq!(error.downcast_ref::<BasicError>(), Some(&BasicError::SubscriptOutOfRange));
+        assert_eq!(error.to_string(), "Subscript out of range: GRID(1, 3) (dimension 2 runs 1 to 2)");
+        assert!(grid.number_at(&[0.0, 1.0]).is_err(), "below OPTION BASE 1");
+        assert!(grid.number_at(&[1.0]).is_err(), "one subscript for two dimensions");
+        assert!(BasicArray::new("A", vec![0], 1).is_err());
+        assert!(BasicArray::new("A", vec![1, 1, 1], 0).is_err());
+    }
+
+    #[test]
+    fn test_element_references() {
+        assert_eq!(element_reference("names$(i + 1)"), Some(("NAMES$".to_string(), "i + 1")));
+        assert_eq!(element_reference("GRID(INT(X), 2)"), Some(("GRID".to_string(), "INT(X), 2")));
+        assert_eq!(element_reference("A(1) + B(2)"), None);
+        assert_eq!(element_reference("X"), None);
+        assert_eq!(split_top_level("A(1, 2), \"x, y\", B$"), vec!["A(1, 2)", "\"x, y\"", "B$"]);
+    }
+}
@@ -0,0 +1,126 @@
+//! Breakpoints written into the program: `REM BREAK`, `'BREAK`, `R:BREAK`, `;BREAK`
+//!
+//! A worksheet handed out to a class can carry the spots where every
+//! student's run should stop. With `honor_source_breakpoints` on (Settings →
+//! Programs, a project's `honor_source_breakpoints`, or `--debug` with
+//! `--run`), `load_program` puts a breakpoint on the statement after each
+//! directive. `execute` stops before that statement with
+//! [`Breakpoints::paused_at`] set, and the next `execute` carries on with it.
+//! The directives themselves run as comments. Turning
+//! [`Breakpoints::source_enabled`] off passes all of them at once.
+
+use super::metadata;
+use super::Interpreter;
+
+/// A breakpoint a directive put in the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceBreakpoint {
+    /// Program line the run stops before
+    pub line: usize,
+    /// 1-based source line of that statement
+    pub source_line: usize,
+}
+
+/// Where the loaded program stops, and where it stopped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoints {
+    from_source: Vec<SourceBreakpoint>,
+    /// Stop at the breakpoints from source; off passes them all (kept from run to run)
+    pub source_enabled: bool,
+    /// Program line the run stopped before; the next `execute` starts with it
+    pub paused_at: Option<usize>,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Self { from_source: Vec::new(), source_enabled: true, paused_at: None }
+    }
+}
+
+impl Breakpoints {
+    /// The breakpoints directives put in the loaded program, in program order
+    pub fn from_source(&self) -> &[SourceBreakpoint] {
+        &self.from_source
+    }
+
+    /// Whether the run stops before program line `line`
+    pub fn stops_at(&self, line: usize) -> bool {
+        self.source_enabled && self.from_source.iter().any(|b| b.line == line)
+    }
+}
+
+/// Whether `command` is a breakpoint directive (`REM BREAK`, `'BREAK`, `R:BREAK`, `;BREAK`)
+pub fn is_directive(command: &str) -> bool {
+    metadata::comment_text(command).is_some_and(|text| text.trim().eq_ignore_ascii_case("BREAK"))
+}
+
+impl Interpreter {
+    /// Put a breakpoint on the statement after each directive, if source breakpoints are honoured
+    pub(crate) fn find_source_breakpoints(&mut self) {
+        self.breakpoints.from_source.clear();
+        if !self.honor_source_breakpoints {
+            return;
+        }
+        let mut directive = false;
+        for (line, (_, command)) in self.program_lines.iter().enumerate() {
+            if is_directive(command) {
+                directive = true;
+            } else if directive && !command.trim().is_empty() {
+                let source_line = self.source_lines.get(line).map_or(line + 1, |lines| *lines.start());
+                self.breakpoints.from_source.push(SourceBreakpoint { line, source_line });
+                directive = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+
+    const WORKSHEET: &str = "10 PRINT \"A\"\n20 REM BREAK\n30 PRINT \"B\"\n40 'BREAK\n\n50 PRINT \"C\"";
+
+    #[test]
+    fn test_run_stops_at_each_source_breakpoint() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.honor_source_breakpoints = true;
+        interp.load_program(WORKSHEET).unwrap();
+        let found: Vec<(usize, usize)> = interp.breakpoints.from_source().iter().map(|b| (b.line, b.source_line)).collect();
+        assert_eq!(found, [(2, 3), (5, 6)]);
+
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["A"]);
+        assert_eq!((interp.breakpoints.paused_at, interp.source_line()), (Some(2), 3));
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["A", "B"]);
+        assert_eq!(interp.breakpoints.paused_at, Some(5));
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["A", "B", "C"]);
+        assert_eq!(interp.breakpoints.paused_at, None);
+
+        // Off as a group, the run goes straight through
+        interp.breakpoints.source_enabled = false;
+        interp.load_program(WORKSHEET).unwrap();
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn test_directives_are_comments_unless_honored() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program(WORKSHEET).unwrap();
+        assert!(interp.breakpoints.from_source().is_empty());
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["A", "B", "C"]);
+        interp.load_program("R:BREAK\nT:one\n;BREAK\nFD 10").unwrap();
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["one"]);
+    }
+
+    #[test]
+    fn test_directives_in_each_language() {
+        for directive in ["REM BREAK", "rem break", "'BREAK", "' break", "R:BREAK", ";BREAK", "; Break "] {
+            assert!(is_directive(directive), "{}", directive);
+        }
+        for other in ["REM BREAKFAST", "PRINT \"BREAK\"", "R:SAVE", "; break here", "BREAK"] {
+            assert!(!is_directive(other), "{}", other);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Time of day for BASIC `TIMER`, `DATE$`, and `TIME$`
+//!
+//! The interpreter reads time only through its [`Clock`], so a run can be
+//! replayed against a [`FakeClock`] that starts at a fixed date and advances
+//! a fixed step per statement. With it, `TIMER` measures how many statements
+//! a loop took rather than how busy the machine was.
+
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use std::time::{Duration, Instant};
+
+/// Source of elapsed time and the local date and time
+pub trait Clock: Send {
+    /// Time since the interpreter started; never goes backwards
+    fn elapsed(&self) -> Duration;
+    /// The local date and time now
+    fn now(&self) -> NaiveDateTime;
+    /// Called once before each statement runs
+    fn tick(&mut self) {}
+}
+
+/// The real clock: a monotonic timer plus the system's local time
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// Fast-forward clock: starts at `start` and advances `step` per statement
+#[derive(Debug, Clone, Copy)]
+pub struct FakeClock {
+    start: NaiveDateTime,
+    step: Duration,
+    elapsed: Duration,
+}
+
+impl FakeClock {
+    pub fn new(start: NaiveDateTime, step: Duration) -> Self {
+        Self { start, step, elapsed: Duration::ZERO }
+    }
+
+    /// Starting at midnight on 1 January 1985, one millisecond per statement
+    pub fn fast_forward() -> Self {
+        let start = NaiveDate::from_ymd_opt(1985, 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0)).unwrap_or_default();
+        Self::new(start, Duration::from_millis(1))
+    }
+}
+
+impl Clock for FakeClock {
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn now(&self) -> NaiveDateTime {
+        self.start + self.elapsed
+    }
+
+    fn tick(&mut self) {
+        self.elapsed += self.step;
+    }
+}
+
+/// `DATE$`: `MM-DD-YYYY`
+pub fn date_string(at: NaiveDateTime) -> String {
+    at.format("%m-%d-%Y").to_string()
+}
+
+/// `TIME$`: `HH:MM:SS`, 24-hour
+pub fn time_string(at: NaiveDateTime) -> String {
+    at.format("%H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_clock_advances_per_tick() {
+        let start = NaiveDate::from_ymd_opt(1999, 12, 31).unwrap().and_hms_opt(23, 59, 59).unwrap();
+        let mut clock = FakeClock::new(start, Duration::from_millis(500));
+        assert_eq!((date_string(clock.now()), time_string(clock.now())), ("12-31-1999".into(), "23:59:59".into()));
+        clock.tick();
+        clock.tick();
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+        assert_eq!((date_string(clock.now()), time_string(clock.now())), ("01-01-2000".into(), "00:00:00".into()));
+    }
+}
@@ -0,0 +1,110 @@
+//! Message boxes a program shows: BASIC `MSGBOX` and PILOT `D:`
+//!
+//! A message box pauses the program like `INPUT` does: with an input
+//! callback (tests, `--run`) the callback answers it at once, and otherwise a
+//! pending [`InputRequest`](super::InputRequest) of kind
+//! [`InputKind::Dialog`] waits for the IDE to show a window and report the
+//! button. A box has an OK button, or Yes and No buttons; the Yes/No answer
+//! lands in a variable (BASIC) or the match flag (PILOT, so the next `T:`
+//! only prints on Yes, as after `Y:`). An empty answer, as scripted input
+//! gives when it runs out, counts as OK (or Yes) and says so in the output.
+
+use anyhow::Result;
+
+use super::{ExecutionResult, InputKind, Interpreter};
+use crate::i18n::trf;
+
+/// Button a message box was closed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogAnswer {
+    Ok,
+    Yes,
+    No,
+}
+
+impl DialogAnswer {
+    /// The button `answer` names; `None` for an empty answer
+    ///
+    /// `N`, `NO`, `0`, and `FALSE` (any case) are No; anything else is Yes,
+    /// or OK when the box has no Yes/No buttons.
+    pub fn parse(answer: &str, yes_no: bool) -> Option<DialogAnswer> {
+        let answer = answer.trim().to_ascii_uppercase();
+        if answer.is_empty() {
+            return None;
+        }
+        Some(match (yes_no, answer.as_str()) {
+            (false, _) => DialogAnswer::Ok,
+            (true, "N" | "NO" | "0" | "FALSE") => DialogAnswer::No,
+            (true, _) => DialogAnswer::Yes,
+        })
+    }
+
+    /// The default button: OK, or Yes
+    pub fn default_for(yes_no: bool) -> DialogAnswer {
+        if yes_no { DialogAnswer::Yes } else { DialogAnswer::Ok }
+    }
+
+    /// The answer as the IDE reports it and a text variable stores it
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DialogAnswer::Ok => "OK",
+            DialogAnswer::Yes => "YES",
+            DialogAnswer::No => "NO",
+        }
+    }
+}
+
+impl Interpreter {
+    /// Show `message` in a message box, Yes/No when `yes_no`
+    ///
+    /// The Yes/No answer goes to `var_name`, or to the match flag when it is empty.
+    pub fn show_dialog(&mut self, message: &str, yes_no: bool, var_name: &str) -> Result<ExecutionResult> {
+        if self.input_callback.is_none() {
+            self.start_input_request(message, var_name, false);
+            if let Some(req) = self.pending_input.as_mut() {
+                req.kind = InputKind::Dialog { yes_no };
+            }
+            return Ok(ExecutionResult::WaitForInput);
+        }
+        let prompt = if yes_no { format!("{} [Y/N]", message) } else { message.to_string() };
+        let answer = self.request_input(&prompt);
+        self.answer_dialog(message, &answer, yes_no, var_name)?;
+        Ok(ExecutionResult::Continue)
+    }
+
+    /// Store the answer to a message box, the default one when `answer` is empty
+    pub(crate) fn answer_dialog(&mut self, message: &str, answer: &str, yes_no: bool, var_name: &str) -> Result<()> {
+        let answer = DialogAnswer::parse(answer, yes_no).unwrap_or_else(|| {
+            let chosen = DialogAnswer::default_for(yes_no);
+            self.log_output(trf("dialog.default", &[&message, &chosen.as_str()]));
+            chosen
+        });
+        if !yes_no {
+            return Ok(());
+        }
+        let yes = answer == DialogAnswer::Yes;
+        if var_name.is_empty() {
+            self.match_flag = yes;
+            self.last_match_set = true;
+            Ok(())
+        } else if self.holds_text(var_name) {
+            self.assign_text(var_name, answer.as_str().to_string())
+        } else {
+            self.assign_number(var_name, if yes { 1.0 } else { 0.0 })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        assert_eq!(DialogAnswer::parse("yes", true), Some(DialogAnswer::Yes));
+        assert_eq!(DialogAnswer::parse(" n ", true), Some(DialogAnswer::No));
+        assert_eq!(DialogAnswer::parse("FALSE", true), Some(DialogAnswer::No));
+        assert_eq!(DialogAnswer::parse("no", false), Some(DialogAnswer::Ok));
+        assert_eq!(DialogAnswer::parse("", true), None);
+    }
+}
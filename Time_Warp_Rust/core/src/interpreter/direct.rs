@@ -0,0 +1,314 @@
+//! Direct mode: statements typed at the command line under the screen
+//!
+//! `Interpreter::execute_direct` runs one statement outside any program,
+//! against the same variables and turtle. Each Logo statement is written to
+//! the interpreter's `CommandJournal` together with whether it failed and
+//! whether it changed anything, so a drawing made by hand can be turned back
+//! into a program: `logo_program` replays the statements that worked, and
+//! `basic_program` redraws the recorded segments with BASIC `LINE`s.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use super::arrays::BasicArray;
+use super::Interpreter;
+use crate::graphics::{Rgba, TurtleLine, TurtleState};
+use crate::i18n::trf;
+use crate::languages::Language;
+
+/// One Logo statement run in direct mode
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    /// The statement as typed (trimmed)
+    pub command: String,
+    /// It returned an error or reported one in the output
+    pub failed: bool,
+    /// It moved or restyled the turtle, drew, or set a variable or procedure
+    pub changed: bool,
+    /// Whether it cleared the screen before drawing `lines`
+    pub cleared: bool,
+    /// Segments it drew
+    pub lines: Vec<TurtleLine>,
+}
+
+impl JournalEntry {
+    /// Kept by the cleanup pass: ran without errors and had some effect
+    pub fn kept(&self) -> bool {
+        !self.failed && self.changed
+    }
+}
+
+/// Where the turtle stood when recording began
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartPose {
+    pub x: f32,
+    pub y: f32,
+    /// Heading as the angle mode in use then writes it
+    pub heading: f32,
+    pub pen_down: bool,
+}
+
+/// Logo statements run in direct mode since the journal was last cleared
+#[derive(Debug, Clone, Default)]
+pub struct CommandJournal {
+    pub start: Option<StartPose>,
+    pub entries: Vec<JournalEntry>,
+}
+
+/// Everything a statement can change that later statements or the drawing depend on
+#[derive(PartialEq)]
+struct Effects {
+    pose: (f32, f32, f32, bool, bool),
+    style: (Rgba, f32, Rgba),
+    drawn: (u64, usize, usize),
+    variables: HashMap<String, f64>,
+    string_variables: HashMap<String, String>,
+    arrays: HashMap<String, BasicArray>,
+    procedures: Vec<String>,
+}
+
+impl Effects {
+    fn capture(interp: &Interpreter, turtle: &TurtleState) -> Self {
+        let mut procedures: Vec<String> = interp.logo_procedures.keys().cloned().collect();
+        procedures.sort();
+        Self {
+            pose: (turtle.x, turtle.y, turtle.heading, turtle.pen_down, turtle.visible),
+            style: (turtle.pen_color, turtle.pen_width, turtle.bg_color),
+            drawn: (turtle.generation, turtle.lines.len(), turtle.texts.len()),
+            variables: interp.variables.clone(),
+            string_variables: interp.string_variables.clone(),
+            arrays: interp.arrays.clone(),
+            procedures,
+        }
+    }
+}
+
+impl CommandJournal {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Statements the cleanup pass keeps, in the order they ran
+    pub fn kept_commands(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter(|e| e.kept()).map(|e| e.command.as_str())
+    }
+
+    /// The recorded statements as a Logo program, optionally as `TO <name>` … `END` plus a call
+    ///
+    /// When recording began away from home, the program first moves there
+    /// with the pen up so it draws in the same place.
+    pub fn logo_program(&self, procedure: Option<&str>) -> String {
+        let mut body: Vec<String> = Vec::new();
+        if let Some(start) = self.start.filter(|s| (s.x, s.y, s.heading) != (0.0, 0.0, 0.0) || !s.pen_down) {
+            body.push("PENUP".to_string());
+            body.push(format!("SETXY {} {}", number(start.x), number(start.y)));
+            body.push(format!("SETHEADING {}", number(start.heading)));
+            if start.pen_down {
+                body.push("PENDOWN".to_string());
+            }
+        }
+        body.extend(self.kept_commands().map(str::to_string));
+        let mut program = String::new();
+        match procedure {
+            Some(name) => {
+                program.push_str(&format!("TO {}\n", name));
+                for line in &body {
+                    program.push_str(&format!("  {}\n", line));
+                }
+                program.push_str(&format!("END\n{}\n", name));
+            }
+            None => {
+                for line in &body {
+                    program.push_str(line);
+                    program.push('\n');
+                }
+            }
+        }
+        program
+    }
+
+    /// Segments on screen after the kept statements: a clear drops what came before
+    pub fn segments(&self) -> Vec<TurtleLine> {
+        let mut segments = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.kept()) {
+            if entry.cleared {
+                segments.clear();
+            }
+            segments.extend(entry.lines.iter().cloned());
+        }
+        segments
+    }
+
+    /// The recorded drawing as numbered BASIC `LINE x1, y1, x2, y2` statements
+    pub fn basic_program(&self) -> String {
+        basic_drawing(&self.segments(), "Drawing recorded in Logo direct mode")
+    }
+}
+
+/// A BASIC program redrawing `segments` with `LINE`s, after a `REM remark`
+pub fn basic_drawing(segments: &[TurtleLine], remark: &str) -> String {
+    let mut program = format!("10 REM {}\n", remark);
+    let mut number_line = 20;
+    for segment in segments {
+        program.push_str(&format!(
+            "{} LINE {}, {}, {}, {}\n",
+            number_line,
+            number(segment.start.x),
+            number(segment.start.y),
+            number(segment.end.x),
+            number(segment.end.y),
+        ));
+        number_line += 10;
+    }
+    program.push_str(&format!("{} END\n", number_line));
+    program
+}
+
+/// `v` with at most two decimals and no trailing zeros (`-0` as `0`)
+fn number(v: f32) -> String {
+    let rounded = (v as f64 * 100.0).round() / 100.0;
+    let text = format!("{:.2}", rounded);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+impl Interpreter {
+    /// Run one statement typed in direct mode, recording Logo statements in `journal`
+    ///
+    /// The statement runs outside the loaded program (jumps and `TO` have
+    /// nothing to reach). An error is also reported in the output, as a
+    /// program's would be.
+    pub fn execute_direct(&mut self, command: &str, turtle: &mut TurtleState) -> Result<()> {
+        let command = command.trim();
+        if command.is_empty() {
+            return Ok(());
+        }
+        // Each statement picks its own language, whatever the loaded program's @lang says
+        let program_language = self.metadata.language.take();
+        let is_logo = self.determine_command_type(command) == Language::Logo;
+        if is_logo && self.journal.start.is_none() {
+            self.journal.start = Some(StartPose {
+                x: turtle.x,
+                y: turtle.y,
+                heading: self.angle_mode.heading(turtle.heading),
+                pen_down: turtle.pen_down,
+            });
+        }
+        let before = Effects::capture(self, turtle);
+        let output_before = self.output.len();
+        let result = self.execute_detached(command, turtle);
+        self.metadata.language = program_language;
+        if let Err(e) = &result {
+            self.log_error(trf("error.plain", &[&e]));
+        }
+
+        if is_logo {
+            let failed = result.is_err() || self.output[output_before.min(self.output.len())..].iter().any(|l| l.starts_with('❌'));
+            let cleared = turtle.generation != before.drawn.0;
+            let drawn_from = if cleared { 0 } else { before.drawn.1.min(turtle.lines.len()) };
+            self.journal.entries.push(JournalEntry {
+                command: command.to_string(),
+                failed,
+                changed: Effects::capture(self, turtle) != before,
+                cleared,
+                lines: turtle.lines[drawn_from..].to_vec(),
+            });
+        }
+        result
+    }
+
+    /// Run one statement outside the loaded program, as if past its last line
+    ///
+    /// A panic becomes an error; nothing is logged.
+    pub(crate) fn execute_detached(&mut self, command: &str, turtle: &mut TurtleState) -> Result<()> {
+        let program_line = self.current_line;
+        self.current_line = self.program_lines.len();
+        let result = match self.execute_line_caught(command, turtle) {
+            Ok(result) => result.map(|_| ()),
+            Err(panic) => Err(anyhow::anyhow!(trf("error.internal_direct", &[&panic]))),
+        };
+        // Unset variables read as 0 here too, but a typed line has no line number to warn at
+        self.unset_reads.get_mut().clear();
+        self.current_line = program_line;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direct(interp: &mut Interpreter, turtle: &mut TurtleState, commands: &[&str]) {
+        for command in commands {
+            let _ = interp.execute_direct(command, turtle);
+        }
+    }
+
+    fn run(source: &str) -> TurtleState {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program(source).unwrap();
+        interp.execute(&mut turtle).unwrap();
+        turtle
+    }
+
+    fn endpoints(lines: &[TurtleLine]) -> Vec<(i32, i32, i32, i32)> {
+        let r = |v: f32| (v * 10.0).round() as i32;
+        lines.iter().map(|l| (r(l.start.x), r(l.start.y), r(l.end.x), r(l.end.y))).collect()
+    }
+
+    #[test]
+    fn test_cleanup_drops_failures_and_no_ops() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        direct(&mut interp, &mut turtle, &["FD 50", "HEADING", "SETXY 10", "RT 90", "SETCOLOR PLAID", "PATHCOUNT", "FD 30"]);
+        assert_eq!(interp.journal.entries.len(), 7);
+        assert_eq!(interp.journal.kept_commands().collect::<Vec<_>>(), vec!["FD 50", "RT 90", "FD 30"]);
+        assert_eq!(interp.journal.logo_program(None), "FD 50\nRT 90\nFD 30\n");
+        // Only Logo statements are recorded
+        direct(&mut interp, &mut turtle, &["PRINT 5", "T:Hello"]);
+        assert_eq!(interp.journal.entries.len(), 7);
+    }
+
+    #[test]
+    fn test_logo_program_reproduces_the_drawing() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        let session = ["REPEAT 4 [FD 40 RT 90]", "PU", "FD 100", "PD", "SETPENCOLOR RED", "BK 25", "SETXY 1"];
+        direct(&mut interp, &mut turtle, &session);
+        let drawn = endpoints(&turtle.lines);
+        assert_eq!(drawn.len(), 5);
+
+        assert_eq!(endpoints(&run(&interp.journal.logo_program(None)).lines), drawn);
+        let wrapped = interp.journal.logo_program(Some("DRAWING"));
+        assert!(wrapped.starts_with("TO DRAWING\n  REPEAT 4 [FD 40 RT 90]\n") && wrapped.ends_with("END\nDRAWING\n"), "{}", wrapped);
+        assert_eq!(endpoints(&run(&wrapped).lines), drawn);
+    }
+
+    #[test]
+    fn test_recording_starts_where_the_turtle_stood() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        turtle.goto(20.0, -30.0);
+        turtle.heading = 45.0;
+        direct(&mut interp, &mut turtle, &["FD 10", "CS", "LT 45", "FD 20"]);
+        let program = interp.journal.logo_program(None);
+        assert!(program.starts_with("PENUP\nSETXY 20 -30\nSETHEADING 45\nPENDOWN\n"), "{}", program);
+        assert_eq!(endpoints(&run(&program).lines), endpoints(&turtle.lines));
+    }
+
+    #[test]
+    fn test_basic_lines_redraw_the_segments() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        direct(&mut interp, &mut turtle, &["FD 100", "CS", "REPEAT 3 [FD 60 RT 120]", "PU", "FD 10", "PD", "SETXY 0 0"]);
+        let program = interp.journal.basic_program();
+        assert!(program.contains("20 LINE 0, 0, 0, -60\n"), "{}", program);
+        assert_eq!(endpoints(&interp.journal.segments()), endpoints(&turtle.lines));
+        assert_eq!(endpoints(&run(&program).lines), endpoints(&turtle.lines));
+    }
+}
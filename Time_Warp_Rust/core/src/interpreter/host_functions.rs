@@ -0,0 +1,142 @@
+//! Functions the host application adds to expressions
+//!
+//! An embedder (or a plugin) registers a function with
+//! [`Interpreter::register_function`], and from then on `NAME(…)` calls it
+//! wherever an expression is read: BASIC `LET`, `PRINT`, and `IF`, PILOT
+//! `U:` and `C:`, Logo arguments. Expressions hold numbers, so a function takes its
+//! arity's worth of numbers and returns one; an `Err` it returns fails the
+//! statement like any other error.
+//!
+//! A program's own arrays come first, then registered functions, then the
+//! built-in ones. A registration may not take a built-in function's name,
+//! unless it is made with [`Interpreter::register_function_shadowing`] to
+//! replace it on purpose; the string functions (`LEN`, `VAL`, …) read their
+//! arguments as text and can never be replaced. Registering a name again
+//! replaces the function. Registrations last for the interpreter's life,
+//! across runs.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+
+use super::Interpreter;
+use crate::i18n::trf;
+use crate::utils::expr_eval::{HostFunctions, TEXT_FUNCTIONS};
+use crate::utils::ExpressionEvaluator;
+
+/// A registered function: its arguments in, a number out
+/// (Send so the interpreter can still move to a RunWorker thread)
+pub type HostFunction = Box<dyn Fn(&[f64]) -> Result<f64> + Send>;
+
+/// Registered functions by upper-case name, with their arity
+#[derive(Default)]
+pub struct HostFunctionTable {
+    functions: HashMap<String, (usize, HostFunction)>,
+}
+
+impl HostFunctionTable {
+    fn insert(&mut self, name: &str, arity: usize, function: HostFunction, shadowing: bool) -> Result<()> {
+        let upper = name.to_uppercase();
+        let mut chars = upper.chars();
+        if !chars.next().is_some_and(|c| c.is_ascii_alphabetic()) || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!(trf("expr.host_bad_name", &[&name]));
+        }
+        if TEXT_FUNCTIONS.contains(&upper.as_str()) {
+            bail!(trf("expr.host_string_function", &[&upper]));
+        }
+        if !shadowing && ExpressionEvaluator::FUNCTIONS.contains(&upper.as_str()) {
+            bail!(trf("expr.host_builtin", &[&upper]));
+        }
+        self.functions.insert(upper, (arity, function));
+        Ok(())
+    }
+}
+
+impl HostFunctions for HostFunctionTable {
+    fn arity(&self, name: &str) -> Option<usize> {
+        self.functions.get(name).map(|(arity, _)| *arity)
+    }
+
+    fn call(&self, name: &str, arguments: &[f64]) -> Result<f64> {
+        let (_, function) = self.functions.get(name).ok_or_else(|| anyhow!(trf("expr.unknown_function", &[&name])))?;
+        function(arguments).map_err(|e| anyhow!("{}: {}", name, e))
+    }
+}
+
+impl Interpreter {
+    /// Let expressions call `name` with `arity` arguments; a built-in function's name is refused
+    pub fn register_function(&mut self, name: &str, arity: usize, function: HostFunction) -> Result<()> {
+        self.host_functions.insert(name, arity, function, false)
+    }
+
+    /// Like [`Self::register_function`], but replacing a built-in function of the same name
+    pub fn register_function_shadowing(&mut self, name: &str, arity: usize, function: HostFunction) -> Result<()> {
+        self.host_functions.insert(name, arity, function, true)
+    }
+
+    /// Forget a registered function; true when there was one
+    pub fn unregister_function(&mut self, name: &str) -> bool {
+        self.host_functions.functions.remove(&name.to_uppercase()).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphics::TurtleState;
+    use crate::interpreter::Interpreter;
+
+    fn fib(n: f64) -> f64 {
+        let (mut a, mut b) = (0.0, 1.0);
+        for _ in 0..n as u32 {
+            (a, b) = (b, a + b);
+        }
+        a
+    }
+
+    fn run(interp: &mut Interpreter, program: &str) -> TurtleState {
+        let mut turtle = TurtleState::new();
+        interp.load_program(program).unwrap();
+        interp.execute(&mut turtle).unwrap();
+        turtle
+    }
+
+    #[test]
+    fn test_registered_function_in_every_language() {
+        let mut interp = Interpreter::new();
+        interp.register_function("Fib", 1, Box::new(|args| Ok(fib(args[0])))).unwrap();
+
+        run(&mut interp, "10 LET X = FIB(10) + 1\n20 PRINT FIB(X - 48)\n30 IF fib(3) = 2 THEN PRINT \"yes\"");
+        assert_eq!(interp.variables["X"], 56.0);
+        assert!(interp.output.iter().any(|l| l.trim() == "21"), "{:?}", interp.output);
+        assert!(interp.output.iter().any(|l| l.trim() == "yes"), "{:?}", interp.output);
+
+        run(&mut interp, "U:Y=FIB(7)*2\nC:FIB(4) = 3");
+        assert_eq!((interp.variables["Y"], interp.stored_condition), (26.0, Some(true)));
+
+        let turtle = run(&mut interp, "FORWARD FIB(6)");
+        assert!((turtle.y + 8.0).abs() < 1e-3, "{}", turtle.y);
+    }
+
+    #[test]
+    fn test_names_arity_and_errors() {
+        let mut interp = Interpreter::new();
+        let refuse = |interp: &mut Interpreter, name| interp.register_function(name, 1, Box::new(|_| Ok(0.0))).unwrap_err().to_string();
+        assert!(refuse(&mut interp, "SQR").contains("built-in"));
+        assert!(refuse(&mut interp, "LEN").contains("string function"));
+        assert!(refuse(&mut interp, "2X").contains("not a function name"));
+        assert!(interp.register_function_shadowing("LEN", 1, Box::new(|_| Ok(0.0))).is_err());
+
+        // Replacing a built-in on purpose
+        interp.register_function_shadowing("SQR", 1, Box::new(|args| Ok(args[0] * args[0]))).unwrap();
+        assert_eq!(interp.evaluate_expression("SQR(3)").unwrap(), 9.0);
+        assert!(interp.unregister_function("sqr"));
+        assert_eq!(interp.evaluate_expression("SQR(9)").unwrap(), 3.0);
+
+        interp.register_function("HYPOT", 2, Box::new(|args| Ok(args[0].hypot(args[1])))).unwrap();
+        assert_eq!(interp.evaluate_expression("HYPOT(3, 4)").unwrap(), 5.0);
+        assert!(interp.evaluate_expression("HYPOT()").is_err());
+        interp.register_function("FAIL", 0, Box::new(|_| Err(anyhow::anyhow!("sensor offline")))).unwrap();
+        let err = interp.evaluate_expression("FAIL() + 1").unwrap_err();
+        assert!(format!("{:#}", err).contains("FAIL: sensor offline"), "{:#}", err);
+    }
+}
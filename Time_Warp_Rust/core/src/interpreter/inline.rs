@@ -0,0 +1,175 @@
+//! Evaluate Selection: a selected expression, statement, or block run against the session
+//!
+//! Ctrl+Enter in the editor hands the selection (or the cursor's line) to
+//! [`Interpreter::evaluate_selection`], and [`classify`] decides what it is.
+//! One statement runs as a direct-mode statement does, against the current
+//! variables and turtle. Anything else on one line is an expression, worked
+//! out with the current variables. Several lines run as a program of their
+//! own through [`Interpreter::run_isolated`], starting from copies of the
+//! variables and changing nothing here. Each gives one short line of result.
+
+use super::isolated::IsolatedRunOptions;
+use super::Interpreter;
+use crate::graphics::TurtleState;
+use crate::i18n::tr;
+use crate::languages::{basic, logo};
+
+/// What a selection is, and so how it runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection<'a> {
+    /// Worked out and shown, changing nothing
+    Expression(&'a str),
+    /// Run in the session, without any BASIC line number it was selected with
+    Statement(&'a str),
+    /// More than one line: run in a throwaway interpreter
+    Block(&'a str),
+}
+
+/// What a selection gave
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineResult {
+    /// The value, the last line it printed, "ok", or the error
+    pub text: String,
+    pub failed: bool,
+}
+
+impl InlineResult {
+    fn ok(output: Option<&String>) -> Self {
+        Self { text: output.cloned().unwrap_or_else(|| tr("inline.ok").to_string()), failed: false }
+    }
+
+    fn failed(error: impl ToString) -> Self {
+        Self { text: error.to_string(), failed: true }
+    }
+}
+
+/// How `selection` runs; `None` when it is blank
+///
+/// A line is a statement when it starts with a PILOT `X:`, a BASIC or Logo
+/// keyword, or a Logo procedure the session knows, after any line number.
+pub fn classify<'a>(interp: &Interpreter, selection: &'a str) -> Option<Selection<'a>> {
+    let text = selection.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if text.lines().filter(|line| !line.trim().is_empty()).count() > 1 {
+        return Some(Selection::Block(text));
+    }
+    let (number, statement) = interp.parse_line(text);
+    if number.is_some() && is_statement(interp, statement) {
+        return Some(Selection::Statement(statement));
+    }
+    Some(if is_statement(interp, text) { Selection::Statement(text) } else { Selection::Expression(text) })
+}
+
+fn is_statement(interp: &Interpreter, text: &str) -> bool {
+    if text.chars().next().is_some_and(char::is_alphabetic) && text.chars().nth(1) == Some(':') {
+        return true;
+    }
+    let first = text.split_whitespace().next().unwrap_or("").to_uppercase();
+    basic::KEYWORDS.contains(&first.as_str()) || logo::KEYWORDS.contains(&first.as_str()) || interp.logo_procedures.contains_key(&first)
+}
+
+impl Interpreter {
+    /// Evaluate or run `selection` as [`classify`] decides; `None` when it is blank
+    ///
+    /// A statement's printed lines go to the output as direct mode's do; its
+    /// drawing goes on `turtle`. Errors are returned rather than logged.
+    pub fn evaluate_selection(&mut self, selection: &str, turtle: &mut TurtleState) -> Option<InlineResult> {
+        Some(match classify(self, selection)? {
+            Selection::Expression(expr) => match self.selection_value(expr) {
+                Ok(value) => InlineResult { text: value, failed: false },
+                Err(e) => InlineResult::failed(e),
+            },
+            Selection::Statement(statement) => {
+                let program_language = self.metadata.language.take();
+                let output_before = self.output.len();
+                let result = self.execute_detached(statement, turtle);
+                self.metadata.language = program_language;
+                let printed = &self.output[output_before.min(self.output.len())..];
+                match result {
+                    Err(e) => InlineResult::failed(e),
+                    Ok(()) => match printed.iter().find(|line| line.starts_with('❌')) {
+                        Some(error) => InlineResult::failed(error),
+                        None => InlineResult::ok(printed.last()),
+                    },
+                }
+            }
+            Selection::Block(source) => {
+                let options = IsolatedRunOptions {
+                    variables: self.variables.clone(),
+                    string_variables: self.string_variables.clone(),
+                    limits: self.limits.clone(),
+                    ..IsolatedRunOptions::default()
+                };
+                let report = Interpreter::run_isolated(source, options);
+                match report.error.or_else(|| report.output.iter().find(|line| line.starts_with('❌')).cloned()) {
+                    Some(error) => InlineResult::failed(error),
+                    None => InlineResult::ok(report.output.last()),
+                }
+            }
+        })
+    }
+
+    /// An expression's value as text: a string in quotes, or a number
+    fn selection_value(&self, expr: &str) -> anyhow::Result<String> {
+        if expr.len() >= 2 && expr.starts_with('"') && expr.ends_with('"') {
+            return Ok(expr.to_string());
+        }
+        let text = match self.element_text(expr) {
+            Some(text) => Some(text?),
+            None => expr.ends_with('$').then(|| self.string_variables.get(&expr.to_uppercase()).cloned().unwrap_or_default()),
+        };
+        if let Some(text) = text {
+            return Ok(format!("\"{}\"", text));
+        }
+        let value = self.evaluate_expression(expr);
+        self.unset_reads.borrow_mut().clear();
+        Ok(value?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_picks_how_a_selection_runs() {
+        let mut interp = Interpreter::new();
+        assert_eq!(classify(&interp, "  \n "), None);
+        assert_eq!(classify(&interp, "X * 2 + 1"), Some(Selection::Expression("X * 2 + 1")));
+        assert_eq!(classify(&interp, "10"), Some(Selection::Expression("10")));
+        assert_eq!(classify(&interp, "PRINT X"), Some(Selection::Statement("PRINT X")));
+        assert_eq!(classify(&interp, "20 LET X = 5"), Some(Selection::Statement("LET X = 5")));
+        assert_eq!(classify(&interp, "10 + 5"), Some(Selection::Expression("10 + 5")));
+        assert_eq!(classify(&interp, "fd 50"), Some(Selection::Statement("fd 50")));
+        assert_eq!(classify(&interp, "T:Hello"), Some(Selection::Statement("T:Hello")));
+        assert_eq!(classify(&interp, "SQUARE 40"), Some(Selection::Expression("SQUARE 40")));
+        interp.load_program("TO SQUARE :SIZE\nREPEAT 4 [FD :SIZE RT 90]\nEND\nSQUARE 10").unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+        assert_eq!(classify(&interp, "SQUARE 40"), Some(Selection::Statement("SQUARE 40")));
+        assert_eq!(classify(&interp, "\nFD 10\n\nRT 90\n"), Some(Selection::Block("FD 10\n\nRT 90")));
+    }
+
+    #[test]
+    fn test_selections_run_against_the_session() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program("10 LET X = 4\n20 LET N$ = \"Ada\"").unwrap();
+        interp.execute(&mut turtle).unwrap();
+        let mut evaluate = |text: &str| interp.evaluate_selection(text, &mut turtle).unwrap();
+
+        assert_eq!(evaluate("X * 2 + 1"), InlineResult { text: "9".into(), failed: false });
+        assert_eq!(evaluate("N$").text, "\"Ada\"");
+        assert!(evaluate("X +").failed);
+        assert_eq!(evaluate("LET X = 10").text, "ok");
+        assert_eq!(evaluate("PRINT X + 1").text, "11");
+        assert_eq!(evaluate("FORWARD 30").text, "ok");
+        // A block starts from the session's variables but leaves them alone
+        assert_eq!(evaluate("LET X = X + 1\nPRINT X").text, "11");
+        assert_eq!(evaluate("X").text, "10");
+        assert!(evaluate("GOTO 999\nPRINT 1").failed);
+        assert_eq!(turtle.lines.len(), 1, "statements draw on the live canvas");
+        assert_eq!(interp.variables["X"], 10.0);
+    }
+}
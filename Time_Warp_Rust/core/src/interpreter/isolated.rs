@@ -0,0 +1,260 @@
+//! Run a program fragment in a throwaway interpreter
+//!
+//! Used by graders and tutorials to check a student's snippet without touching
+//! the main session: a fresh `Interpreter` and `TurtleState` are built for the
+//! run and dropped afterwards, leaving only the `RunReport`. Unless the options
+//! say otherwise, the snippet cannot touch files, the network, plugins, or
+//! shell commands, and is stopped after [`ISOLATED_TIME_LIMIT_SECS`] or
+//! [`ISOLATED_MAX_ITERATIONS`] statements, whichever comes first.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use super::timing::RunTiming;
+use super::warnings::Diagnostic;
+use super::{ExecutionLimits, Interpreter, SecurityPolicy};
+use crate::graphics::{TurtleLine, TurtleState};
+use crate::utils::error::BasicError;
+
+/// Wall-clock budget of an isolated run with the default options
+pub const ISOLATED_TIME_LIMIT_SECS: u64 = 2;
+/// Statements an isolated run with the default options may execute
+pub const ISOLATED_MAX_ITERATIONS: usize = 20_000;
+
+/// Settings for `Interpreter::run_isolated`
+#[derive(Debug, Clone)]
+pub struct IsolatedRunOptions {
+    /// Numeric variables set before the first line runs
+    pub variables: HashMap<String, f64>,
+    /// String variables set before the first line runs
+    pub string_variables: HashMap<String, String>,
+    /// Answers handed to A:/INPUT in order; when exhausted the run pauses
+    pub inputs: Vec<String>,
+    /// Report BASIC errors in the classic `?SYNTAX ERROR IN 20` style
+    pub classic_errors: bool,
+    pub limits: ExecutionLimits,
+    /// Seed for RND and QZ:SHUFFLE, so a grader sees the same numbers every time
+    pub random_seed: Option<u64>,
+    /// What the run may do, and its time and statement budget
+    pub policy: SecurityPolicy,
+}
+
+impl Default for IsolatedRunOptions {
+    fn default() -> Self {
+        Self {
+            variables: HashMap::new(),
+            string_variables: HashMap::new(),
+            inputs: Vec::new(),
+            classic_errors: false,
+            random_seed: None,
+            // Much tighter than the IDE defaults: snippets are short
+            limits: ExecutionLimits {
+                max_program_bytes: 16 * 1024,
+                max_program_lines: 500,
+                max_repeat_count: 1_000,
+                max_for_iterations: 10_000,
+                max_output_lines: 500,
+                ..ExecutionLimits::default()
+            },
+            policy: SecurityPolicy {
+                time_limit_secs: Some(ISOLATED_TIME_LIMIT_SECS),
+                max_iterations: Some(ISOLATED_MAX_ITERATIONS),
+                ..SecurityPolicy::restricted()
+            },
+        }
+    }
+}
+
+/// Outcome of an isolated run
+#[derive(Debug, Clone, Default)]
+pub struct RunReport {
+    pub output: Vec<String>,
+    pub variables: HashMap<String, f64>,
+    pub string_variables: HashMap<String, String>,
+    pub recorded_series: HashMap<String, Vec<f64>>,
+    /// Number of line segments the turtle drew
+    pub lines_drawn: usize,
+    /// The segments themselves, in the order they were drawn
+    pub lines: Vec<TurtleLine>,
+    /// Final turtle position and heading
+    pub turtle_position: (f32, f32),
+    pub turtle_heading: f32,
+    /// Load or fatal execution error (non-fatal errors appear in `output`)
+    pub error: Option<String>,
+    /// The program stopped at an INPUT with no answer available
+    pub waiting_for_input: bool,
+    /// Last BASIC error raised (its number is `BasicError::code`)
+    pub basic_error: Option<BasicError>,
+    /// Source lines of the statements that raised errors, in the order they failed
+    pub error_spans: Vec<RangeInclusive<usize>>,
+    /// Non-fatal errors reported, in order (see `Interpreter::log_error`)
+    pub errors: Vec<String>,
+    /// Warnings raised, and any strict mode made errors
+    pub diagnostics: Vec<Diagnostic>,
+    /// Seed the run's random numbers started from, when it drew any
+    pub random_seed: Option<u64>,
+    /// When lines appeared and answers arrived, when the run recorded them
+    pub timing: Option<RunTiming>,
+}
+
+impl RunReport {
+    /// Snapshot an interpreter and turtle after a run; `error` is the load/fatal error
+    pub fn capture(interp: &Interpreter, turtle: &TurtleState, error: Option<String>) -> Self {
+        Self {
+            output: interp.output.clone(),
+            variables: interp.variables.clone(),
+            string_variables: interp.string_variables.clone(),
+            recorded_series: interp.recorded_series.clone(),
+            lines_drawn: turtle.lines.len(),
+            lines: turtle.lines.clone(),
+            turtle_position: (turtle.x, turtle.y),
+            turtle_heading: turtle.heading,
+            error,
+            waiting_for_input: interp.pending_input.is_some(),
+            basic_error: interp.last_basic_error,
+            error_spans: interp.error_spans.clone(),
+            errors: interp.errors.clone(),
+            diagnostics: interp.diagnostics.clone(),
+            random_seed: interp.rng.borrow().used().then(|| interp.random_seed()),
+            timing: interp.record_timing.then(|| interp.timing.clone()),
+        }
+    }
+
+    /// Whether the run finished without a load/fatal error or a pending INPUT
+    pub fn completed(&self) -> bool {
+        self.error.is_none() && !self.waiting_for_input
+    }
+
+    /// Compare output to `expected`, ignoring differences in whitespace
+    ///
+    /// Runs of spaces/tabs collapse to one, lines are trimmed, and blank
+    /// lines are dropped on both sides before comparing.
+    pub fn output_matches(&self, expected: &str) -> bool {
+        normalize_lines(self.output.iter().flat_map(|l| l.lines())) == normalize_lines(expected.lines())
+    }
+}
+
+fn normalize_lines<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<String> {
+    lines
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+impl Interpreter {
+    /// Run `source` in a fresh interpreter and turtle, returning what happened
+    ///
+    /// Nothing is shared with any existing interpreter; seeded variables from
+    /// `options` are applied after loading so the program sees them.
+    pub fn run_isolated(source: &str, options: IsolatedRunOptions) -> RunReport {
+        let mut interp = Interpreter::new();
+        let mut error = interp.set_policy(options.policy).err().map(|e| e.to_string());
+        interp.limits = options.limits;
+        interp.classic_errors = options.classic_errors;
+        interp.run_seed = options.random_seed;
+        if !options.inputs.is_empty() {
+            let mut answers = options.inputs.into_iter();
+            interp.input_callback = Some(Box::new(move |_prompt| answers.next().unwrap_or_default()));
+        }
+        let mut turtle = TurtleState::new();
+
+        match interp.load_program(source) {
+            Ok(()) if error.is_none() => {
+                interp.variables.extend(options.variables);
+                interp.string_variables.extend(options.string_variables);
+                if let Err(e) = interp.execute(&mut turtle) {
+                    error = Some(e.to_string());
+                }
+            }
+            Ok(()) => {}
+            Err(e) => error = Some(e.to_string()),
+        }
+        RunReport::capture(&interp, &turtle, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_isolated_leaves_main_interpreter_untouched() {
+        let mut main = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        main.load_program("10 LET X = 1\n20 PRINT X").unwrap();
+        main.execute(&mut turtle).unwrap();
+
+        let report = Interpreter::run_isolated("10 LET X = 99\n20 PRINT X", IsolatedRunOptions::default());
+
+        assert!(report.completed());
+        assert_eq!(report.variables.get("X"), Some(&99.0));
+        assert!(report.output_matches("99"));
+        assert_eq!(main.variables.get("X"), Some(&1.0));
+        assert_eq!(main.output, vec!["1"]);
+    }
+
+    #[test]
+    fn test_run_isolated_with_seeded_variables() {
+        let mut options = IsolatedRunOptions::default();
+        options.variables.insert("SIDE".to_string(), 50.0);
+
+        let report = Interpreter::run_isolated("REPEAT 4 [FORWARD :SIDE RIGHT 90]", options);
+
+        assert!(report.completed());
+        assert_eq!(report.lines_drawn, 4);
+        assert!(report.turtle_position.0.abs() < 0.01 && report.turtle_position.1.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_run_isolated_feeds_scripted_inputs() {
+        let options = IsolatedRunOptions { inputs: vec!["Ada".to_string()], ..IsolatedRunOptions::default() };
+        let report = Interpreter::run_isolated("A:NAME\nT:Hi *NAME*", options);
+        assert!(report.completed());
+        assert!(report.output_matches("Hi Ada"));
+    }
+
+    #[test]
+    fn test_run_isolated_stops_an_endless_loop_and_keeps_to_itself() {
+        let started = std::time::Instant::now();
+        let report = Interpreter::run_isolated("10 LET N = N + 1\n20 GOTO 10", IsolatedRunOptions::default());
+        assert!(started.elapsed() < std::time::Duration::from_secs(ISOLATED_TIME_LIMIT_SECS + 1), "{:?}", started.elapsed());
+        assert!(report.variables["N"] <= ISOLATED_MAX_ITERATIONS as f64);
+        assert!(report.output.last().is_some_and(|l| l.contains(crate::i18n::tr("warning.max_iterations"))), "{:?}", report.output);
+
+        let slow = IsolatedRunOptions { policy: SecurityPolicy { max_iterations: None, time_limit_secs: Some(0), ..SecurityPolicy::restricted() }, ..IsolatedRunOptions::default() };
+        assert!(Interpreter::run_isolated("10 LET N = N + 1\n20 GOTO 10", slow).error.is_some_and(|e| e.contains("0")));
+
+        let report = Interpreter::run_isolated("R:LOAD scores.txt", IsolatedRunOptions::default());
+        assert!(report.output.iter().any(|l| l.contains("disabled by policy")), "{:?}", report.output);
+    }
+
+    #[test]
+    fn test_run_isolated_classic_errors() {
+        let options = IsolatedRunOptions { classic_errors: true, ..IsolatedRunOptions::default() };
+        let report = Interpreter::run_isolated("10 FOR I = 1 TO 2\n20 NEXT J", options);
+        assert!(report.output_matches("?NEXT WITHOUT FOR ERROR IN 20"));
+        assert_eq!(report.basic_error, Some(BasicError::NextWithoutFor));
+        assert_eq!(report.error_spans, vec![2..=2]);
+    }
+
+    #[test]
+    fn test_run_isolated_reports_load_errors() {
+        let options = IsolatedRunOptions {
+            limits: ExecutionLimits { max_program_lines: 1, ..ExecutionLimits::default() },
+            ..IsolatedRunOptions::default()
+        };
+        let report = Interpreter::run_isolated("T:one\nT:two", options);
+        assert!(!report.completed());
+        assert!(report.output.is_empty());
+    }
+
+    #[test]
+    fn test_output_matches_normalizes_whitespace() {
+        let report = RunReport {
+            output: vec!["Hello   world ".to_string(), String::new(), "\tBye".to_string()],
+            ..RunReport::default()
+        };
+        assert!(report.output_matches("Hello world\nBye\n"));
+        assert!(!report.output_matches("Hello world"));
+    }
+}
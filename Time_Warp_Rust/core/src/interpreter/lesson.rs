@@ -0,0 +1,192 @@
+//! Statements a lesson turns off, e.g. GOTO to teach structured programming
+//!
+//! A project's `disabled_statements = ["GOTO", "GOSUB"]` (or `--disable
+//! GOTO,GOSUB`) makes each language's dispatcher refuse those statements: the
+//! statement is reported as an error, "GOTO is disabled for this lesson —
+//! use FOR … NEXT instead", and the run goes on with the next line as after
+//! any other error. BASIC `IF … THEN 100` counts as a GOTO. Names are BASIC
+//! and Logo keywords or PILOT commands (`J` or `J:`); a Logo abbreviation
+//! names the same statement as the full word, so disabling `SETXY` disables
+//! `SETPOS` too. [`disabled_uses`] finds them in a source without running it,
+//! for the editor to underline.
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+
+use super::Interpreter;
+use crate::i18n::trf;
+
+/// What to use instead of a statement, for the message when it is disabled
+const ALTERNATIVES: &[(&str, &str)] = &[
+    ("GOTO", "FOR … NEXT"),
+    ("GOSUB", "DEF FN"),
+    ("J", "Y:, N: and conditional T:"),
+    ("SETXY", "FORWARD and RIGHT"),
+    ("SETX", "FORWARD and RIGHT"),
+    ("SETY", "FORWARD and RIGHT"),
+    ("SETHEADING", "RIGHT and LEFT"),
+];
+
+/// Logo abbreviations and synonyms, and the statement they name
+const ALIASES: &[(&str, &str)] = &[
+    ("FD", "FORWARD"),
+    ("BK", "BACK"),
+    ("BACKWARD", "BACK"),
+    ("LT", "LEFT"),
+    ("RT", "RIGHT"),
+    ("PU", "PENUP"),
+    ("PD", "PENDOWN"),
+    ("CS", "CLEARSCREEN"),
+    ("SETPOS", "SETXY"),
+    ("SETH", "SETHEADING"),
+    ("SETPENCOLOR", "SETCOLOR"),
+    ("SETPENSIZE", "PENWIDTH"),
+    ("HT", "HIDETURTLE"),
+    ("ST", "SHOWTURTLE"),
+];
+
+/// `name` uppercased, without a PILOT colon, and with an alias replaced by its statement
+fn canonical(name: &str) -> String {
+    let upper = name.trim().trim_end_matches(':').to_uppercase();
+    ALIASES.iter().find(|(alias, _)| *alias == upper).map_or(upper, |(_, statement)| statement.to_string())
+}
+
+/// The statements a lesson turns off
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DisabledStatements {
+    names: BTreeSet<String>,
+}
+
+impl DisabledStatements {
+    pub fn new<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Self {
+        let names = names.into_iter().map(|n| canonical(n.as_ref())).filter(|n| !n.is_empty()).collect();
+        Self { names }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Whether the statement starting with `keyword` is disabled
+    pub fn contains(&self, keyword: &str) -> bool {
+        !self.names.is_empty() && self.names.contains(&canonical(keyword))
+    }
+
+    /// The names, uppercased, as the settings would list them
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+}
+
+/// "`shown` is disabled for this lesson", with what to use instead when the table knows
+pub fn disabled_message(keyword: &str, shown: &str) -> String {
+    match ALTERNATIVES.iter().find(|(name, _)| *name == canonical(keyword)) {
+        Some((_, instead)) => trf("lesson.disabled_use", &[&shown, instead]),
+        None => trf("lesson.disabled", &[&shown]),
+    }
+}
+
+impl Interpreter {
+    /// Refuse the statement starting with `keyword` (shown as `shown`) if the lesson disabled it
+    pub(crate) fn check_enabled(&self, keyword: &str, shown: &str) -> Result<()> {
+        if self.disabled.contains(keyword) {
+            return Err(anyhow::anyhow!(disabled_message(keyword, shown)));
+        }
+        Ok(())
+    }
+}
+
+/// A disabled statement found in a source
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisabledUse {
+    /// 1-based source line
+    pub line: usize,
+    /// Char columns of the keyword on the line
+    pub columns: std::ops::Range<usize>,
+    pub message: String,
+}
+
+/// Every disabled statement in `source`, without running it
+///
+/// A PILOT line (`J:…`) is judged by its command; any other line by each
+/// word outside string literals and comments, so `IF X THEN GOTO 10` and a
+/// Logo `REPEAT 4 [SETXY 0 0]` are caught, and `THEN 100` counts as a GOTO.
+pub fn disabled_uses(source: &str, disabled: &DisabledStatements) -> Vec<DisabledUse> {
+    let mut uses = Vec::new();
+    if disabled.is_empty() {
+        return uses;
+    }
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = text.trim_start();
+        let indent = text.chars().count() - trimmed.chars().count();
+        // PILOT: one or two letters and a colon
+        if let Some((command, _)) = trimmed.split_once(':').filter(|(c, _)| (1..=2).contains(&c.len()) && c.chars().all(|c| c.is_ascii_alphabetic())) {
+            if disabled.contains(command) {
+                let shown = format!("{}:", command.to_uppercase());
+                uses.push(DisabledUse { line, columns: indent..indent + shown.len(), message: disabled_message(command, &shown) });
+            }
+            continue;
+        }
+        let mut after_branch = false;
+        for (start, word) in words(text) {
+            let upper = word.to_uppercase();
+            if upper == "REM" {
+                break;
+            }
+            let keyword = if after_branch && upper.chars().all(|c| c.is_ascii_digit()) { "GOTO" } else { upper.as_str() };
+            if disabled.contains(keyword) {
+                uses.push(DisabledUse { line, columns: start..start + word.chars().count(), message: disabled_message(keyword, keyword) });
+            }
+            after_branch = upper == "THEN" || upper == "ELSE";
+        }
+    }
+    uses
+}
+
+/// Words of a line outside string literals and `;` comments, with their starting char column
+fn words(text: &str) -> Vec<(usize, String)> {
+    let mut words = Vec::new();
+    let mut in_string = false;
+    let mut current: Option<(usize, String)> = None;
+    for (column, c) in text.chars().enumerate() {
+        let word_char = !in_string && (c.is_alphanumeric() || matches!(c, '_' | '$'));
+        if word_char {
+            current.get_or_insert_with(|| (column, String::new())).1.push(c);
+            continue;
+        }
+        words.extend(current.take());
+        match c {
+            '"' => in_string = !in_string,
+            ';' if !in_string => break,
+            _ => {}
+        }
+    }
+    words.extend(current);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_normalized() {
+        let disabled = DisabledStatements::new(["goto", " J: ", "SETPOS", ""]);
+        assert_eq!(disabled.names().collect::<Vec<_>>(), ["GOTO", "J", "SETXY"]);
+        assert!(disabled.contains("Goto") && disabled.contains("SETXY") && disabled.contains("setpos"));
+        assert!(!disabled.contains("GOSUB"));
+        assert_eq!(disabled_message("goto", "GOTO"), "GOTO is disabled for this lesson — use FOR … NEXT instead");
+        assert_eq!(disabled_message("PRINT", "PRINT"), "PRINT is disabled for this lesson");
+    }
+
+    #[test]
+    fn test_disabled_uses_are_found_before_running() {
+        let disabled = DisabledStatements::new(["GOTO", "J", "SETXY"]);
+        let source = "10 PRINT \"GOTO is off\"\n20 IF X > 1 THEN 10\n30 goto 10\n40 REM GOTO later\nJ:DONE\nT:no J: here\nREPEAT 2 [SETPOS 0 0] ; GOTO";
+        let found: Vec<(usize, std::ops::Range<usize>)> = disabled_uses(source, &disabled).into_iter().map(|u| (u.line, u.columns)).collect();
+        assert_eq!(found, [(2, 17..19), (3, 3..7), (5, 0..2), (7, 10..16)]);
+        assert!(disabled_uses(source, &DisabledStatements::default()).is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+//! Resource limits that guard against pathological programs
+//!
+//! These are checked before or during execution so that a huge paste or a
+//! runaway `REPEAT 1000000000 [...]` fails fast with a clear diagnostic instead
+//! of hanging the IDE before the execution timeout can kick in. Nesting
+//! limits keep `REPEAT 2 [[[[…]]]]` or an expression with thousands of
+//! parentheses from running the interpreter out of stack, which would end
+//! the whole IDE rather than the run.
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::trf;
+use crate::languages::logo::DEFAULT_BRACKET_DEPTH;
+use crate::languages::pilot::history::DEFAULT_ANSWER_HISTORY;
+use crate::utils::expr_eval::DEFAULT_PAREN_DEPTH;
+
+/// What to do when a REPEAT count or FOR span exceeds its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LimitPolicy {
+    /// Print a warning and run the loop up to the limit
+    #[default]
+    WarnAndClamp,
+    /// Report an error and skip the statement
+    Error,
+}
+
+/// Configurable execution limits (edited in Settings → Limits)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionLimits {
+    pub max_program_bytes: usize,
+    pub max_program_lines: usize,
+    pub max_repeat_count: usize,
+    pub max_for_iterations: usize,
+    pub max_output_lines: usize,
+    /// Characters an output line keeps; longer lines are cut, with the full text kept aside
+    pub max_output_line_chars: usize,
+    pub loop_policy: LimitPolicy,
+    /// Wrong answers a validated INPUT re-prompts for before raising BAD INPUT
+    pub max_input_retries: usize,
+    /// PILOT answers kept for `%ANS1`, `%ANS2`, …
+    pub max_answer_history: usize,
+    /// `[` brackets a REPEAT may nest, and REPEATs that may run inside each other
+    pub max_bracket_depth: usize,
+    /// Parentheses an expression may nest
+    pub max_paren_depth: usize,
+}
+
+impl Default for ExecutionLimits {
+    fn default() -> Self {
+        Self {
+            max_program_bytes: 1024 * 1024,
+            max_program_lines: 20_000,
+            max_repeat_count: 100_000,
+            max_for_iterations: 1_000_000,
+            max_output_lines: 10_000,
+            max_output_line_chars: 10_000,
+            loop_policy: LimitPolicy::WarnAndClamp,
+            max_input_retries: 3,
+            max_answer_history: DEFAULT_ANSWER_HISTORY,
+            max_bracket_depth: DEFAULT_BRACKET_DEPTH,
+            max_paren_depth: DEFAULT_PAREN_DEPTH,
+        }
+    }
+}
+
+impl ExecutionLimits {
+    /// Build the diagnostic for an exceeded limit, naming the Settings field to raise
+    pub fn exceeded(what: &str, value: impl std::fmt::Display, limit: usize, setting: &str) -> String {
+        trf("limit.exceeded", &[&what, &value, &limit, &setting])
+    }
+}
+
+/// A statement nested deeper than a limit allows
+///
+/// Always an error, whatever the loop policy: there is nothing to clamp. The
+/// statement stops rather than falling back (PRINT showing the text, IF
+/// taking it as false).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TooDeeplyNested(pub String);
+
+impl TooDeeplyNested {
+    pub fn new(what: &str, depth: usize, limit: usize, setting: &str) -> Self {
+        Self(ExecutionLimits::exceeded(what, depth, limit, setting))
+    }
+}
+
+impl std::fmt::Display for TooDeeplyNested {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TooDeeplyNested {}
@@ -0,0 +1,180 @@
+//! Early stop for PILOT jump loops that can never end
+//!
+//! A program such as `*A` / `J:*A` would otherwise spin through the whole
+//! iteration budget. After every PILOT jump the run's state is hashed with
+//! [`state_hash`]: variables, arrays, the match flags, the last answer,
+//! output and drawing so far, and the subroutine stack. Once a jump lands on
+//! the same line with the same state [`SAME_STATE_REPEATS`] times in a row,
+//! nothing in the loop can change and the run stops with "Infinite loop
+//! detected".
+//!
+//! Only loops made of statements whose effect the state fully describes are
+//! judged. A BASIC or Logo statement, `A:` or `QZ:` (input), `R:` (runtime
+//! extensions), or anything reading `RND`, `TIMER`, `INKEY$`, `DATE$` or
+//! `TIME$` resets the guard, so a loop waiting on the outside world is left
+//! to the iteration and time limits.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::arrays::ArrayValues;
+use super::Interpreter;
+use crate::graphics::TurtleState;
+
+/// Returns to one line with the same state before a loop is called infinite
+pub const SAME_STATE_REPEATS: usize = 1_000;
+
+/// Words that make a statement's result depend on more than the state
+const OUTSIDE_INPUTS: &[&str] = &["RND", "TIMER", "INKEY$", "DATE$", "TIME$"];
+
+/// PILOT commands that read input or reach outside the program
+const OUTSIDE_COMMANDS: &[&str] = &["A", "AN", "QZ", "R"];
+
+/// Hash of `value`, for summing unordered entries
+fn hash_of(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of everything a PILOT statement can read or change
+///
+/// Maps are summed entry by entry, so the hash does not depend on their
+/// iteration order.
+pub fn state_hash(interp: &Interpreter, turtle: &TurtleState) -> u64 {
+    let variables = interp.variables.iter().fold(0u64, |sum, (name, value)| sum.wrapping_add(hash_of((name, value.to_bits()))));
+    let strings = interp.string_variables.iter().fold(0u64, |sum, entry| sum.wrapping_add(hash_of(entry)));
+    let arrays = interp.arrays.iter().fold(0u64, |sum, (name, array)| {
+        let values = match &array.values {
+            ArrayValues::Numbers(values) => hash_of(values.iter().map(|v| v.to_bits()).collect::<Vec<_>>()),
+            ArrayValues::Strings(values) => hash_of(values),
+        };
+        sum.wrapping_add(hash_of((name, &array.bounds, values)))
+    });
+    hash_of((
+        (variables, strings, arrays),
+        (interp.match_flag, interp.last_match_set, interp.stored_condition),
+        &interp.last_input,
+        (interp.output.len(), turtle.total_lines(), turtle.texts.len()),
+        (turtle.x.to_bits(), turtle.y.to_bits(), turtle.heading.to_bits()),
+        (&interp.gosub_stack, interp.call_stack.len()),
+    ))
+}
+
+/// Whether the guard may judge a loop through `command` (a PILOT statement)
+pub fn judged(command: &str) -> bool {
+    let command = command.trim();
+    let name = command.split_once(':').map_or("", |(name, _)| name.trim());
+    if OUTSIDE_COMMANDS.contains(&name) {
+        return false;
+    }
+    let upper = command.to_uppercase();
+    !OUTSIDE_INPUTS.iter().any(|word| upper.contains(word))
+}
+
+/// Jumps seen since the state last changed
+#[derive(Debug, Default)]
+pub struct LoopGuard {
+    state: Option<u64>,
+    /// Times each target line was jumped to with `state`
+    arrivals: HashMap<usize, usize>,
+    /// Program lines (first, last) of the jumps that came back around
+    span: Option<(usize, usize)>,
+}
+
+impl LoopGuard {
+    /// Forget the jumps so far; a statement the guard cannot judge ran
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.arrivals.clear();
+        self.span = None;
+    }
+
+    /// Note a jump from program line `from` to `to` that left the run in `state`
+    ///
+    /// Returns the program lines (first, last) of the loop once it has come
+    /// back to a line with an unchanged state `SAME_STATE_REPEATS` times.
+    pub fn jump(&mut self, from: usize, to: usize, state: u64) -> Option<(usize, usize)> {
+        if self.state != Some(state) {
+            self.reset();
+            self.state = Some(state);
+        }
+        let arrivals = self.arrivals.entry(to).or_insert(0);
+        *arrivals += 1;
+        if *arrivals < 2 {
+            return None;
+        }
+        let (first, last) = self.span.unwrap_or((to, to));
+        self.span = Some((first.min(from).min(to), last.max(from).max(to)));
+        if *arrivals > SAME_STATE_REPEATS { self.span } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_hash_sees_every_change() {
+        let mut interp = Interpreter::new();
+        let turtle = TurtleState::new();
+        interp.variables.insert("N".to_string(), 1.0);
+        interp.variables.insert("M".to_string(), 2.0);
+        let before = state_hash(&interp, &turtle);
+        assert_eq!(state_hash(&interp, &turtle), before, "same state, same hash");
+
+        // The same entries inserted in another order hash alike
+        let mut other = Interpreter::new();
+        other.variables.insert("M".to_string(), 2.0);
+        other.variables.insert("N".to_string(), 1.0);
+        assert_eq!(state_hash(&other, &turtle), before);
+
+        interp.variables.insert("N".to_string(), 1.5);
+        assert_ne!(state_hash(&interp, &turtle), before);
+        interp.variables.insert("N".to_string(), 1.0);
+        interp.match_flag = true;
+        assert_ne!(state_hash(&interp, &turtle), before);
+        interp.match_flag = false;
+        interp.output.push("hi".to_string());
+        assert_ne!(state_hash(&interp, &turtle), before);
+        interp.output.clear();
+        interp.last_input = "yes".to_string();
+        assert_ne!(state_hash(&interp, &turtle), before);
+        interp.last_input.clear();
+        assert_eq!(state_hash(&interp, &turtle), before, "back where it started");
+    }
+
+    #[test]
+    fn test_unchanged_state_stops_after_the_repeats() {
+        let mut guard = LoopGuard::default();
+        let stopped = (0..=SAME_STATE_REPEATS).find_map(|i| guard.jump(1, 0, 7).map(|span| (i, span)));
+        assert_eq!(stopped, Some((SAME_STATE_REPEATS, (0, 1))));
+
+        // Two jumps taking turns, as in *A / J:*B / *B / J:*A
+        let mut guard = LoopGuard::default();
+        let stopped = (0..=SAME_STATE_REPEATS).find_map(|_| guard.jump(1, 2, 7).or_else(|| guard.jump(3, 0, 7)));
+        assert_eq!(stopped, Some((0, 3)));
+    }
+
+    #[test]
+    fn test_a_loop_changing_a_variable_every_1000th_iteration_runs_on() {
+        let mut guard = LoopGuard::default();
+        let mut interp = Interpreter::new();
+        let turtle = TurtleState::new();
+        for i in 0..20 * SAME_STATE_REPEATS {
+            if i % 1000 == 999 {
+                *interp.variables.entry("N".to_string()).or_insert(0.0) += 1.0;
+            }
+            assert_eq!(guard.jump(2, 0, state_hash(&interp, &turtle)), None, "iteration {}", i);
+        }
+    }
+
+    #[test]
+    fn test_outside_inputs_are_not_judged() {
+        assert!(judged("J:*A") && judged("U:N=N+1") && judged("*A") && judged("T:Hello"));
+        for command in ["A:", "QZ:", "R:HTTP get", "C:X=RND()", "Y:TIMER<5", "C:K$=INKEY$"] {
+            assert!(!judged(command), "{}", command);
+        }
+    }
+}
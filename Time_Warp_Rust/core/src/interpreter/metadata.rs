@@ -0,0 +1,141 @@
+//! Program metadata headers
+//!
+//! A program can describe itself with `@key value` directives in the comment
+//! lines at its top, written in its own language's comment syntax:
+//!
+//! ```text
+//! REM @title Bouncing Ball        (BASIC, optionally after a line number)
+//! ; @author Ms. Rivera            (Logo)
+//! R:@lang pilot                   (PILOT remark)
+//! ```
+//!
+//! The header ends at the first line that is neither blank nor a comment.
+//! `@lang` overrides per-line language detection; keys other than title,
+//! author, description, and lang are kept in `extra`.
+
+use std::collections::BTreeMap;
+
+use crate::languages::Language;
+
+/// Directives from a program's comment header
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// From `@lang`; `None` when absent or not a known language
+    pub language: Option<Language>,
+    /// Any other `@key value` pairs (keys lowercased), including an unrecognised `@lang`
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Language whose comment syntax `line` uses, or `None` if it is not a comment
+///
+/// A leading BASIC line number is ignored.
+pub fn comment_language(line: &str) -> Option<Language> {
+    comment_body(line).map(|(language, _)| language)
+}
+
+/// The text after the comment marker of a comment line, or `None` if it is not a comment
+pub fn comment_text(line: &str) -> Option<&str> {
+    comment_body(line).map(|(_, text)| text)
+}
+
+/// Split a comment line into its language and the text after the comment marker
+fn comment_body(line: &str) -> Option<(Language, &str)> {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix(';') {
+        return Some((Language::Logo, rest));
+    }
+    if let Some(rest) = line.strip_prefix("R:") {
+        return Some((Language::Pilot, rest));
+    }
+    let line = line.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+    if let Some(rest) = line.strip_prefix('\'') {
+        return Some((Language::Basic, rest));
+    }
+    let keyword = line.get(..3).filter(|k| k.eq_ignore_ascii_case("REM"))?;
+    let rest = &line[keyword.len()..];
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((Language::Basic, rest))
+}
+
+/// Read the `@key value` directives from the comment header of `source`
+pub fn parse_metadata(source: &str) -> ProgramMetadata {
+    let mut metadata = ProgramMetadata::default();
+    for line in source.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((_, body)) = comment_body(line) else { break };
+        let Some(directive) = body.trim().strip_prefix('@') else { continue };
+        let (key, value) = directive.split_once(char::is_whitespace).unwrap_or((directive, ""));
+        let key = key.to_lowercase();
+        let value = value.trim().to_string();
+        match key.as_str() {
+            "title" => metadata.title = Some(value),
+            "author" => metadata.author = Some(value),
+            "description" => metadata.description = Some(value),
+            "lang" | "language" => match Language::from_name(&value) {
+                Some(language) => metadata.language = Some(language),
+                None => {
+                    metadata.extra.insert(key, value);
+                }
+            },
+            _ if !key.is_empty() => {
+                metadata.extra.insert(key, value);
+            }
+            _ => {}
+        }
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_rem_header() {
+        let source = "10 REM @title Bouncing Ball\n20 rem @Author Ms. Rivera\n30 REM plain remark\n40 PRINT \"HI\"\n50 REM @title Ignored";
+        let metadata = parse_metadata(source);
+        assert_eq!(metadata.title.as_deref(), Some("Bouncing Ball"));
+        assert_eq!(metadata.author.as_deref(), Some("Ms. Rivera"));
+        assert_eq!(metadata.language, None);
+    }
+
+    #[test]
+    fn test_logo_semicolon_header() {
+        let source = "; @title Square\n;@description Four equal sides\n\n; @grade 3\nREPEAT 4 [FD 50 RT 90]";
+        let metadata = parse_metadata(source);
+        assert_eq!(metadata.title.as_deref(), Some("Square"));
+        assert_eq!(metadata.description.as_deref(), Some("Four equal sides"));
+        assert_eq!(metadata.extra.get("grade").map(String::as_str), Some("3"));
+    }
+
+    #[test]
+    fn test_pilot_remark_header_and_lang() {
+        let metadata = parse_metadata("R:@lang pilot\nR:@title Quiz\nT:Hello");
+        assert_eq!(metadata.language, Some(Language::Pilot));
+        assert_eq!(metadata.title.as_deref(), Some("Quiz"));
+
+        let unknown = parse_metadata("; @lang klingon");
+        assert_eq!(unknown.language, None);
+        assert_eq!(unknown.extra.get("lang").map(String::as_str), Some("klingon"));
+    }
+
+    #[test]
+    fn test_no_header() {
+        let none = ProgramMetadata::default();
+        assert_eq!(parse_metadata(""), none);
+        assert_eq!(parse_metadata("T:@title not a comment\nR:@title too late"), none);
+        assert_eq!(parse_metadata("REMARKABLE @title no"), none);
+    }
+
+    #[test]
+    fn test_comment_language() {
+        assert_eq!(comment_language("  ; note"), Some(Language::Logo));
+        assert_eq!(comment_language("R:@lang logo"), Some(Language::Pilot));
+        assert_eq!(comment_language("100 REM"), Some(Language::Basic));
+        assert_eq!(comment_language("FD 10"), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! A panic in a language module becomes an error report, not a crash
+//!
+//! A bug in a statement (a slice off a character boundary, an `unwrap` on an
+//! empty stack) would otherwise unwind through the run and take the IDE, and
+//! every unsaved buffer, down with it. Each statement runs under
+//! `catch_unwind`; a panic ends the run with "Internal interpreter error —
+//! please report: …, line N" in the output and as the run's fatal error, and
+//! the interpreter is left loaded and usable for the next run. The worker
+//! thread catches anything that escapes a statement as well.
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use anyhow::Result;
+
+use super::{ExecutionResult, Interpreter};
+use crate::graphics::TurtleState;
+use crate::i18n::{tr, trf};
+
+/// Statement that panics on purpose, to test the guard
+#[cfg(any(test, feature = "testing"))]
+pub const PANIC_STATEMENT: &str = "__PANIC__";
+
+/// The text a panic was raised with
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| tr("error.unknown_panic").to_string())
+}
+
+impl Interpreter {
+    /// Run one statement; `Err` holds the message of a panic it raised
+    pub(crate) fn execute_line_caught(&mut self, command: &str, turtle: &mut TurtleState) -> std::result::Result<Result<ExecutionResult>, String> {
+        // The interpreter is reported on and left for the next run, never resumed mid-statement
+        catch_unwind(AssertUnwindSafe(|| {
+            #[cfg(any(test, feature = "testing"))]
+            if command.trim() == PANIC_STATEMENT {
+                panic!("{} reached", PANIC_STATEMENT);
+            }
+            self.execute_line(command, turtle)
+        }))
+        .map_err(|payload| panic_message(payload.as_ref()))
+    }
+
+    /// Report a panic at the current line in the output, end the run, and return its error
+    pub fn internal_error(&mut self, message: &str) -> anyhow::Error {
+        let line = self.source_line();
+        tracing::error!(line, panic = message, "interpreter panicked");
+        let report = trf("error.internal", &[&message, &line]);
+        self.mark_error_span();
+        self.log_error(trf("error.plain", &[&report]));
+        self.pending_input = None;
+        self.pending_resume_line = None;
+        self.current_line = self.program_lines.len();
+        anyhow::anyhow!(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_ends_the_run_with_a_report() {
+        let mut interp = Interpreter::new();
+        interp.load_program(&format!("T:before\n{}\nT:after", PANIC_STATEMENT)).unwrap();
+        let mut turtle = TurtleState::new();
+        let error = interp.execute(&mut turtle).unwrap_err().to_string();
+        assert!(error.starts_with("Internal interpreter error — please report: __PANIC__ reached, line 2"), "{}", error);
+        assert_eq!(interp.output[0], "before");
+        assert!(interp.output[1].contains(&error));
+        assert_eq!(interp.output.len(), 2);
+        // The interpreter still runs programs
+        interp.load_program("T:again").unwrap();
+        assert_eq!(interp.execute(&mut turtle).unwrap(), ["again"]);
+    }
+}
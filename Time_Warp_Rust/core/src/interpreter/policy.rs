@@ -0,0 +1,275 @@
+//! Security policy for classroom deployment (Settings → Restricted mode, `--restricted`)
+//!
+//! Statements that reach outside the program — files, the network, plugins —
+//! ask the interpreter's policy first and fail with a "disabled by policy"
+//! diagnostic instead of attempting the operation. The policy can only be
+//! replaced between runs. Shell commands (BASIC `SHELL`) are off in every
+//! policy, even the one that allows everything else, until the user's own
+//! settings turn them on and list the programs they may run.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::i18n::{tr, trf};
+
+/// Default wall-clock budget for one run
+pub const DEFAULT_TIME_LIMIT_SECS: u64 = 10;
+/// Default number of statements executed per run
+pub const DEFAULT_MAX_ITERATIONS: usize = 100_000;
+/// Default wall-clock budget for one shell command
+pub const DEFAULT_SHELL_TIMEOUT_SECS: u64 = 5;
+
+/// A capability a statement may need
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    FileIo,
+    Network,
+    Plugins,
+    Shell,
+}
+
+impl Permission {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Permission::FileIo => tr("policy.permission.file_io"),
+            Permission::Network => tr("policy.permission.network"),
+            Permission::Plugins => tr("policy.permission.plugins"),
+            Permission::Shell => tr("policy.permission.shell"),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PolicyError {
+    #[error("{}", trf("policy.disabled", &[statement, &permission.describe()]))]
+    Disabled { statement: String, permission: Permission },
+    #[error("{}", trf("policy.outside_workspace", &[statement, path]))]
+    OutsideWorkspace { statement: String, path: String },
+    #[error("{}", trf("policy.shell_disabled", &[statement]))]
+    ShellDisabled { statement: String },
+    #[error("{}", trf("policy.not_allowlisted", &[statement, program]))]
+    NotAllowlisted { statement: String, program: String },
+    #[error("{}", tr("policy.run_in_progress"))]
+    RunInProgress,
+}
+
+/// `path` as it lies inside `root`, or `None` when it would leave `root`
+///
+/// Relative paths count from `root`; absolute ones must start with it, and
+/// `..` is never allowed.
+pub fn path_in_workspace<'a>(root: &Path, path: &'a Path) -> Option<&'a Path> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        .then_some(relative)
+}
+
+/// What programs are allowed to do
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityPolicy {
+    pub allow_file_io: bool,
+    pub allow_network: bool,
+    pub allow_plugins: bool,
+    /// Run shell commands (BASIC `SHELL`); off unless the user's settings turn it on
+    pub allow_shell: bool,
+    /// Programs shell commands may run, by bare name (`echo`, `python`)
+    pub shell_allowlist: Vec<String>,
+    /// Overrides `DEFAULT_SHELL_TIMEOUT_SECS`
+    pub shell_timeout_secs: Option<u64>,
+    /// Folder that file statements are confined to (relative paths resolve inside it)
+    pub workspace_path: Option<PathBuf>,
+    /// Overrides `DEFAULT_TIME_LIMIT_SECS`
+    pub time_limit_secs: Option<u64>,
+    /// Overrides `DEFAULT_MAX_ITERATIONS`
+    pub max_iterations: Option<usize>,
+}
+
+impl Default for SecurityPolicy {
+    /// Everything but shell commands allowed, default limits
+    fn default() -> Self {
+        Self {
+            allow_file_io: true,
+            allow_network: true,
+            allow_plugins: true,
+            allow_shell: false,
+            shell_allowlist: Vec::new(),
+            shell_timeout_secs: None,
+            workspace_path: None,
+            time_limit_secs: None,
+            max_iterations: None,
+        }
+    }
+}
+
+impl SecurityPolicy {
+    /// Classroom lockdown: no files, network, plugins, or shell commands
+    pub fn restricted() -> Self {
+        Self {
+            allow_file_io: false,
+            allow_network: false,
+            allow_plugins: false,
+            ..Self::default()
+        }
+    }
+
+    /// This policy with files, the network, plugins, and shell commands turned off, keeping
+    /// its workspace and limits: for a program opened from outside the
+    /// workspace, until the user says it can be trusted
+    pub fn untrusted(self) -> Self {
+        Self {
+            allow_file_io: false,
+            allow_network: false,
+            allow_plugins: false,
+            allow_shell: false,
+            ..self
+        }
+    }
+
+    /// The stricter of this policy and `other`, field by field: a permission
+    /// both grant, the programs both allowlist, and the lower of each limit
+    pub fn narrowed_by(&self, other: &SecurityPolicy) -> Self {
+        fn lower<T: Ord + Copy>(a: Option<T>, b: Option<T>, default: T) -> Option<T> {
+            (a.is_some() || b.is_some()).then(|| a.unwrap_or(default).min(b.unwrap_or(default)))
+        }
+        Self {
+            allow_file_io: self.allow_file_io && other.allow_file_io,
+            allow_network: self.allow_network && other.allow_network,
+            allow_plugins: self.allow_plugins && other.allow_plugins,
+            allow_shell: self.allow_shell && other.allow_shell,
+            shell_allowlist: self.shell_allowlist.iter().filter(|name| other.shell_allowlist.contains(name)).cloned().collect(),
+            shell_timeout_secs: lower(self.shell_timeout_secs, other.shell_timeout_secs, DEFAULT_SHELL_TIMEOUT_SECS),
+            workspace_path: self.workspace_path.clone().or_else(|| other.workspace_path.clone()),
+            time_limit_secs: lower(self.time_limit_secs, other.time_limit_secs, DEFAULT_TIME_LIMIT_SECS),
+            max_iterations: lower(self.max_iterations, other.max_iterations, DEFAULT_MAX_ITERATIONS),
+        }
+    }
+
+    /// Whether anything is locked down (shown as "🔒 Restricted")
+    pub fn is_restricted(&self) -> bool {
+        !(self.allow_file_io && self.allow_network && self.allow_plugins) || self.workspace_path.is_some()
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::FileIo => self.allow_file_io,
+            Permission::Network => self.allow_network,
+            Permission::Plugins => self.allow_plugins,
+            Permission::Shell => self.allow_shell,
+        }
+    }
+
+    /// Fail with a "disabled by policy" error unless `permission` is granted
+    pub fn require(&self, permission: Permission, statement: &str) -> Result<(), PolicyError> {
+        if self.allows(permission) {
+            Ok(())
+        } else {
+            Err(PolicyError::Disabled { statement: statement.to_string(), permission })
+        }
+    }
+
+    /// Check file access and resolve `path` against the workspace
+    ///
+    /// With a workspace set, relative paths are joined to it and any path that
+    /// would leave it (absolute elsewhere, or through `..`) is refused.
+    pub fn resolve_path(&self, path: &str, statement: &str) -> Result<PathBuf, PolicyError> {
+        self.require(Permission::FileIo, statement)?;
+        let Some(root) = &self.workspace_path else { return Ok(PathBuf::from(path)) };
+        match path_in_workspace(root, Path::new(path)) {
+            Some(relative) => Ok(root.join(relative)),
+            None => Err(PolicyError::OutsideWorkspace { statement: statement.to_string(), path: path.to_string() }),
+        }
+    }
+
+    /// Check that shell commands are on and `program` is allowlisted
+    ///
+    /// `program` must be a bare name from the allowlist: a path (`./tool`,
+    /// `/bin/rm`) is refused even when its file name is listed.
+    pub fn require_shell(&self, program: &str, statement: &str) -> Result<(), PolicyError> {
+        if !self.allow_shell {
+            return Err(PolicyError::ShellDisabled { statement: statement.to_string() });
+        }
+        let bare = !program.is_empty() && !program.contains(['/', '\\']);
+        if bare && self.shell_allowlist.iter().any(|name| name == program) {
+            Ok(())
+        } else {
+            Err(PolicyError::NotAllowlisted { statement: statement.to_string(), program: program.to_string() })
+        }
+    }
+
+    pub fn shell_timeout(&self) -> Duration {
+        Duration::from_secs(self.shell_timeout_secs.unwrap_or(DEFAULT_SHELL_TIMEOUT_SECS))
+    }
+
+    pub fn time_limit(&self) -> Duration {
+        Duration::from_secs(self.time_limit_secs.unwrap_or(DEFAULT_TIME_LIMIT_SECS))
+    }
+
+    pub fn iteration_limit(&self) -> usize {
+        self.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_denies_each_permission() {
+        let open = SecurityPolicy::default();
+        let locked = SecurityPolicy::restricted();
+        assert!(!open.is_restricted());
+        assert!(locked.is_restricted());
+        for permission in [Permission::FileIo, Permission::Network, Permission::Plugins] {
+            assert!(open.require(permission, "X").is_ok());
+            let err = locked.require(permission, "X").unwrap_err();
+            assert!(err.to_string().contains("disabled by policy"), "{}", err);
+        }
+        let limited = SecurityPolicy { time_limit_secs: Some(30), ..SecurityPolicy::default() }.untrusted();
+        assert_eq!(limited, SecurityPolicy { time_limit_secs: Some(30), ..SecurityPolicy::restricted() });
+    }
+
+    #[test]
+    fn test_shell_fails_closed() {
+        for policy in [SecurityPolicy::default(), SecurityPolicy::restricted()] {
+            assert_eq!(policy.require_shell("echo", "SHELL"), Err(PolicyError::ShellDisabled { statement: "SHELL".into() }));
+        }
+        let on = SecurityPolicy { allow_shell: true, shell_allowlist: vec!["echo".into()], ..SecurityPolicy::default() };
+        assert!(on.require_shell("echo", "SHELL").is_ok());
+        for program in ["rm", "/bin/echo", "./echo", "ECHO", ""] {
+            assert!(matches!(on.require_shell(program, "SHELL"), Err(PolicyError::NotAllowlisted { .. })), "{}", program);
+        }
+        assert!(!on.untrusted().allows(Permission::Shell));
+    }
+
+    #[test]
+    fn test_narrowing_keeps_the_stricter_of_each_field() {
+        let global = SecurityPolicy { allow_shell: true, shell_allowlist: vec!["echo".into(), "python".into()], time_limit_secs: Some(30), ..SecurityPolicy::default() };
+        let project = SecurityPolicy {
+            allow_file_io: true,
+            allow_network: false,
+            allow_shell: true,
+            shell_allowlist: vec!["python".into(), "sh".into()],
+            time_limit_secs: Some(600),
+            max_iterations: Some(500),
+            ..SecurityPolicy::restricted()
+        };
+        let narrowed = global.narrowed_by(&project);
+        assert!(narrowed.allow_file_io && !narrowed.allow_network && !narrowed.allow_plugins && narrowed.allow_shell);
+        assert_eq!(narrowed.shell_allowlist, ["python"]);
+        assert_eq!((narrowed.time_limit_secs, narrowed.max_iterations, narrowed.shell_timeout_secs), (Some(30), Some(500), None));
+        assert_eq!(SecurityPolicy::restricted().narrowed_by(&SecurityPolicy::default()), SecurityPolicy::restricted());
+    }
+
+    #[test]
+    fn test_workspace_confines_paths() {
+        let policy = SecurityPolicy { workspace_path: Some(PathBuf::from("/class/work")), ..SecurityPolicy::default() };
+        assert_eq!(policy.resolve_path("scores.txt", "R:SAVE").unwrap(), PathBuf::from("/class/work/scores.txt"));
+        assert_eq!(policy.resolve_path("/class/work/a/b.txt", "R:SAVE").unwrap(), PathBuf::from("/class/work/a/b.txt"));
+        assert!(matches!(policy.resolve_path("../secret.txt", "R:LOAD"), Err(PolicyError::OutsideWorkspace { .. })));
+        assert!(matches!(policy.resolve_path("/etc/passwd", "R:LOAD"), Err(PolicyError::OutsideWorkspace { .. })));
+    }
+}
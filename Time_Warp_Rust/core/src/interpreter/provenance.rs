@@ -0,0 +1,66 @@
+//! Variable provenance: the source line that last assigned each variable
+//!
+//! "Where did this get set?" is the first question when a value is wrong.
+//! Every assignment — `LET`, `INPUT`, `FOR` and `NEXT`, `READ`, PILOT `A:`
+//! and `U:`, an answer given through `provide_input` — stores the statement's
+//! first source line under the variable's name, overwriting the line before.
+//! The Variables panel shows it beside the value, and the editor's hover at a
+//! breakpoint names it. A line inside a CALLFILE subprogram belongs to another
+//! file, so an assignment there leaves the variable without one. A new run
+//! starts with none, even for the variables it keeps.
+
+use super::Interpreter;
+
+impl Interpreter {
+    /// Remember that the running statement assigned `name`
+    pub(crate) fn note_assignment(&mut self, name: &str) {
+        let name = name.trim();
+        if !self.call_stack.is_empty() {
+            self.assigned_at.remove(name);
+            return;
+        }
+        let line = self.source_line();
+        match self.assigned_at.get_mut(name) {
+            Some(at) => *at = line,
+            None => {
+                self.assigned_at.insert(name.to_string(), line);
+            }
+        }
+    }
+
+    /// 1-based source line that last assigned `name` this run
+    pub fn assigned_line(&self, name: &str) -> Option<usize> {
+        self.assigned_at.get(name).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graphics::TurtleState;
+    use crate::interpreter::Interpreter;
+
+    #[test]
+    fn test_the_last_assignment_wins() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.input_callback = Some(Box::new(|_| "7".to_string()));
+        let program = "10 LET X = 1\n20 PRINT X\n30 INPUT X\n40 FOR I = 1 TO 2\n50 NEXT I\n60 LET X = X + 1\n70 LET N$ = \"Ada\"";
+        interp.load_program(program).unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(interp.variables["X"], 8.0);
+        assert_eq!(interp.assigned_line("X"), Some(6));
+        assert_eq!(interp.assigned_line("I"), Some(5));
+        assert_eq!(interp.assigned_line("N$"), Some(7));
+        assert_eq!(interp.assigned_line("Y"), None);
+
+        // PILOT, with the answer typed in the UI
+        interp.input_callback = None;
+        interp.load_program("U:SCORE=1\nA:SCORE\nT:*SCORE*").unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(interp.assigned_line("SCORE"), Some(1));
+        interp.provide_input("12");
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!((interp.variables["SCORE"], interp.assigned_line("SCORE")), (12.0, Some(2)));
+        assert_eq!(interp.assigned_line("X"), None, "a new run starts without lines");
+    }
+}
@@ -0,0 +1,99 @@
+//! One random number generator per run
+//!
+//! `RND`, `RANDOMIZE` and `QZ:SHUFFLE` all draw from the interpreter's
+//! [`RunRandom`], which is seeded when a program is loaded: from the seed
+//! fixed with `--seed` or Settings → Programs, or else a fresh one. The seed
+//! is reported with the run, so running again with it repeats every random
+//! choice.
+
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use std::cell::RefCell;
+
+use crate::utils::expr_eval::RandomSource;
+
+/// The run's seed and the generator started from it
+#[derive(Debug, Clone)]
+pub struct RunRandom {
+    seed: u64,
+    rng: StdRng,
+    used: bool,
+}
+
+/// A fresh seed
+impl Default for RunRandom {
+    fn default() -> Self {
+        Self::seeded(rand::random())
+    }
+}
+
+impl RunRandom {
+    pub fn seeded(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed), used: false }
+    }
+
+    /// Seed the run started from, the one to run with again to repeat it
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Whether the run has drawn a random number yet
+    pub fn used(&self) -> bool {
+        self.used
+    }
+
+    /// Restart the numbers from `seed` (`RANDOMIZE n`, `QZ:SHUFFLE n`); the run keeps its own seed
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+impl RngCore for RunRandom {
+    fn next_u32(&mut self) -> u32 {
+        self.used = true;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.used = true;
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.used = true;
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.used = true;
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// `RND` in the interpreter's expressions
+impl RandomSource for RefCell<RunRandom> {
+    fn random(&self) -> f64 {
+        self.borrow_mut().gen()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let draw = |random: &mut RunRandom| (0..5).map(|_| random.gen::<u32>()).collect::<Vec<_>>();
+        let mut first = RunRandom::seeded(42);
+        assert!(!first.used());
+        let numbers = draw(&mut first);
+        assert!(first.used());
+        assert_eq!(numbers, draw(&mut RunRandom::seeded(42)));
+        assert_ne!(numbers, draw(&mut RunRandom::seeded(43)));
+
+        // Reseeding restarts the numbers but the run is still known by its first seed
+        first.reseed(42);
+        assert_eq!(draw(&mut first), numbers);
+        assert_eq!(first.seed(), 42);
+    }
+}
@@ -0,0 +1,115 @@
+//! Reporters: follow a run from outside, e.g. to log it or send it to a teacher dashboard
+//!
+//! Whoever starts a run hands the interpreter its reporters with
+//! [`Interpreter::start_report`]; they then hear every output line and
+//! diagnostic as it happens, on whatever thread the run is on, and the
+//! finished run's [`RunReport`] when [`Interpreter::finish_report`] is
+//! called. A run that pauses for input is still one run: it is reported as
+//! finished once it ends, or when it is abandoned at a prompt. Reporters must
+//! not block: anything slow (a network request) belongs on a thread of its
+//! own, and a reporter that fails says so in the log, never to the program.
+
+use super::isolated::RunReport;
+use super::warnings::Diagnostic;
+use super::Interpreter;
+use crate::graphics::TurtleState;
+
+/// Told about a run as it happens; every method does nothing unless overridden
+pub trait Reporter: Send {
+    /// A run of `program` (a file name, or a tab's name) is starting
+    fn on_run_start(&mut self, _program: &str) {}
+
+    /// The program wrote `line` (errors and warnings included)
+    fn on_output(&mut self, _line: &str) {}
+
+    /// The program raised a warning, or strict mode made one an error
+    fn on_diagnostic(&mut self, _diagnostic: &Diagnostic) {}
+
+    /// The run ended; `report.error` holds a fatal error
+    fn on_run_end(&mut self, _report: &RunReport) {}
+}
+
+impl Interpreter {
+    /// Start reporting the run about to start to `reporters`, replacing any from an earlier run
+    pub fn start_report(&mut self, program: &str, reporters: Vec<Box<dyn Reporter>>) {
+        self.reporters = reporters;
+        for reporter in &mut self.reporters {
+            reporter.on_run_start(program);
+        }
+    }
+
+    /// Report the run as finished, with its fatal error if it had one, and let go of the reporters
+    pub fn finish_report(&mut self, turtle: &TurtleState, error: Option<String>) {
+        if self.reporters.is_empty() {
+            return;
+        }
+        let report = RunReport::capture(self, turtle, error);
+        for mut reporter in std::mem::take(&mut self.reporters) {
+            reporter.on_run_end(&report);
+        }
+    }
+
+    /// Whether a run is being reported
+    pub fn is_reporting(&self) -> bool {
+        !self.reporters.is_empty()
+    }
+
+    pub(crate) fn report_output(&mut self, line: &str) {
+        for reporter in &mut self.reporters {
+            reporter.on_output(line);
+        }
+    }
+
+    pub(crate) fn report_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        for reporter in &mut self.reporters {
+            reporter.on_diagnostic(diagnostic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Writes what it hears to a shared list
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Reporter for Recorder {
+        fn on_run_start(&mut self, program: &str) {
+            self.0.lock().unwrap().push(format!("start {}", program));
+        }
+        fn on_output(&mut self, line: &str) {
+            self.0.lock().unwrap().push(format!("output {}", line));
+        }
+        fn on_diagnostic(&mut self, diagnostic: &Diagnostic) {
+            self.0.lock().unwrap().push(format!("diagnostic {} {}", diagnostic.line, diagnostic.message));
+        }
+        fn on_run_end(&mut self, report: &RunReport) {
+            self.0.lock().unwrap().push(format!("end {} lines, X={:?}", report.output.len(), report.variables.get("X")));
+        }
+    }
+
+    #[test]
+    fn test_a_run_is_reported_in_order_and_once() {
+        let heard = Arc::new(Mutex::new(Vec::new()));
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program("10 LET X = 5\n20 PRINT X + Y\n30 INPUT A\n40 PRINT \"done\"").unwrap();
+        interp.start_report("sum.bas", vec![Box::new(Recorder(heard.clone()))]);
+        interp.execute(&mut turtle).unwrap();
+        interp.provide_input("1");
+        interp.execute(&mut turtle).unwrap();
+        interp.finish_report(&turtle, None);
+        // Nothing is left to report to
+        interp.finish_report(&turtle, None);
+        interp.log_output("after".into());
+
+        let heard = heard.lock().unwrap();
+        assert_eq!(heard[..2], ["start sum.bas", "output 5"]);
+        assert!(heard[2].starts_with("output ⚠️ Warning at line 2"), "{:?}", heard);
+        assert_eq!(heard[3], "diagnostic 2 Y is used before it is set, so it reads as 0");
+        assert_eq!(heard[4..], ["output done", "end 3 lines, X=Some(5.0)"]);
+        assert!(!interp.is_reporting());
+    }
+}
@@ -0,0 +1,84 @@
+//! Where the parts of an output line came from
+//!
+//! `T:` interpolation and `PRINT` build a line from [`Segment`]s: text as
+//! the program wrote it, and the values of variables put in. The interpreter
+//! keeps the segments of each line that shows a variable
+//! ([`Interpreter::output_segments`](super::Interpreter::output_segments)),
+//! so the Output tab can highlight them; `output` itself stays plain text.
+
+/// One part of an output line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// Text as written in the program
+    Text(String),
+    /// The value of variable `name` (or an array element, such as `N$(2)`)
+    Variable { name: String, value: String },
+}
+
+impl Segment {
+    /// The text this part shows
+    pub fn text(&self) -> &str {
+        match self {
+            Segment::Text(text) => text,
+            Segment::Variable { value, .. } => value,
+        }
+    }
+}
+
+/// The line the segments make up
+pub fn join(segments: &[Segment]) -> String {
+    segments.iter().map(Segment::text).collect()
+}
+
+/// Whether any part came from a variable
+pub fn shows_variables(segments: &[Segment]) -> bool {
+    segments.iter().any(|s| matches!(s, Segment::Variable { .. }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::interpreter::Interpreter;
+
+    fn variable(name: &str, value: &str) -> Segment {
+        Segment::Variable { name: name.to_string(), value: value.to_string() }
+    }
+
+    fn text(text: &str) -> Segment {
+        Segment::Text(text.to_string())
+    }
+
+    #[test]
+    fn test_interpolated_line_with_two_variables() {
+        let mut interp = Interpreter::new();
+        interp.string_variables.insert("NAME".to_string(), "Ada".to_string());
+        interp.variables.insert("AGE".to_string(), 36.0);
+        let segments = interp.interpolate_segments("Hi *NAME*, you are *AGE*! *UNSET* stays");
+        assert_eq!(segments, vec![
+            text("Hi "),
+            variable("NAME", "Ada"),
+            text(", you are "),
+            variable("AGE", "36"),
+            text("! *UNSET* stays"),
+        ]);
+        assert_eq!(join(&segments), "Hi Ada, you are 36! *UNSET* stays");
+        assert_eq!(interp.interpolate_text("Hi *NAME*, you are *AGE*! *UNSET* stays"), join(&segments));
+        assert_eq!(interp.interpolate_segments("no stars"), vec![text("no stars")]);
+    }
+
+    #[test]
+    fn test_output_keeps_segments_of_lines_with_variables() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program("U:N=3\nT:Plain\nT:N is *N*\n10 LET A$ = \"x\"\n20 PRINT \"A$ =\", A$, N").unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        assert_eq!(output, vec!["Plain", "N is 3", "A$ = x 3"]);
+        assert_eq!(interp.output_segments(0), None);
+        assert_eq!(interp.output_segments(1), Some(&[text("N is "), variable("N", "3")][..]));
+        assert_eq!(
+            interp.output_segments(2),
+            Some(&[text("A$ ="), text(" "), variable("A$", "x"), text(" "), variable("N", "3")][..])
+        );
+    }
+}
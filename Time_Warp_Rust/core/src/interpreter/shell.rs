@@ -0,0 +1,145 @@
+//! BASIC `SHELL "command"`: run an allowlisted program for interop lessons
+//!
+//! Everything fails closed. The policy must have shell commands on and list
+//! the program by bare name ([`SecurityPolicy::require_shell`]); Restricted
+//! mode and untrusted files never have them. The command line is split into
+//! words like a shell would split plain words and `"quoted strings"`, but no
+//! shell runs it, so pipes, redirections, and variables are just text. The
+//! program runs in the policy's workspace (else the folder Time Warp runs
+//! in) with no input, and is killed after the policy's shell timeout. Its
+//! output, then its error output, is appended to the program's output, and
+//! `SHELLRESULT` holds the exit code (-1 when it was killed or had none).
+//! Anything it left running in the background may hold its output open; that
+//! output is read for a quarter of a second after the program ends and then left
+//! behind, so the run never waits on it.
+//!
+//! [`SecurityPolicy::require_shell`]: super::SecurityPolicy::require_shell
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use super::Interpreter;
+use crate::i18n::trf;
+
+/// Variable holding the exit code of the last `SHELL`
+pub const RESULT_VAR: &str = "SHELLRESULT";
+
+/// How often a running command is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long output is still read once the command has ended or been killed
+const PIPE_GRACE: Duration = Duration::from_millis(250);
+
+/// `command` split at spaces, with `"…"` kept together (quotes removed)
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// One of a child's pipes, read on its own thread so a full pipe never stalls the child
+struct Drain {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    /// Sends once the pipe is closed
+    closed: mpsc::Receiver<()>,
+}
+
+impl Drain {
+    fn new(pipe: Option<impl Read + Send + 'static>) -> Self {
+        let bytes = Arc::new(Mutex::new(Vec::new()));
+        let (done, closed) = mpsc::channel();
+        let shared = Arc::clone(&bytes);
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            if let Some(mut pipe) = pipe {
+                while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                    shared.lock().unwrap_or_else(PoisonError::into_inner).extend_from_slice(&chunk[..n]);
+                }
+            }
+            let _ = done.send(());
+        });
+        Self { bytes, closed }
+    }
+
+    /// What was read by the time the pipe closed, or by `deadline` if something still holds it open
+    fn finish(self, deadline: Instant) -> String {
+        let _ = self.closed.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let bytes = self.bytes.lock().unwrap_or_else(PoisonError::into_inner);
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl Interpreter {
+    /// Run `command` under the policy's shell rules (BASIC `SHELL`)
+    pub fn run_shell(&mut self, command: &str) -> Result<()> {
+        let words = split_command(command);
+        let program = words.first().map(String::as_str).unwrap_or_default();
+        self.policy.require_shell(program, "SHELL")?;
+        let cwd = match &self.policy.workspace_path {
+            Some(root) => root.clone(),
+            None => std::env::current_dir()?,
+        };
+        let mut child = Command::new(program)
+            .args(&words[1..])
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!(trf("shell.start_failed", &[&program, &e])))?;
+        let (stdout, stderr) = (Drain::new(child.stdout.take()), Drain::new(child.stderr.take()));
+        let timeout = self.policy.shell_timeout();
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break Some(status);
+            }
+            if started.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+        let deadline = Instant::now() + PIPE_GRACE;
+        for pipe in [stdout, stderr] {
+            for line in pipe.finish(deadline).lines() {
+                self.log_output(line.to_string());
+            }
+        }
+        let code = status.and_then(|s| s.code()).unwrap_or(-1);
+        self.variables.insert(RESULT_VAR.to_string(), code as f64);
+        if status.is_none() {
+            return Err(anyhow!(trf("shell.timed_out", &[&program, &timeout.as_secs()])));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("python  analyze.py \"my data.csv\""), ["python", "analyze.py", "my data.csv"]);
+        assert_eq!(split_command("echo \"\" | rm"), ["echo", "", "|", "rm"]);
+        assert!(split_command("   ").is_empty());
+    }
+}
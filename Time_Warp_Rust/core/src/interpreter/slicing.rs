@@ -0,0 +1,212 @@
+//! Time slices: a run on the worker is sent to the IDE a few milliseconds at a time
+//!
+//! The worker cuts a run into slices of about [`SLICE_TIME`], and at the end
+//! of each it sends what was drawn and printed, so the canvas and the Output
+//! tab fill in at a steady pace whatever the program does. Reading the clock
+//! after every statement would cost more than a cheap `LET`, so the slicer
+//! guesses from the last slice how many statements fit in the next and reads
+//! the clock only a few times on the way: a loop of `LET`s gets long slices,
+//! a loop of `CIRCLE`s short ones. A slice runs at least
+//! [`MIN_STATEMENTS`] and at most [`MAX_STATEMENTS`] statements, and
+//! Settings → Programs can fix its length instead. The time comes from the
+//! interpreter's [`Clock`](super::clock::Clock), so a fake clock makes the
+//! slices repeatable. The run's pace, statements per second, is shown in the
+//! status bar.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use super::Interpreter;
+use crate::graphics::TurtleState;
+
+/// Time a slice aims to take
+pub const SLICE_TIME: Duration = Duration::from_millis(4);
+
+/// Fewest statements a slice runs, however slow they are
+pub const MIN_STATEMENTS: usize = 8;
+
+/// Most statements a slice runs, however fast they are
+pub const MAX_STATEMENTS: usize = 100_000;
+
+/// How long slices are
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliceBudget {
+    pub time: Duration,
+    pub min_statements: usize,
+    pub max_statements: usize,
+    /// Statements in every slice, whatever they take (Settings → Programs)
+    pub fixed: Option<usize>,
+}
+
+impl Default for SliceBudget {
+    fn default() -> Self {
+        Self { time: SLICE_TIME, min_statements: MIN_STATEMENTS, max_statements: MAX_STATEMENTS, fixed: None }
+    }
+}
+
+/// Where the current slice is, and the pace so far
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Slicer {
+    /// Statements the slice runs before the clock is read again
+    check_at: usize,
+    /// Statements run in the slice
+    run: usize,
+    /// Clock reading when the slice started
+    started: Duration,
+    /// Clock reading when this stretch of the run started
+    run_started: Duration,
+    /// Statements run this stretch
+    statements: u64,
+    /// Clock reading at the last slice's end
+    measured: Duration,
+    /// Slices ended this stretch
+    slices: usize,
+}
+
+impl Slicer {
+    /// Start slicing a run (or its next stretch, after an INPUT) at clock reading `now`
+    pub(crate) fn start(&mut self, now: Duration, budget: &SliceBudget) {
+        *self = Self { check_at: budget.fixed.unwrap_or(budget.min_statements).max(1), started: now, run_started: now, measured: now, ..Self::default() };
+    }
+
+    /// Count a statement about to run; true when the slice ended before it
+    ///
+    /// `now` reads the clock, and is only called when the slice may be over.
+    pub(crate) fn next_statement(&mut self, now: impl FnOnce() -> Duration, budget: &SliceBudget) -> bool {
+        let ended = self.run >= self.check_at && self.check(now(), budget);
+        self.run += 1;
+        self.statements += 1;
+        ended
+    }
+
+    /// Whether the slice has run long enough; otherwise when to look again
+    ///
+    /// Looking again halfway to the estimate, and a quarter of the way into
+    /// the next slice, keeps a turn to slower statements from running on for
+    /// more than a slice or two.
+    fn check(&mut self, now: Duration, budget: &SliceBudget) -> bool {
+        let spent = now.saturating_sub(self.started);
+        let fits = fitting(self.run, spent, budget.time);
+        if let Some(fixed) = budget.fixed {
+            self.check_at = fixed.max(1);
+        } else if spent < budget.time && self.run < budget.max_statements {
+            self.check_at = (self.run + (fits.saturating_sub(self.run) / 2).max(1)).min(budget.max_statements);
+            return false;
+        } else {
+            self.check_at = (fits / 4).clamp(budget.min_statements.max(1), budget.max_statements);
+        }
+        self.run = 0;
+        self.started = now;
+        self.measured = now;
+        self.slices += 1;
+        true
+    }
+
+    /// Statements per second over the slices ended so far; `None` before the clock moved
+    pub(crate) fn pace(&self) -> Option<f64> {
+        let spent = self.measured.saturating_sub(self.run_started).as_secs_f64();
+        (spent > 0.0).then(|| (self.statements - self.run as u64) as f64 / spent)
+    }
+}
+
+/// Statements that fit in `time`, when `run` took `spent`
+fn fitting(run: usize, spent: Duration, time: Duration) -> usize {
+    if spent.is_zero() {
+        // The clock has not moved yet: try twice as many
+        return run.saturating_mul(2);
+    }
+    (run as f64 * time.as_secs_f64() / spent.as_secs_f64()) as usize
+}
+
+impl Interpreter {
+    /// Start slicing the run that is starting or resuming
+    pub(crate) fn start_slices(&mut self) {
+        let now = self.clock.elapsed();
+        self.slicer.start(now, &self.slice_budget);
+    }
+
+    /// Count the statement about to run; at a slice's end, send the drawing, output, and pace to the IDE
+    pub(crate) fn next_slice_statement(&mut self, turtle: &TurtleState) {
+        let clock = &self.clock;
+        if !self.slicer.next_statement(|| clock.elapsed(), &self.slice_budget) {
+            return;
+        }
+        let pace = self.slicer.pace();
+        if let Some(link) = self.worker_link.as_mut() {
+            link.publish(turtle, true);
+            link.publish_output(&self.output);
+            if let Some(pace) = pace {
+                link.pace.store(pace.to_bits(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Statements per second in the last stretch of the run, measured at slice ends
+    pub fn statements_per_second(&self) -> Option<f64> {
+        self.slicer.pace()
+    }
+
+    /// Slices the last stretch of the run was cut into
+    pub fn slice_count(&self) -> usize {
+        self.slicer.slices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// Statements per slice when each statement takes `cost`
+    fn slices(budget: &SliceBudget, cost: impl Fn(usize) -> Duration, statements: usize) -> Vec<usize> {
+        let mut slicer = Slicer::default();
+        let mut now = Duration::ZERO;
+        slicer.start(now, budget);
+        let (mut lengths, mut run) = (Vec::new(), 0);
+        for i in 0..statements {
+            if slicer.next_statement(|| now, budget) {
+                lengths.push(run);
+                run = 0;
+            }
+            run += 1;
+            now += cost(i);
+        }
+        lengths
+    }
+
+    #[test]
+    fn test_slices_adapt_to_the_statements() {
+        let budget = SliceBudget { min_statements: 1, ..SliceBudget::default() };
+        // A millisecond a statement: four to a slice once the first has been measured
+        assert_eq!(slices(&budget, |_| MS, 20), [4, 4, 4, 4]);
+        // Slow statements after fast ones shorten the slices
+        let lengths = slices(&budget, |i| if i < 400 { MS / 100 } else { MS * 2 }, 600);
+        assert_eq!(lengths[0], 400);
+        assert!(lengths[1] < 200 && lengths.ends_with(&[2, 2, 2]), "{:?}", lengths);
+        // A slow statement still ends its slice when the floor allows it
+        assert_eq!(slices(&SliceBudget { min_statements: 3, ..budget }, |_| MS * 10, 10), [3, 3, 3]);
+    }
+
+    #[test]
+    fn test_ceiling_and_fixed_length() {
+        let budget = SliceBudget { max_statements: 50, ..SliceBudget::default() };
+        // The clock never moves: slices stop at the ceiling
+        assert!(slices(&budget, |_| Duration::ZERO, 200).iter().all(|&n| n == 50));
+        let fixed = SliceBudget { fixed: Some(7), ..budget };
+        assert_eq!(slices(&fixed, |_| MS, 22), [7, 7, 7]);
+    }
+
+    #[test]
+    fn test_pace_counts_ended_slices() {
+        let mut slicer = Slicer::default();
+        let budget = SliceBudget { min_statements: 1, ..SliceBudget::default() };
+        slicer.start(Duration::ZERO, &budget);
+        assert_eq!(slicer.pace(), None);
+        for i in 0..10 {
+            slicer.next_statement(|| MS * i, &budget);
+        }
+        // Eight statements in the two slices that ended, 8 ms in all
+        assert_eq!((slicer.slices, slicer.pace()), (2, Some(1000.0)));
+    }
+}
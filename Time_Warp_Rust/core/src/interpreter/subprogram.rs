@@ -0,0 +1,325 @@
+//! Running other program files: `CALLFILE "shapes.logo"`, BASIC `CHAIN "menu.bas"`,
+//! and BASIC `MERGE "lib.bas"`
+//!
+//! CALLFILE works from any language. The callee gets its own program lines,
+//! labels, line numbers, and GOSUB/FOR stacks, but shares variables, Logo
+//! procedures, the turtle, and output with its caller. When it ends (or runs
+//! `END`), execution resumes after the CALLFILE line. Calls nest up to
+//! `MAX_CALL_DEPTH` files, so a file that calls itself, directly or through
+//! others, stops with an error instead of looping forever.
+//!
+//! CHAIN replaces the running program. Variables named by `COMMON` (or all of
+//! them with `CHAIN "file", ALL`) carry over; output and the screen are kept.
+//! The COMMON variables also outlive loading the next program in the same
+//! session (all of them with `keep_variables` set), until a `CLEAR`.
+//!
+//! MERGE adds another file's numbered lines to the running program, replacing
+//! lines with the same number, as classic BASIC did for shared subroutine
+//! libraries. The program then carries on after the MERGE line.
+//!
+//! Paths go through the security policy: file access must be allowed, and
+//! with a workspace set, relative names resolve inside it. Without one they
+//! resolve in the folder of the file doing the calling (`program_dir`), so
+//! a program finds its neighbours wherever the IDE was started.
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::arrays::BasicArray;
+use super::metadata::ProgramMetadata;
+use super::var_types::VarType;
+use super::{logical_lines, ExecutionLimits, ExecutionResult, ForContext, Interpreter};
+use crate::i18n::{tr, trf};
+use crate::utils::error::BasicError;
+use crate::utils::line_endings;
+
+/// Deepest CALLFILE nesting, counting repeated calls to the same file
+pub const MAX_CALL_DEPTH: usize = 8;
+
+/// Variables carried into the next program, by CHAIN or by loading it
+#[derive(Default)]
+pub(crate) struct KeptVariables {
+    numbers: HashMap<String, f64>,
+    strings: HashMap<String, String>,
+    arrays: HashMap<String, BasicArray>,
+    types: HashMap<String, VarType>,
+}
+
+/// A caller's program, saved while a CALLFILE subprogram runs
+pub(crate) struct CallFrame {
+    /// The file being called (shown when the nesting limit is hit)
+    callee: String,
+    program_lines: Vec<(Option<usize>, String)>,
+    source_lines: Vec<std::ops::RangeInclusive<usize>>,
+    current_line: usize,
+    labels: HashMap<String, usize>,
+    line_number_map: HashMap<usize, usize>,
+    procedure_ends: HashSet<usize>,
+    gosub_stack: Vec<usize>,
+    for_stack: Vec<ForContext>,
+    for_loops: Vec<(usize, usize)>,
+    do_stack: Vec<usize>,
+    do_loops: Vec<(usize, usize)>,
+    metadata: ProgramMetadata,
+    program_dir: Option<PathBuf>,
+}
+
+/// File name from a statement argument: `"name"` or a bare name
+pub fn file_argument(args: &str) -> &str {
+    let args = args.trim();
+    args.strip_prefix('"')
+        .map(|rest| rest.split('"').next().unwrap_or(rest))
+        .unwrap_or(args)
+}
+
+impl Interpreter {
+    /// Text of program file `path`, normalized as [`Interpreter::load_program`] does, and the folder it is in
+    fn read_program_file(&self, path: &str, statement: &str) -> Result<(String, Option<PathBuf>)> {
+        let mut resolved: PathBuf = self.policy.resolve_path(path, statement)?;
+        if let (None, Some(dir)) = (&self.policy.workspace_path, &self.program_dir) {
+            resolved = dir.join(resolved);
+        }
+        let text = std::fs::read_to_string(&resolved)
+            .map(|text| line_endings::normalize_source(&text).into_owned())
+            .map_err(|e| anyhow::Error::new(BasicError::FileNotFound).context(format!("{} \"{}\": {}", statement, path, e)))?;
+        Ok((text, resolved.parent().filter(|dir| !dir.as_os_str().is_empty()).map(Path::to_path_buf)))
+    }
+
+    /// Name the file about to run, so its relative CALLFILE/CHAIN/MERGE names resolve beside it
+    pub fn set_program_file(&mut self, file: &Path) {
+        self.program_dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).map(Path::to_path_buf);
+    }
+
+    /// Start running `path` as a subprogram; the caller resumes after the current line
+    pub fn call_file(&mut self, path: &str) -> Result<ExecutionResult> {
+        if path.is_empty() {
+            return Err(anyhow::anyhow!(tr("basic.callfile_missing_name")));
+        }
+        if self.call_stack.len() >= MAX_CALL_DEPTH {
+            let chain: Vec<&str> = self.call_stack.iter().map(|f| f.callee.as_str()).collect();
+            return Err(anyhow::anyhow!(trf(
+                "basic.callfile_too_deep",
+                &[&path, &MAX_CALL_DEPTH, &chain.join(" → ")]
+            )));
+        }
+        let (source, dir) = self.read_program_file(path, "CALLFILE")?;
+
+        let frame = CallFrame {
+            callee: path.to_string(),
+            program_lines: std::mem::take(&mut self.program_lines),
+            source_lines: std::mem::take(&mut self.source_lines),
+            current_line: self.current_line,
+            labels: std::mem::take(&mut self.labels),
+            line_number_map: std::mem::take(&mut self.line_number_map),
+            procedure_ends: std::mem::take(&mut self.procedure_ends),
+            gosub_stack: std::mem::take(&mut self.gosub_stack),
+            for_stack: std::mem::take(&mut self.for_stack),
+            for_loops: std::mem::take(&mut self.for_loops),
+            do_stack: std::mem::take(&mut self.do_stack),
+            do_loops: std::mem::take(&mut self.do_loops),
+            metadata: std::mem::take(&mut self.metadata),
+            program_dir: std::mem::replace(&mut self.program_dir, dir),
+        };
+        self.call_stack.push(frame);
+        if let Err(e) = self.parse_program(&source) {
+            let frame = self.call_stack.pop().expect("frame was just pushed");
+            self.restore_frame(frame);
+            return Err(e.context(format!("CALLFILE \"{}\"", path)));
+        }
+        Ok(ExecutionResult::Jump(0))
+    }
+
+    /// Restore the innermost caller after its subprogram ends; false at top level
+    pub(crate) fn return_from_call(&mut self) -> bool {
+        let Some(frame) = self.call_stack.pop() else { return false };
+        self.restore_frame(frame);
+        self.current_line += 1;
+        true
+    }
+
+    fn restore_frame(&mut self, frame: CallFrame) {
+        self.program_lines = frame.program_lines;
+        self.source_lines = frame.source_lines;
+        self.current_line = frame.current_line;
+        self.labels = frame.labels;
+        self.line_number_map = frame.line_number_map;
+        self.procedure_ends = frame.procedure_ends;
+        self.gosub_stack = frame.gosub_stack;
+        self.for_stack = frame.for_stack;
+        self.for_loops = frame.for_loops;
+        self.do_stack = frame.do_stack;
+        self.do_loops = frame.do_loops;
+        self.metadata = frame.metadata;
+        self.program_dir = frame.program_dir;
+    }
+
+    /// Replace the running program with `path` (BASIC CHAIN)
+    ///
+    /// Only the `COMMON` variables survive unless `keep_all` is set, which
+    /// keeps arrays too. A CHAIN inside a CALLFILE subprogram replaces just
+    /// that subprogram.
+    pub fn chain_file(&mut self, path: &str, keep_all: bool) -> Result<ExecutionResult> {
+        if path.is_empty() {
+            return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.chain_missing_name")));
+        }
+        let (source, dir) = self.read_program_file(path, "CHAIN")?;
+
+        let kept = self.take_kept_variables(keep_all);
+        let output = std::mem::take(&mut self.output);
+        let text_lines = std::mem::take(&mut self.text_lines);
+        let output_limit_reached = self.output_limit_reached;
+        let call_stack = std::mem::take(&mut self.call_stack);
+        // The chained program goes on with the same random numbers, so the run's seed still repeats it
+        let random = std::mem::take(self.rng.get_mut());
+
+        let loaded = self.load_program(&source);
+        *self.rng.get_mut() = random;
+        self.output = output;
+        self.text_lines = text_lines;
+        self.output_limit_reached = output_limit_reached;
+        self.call_stack = call_stack;
+        loaded.with_context(|| format!("CHAIN \"{}\"", path))?;
+        self.program_dir = dir;
+        self.restore_kept_variables(kept);
+        Ok(ExecutionResult::Jump(0))
+    }
+
+    /// Take out the variables a new program keeps: every one when `all` or
+    /// `keep_variables` is set (arrays too), else the `COMMON` ones
+    pub(crate) fn take_kept_variables(&mut self, all: bool) -> KeptVariables {
+        if all || self.keep_variables {
+            return KeptVariables {
+                numbers: std::mem::take(&mut self.variables),
+                strings: std::mem::take(&mut self.string_variables),
+                arrays: std::mem::take(&mut self.arrays),
+                types: std::mem::take(&mut self.variable_types),
+            };
+        }
+        let mut kept = KeptVariables::default();
+        for name in &self.common_variables {
+            if let Some(value) = self.variables.remove(name) {
+                kept.numbers.insert(name.clone(), value);
+            }
+            if let Some(value) = self.string_variables.remove(name) {
+                kept.strings.insert(name.clone(), value);
+            }
+            if let Some(var_type) = self.variable_types.remove(name) {
+                kept.types.insert(name.clone(), var_type);
+            }
+        }
+        kept
+    }
+
+    pub(crate) fn restore_kept_variables(&mut self, kept: KeptVariables) {
+        self.variables.extend(kept.numbers);
+        self.string_variables.extend(kept.strings);
+        self.arrays.extend(kept.arrays);
+        self.variable_types.extend(kept.types);
+    }
+
+    /// Whether `name` survives loading the next program (the Variables panel marks it)
+    pub fn is_kept(&self, name: &str) -> bool {
+        self.keep_variables || self.common_variables.iter().any(|v| v == name)
+    }
+
+    /// BASIC `CLEAR`: forget every variable and array, and what COMMON kept
+    pub fn clear_variables(&mut self) {
+        self.variables.clear();
+        self.string_variables.clear();
+        self.arrays.clear();
+        self.variable_types.clear();
+        self.common_variables.clear();
+        self.option_base = 0;
+        self.data_pointer = 0;
+    }
+
+    /// Merge the numbered lines of `path` into the running program (BASIC MERGE)
+    ///
+    /// A line whose number the program already has replaces it; the others go
+    /// in before the first higher-numbered line. Merged lines report errors
+    /// at the MERGE line. Returns the line numbers that were replaced.
+    pub fn merge_file(&mut self, path: &str) -> Result<Vec<usize>> {
+        if path.is_empty() {
+            return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.merge_missing_name")));
+        }
+        let (source, _) = self.read_program_file(path, "MERGE")?;
+        let mut merged = Vec::new();
+        for (statement, lines) in logical_lines(&source) {
+            // Blank lines and @lang-style headers have nothing to merge
+            if statement.trim().is_empty() || statement.trim_start().starts_with('@') {
+                continue;
+            }
+            match self.parse_line(&statement) {
+                (Some(num), command) => merged.push((num, self.statements(command).into_iter().map(String::from).collect::<Vec<_>>())),
+                (None, _) => {
+                    return Err(anyhow::Error::new(BasicError::Syntax)
+                        .context(trf("basic.merge_unnumbered", &[&path, lines.start()])));
+                }
+            }
+        }
+
+        let added: std::collections::HashSet<usize> =
+            merged.iter().map(|(num, _)| *num).filter(|num| !self.line_number_map.contains_key(num)).collect();
+        let count = self.program_lines.len() + added.len();
+        if count > self.limits.max_program_lines {
+            return Err(anyhow::anyhow!(ExecutionLimits::exceeded(
+                tr("limit.program_lines"), count, self.limits.max_program_lines, tr("settings.limits.max_program_lines"),
+            )));
+        }
+
+        let origin = self.statement_lines();
+        // Each line remembers where it was, so positions held by the run can follow it
+        let mut lines: Vec<_> = std::mem::take(&mut self.program_lines)
+            .into_iter()
+            .zip(std::mem::take(&mut self.source_lines))
+            .enumerate()
+            .map(|(idx, ((num, command), range))| (num, command, range, Some(idx)))
+            .collect();
+        let mut replaced = Vec::new();
+        for (num, commands) in merged {
+            let (at, range, old) = match lines.iter().position(|l| l.0 == Some(num)) {
+                Some(at) => {
+                    // Every statement of the old line goes
+                    let range = lines[at].2.clone();
+                    let count = lines[at..].iter().take_while(|l| l.0 == Some(num) && l.2 == range).count();
+                    let old = lines.drain(at..at + count).next().and_then(|l| l.3);
+                    replaced.push(num);
+                    (at, range, old)
+                }
+                None => (lines.iter().position(|l| l.0.is_some_and(|n| n > num)).unwrap_or(lines.len()), origin.clone(), None),
+            };
+            // The first new statement stands where the old line did
+            let statements = commands.into_iter().enumerate().map(|(i, command)| (Some(num), command, range.clone(), old.filter(|_| i == 0)));
+            lines.splice(at..at, statements);
+        }
+        let mut moved = HashMap::new();
+        for (new, line) in lines.iter().enumerate() {
+            if let Some(old) = line.3 {
+                moved.insert(old, new);
+            }
+        }
+        let follow = |idx: &mut usize| *idx = moved.get(idx).copied().unwrap_or(*idx);
+        follow(&mut self.current_line);
+        self.gosub_stack.iter_mut().for_each(follow);
+        self.for_stack.iter_mut().for_each(|f| follow(&mut f.for_line));
+        self.do_stack.iter_mut().for_each(follow);
+        (self.program_lines, self.source_lines) = lines.into_iter().map(|(num, command, range, _)| ((num, command), range)).unzip();
+        self.index_lines();
+        replaced.sort_unstable();
+        Ok(replaced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_argument() {
+        assert_eq!(file_argument(" \"shapes.logo\" "), "shapes.logo");
+        assert_eq!(file_argument("\"menu.bas\", ALL"), "menu.bas");
+        assert_eq!(file_argument("lib/util.pilot"), "lib/util.pilot");
+        assert_eq!(file_argument(""), "");
+    }
+}
@@ -0,0 +1,98 @@
+//! Fixed-timestep scheduling for BASIC `EVERY` and `ONTICK`
+//!
+//! `EVERY 0.1 GOSUB 1000` runs the subroutine at 1000 ten times a second of
+//! [`Clock`](super::clock::Clock) time, whatever the machine's speed. Time
+//! since the last check is added to an accumulator; each handler run takes
+//! one interval off it. When the program falls behind (a slow statement, a
+//! pause for `INPUT`), the handler runs back to back until it has caught up,
+//! but never more than [`MAX_CATCH_UP`] intervals' worth: older time is
+//! dropped, so a long stall cannot start a spiral of handlers that never
+//! catch up. `DELTA` is the clock time between the starts of the last two
+//! runs, so the `DELTA`s add up to the time the handler has been running.
+
+use std::time::Duration;
+
+/// `ONTICK` interval: sixty ticks a second, one per display frame
+pub const FRAME_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Most intervals kept in the accumulator; time beyond this is dropped
+pub const MAX_CATCH_UP: u32 = 5;
+
+/// An `EVERY`/`ONTICK` handler and the time it owes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker {
+    /// Clock time between handler runs
+    pub interval: Duration,
+    /// BASIC line number of the handler
+    pub handler: usize,
+    accumulator: Duration,
+    last_poll: Duration,
+    last_run: Duration,
+    /// `DELTA`: seconds between the starts of the last two runs
+    pub delta: f64,
+    /// GOSUB depth under the running handler; `None` when it is not running
+    pub running: Option<usize>,
+}
+
+impl Ticker {
+    /// A handler scheduled at clock time `now`; its first run is one interval later
+    pub fn new(interval: Duration, handler: usize, now: Duration) -> Self {
+        Self { interval, handler, accumulator: Duration::ZERO, last_poll: now, last_run: now, delta: 0.0, running: None }
+    }
+
+    /// Add the time since the last poll; true when a run is due
+    pub fn poll(&mut self, now: Duration) -> bool {
+        self.accumulator += now.saturating_sub(self.last_poll);
+        self.last_poll = now;
+        self.accumulator = self.accumulator.min(self.interval * MAX_CATCH_UP);
+        self.accumulator >= self.interval
+    }
+
+    /// Take one interval off the accumulator for a run starting at `now`
+    pub fn start(&mut self, now: Duration) {
+        self.accumulator = self.accumulator.saturating_sub(self.interval);
+        self.delta = now.saturating_sub(self.last_run).as_secs_f64();
+        self.last_run = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs per frame for frames that took `frames` milliseconds each
+    fn runs_per_frame(interval_ms: u64, frames: &[u64]) -> (Vec<u32>, f64) {
+        let mut ticker = Ticker::new(Duration::from_millis(interval_ms), 1000, Duration::ZERO);
+        let (mut now, mut deltas) = (Duration::ZERO, 0.0);
+        let runs = frames
+            .iter()
+            .map(|ms| {
+                now += Duration::from_millis(*ms);
+                let mut runs = 0;
+                while ticker.poll(now) {
+                    ticker.start(now);
+                    deltas += ticker.delta;
+                    runs += 1;
+                }
+                runs
+            })
+            .collect();
+        (runs, deltas)
+    }
+
+    #[test]
+    fn test_jittery_frames_catch_up() {
+        // 20 ms ticks against frames of 10-50 ms: 300 ms in all, 15 runs
+        let (runs, deltas) = runs_per_frame(20, &[10, 30, 50, 10, 20, 40, 15, 25, 60, 40]);
+        assert_eq!(runs, vec![0, 2, 2, 1, 1, 2, 0, 2, 3, 2]);
+        assert_eq!(runs.iter().sum::<u32>(), 15);
+        assert!((deltas - 0.3).abs() < 1e-9, "runs span the whole 300 ms: {}", deltas);
+    }
+
+    #[test]
+    fn test_catch_up_is_bounded() {
+        // A 1 s stall owes 50 runs but only MAX_CATCH_UP are made up
+        let (runs, _) = runs_per_frame(20, &[20, 1000, 20]);
+        assert_eq!(runs, vec![1, MAX_CATCH_UP, 1]);
+    }
+}
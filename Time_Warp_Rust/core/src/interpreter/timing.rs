@@ -0,0 +1,159 @@
+//! Output timing: when each output line appeared and how long each prompt waited
+//!
+//! With `record_timing` on (Settings → Output timing), a run notes, from the
+//! interpreter's [`Clock`](super::clock::Clock) and relative to its start,
+//! when each output line was written and when each prompt was asked and
+//! answered. Lines are stamped between statements, so the lines of one
+//! statement share a time. The times go into the run report, the Output
+//! panel's time gutter, and its replay. With a fake clock they repeat exactly.
+
+use std::time::Duration;
+
+use super::Interpreter;
+
+/// An answer, and the time it took to arrive
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputTiming {
+    pub answer: String,
+    /// When the prompt asked (or the previous answer to it was turned down)
+    pub asked: Duration,
+    pub answered: Duration,
+}
+
+impl InputTiming {
+    /// How long the prompt waited for this answer
+    pub fn wait(&self) -> Duration {
+        self.answered.saturating_sub(self.asked)
+    }
+}
+
+/// The times of one run, from its start
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunTiming {
+    /// When each output line was written, by output index
+    pub output: Vec<Duration>,
+    /// Every answer given, in order
+    pub inputs: Vec<InputTiming>,
+    /// Clock reading the run started at
+    started: Duration,
+    /// When the prompt now waiting asked
+    asked: Option<Duration>,
+}
+
+impl RunTiming {
+    pub(crate) fn starting_at(started: Duration) -> Self {
+        Self { started, ..Self::default() }
+    }
+}
+
+impl Interpreter {
+    /// Time since the run started
+    fn run_elapsed(&self) -> Duration {
+        self.clock.elapsed().saturating_sub(self.timing.started)
+    }
+
+    /// Stamp the output lines written since the last stamp with the time now
+    pub(crate) fn stamp_output(&mut self) {
+        if self.record_timing && self.timing.output.len() < self.output.len() {
+            let now = self.run_elapsed();
+            self.timing.output.resize(self.output.len(), now);
+        }
+    }
+
+    /// Note that a prompt is asking now, after the lines that lead up to it
+    pub(crate) fn stamp_prompt(&mut self) {
+        self.stamp_output();
+        if self.record_timing {
+            self.timing.asked = Some(self.run_elapsed());
+        }
+    }
+
+    /// Note `answer` arriving now; a prompt that asks again counts from here
+    pub(crate) fn stamp_answer(&mut self, answer: &str) {
+        if self.record_timing {
+            let now = self.run_elapsed();
+            let asked = self.timing.asked.replace(now).unwrap_or(now);
+            self.timing.inputs.push(InputTiming { answer: answer.to_string(), asked, answered: now });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::interpreter::clock::Clock;
+    use chrono::NaiveDateTime;
+    use std::sync::{Arc, Mutex};
+
+    /// 10 ms per statement; the test moves it on while a prompt waits
+    struct TestClock(Arc<Mutex<Duration>>);
+
+    impl Clock for TestClock {
+        fn elapsed(&self) -> Duration {
+            *self.0.lock().unwrap()
+        }
+        fn now(&self) -> NaiveDateTime {
+            NaiveDateTime::default() + self.elapsed()
+        }
+        fn tick(&mut self) {
+            *self.0.lock().unwrap() += Duration::from_millis(10);
+        }
+    }
+
+    const SURVEY: &str = "10 PRINT \"Name?\"\n20 INPUT N$\n30 PRINT \"Hello \"; N$\n40 INPUT A\n50 PRINT \"Bye\"";
+
+    fn timed_interpreter() -> (Interpreter, Arc<Mutex<Duration>>) {
+        let time = Arc::new(Mutex::new(Duration::from_secs(100)));
+        let mut interp = Interpreter::new();
+        interp.set_clock(TestClock(time.clone()));
+        interp.record_timing = true;
+        interp.load_program(SURVEY).unwrap();
+        (interp, time)
+    }
+
+    fn assert_monotonic(timing: &RunTiming, lines: usize) {
+        assert_eq!(timing.output.len(), lines);
+        assert!(timing.output.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", timing.output);
+    }
+
+    #[test]
+    fn test_answers_are_timed_with_a_callback() {
+        let (mut interp, time) = timed_interpreter();
+        let mut answers = [("Ada", 3), ("12", 5)].into_iter();
+        let clock = time.clone();
+        interp.input_callback = Some(Box::new(move |_| {
+            let (answer, seconds) = answers.next().unwrap_or_default();
+            *clock.lock().unwrap() += Duration::from_secs(seconds);
+            answer.to_string()
+        }));
+        let output = interp.execute(&mut TurtleState::new()).unwrap();
+        assert_monotonic(&interp.timing, output.len());
+        let waits: Vec<(&str, Duration)> = interp.timing.inputs.iter().map(|i| (i.answer.as_str(), i.wait())).collect();
+        assert_eq!(waits, [("Ada", Duration::from_secs(3)), ("12", Duration::from_secs(5))]);
+        // The first line is stamped after its statement, from the run's start
+        assert_eq!(interp.timing.output[0], Duration::from_millis(10));
+        assert_eq!(*interp.timing.output.last().unwrap(), Duration::from_millis(50) + Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_answers_are_timed_when_the_ui_answers() {
+        let (mut interp, time) = timed_interpreter();
+        let mut turtle = TurtleState::new();
+        interp.execute(&mut turtle).unwrap();
+        for (answer, seconds) in [("Ada", 2), ("12", 7)] {
+            *time.lock().unwrap() += Duration::from_secs(seconds);
+            interp.provide_input(answer);
+            interp.execute(&mut turtle).unwrap();
+        }
+        assert_monotonic(&interp.timing, interp.output.len());
+        let waits: Vec<Duration> = interp.timing.inputs.iter().map(InputTiming::wait).collect();
+        assert_eq!(waits, [Duration::from_secs(2), Duration::from_secs(7)]);
+
+        // Off, nothing is noted
+        interp.record_timing = false;
+        interp.load_program(SURVEY).unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert!(interp.timing.output.is_empty() && interp.timing.inputs.is_empty());
+    }
+}
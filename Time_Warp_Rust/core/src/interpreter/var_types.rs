@@ -0,0 +1,171 @@
+//! BASIC variable types: `COUNT%` integers, `X!` and `X#` floats, `NAME$` strings
+//!
+//! A suffix decides a variable's type. A name without one takes the type
+//! `DEFINT`, `DEFSNG`, `DEFDBL`, or `DEFSTR` gave its first letter when the
+//! variable is first stored, and is single precision otherwise; it keeps that
+//! type for the rest of the run. An integer holds whole numbers from -32768
+//! to 32767, so a float stored in one is rounded, as in GW-BASIC. Single and
+//! double precision both keep every digit a number has. Text stored in a
+//! name no suffix or DEF statement typed is kept as text, as it always was.
+
+use anyhow::Result;
+
+use super::arrays::{element_reference, is_string_target};
+use super::Interpreter;
+use crate::i18n::trf;
+use crate::utils::error::BasicError;
+
+/// Smallest and largest values an integer variable holds
+pub const INTEGER_RANGE: std::ops::RangeInclusive<f64> = -32768.0..=32767.0;
+
+/// Type of a BASIC variable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VarType {
+    Integer,
+    #[default]
+    Single,
+    Double,
+    String,
+}
+
+impl VarType {
+    /// The type a name's suffix gives it, if it has one
+    pub fn from_suffix(name: &str) -> Option<VarType> {
+        match name.trim().chars().last()? {
+            '%' => Some(VarType::Integer),
+            '!' => Some(VarType::Single),
+            '#' => Some(VarType::Double),
+            '$' => Some(VarType::String),
+            _ => None,
+        }
+    }
+
+    /// The type a `DEFINT`-style statement declares
+    pub fn from_statement(keyword: &str) -> Option<VarType> {
+        match keyword.to_ascii_uppercase().as_str() {
+            "DEFINT" => Some(VarType::Integer),
+            "DEFSNG" => Some(VarType::Single),
+            "DEFDBL" => Some(VarType::Double),
+            "DEFSTR" => Some(VarType::String),
+            _ => None,
+        }
+    }
+
+    /// Name shown in the Variables panel
+    pub fn name(self) -> &'static str {
+        match self {
+            VarType::Integer => "INTEGER",
+            VarType::Single => "SINGLE",
+            VarType::Double => "DOUBLE",
+            VarType::String => "STRING",
+        }
+    }
+}
+
+/// The type each first letter gives a name without a suffix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDefaults([VarType; 26]);
+
+impl Default for TypeDefaults {
+    fn default() -> Self {
+        TypeDefaults([VarType::Single; 26])
+    }
+}
+
+impl TypeDefaults {
+    /// Type of `name` from its suffix, else from its first letter
+    pub fn of(&self, name: &str) -> VarType {
+        VarType::from_suffix(name).unwrap_or_else(|| {
+            match name.trim().bytes().next().map(|b| b.to_ascii_uppercase()) {
+                Some(letter @ b'A'..=b'Z') => self.0[(letter - b'A') as usize],
+                _ => VarType::Single,
+            }
+        })
+    }
+
+    /// Give `var_type` to every letter in `ranges`, such as `I-N, X`
+    pub fn declare(&mut self, ranges: &str, var_type: VarType) -> Result<()> {
+        for range in ranges.split(',') {
+            let (first, last) = match range.split_once('-') {
+                Some((first, last)) => (letter(first)?, letter(last)?),
+                None => (letter(range)?, letter(range)?),
+            };
+            if first > last {
+                return Err(anyhow::Error::new(BasicError::Syntax).context(trf("basic.type.range_backwards", &[&range.trim()])));
+            }
+            for index in first..=last {
+                self.0[index] = var_type;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Index of the single letter `text` holds
+fn letter(text: &str) -> Result<usize> {
+    match text.trim().as_bytes() {
+        [c] if c.is_ascii_alphabetic() => Ok((c.to_ascii_uppercase() - b'A') as usize),
+        _ => Err(anyhow::Error::new(BasicError::Syntax).context(trf("basic.type.expected_letter", &[&text.trim()]))),
+    }
+}
+
+impl Interpreter {
+    /// Type of variable `name`: the one it was created with, or the one it would get now
+    pub fn var_type(&self, name: &str) -> VarType {
+        let name = name.trim();
+        self.variable_types.get(name).copied().unwrap_or_else(|| self.type_defaults.of(name))
+    }
+
+    /// Whether `target` holds text: a `$` name, a `$` array element, or a `DEFSTR` name
+    pub fn holds_text(&self, target: &str) -> bool {
+        is_string_target(target) || (element_reference(target).is_none() && self.var_type(target) == VarType::String)
+    }
+
+    /// Fix the type of `name` the first time it is stored, and return it
+    pub(crate) fn declare_variable(&mut self, name: &str) -> VarType {
+        let name = name.trim();
+        if VarType::from_suffix(name).is_some() {
+            return self.type_defaults.of(name);
+        }
+        let var_type = self.type_defaults.of(name);
+        *self.variable_types.entry(name.to_string()).or_insert(var_type)
+    }
+
+    /// `value` as variable `name` stores it: rounded for an integer, refused for a string
+    pub(crate) fn coerce_number(&mut self, name: &str, value: f64) -> Result<f64> {
+        match self.declare_variable(name) {
+            VarType::Integer => {
+                let rounded = value.round();
+                if !INTEGER_RANGE.contains(&rounded) {
+                    return Err(anyhow::Error::new(BasicError::Overflow)
+                        .context(trf("basic.type.integer_range", &[&name.trim(), &value])));
+                }
+                Ok(rounded)
+            }
+            VarType::String => Err(anyhow::Error::new(BasicError::TypeMismatch).context(trf("basic.type.holds_text", &[&name.trim()]))),
+            VarType::Single | VarType::Double => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_beats_declared_letter() {
+        let mut defaults = TypeDefaults::default();
+        defaults.declare("I-N, x", VarType::Integer).unwrap();
+        defaults.declare("S", VarType::String).unwrap();
+        assert_eq!(defaults.of("index"), VarType::Integer);
+        assert_eq!(defaults.of("N"), VarType::Integer);
+        assert_eq!(defaults.of("X"), VarType::Integer);
+        assert_eq!(defaults.of("OTHER"), VarType::Single);
+        assert_eq!(defaults.of("SCORE"), VarType::String);
+        assert_eq!(defaults.of("I#"), VarType::Double);
+        assert_eq!(defaults.of("S%"), VarType::Integer);
+        assert_eq!(defaults.of("TOTAL$"), VarType::String);
+        assert!(defaults.declare("N-I", VarType::Double).is_err());
+        assert!(defaults.declare("AB", VarType::Double).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Warnings: things worth pointing out that do not stop a run
+//!
+//! Reading a BASIC variable that was never set (it reads as 0, as in classic
+//! BASIC), a GOTO into the body of a FOR loop that is not running, text put
+//! in a numeric variable, a condition that is NaN (taken as false), and a
+//! run nearing its iteration limit are reported
+//! as [`Diagnostic`]s of severity [`Severity::Warning`]: a yellow
+//! `⚠️ Warning at line N: …` line in the output, and the run goes on. Strict
+//! mode promotes chosen kinds to errors, reported like any other error on
+//! the statement. The text follows the IDE's language; the kind does not.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::i18n::{tr, tr_in, trf, Locale};
+
+/// Share of the iteration limit after which a run is warned, in percent
+pub const NEAR_LIMIT_PERCENT: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    /// A warning promoted by strict mode
+    Error,
+}
+
+/// What a warning is about; strict mode is chosen per kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WarningKind {
+    /// A BASIC variable read before it was set, used as 0
+    UndefinedVariable,
+    /// A GOTO or IF…THEN into a FOR loop's body while that loop is not running
+    JumpIntoLoop,
+    /// Text stored in a variable without `$`, where a number was probably meant
+    StringForNumber,
+    /// The run has used 80% of its iteration limit
+    NearIterationLimit,
+    /// An IF or C: condition that came out NaN, taken as false
+    NanCondition,
+}
+
+impl WarningKind {
+    pub const ALL: [WarningKind; 5] = [
+        WarningKind::UndefinedVariable,
+        WarningKind::JumpIntoLoop,
+        WarningKind::StringForNumber,
+        WarningKind::NearIterationLimit,
+        WarningKind::NanCondition,
+    ];
+
+    /// Description for the Settings strict mode list
+    pub fn label(self) -> &'static str {
+        match self {
+            WarningKind::UndefinedVariable => tr("warning.kind.undefined_variable"),
+            WarningKind::JumpIntoLoop => tr("warning.kind.jump_into_loop"),
+            WarningKind::StringForNumber => tr("warning.kind.string_for_number"),
+            WarningKind::NearIterationLimit => tr("warning.kind.near_iteration_limit"),
+            WarningKind::NanCondition => tr("warning.kind.nan_condition"),
+        }
+    }
+}
+
+/// A warning raised by a run, or the error strict mode made of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: WarningKind,
+    /// Source line of the statement that raised it
+    pub line: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// The output line for a warning
+    pub fn text(&self) -> String {
+        trf("warning.at_line", &[&tr("warning.prefix"), &self.line, &self.message])
+    }
+}
+
+/// A warning strict mode made the statement's error
+#[derive(Debug, Clone, PartialEq)]
+pub struct Promoted {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl fmt::Display for Promoted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&trf("warning.strict", &[&self.message]))
+    }
+}
+
+impl std::error::Error for Promoted {}
+
+/// What is wrong with reading `name` before it was set
+pub fn unset_message(name: &str) -> String {
+    trf("warning.unset", &[&name])
+}
+
+/// Whether an output line is a warning (yellow, and hidden by the Output filter)
+///
+/// Lines written in any locale count, so a transcript keeps its colors when
+/// the language changes.
+pub fn is_warning_line(line: &str) -> bool {
+    Locale::ALL.into_iter().any(|locale| line.starts_with(tr_in(locale, "warning.prefix")))
+}
+
+/// Whether an output line is an error report (modern `❌ …` or classic `?… ERROR`)
+pub fn is_error_line(line: &str) -> bool {
+    line.starts_with('❌') || (line.starts_with('?') && line.contains(" ERROR"))
+}
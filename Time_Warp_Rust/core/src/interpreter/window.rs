@@ -0,0 +1,76 @@
+//! The window around a program: a title and a status message it sets
+//!
+//! BASIC `TITLE "Space Quiz"` and PILOT `TI:Space Quiz` name the running
+//! program; the IDE shows the name after its own in the window title and on
+//! the Output tab's banner. BASIC `STATUS "Level 2"` puts a message in the
+//! status bar. Empty text clears either one. Both are kept on the
+//! interpreter for the run and start out unset with the next one; on a
+//! worker each change is also streamed as a [`ProgramEvent`] so the IDE can
+//! show it while the program is still running, and the IDE drops both once
+//! the run ends.
+
+use super::Interpreter;
+
+/// Characters a title or status keeps; longer text is cut
+pub const MAX_WINDOW_TEXT: usize = 80;
+
+/// A change a running program made to the window around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramEvent {
+    /// `None` clears the title
+    Title(Option<String>),
+    /// `None` clears the status message
+    Status(Option<String>),
+}
+
+/// `text` on one line and at most [`MAX_WINDOW_TEXT`] characters; `None` when blank
+fn window_text(text: &str) -> Option<String> {
+    let line: String = text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect();
+    let line = line.trim();
+    (!line.is_empty()).then(|| line.chars().take(MAX_WINDOW_TEXT).collect())
+}
+
+impl Interpreter {
+    /// Set the program's title (`TITLE`, `TI:`)
+    pub fn set_program_title(&mut self, title: &str) {
+        self.program_title = window_text(title);
+        self.send_program_event(ProgramEvent::Title(self.program_title.clone()));
+    }
+
+    /// Set the program's status message (`STATUS`)
+    pub fn set_program_status(&mut self, status: &str) {
+        self.program_status = window_text(status);
+        self.send_program_event(ProgramEvent::Status(self.program_status.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+
+    #[test]
+    fn test_title_and_status_are_set_by_the_program() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program("10 TITLE \"Space Quiz\"\n20 LET L$ = \"Level 2\"\n30 STATUS L$\n40 PRINT \"go\"").unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(interp.program_title.as_deref(), Some("Space Quiz"));
+        assert_eq!(interp.program_status.as_deref(), Some("Level 2"));
+        assert_eq!(interp.output, ["go"]);
+
+        // PILOT names it with TI:; the next run starts without either
+        interp.load_program("TI:  Capitals\tof Europe \nT:ok").unwrap();
+        assert_eq!((interp.program_title.as_deref(), interp.program_status.as_deref()), (None, None));
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(interp.program_title.as_deref(), Some("Capitals of Europe"));
+        assert_eq!(interp.program_status, None);
+    }
+
+    #[test]
+    fn test_window_text_is_one_short_line() {
+        assert_eq!(window_text("  "), None);
+        assert_eq!(window_text("a\nb"), Some("a b".to_string()));
+        assert_eq!(window_text(&"x".repeat(200)).map(|t| t.len()), Some(MAX_WINDOW_TEXT));
+    }
+}
@@ -0,0 +1,435 @@
+//! Run a program on a background thread while the UI keeps drawing
+//!
+//! The UI moves its interpreter and a copy of the turtle into a `RunWorker`
+//! and takes both back when the run ends. Meanwhile the lines the turtle
+//! draws are streamed back in batches, at most one per `STREAM_INTERVAL`, so
+//! the canvas fills in as a slow program draws without a tight `FORWARD` loop
+//! flooding the channel. At the end of each time slice (see [`slicing`](super::slicing))
+//! the drawing and the output lines written so far go back too, with the
+//! run's pace. Stop is checked between statements and leaves what
+//! was drawn so far in place. Title and status changes
+//! ([`ProgramEvent`]s) go back on a channel of their own as they happen. A panic in the run ends it with an error
+//! rather than the thread, so the interpreter always comes back.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::panic_guard::panic_message;
+use super::window::ProgramEvent;
+use super::Interpreter;
+use crate::graphics::{Flash, TurtleLine, TurtleState};
+
+/// Shortest gap between two streamed batches (about one frame)
+pub const STREAM_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Lines drawn since the previous batch
+///
+/// A `generation` different from the receiving turtle's means the program
+/// cleared the screen: drop what was drawn before adding `lines`.
+#[derive(Debug, Clone)]
+pub struct TurtleBatch {
+    pub generation: u64,
+    pub lines: Vec<TurtleLine>,
+    /// The running turtle's last `FLASH`
+    pub flash: Option<Flash>,
+}
+
+impl TurtleBatch {
+    /// Add this batch to a turtle that mirrors the running one
+    pub fn apply(self, turtle: &mut TurtleState) {
+        if self.generation != turtle.generation {
+            turtle.lines.clear();
+            turtle.texts.clear();
+            turtle.backdrop = None;
+            turtle.generation = self.generation;
+        }
+        turtle.lines.extend(self.lines);
+        turtle.flash = self.flash;
+        turtle.consolidate();
+    }
+}
+
+/// Output lines written since the previous batch
+///
+/// `from` is the index of the first: output the receiver holds from there on
+/// was cleared by the program starting over, and is replaced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputBatch {
+    pub from: usize,
+    pub lines: Vec<String>,
+}
+
+impl OutputBatch {
+    /// Add this batch to output that mirrors the running program's
+    pub fn apply(self, output: &mut Vec<String>) {
+        output.truncate(self.from);
+        output.extend(self.lines);
+    }
+}
+
+/// The running interpreter's end of a worker: where batches go and the stop flag
+pub struct WorkerLink {
+    batches: Sender<TurtleBatch>,
+    output: Sender<OutputBatch>,
+    /// Output lines already sent
+    output_sent: usize,
+    /// Statements per second, as `f64` bits, at the last slice's end
+    pub(crate) pace: Arc<AtomicU64>,
+    events: Sender<ProgramEvent>,
+    stop: Arc<AtomicBool>,
+    interval: Duration,
+    last_sent: Instant,
+    /// Lines of the current generation already sent, counting folded ones
+    sent: usize,
+    generation: u64,
+    /// `Flash::serial` of the last FLASH sent
+    flash_serial: u64,
+}
+
+impl WorkerLink {
+    /// Send lines drawn since the last batch, unless one went out within the interval
+    pub(crate) fn publish(&mut self, turtle: &TurtleState, flush: bool) {
+        let cleared = turtle.generation != self.generation;
+        let total = turtle.total_lines();
+        let sent = if cleared { 0 } else { self.sent.min(total) };
+        let flash_serial = turtle.flash.as_ref().map_or(self.flash_serial, |flash| flash.serial);
+        if sent == total && !cleared && flash_serial == self.flash_serial {
+            return;
+        }
+        if !flush && self.last_sent.elapsed() < self.interval {
+            return;
+        }
+        // Lines are sent before they are folded, so the unsent ones are all still lines
+        let unsent = &turtle.lines[sent.saturating_sub(turtle.folded_lines()).min(turtle.lines.len())..];
+        let batch = TurtleBatch { generation: turtle.generation, lines: unsent.to_vec(), flash: turtle.flash.clone() };
+        // The receiver only disappears when the UI gave up on the run
+        let _ = self.batches.send(batch);
+        self.generation = turtle.generation;
+        self.sent = total;
+        self.flash_serial = flash_serial;
+        self.last_sent = Instant::now();
+    }
+
+    /// Send output lines written since the last batch (at a slice's end)
+    pub(crate) fn publish_output(&mut self, output: &[String]) {
+        let from = self.output_sent.min(output.len());
+        if from == output.len() {
+            return;
+        }
+        let _ = self.output.send(OutputBatch { from, lines: output[from..].to_vec() });
+        self.output_sent = output.len();
+    }
+}
+
+impl Interpreter {
+    /// Stream new turtle lines to the UI when running on a worker (called between commands),
+    /// then fold the oldest into the backdrop if the turtle is over its line budget
+    pub fn stream_turtle(&mut self, turtle: &mut TurtleState) {
+        // The backdrop is not streamed, so every line goes out before it is folded
+        let over = turtle.over_budget();
+        if let Some(link) = self.worker_link.as_mut() {
+            link.publish(turtle, over);
+        }
+        if over {
+            turtle.consolidate();
+        }
+    }
+
+    /// Send the output from its first line again, after it was cleared
+    pub(crate) fn restart_output_stream(&mut self) {
+        if let Some(link) = self.worker_link.as_mut() {
+            link.output_sent = 0;
+            let _ = link.output.send(OutputBatch { from: 0, lines: Vec::new() });
+        }
+    }
+
+    /// Tell the UI about a title or status change, when running on a worker
+    pub(crate) fn send_program_event(&self, event: ProgramEvent) {
+        if let Some(link) = self.worker_link.as_ref() {
+            let _ = link.events.send(event);
+        }
+    }
+
+    /// Whether Stop was pressed for the worker running this interpreter
+    pub fn stop_requested(&self) -> bool {
+        self.worker_link.as_ref().is_some_and(|link| link.stop.load(Ordering::Relaxed))
+    }
+}
+
+/// A run finished (or stopped) on a worker, handed back to the UI
+pub struct FinishedRun {
+    pub interpreter: Interpreter,
+    pub turtle: TurtleState,
+    /// What `Interpreter::execute` returned
+    pub result: Result<Vec<String>>,
+    /// Time the program ran on the worker (one stretch between INPUT pauses)
+    pub elapsed: Duration,
+}
+
+/// A program running on its own thread
+pub struct RunWorker {
+    batches: Receiver<TurtleBatch>,
+    output: Receiver<OutputBatch>,
+    pace: Arc<AtomicU64>,
+    events: Receiver<ProgramEvent>,
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<FinishedRun>,
+}
+
+impl RunWorker {
+    /// Continue `interpreter`'s loaded program (fresh or resumed after INPUT) on a new thread
+    pub fn spawn(interpreter: Interpreter, turtle: TurtleState) -> Self {
+        Self::spawn_with_interval(interpreter, turtle, STREAM_INTERVAL)
+    }
+
+    /// `spawn` with a custom gap between streamed batches
+    pub fn spawn_with_interval(mut interpreter: Interpreter, mut turtle: TurtleState, interval: Duration) -> Self {
+        let (sender, batches) = mpsc::channel();
+        let (event_sender, events) = mpsc::channel();
+        let (output_sender, output) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let pace = Arc::new(AtomicU64::new(0));
+        interpreter.worker_link = Some(WorkerLink {
+            batches: sender,
+            output: output_sender,
+            output_sent: interpreter.output.len(),
+            pace: pace.clone(),
+            events: event_sender,
+            stop: stop.clone(),
+            interval,
+            last_sent: Instant::now(),
+            sent: turtle.total_lines(),
+            generation: turtle.generation,
+            flash_serial: turtle.flash.as_ref().map_or(0, |flash| flash.serial),
+        });
+        let handle = std::thread::spawn(move || {
+            let started = Instant::now();
+            // A panic outside any one statement still hands the interpreter back
+            let result = catch_unwind(AssertUnwindSafe(|| interpreter.execute(&mut turtle)))
+                .unwrap_or_else(|panic| Err(interpreter.internal_error(&panic_message(panic.as_ref()))));
+            if let Some(mut link) = interpreter.worker_link.take() {
+                link.publish(&turtle, true);
+                link.publish_output(&interpreter.output);
+            }
+            FinishedRun { interpreter, turtle, result, elapsed: started.elapsed() }
+        });
+        Self { batches, output, pace, events, stop, handle }
+    }
+
+    /// Apply every batch streamed so far to `turtle`; returns how many lines were added
+    pub fn drain_into(&self, turtle: &mut TurtleState) -> usize {
+        let mut added = 0;
+        for batch in self.batches.try_iter() {
+            added += batch.lines.len();
+            batch.apply(turtle);
+        }
+        added
+    }
+
+    /// Apply every output batch sent so far to `output`, a copy of the output the run started with
+    pub fn drain_output_into(&self, output: &mut Vec<String>) {
+        for batch in self.output.try_iter() {
+            batch.apply(output);
+        }
+    }
+
+    /// Statements per second at the last slice's end; `None` before the first
+    pub fn statements_per_second(&self) -> Option<f64> {
+        let bits = self.pace.load(Ordering::Relaxed);
+        (bits != 0).then(|| f64::from_bits(bits))
+    }
+
+    /// Title and status changes sent since the last call, oldest first
+    pub fn take_events(&self) -> Vec<ProgramEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Ask the program to stop before its next statement
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the thread and take the interpreter and turtle back
+    pub fn finish(self) -> FinishedRun {
+        match self.handle.join() {
+            Ok(run) => run,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::clock::FakeClock;
+    use crate::interpreter::slicing::SliceBudget;
+    use crate::interpreter::SecurityPolicy;
+
+    fn loaded(source: &str) -> Interpreter {
+        let mut interp = Interpreter::new();
+        interp.load_program(source).unwrap();
+        interp
+    }
+
+    /// Run to completion, counting lines and batches as the UI would receive them
+    fn stream(worker: RunWorker, mirror: &mut TurtleState) -> (FinishedRun, usize, usize) {
+        let (mut streamed, mut batches) = (0, 0);
+        loop {
+            let finished = worker.is_finished();
+            for batch in worker.batches.try_iter() {
+                streamed += batch.lines.len();
+                batches += 1;
+                batch.apply(mirror);
+            }
+            if finished {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        (worker.finish(), streamed, batches)
+    }
+
+    #[test]
+    fn test_streamed_batches_add_up_to_the_drawing() {
+        let source = "REPEAT 360 [FD 1 RT 1]\n10 FOR I = 1 TO 50\n20 FORWARD 2\n30 NEXT I";
+        let worker = RunWorker::spawn_with_interval(loaded(source), TurtleState::new(), Duration::ZERO);
+        let mut mirror = TurtleState::new();
+        let (run, streamed, batches) = stream(worker, &mut mirror);
+
+        assert!(run.result.is_ok());
+        assert_eq!(run.turtle.lines.len(), 410);
+        assert_eq!(streamed, run.turtle.lines.len());
+        assert_eq!(mirror.lines.len(), run.turtle.lines.len());
+        assert!(batches > 1, "lines arrive incrementally");
+        assert!(run.interpreter.worker_link.is_none());
+    }
+
+    #[test]
+    fn test_folded_lines_still_reach_the_mirror() {
+        let mut turtle = TurtleState::new();
+        turtle.line_budget = Some(1000);
+        let worker = RunWorker::spawn_with_interval(loaded("REPEAT 5000 [FD 1 RT 1]"), turtle.clone(), Duration::ZERO);
+        let mut mirror = turtle;
+        let (run, streamed, _) = stream(worker, &mut mirror);
+        assert_eq!(streamed, 5000);
+        assert_eq!((run.turtle.total_lines(), mirror.total_lines()), (5000, 5000));
+        assert!(run.turtle.lines.len() <= 1000 && mirror.lines.len() <= 1000);
+    }
+
+    #[test]
+    fn test_batches_are_coalesced() {
+        let worker = RunWorker::spawn_with_interval(loaded("REPEAT 500 [FD 1 RT 1]"), TurtleState::new(), Duration::from_secs(3600));
+        let (run, streamed, batches) = stream(worker, &mut TurtleState::new());
+        assert_eq!(batches, 1, "only the final flush fits in the interval");
+        assert_eq!(streamed, run.turtle.lines.len());
+    }
+
+    #[test]
+    fn test_clearscreen_restarts_the_mirror() {
+        let worker = RunWorker::spawn_with_interval(loaded("FD 10\nFD 10\nCLEARSCREEN\nFD 5"), TurtleState::new(), Duration::ZERO);
+        let mut mirror = TurtleState::new();
+        let (run, _, _) = stream(worker, &mut mirror);
+        assert_eq!(run.turtle.generation, 1);
+        assert_eq!(mirror.generation, 1);
+        let ends = |t: &TurtleState| t.lines.iter().map(|l| (l.start, l.end)).collect::<Vec<_>>();
+        assert_eq!(ends(&mirror), ends(&run.turtle), "lines from before the clear are gone");
+    }
+
+    #[test]
+    fn test_flash_reaches_the_mirror() {
+        let worker = RunWorker::spawn_with_interval(loaded("FD 10\nFD 10\nFLASH 1"), TurtleState::new(), Duration::from_secs(3600));
+        let mut mirror = TurtleState::new();
+        let (run, _, _) = stream(worker, &mut mirror);
+        assert_eq!(run.turtle.flash.as_ref().map(|f| f.lines.clone()), Some(1..2));
+        assert_eq!(mirror.flash, run.turtle.flash);
+
+        // A FLASH with nothing new drawn is still sent
+        let mut drawn = TurtleState::new();
+        drawn.forward(5.0);
+        let worker = RunWorker::spawn_with_interval(loaded("FLASH 1"), drawn.clone(), Duration::ZERO);
+        let (_, streamed, batches) = stream(worker, &mut drawn);
+        assert_eq!((streamed, batches), (0, 1));
+        assert_eq!(drawn.flash.map(|f| f.lines), Some(0..1));
+    }
+
+    #[test]
+    fn test_output_arrives_a_slice_at_a_time() {
+        let source: String = (1..=20).map(|i| format!("{} PRINT {}\n", i * 10, i)).collect();
+        let mut interp = loaded(&source);
+        interp.set_clock(FakeClock::fast_forward());
+        interp.slice_budget = SliceBudget { min_statements: 1, ..SliceBudget::default() };
+        let worker = RunWorker::spawn(interp, TurtleState::new());
+        while !worker.is_finished() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let batches: Vec<OutputBatch> = worker.output.try_iter().collect();
+        // A millisecond a statement on the fake clock: four statements to a slice
+        assert_eq!(worker.statements_per_second(), Some(1000.0));
+        let run = worker.finish();
+        assert_eq!(batches.iter().map(|b| b.lines.len()).collect::<Vec<_>>(), [0, 4, 4, 4, 4, 4], "the run clears first");
+        assert_eq!(run.interpreter.slice_count(), 4);
+
+        let mut mirror = vec!["from the last run".to_string()];
+        for batch in batches {
+            batch.apply(&mut mirror);
+        }
+        assert_eq!(mirror, run.interpreter.output);
+        assert_eq!(mirror.first().map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn test_title_and_status_are_streamed() {
+        let worker = RunWorker::spawn(loaded("10 TITLE \"Quiz\"\n20 STATUS \"Level 1\"\n30 STATUS \"\""), TurtleState::new());
+        while !worker.is_finished() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(worker.take_events(), [
+            ProgramEvent::Title(Some("Quiz".into())),
+            ProgramEvent::Status(Some("Level 1".into())),
+            ProgramEvent::Status(None),
+        ]);
+        let run = worker.finish();
+        assert_eq!((run.interpreter.program_title.as_deref(), run.interpreter.program_status.as_deref()), (Some("Quiz"), None));
+    }
+
+    #[test]
+    fn test_stop_keeps_the_partial_drawing() {
+        // Runs until stopped
+        let mut interp = loaded("10 FORWARD 1\n20 GOTO 10");
+        let policy = SecurityPolicy { max_iterations: Some(usize::MAX), time_limit_secs: Some(600), ..SecurityPolicy::default() };
+        interp.set_policy(policy).unwrap();
+        let worker = RunWorker::spawn(interp, TurtleState::new());
+        let mut mirror = TurtleState::new();
+        while worker.drain_into(&mut mirror) == 0 && !worker.is_finished() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        worker.stop();
+        let run = worker.finish();
+
+        let drawn = run.turtle.lines.len();
+        assert!(drawn > 0);
+        assert!(mirror.lines.len() <= drawn);
+        assert!(run.interpreter.output.iter().any(|l| l.contains("stopped")));
+    }
+
+    #[test]
+    fn test_a_panic_hands_the_interpreter_back() {
+        let source = format!("FD 10\n{}\nFD 10", crate::interpreter::panic_guard::PANIC_STATEMENT);
+        let worker = RunWorker::spawn_with_interval(loaded(&source), TurtleState::new(), Duration::ZERO);
+        let (run, streamed, _) = stream(worker, &mut TurtleState::new());
+        let error = run.result.unwrap_err().to_string();
+        assert!(error.contains("please report"), "{}", error);
+        assert_eq!((run.turtle.lines.len(), streamed), (1, 1));
+        assert!(run.interpreter.worker_link.is_none());
+    }
+}
@@ -0,0 +1,476 @@
+//! Program statistics for grading (Tools ▸ Program Report, `--analyze`)
+//!
+//! A static pass over the statements the interpreter loads — the same `_`
+//! continuations, line numbers, PILOT labels, FOR/NEXT pairs, and per-line
+//! language detection a run uses — without running anything. It counts
+//! statements per construct, measures nesting, lists variables, and finds
+//! lines no run can reach.
+//!
+//! Unused variables and unreachable lines are best effort: a computed jump
+//! (`GOTO N * 10`) turns the unreachable check off, and a name only ever
+//! written inside a string is not seen as a read.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use super::{basic, logo, pilot, Language};
+use crate::interpreter::arrays::{element_reference, split_top_level};
+use crate::interpreter::{metadata, ExecutionLimits, Interpreter};
+use crate::utils::{expr_eval, ExpressionEvaluator};
+
+/// Names in expressions that are not the program's variables
+pub(super) const RESERVED: &[&str] = &[
+    "AND", "OR", "NOT", "MOD", "THEN", "ELSE", "TO", "STEP", "AS", "NUMBER", "RANGE", "USING", "ALL",
+    "TIMER", "PATHCOUNT", "HEADING", "DELTA", "INKEY$", "INPUT$", "DATE$", "TIME$", "ERR", "ERL",
+];
+
+/// BASIC statements whose arguments are not expressions
+const NO_EXPRESSIONS: &[&str] = &["REM", "DATA", "DEF", "OPTION", "CHAIN", "MERGE", "RESTORE", "CLEAR", "RETURN", "END"];
+
+static IDENTIFIER: Lazy<Regex> = Lazy::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*\$?").expect("Invalid regex pattern"));
+/// Line numbers after GOTO, GOSUB, THEN, or ELSE (`ON X GOTO 10, 20` gives both)
+static LINE_TARGETS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\b(?:GOTO|GOSUB|THEN|ELSE)\s*(\d+(?:\s*,\s*\d+)*)").expect("Invalid regex pattern"));
+/// What follows each GOTO or GOSUB, to spot jumps to a computed line
+static JUMP_ARGUMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(?:GOTO|GOSUB)\s*([^\s,]+)").expect("Invalid regex pattern"));
+static INTERPOLATED: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*([A-Z_][A-Z0-9_]*)\*").expect("Invalid regex pattern"));
+static LOGO_VARIABLE: Lazy<Regex> = Lazy::new(|| Regex::new(r":([A-Za-z_][A-Za-z0-9_]*)").expect("Invalid regex pattern"));
+
+/// What a static pass over a program found
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgramStats {
+    pub language: Language,
+    /// Statements, not counting blank lines and comments
+    pub statements: usize,
+    /// Statements per construct, by keyword: `PRINT`, `IF`, `FOR`, `GOSUB`, `TO`, `T:`, …;
+    /// calls of the program's own Logo procedures count as `procedure call`
+    pub constructs: BTreeMap<String, usize>,
+    /// Deepest nesting of FOR loops, IF statements, REPEAT brackets, and procedure bodies
+    pub max_nesting: usize,
+    /// Every variable the program sets or reads, sorted
+    pub variables: Vec<String>,
+    /// Variables set (or declared with DIM) but never read
+    pub unused_variables: Vec<String>,
+    /// 1-based source lines that no path from the first statement reaches
+    pub unreachable_lines: Vec<usize>,
+    pub longest_line: Option<LongestLine>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LongestLine {
+    /// 1-based source line (the first, when several are as long)
+    pub line: usize,
+    /// Length in characters
+    pub chars: usize,
+}
+
+/// Statistics for `source` written in `language`
+pub fn analyze_program(source: &str, language: Language) -> ProgramStats {
+    let mut interp = Interpreter::new();
+    // Only the size limits can refuse a program, and a report should cover any program
+    interp.limits = ExecutionLimits { max_program_bytes: usize::MAX, max_program_lines: usize::MAX, ..ExecutionLimits::default() };
+    let _ = interp.load_program(source);
+    interp.metadata.language = Some(language);
+    // Procedures are defined as a run reaches them; name them all up front so calls are recognised
+    for (_, statement) in &interp.program_lines {
+        if let Some((name, params)) = logo_definition(statement) {
+            interp.logo_procedures.insert(name, logo::LogoProcedure { params, body: Vec::new() });
+        }
+    }
+    let languages: Vec<Option<Language>> = interp
+        .program_lines
+        .iter()
+        .map(|(_, statement)| {
+            let statement = statement.trim();
+            (!statement.is_empty() && metadata::comment_language(statement).is_none())
+                .then(|| interp.determine_command_type(statement))
+        })
+        .collect();
+
+    let mut constructs = BTreeMap::new();
+    let mut names = Names::default();
+    for ((_, statement), language) in interp.program_lines.iter().zip(&languages) {
+        match language {
+            Some(Language::Basic) => basic_statement(statement, &mut constructs, &mut names),
+            Some(Language::Pilot) => pilot_statement(statement, &mut constructs, &mut names),
+            Some(Language::Logo) => logo_statement(&interp, statement, &mut constructs, &mut names),
+            _ => {}
+        }
+    }
+    let unused_variables = names.set.difference(&names.read).cloned().collect();
+    ProgramStats {
+        language,
+        statements: languages.iter().flatten().count(),
+        constructs,
+        max_nesting: max_nesting(&interp, &languages),
+        variables: names.set.union(&names.read).cloned().collect(),
+        unused_variables,
+        unreachable_lines: unreachable_lines(&interp, &languages),
+        longest_line: source
+            .lines()
+            .enumerate()
+            .map(|(index, line)| LongestLine { line: index + 1, chars: line.chars().count() })
+            .max_by_key(|longest| (longest.chars, std::cmp::Reverse(longest.line))),
+    }
+}
+
+/// Variables a program sets and reads
+#[derive(Default)]
+struct Names {
+    set: BTreeSet<String>,
+    read: BTreeSet<String>,
+}
+
+impl Names {
+    /// Every variable named in the expression `text` is read
+    fn read_in(&mut self, text: &str) {
+        let outside_strings: String = text.split('"').step_by(2).collect::<Vec<_>>().join(" ");
+        for name in IDENTIFIER.find_iter(&outside_strings).map(|m| m.as_str()) {
+            let upper = name.to_ascii_uppercase();
+            let reserved = RESERVED.contains(&upper.as_str())
+                || basic::KEYWORDS.contains(&upper.as_str())
+                || ExpressionEvaluator::FUNCTIONS.contains(&upper.as_str())
+                || expr_eval::TEXT_FUNCTIONS.contains(&upper.as_str())
+                || upper.starts_with("FN");
+            if !reserved {
+                self.read.insert(name.to_string());
+            }
+        }
+    }
+
+    /// `target` (a variable or `NAME(subscripts)`) is set; its subscripts are read
+    fn set_target(&mut self, target: &str) {
+        let target = target.trim();
+        match element_reference(target) {
+            Some((name, subscripts)) => {
+                self.read_in(subscripts);
+                self.set.insert(target[..name.len()].to_string());
+            }
+            None if !target.is_empty() => {
+                self.set.insert(target.to_string());
+            }
+            None => {}
+        }
+    }
+}
+
+fn count(constructs: &mut BTreeMap<String, usize>, construct: &str) {
+    *constructs.entry(construct.to_string()).or_default() += 1;
+}
+
+/// `IF`'s condition, its THEN part, and its ELSE part
+fn split_if(args: &str) -> (&str, &str, &str) {
+    let Some(then) = basic::find_keyword(args, "THEN") else { return (args, "", "") };
+    let branches = &args[then + 4..];
+    match basic::find_keyword(branches, "ELSE") {
+        Some(at) => (&args[..then], &branches[..at], &branches[at + 4..]),
+        None => (&args[..then], branches, ""),
+    }
+}
+
+fn basic_statement(statement: &str, constructs: &mut BTreeMap<String, usize>, names: &mut Names) {
+    // IF branches wait here rather than recursing, so a long IF … THEN IF chain cannot overflow the stack
+    let mut pending = vec![statement];
+    while let Some(statement) = pending.pop() {
+        basic_clause(statement, constructs, names, &mut pending);
+    }
+}
+
+/// Count one statement, leaving the branches of an IF in `pending`
+fn basic_clause<'a>(statement: &'a str, constructs: &mut BTreeMap<String, usize>, names: &mut Names, pending: &mut Vec<&'a str>) {
+    let statement = statement.trim();
+    let (keyword, args) = statement.split_once(char::is_whitespace).unwrap_or((statement, ""));
+    let keyword = keyword.to_ascii_uppercase();
+    if keyword.is_empty() {
+        return;
+    }
+    count(constructs, &keyword);
+    match keyword.as_str() {
+        "LET" => {
+            let (target, value) = args.split_once('=').unwrap_or((args, ""));
+            names.set_target(target);
+            names.read_in(value);
+        }
+        "FOR" => {
+            let (variable, range) = args.split_once('=').unwrap_or((args, ""));
+            // NEXT and the loop test read the variable
+            names.set_target(variable);
+            names.read_in(variable);
+            names.read_in(range);
+        }
+        "INPUT" | "READ" | "DIM" => {
+            // INPUT "Prompt"; A, B AS NUMBER RANGE 1 TO 9
+            let mut targets = args.trim_start();
+            if keyword == "INPUT" && targets.starts_with('"') {
+                let close = targets[1..].find('"').map_or(targets.len(), |at| at + 2);
+                targets = targets[close..].trim_start().trim_start_matches([';', ',']);
+            }
+            let targets = basic::find_keyword(targets, "AS").map_or(targets, |at| &targets[..at]);
+            for target in split_top_level(targets) {
+                names.set_target(target);
+            }
+        }
+        "IF" => {
+            let (condition, then, otherwise) = split_if(args);
+            names.read_in(condition);
+            for branch in [otherwise, then].map(str::trim) {
+                if !branch.is_empty() && !branch.starts_with(|c: char| c.is_ascii_digit()) {
+                    pending.push(branch);
+                }
+            }
+        }
+        _ if NO_EXPRESSIONS.contains(&keyword.as_str()) => {}
+        _ => names.read_in(args),
+    }
+}
+
+fn pilot_statement(statement: &str, constructs: &mut BTreeMap<String, usize>, names: &mut Names) {
+    let statement = statement.trim();
+    let Some((kind, args)) = statement.split_once(':') else {
+        // `*LABEL` defines a label like `L:LABEL`
+        if statement.starts_with('*') {
+            count(constructs, "L:");
+        }
+        return;
+    };
+    count(constructs, &format!("{}:", kind.to_ascii_uppercase()));
+    match kind.to_ascii_uppercase().as_str() {
+        "U" => {
+            if let Some((variable, value)) = args.split_once('=') {
+                names.set_target(variable);
+                names.read_in(value);
+            }
+        }
+        "A" if !args.trim().is_empty() => names.set_target(args),
+        "C" | "Y" | "N" | "TW" => names.read_in(args),
+        _ => {}
+    }
+    for name in INTERPOLATED.captures_iter(args) {
+        names.read.insert(name[1].to_string());
+    }
+}
+
+fn logo_statement(interp: &Interpreter, statement: &str, constructs: &mut BTreeMap<String, usize>, names: &mut Names) {
+    if let Some((_, params)) = logo_definition(statement) {
+        count(constructs, "TO");
+        names.set.extend(params);
+        return;
+    }
+    // Every command on the line, including those inside REPEAT brackets
+    for word in statement.split(|c: char| c.is_whitespace() || c == '[' || c == ']').filter(|w| !w.is_empty()) {
+        let upper = word.to_ascii_uppercase();
+        if interp.logo_procedures.contains_key(&upper) {
+            count(constructs, "procedure call");
+        } else if logo::KEYWORDS.contains(&upper.as_str()) && upper != "END" {
+            count(constructs, &upper);
+        }
+    }
+    for name in LOGO_VARIABLE.captures_iter(statement) {
+        names.read.insert(name[1].to_ascii_uppercase());
+    }
+}
+
+/// Name and parameters of a Logo `TO NAME :A :B` statement, upper-case as Logo keeps them
+fn logo_definition(statement: &str) -> Option<(String, Vec<String>)> {
+    let mut words = statement.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("TO") {
+        return None;
+    }
+    let name = words.next()?.to_ascii_uppercase();
+    let params = words.map(|p| p.trim_start_matches(':').to_ascii_uppercase()).collect();
+    Some((name, params))
+}
+
+/// Index of each Logo `TO` statement with the index of its `END`
+fn procedure_bodies(interp: &Interpreter) -> Vec<(String, usize, usize)> {
+    let mut bodies = Vec::new();
+    let mut open = None;
+    for (index, (_, statement)) in interp.program_lines.iter().enumerate() {
+        if let Some((name, _)) = logo_definition(statement) {
+            open = Some((name, index));
+        } else if statement.trim().eq_ignore_ascii_case("END") {
+            if let Some((name, start)) = open.take() {
+                bodies.push((name, start, index));
+            }
+        }
+    }
+    bodies
+}
+
+fn max_nesting(interp: &Interpreter, languages: &[Option<Language>]) -> usize {
+    let bodies = procedure_bodies(interp);
+    let mut brackets = 0usize;
+    let mut deepest = 0;
+    for (index, (_, statement)) in interp.program_lines.iter().enumerate() {
+        let in_body = bodies.iter().any(|&(_, start, end)| start < index && index < end) as usize;
+        let depth = match languages[index] {
+            Some(Language::Basic) => {
+                let loops = interp.for_loops.iter().chain(&interp.do_loops).filter(|&&(start, end)| start <= index && index <= end).count();
+                // IF … THEN IF … nests once more
+                let mut ifs = 0;
+                let mut rest = statement.as_str();
+                while let Some(at) = basic::find_keyword(rest, "IF") {
+                    ifs += 1;
+                    rest = &rest[at + 2..];
+                }
+                loops + ifs
+            }
+            Some(Language::Logo) => {
+                let mut deepest_here = brackets;
+                for c in statement.chars() {
+                    match c {
+                        '[' => {
+                            brackets += 1;
+                            deepest_here = deepest_here.max(brackets);
+                        }
+                        ']' => brackets = brackets.saturating_sub(1),
+                        _ => {}
+                    }
+                }
+                deepest_here + in_body
+            }
+            _ => 0,
+        };
+        deepest = deepest.max(depth);
+    }
+    deepest
+}
+
+fn unreachable_lines(interp: &Interpreter, languages: &[Option<Language>]) -> Vec<usize> {
+    let lines = &interp.program_lines;
+    let computed = |statement: &str| JUMP_ARGUMENT.captures_iter(statement).any(|c| c[1].parse::<usize>().is_err());
+    if lines.iter().zip(languages).any(|((_, statement), language)| *language == Some(Language::Basic) && computed(statement)) {
+        return Vec::new();
+    }
+    let bodies = procedure_bodies(interp);
+    let body_of: HashMap<&str, (usize, usize)> = bodies.iter().map(|(name, start, end)| (name.as_str(), (*start, *end))).collect();
+    let mut reached = HashSet::new();
+    let mut pending = vec![0];
+    while let Some(index) = pending.pop() {
+        if index >= lines.len() || !reached.insert(index) {
+            continue;
+        }
+        let statement = lines[index].1.trim();
+        let upper = statement.to_ascii_uppercase();
+        let mut next = true;
+        match languages[index] {
+            Some(Language::Basic) => {
+                let targets = LINE_TARGETS.captures_iter(statement).flat_map(|c| {
+                    c[1].split(',').filter_map(|n| n.trim().parse::<usize>().ok()).collect::<Vec<_>>()
+                });
+                pending.extend(targets.filter_map(|number| interp.line_number_map.get(&number).copied()));
+                let keyword = upper.split_whitespace().next().unwrap_or("");
+                next = !matches!(keyword, "GOTO" | "END" | "RETURN" | "CHAIN");
+            }
+            Some(Language::Pilot) => {
+                if let Some(label) = statement.strip_prefix("J:").map(|l| l.trim().trim_start_matches('*')) {
+                    if let Some(&target) = interp.labels.get(label) {
+                        pending.push(target);
+                        next = false;
+                    }
+                }
+                next &= !upper.starts_with("E:");
+            }
+            Some(Language::Logo) => {
+                if let Some(&(_, _, end)) = bodies.iter().find(|&&(_, start, _)| start == index) {
+                    // A definition runs nothing: the run carries on after its END,
+                    // and the body is reached only by calling it
+                    reached.insert(end);
+                    pending.push(end + 1);
+                    next = false;
+                } else {
+                    for word in upper.split(|c: char| c.is_whitespace() || c == '[' || c == ']') {
+                        if let Some(&(start, end)) = body_of.get(word) {
+                            pending.extend(start + 1..end);
+                        }
+                    }
+                }
+                // The last statement of a body returns to its caller
+                next &= !bodies.iter().any(|&(_, _, end)| end == index + 1);
+            }
+            _ => {}
+        }
+        if next {
+            pending.push(index + 1);
+        }
+    }
+    (0..lines.len())
+        .filter(|index| !reached.contains(index) && languages[*index].is_some())
+        .filter(|&index| {
+            let statement = lines[index].1.trim();
+            basic::data_values(statement).is_none() && !statement.strip_prefix("D:").is_some_and(pilot::quiz::is_quiz_data)
+        })
+        .map(|index| *interp.source_lines[index].start())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_constructs_nesting_and_variables() {
+        let source = "10 LET TOTAL = 0\n20 FOR I = 1 TO 3\n30 FOR J = 1 TO 2\n40 IF I > J THEN PRINT I\n\
+                      50 NEXT J\n60 NEXT I\n70 INPUT \"Name\"; N$\n80 LET UNUSED = 5\n90 GOSUB 200\n100 END\n\
+                      200 PRINT TOTAL; N$\n210 RETURN";
+        let stats = analyze_program(source, Language::Basic);
+        assert_eq!(stats.statements, 12);
+        assert_eq!(stats.constructs["PRINT"], 2);
+        assert_eq!(stats.constructs["IF"], 1);
+        assert_eq!(stats.constructs["FOR"], 2);
+        assert_eq!(stats.constructs["GOSUB"], 1);
+        assert_eq!(stats.constructs["LET"], 2);
+        // IF inside two FOR loops
+        assert_eq!(stats.max_nesting, 3);
+        assert_eq!(stats.variables, ["I", "J", "N$", "TOTAL", "UNUSED"]);
+        assert_eq!(stats.unused_variables, ["UNUSED"]);
+        assert_eq!(stats.unreachable_lines, Vec::<usize>::new());
+        assert_eq!(stats.longest_line, Some(LongestLine { line: 4, chars: 24 }));
+    }
+
+    #[test]
+    fn test_basic_unreachable_lines_follow_jumps() {
+        let source = "10 GOTO 40\n20 PRINT \"SKIPPED\"\n30 REM only a comment\n40 IF X THEN 70\n50 PRINT \"MAYBE\"\n\
+                      60 END\n70 PRINT \"THEN\"\n80 END\n90 PRINT \"AFTER END\"\n100 DATA 1, 2";
+        let stats = analyze_program(source, Language::Basic);
+        // Comments and DATA are not counted: DATA is read wherever it is
+        assert_eq!(stats.unreachable_lines, [2, 9]);
+        // A computed jump could land anywhere
+        let computed = analyze_program("10 GOTO X * 10\n20 PRINT 1", Language::Basic);
+        assert_eq!(computed.unreachable_lines, Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_pilot_labels_and_interpolation() {
+        let source = "R: quiz\nA:NAME\nU:SCORE=0\nU:SPARE=1\nC:SCORE > 1\nY:\nT:Well done *NAME*\nJ:DONE\n\
+                      T:Never shown\nL:DONE\nE:\nT:After the end";
+        let stats = analyze_program(source, Language::Pilot);
+        assert_eq!(stats.statements, 11);
+        assert_eq!(stats.constructs["T:"], 3);
+        assert_eq!(stats.constructs["U:"], 2);
+        assert_eq!(stats.constructs["J:"], 1);
+        assert_eq!(stats.max_nesting, 0);
+        assert_eq!(stats.variables, ["NAME", "SCORE", "SPARE"]);
+        assert_eq!(stats.unused_variables, ["SPARE"]);
+        assert_eq!(stats.unreachable_lines, [9, 12]);
+    }
+
+    #[test]
+    fn test_logo_procedures_and_brackets() {
+        let source = "TO SQUARE :SIZE\n  REPEAT 4 [FD :SIZE RT 90]\nEND\nTO UNUSED :N\n  FD 10\nEND\n\
+                      REPEAT 3 [REPEAT 2 [SQUARE 20 RT 60]]";
+        let stats = analyze_program(source, Language::Logo);
+        assert_eq!(stats.constructs["TO"], 2);
+        assert_eq!(stats.constructs["REPEAT"], 3);
+        assert_eq!(stats.constructs["FD"], 2);
+        assert_eq!(stats.constructs["procedure call"], 1);
+        assert_eq!(stats.max_nesting, 2);
+        assert_eq!(stats.variables, ["N", "SIZE"]);
+        assert_eq!(stats.unused_variables, ["N"]);
+        // The body of a procedure nothing calls
+        assert_eq!(stats.unreachable_lines, [5]);
+    }
+}
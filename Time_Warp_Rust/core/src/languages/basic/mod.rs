@@ -0,0 +1,1131 @@
+use anyhow::Result;
+use crate::interpreter::{Interpreter, ExecutionResult, InputValidation, TooDeeplyNested};
+use crate::interpreter::arrays::{element_reference, is_subscript_error, split_top_level, split_top_level_on};
+use crate::graphics::{CoordinateMode, TurtleState};
+use crate::i18n::{tr, trf};
+use crate::interpreter::ScreenMode;
+use crate::interpreter::subprogram::file_argument;
+use crate::interpreter::ticker::FRAME_INTERVAL;
+use crate::interpreter::var_types::VarType;
+use crate::interpreter::segments::{self, Segment};
+use crate::interpreter::warnings::{Promoted, WarningKind};
+use crate::languages::reserved::ReservedWord;
+use crate::utils::error::BasicError;
+use std::time::Duration;
+
+pub mod print_using;
+
+/// First words that make a line BASIC in a mixed program
+pub const KEYWORDS: [&str; 44] = [
+    "LET", "PRINT", "INPUT", "GOTO", "IF", "THEN", "FOR", "NEXT",
+    "GOSUB", "RETURN", "REM", "DIM", "DATA", "READ", "LINE", "CIRCLE",
+    "SCREEN", "CLS", "LOCATE", "LOGVAR", "PLOT", "CHART", "OPTION", "WIDTH",
+    "CHAIN", "COMMON", "CLEAR", "MERGE", "EVERY", "ONTICK", "RESTORE", "RANDOMIZE",
+    "DEFINT", "DEFSNG", "DEFDBL", "DEFSTR", "MSGBOX", "SHELL",
+    "TITLE", "STATUS", "DO", "LOOP", "EXIT", "ON",
+];
+
+pub fn execute(interp: &mut Interpreter, command: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    let trimmed = command.trim();
+    // Blank, or a `'` comment (REM's short form)
+    if trimmed.is_empty() || trimmed.starts_with('\'') {
+        return Ok(ExecutionResult::Continue);
+    }
+    let chain = statements(trimmed);
+    if chain.len() > 1 {
+        return execute_statements(interp, &chain, turtle);
+    }
+    // Determine keyword in a case-insensitive way but preserve original args
+    let mut it = trimmed.splitn(2, char::is_whitespace);
+    let keyword = it.next().unwrap_or("");
+    let args = it.next().unwrap_or("");
+    let kw = keyword.to_uppercase();
+    interp.check_enabled(&kw, &kw)?;
+    
+    match kw.as_str() {
+        "PRINT" => execute_print(interp, args),
+        "LET" => execute_let(interp, args),
+        "INPUT" => execute_input(interp, args),
+        "GOTO" => execute_goto(interp, args),
+        "IF" => execute_if(interp, args, turtle),
+        "FOR" => execute_for(interp, args),
+        "NEXT" => execute_next(interp, args),
+        "DO" => execute_do(interp, args),
+        "LOOP" => execute_loop(interp, args),
+        "EXIT" => execute_exit(interp, args),
+        "GOSUB" => execute_gosub(interp, args),
+        "RETURN" => execute_return(interp),
+        "ON" => execute_on(interp, args),
+        "REM" => Ok(ExecutionResult::Continue), // Comment
+        "DIM" => execute_dim(interp, args),
+        "DATA" => Ok(ExecutionResult::Continue), // Read ahead by index_lines
+        "READ" => execute_read(interp, args),
+        "RESTORE" => execute_restore(interp, args),
+        "RANDOMIZE" => execute_randomize(interp, args),
+        "END" => Ok(ExecutionResult::End),
+        "LINE" => execute_line(interp, args, turtle),
+        "CIRCLE" => execute_circle(interp, args, turtle),
+        "SCREEN" => execute_screen(interp, args, turtle),
+        "CLS" => execute_cls(interp),
+        "LOCATE" => execute_locate(interp, args),
+        "LOGVAR" => {
+            interp.log_variable(args)?;
+            Ok(ExecutionResult::Continue)
+        }
+        "PLOT" | "CHART" => execute_plot(interp, args, turtle),
+        "OPTION" => execute_option(interp, args, turtle),
+        "WIDTH" => execute_width(interp, args),
+        "CHAIN" => execute_chain(interp, args),
+        "COMMON" => execute_common(interp, args),
+        "CLEAR" => {
+            interp.clear_variables();
+            Ok(ExecutionResult::Continue)
+        }
+        "MERGE" => execute_merge(interp, args),
+        "EVERY" => execute_every(interp, args, None),
+        "ONTICK" => execute_every(interp, args, Some(FRAME_INTERVAL)),
+        "DEFINT" | "DEFSNG" | "DEFDBL" | "DEFSTR" => {
+            // DEFINT I-N: names starting with I to N are integers from now on
+            let var_type = VarType::from_statement(&kw).expect("matched a DEF statement");
+            interp.type_defaults.declare(args, var_type)?;
+            Ok(ExecutionResult::Continue)
+        }
+        "MSGBOX" => execute_msgbox(interp, args),
+        "TITLE" | "STATUS" => {
+            // TITLE "name" / STATUS "message": see interpreter::window
+            let text = match string_operand(interp, args) {
+                Some(text) => text?,
+                None => interp.evaluate_expression(args)?.to_string(),
+            };
+            if kw == "TITLE" {
+                interp.set_program_title(&text);
+            } else {
+                interp.set_program_status(&text);
+            }
+            Ok(ExecutionResult::Continue)
+        }
+        "SHELL" => {
+            // SHELL "command": see interpreter::shell for the policy it runs under
+            let command = string_operand(interp, args).ok_or_else(|| {
+                anyhow::Error::new(BasicError::Syntax).context(tr("shell.expects_command"))
+            })??;
+            interp.run_shell(&command)?;
+            Ok(ExecutionResult::Continue)
+        }
+        _ => {
+            // Allow PILOT to issue SCREEN lines by passing through to BASIC executor when keyword matches
+            if keyword.eq_ignore_ascii_case("SCREEN") {
+                return execute_screen(interp, args, turtle);
+            }
+            interp.report_basic_error(BasicError::Syntax, trf("basic.unknown_command", &[&keyword]));
+            Ok(ExecutionResult::Continue)
+        }
+    }
+}
+
+fn execute_screen(interp: &mut Interpreter, args: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // SCREEN mode[, w, h]
+    let parts: Vec<&str> = args.split(',').map(|s| s.trim()).collect();
+    if parts.is_empty() || parts[0].is_empty() {
+        interp.log_error(tr("basic.screen_missing_mode").to_string());
+        return Ok(ExecutionResult::Continue);
+    }
+    let mode_val = interp.evaluate_expression(parts[0]).unwrap_or(0.0) as i32;
+    let mut made_change = false;
+    match mode_val {
+        0 => {
+            // Text mode
+            let cols = if parts.len() > 1 { interp.evaluate_expression(parts[1]).unwrap_or(80.0) as u32 } else { 80 };
+            let rows = if parts.len() > 2 { interp.evaluate_expression(parts[2]).unwrap_or(25.0) as u32 } else { 25 };
+            interp.screen_mode = ScreenMode::Text { cols, rows };
+            made_change = true;
+        }
+        1 => {
+            // Graphics default 640x480 unless overridden
+            let mut w = 640u32;
+            let mut h = 480u32;
+            if parts.len() > 1 { w = interp.evaluate_expression(parts[1]).unwrap_or(w as f64) as u32; }
+            if parts.len() > 2 { h = interp.evaluate_expression(parts[2]).unwrap_or(h as f64) as u32; }
+            interp.screen_mode = ScreenMode::Graphics { width: w, height: h };
+            made_change = true;
+        }
+        2 => {
+            // Graphics default 1024x768 unless overridden
+            let mut w = 1024u32;
+            let mut h = 768u32;
+            if parts.len() > 1 { w = interp.evaluate_expression(parts[1]).unwrap_or(w as f64) as u32; }
+            if parts.len() > 2 { h = interp.evaluate_expression(parts[2]).unwrap_or(h as f64) as u32; }
+            interp.screen_mode = ScreenMode::Graphics { width: w, height: h };
+            made_change = true;
+        }
+        _ => {
+            interp.log_error(trf("basic.screen_unsupported", &[&mode_val]));
+        }
+    }
+
+    if made_change {
+        // Map the mode to the pixel canvas for consistency
+        (turtle.canvas_width, turtle.canvas_height) = interp.screen_mode.canvas_size();
+        // Graphics modes count from the top-left corner, as GW-BASIC listings expect
+        turtle.coordinate_mode = match interp.screen_mode {
+            ScreenMode::Graphics { .. } => CoordinateMode::ScreenTopLeft,
+            _ => CoordinateMode::Centered,
+        };
+        interp.log_output(trf("basic.screen_set", &[&format!("{:?}", interp.screen_mode)]));
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_print(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    if let Some(rest) = strip_keyword(args.trim_start(), "USING") {
+        return execute_print_using(interp, rest);
+    }
+    // Split by commas, respecting quotes and array subscripts
+    let parts: Vec<&str> = split_top_level(args).into_iter().filter(|p| !p.is_empty()).collect();
+
+    if parts.is_empty() {
+        interp.log_output(String::new());
+        return Ok(ExecutionResult::Continue);
+    }
+
+    // Items are separated by a space; a variable's value is kept as its own segment
+    let mut line: Vec<Segment> = Vec::with_capacity(parts.len() * 2);
+    for item in parts {
+        if !line.is_empty() {
+            line.push(Segment::Text(" ".to_string()));
+        }
+        let item_trim = item.trim();
+        if item_trim.starts_with('"') && item_trim.ends_with('"') && item_trim.len() >= 2 {
+            // String literal
+            line.push(Segment::Text(item_trim[1..item_trim.len()-1].to_string()));
+        } else if item_trim.to_uppercase() == "INKEY$" {
+            // Special handling for INKEY$
+            line.push(Segment::Text(interp.get_inkey()));
+        } else if let Some(text) = interp.element_text(item_trim) {
+            line.push(print_segment(interp, item_trim, text?));
+        } else if let Some(text) = string_value(interp, item_trim)? {
+            line.push(print_segment(interp, item_trim, text));
+        } else {
+            // Try numeric expression first
+            match interp.evaluate_expression(item_trim) {
+                Ok(v) => line.push(print_segment(interp, item_trim, v.to_string())),
+                Err(e) if stops_statement(&e) => return Err(e),
+                Err(_) => {
+                    // Try variable lookup (string or numeric) before interpolation
+                    if let Some(s) = interp.string_variables.get(item_trim) {
+                        line.push(print_segment(interp, item_trim, s.clone()));
+                    } else if let Some(n) = interp.variables.get(item_trim) {
+                        line.push(print_segment(interp, item_trim, n.to_string()));
+                    } else {
+                        // Fallback: interpolate *VAR* style
+                        line.extend(interp.interpolate_segments(item_trim));
+                    }
+                }
+            }
+        }
+    }
+    interp.speak_output_line(&segments::join(&line));
+    interp.log_program_segments(line);
+    Ok(ExecutionResult::Continue)
+}
+
+/// Value of `expr` when it is a string expression like `A$ + "!"`
+///
+/// One that fails is left to the raw-text fallbacks, as a failed number is,
+/// unless the failure stops the statement.
+fn string_value(interp: &Interpreter, expr: &str) -> Result<Option<String>> {
+    match interp.evaluate_text(expr) {
+        Some(Ok(text)) => Ok(Some(text)),
+        Some(Err(e)) if stops_statement(&e) => Err(e),
+        _ => Ok(None),
+    }
+}
+
+/// A PRINT item's value, marked as a variable's when the item names a variable or array element
+fn print_segment(interp: &Interpreter, item: &str, value: String) -> Segment {
+    let names_variable = interp.variables.contains_key(item)
+        || interp.variables.contains_key(&item.to_uppercase())
+        || interp.string_variables.contains_key(item)
+        || element_reference(item).is_some_and(|(name, _)| interp.arrays.contains_key(&name));
+    if names_variable {
+        Segment::Variable { name: item.to_string(), value }
+    } else {
+        Segment::Text(value)
+    }
+}
+
+/// Errors PRINT, LET, and IF report instead of falling back to text or false:
+/// a bad subscript, a warning strict mode made an error, a reserved word, or nesting past a limit
+fn stops_statement(error: &anyhow::Error) -> bool {
+    is_subscript_error(error) || error.is::<Promoted>() || error.is::<ReservedWord>() || error.is::<TooDeeplyNested>()
+}
+
+/// `args` without a leading `keyword` (any case), if it starts with one
+fn strip_keyword<'a>(args: &'a str, keyword: &str) -> Option<&'a str> {
+    let head = args.get(..keyword.len())?;
+    let rest = &args[keyword.len()..];
+    let ends_word = rest.chars().next().is_none_or(|c| c.is_whitespace() || c == '"');
+    (head.eq_ignore_ascii_case(keyword) && ends_word).then_some(rest)
+}
+
+fn execute_print_using(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // PRINT USING "picture"; value; value ...  (the picture may be a string variable)
+    // Split as PRINT does, so `MAX(1, 2)` stays one value
+    let parts = split_top_level_on(args, &[';', ',']);
+
+    let picture = parts[0];
+    let format = if picture.len() >= 2 && picture.starts_with('"') && picture.ends_with('"') {
+        picture[1..picture.len() - 1].to_string()
+    } else if let Some(s) = interp.string_variables.get(picture).filter(|_| picture.ends_with('$')) {
+        s.clone()
+    } else {
+        return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.print_using_format")));
+    };
+
+    let mut values = Vec::new();
+    for item in parts[1..].iter().filter(|p| !p.is_empty()) {
+        // A string literal, string variable, or string function such as `LEFT$(A$, 2)`
+        if item.starts_with('"') || item.split('(').next().is_some_and(|name| name.trim_end().ends_with('$')) {
+            return Err(BasicError::TypeMismatch.into());
+        }
+        values.push(interp.evaluate_expression(item)?);
+    }
+    let line = print_using::format_using(&format, &values)?;
+    interp.speak_output_line(&line);
+    interp.log_program_output(line);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_let(interp: &mut Interpreter, assignment: &str) -> Result<ExecutionResult> {
+    if let Some(pos) = assignment.find('=') {
+        let var_name = assignment[..pos].trim().to_string();
+        let expr = assignment[pos + 1..].trim();
+        
+        // Special handling for INKEY$
+        if expr.trim().to_uppercase() == "INKEY$" {
+            let key = interp.get_inkey();
+            interp.assign_text(&var_name, key)?;
+            return Ok(ExecutionResult::Continue);
+        }
+        if let Some(count) = input_keys_count(interp, expr) {
+            let count = count?;
+            if interp.input_callback.is_none() {
+                interp.start_key_input(&var_name, count);
+                return Ok(ExecutionResult::WaitForInput);
+            }
+            let keys = interp.request_keys(count);
+            interp.assign_text(&var_name, keys)?;
+            return Ok(ExecutionResult::Continue);
+        }
+        if let Some(text) = interp.element_text(expr) {
+            interp.assign_text(&var_name, text?)?;
+            return Ok(ExecutionResult::Continue);
+        }
+        let text = string_value(interp, expr)?;
+        let number = if text.is_some() { None } else { Some(interp.evaluate_expression(expr)) };
+        
+        match number {
+            Some(Ok(value)) => {
+                interp.assign_number(&var_name, value)?;
+            }
+            Some(Err(e)) if stops_statement(&e) => return Err(e),
+            _ => {
+                // A string expression's value, or else a string literal or raw text
+                let val = if let Some(text) = text {
+                    text
+                } else if expr.starts_with('"') && expr.ends_with('"') && expr.len() >= 2 {
+                    expr[1..expr.len()-1].to_string()
+                } else {
+                    expr.to_string()
+                };
+                if !interp.holds_text(&var_name) && element_reference(&var_name).is_none() {
+                    let message = trf("warning.string_for_number", &[&var_name, &val]);
+                    interp.warn(WarningKind::StringForNumber, message)?;
+                }
+                interp.assign_text(&var_name, val)?;
+            }
+        }
+    }
+    
+    Ok(ExecutionResult::Continue)
+}
+
+/// Keys asked for by `INPUT$(n)`, or `None` when `expr` is something else
+///
+/// `n` must be a whole number from 1 to 255, as in GW-BASIC.
+fn input_keys_count(interp: &Interpreter, expr: &str) -> Option<Result<usize>> {
+    let expr = expr.trim();
+    let head = expr.get(..7).filter(|head| head.eq_ignore_ascii_case("INPUT$("))?;
+    let inner = expr[head.len()..].strip_suffix(')')?;
+    Some(interp.evaluate_expression(inner).and_then(|n| {
+        if n.fract() == 0.0 && (1.0..=255.0).contains(&n) {
+            Ok(n as usize)
+        } else {
+            Err(anyhow::Error::new(BasicError::IllegalFunctionCall).context(trf("basic.input_keys_range", &[&n])))
+        }
+    }))
+}
+
+fn execute_input(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // INPUT ["prompt"(;|,)] VAR [AS NUMBER] [RANGE lo TO hi]
+    if let Some(error) = interp.input_error.take() {
+        return Err(error);
+    }
+    let (prompt, rest) = split_input_prompt(args);
+    // ASCII-only uppercasing keeps byte offsets valid for slicing `rest`
+    let upper = rest.to_ascii_uppercase();
+    let var_end = upper.find(" AS ").or_else(|| upper.find(" RANGE ")).unwrap_or(rest.len());
+    let var_name = rest[..var_end].trim().to_string();
+    let prompt = prompt.unwrap_or_else(|| format!("{}? ", var_name));
+    if var_name.is_empty() {
+        return Err(anyhow::anyhow!(tr("basic.input_missing_variable")));
+    }
+    interp.check_not_reserved(&var_name)?;
+    let validation = parse_input_validation(interp, &upper[var_end..], &rest[var_end..])?;
+
+    if let Some(mut validation) = validation {
+        if interp.input_callback.is_none() {
+            interp.start_validated_input(&prompt, &var_name, validation);
+            return Ok(ExecutionResult::WaitForInput);
+        }
+        loop {
+            let answer = interp.request_input(&prompt);
+            match validation.check(&answer) {
+                Ok(num) => {
+                    interp.assign_number(&var_name, num)?;
+                    return Ok(ExecutionResult::Continue);
+                }
+                Err(reason) => {
+                    if let Some(error) = validation.reject(reason.clone(), &var_name, interp.limits.max_input_retries) {
+                        return Err(error);
+                    }
+                    interp.log_output(format!("⚠️ {}", reason));
+                }
+            }
+        }
+    }
+
+    // If an input callback is wired (tests or headless), use it synchronously
+    if interp.input_callback.is_some() {
+        let input_value = interp.request_input(&prompt);
+        interp.assign_answer(&var_name, &input_value)?;
+        return Ok(ExecutionResult::Continue);
+    }
+
+    // Otherwise, initiate a pending UI input and pause execution
+    interp.start_input_request(&prompt, &var_name, true);
+    Ok(ExecutionResult::WaitForInput)
+}
+
+/// Split off a leading `"prompt";` (adds `? `) or `"prompt",` (as written)
+pub(crate) fn split_input_prompt(args: &str) -> (Option<String>, &str) {
+    let args = args.trim();
+    let Some(body) = args.strip_prefix('"') else { return (None, args) };
+    let Some(close) = body.find('"') else { return (None, args) };
+    let text = &body[..close];
+    let after = body[close + 1..].trim_start();
+    if let Some(rest) = after.strip_prefix(';') {
+        (Some(format!("{}? ", text)), rest)
+    } else if let Some(rest) = after.strip_prefix(',') {
+        (Some(text.to_string()), rest)
+    } else {
+        (None, args)
+    }
+}
+
+/// `AS NUMBER` and/or `RANGE lo TO hi` after the INPUT variable
+fn parse_input_validation(interp: &Interpreter, upper: &str, original: &str) -> Result<Option<InputValidation>> {
+    let mut clause = upper.trim();
+    if clause.is_empty() {
+        return Ok(None);
+    }
+    if let Some(rest) = clause.strip_prefix("AS NUMBER") {
+        clause = rest.trim();
+    } else if !clause.starts_with("RANGE ") {
+        return Err(anyhow::anyhow!(tr("basic.input_expects_clause")));
+    }
+    let mut validation = InputValidation::default();
+    if let Some(range) = clause.strip_prefix("RANGE ") {
+        // Evaluate the bounds from the original text so string case is kept
+        let offset = original.len() - range.len();
+        let range_src = &original[offset..];
+        let to = range.find(" TO ").ok_or_else(|| anyhow::anyhow!(tr("basic.input_range_missing_to")))?;
+        validation.min = Some(interp.evaluate_expression(range_src[..to].trim())?);
+        validation.max = Some(interp.evaluate_expression(range_src[to + 4..].trim())?);
+    } else if !clause.is_empty() {
+        return Err(anyhow::anyhow!(tr("basic.input_expects_range")));
+    }
+    Ok(Some(validation))
+}
+
+fn execute_msgbox(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // MSGBOX message [, YESNO, var]: var gets 1 (or "YES") for Yes, 0 (or "NO") for No
+    let parts = split_top_level(args);
+    let message = match string_operand(interp, parts[0]) {
+        Some(text) => text?,
+        None => interp.evaluate_expression(parts[0])?.to_string(),
+    };
+    match parts[1..] {
+        [] => interp.show_dialog(&message, false, ""),
+        [style, var] if style.eq_ignore_ascii_case("YESNO") && !var.is_empty() => interp.show_dialog(&message, true, var),
+        _ => Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.msgbox_expects"))),
+    }
+}
+
+fn execute_goto(interp: &mut Interpreter, line_num: &str) -> Result<ExecutionResult> {
+    if let Ok(num) = line_num.trim().parse::<usize>() {
+        if let Some(idx) = find_line_index(interp, num) {
+            warn_jump_into_loop(interp, num, idx)?;
+            return Ok(ExecutionResult::Jump(idx));
+        } else {
+            interp.report_basic_error(BasicError::UndefinedLineNumber, format!("❌ {}", trf("basic.goto_failed", &[&num])));
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+/// Warn when GOTO `num` lands in a FOR loop's body while that loop is not running
+///
+/// Its NEXT then finds no FOR (or another loop's), which is hard to trace back here.
+fn warn_jump_into_loop(interp: &mut Interpreter, num: usize, idx: usize) -> Result<()> {
+    let entered = interp.for_loops.iter().copied().find(|&(start, next)| {
+        start < idx && idx <= next && !interp.for_stack.iter().any(|f| f.for_line == start)
+    });
+    if let Some((start, _)) = entered {
+        let header = interp.program_lines[start].1.trim().to_string();
+        interp.warn(WarningKind::JumpIntoLoop, trf("warning.jump_into_loop", &[&num, &header]))?;
+    }
+    Ok(())
+}
+
+fn execute_if(interp: &mut Interpreter, condition: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // IF <expr> THEN <command or line> [ELSE <command or line>]
+    let mut condition = condition;
+    // A branch that is itself an IF loops here rather than recursing, however long the chain
+    loop {
+        // ASCII-only uppercasing keeps byte offsets valid for slicing `condition`
+        let cond_upper = condition.to_ascii_uppercase();
+        let Some(pos) = cond_upper.find("THEN") else {
+            interp.report_basic_error(BasicError::Syntax, tr("basic.if_missing_then").to_string());
+            return Ok(ExecutionResult::Continue);
+        };
+        let cond_str = condition[..pos].trim();
+        let then_str = condition[pos + 4..].trim();
+        let (then_str, else_str) = match find_keyword(then_str, "ELSE") {
+            Some(at) => (then_str[..at].trim(), Some(then_str[at + 4..].trim())),
+            None => (then_str, None),
+        };
+        let truthy = condition_true(interp, cond_str)?;
+        let Some(branch) = (if truthy { Some(then_str) } else { else_str }) else {
+            return Ok(ExecutionResult::Continue);
+        };
+        if branch.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+            // THEN/ELSE <line>
+            interp.check_enabled("GOTO", "GOTO")?;
+            return execute_goto(interp, branch);
+        }
+        match strip_keyword(branch, "IF") {
+            Some(nested) if !nested.is_empty() => {
+                interp.check_enabled("IF", "IF")?;
+                condition = nested;
+            }
+            // THEN/ELSE <command>
+            _ => return execute(interp, branch, turtle),
+        }
+    }
+}
+
+/// Whether an IF, DO, or LOOP condition holds
+///
+/// A string comparison is tried first; a condition that cannot be evaluated
+/// is false unless its error stops the statement.
+fn condition_true(interp: &mut Interpreter, condition: &str) -> Result<bool> {
+    match string_comparison(interp, condition) {
+        Some(equal) => equal,
+        None => match interp.evaluate_expression(condition) {
+            Ok(value) => interp.condition_holds(value),
+            Err(e) if stops_statement(&e) => Err(e),
+            Err(_) => Ok(false),
+        },
+    }
+}
+
+/// `a$ = b$` or `a$ <> b$` between string variables, elements, or literals (`IF K$ = "Y"`)
+///
+/// `None` when either side is not a string, leaving the condition to the
+/// numeric evaluator.
+fn string_comparison(interp: &Interpreter, condition: &str) -> Option<Result<bool>> {
+    let mut in_string = false;
+    let (at, op) = condition.char_indices().find_map(|(i, c)| {
+        if c == '"' {
+            in_string = !in_string;
+        }
+        match (in_string, c) {
+            (false, '<') if condition[i..].starts_with("<>") => Some((i, "<>")),
+            (false, '=') => Some((i, "=")),
+            _ => None,
+        }
+    })?;
+    let left = string_operand(interp, &condition[..at])?;
+    let right = string_operand(interp, &condition[at + op.len()..])?;
+    Some(left.and_then(|left| right.map(|right| (left == right) == (op == "="))))
+}
+
+/// Text of a string expression, a string variable, or a string array element
+fn string_operand(interp: &Interpreter, text: &str) -> Option<Result<String>> {
+    let text = text.trim();
+    if let Some(value) = interp.evaluate_text(text) {
+        return Some(value);
+    }
+    if interp.holds_text(text) && element_reference(text).is_none() {
+        let value = interp.string_variables.get(text).or_else(|| interp.string_variables.get(&text.to_uppercase()));
+        return Some(Ok(value.cloned().unwrap_or_default()));
+    }
+    interp.element_text(text)
+}
+
+/// Byte offset of `keyword` standing as its own word outside string literals
+pub(crate) fn find_keyword(text: &str, keyword: &str) -> Option<usize> {
+    let upper = text.to_ascii_uppercase();
+    let start = upper.find(keyword)?;
+    // Quotes before the first match still count toward whether it is in a string
+    let mut in_string = upper[..start].matches('"').count() % 2 == 1;
+    for (i, c) in upper[start..].char_indices().map(|(i, c)| (i + start, c)) {
+        if c == '"' {
+            in_string = !in_string;
+        } else if !in_string && upper[i..].starts_with(keyword) {
+            let word_start = upper[..i].chars().next_back().is_none_or(char::is_whitespace);
+            let word_end = upper[i + keyword.len()..].chars().next().is_none_or(char::is_whitespace);
+            if word_start && word_end {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+fn execute_for(interp: &mut Interpreter, params: &str) -> Result<ExecutionResult> {
+    // FOR var = start TO end [STEP step]
+    let params_upper = params.to_ascii_uppercase();
+    
+    // Find '=' and 'TO'
+    let eq_pos = params.find('=').ok_or_else(|| anyhow::anyhow!(tr("basic.for_missing_equals")))?;
+    let to_pos = params_upper.find(" TO ").ok_or_else(|| anyhow::anyhow!(tr("basic.for_missing_to")))?;
+    
+    let var_name = params[..eq_pos].trim().to_string();
+    interp.check_not_reserved(&var_name)?;
+    let start_expr = params[eq_pos + 1..to_pos].trim();
+    
+    // Check for STEP
+    let (end_expr, step_val) = if let Some(step_pos) = params_upper.find(" STEP ") {
+        let end = params[to_pos + 4..step_pos].trim();
+        let step = params[step_pos + 6..].trim();
+        (end, interp.evaluate_expression(step)?)
+    } else {
+        (params[to_pos + 4..].trim(), 1.0)
+    };
+    
+    let start = interp.evaluate_expression(start_expr)?;
+    let mut end = interp.evaluate_expression(end_expr)?;
+    
+    // Guard against enormous spans before the loop starts
+    if step_val == 0.0 {
+        return Err(anyhow::anyhow!(tr("basic.for_step_zero")));
+    }
+    let span = ((end - start) / step_val).floor() + 1.0;
+    let limit = interp.limits.max_for_iterations;
+    let allowed = interp.check_loop_span(tr("limit.for_span"), span, limit, tr("settings.limits.max_for_iterations"))?;
+    if (allowed as f64) < span {
+        end = start + step_val * (allowed as f64 - 1.0);
+    }
+    
+    // Initialize loop variable
+    interp.variables.insert(var_name.clone(), start);
+    interp.note_assignment(&var_name);
+    
+    // Push FOR context onto stack
+    interp.for_stack.push(crate::interpreter::ForContext {
+        var_name,
+        end_value: end,
+        step: step_val,
+        for_line: interp.current_line,
+    });
+    
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_next(interp: &mut Interpreter, var: &str) -> Result<ExecutionResult> {
+    // NEXT var
+    let var_name = var.trim();
+    
+    if let Some(ctx) = interp.for_stack.last() {
+        // Verify variable name matches
+        if !var_name.is_empty() && ctx.var_name != var_name {
+            return Err(anyhow::anyhow!(trf("basic.next_mismatch", &[&var_name, &ctx.var_name])));
+        }
+        
+        // Get current value
+        let current = interp.variables.get(&ctx.var_name).copied().unwrap_or(0.0);
+        let new_val = current + ctx.step;
+        
+        // Check if loop should continue
+        let should_continue = if ctx.step >= 0.0 {
+            new_val <= ctx.end_value
+        } else {
+            new_val >= ctx.end_value
+        };
+        
+        if should_continue {
+            let (name, for_line) = (ctx.var_name.clone(), ctx.for_line);
+            interp.variables.insert(name.clone(), new_val);
+            interp.note_assignment(&name);
+            return Ok(ExecutionResult::Jump(for_line + 1));
+        } else {
+            // Loop complete, pop context
+            interp.for_stack.pop();
+        }
+    } else {
+        return Err(BasicError::NextWithoutFor.into());
+    }
+    
+    Ok(ExecutionResult::Continue)
+}
+
+/// Whether a DO or LOOP's `WHILE cond` or `UNTIL cond` lets the loop go on; `None` without one
+fn loop_condition(interp: &mut Interpreter, args: &str, statement: &str) -> Result<Option<bool>> {
+    let args = args.trim();
+    if args.is_empty() {
+        return Ok(None);
+    }
+    if let Some(condition) = strip_keyword(args, "WHILE") {
+        return condition_true(interp, condition).map(Some);
+    }
+    if let Some(condition) = strip_keyword(args, "UNTIL") {
+        return condition_true(interp, condition).map(|holds| Some(!holds));
+    }
+    Err(anyhow::Error::new(BasicError::Syntax).context(trf("basic.loop_condition", &[&statement])))
+}
+
+/// Index of the NEXT or LOOP closing the loop that starts at `start`
+fn loop_end(loops: &[(usize, usize)], start: usize) -> Option<usize> {
+    loops.iter().find(|&&(open, _)| open == start).map(|&(_, end)| end)
+}
+
+fn execute_do(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // DO [WHILE cond | UNTIL cond]: a false test skips the body, past its LOOP
+    if loop_condition(interp, args, "DO")? == Some(false) {
+        let end = loop_end(&interp.do_loops, interp.current_line)
+            .ok_or_else(|| anyhow::Error::new(BasicError::Syntax).context(tr("basic.do_without_loop")))?;
+        return Ok(ExecutionResult::Jump(end + 1));
+    }
+    interp.do_stack.push(interp.current_line);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_loop(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // LOOP [WHILE cond | UNTIL cond]: back to the DO, which tests its own condition again
+    let Some(&start) = interp.do_stack.last() else {
+        return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.loop_without_do")));
+    };
+    let goes_on = loop_condition(interp, args, "LOOP")? != Some(false);
+    interp.do_stack.pop();
+    Ok(if goes_on { ExecutionResult::Jump(start) } else { ExecutionResult::Continue })
+}
+
+fn execute_exit(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // EXIT FOR | EXIT DO: leave the innermost loop of that kind, past its NEXT or LOOP
+    let kind = args.trim().to_uppercase();
+    let (start, loops) = match kind.as_str() {
+        "FOR" => (interp.for_stack.last().map(|f| f.for_line), &interp.for_loops),
+        "DO" => (interp.do_stack.last().copied(), &interp.do_loops),
+        _ => return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.exit_expects"))),
+    };
+    let Some(start) = start else {
+        return Err(anyhow::Error::new(BasicError::Syntax).context(trf("basic.exit_outside_loop", &[&kind])));
+    };
+    let end = loop_end(loops, start)
+        .ok_or_else(|| anyhow::Error::new(BasicError::Syntax).context(trf("basic.exit_unclosed", &[&kind])))?;
+    // Loops opened inside this one end with it; the loop variable keeps the value it had
+    let inside = |line: usize| start < line && line < end;
+    interp.for_stack.retain(|f| !inside(f.for_line));
+    interp.do_stack.retain(|&line| !inside(line));
+    if kind == "FOR" {
+        interp.for_stack.pop();
+    } else {
+        interp.do_stack.pop();
+    }
+    Ok(ExecutionResult::Jump(end + 1))
+}
+
+fn execute_gosub(interp: &mut Interpreter, line_num: &str) -> Result<ExecutionResult> {
+    if let Ok(num) = line_num.trim().parse::<usize>() {
+        interp.push_gosub(interp.current_line);
+        if let Some(idx) = find_line_index(interp, num) {
+            return Ok(ExecutionResult::Jump(idx));
+        } else {
+            interp.report_basic_error(BasicError::UndefinedLineNumber, trf("basic.gosub_failed", &[&num]));
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_on(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // ON n GOTO a, b, c / ON n GOSUB a, b, c - to the nth line; 0 or past the list carries on
+    let Some((keyword, at)) = ["GOTO", "GOSUB"].into_iter().find_map(|k| Some((k, find_keyword(args, k)?))) else {
+        return Err(anyhow::Error::new(BasicError::Syntax).context(tr("basic.on_expects")));
+    };
+    interp.check_enabled(keyword, keyword)?;
+    let selector = interp.evaluate_expression(args[..at].trim())?.round();
+    if !(0.0..=255.0).contains(&selector) {
+        return Err(anyhow::Error::new(BasicError::IllegalFunctionCall).context(trf("basic.on_range", &[&selector])));
+    }
+    let targets = split_top_level(&args[at + keyword.len()..]);
+    let Some(target) = (selector as usize).checked_sub(1).and_then(|n| targets.get(n)) else {
+        return Ok(ExecutionResult::Continue);
+    };
+    if keyword == "GOTO" {
+        execute_goto(interp, target)
+    } else {
+        execute_gosub(interp, target)
+    }
+}
+
+fn execute_return(interp: &mut Interpreter) -> Result<ExecutionResult> {
+    if let Some(line) = interp.pop_gosub() {
+        Ok(ExecutionResult::Jump(line + 1))
+    } else {
+        interp.report_basic_error(BasicError::ReturnWithoutGosub, BasicError::ReturnWithoutGosub.to_string());
+        Ok(ExecutionResult::Continue)
+    }
+}
+
+// Helper: Find the index of a program line by BASIC line number
+fn find_line_index(interp: &Interpreter, num: usize) -> Option<usize> {
+    // Use line_number_map for O(1) lookup instead of O(n) scan
+    interp.line_number_map.get(&num).copied()
+}
+
+fn execute_line(interp: &mut Interpreter, args: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // LINE x1, y1, x2, y2
+    let parts: Vec<&str> = args.split(',').map(|s| s.trim()).collect();
+    if parts.len() >= 4 {
+        let x1 = interp.evaluate_expression(parts[0])? as f32;
+        let y1 = interp.evaluate_expression(parts[1])? as f32;
+        let x2 = interp.evaluate_expression(parts[2])? as f32;
+        let y2 = interp.evaluate_expression(parts[3])? as f32;
+        let (x1, y1) = turtle.basic_point(x1, y1);
+        let (x2, y2) = turtle.basic_point(x2, y2);
+        
+        // Draw line by moving turtle with pen down
+        let old_pen = turtle.pen_down;
+        turtle.pen_down = false;
+        turtle.goto(x1, y1);
+        turtle.pen_down = true;
+        turtle.goto(x2, y2);
+        turtle.pen_down = old_pen;
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_option(interp: &mut Interpreter, args: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // OPTION ERRORS CLASSIC | MODERN, OPTION BASE 0 | 1, OPTION COORDS SCREEN | CENTERED
+    let words: Vec<String> = args.split_whitespace().map(|w| w.to_uppercase()).collect();
+    match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["ERRORS", "CLASSIC"] => interp.classic_errors = true,
+        ["ERRORS", "MODERN"] => interp.classic_errors = false,
+        ["BASE", "0"] => interp.set_option_base(0)?,
+        ["BASE", "1"] => interp.set_option_base(1)?,
+        ["COORDS", "SCREEN"] => turtle.coordinate_mode = CoordinateMode::ScreenTopLeft,
+        ["COORDS", "CENTERED"] => turtle.coordinate_mode = CoordinateMode::Centered,
+        _ => return Err(anyhow::anyhow!(tr("basic.option_expects"))),
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_dim(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // DIM A(10), NAMES$(10), GRID(20, 20)
+    for declaration in split_top_level(args) {
+        interp.dim_array(declaration)?;
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+/// Each `FOR … TO` statement's index in `lines` with its matching `NEXT`
+///
+/// Loops pair up by nesting; a FOR without a NEXT is left out.
+pub fn for_loops(lines: &[(Option<usize>, String)]) -> Vec<(usize, usize)> {
+    pair_loops(lines, |upper| strip_keyword(upper, "FOR").is_some_and(|rest| rest.contains(" TO ")), "NEXT")
+}
+
+/// Each `DO` statement's index in `lines` with its matching `LOOP`, paired as [`for_loops`] pairs
+pub fn do_loops(lines: &[(Option<usize>, String)]) -> Vec<(usize, usize)> {
+    pair_loops(lines, |upper| strip_keyword(upper, "DO").is_some(), "LOOP")
+}
+
+/// Statements `opens` accepts paired by nesting with those starting with `close`
+fn pair_loops(lines: &[(Option<usize>, String)], opens: impl Fn(&str) -> bool, close: &str) -> Vec<(usize, usize)> {
+    let mut open = Vec::new();
+    let mut loops = Vec::new();
+    for (idx, (_, statement)) in lines.iter().enumerate() {
+        let upper = statement.trim_start().to_ascii_uppercase();
+        if opens(&upper) {
+            open.push(idx);
+        } else if strip_keyword(&upper, close).is_some() {
+            if let Some(start) = open.pop() {
+                loops.push((start, idx));
+            }
+        }
+    }
+    loops
+}
+
+/// The statements of a line joined with `:`, as written
+///
+/// Colons inside strings do not split, and an `IF`, `REM`, or `'` takes the
+/// rest of the line: everything after `THEN` is the IF's to run or skip.
+pub fn statements(line: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let (mut start, mut in_string) = (0, false);
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            ':' if !in_string => {
+                let piece = line[start..i].trim();
+                if takes_rest(piece) {
+                    break;
+                }
+                pieces.push(piece);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    pieces.push(line[start..].trim());
+    pieces.retain(|piece| !piece.is_empty());
+    pieces
+}
+
+fn takes_rest(statement: &str) -> bool {
+    statement.starts_with('\'') || ["IF", "REM"].iter().any(|k| strip_keyword(statement, k).is_some())
+}
+
+/// Run the statements of one line in order; a jump, END, or wait for input leaves the rest
+fn execute_statements(interp: &mut Interpreter, chain: &[&str], turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    for statement in chain {
+        match execute(interp, statement, turtle)? {
+            ExecutionResult::Continue => {}
+            result => return Ok(result),
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+/// Values of a `DATA` statement, unquoted; `None` for any other statement
+pub fn data_values(statement: &str) -> Option<Vec<String>> {
+    let values = strip_keyword(statement.trim_start(), "DATA")?;
+    let unquote = |v: &str| v.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(v).to_string();
+    Some(split_top_level(values).into_iter().map(unquote).collect())
+}
+
+fn execute_read(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // READ A, NAMES$(I), GRID(I, J) - next DATA value into each
+    for target in split_top_level(args) {
+        let (_, value) = interp.data.get(interp.data_pointer).cloned().ok_or(BasicError::OutOfData)?;
+        interp.data_pointer += 1;
+        if interp.holds_text(target) {
+            interp.assign_text(target, value)?;
+        } else {
+            let number = value.trim().parse::<f64>().map_err(|_| {
+                anyhow::Error::new(BasicError::Syntax).context(trf("basic.read_not_number", &[&target, &value]))
+            })?;
+            interp.assign_number(target, number)?;
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_restore(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // RESTORE - READ starts over; RESTORE n - from the first DATA at or after line n
+    let from = match args.trim() {
+        "" => 0,
+        line => {
+            let number = line.parse::<usize>().map_err(|_| BasicError::Syntax)?;
+            find_line_index(interp, number).ok_or(BasicError::UndefinedLineNumber)?
+        }
+    };
+    interp.data_pointer = interp.data.iter().position(|(idx, _)| *idx >= from).unwrap_or(interp.data.len());
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_randomize(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // RANDOMIZE n - RND starts over from seed n; bare RANDOMIZE takes a seed from the run's own numbers
+    match args.trim() {
+        "" => interp.reseed_random(),
+        seed => {
+            let seed = interp.evaluate_expression(seed)?;
+            interp.seed_random(seed.to_bits());
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_width(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // WIDTH n - wrap PRINT output at n columns (WIDTH 0 turns wrapping off)
+    let width = interp.evaluate_expression(args.trim())?;
+    if !(0.0..=1000.0).contains(&width) {
+        return Err(BasicError::IllegalFunctionCall.into());
+    }
+    interp.text_width = Some(width as usize);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_chain(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // CHAIN "file" [, ALL]  or  CHAIN F$ [, ALL]
+    let (target, option) = match args.rsplit_once(',') {
+        Some((target, option)) if option.trim().eq_ignore_ascii_case("ALL") => (target.trim_end_matches([',', ' ']), true),
+        _ => (args, false),
+    };
+    let target = target.trim();
+    let path = match interp.string_variables.get(target) {
+        Some(name) if target.ends_with('$') => name.clone(),
+        _ => file_argument(target).to_string(),
+    };
+    interp.chain_file(&path, option)
+}
+
+fn execute_every(interp: &mut Interpreter, args: &str, interval: Option<Duration>) -> Result<ExecutionResult> {
+    // EVERY seconds GOSUB line, ONTICK GOSUB line (every frame), EVERY OFF / ONTICK OFF
+    let upper = args.trim().to_ascii_uppercase();
+    if upper == "OFF" {
+        interp.ticker = None;
+        return Ok(ExecutionResult::Continue);
+    }
+    let (seconds, line) = upper.split_once("GOSUB").ok_or(BasicError::Syntax)?;
+    let interval = match interval {
+        Some(interval) if seconds.trim().is_empty() => interval,
+        Some(_) => return Err(BasicError::Syntax.into()),
+        None => {
+            let seconds = interp.evaluate_expression(seconds.trim())?;
+            if !(seconds > 0.0 && seconds.is_finite()) {
+                return Err(BasicError::IllegalFunctionCall.into());
+            }
+            Duration::from_secs_f64(seconds)
+        }
+    };
+    let line = line.trim().parse::<usize>().map_err(|_| BasicError::Syntax)?;
+    if find_line_index(interp, line).is_none() {
+        return Err(BasicError::UndefinedLineNumber.into());
+    }
+    interp.schedule_ticks(interval, line);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_merge(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // MERGE "file"  or  MERGE F$
+    let target = args.trim();
+    let path = match interp.string_variables.get(target) {
+        Some(name) if target.ends_with('$') => name.clone(),
+        _ => file_argument(target).to_string(),
+    };
+    let replaced = interp.merge_file(&path)?;
+    if !replaced.is_empty() {
+        let numbers = replaced.iter().map(usize::to_string).collect::<Vec<_>>().join(", ");
+        interp.log_output(if replaced.len() == 1 {
+            trf("basic.merge_replaced_one", &[&path, &numbers])
+        } else {
+            trf("basic.merge_replaced_many", &[&path, &numbers])
+        });
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_common(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // COMMON A, B$, ... - variables kept by the next CHAIN or program loaded
+    for name in args.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if !interp.common_variables.iter().any(|v| v == name) {
+            interp.common_variables.push(name.to_string());
+        }
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_plot(interp: &mut Interpreter, args: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // PLOT Y  or  PLOT Y VS X  (series recorded with LOGVAR)
+    let upper = args.trim().to_uppercase();
+    let (y_name, x_name) = match upper.split_once(" VS ") {
+        Some((y, x)) => (y.trim(), Some(x.trim())),
+        None => (upper.as_str(), None),
+    };
+    if y_name.is_empty() {
+        return Err(anyhow::anyhow!(tr("basic.plot_missing_series")));
+    }
+    let series = |name: &str| {
+        interp.recorded_series.get(name).cloned()
+            .ok_or_else(|| anyhow::anyhow!(trf("basic.plot_no_series", &[&name])))
+    };
+    let ys = series(y_name)?;
+    let xs = x_name.map(series).transpose()?;
+    let title = match x_name {
+        Some(x) => format!("{} VS {}", y_name, x),
+        None => y_name.to_string(),
+    };
+    crate::graphics::chart::plot(turtle, &ys, xs.as_deref(), &title)?;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_circle(interp: &mut Interpreter, args: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // CIRCLE x, y, radius
+    let parts: Vec<&str> = args.split(',').map(|s| s.trim()).collect();
+    if parts.len() >= 3 {
+        let cx = interp.evaluate_expression(parts[0])? as f32;
+        let cy = interp.evaluate_expression(parts[1])? as f32;
+        let r = interp.evaluate_expression(parts[2])? as f32;
+        let (cx, cy) = turtle.basic_point(cx, cy);
+        
+        // Approximate circle with line segments
+        let old_pen = turtle.pen_down;
+        turtle.pen_down = false;
+        
+        let segments = 36;
+        let angle_step = 360.0 / segments as f32;
+        
+        // Start at top of circle
+        let start_x = cx;
+        let start_y = cy + r;
+        turtle.goto(start_x, start_y);
+        turtle.pen_down = true;
+        
+        for i in 1..=segments {
+            let angle = (i as f32 * angle_step).to_radians();
+            let x = cx + r * angle.sin();
+            let y = cy + r * angle.cos();
+            turtle.goto(x, y);
+        }
+        
+        turtle.pen_down = old_pen;
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_cls(interp: &mut Interpreter) -> Result<ExecutionResult> {
+    // Clear screen: reset text buffer and cursor position
+    interp.text_lines.clear();
+    interp.cursor_row = 0;
+    interp.cursor_col = 0;
+    // Also log empty line to output for consistency
+    interp.output.push(tr("basic.screen_cleared").to_string());
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_locate(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    // LOCATE row, col - set cursor position (1-based)
+    let parts: Vec<&str> = args.split(',').map(|s| s.trim()).collect();
+    if parts.len() >= 2 {
+        let row = interp.evaluate_expression(parts[0]).unwrap_or(1.0) as u32;
+        let col = interp.evaluate_expression(parts[1]).unwrap_or(1.0) as u32;
+        // Convert to 0-based
+        interp.cursor_row = row.saturating_sub(1);
+        interp.cursor_col = col.saturating_sub(1);
+    }
+    Ok(ExecutionResult::Continue)
+}
@@ -0,0 +1,279 @@
+//! `PRINT USING`: numbers laid out by a picture string such as `"$$#,###.##"`
+//!
+//! Fields in the picture:
+//! - `#` is one digit position; numbers are right-aligned in the field
+//! - `.` places the decimal point, followed by one `#` per decimal (rounded half away from zero)
+//! - `,` left of the point groups thousands with commas (and is a digit position itself)
+//! - `$$` at the start prints a `$` just left of the number (two positions, one for the `$`)
+//! - a `-` or `+` right after the field prints the sign there instead of in front
+//!
+//! Everything else is literal text. A number too wide for its field is
+//! printed in full after a `%`, as GW-BASIC does. With more values than
+//! fields the picture starts over; output stops at the first field left
+//! without a value.
+
+use crate::utils::error::BasicError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TrailingSign {
+    #[default]
+    None,
+    /// `-`: a minus for negatives, a space otherwise
+    Minus,
+    /// `+`: always a sign
+    Plus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct NumberField {
+    /// Positions left of the point, counting commas and the `$$` pair
+    width: usize,
+    /// Digits after the point; `None` when the field has no point
+    decimals: Option<usize>,
+    comma: bool,
+    dollar: bool,
+    sign: TrailingSign,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Field(NumberField),
+}
+
+fn parse(format: &str) -> Vec<Piece> {
+    let chars: Vec<char> = format.chars().collect();
+    let at = |i: usize| chars.get(i).copied();
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let starts_field = c == '#' || (c == '$' && at(i + 1) == Some('$')) || (c == '.' && at(i + 1) == Some('#'));
+        if !starts_field {
+            literal.push(c);
+            i += 1;
+            continue;
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+        }
+
+        let mut field = NumberField::default();
+        if c == '$' {
+            field.dollar = true;
+            field.width = 2;
+            i += 2;
+        }
+        while let Some(c) = at(i) {
+            // A comma only belongs to the field when more of the field follows
+            let grouping = c == ',' && matches!(at(i + 1), Some('#' | ',' | '.'));
+            if c != '#' && !grouping {
+                break;
+            }
+            field.comma |= grouping;
+            field.width += 1;
+            i += 1;
+        }
+        if at(i) == Some('.') {
+            i += 1;
+            let mut decimals = 0;
+            while at(i) == Some('#') {
+                decimals += 1;
+                i += 1;
+            }
+            field.decimals = Some(decimals);
+        }
+        field.sign = match at(i) {
+            Some('-') => TrailingSign::Minus,
+            Some('+') => TrailingSign::Plus,
+            _ => TrailingSign::None,
+        };
+        if field.sign != TrailingSign::None {
+            i += 1;
+        }
+        pieces.push(Piece::Field(field));
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    pieces
+}
+
+/// `1234567` → `1,234,567`
+fn group_thousands(digits: &str) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn format_number(field: &NumberField, value: f64) -> String {
+    let decimals = field.decimals.unwrap_or(0);
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (value.abs() * scale).round() / scale;
+    let digits = format!("{:.*}", decimals, rounded);
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((&digits, ""));
+    let negative = value < 0.0 && rounded != 0.0;
+
+    let mut int_text = if field.comma { group_thousands(int_part) } else { int_part.to_string() };
+    // `.##` prints 0.25 as `.25`
+    if int_text == "0" && field.width == 0 && decimals > 0 {
+        int_text.clear();
+    }
+    let mut head = String::new();
+    if negative && field.sign == TrailingSign::None {
+        head.push('-');
+    }
+    if field.dollar {
+        head.push('$');
+    }
+    head.push_str(&int_text);
+
+    let mut out = if head.chars().count() > field.width {
+        format!("%{}", head)
+    } else {
+        format!("{:>width$}", head, width = field.width)
+    };
+    if field.decimals.is_some() {
+        out.push('.');
+        out.push_str(frac_part);
+    }
+    match field.sign {
+        TrailingSign::None => {}
+        TrailingSign::Minus => out.push(if negative { '-' } else { ' ' }),
+        TrailingSign::Plus => out.push(if negative { '-' } else { '+' }),
+    }
+    out
+}
+
+/// Lay out `values` with the picture string `format`
+///
+/// Fails with `IllegalFunctionCall` when there are values but the picture has no field.
+pub fn format_using(format: &str, values: &[f64]) -> Result<String, BasicError> {
+    let pieces = parse(format);
+    let has_field = pieces.iter().any(|p| matches!(p, Piece::Field(_)));
+    if !has_field {
+        if values.is_empty() {
+            return Ok(format.to_string());
+        }
+        return Err(BasicError::IllegalFunctionCall);
+    }
+
+    let mut out = String::new();
+    let mut values = values.iter();
+    let mut next = values.next();
+    let mut index = 0;
+    loop {
+        match &pieces[index] {
+            Piece::Literal(text) => out.push_str(text),
+            Piece::Field(field) => match next {
+                Some(value) => {
+                    out.push_str(&format_number(field, *value));
+                    next = values.next();
+                }
+                None => break,
+            },
+        }
+        index += 1;
+        if index == pieces.len() {
+            if next.is_none() {
+                break;
+            }
+            index = 0;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn using(format: &str, value: f64) -> String {
+        format_using(format, &[value]).unwrap()
+    }
+
+    #[test]
+    fn test_digits_and_decimals() {
+        let cases = [
+            ("##.##", 4.56789, " 4.57"),
+            ("##.##", 12.5, "12.50"),
+            ("###", 7.0, "  7"),
+            ("###", 7.6, "  8"),
+            ("#.#", 0.0, "0.0"),
+            (".##", 0.25, ".25"),
+            ("##.", 4.0, " 4."),
+            ("#", 0.4, "0"),
+        ];
+        for (format, value, expected) in cases {
+            assert_eq!(using(format, value), expected, "{} with {}", format, value);
+        }
+    }
+
+    #[test]
+    fn test_rounding() {
+        let cases = [
+            ("#.##", 0.125, "0.13"),
+            ("#.##", 0.124, "0.12"),
+            ("##.#", 9.96, "10.0"),
+            ("#.#", 9.96, "%10.0"),
+            ("###", 2.5, "  3"),
+            ("###", -2.5, " -3"),
+            ("#.##", -0.001, "0.00"),
+        ];
+        for (format, value, expected) in cases {
+            assert_eq!(using(format, value), expected, "{} with {}", format, value);
+        }
+    }
+
+    #[test]
+    fn test_negatives_and_trailing_sign() {
+        let cases = [
+            ("##.##", -3.25, "-3.25"),
+            ("##.##", -12.5, "%-12.50"),
+            ("##.##-", -12.5, "12.50-"),
+            ("##.##-", 12.5, "12.50 "),
+            ("##.##+", 12.5, "12.50+"),
+            ("##.##+", -1.0, " 1.00-"),
+            ("$$##.##", -5.0, " -$5.00"),
+        ];
+        for (format, value, expected) in cases {
+            assert_eq!(using(format, value), expected, "{} with {}", format, value);
+        }
+    }
+
+    #[test]
+    fn test_grouping_and_dollars() {
+        let cases = [
+            ("#,###.##", 1234.5, "1,234.50"),
+            ("##,###,###", 1234567.0, " 1,234,567"),
+            ("#,###", 999.0, "  999"),
+            ("#,###", 12345.0, "%12,345"),
+            ("$$###.##", 1234.5, "$1234.50"),
+            ("$$###.##", 4.5, "   $4.50"),
+            ("$$###.##", 12345.6, "%$12345.60"),
+            ("$$#,###.##", 1234.5, " $1,234.50"),
+        ];
+        for (format, value, expected) in cases {
+            assert_eq!(using(format, value), expected, "{} with {}", format, value);
+        }
+    }
+
+    #[test]
+    fn test_literals_and_several_values() {
+        assert_eq!(using("Total: $$###.##", 19.99), "Total:   $19.99");
+        assert_eq!(format_using("## + ## = ###", &[2.0, 3.0, 5.0]).unwrap(), " 2 +  3 =   5");
+        // The picture repeats for extra values and stops at the first unfilled field
+        assert_eq!(format_using("[##]", &[1.0, 22.0]).unwrap(), "[ 1][22]");
+        assert_eq!(format_using("A=## B=##", &[1.0]).unwrap(), "A= 1 B=");
+        // Commas and points that are not part of a field stay literal
+        assert_eq!(using("##, done.", 5.0), " 5, done.");
+        assert_eq!(format_using("No fields", &[]).unwrap(), "No fields");
+        assert_eq!(format_using("No fields", &[1.0]), Err(BasicError::IllegalFunctionCall));
+    }
+}
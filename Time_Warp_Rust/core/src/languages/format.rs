@@ -0,0 +1,514 @@
+//! Format Document: one canonical layout for a program in each language
+//!
+//! BASIC statement keywords are uppercased, expressions get one space around
+//! each operator and after each comma, line numbers are padded to one column,
+//! and FOR…NEXT bodies are indented; statements joined with `:` are each
+//! formatted and rejoined with ` : `. Logo lines are uppercased with single
+//! spaces and indented inside TO…END and inside a `[` continued onto the next
+//! line. PILOT commands lose the spaces after their colon. String literals,
+//! comments, `DATA` values, and `M:` patterns stay as written, and so does any
+//! text the interpreter could read differently once respaced (unquoted text
+//! in a PRINT, say), so a program runs the same after formatting. Formatting
+//! a formatted program changes nothing.
+
+use std::collections::HashSet;
+
+use super::{basic, Language};
+use crate::interpreter::arrays::split_top_level;
+use crate::interpreter::continued_line;
+use crate::interpreter::metadata::{comment_language, parse_metadata};
+use crate::languages::logo;
+use crate::utils::expr_eval::{type_suffix, ExpressionEvaluator};
+
+/// One level of indentation
+pub const INDENT: &str = "  ";
+
+/// PILOT commands whose text the interpreter trims; `M:` patterns and `R:` lines are kept as written
+const PILOT_TRIMMED: [&str; 16] = ["T", "V", "A", "AN", "U", "C", "Y", "N", "J", "L", "D", "E", "TW", "TI", "QZ", "MC"];
+
+/// Words that end a BASIC expression; one inside it leaves the expression as written
+const BASIC_CLAUSES: [&str; 4] = ["THEN", "ELSE", "TO", "STEP"];
+
+/// `source` laid out canonically
+///
+/// An `@lang` header decides the language over `language`. In TempleCode
+/// each line is formatted in the language the interpreter runs it as.
+pub fn format_source(source: &str, language: Language) -> String {
+    let statements = statements(source);
+    let width = statements.iter().filter_map(|(pieces, _)| split_line_number(pieces[0]).0).map(str::len).max().unwrap_or(0);
+    let mut layout = Layout::new(parse_metadata(source).language.unwrap_or(language));
+    let mut lines = Vec::new();
+    for (pieces, joined) in &statements {
+        let (number, head) = split_line_number(pieces[0]);
+        let (language, depth) = layout.place(split_line_number(joined).1);
+        let margin = number.map_or_else(String::new, |n| format!("{:<width$} ", n));
+        let indent = INDENT.repeat(depth);
+        let [_, continuations @ ..] = pieces.as_slice() else { continue };
+        if continuations.is_empty() {
+            let statement = language.map_or_else(|| head.to_string(), |l| format_statement(head, l));
+            lines.push(format!("{}{}{}", margin, indent, statement));
+            continue;
+        }
+        // A statement continued with ` _`: Logo pieces are respaced, others only trimmed
+        let logo = language == Some(Language::Logo);
+        let piece = |text: &str| if logo { logo_statement(text) } else { text.trim().to_string() };
+        lines.push(format!("{}{}{}", margin, indent, piece(head)));
+        let pad = " ".repeat(margin.len());
+        let mut brackets = if logo { bracket_balance(head) } else { 0 };
+        for text in continuations {
+            let closing = logo && text.trim_start().starts_with(']');
+            let level = depth + 1 + (brackets - closing as isize).max(0) as usize;
+            lines.push(format!("{}{}{}", pad, INDENT.repeat(level), piece(text)));
+            if logo {
+                brackets += bracket_balance(text);
+            }
+        }
+    }
+    let mut formatted = lines.iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n");
+    if source.ends_with('\n') {
+        formatted.push('\n');
+    }
+    formatted
+}
+
+/// Physical lines grouped into statements as ` _` continuations join them, with the joined text
+fn statements(source: &str) -> Vec<(Vec<&str>, String)> {
+    let mut statements = Vec::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let mut pieces = vec![line];
+        let mut joined = line.to_string();
+        while let Some(head) = continued_line(&joined) {
+            let head = head.trim_end().to_string();
+            match lines.next() {
+                Some(next) => {
+                    joined = format!("{} {}", head, next.trim());
+                    pieces.push(next);
+                }
+                None => {
+                    joined = head;
+                    break;
+                }
+            }
+        }
+        statements.push((pieces, joined));
+    }
+    statements
+}
+
+/// A leading line number and the statement after it, split as the interpreter splits them
+pub(super) fn split_line_number(line: &str) -> (Option<&str>, &str) {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((number, rest)) if number.parse::<usize>().is_ok() => (Some(number), rest.trim()),
+        _ => (None, line),
+    }
+}
+
+/// `[` minus `]` in `text`
+fn bracket_balance(text: &str) -> isize {
+    text.chars().map(|c| match c {
+        '[' => 1,
+        ']' => -1,
+        _ => 0,
+    }).sum()
+}
+
+/// Where the formatter is in the program: the FOR loops and Logo procedure it is inside
+pub(super) struct Layout {
+    /// From the `@lang` header or the caller; TempleCode detects each line
+    language: Language,
+    /// Logo procedures defined so far, upper case
+    procedures: HashSet<String>,
+    for_depth: usize,
+    in_procedure: bool,
+}
+
+impl Layout {
+    pub(super) fn new(language: Language) -> Self {
+        Layout { language, procedures: HashSet::new(), for_depth: 0, in_procedure: false }
+    }
+
+    /// The language `statement` runs as and its indentation, entering or leaving a FOR, DO, or TO body
+    ///
+    /// `None` for a line the interpreter might not run as Logo although it
+    /// looks like it could be, which is left as written.
+    pub(super) fn place(&mut self, statement: &str) -> (Option<Language>, usize) {
+        if self.in_procedure {
+            // The body runs as Logo up to a line that is just END
+            if statement.eq_ignore_ascii_case("END") {
+                self.in_procedure = false;
+                return (Some(Language::Logo), self.for_depth);
+            }
+            return (Some(Language::Logo), self.for_depth + 1);
+        }
+        let first = statement.split_whitespace().next().unwrap_or("").to_uppercase();
+        let language = match self.language {
+            Language::TempleCode => self.detect(statement, &first),
+            language => language,
+        };
+        match (language, first.as_str()) {
+            (Language::Basic, _) => {
+                // The line sits where its first statement does; the rest of a `:` chain moves only later lines
+                let mut indent = None;
+                for piece in basic::statements(statement) {
+                    let word = piece.split_whitespace().next().unwrap_or("").to_uppercase();
+                    match word.as_str() {
+                        "FOR" | "DO" => self.for_depth += 1,
+                        "NEXT" | "LOOP" => self.for_depth = self.for_depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    let opens = matches!(word.as_str(), "FOR" | "DO");
+                    indent.get_or_insert(if opens { self.for_depth - 1 } else { self.for_depth });
+                }
+                (Some(language), indent.unwrap_or(self.for_depth))
+            }
+            (Language::Logo, "TO") => {
+                if let Some(name) = statement.split_whitespace().nth(1) {
+                    self.procedures.insert(name.to_uppercase());
+                    self.in_procedure = true;
+                }
+                (Some(language), self.for_depth)
+            }
+            (Language::Logo, first) if !self.procedures.contains(first) && !logo::KEYWORDS.contains(&first) => {
+                (None, self.for_depth)
+            }
+            _ => (Some(language), self.for_depth),
+        }
+    }
+
+    /// The language a mixed program runs `statement` as, decided as the interpreter decides it
+    fn detect(&self, statement: &str, first: &str) -> Language {
+        if statement.chars().nth(1) == Some(':') {
+            Language::Pilot
+        } else if self.procedures.contains(first)
+            || logo::KEYWORDS.contains(&first)
+            || (first == "PRINT" && logo::is_logo_print(&statement.trim_start()[first.len()..]))
+        {
+            Language::Logo
+        } else if basic::KEYWORDS.contains(&first) {
+            Language::Basic
+        } else {
+            Language::Pilot
+        }
+    }
+}
+
+fn format_statement(statement: &str, language: Language) -> String {
+    if comment_language(statement).is_some() {
+        // Only the REM keyword changes in a comment
+        return match statement.get(..3) {
+            Some(keyword) if keyword.eq_ignore_ascii_case("REM") => format!("REM{}", &statement[3..]),
+            _ => statement.to_string(),
+        };
+    }
+    match language {
+        Language::Basic => basic_line(statement),
+        Language::Logo => logo_statement(statement),
+        Language::Pilot => pilot_statement(statement),
+        Language::TempleCode => statement.to_string(),
+    }
+}
+
+/// `T:  hello` as `T:hello`
+fn pilot_statement(statement: &str) -> String {
+    match statement.split_once(':') {
+        Some((command, text)) if PILOT_TRIMMED.contains(&command) => format!("{}:{}", command, text.trim_start()),
+        _ => statement.to_string(),
+    }
+}
+
+/// A Logo line in upper case with single spaces, as the interpreter reads it anyway
+///
+/// SETSHAPE keeps its file name as written.
+fn logo_statement(statement: &str) -> String {
+    let mut words = statement.split_whitespace();
+    let Some(first) = words.next().map(str::to_uppercase) else { return String::new() };
+    if first == "SETSHAPE" {
+        let file = statement.trim().split_once(char::is_whitespace).map_or("", |(_, file)| file.trim());
+        return format!("{} {}", first, file);
+    }
+    std::iter::once(first).chain(words.map(str::to_uppercase)).collect::<Vec<_>>().join(" ")
+}
+
+/// A BASIC line's statements each formatted, joined by ` : `
+fn basic_line(line: &str) -> String {
+    basic::statements(line).into_iter().map(basic_statement).collect::<Vec<_>>().join(" : ")
+}
+
+/// A BASIC statement with its keyword in upper case and its expressions respaced
+fn basic_statement(statement: &str) -> String {
+    let (keyword, args) = match statement.split_once(char::is_whitespace) {
+        Some((keyword, args)) => (keyword, args.trim()),
+        None => (statement, ""),
+    };
+    let keyword_upper = keyword.to_uppercase();
+    let formatted = match keyword_upper.as_str() {
+        "REM" => return format!("REM{}", &statement[keyword.len()..]),
+        "LET" => let_args(args),
+        "PRINT" => print_args(args),
+        "IF" => if_args(args),
+        "FOR" => for_args(args),
+        "LINE" | "CIRCLE" | "LOCATE" | "SCREEN" | "DIM" => Some(expression_list(args)),
+        "WIDTH" | "RANDOMIZE" => expression(args),
+        // Read in upper case by the interpreter
+        "OPTION" | "EVERY" | "ONTICK" | "EXIT" => Some(args.split_whitespace().map(str::to_uppercase).collect::<Vec<_>>().join(" ")),
+        "PLOT" | "CHART" | "DEFINT" | "DEFSNG" | "DEFDBL" | "DEFSTR" => Some(args.to_uppercase()),
+        "INPUT" | "GOTO" | "NEXT" | "GOSUB" | "RETURN" | "DATA" | "READ" | "RESTORE" | "END" | "CLS" | "LOGVAR"
+        | "CHAIN" | "COMMON" | "CLEAR" | "MERGE" => None,
+        _ => return statement.to_string(),
+    };
+    let args = formatted.unwrap_or_else(|| args.to_string());
+    if args.is_empty() {
+        keyword_upper
+    } else {
+        format!("{} {}", keyword_upper, args)
+    }
+}
+
+/// `X = expression`
+fn let_args(args: &str) -> Option<String> {
+    let (name, value) = args.split_once('=')?;
+    let value = value.trim();
+    Some(format!("{} = {}", name.trim(), expression(value).unwrap_or_else(|| value.to_string())))
+}
+
+/// `USING "picture"; values` as written, else each comma-separated item respaced
+fn print_args(args: &str) -> Option<String> {
+    let upper = args.to_ascii_uppercase();
+    if let Some(rest) = upper.strip_prefix("USING").filter(|rest| rest.starts_with(char::is_whitespace) || rest.starts_with('"')) {
+        return Some(format!("USING {}", args[args.len() - rest.len()..].trim()));
+    }
+    (!args.is_empty()).then(|| expression_list(args))
+}
+
+/// `condition THEN branch [ELSE branch]`, split where the interpreter splits it
+fn if_args(args: &str) -> Option<String> {
+    let then = basic::find_keyword(args, "THEN").filter(|&at| args.to_ascii_uppercase().find("THEN") == Some(at))?;
+    let condition = args[..then].trim();
+    let branches = args[then + 4..].trim();
+    let (then_branch, else_branch) = match basic::find_keyword(branches, "ELSE") {
+        Some(at) => (branches[..at].trim(), Some(branches[at + 4..].trim())),
+        None => (branches, None),
+    };
+    let branch = |text: &str| {
+        if text.starts_with(|c: char| c.is_ascii_digit()) { text.to_string() } else { basic_line(text) }
+    };
+    let mut formatted = format!(
+        "{} THEN {}",
+        expression(condition).unwrap_or_else(|| condition.to_string()),
+        branch(then_branch)
+    );
+    if let Some(else_branch) = else_branch {
+        formatted.push_str(&format!(" ELSE {}", branch(else_branch)));
+    }
+    Some(formatted)
+}
+
+/// `I = start TO end [STEP step]`, split where the interpreter splits it
+fn for_args(args: &str) -> Option<String> {
+    let upper = args.to_ascii_uppercase();
+    let equals = args.find('=')?;
+    let to = upper.find(" TO ").filter(|&to| to > equals)?;
+    let (end, step) = match upper.find(" STEP ") {
+        Some(step) if step > to => (&args[to + 4..step], Some(&args[step + 6..])),
+        Some(_) => return None,
+        None => (&args[to + 4..], None),
+    };
+    let part = |text: &str| expression(text).unwrap_or_else(|| text.trim().to_string());
+    let mut formatted = format!("{} = {} TO {}", args[..equals].trim(), part(&args[equals + 1..to]), part(end));
+    if let Some(step) = step {
+        formatted.push_str(&format!(" STEP {}", part(step)));
+    }
+    Some(formatted)
+}
+
+/// Comma-separated items (outside strings and parentheses), each respaced if it is an expression
+fn expression_list(args: &str) -> String {
+    let items: Vec<String> = split_top_level(args)
+        .into_iter()
+        .map(|item| expression(item).unwrap_or_else(|| item.to_string()))
+        .collect();
+    items.join(", ")
+}
+
+/// `text` respaced if it is a numeric expression: one space around binary
+/// operators and after commas, none inside parentheses or after a minus sign
+///
+/// `None` for anything else: text with quotes or `$`, two names in a row, an
+/// array or unknown function, a name spaced from its `(`, a sign that does not
+/// start a number. The interpreter may read such text as raw text, or
+/// tokenize it differently once respaced.
+fn expression(text: &str) -> Option<String> {
+    let mut formatted = String::new();
+    let mut chars = text.trim().chars().peekable();
+    let mut operand_next = true;
+    let mut depth = 0usize;
+    let mut after_comparison = false;
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let follows_comparison = std::mem::take(&mut after_comparison);
+        match c {
+            '0'..='9' | '.' | 'A'..='Z' | 'a'..='z' | '_' if operand_next => {
+                let number = c.is_ascii_digit() || c == '.';
+                let mut token = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    let continues = if number { next.is_ascii_digit() || next == '.' } else { next.is_ascii_alphanumeric() || next == '_' };
+                    if !continues {
+                        break;
+                    }
+                    token.push(next);
+                    chars.next();
+                }
+                if BASIC_CLAUSES.iter().any(|clause| token.eq_ignore_ascii_case(clause)) {
+                    return None;
+                }
+                if let Some(suffix) = type_suffix(chars.clone()).filter(|_| !number) {
+                    token.push(suffix);
+                    chars.next();
+                }
+                formatted.push_str(&token);
+                if !number && chars.peek() == Some(&'(') {
+                    // A built-in function call (an array or unknown function may fail and be read as text)
+                    if !ExpressionEvaluator::FUNCTIONS.contains(&token.to_uppercase().as_str()) {
+                        return None;
+                    }
+                    chars.next();
+                    formatted.push('(');
+                    depth += 1;
+                } else {
+                    operand_next = false;
+                }
+            }
+            '(' if operand_next => {
+                formatted.push('(');
+                depth += 1;
+            }
+            ')' if depth > 0 && !operand_next => {
+                formatted.push(')');
+                depth -= 1;
+                operand_next = false;
+            }
+            ',' if depth > 0 && !operand_next => {
+                formatted.push_str(", ");
+                operand_next = true;
+            }
+            // A negative number; any other sign is an error the interpreter may print as text
+            '-' if operand_next && !follows_comparison && chars.peek().is_some_and(char::is_ascii_digit) => {
+                formatted.push(c);
+            }
+            '+' | '-' | '*' | '/' | '^' | '%' if !operand_next => {
+                formatted.push_str(&format!(" {} ", c));
+                operand_next = true;
+            }
+            '<' | '>' | '=' | '!' if !operand_next => {
+                let mut operator = c.to_string();
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                    operator.push('=');
+                }
+                if operator == "!" {
+                    return None;
+                }
+                formatted.push_str(&format!(" {} ", operator));
+                operand_next = true;
+                after_comparison = true;
+            }
+            _ => return None,
+        }
+    }
+    (!operand_next && depth == 0).then_some(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_keywords_spacing_and_line_numbers() {
+        let source = "5 print   \"a  b\",x\n100 let y=-2*(x+z)\n20 if y>=3 then print y else goto 5\n";
+        let formatted = format_source(source, Language::Basic);
+        assert_eq!(
+            formatted,
+            "5   PRINT \"a  b\", x\n100 LET y = -2 * (x + z)\n20  IF y >= 3 THEN PRINT y ELSE GOTO 5\n"
+        );
+        let typed = format_source("defint i-n\nlet count%=i%2+x!*y#", Language::Basic);
+        assert_eq!(typed, "DEFINT I-N\nLET count% = i % 2 + x! * y#");
+        // Text that changes length when upper-cased does not shift THEN, TO or STEP
+        let dotless = format_source("10 if a$=\"ıııııé\" then print 1\n20 for i=len(\"ıß\") to 3 step 1", Language::Basic);
+        assert_eq!(dotless, "10 IF a$=\"ıııııé\" THEN PRINT 1\n20 FOR i = len(\"ıß\") TO 3 STEP 1");
+    }
+
+    #[test]
+    fn test_colon_statements_are_formatted_one_by_one() {
+        let source = "10 let x=1:print x,\"a:b\" : goto 30\n20 if x>0 then print x:goto 5 else print -x\n30 for i=1 to 3:print i:next i\n40 print 1";
+        assert_eq!(
+            format_source(source, Language::Basic),
+            "10 LET x = 1 : PRINT x, \"a:b\" : GOTO 30\n20 IF x > 0 THEN PRINT x : GOTO 5 ELSE PRINT -x\n30 FOR i = 1 TO 3 : PRINT i : NEXT i\n40 PRINT 1"
+        );
+    }
+
+    #[test]
+    fn test_for_bodies_and_procedures_are_indented() {
+        let basic = "10 for i=1 to 3 step 1\n20 for j=1 to 2\n30 print i*j\n40 next j\n50 next i";
+        assert_eq!(
+            format_source(basic, Language::Basic),
+            "10 FOR i = 1 TO 3 STEP 1\n20   FOR j = 1 TO 2\n30     PRINT i * j\n40   NEXT j\n50 NEXT i"
+        );
+        let logo = "to square :size\nrepeat 4 [forward   :size right 90]\nend\nsquare 50";
+        assert_eq!(
+            format_source(logo, Language::Logo),
+            "TO SQUARE :SIZE\n  REPEAT 4 [FORWARD :SIZE RIGHT 90]\nEND\nSQUARE 50"
+        );
+    }
+
+    #[test]
+    fn test_pilot_loses_spaces_after_the_colon_but_not_in_patterns() {
+        let source = "T:   hello\nM:  yes  \nR:  a comment\nt:  not a command\n*start";
+        assert_eq!(format_source(source, Language::Pilot), "T:hello\nM:  yes\nR:  a comment\nt:  not a command\n*start");
+    }
+
+    #[test]
+    fn test_text_the_interpreter_reads_raw_is_kept() {
+        assert_eq!(expression("hello world"), None);
+        assert_eq!(expression("A$+\"x\""), None);
+        assert_eq!(expression("A (1)"), None);
+        assert_eq!(expression("- 5"), None);
+        assert_eq!(expression("-X+1"), None);
+        assert_eq!(expression("X=-1"), None);
+        assert_eq!(expression("X<>Y"), None);
+        assert_eq!(expression("rand()*6"), None);
+        assert_eq!(expression("A(I)+1"), None);
+        assert_eq!(expression("int(rnd(6))+1"), Some("int(rnd(6)) + 1".to_string()));
+        let source = "10 PRINT \"A\";x;  \"B\"\n20 rem  keep   this\n30 data  New  York, 2";
+        assert_eq!(format_source(source, Language::Basic), "10 PRINT \"A\";x;  \"B\"\n20 REM  keep   this\n30 DATA New  York, 2");
+    }
+
+    #[test]
+    fn test_templecode_formats_each_line_in_its_language() {
+        let source = "T:  hi\nlet x = 1\nfd   50\nsomething else";
+        assert_eq!(format_source(source, Language::TempleCode), "T:hi\nLET x = 1\nFD 50\nsomething else");
+    }
+
+    #[test]
+    fn test_continued_logo_lines_are_indented_by_bracket() {
+        let source = "repeat 4 [ _\nfd 50 _\nrt 90 _\n]";
+        assert_eq!(format_source(source, Language::Logo), "REPEAT 4 [ _\n    FD 50 _\n    RT 90 _\n  ]");
+    }
+
+    #[test]
+    fn test_formatting_is_idempotent() {
+        let sources = [
+            ("10 for i=1 to 3\n20 print i,i*2\n30 next i\n100 end\n", Language::Basic),
+            ("to tri :s\nrepeat 3 [fd :s rt 120]\nend\ntri 40\n", Language::Logo),
+            ("T:  hi\nA:   name\nM:  bob\nJ:   start\n", Language::Pilot),
+            ("REM @lang templecode\nT: x\n10 for i=1 to 2 _\n step 1\n20 next i\n", Language::TempleCode),
+        ];
+        for (source, language) in sources {
+            let once = format_source(source, language);
+            assert_eq!(format_source(&once, language), once, "{:?}", source);
+        }
+    }
+}
@@ -0,0 +1,698 @@
+use anyhow::Result;
+use crate::interpreter::{Interpreter, ExecutionResult, TooDeeplyNested};
+use crate::graphics::{AngleMode, Rgba, TurtleShape, TurtleState};
+use crate::i18n::{tr, trf};
+use std::collections::HashMap;
+
+/// First words that make a line Logo in a mixed program, besides defined procedure names
+///
+/// `PRINT` is BASIC's unless its input is Logo's (see [`is_logo_print`]).
+pub const KEYWORDS: [&str; 44] = [
+    "FORWARD", "FD", "BACK", "BK", "LEFT", "LT", "RIGHT", "RT",
+    "PENUP", "PU", "PENDOWN", "PD", "CLEARSCREEN", "CS", "HOME",
+    "SETXY", "REPEAT", "TO", "END", "SETHEADING", "SETH",
+    "SETCOLOR", "SETPENCOLOR", "PENWIDTH", "SETPENSIZE", "SETBGCOLOR",
+    "HIDETURTLE", "HT", "SHOWTURTLE", "ST", "SETSHAPE", "PATHCOUNT",
+    "SETANGLEMODE", "HEADING", "TOWARDS", "ARC", "SETX", "SETY", "SETPOS", "CLEAN",
+    "SETCOLORCYCLE", "FLASH", "TYPE", "SHOW",
+];
+
+/// Procedure calls that may run inside each other before a call fails
+///
+/// A procedure that calls itself without ever stopping would otherwise
+/// overflow the stack, which ends the whole IDE rather than the run.
+pub const MAX_PROCEDURE_DEPTH: usize = 200;
+
+/// `[` brackets a REPEAT may nest when no limit says otherwise
+///
+/// Each nested REPEAT runs inside the one around it, so like procedure calls
+/// they are capped before they can overflow the stack.
+pub const DEFAULT_BRACKET_DEPTH: usize = 64;
+
+#[derive(Clone)]
+pub struct LogoProcedure {
+    pub params: Vec<String>, // Uppercase names without ':'
+    pub body: Vec<String>,
+}
+
+pub fn execute(interp: &mut Interpreter, command: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    let cmd = command.trim().to_uppercase();
+    if cmd.starts_with(';') {
+        return Ok(ExecutionResult::Continue); // Comment
+    }
+    // REPEAT bodies and procedures run here without passing through execute_line
+    interp.path_count = turtle.total_lines();
+    interp.turtle_heading = turtle.heading;
+    interp.stream_turtle(turtle);
+    if interp.stop_requested() {
+        return Err(anyhow::anyhow!(tr("logo.program_stopped")));
+    }
+    let parts: Vec<&str> = cmd.splitn(2, char::is_whitespace).collect();
+    
+    if parts.is_empty() {
+        return Ok(ExecutionResult::Continue);
+    }
+    // User-defined procedure names take precedence over built-in keywords
+    let proc_upper = parts[0].to_uppercase();
+    if interp.logo_procedures.contains_key(&proc_upper) {
+        let arg_str = parts.get(1).copied().unwrap_or("");
+        return execute_procedure(interp, &proc_upper, arg_str, turtle);
+    }
+    interp.check_enabled(parts[0], parts[0])?;
+    
+    match parts[0] {
+        "FORWARD" | "FD" => execute_forward(interp, turtle, parts.get(1).unwrap_or(&"0")),
+        "BACK" | "BK" | "BACKWARD" => execute_back(interp, turtle, parts.get(1).unwrap_or(&"0")),
+    "LEFT" | "LT" => execute_left(interp, turtle, parts.get(1).unwrap_or(&"0")),
+    "RIGHT" | "RT" => execute_right(interp, turtle, parts.get(1).unwrap_or(&"0")),
+        "PENUP" | "PU" => execute_penup(turtle),
+        "PENDOWN" | "PD" => execute_pendown(turtle),
+    "CLEARSCREEN" | "CS" => execute_clearscreen(turtle),
+        "HOME" => execute_home(turtle),
+        "CLEAN" => execute_clean(turtle),
+        "SETXY" | "SETPOS" => execute_setxy(interp, turtle, parts[0], parts.get(1).unwrap_or(&"")),
+        "SETX" => execute_setx(interp, turtle, parts.get(1).unwrap_or(&"")),
+        "SETY" => execute_sety(interp, turtle, parts.get(1).unwrap_or(&"")),
+        "SETHEADING" | "SETH" => execute_setheading(interp, turtle, parts.get(1).unwrap_or(&"0")),
+    "SETCOLOR" | "SETPENCOLOR" => execute_setcolor(interp, turtle, parts.get(1).unwrap_or(&"")),
+    "PENWIDTH" | "SETPENSIZE" => execute_penwidth(interp, turtle, parts.get(1).unwrap_or(&"")),
+    "SETBGCOLOR" => execute_setbgcolor(interp, turtle, parts.get(1).unwrap_or(&"")),
+        "SETCOLORCYCLE" => execute_setcolorcycle(turtle, parts.get(1).unwrap_or(&"")),
+        "FLASH" => execute_flash(interp, turtle, parts.get(1).unwrap_or(&"1")),
+        "HIDETURTLE" | "HT" => execute_hideturtle(turtle),
+        "SHOWTURTLE" | "ST" => execute_showturtle(turtle),
+        // File names keep their case, so the argument comes from the original command
+        "SETSHAPE" => execute_setshape(interp, turtle, command.trim().split_once(char::is_whitespace).map_or("", |(_, arg)| arg)),
+    "REPEAT" => execute_repeat(interp, parts.get(1).unwrap_or(&""), turtle),
+        "TO" => execute_to(interp, parts.get(1).unwrap_or(&"")),
+        "END" => Ok(ExecutionResult::Continue), // END handled in execute_to
+        "LOGVAR" => {
+            interp.log_variable(parts.get(1).unwrap_or(&""))?;
+            Ok(ExecutionResult::Continue)
+        }
+        "PATHCOUNT" => {
+            interp.log_output(format!("PATHCOUNT {}", turtle.total_lines()));
+            Ok(ExecutionResult::Continue)
+        }
+        "SETANGLEMODE" => execute_setanglemode(interp, parts.get(1).unwrap_or(&"")),
+        "HEADING" => {
+            interp.log_output(format!("HEADING {}", format_angle(interp.angle_mode.heading(turtle.heading))));
+            Ok(ExecutionResult::Continue)
+        }
+        "TOWARDS" => {
+            let bearing = eval_towards(interp, turtle, parts.get(1).unwrap_or(&""))?;
+            interp.log_output(format!("TOWARDS {}", format_angle(interp.angle_mode.heading(bearing))));
+            Ok(ExecutionResult::Continue)
+        }
+        "ARC" => execute_arc(interp, turtle, parts.get(1).unwrap_or(&"")),
+        // Words and lists keep their case, so the input comes from the original command
+        "PRINT" | "TYPE" | "SHOW" => execute_text(interp, parts[0], command.trim().split_once(char::is_whitespace).map_or("", |(_, arg)| arg)),
+        _ => {
+            // Unknown command (user procedures already handled before match)
+            interp.log_error(format!("❌ {}", trf("logo.unknown_command", &[&parts[0]])));
+            Ok(ExecutionResult::Continue)
+        }
+    }
+}
+
+fn execute_forward(interp: &mut Interpreter, turtle: &mut TurtleState, distance_str: &str) -> Result<ExecutionResult> {
+    let distance = eval_logo_expr(interp, distance_str.trim())?;
+    turtle.forward(distance as f32);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_back(interp: &mut Interpreter, turtle: &mut TurtleState, distance_str: &str) -> Result<ExecutionResult> {
+    let distance = eval_logo_expr(interp, distance_str.trim())?;
+    turtle.back(distance as f32);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_left(interp: &mut Interpreter, turtle: &mut TurtleState, angle_str: &str) -> Result<ExecutionResult> {
+    let angle = eval_logo_expr(interp, angle_str.trim())? as f32;
+    turtle.left(angle);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_right(interp: &mut Interpreter, turtle: &mut TurtleState, angle_str: &str) -> Result<ExecutionResult> {
+    let angle = eval_logo_expr(interp, angle_str.trim())? as f32;
+    turtle.right(angle);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_penup(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.pen_down = false;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_pendown(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.pen_down = true;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_clearscreen(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // Home first so the trip back to the centre is wiped too
+    turtle.home();
+    turtle.clear();
+    Ok(ExecutionResult::Continue)
+}
+
+/// CLEAN: erase the drawing but leave the turtle where it is
+fn execute_clean(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.clear();
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_home(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.home();
+    Ok(ExecutionResult::Continue)
+}
+
+/// Coordinates written `x y` or as a list `[x y]` (SETXY, SETPOS)
+fn parse_position(interp: &Interpreter, keyword: &str, coords: &str) -> Result<(f32, f32)> {
+    let coords = coords.trim();
+    let inner = coords.strip_prefix('[').and_then(|c| c.strip_suffix(']')).unwrap_or(coords);
+    match inner.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [x, y, ..] => Ok((eval_logo_expr(interp, x)? as f32, eval_logo_expr(interp, y)? as f32)),
+        _ => Err(anyhow::anyhow!(trf("logo.needs_xy", &[&keyword]))),
+    }
+}
+
+fn execute_setxy(interp: &mut Interpreter, turtle: &mut TurtleState, keyword: &str, coords: &str) -> Result<ExecutionResult> {
+    let (x, y) = parse_position(interp, keyword, coords)?;
+    turtle.goto(x, y);
+    Ok(ExecutionResult::Continue)
+}
+
+/// SETX: move horizontally, keeping y
+fn execute_setx(interp: &mut Interpreter, turtle: &mut TurtleState, arg: &str) -> Result<ExecutionResult> {
+    let x = eval_logo_expr(interp, arg.trim())? as f32;
+    turtle.goto(x, turtle.y);
+    Ok(ExecutionResult::Continue)
+}
+
+/// SETY: move vertically, keeping x
+fn execute_sety(interp: &mut Interpreter, turtle: &mut TurtleState, arg: &str) -> Result<ExecutionResult> {
+    let y = eval_logo_expr(interp, arg.trim())? as f32;
+    turtle.goto(turtle.x, y);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_setheading(interp: &mut Interpreter, turtle: &mut TurtleState, angle_str: &str) -> Result<ExecutionResult> {
+    // SETHEADING TOWARDS x y turns to face a point
+    let angle_str = angle_str.trim();
+    if let Some(target) = angle_str.strip_prefix("TOWARDS ") {
+        turtle.heading = eval_towards(interp, turtle, target)?;
+        return Ok(ExecutionResult::Continue);
+    }
+    let angle = eval_logo_expr(interp, angle_str)? as f32;
+    turtle.heading = interp.angle_mode.bearing(angle);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_setanglemode(interp: &mut Interpreter, mode: &str) -> Result<ExecutionResult> {
+    interp.angle_mode = AngleMode::from_name(mode)
+        .ok_or_else(|| anyhow::anyhow!(trf("logo.angle_mode_expects", &[&mode.trim()])))?;
+    Ok(ExecutionResult::Continue)
+}
+
+/// Compass bearing from the turtle to the point `x y`
+fn eval_towards(interp: &Interpreter, turtle: &TurtleState, coords: &str) -> Result<f32> {
+    let parts: Vec<&str> = coords.split_whitespace().collect();
+    if parts.len() < 2 {
+        return Err(anyhow::anyhow!(tr("logo.towards_expects")));
+    }
+    let x = eval_logo_expr(interp, parts[0])? as f32;
+    let y = eval_logo_expr(interp, parts[1])? as f32;
+    Ok(turtle.towards(x, y))
+}
+
+fn execute_arc(interp: &mut Interpreter, turtle: &mut TurtleState, args: &str) -> Result<ExecutionResult> {
+    // ARC angle radius: sweep from the current heading in the mode's positive direction
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    if parts.len() < 2 {
+        return Err(anyhow::anyhow!(tr("logo.arc_expects")));
+    }
+    let angle = eval_logo_expr(interp, parts[0])? as f32;
+    let radius = eval_logo_expr(interp, parts[1])? as f32;
+    turtle.arc(interp.angle_mode.clockwise(angle), radius);
+    Ok(ExecutionResult::Continue)
+}
+
+/// Reported angles: whole degrees when close, otherwise two decimals
+fn format_angle(angle: f32) -> String {
+    let rounded = (angle * 100.0).round() / 100.0;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded.rem_euclid(360.0) as i32)
+    } else {
+        format!("{:.2}", rounded)
+    }
+}
+
+fn execute_setcolor(interp: &mut Interpreter, turtle: &mut TurtleState, args: &str) -> Result<ExecutionResult> {
+    // SETCOLOR accepts: r g b (0-255), named color (RED, BLUE), or hex (#RRGGBB, #RGB)
+    // A single pen color ends any SETCOLORCYCLE
+    turtle.set_color_cycle(Vec::new());
+    let trimmed = args.trim();
+    let parts: Vec<&str> = trimmed.split_whitespace().collect();
+    
+    if parts.len() == 1 {
+        let arg = parts[0].to_uppercase();
+        // Check named color
+        if let Some(color) = parse_named_color(&arg) {
+            turtle.pen_color = color;
+            return Ok(ExecutionResult::Continue);
+        }
+        // Check hex color
+        if trimmed.starts_with('#') {
+            if let Some(color) = parse_hex_color(trimmed) {
+                turtle.pen_color = color;
+                return Ok(ExecutionResult::Continue);
+            }
+        }
+    } else if parts.len() >= 3 {
+        // RGB values
+        let r = eval_logo_expr(interp, parts[0])?.clamp(0.0, 255.0) as u8;
+        let g = eval_logo_expr(interp, parts[1])?.clamp(0.0, 255.0) as u8;
+        let b = eval_logo_expr(interp, parts[2])?.clamp(0.0, 255.0) as u8;
+        turtle.pen_color = Rgba::from_rgb(r, g, b);
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+/// `SETCOLORCYCLE [RED YELLOW #00F]`: each segment drawn takes the next color; `[]` turns it off
+fn execute_setcolorcycle(turtle: &mut TurtleState, args: &str) -> Result<ExecutionResult> {
+    let list = args.trim();
+    let names = list
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| anyhow::anyhow!(trf("logo.color_cycle_list", &[&list])))?;
+    let colors = names
+        .split_whitespace()
+        .map(|name| {
+            parse_named_color(name)
+                .or_else(|| name.starts_with('#').then(|| parse_hex_color(name)).flatten())
+                .ok_or_else(|| anyhow::anyhow!(trf("logo.color_cycle_color", &[&name])))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    turtle.set_color_cycle(colors);
+    Ok(ExecutionResult::Continue)
+}
+
+/// `FLASH n`: light up the last n segments on the canvas for a moment
+fn execute_flash(interp: &mut Interpreter, turtle: &mut TurtleState, arg: &str) -> Result<ExecutionResult> {
+    let count = eval_logo_expr(interp, arg.trim())?.max(0.0) as usize;
+    turtle.flash(count);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_penwidth(interp: &mut Interpreter, turtle: &mut TurtleState, arg: &str) -> Result<ExecutionResult> {
+    let w = eval_logo_expr(interp, arg.trim())?.max(0.1) as f32;
+    turtle.pen_width = w;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_setbgcolor(interp: &mut Interpreter, turtle: &mut TurtleState, args: &str) -> Result<ExecutionResult> {
+    let trimmed = args.trim();
+    let parts: Vec<&str> = trimmed.split_whitespace().collect();
+    
+    if parts.len() == 1 {
+        let arg = parts[0].to_uppercase();
+        if let Some(color) = parse_named_color(&arg) {
+            turtle.bg_color = color;
+            return Ok(ExecutionResult::Continue);
+        }
+        if trimmed.starts_with('#') {
+            if let Some(color) = parse_hex_color(trimmed) {
+                turtle.bg_color = color;
+                return Ok(ExecutionResult::Continue);
+            }
+        }
+    } else if parts.len() >= 3 {
+        let r = eval_logo_expr(interp, parts[0])?.clamp(0.0, 255.0) as u8;
+        let g = eval_logo_expr(interp, parts[1])?.clamp(0.0, 255.0) as u8;
+        let b = eval_logo_expr(interp, parts[2])?.clamp(0.0, 255.0) as u8;
+        turtle.bg_color = Rgba::from_rgb(r, g, b);
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_hideturtle(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.visible = false;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_showturtle(turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    turtle.visible = true;
+    Ok(ExecutionResult::Continue)
+}
+
+/// `SETSHAPE "TURTLE` picks a built-in shape; a name with an extension is a points file
+fn execute_setshape(interp: &mut Interpreter, turtle: &mut TurtleState, arg: &str) -> Result<ExecutionResult> {
+    let name = arg.trim().trim_start_matches('"');
+    turtle.shape = if name.contains('.') {
+        let path = interp.policy().resolve_path(name, "SETSHAPE")?;
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!(trf("logo.shape_unreadable", &[&path.display(), &e])))?;
+        TurtleShape::from_points_text(&text).map_err(|e| anyhow::anyhow!("SETSHAPE {}: {}", name, e))?
+    } else {
+        TurtleShape::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = TurtleShape::BUILT_IN.iter().map(|s| s.name()).collect();
+            anyhow::anyhow!(trf("logo.shape_expects", &[&names.join(", ").to_uppercase(), &name]))
+        })?
+    };
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_repeat(interp: &mut Interpreter, params: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // REPEAT n [commands] - supports nested brackets
+    let params = params.trim();
+    
+    // Find count and bracket section
+    let bracket_start = params.find('[').ok_or_else(|| anyhow::anyhow!(tr("logo.repeat_missing_bracket")))?;
+    
+    // Refused before anything runs, counting the REPEATs already running
+    // around this one (through procedure calls too)
+    let max_depth = interp.limits.max_bracket_depth;
+    let depth = interp.repeat_depth + bracket_depth(&params[bracket_start..]);
+    if depth > max_depth {
+        let setting = tr("settings.limits.max_bracket_depth");
+        return Err(TooDeeplyNested::new(tr("limit.bracket_depth"), depth, max_depth, setting).into());
+    }
+    
+    let count_str = params[..bracket_start].trim();
+    let requested = eval_logo_expr(interp, count_str)?;
+    let limit = interp.limits.max_repeat_count;
+    let count = interp.check_loop_span(tr("limit.repeat_count"), requested, limit, tr("settings.limits.max_repeat_count"))?;
+    
+    // Extract balanced bracket content
+    let commands = extract_bracket_content(&params[bracket_start..])?;
+    
+    // Parse commands into a list (handles nested REPEAT)
+    let cmd_list = parse_commands(&commands)?;
+    
+    // Execute commands count times using same turtle
+    interp.repeat_depth += 1;
+    let result = (0..count).try_for_each(|_| cmd_list.iter().try_for_each(|cmd| execute(interp, cmd, turtle).map(|_| ())));
+    interp.repeat_depth -= 1;
+    result?;
+    
+    Ok(ExecutionResult::Continue)
+}
+
+/// How deep `text`'s `[` brackets nest
+fn bracket_depth(text: &str) -> usize {
+    let (mut depth, mut deepest) = (0usize, 0);
+    for c in text.chars() {
+        match c {
+            '[' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Extract content between balanced brackets (including nested ones)
+fn extract_bracket_content(text: &str) -> Result<String> {
+    let mut depth = 0;
+    let mut start_idx = None;
+    let mut end_idx = None;
+    
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '[' => {
+                if depth == 0 {
+                    start_idx = Some(i + 1);
+                }
+                depth += 1;
+            }
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    end_idx = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    if let (Some(start), Some(end)) = (start_idx, end_idx) {
+        Ok(text[start..end].trim().to_string())
+    } else {
+        Err(anyhow::anyhow!(trf("logo.unbalanced_brackets", &[&"REPEAT"])))
+    }
+}
+
+/// Parse commands from a block (splits on whitespace but respects brackets)
+fn parse_commands(block: &str) -> Result<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut depth: i32 = 0;
+    let tokens = block.split_whitespace();
+
+    for token in tokens {
+        // Decide if we should start a new command before appending this token
+        let starts_upper = token.chars().next().map(|c| c.is_ascii_uppercase()).unwrap_or(false);
+        if depth == 0 && starts_upper && !current.is_empty() {
+            // New top-level command starts; flush current
+            commands.push(current.trim().to_string());
+            current.clear();
+        }
+
+        if !current.is_empty() { current.push(' '); }
+        current.push_str(token);
+
+        // Update bracket depth after appending token
+        depth += token.chars().filter(|&c| c == '[').count() as i32;
+        depth -= token.chars().filter(|&c| c == ']').count() as i32;
+    }
+
+    if !current.is_empty() {
+        commands.push(current.trim().to_string());
+    }
+
+    Ok(commands)
+}
+
+
+/// Whether `PRINT input` is Logo's: a `"word` with no closing quote, a `:variable`, or a `[list]`
+///
+/// Anything else, `PRINT "Hi"` or `PRINT 3 + 4`, stays BASIC's PRINT in a
+/// mixed program (a number prints the same either way).
+pub fn is_logo_print(input: &str) -> bool {
+    let input = input.trim();
+    input.starts_with('[') || input.starts_with(':') || input.strip_prefix('"').is_some_and(|word| !word.contains('"'))
+}
+
+/// PRINT writes its input and ends the line, TYPE leaves the line open for
+/// more, and SHOW writes a list with its brackets
+///
+/// The input is a `"word` (the rest of the line), a `[list]`, a `:variable`,
+/// or an expression. A line TYPE leaves open is finished by the next PRINT or
+/// SHOW, or by any other output.
+fn execute_text(interp: &mut Interpreter, command: &str, input: &str) -> Result<ExecutionResult> {
+    let input = input.trim();
+    let text = if let Some(word) = input.strip_prefix('"') {
+        word.to_string()
+    } else if input.starts_with('[') {
+        if !balanced(input) {
+            return Err(anyhow::anyhow!(trf("logo.unbalanced_brackets", &[&command])));
+        }
+        let list = input.split_whitespace().collect::<Vec<_>>().join(" ");
+        if command == "SHOW" { list } else { list[1..list.len() - 1].trim().to_string() }
+    } else if let Some(text) = input.strip_prefix(':').and_then(|name| interp.string_variables.get(&name.to_uppercase())) {
+        text.clone()
+    } else {
+        eval_logo_expr(interp, input)?.to_string()
+    };
+    interp.typed.push_str(&text);
+    if command != "TYPE" {
+        interp.flush_typed();
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+/// Whether `text`'s first `[` is closed by its last character, and every `[` between is closed
+fn balanced(text: &str) -> bool {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth = match depth.checked_sub(1) {
+                    Some(depth) => depth,
+                    None => return false,
+                };
+                if depth == 0 && i + 1 < text.len() {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
+
+fn execute_to(interp: &mut Interpreter, name_and_params: &str) -> Result<ExecutionResult> {
+    // TO <name> [:param ...]: collect subsequent lines until END
+    let tokens: Vec<&str> = name_and_params.split_whitespace().collect();
+    if tokens.is_empty() { return Err(anyhow::anyhow!(tr("logo.to_missing_name"))); }
+    let proc_name = tokens[0].trim().to_uppercase();
+    if proc_name.is_empty() {
+        return Err(anyhow::anyhow!(tr("logo.to_missing_name")));
+    }
+    // Parse params
+    let mut params: Vec<String> = Vec::new();
+    for t in tokens.iter().skip(1) {
+        let t = t.trim();
+        let param = t.strip_prefix(':').unwrap_or(t);
+        interp.check_not_reserved(param)?;
+        params.push(param.to_uppercase());
+    }
+    
+    let mut body: Vec<String> = Vec::new();
+    let start_line = interp.current_line + 1;
+    
+    // Collect lines until END (skip TO line itself)
+    for idx in start_line..interp.program_lines.len() {
+        let (_, line) = &interp.program_lines[idx];
+        let upper = line.trim().to_uppercase();
+        if upper == "END" {
+            // Store procedure and jump past END
+            interp.logo_procedures.insert(proc_name.clone(), LogoProcedure { params, body });
+            interp.current_line = idx;
+            return Ok(ExecutionResult::Continue);
+        }
+        body.push(line.clone());
+    }
+    
+    Err(anyhow::anyhow!(trf("logo.to_missing_end", &[&proc_name])))
+}
+
+fn execute_procedure(interp: &mut Interpreter, name: &str, arg_str: &str, turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    // Execute stored procedure body with optional args
+    if let Some(proc_def) = interp.logo_procedures.get(name).cloned() {
+        if interp.procedure_depth >= MAX_PROCEDURE_DEPTH {
+            return Err(anyhow::anyhow!(trf("logo.too_deep", &[&name, &MAX_PROCEDURE_DEPTH])));
+        }
+    // Bind parameters
+    let args: Vec<&str> = arg_str.split_whitespace().collect();
+        let mut old_num: HashMap<String, Option<f64>> = HashMap::new();
+        let mut old_str: HashMap<String, Option<String>> = HashMap::new();
+        for (i, p) in proc_def.params.iter().enumerate() {
+            // Save old values
+            old_num.insert(p.clone(), interp.variables.get(p).copied());
+            old_str.insert(p.clone(), interp.string_variables.get(p).cloned());
+            // Bind argument
+            if let Some(arg) = args.get(i) {
+                let tok = arg.trim();
+                if tok.len() >= 2 && tok.starts_with('"') && tok.ends_with('"') {
+                    // Quoted string
+                    interp.string_variables.insert(p.clone(), tok[1..tok.len()-1].to_string());
+                    interp.variables.remove(p);
+                } else if let Ok(val) = eval_logo_expr(interp, tok) {
+                    // Numeric
+                    interp.variables.insert(p.clone(), val);
+                    interp.string_variables.remove(p);
+                } else {
+                    // Fallback: raw token as string
+                    interp.string_variables.insert(p.clone(), tok.to_string());
+                    interp.variables.remove(p);
+                }
+            } else {
+                // Default 0 for numeric, remove string
+                interp.variables.insert(p.clone(), 0.0);
+                interp.string_variables.remove(p);
+            }
+        }
+        // Execute body
+        interp.procedure_depth += 1;
+        let result = proc_def.body.iter().try_for_each(|line| execute(interp, line, turtle).map(|_| ()));
+        interp.procedure_depth -= 1;
+        result?;
+        // Restore old vars
+        for (k, v) in old_num.into_iter() {
+            if let Some(val) = v { interp.variables.insert(k.clone(), val); } else { interp.variables.remove(&k); }
+        }
+        for (k, v) in old_str.into_iter() {
+            if let Some(val) = v { interp.string_variables.insert(k.clone(), val); } else { interp.string_variables.remove(&k); }
+        }
+        Ok(ExecutionResult::Continue)
+    } else {
+        Err(anyhow::anyhow!(trf("logo.procedure_not_found", &[&name])))
+    }
+}
+
+fn parse_named_color(name: &str) -> Option<Rgba> {
+    match name {
+        "BLACK" => Some(Rgba::BLACK),
+        "WHITE" => Some(Rgba::WHITE),
+        "RED" => Some(Rgba::from_rgb(255, 0, 0)),
+        "GREEN" => Some(Rgba::from_rgb(0, 255, 0)),
+        "BLUE" => Some(Rgba::from_rgb(0, 0, 255)),
+        "YELLOW" => Some(Rgba::from_rgb(255, 255, 0)),
+        "CYAN" => Some(Rgba::from_rgb(0, 255, 255)),
+        "MAGENTA" => Some(Rgba::from_rgb(255, 0, 255)),
+        "ORANGE" => Some(Rgba::from_rgb(255, 165, 0)),
+        "PURPLE" => Some(Rgba::from_rgb(128, 0, 128)),
+        "PINK" => Some(Rgba::from_rgb(255, 192, 203)),
+        "BROWN" => Some(Rgba::from_rgb(165, 42, 42)),
+        "GRAY" | "GREY" => Some(Rgba::GRAY),
+        _ => None,
+    }
+}
+
+fn parse_hex_color(hex: &str) -> Option<Rgba> {
+    let hex = hex.trim_start_matches('#');
+    
+    if hex.len() == 6 {
+        // #RRGGBB
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+        Some(Rgba::from_rgb(r, g, b))
+    } else if hex.len() == 3 {
+        // #RGB -> #RRGGBB
+        let r = u8::from_str_radix(&hex[0..1].repeat(2), 16).ok()?;
+        let g = u8::from_str_radix(&hex[1..2].repeat(2), 16).ok()?;
+        let b = u8::from_str_radix(&hex[2..3].repeat(2), 16).ok()?;
+        Some(Rgba::from_rgb(r, g, b))
+    } else {
+        None
+    }
+}
+
+fn eval_logo_expr(interp: &Interpreter, expr: &str) -> anyhow::Result<f64> {
+    // Replace occurrences of :VAR with VAR to align with evaluator variables
+    let mut sanitized = String::with_capacity(expr.len());
+    let mut chars = expr.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == ':' {
+            // Collect identifier
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if !name.is_empty() {
+                sanitized.push_str(&name.to_uppercase());
+                continue;
+            } else {
+                // Lone ':'
+                sanitized.push(ch);
+                continue;
+            }
+        }
+        sanitized.push(ch);
+    }
+    interp.evaluate_expression(&sanitized)
+}
+
@@ -0,0 +1,63 @@
+pub mod pilot;
+pub mod basic;
+pub mod logo;
+pub mod symbols;
+pub mod analysis;
+pub mod format;
+pub mod reserved;
+pub mod rename;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// Unified TempleCode language (BASIC + PILOT + Logo)
+    TempleCode,
+    Pilot,
+    Basic,
+    Logo,
+}
+
+impl Language {
+    pub fn from_extension(ext: &str) -> Self {
+        match ext.to_lowercase().as_str() {
+            // TempleCode unified language
+            "tc" | "temple" | "templecode" => Language::TempleCode,
+            "pilot" | "pil" => Language::Pilot,
+            "bas" | "basic" => Language::Basic,
+            "logo" | "lgo" => Language::Logo,
+            _ => Language::Pilot,
+        }
+    }
+    
+    /// Parse a language name such as `basic` or `TempleCode` (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "templecode" | "temple" | "tc" => Some(Language::TempleCode),
+            "pilot" => Some(Language::Pilot),
+            "basic" => Some(Language::Basic),
+            "logo" => Some(Language::Logo),
+            _ => None,
+        }
+    }
+    
+    /// File name extension for a new program in this language
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Language::TempleCode => "tc",
+            Language::Pilot => "pilot",
+            Language::Basic => "bas",
+            Language::Logo => "logo",
+        }
+    }
+    
+    pub fn name(&self) -> &str {
+        match self {
+            Language::TempleCode => "TempleCode",
+            Language::Pilot => "PILOT",
+            Language::Basic => "BASIC",
+            Language::Logo => "Logo",
+        }
+    }
+}
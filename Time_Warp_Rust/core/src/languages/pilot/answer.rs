@@ -0,0 +1,172 @@
+//! Answer matching for `M:` and `QZ:`
+//!
+//! Both the answer and the pattern are normalized the same way before they
+//! are compared, so "Paris.", "  paris" and "PARIS" all match `M:Paris`.
+//! `MC:` (match control) picks the normalization and whether the whole
+//! answer must equal the pattern (`EXACT`) or only contain it. A pattern
+//! starting `%n`, as in `M:%80 necessary`, accepts answers spelled at least
+//! n% like it.
+
+use anyhow::{anyhow, Result};
+
+use crate::i18n::trf;
+
+/// How answers are compared; set by `MC:`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchControl {
+    /// Ignore upper/lower case (`FOLD`)
+    pub case_fold: bool,
+    /// Drop punctuation and symbols (`PUNCT`)
+    pub strip_punctuation: bool,
+    /// Trim, and treat any run of spaces as one (`SPACE`)
+    pub collapse_whitespace: bool,
+    /// The whole answer must equal the pattern, not just contain it (`EXACT`)
+    pub exact: bool,
+}
+
+impl Default for MatchControl {
+    fn default() -> Self {
+        Self { case_fold: true, strip_punctuation: true, collapse_whitespace: true, exact: false }
+    }
+}
+
+impl MatchControl {
+    /// Options from the text after `MC:`, e.g. `EXACT -PUNCT`
+    ///
+    /// Each word turns an option on, or off with a leading `-`; options not
+    /// named keep their defaults, so a bare `MC:` restores them all.
+    pub fn parse(options: &str) -> Result<Self> {
+        let mut control = Self::default();
+        for word in options.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()) {
+            let (on, name) = match word.strip_prefix('-') {
+                Some(name) => (false, name),
+                None => (true, word),
+            };
+            let option = match name.to_uppercase().as_str() {
+                "FOLD" => &mut control.case_fold,
+                "PUNCT" => &mut control.strip_punctuation,
+                "SPACE" => &mut control.collapse_whitespace,
+                "EXACT" => &mut control.exact,
+                _ => return Err(anyhow!(trf("pilot.match_option", &[&word]))),
+            };
+            *option = on;
+        }
+        Ok(control)
+    }
+
+    /// `text` as it is compared
+    pub fn normalize(&self, text: &str) -> String {
+        let mut text = if self.case_fold { text.to_lowercase() } else { text.to_string() };
+        if self.strip_punctuation {
+            text.retain(|c| c.is_alphanumeric() || c.is_whitespace());
+        }
+        if self.collapse_whitespace {
+            text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+        text
+    }
+}
+
+/// Edits (insert, delete, substitute one character) turning `a` into `b`
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How alike two strings are, from 0.0 (nothing in common) to 1.0 (equal)
+///
+/// One minus the edit distance over the longer length, so one typo in a
+/// six-letter word scores about 0.83.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+/// A `%n` prefix as a 0.0–1.0 threshold, and the pattern after it
+fn split_threshold(pattern: &str) -> (Option<f64>, &str) {
+    let trimmed = pattern.trim_start();
+    if let Some(rest) = trimmed.strip_prefix('%') {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if let Ok(percent) = rest[..digits].parse::<u32>() {
+            if percent <= 100 {
+                return (Some(percent as f64 / 100.0), &rest[digits..]);
+            }
+        }
+    }
+    (None, pattern)
+}
+
+/// The answer a pattern expects, without its `%n` threshold (for "The answer is …")
+pub fn expected_answer(pattern: &str) -> &str {
+    split_threshold(pattern).1.trim()
+}
+
+/// Whether `input` answers `pattern` under `control`
+///
+/// With a `%n` threshold the whole answer is scored against the pattern;
+/// otherwise it must contain the pattern, or equal it under `EXACT`.
+pub fn matches_answer(input: &str, pattern: &str, control: &MatchControl) -> bool {
+    let (threshold, pattern) = split_threshold(pattern);
+    let (input, pattern) = (control.normalize(input), control.normalize(pattern));
+    match threshold {
+        Some(threshold) => similarity(&input, &pattern) >= threshold,
+        None if control.exact => input == pattern,
+        None => input.contains(&pattern),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_ignores_case_punctuation_and_spacing() {
+        let control = MatchControl::default();
+        for answer in ["Paris.", "  paris", "PARIS", "paris!!"] {
+            assert_eq!(control.normalize(answer), "paris", "{:?}", answer);
+        }
+        assert_eq!(control.normalize(" New   York,  NY "), "new york ny");
+        let raw = MatchControl { case_fold: false, strip_punctuation: false, collapse_whitespace: false, exact: false };
+        assert_eq!(raw.normalize(" Paris. "), " Paris. ");
+    }
+
+    #[test]
+    fn test_match_control_options() {
+        assert_eq!(MatchControl::parse("").unwrap(), MatchControl::default());
+        let control = MatchControl::parse("EXACT -fold, -PUNCT").unwrap();
+        assert!(control.exact && !control.case_fold && !control.strip_punctuation && control.collapse_whitespace);
+        assert!(MatchControl::parse("LOUD").unwrap_err().to_string().contains("unknown option 'LOUD'"));
+    }
+
+    #[test]
+    fn test_substring_exact_and_approximate_matching() {
+        let partial = MatchControl::default();
+        let exact = MatchControl { exact: true, ..partial };
+        assert!(matches_answer("it's paris", "PARIS", &partial));
+        assert!(!matches_answer("London", "Paris", &partial));
+        assert!(matches_answer("Paris.", "paris", &exact));
+        assert!(!matches_answer("pariss", "paris", &exact));
+        assert!(matches_answer("pariss", "%80 paris", &exact));
+        assert!(!matches_answer("parsi", "%80 paris", &partial), "two edits in five letters");
+        assert_eq!((expected_answer("%80 Paris"), expected_answer("%x Paris")), ("Paris", "%x Paris"));
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("kitten", "sitting"), 1.0 - 3.0 / 7.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+    }
+}
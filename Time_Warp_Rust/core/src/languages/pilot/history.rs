@@ -0,0 +1,110 @@
+//! Answer history: the last answers given to `A:`, and `AN:` to ask again
+//!
+//! Every answer an `A:` (or `AN:`) accepts is remembered, most recent first,
+//! up to `ExecutionLimits::max_answer_history`. Text that interpolates
+//! `*VAR*` also fills in `%ANS1` (the latest answer), `%ANS2` (the one
+//! before), and so on; a `%ANSn` with no answer yet stays as written.
+//!
+//! `AN:` asks the last `A:` again: it repeats the `T:` line the question was
+//! asked with and waits for an answer into the same variable, so a quiz can
+//! re-ask after a wrong answer without writing the question out twice. The
+//! history starts empty each run.
+
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, Result};
+
+use crate::i18n::tr;
+use crate::interpreter::{ExecutionResult, Interpreter};
+
+/// Answers kept when no limit says otherwise
+pub const DEFAULT_ANSWER_HISTORY: usize = 10;
+
+/// An `A:` as `AN:` repeats it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AskedQuestion {
+    /// The text after `A:`
+    pub var_spec: String,
+    /// The last `T:` line shown before it, if any
+    pub question: Option<String>,
+}
+
+/// Recent answers to `A:`, and what `AN:` asks again
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnswerHistory {
+    answers: VecDeque<String>,
+    /// The last line `T:` showed
+    last_text: Option<String>,
+    last_question: Option<AskedQuestion>,
+}
+
+impl AnswerHistory {
+    /// Remember an accepted answer, forgetting the oldest past `capacity`
+    pub fn push(&mut self, answer: &str, capacity: usize) {
+        self.answers.push_front(answer.to_string());
+        self.answers.truncate(capacity);
+    }
+
+    /// The `n`th most recent answer, 1-based as in `%ANS1`
+    pub fn get(&self, n: usize) -> Option<&str> {
+        self.answers.get(n.checked_sub(1)?).map(String::as_str)
+    }
+
+    /// The answers, most recent first
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.answers.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    /// The answer `%ANSn` names, for a name like `ANS2`
+    pub fn lookup(&self, name: &str) -> Option<&str> {
+        self.get(name.strip_prefix("ANS")?.parse().ok()?)
+    }
+
+    /// Note a line `T:` showed, the question for an `A:` that follows
+    pub fn note_text(&mut self, text: &str) {
+        self.last_text = Some(text.to_string());
+    }
+
+    /// Note an `A:`, so `AN:` can ask it again
+    pub fn note_question(&mut self, var_spec: &str) {
+        self.last_question = Some(AskedQuestion { var_spec: var_spec.to_string(), question: self.last_text.clone() });
+    }
+
+    pub fn last_question(&self) -> Option<&AskedQuestion> {
+        self.last_question.as_ref()
+    }
+}
+
+/// `AN:` - ask the last `A:` again, with its question
+pub fn execute_ask_again(interp: &mut Interpreter) -> Result<ExecutionResult> {
+    let asked = interp.answer_history.last_question().cloned().ok_or_else(|| anyhow!(tr("pilot.ask_again_without_question")))?;
+    if let Some(question) = asked.question {
+        interp.speak_output_line(&question);
+        interp.log_output(question);
+    }
+    super::accept(interp, &asked.var_spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_the_most_recent() {
+        let mut history = AnswerHistory::default();
+        for answer in ["red", "green", "blue"] {
+            history.push(answer, 2);
+        }
+        assert_eq!(history.iter().collect::<Vec<_>>(), ["blue", "green"]);
+        assert_eq!((history.lookup("ANS1"), history.lookup("ANS2"), history.lookup("ANS3")), (Some("blue"), Some("green"), None));
+        assert_eq!((history.lookup("ANS0"), history.lookup("ANSWER")), (None, None));
+    }
+}
@@ -0,0 +1,260 @@
+use anyhow::Result;
+use crate::interpreter::{Interpreter, ExecutionResult, Permission};
+use crate::interpreter::segments;
+use crate::graphics::TurtleState;
+use crate::i18n::{tr, trf};
+
+pub mod answer;
+pub mod history;
+pub mod quiz;
+
+pub fn execute(interp: &mut Interpreter, command: &str, _turtle: &mut TurtleState) -> Result<ExecutionResult> {
+    let cmd = command.trim();
+    
+    if cmd.len() < 2 {
+        return Ok(ExecutionResult::Continue);
+    }
+    
+    // Command type is what comes before the colon; splitting there rather than
+    // at byte 2 keeps a line starting with a multi-byte character from panicking
+    let (cmd_type, args) = cmd.split_once(':').unwrap_or(("", cmd));
+    interp.check_enabled(cmd_type, &format!("{}:", cmd_type))?;
+    
+    match cmd_type {
+        "T" => execute_text(interp, args),
+        "V" => execute_voice(interp, args),
+        "A" => execute_accept(interp, args),
+        "AN" => history::execute_ask_again(interp),
+        "U" => execute_use(interp, args),
+        "C" => execute_compute(interp, args),
+        "Y" => execute_yes(interp, args),
+        "N" => execute_no(interp, args),
+        "M" => execute_match(interp, args),
+        "J" => execute_jump(interp, args),
+        "L" => Ok(ExecutionResult::Continue), // Label, no action
+        "D" if quiz::is_quiz_data(args) => quiz::execute_data(args), // Quiz question, collected at load
+        "D" => execute_dialog(interp, args),
+        "E" => Ok(ExecutionResult::End),
+        "R" => execute_runtime(interp, args),
+        "TW" => execute_text_width(interp, args),
+        "TI" => {
+            // TI:name - the program's title in the window and on the Output banner
+            let title = interp.interpolate_text(args.trim());
+            interp.set_program_title(&title);
+            Ok(ExecutionResult::Continue)
+        }
+        "QZ" => quiz::execute_quiz(interp, args),
+        "MC" => execute_match_control(interp, args),
+        "" if label_definition(cmd).is_some() => Ok(ExecutionResult::Continue), // *label
+        _ => {
+            interp.log_output(trf("pilot.unknown_command", &[&cmd]));
+            Ok(ExecutionResult::Continue)
+        }
+    }
+}
+
+/// Name defined by a label line: `L:name`, or classic PILOT `*name` on a line of its own
+pub fn label_definition(command: &str) -> Option<&str> {
+    let command = command.trim();
+    if let Some(name) = command.strip_prefix("L:") {
+        return Some(name.trim());
+    }
+    let name = command.strip_prefix('*')?;
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some(name)
+}
+
+fn execute_text(interp: &mut Interpreter, text: &str) -> Result<ExecutionResult> {
+    // Check if conditional output (following Y: or N:)
+    if interp.last_match_set {
+        interp.last_match_set = false;
+        if !interp.match_flag {
+            return Ok(ExecutionResult::Continue);
+        }
+    }
+    
+    let segments = interp.interpolate_segments(text.trim());
+    let line = segments::join(&segments);
+    interp.speak_output_line(&line);
+    interp.answer_history.note_text(&line);
+    interp.log_program_segments(segments);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_text_width(interp: &mut Interpreter, width: &str) -> Result<ExecutionResult> {
+    // TW:n - wrap T: output at n columns (TW:0 turns wrapping off)
+    let width = interp.evaluate_expression(width.trim())?;
+    if !(0.0..=1000.0).contains(&width) {
+        return Err(anyhow::anyhow!(tr("pilot.text_width_range")));
+    }
+    interp.text_width = Some(width as usize);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_voice(interp: &mut Interpreter, text: &str) -> Result<ExecutionResult> {
+    // V:text - speak without printing; conditional like T:
+    if interp.last_match_set {
+        interp.last_match_set = false;
+        if !interp.match_flag {
+            return Ok(ExecutionResult::Continue);
+        }
+    }
+    
+    let spoken = interp.interpolate_text(text.trim());
+    if !interp.speak(&spoken) {
+        // No speech engine: degrade to a visible log line
+        interp.log_output(format!("[voice] {}", spoken));
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_accept(interp: &mut Interpreter, var: &str) -> Result<ExecutionResult> {
+    interp.answer_history.note_question(var);
+    accept(interp, var)
+}
+
+/// Ask for an answer into `var` (the text after `A:`), remembering it in the answer history
+fn accept(interp: &mut Interpreter, var: &str) -> Result<ExecutionResult> {
+    let var_name = var.trim();
+
+    // If an input callback is wired, use it synchronously
+    if interp.input_callback.is_some() {
+        let input = interp.request_input(var_name);
+        interp.answer_history.push(&input, interp.limits.max_answer_history);
+        match input.trim().parse::<f64>() {
+            Ok(num) => { interp.variables.insert(var_name.to_string(), num); }
+            Err(_) => { interp.string_variables.insert(var_name.to_string(), input); }
+        }
+        interp.note_assignment(var_name);
+        return Ok(ExecutionResult::Continue);
+    }
+
+    // Otherwise, start pending input request and pause
+    let prompt = format!("{} ", var_name);
+    interp.start_input_request(&prompt, var_name, true);
+    if let Some(request) = interp.pending_input.as_mut() {
+        request.remember_answer = true;
+    }
+    Ok(ExecutionResult::WaitForInput)
+}
+
+fn execute_dialog(interp: &mut Interpreter, text: &str) -> Result<ExecutionResult> {
+    // D:message - OK message box; D:YESNO question - Yes/No box, Yes sets the match flag
+    let text = text.trim();
+    let (yes_no, message) = match text.get(..5) {
+        Some(word) if word.eq_ignore_ascii_case("YESNO") && text[5..].starts_with(char::is_whitespace) => (true, text[5..].trim()),
+        _ => (false, text),
+    };
+    let message = interp.interpolate_text(message);
+    interp.show_dialog(&message, yes_no, "")
+}
+
+fn execute_use(interp: &mut Interpreter, assignment: &str) -> Result<ExecutionResult> {
+    // U:VAR=expression
+    if let Some(pos) = assignment.find('=') {
+        let var_name = assignment[..pos].trim().to_string();
+        let expr = assignment[pos + 1..].trim();
+        
+        match interp.evaluate_expression(expr) {
+            Ok(value) => {
+                interp.variables.insert(var_name.clone(), value);
+            }
+            Err(_) => {
+                // Treat as string
+                interp.string_variables.insert(var_name.clone(), expr.to_string());
+            }
+        }
+        interp.note_assignment(&var_name);
+    }
+    
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_compute(interp: &mut Interpreter, condition: &str) -> Result<ExecutionResult> {
+    // C:condition - store for Y:/N:
+    let result = evaluate_condition(interp, condition)?;
+    interp.stored_condition = Some(result);
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_yes(interp: &mut Interpreter, condition: &str) -> Result<ExecutionResult> {
+    let result = if condition.trim().is_empty() {
+        interp.stored_condition.unwrap_or(false)
+    } else {
+        evaluate_condition(interp, condition)?
+    };
+    
+    interp.match_flag = result;
+    interp.last_match_set = true;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_no(interp: &mut Interpreter, condition: &str) -> Result<ExecutionResult> {
+    let result = if condition.trim().is_empty() {
+        interp.stored_condition.unwrap_or(false)
+    } else {
+        evaluate_condition(interp, condition)?
+    };
+    
+    interp.match_flag = !result;
+    interp.last_match_set = true;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_match(interp: &mut Interpreter, pattern: &str) -> Result<ExecutionResult> {
+    // M:pattern - match last input against pattern (contains it, as MC: normalizes both)
+    interp.match_flag = answer::matches_answer(&interp.last_input, pattern, &interp.match_control);
+    interp.last_match_set = true;
+    
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_match_control(interp: &mut Interpreter, options: &str) -> Result<ExecutionResult> {
+    // MC:options - how later M: and QZ: compare answers (EXACT, -FOLD, ...)
+    interp.match_control = answer::MatchControl::parse(options)?;
+    Ok(ExecutionResult::Continue)
+}
+
+fn execute_jump(interp: &mut Interpreter, label: &str) -> Result<ExecutionResult> {
+    // J:*LABEL names a classic *LABEL the same as J:LABEL
+    let label = label.trim().trim_start_matches('*');
+    
+    if let Some(line) = interp.jump_to_label(label) {
+        Ok(ExecutionResult::Jump(line))
+    } else {
+        interp.log_output(trf("pilot.label_not_found", &[&label]));
+        Ok(ExecutionResult::Continue)
+    }
+}
+
+fn execute_runtime(interp: &mut Interpreter, command: &str) -> Result<ExecutionResult> {
+    // R: commands - runtime/hardware simulation; R:@key lines are metadata headers
+    if command.trim_start().starts_with('@') {
+        return Ok(ExecutionResult::Continue);
+    }
+    // Check the security policy before anything reaches outside the program
+    let mut words = command.split_whitespace();
+    let name = words.next().unwrap_or("").to_uppercase();
+    let statement = format!("R:{}", name);
+    match name.as_str() {
+        "SAVE" | "LOAD" => {
+            interp.policy().resolve_path(words.next().unwrap_or(""), &statement)?;
+        }
+        "MQTT" | "HTTP" => interp.policy().require(Permission::Network, &statement)?,
+        "PLUGIN" => interp.policy().require(Permission::Plugins, &statement)?,
+        // A source breakpoint (see interpreter::breakpoints); nothing to run
+        "BREAK" => return Ok(ExecutionResult::Continue),
+        _ => {}
+    }
+    // TODO: Implement R: commands (SAVE, LOAD, RPI, ARDUINO, ROBOT, etc.)
+    interp.log_output(trf("pilot.runtime_unimplemented", &[&command]));
+    Ok(ExecutionResult::Continue)
+}
+
+/// Whether a C:/Y:/N: condition holds: `X > 5`, `A = B AND C <> 0`, or any
+/// expression, true when it is not 0 (see [`Interpreter::condition_holds`])
+fn evaluate_condition(interp: &mut Interpreter, condition: &str) -> Result<bool> {
+    let value = interp.evaluate_expression(condition.trim())?;
+    interp.condition_holds(value)
+}
@@ -0,0 +1,170 @@
+//! Data-driven quizzes: `D:question|answer1,answer2|feedback` lines and `QZ:`
+//!
+//! `D:` lines are collected when the program loads and do nothing when
+//! reached. `QZ:` asks every collected question in order (`QZ:SHUFFLE`
+//! randomizes it; `QZ:SHUFFLE 7` seeds the generator first so the order
+//! repeats), matches each answer the way `M:` does, under the same `MC:`
+//! options, against any of the listed answers, prints the verdict and the
+//! question's feedback, and keeps the score in the `RIGHT` and `WRONG`
+//! variables.
+//!
+//! Without an input callback each question pauses for the UI like `A:`; the
+//! `QZ:` line runs again after the answer to grade it and ask the next one.
+
+use anyhow::{anyhow, Result};
+use rand::seq::SliceRandom;
+
+use super::answer::{expected_answer, matches_answer};
+use crate::i18n::{tr, trf};
+use crate::interpreter::{ExecutionResult, Interpreter};
+
+/// Variable names holding the running score
+pub const RIGHT_VAR: &str = "RIGHT";
+pub const WRONG_VAR: &str = "WRONG";
+
+/// One `D:` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuizItem {
+    pub question: String,
+    /// Accepted answers; matching any one is correct
+    pub answers: Vec<String>,
+    pub feedback: Option<String>,
+}
+
+/// Progress through a `QZ:` run, kept while it waits for an answer
+#[derive(Debug, Clone)]
+pub struct QuizRun {
+    /// Indexes into the program's questions, in asking order
+    order: Vec<usize>,
+    next: usize,
+    right: usize,
+    wrong: usize,
+    /// The question at `next` was asked and its answer is in `last_input`
+    awaiting: bool,
+}
+
+/// Parse the text after `D:`; `None` unless it has a question and at least one answer
+pub fn parse_data_line(data: &str) -> Option<QuizItem> {
+    let mut fields = data.split('|');
+    let question = fields.next()?.trim();
+    let answers: Vec<String> = fields.next()?
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect();
+    let feedback = fields.next().map(str::trim).filter(|f| !f.is_empty()).map(str::to_string);
+    if question.is_empty() || answers.is_empty() || fields.next().is_some() {
+        return None;
+    }
+    Some(QuizItem { question: question.to_string(), answers, feedback })
+}
+
+/// Whether the text after `D:` is a quiz question; without a `|` it is a message box
+pub fn is_quiz_data(data: &str) -> bool {
+    data.contains('|')
+}
+
+/// `D:` reached while running: already collected, only checked
+pub fn execute_data(data: &str) -> Result<ExecutionResult> {
+    if parse_data_line(data).is_none() {
+        return Err(anyhow!(tr("pilot.quiz_data_expects")));
+    }
+    Ok(ExecutionResult::Continue)
+}
+
+fn start(interp: &mut Interpreter, args: &str) -> Result<QuizRun> {
+    let mut words = args.split_whitespace();
+    let shuffle = match words.next() {
+        None => false,
+        Some(word) if word.eq_ignore_ascii_case("SHUFFLE") => true,
+        Some(word) => return Err(anyhow!(trf("pilot.quiz_option", &[&word]))),
+    };
+    if let Some(seed) = words.next() {
+        let seed = seed.parse::<u64>().map_err(|_| anyhow!(tr("pilot.quiz_seed")))?;
+        interp.seed_random(seed);
+    }
+    if interp.quiz_items.is_empty() {
+        return Err(anyhow!(tr("pilot.quiz_no_questions")));
+    }
+
+    let mut order: Vec<usize> = (0..interp.quiz_items.len()).collect();
+    if shuffle {
+        order.shuffle(interp.rng.get_mut());
+    }
+    interp.variables.insert(RIGHT_VAR.to_string(), 0.0);
+    interp.variables.insert(WRONG_VAR.to_string(), 0.0);
+    Ok(QuizRun { order, next: 0, right: 0, wrong: 0, awaiting: false })
+}
+
+fn say(interp: &mut Interpreter, text: String) {
+    interp.speak_output_line(&text);
+    interp.log_program_output(text);
+}
+
+/// Grade `answer` against the current question, print the verdict, and move on
+fn grade(interp: &mut Interpreter, run: &mut QuizRun, answer: &str) {
+    let item = interp.quiz_items[run.order[run.next]].clone();
+    if item.answers.iter().any(|a| matches_answer(answer, a, &interp.match_control)) {
+        run.right += 1;
+        say(interp, tr("pilot.quiz_correct").to_string());
+    } else {
+        run.wrong += 1;
+        say(interp, trf("pilot.quiz_wrong", &[&expected_answer(&item.answers[0])]));
+    }
+    if let Some(feedback) = item.feedback {
+        say(interp, interp.interpolate_text(&feedback));
+    }
+    interp.variables.insert(RIGHT_VAR.to_string(), run.right as f64);
+    interp.variables.insert(WRONG_VAR.to_string(), run.wrong as f64);
+    run.next += 1;
+    run.awaiting = false;
+}
+
+/// `QZ:` / `QZ:SHUFFLE [seed]`: start a quiz, or carry one on after an answer
+pub fn execute_quiz(interp: &mut Interpreter, args: &str) -> Result<ExecutionResult> {
+    let mut run = match interp.quiz.take() {
+        Some(run) => run,
+        None => start(interp, args.trim())?,
+    };
+    loop {
+        if run.awaiting {
+            let answer = interp.last_input.clone();
+            grade(interp, &mut run, &answer);
+        }
+        if run.next >= run.order.len() {
+            say(interp, trf("pilot.quiz_score", &[&run.right, &run.order.len()]));
+            return Ok(ExecutionResult::Continue);
+        }
+
+        let question = interp.interpolate_text(&interp.quiz_items[run.order[run.next]].question);
+        say(interp, question.clone());
+        run.awaiting = true;
+        if interp.input_callback.is_some() {
+            interp.request_input(&question);
+            continue;
+        }
+        interp.start_input_request("> ", "ANSWER", false);
+        interp.rerun_after_input();
+        interp.quiz = Some(run);
+        return Ok(ExecutionResult::WaitForInput);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_line() {
+        let item = parse_data_line(" Capital of France? | Paris , paris  | Paris is on the Seine ").unwrap();
+        assert_eq!(item.question, "Capital of France?");
+        assert_eq!(item.answers, vec!["Paris", "paris"]);
+        assert_eq!(item.feedback.as_deref(), Some("Paris is on the Seine"));
+
+        assert_eq!(parse_data_line("2+2?|4").unwrap().feedback, None);
+        for bad in ["no answers", "|4", "Q?| , ", "Q?|A|B|C"] {
+            assert!(parse_data_line(bad).is_none(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,535 @@
+//! Rename Symbol: every true reference to a variable, label, line number, or procedure
+//!
+//! A small scanner reads each line the way its language does, skipping string
+//! literals, comments (`REM`, `'`, `;`), `DATA` values, PILOT `R:` and `M:`
+//! lines, and text Logo prints, so renaming `A` leaves `"A"`, `AB`, and `A$`
+//! alone. Variables and procedures match without regard to case, labels
+//! exactly, and line numbers by value. A BASIC line number is renamed with
+//! every `GOTO`, `GOSUB`, `THEN`, `ELSE`, and `RESTORE` that names it, `ON …
+//! GOTO` lists included; a PILOT label with its `J:name` and `J:*name` jumps
+//! (a `*NAME*` in `T:` text is a variable, not the label). A Logo procedure's
+//! input belongs to that procedure. In TempleCode each line is read in the
+//! language it runs as, decided as [`format`](super::format) decides it.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::format::{split_line_number, Layout};
+use super::{analysis, basic, logo, pilot, Language};
+use crate::i18n::trf;
+use crate::interpreter::metadata::parse_metadata;
+use crate::utils::expr_eval::{type_suffix, TEXT_FUNCTIONS};
+use crate::utils::ExpressionEvaluator;
+
+/// BASIC words, besides keywords and functions, that never name a variable
+const NOT_NAMES: [&str; 4] = ["ON", "DEF", "BASE", "STOP"];
+
+/// BASIC words followed by line numbers
+const JUMPS: [&str; 5] = ["GOTO", "GOSUB", "THEN", "ELSE", "RESTORE"];
+
+/// Logo commands whose `[list]` or `"word` input is text
+const LOGO_TEXT: [&str; 4] = ["PRINT", "PR", "SHOW", "TYPE"];
+
+static INTERPOLATED: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*([A-Z_][A-Z0-9_]*)\*").expect("Invalid regex pattern"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    /// BASIC or PILOT variable, or Logo `:input`
+    Variable,
+    /// PILOT `L:name` / `*name`
+    Label,
+    /// BASIC line number
+    LineNumber,
+    /// Logo `TO name`
+    Procedure,
+}
+
+/// What Rename Symbol renames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// Upper case for a variable or procedure, as written for a label, the number for a line number
+    pub name: String,
+    pub kind: TargetKind,
+    /// 1-based line of the `TO` whose input this is; `None` for a name the whole program shares
+    pub scope: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    /// Bytes of the name in the source, without a `:`, `*`, or `"` before it
+    pub range: Range<usize>,
+    /// 1-based line
+    pub line: usize,
+}
+
+/// Why a new name was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// Not a name (or, for a line number, not a number)
+    Invalid(String),
+    /// A keyword or built-in function
+    Reserved(String),
+    /// Already used, so the two would become one
+    Taken(String),
+    /// A variable's type suffix (`$`, `%`, `!`, `#`) cannot change
+    Suffix(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            RenameError::Invalid(name) => trf("rename.error.invalid", &[name]),
+            RenameError::Reserved(name) => trf("rename.error.reserved", &[name]),
+            RenameError::Taken(name) => trf("rename.error.taken", &[name]),
+            RenameError::Suffix(name) => trf("rename.error.suffix", &[name]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+/// The symbol at byte `at` of `source`, or ending there
+pub fn symbol_at(source: &str, language: Language, at: usize) -> Option<Target> {
+    scan(source, language)
+        .into_iter()
+        .find(|(_, occurrence)| occurrence.range.contains(&at) || occurrence.range.end == at)
+        .map(|(target, _)| target)
+}
+
+/// Every reference to `target` in `source`, definitions included, in order
+pub fn references(source: &str, language: Language, target: &Target) -> Vec<Occurrence> {
+    scan(source, language).into_iter().filter(|(t, _)| t == target).map(|(_, occurrence)| occurrence).collect()
+}
+
+/// Whether `new_name` can replace `target` in `source`
+pub fn check_name(source: &str, language: Language, target: &Target, new_name: &str) -> Result<(), RenameError> {
+    replacement(&scan(source, language), target, new_name.trim()).map(|_| ())
+}
+
+/// `source` with every reference to `target` renamed to `new_name`
+pub fn rename(source: &str, language: Language, target: &Target, new_name: &str) -> Result<String, RenameError> {
+    let found = scan(source, language);
+    let text = replacement(&found, target, new_name.trim())?;
+    let mut renamed = source.to_string();
+    for (_, occurrence) in found.iter().rev().filter(|(t, _)| t == target) {
+        renamed.replace_range(occurrence.range.clone(), &text);
+    }
+    Ok(renamed)
+}
+
+/// The text each occurrence of `target` becomes, when `new_name` is allowed
+fn replacement(found: &[(Target, Occurrence)], target: &Target, new_name: &str) -> Result<String, RenameError> {
+    let invalid = || RenameError::Invalid(new_name.to_string());
+    let key = match target.kind {
+        TargetKind::LineNumber => {
+            if new_name.is_empty() || !new_name.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            new_name.parse::<usize>().map_err(|_| invalid())?.to_string()
+        }
+        TargetKind::Label => {
+            if !is_identifier(new_name) {
+                return Err(invalid());
+            }
+            new_name.to_string()
+        }
+        TargetKind::Variable => {
+            if !is_variable(new_name) {
+                return Err(invalid());
+            }
+            let upper = new_name.to_ascii_uppercase();
+            if suffix(&upper) != suffix(&target.name) {
+                return Err(RenameError::Suffix(target.name.clone()));
+            }
+            if !is_name(&upper) || logo::KEYWORDS.contains(&upper.as_str()) {
+                return Err(RenameError::Reserved(upper));
+            }
+            upper
+        }
+        TargetKind::Procedure => {
+            if !is_identifier(new_name) {
+                return Err(invalid());
+            }
+            let upper = new_name.to_ascii_uppercase();
+            if basic::KEYWORDS.contains(&upper.as_str()) || logo::KEYWORDS.contains(&upper.as_str()) {
+                return Err(RenameError::Reserved(upper));
+            }
+            upper
+        }
+    };
+    if key != target.name && found.iter().any(|(t, _)| t.kind == target.kind && t.name == key) {
+        return Err(RenameError::Taken(new_name.to_string()));
+    }
+    Ok(if target.kind == TargetKind::LineNumber { key } else { new_name.to_string() })
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A name with at most a type suffix after it
+fn is_variable(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name_end(name, 0) == name.len()
+}
+
+fn suffix(name: &str) -> Option<char> {
+    name.chars().last().filter(|c| matches!(c, '$' | '%' | '!' | '#'))
+}
+
+/// Whether an upper-case word in an expression can name a variable
+fn is_name(upper: &str) -> bool {
+    !(basic::KEYWORDS.contains(&upper)
+        || analysis::RESERVED.contains(&upper)
+        || NOT_NAMES.contains(&upper)
+        || ExpressionEvaluator::FUNCTIONS.contains(&upper)
+        || TEXT_FUNCTIONS.contains(&upper)
+        || upper.starts_with("FN"))
+}
+
+/// End of the name starting at byte `start` of `text`, its type suffix included
+fn name_end(text: &str, start: usize) -> usize {
+    let end = text[start..].find(|c: char| !c.is_ascii_alphanumeric() && c != '_').map_or(text.len(), |n| start + n);
+    if text[end..].starts_with('$') || type_suffix(text[end..].chars()).is_some() {
+        end + 1
+    } else {
+        end
+    }
+}
+
+/// End of the number starting at byte `start` of `text`, an exponent included
+fn number_end(text: &str, start: usize) -> usize {
+    let digits = |from: usize| text[from..].find(|c: char| !c.is_ascii_digit() && c != '.').map_or(text.len(), |n| from + n);
+    let end = digits(start);
+    let rest = &text.as_bytes()[end..];
+    match rest {
+        [b'e' | b'E', b'+' | b'-', b'0'..=b'9', ..] => digits(end + 2),
+        [b'e' | b'E', b'0'..=b'9', ..] => digits(end + 1),
+        _ => end,
+    }
+}
+
+/// Every symbol in `source`, in order
+fn scan(source: &str, language: Language) -> Vec<(Target, Occurrence)> {
+    let language = parse_metadata(source).language.unwrap_or(language);
+    let mut scanner = Scanner {
+        procedures: source.lines().filter_map(procedure_definition).collect(),
+        layout: Layout::new(language),
+        numbered: matches!(language, Language::Basic | Language::TempleCode),
+        found: Vec::new(),
+        line: 0,
+        start: 0,
+        procedure: None,
+    };
+    for line in source.split('\n') {
+        scanner.line += 1;
+        scanner.scan_line(line.strip_suffix('\r').unwrap_or(line));
+        scanner.start += line.len() + 1;
+    }
+    scanner.found
+}
+
+/// Upper-case name of the Logo procedure `line` defines
+fn procedure_definition(line: &str) -> Option<String> {
+    let mut words = split_line_number(line).1.split_whitespace();
+    let to = words.next()?;
+    to.eq_ignore_ascii_case("TO").then(|| words.next()).flatten().map(str::to_uppercase)
+}
+
+struct Scanner {
+    /// Every Logo procedure the program defines, upper case
+    procedures: HashSet<String>,
+    layout: Layout,
+    /// Whether lines may start with a BASIC line number
+    numbered: bool,
+    found: Vec<(Target, Occurrence)>,
+    /// 1-based line being read, and its first byte
+    line: usize,
+    start: usize,
+    /// The `TO` line of the procedure being read, and its inputs
+    procedure: Option<(usize, HashSet<String>)>,
+}
+
+impl Scanner {
+    /// Note the symbol at bytes `range` of the current line
+    fn push(&mut self, kind: TargetKind, name: String, range: Range<usize>, scope: Option<usize>) {
+        let range = self.start + range.start..self.start + range.end;
+        self.found.push((Target { name, kind, scope }, Occurrence { range, line: self.line }));
+    }
+
+    fn scan_line(&mut self, line: &str) {
+        let indent = line.len() - line.trim_start().len();
+        let mut at = indent;
+        if let (Some(number), _) = split_line_number(line) {
+            if self.numbered {
+                self.line_number(number, indent);
+            }
+            let rest = &line[indent + number.len()..];
+            at = line.len() - rest.trim_start().len();
+        }
+        let statement = line[at..].trim_end();
+        let (language, _) = self.layout.place(statement);
+        match language.unwrap_or(Language::Logo) {
+            Language::Basic | Language::TempleCode => self.basic(statement, at),
+            Language::Pilot => self.pilot(statement, at),
+            Language::Logo => self.logo_line(statement, at),
+        }
+    }
+
+    fn line_number(&mut self, number: &str, at: usize) {
+        if let Ok(value) = number.parse::<usize>() {
+            self.push(TargetKind::LineNumber, value.to_string(), at..at + number.len(), None);
+        }
+    }
+
+    /// A BASIC statement, or a PILOT expression, starting at byte `at` of the line
+    fn basic(&mut self, text: &str, at: usize) {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        // Whether a number here is a line number
+        let mut jump = false;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    i = text[i + 1..].find('"').map_or(text.len(), |n| i + n + 2);
+                    jump = false;
+                }
+                b'\'' => return,
+                b'0'..=b'9' | b'.' => {
+                    let end = number_end(text, i);
+                    if jump && text[i..end].bytes().all(|b| b.is_ascii_digit()) {
+                        self.line_number(&text[i..end], at + i);
+                        // ON X GOTO 10, 20, 30
+                        jump = text[end..].trim_start().starts_with(',');
+                    } else {
+                        jump = false;
+                    }
+                    i = end;
+                }
+                b if b.is_ascii_alphabetic() || b == b'_' => {
+                    let end = name_end(text, i);
+                    let upper = text[i..end].to_ascii_uppercase();
+                    if upper == "REM" || upper == "DATA" {
+                        return;
+                    }
+                    jump = JUMPS.contains(&upper.as_str());
+                    if is_name(&upper) {
+                        self.push(TargetKind::Variable, upper, at + i..at + end, None);
+                    }
+                    i = end;
+                }
+                b',' | b' ' | b'\t' => i += 1,
+                _ => {
+                    jump = false;
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// A PILOT statement starting at byte `at` of the line
+    fn pilot(&mut self, text: &str, at: usize) {
+        if let Some(name) = text.strip_prefix('*') {
+            if pilot::label_definition(text).is_some() {
+                self.push(TargetKind::Label, name.to_string(), at + 1..at + text.len(), None);
+            }
+            return;
+        }
+        let Some((command, args)) = text.split_once(':') else { return };
+        let args_at = at + command.len() + 1;
+        let name_at = args_at + args.len() - args.trim_start().len();
+        let name = args.trim();
+        match command {
+            "L" if !name.is_empty() => self.push(TargetKind::Label, name.to_string(), name_at..name_at + name.len(), None),
+            "J" => {
+                let label = name.trim_start_matches('*');
+                let label_at = name_at + name.len() - label.len();
+                if !label.is_empty() {
+                    self.push(TargetKind::Label, label.to_string(), label_at..label_at + label.len(), None);
+                }
+            }
+            "A" if is_variable(name) => {
+                self.push(TargetKind::Variable, name.to_ascii_uppercase(), name_at..name_at + name.len(), None);
+            }
+            "U" => {
+                let Some(equals) = args.find('=') else { return };
+                let variable = &args[..equals];
+                let variable_at = args_at + variable.len() - variable.trim_start().len();
+                let variable = variable.trim();
+                if is_variable(variable) {
+                    self.push(TargetKind::Variable, variable.to_ascii_uppercase(), variable_at..variable_at + variable.len(), None);
+                }
+                self.basic(&args[equals + 1..], args_at + equals + 1);
+            }
+            "C" | "Y" | "N" | "TW" => self.basic(args, args_at),
+            "T" | "V" | "TI" => {
+                for name in INTERPOLATED.captures_iter(args).filter_map(|c| c.get(1)) {
+                    let range = args_at + name.start()..args_at + name.end();
+                    self.push(TargetKind::Variable, name.as_str().to_string(), range, None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// A Logo line starting at byte `at`, entering or leaving a procedure
+    fn logo_line(&mut self, text: &str, at: usize) {
+        let mut words = text.split_whitespace();
+        if words.next().is_some_and(|w| w.eq_ignore_ascii_case("TO")) {
+            let inputs = words.filter_map(|w| w.strip_prefix(':')).map(str::to_uppercase).collect();
+            self.procedure = Some((self.line, inputs));
+        }
+        self.logo(text, at);
+        if text.eq_ignore_ascii_case("END") {
+            self.procedure = None;
+        }
+    }
+
+    fn logo(&mut self, text: &str, at: usize) {
+        let bytes = text.as_bytes();
+        let word_end = |from: usize| text[from..].find(|c: char| c.is_whitespace() || "[]()".contains(c)).map_or(text.len(), |n| from + n);
+        let mut previous = String::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b';' => return,
+                b':' => {
+                    let end = name_end(text, i + 1);
+                    if end > i + 1 {
+                        let name = text[i + 1..end].to_ascii_uppercase();
+                        let scope = self.procedure.as_ref().filter(|(_, inputs)| inputs.contains(&name)).map(|(line, _)| *line);
+                        self.push(TargetKind::Variable, name, at + i + 1..at + end, scope);
+                    }
+                    previous.clear();
+                    i = end.max(i + 1);
+                }
+                // A "word is itself, never a name
+                b'"' => {
+                    previous.clear();
+                    i = word_end(i + 1);
+                }
+                b'[' if LOGO_TEXT.contains(&previous.as_str()) => {
+                    let mut depth = 0;
+                    let close = text[i..].char_indices().find(|&(_, c)| {
+                        depth += match c {
+                            '[' => 1,
+                            ']' => -1,
+                            _ => 0,
+                        };
+                        depth == 0
+                    });
+                    previous.clear();
+                    i = close.map_or(text.len(), |(n, _)| i + n + 1);
+                }
+                b if b.is_ascii_alphabetic() || b == b'_' => {
+                    let end = name_end(text, i);
+                    let upper = text[i..end].to_ascii_uppercase();
+                    if previous == "TO" || self.procedures.contains(&upper) {
+                        self.push(TargetKind::Procedure, upper.clone(), at + i..at + end, None);
+                    }
+                    if LOGO_TEXT.contains(&upper.as_str()) && text[end..].trim_start().starts_with('"') {
+                        // PRINT "word prints the rest of the line
+                        return;
+                    }
+                    previous = upper;
+                    i = end;
+                }
+                b' ' | b'\t' => i += 1,
+                _ => {
+                    previous.clear();
+                    i += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rename the symbol at the first `at` in `source`
+    fn rename_at(source: &str, language: Language, at: &str, new_name: &str) -> Result<String, RenameError> {
+        let target = symbol_at(source, language, source.find(at).expect("no such text")).expect("no symbol there");
+        rename(source, language, &target, new_name)
+    }
+
+    #[test]
+    fn test_partial_names_strings_and_comments() {
+        let source = "10 LET VAR = 1\n20 LET VARIANT = VAR + 2\n30 PRINT VAR$; \"VAR\"; VAR\n40 REM VAR\n50 PRINT VAR ' VAR\n60 DATA VAR";
+        assert_eq!(
+            rename_at(source, Language::Basic, "VAR =", "COUNT").unwrap(),
+            "10 LET COUNT = 1\n20 LET VARIANT = COUNT + 2\n30 PRINT VAR$; \"VAR\"; COUNT\n40 REM VAR\n50 PRINT COUNT ' VAR\n60 DATA VAR"
+        );
+        let target = symbol_at(source, Language::Basic, source.find("VAR$").unwrap()).unwrap();
+        assert_eq!(target.name, "VAR$");
+        assert_eq!(references(source, Language::Basic, &target).len(), 1);
+        // Lower case is the same variable
+        assert_eq!(rename_at("10 x = 1\n20 PRINT X", Language::Basic, "x", "Y").unwrap(), "10 Y = 1\n20 PRINT Y");
+    }
+
+    #[test]
+    fn test_pilot_labels_with_and_without_star() {
+        let source = "*START\nT:Hi\nJ:START\nJ:*START\nL:STARTER\nJ:start\nT:START is *START*\nU:START=1";
+        let target = symbol_at(source, Language::Pilot, 2).unwrap();
+        assert_eq!((target.kind, target.name.as_str()), (TargetKind::Label, "START"));
+        assert_eq!(references(source, Language::Pilot, &target).iter().map(|o| o.line).collect::<Vec<_>>(), vec![1, 3, 4]);
+        assert_eq!(
+            rename(source, Language::Pilot, &target, "BEGIN").unwrap(),
+            "*BEGIN\nT:Hi\nJ:BEGIN\nJ:*BEGIN\nL:STARTER\nJ:start\nT:START is *START*\nU:START=1"
+        );
+        // The *START* in the text is the variable, not the label
+        assert_eq!(
+            rename_at(source, Language::Pilot, "START*", "FIRST").unwrap(),
+            "*START\nT:Hi\nJ:START\nJ:*START\nL:STARTER\nJ:start\nT:START is *FIRST*\nU:FIRST=1"
+        );
+        let source = "L:LOOP\nA:NAME\nC:LEN(NAME) > 3\nJ:LOOP";
+        assert_eq!(rename_at(source, Language::Pilot, "LOOP", "AGAIN").unwrap(), "L:AGAIN\nA:NAME\nC:LEN(NAME) > 3\nJ:AGAIN");
+        assert_eq!(rename_at(source, Language::Pilot, "LOOP", "NAME").unwrap(), "L:NAME\nA:NAME\nC:LEN(NAME) > 3\nJ:NAME");
+        assert_eq!(rename_at("L:A\nL:B\nJ:A", Language::Pilot, "A", "B"), Err(RenameError::Taken("B".into())));
+    }
+
+    #[test]
+    fn test_line_numbers_follow_every_jump() {
+        let source = "10 PRINT 10\n20 IF X > 10 THEN 10 ELSE 30\n30 ON X GOTO 10, 20, 10\n40 GOSUB 10: RESTORE 10\n50 GOTO 100: PRINT \"GOTO 10\"";
+        assert_eq!(
+            rename_at(source, Language::Basic, "10", "5").unwrap(),
+            "5 PRINT 10\n20 IF X > 10 THEN 5 ELSE 30\n30 ON X GOTO 5, 20, 5\n40 GOSUB 5: RESTORE 5\n50 GOTO 100: PRINT \"GOTO 10\""
+        );
+        assert_eq!(rename_at(source, Language::Basic, "10", "20"), Err(RenameError::Taken("20".into())));
+        assert_eq!(rename_at(source, Language::Basic, "10", "ten"), Err(RenameError::Invalid("ten".into())));
+        let target = symbol_at(source, Language::Basic, source.find("100").unwrap()).unwrap();
+        assert_eq!((target.kind, target.name.as_str()), (TargetKind::LineNumber, "100"));
+    }
+
+    #[test]
+    fn test_logo_procedures_and_their_inputs() {
+        let source = "TO SQUARE :SIZE\nREPEAT 4 [FD :SIZE RT 90]\nEND\nTO BIG :SIZE\nSQUARE :SIZE * 2\nEND\nPRINT [SQUARE]\nSQUARE 50 ; SQUARE\nFD :SIZE";
+        assert_eq!(
+            rename_at(source, Language::Logo, "SQUARE", "BOX").unwrap(),
+            "TO BOX :SIZE\nREPEAT 4 [FD :SIZE RT 90]\nEND\nTO BIG :SIZE\nBOX :SIZE * 2\nEND\nPRINT [SQUARE]\nBOX 50 ; SQUARE\nFD :SIZE"
+        );
+        // An input is renamed in its own procedure only
+        let target = symbol_at(source, Language::Logo, source.find("SIZE").unwrap()).unwrap();
+        assert_eq!(target.scope, Some(1));
+        assert_eq!(
+            rename(source, Language::Logo, &target, "SIDE").unwrap(),
+            "TO SQUARE :SIDE\nREPEAT 4 [FD :SIDE RT 90]\nEND\nTO BIG :SIZE\nSQUARE :SIZE * 2\nEND\nPRINT [SQUARE]\nSQUARE 50 ; SQUARE\nFD :SIZE"
+        );
+        assert_eq!(rename_at(source, Language::Logo, "SQUARE", "FORWARD"), Err(RenameError::Reserved("FORWARD".into())));
+    }
+
+    #[test]
+    fn test_templecode_and_refused_names() {
+        let source = "10 LET N = 3\nT:N is *N*\nFD :N\nREM N";
+        assert_eq!(rename_at(source, Language::TempleCode, "N =", "SIDE").unwrap(), "10 LET SIDE = 3\nT:N is *SIDE*\nFD :SIDE\nREM N");
+        assert_eq!(rename_at(source, Language::TempleCode, "N =", "PRINT"), Err(RenameError::Reserved("PRINT".into())));
+        assert_eq!(rename_at(source, Language::TempleCode, "N =", "N$"), Err(RenameError::Suffix("N".into())));
+        assert_eq!(rename_at(source, Language::TempleCode, "N =", "2N"), Err(RenameError::Invalid("2N".into())));
+        assert_eq!(symbol_at(source, Language::TempleCode, source.find("is").unwrap()), None);
+    }
+}
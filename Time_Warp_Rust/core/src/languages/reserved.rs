@@ -0,0 +1,162 @@
+//! Reserved words: a language's keywords cannot name its variables
+//!
+//! A BASIC keyword cannot name a BASIC variable or array, nor a Logo command
+//! a Logo procedure input: `LET IF = 5`, `FOR NEXT = 1 TO 3`, and `TO BOX
+//! :FD` fail with "IF is a reserved word in BASIC", and an expression that
+//! reads one fails the same way instead of finding an unset variable. The
+//! words are the keyword tables that sort a mixed program's lines into
+//! languages ([`basic::KEYWORDS`], [`logo::KEYWORDS`]), so the two stay in
+//! step. Reservation is per language: a PILOT statement may use `PRINT` as a
+//! name (`U:PRINT=5`), since PILOT's commands are letters with a colon. A
+//! name with a type suffix (`LINE$`) is a different word, and Logo's
+//! `HEADING` and `PATHCOUNT` stay readable as the values they report.
+//! [`reserved_uses`] finds the names a program assigns without running it,
+//! for the editor to underline.
+
+use std::fmt;
+
+use anyhow::Result;
+
+use super::{basic, logo, Language};
+use crate::i18n::trf;
+use crate::interpreter::arrays::{element_reference, split_top_level};
+use crate::interpreter::metadata::parse_metadata;
+use crate::interpreter::Interpreter;
+
+/// Logo keywords that also read as values in expressions
+const LOGO_VALUES: &[&str] = &["HEADING", "PATHCOUNT"];
+
+/// Whether `name` is a keyword of `language`, and so cannot name its variables
+pub fn is_reserved(language: Language, name: &str) -> bool {
+    let upper = name.trim().to_uppercase();
+    match language {
+        Language::Basic => basic::KEYWORDS.contains(&upper.as_str()),
+        Language::Logo => logo::KEYWORDS.contains(&upper.as_str()) && !LOGO_VALUES.contains(&upper.as_str()),
+        Language::Pilot | Language::TempleCode => false,
+    }
+}
+
+/// A keyword used as a name: "IF is a reserved word in BASIC"
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservedWord {
+    /// The keyword, uppercased
+    pub name: String,
+    pub language: Language,
+}
+
+impl fmt::Display for ReservedWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&trf("reserved.word", &[&self.name, &self.language.name()]))
+    }
+}
+
+impl std::error::Error for ReservedWord {}
+
+impl Interpreter {
+    /// Refuse to assign `target` (a variable or array element) when it names a keyword of the running statement's language
+    pub(crate) fn check_not_reserved(&self, target: &str) -> Result<()> {
+        let name = element_reference(target).map_or_else(|| target.trim().to_uppercase(), |(name, _)| name);
+        if is_reserved(self.current_language, &name) {
+            return Err(ReservedWord { name, language: self.current_language }.into());
+        }
+        Ok(())
+    }
+}
+
+/// A reserved word a program assigns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservedUse {
+    /// 1-based source line
+    pub line: usize,
+    /// Char columns of the name on the line
+    pub columns: std::ops::Range<usize>,
+    pub message: String,
+}
+
+/// Every reserved word `source` uses as a name, without running it
+///
+/// Each line is judged in the language a run gives it (its keyword, or the
+/// `@lang` header). The names are those BASIC `LET`, `FOR`, `INPUT`,
+/// `READ`, and `DIM` assign and the inputs a Logo `TO` declares.
+pub fn reserved_uses(source: &str) -> Vec<ReservedUse> {
+    let mut probe = Interpreter::new();
+    probe.metadata = parse_metadata(source);
+    let mut uses = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        let (_, statement) = probe.parse_line(text);
+        let language = probe.determine_command_type(statement);
+        for name in assigned_names(language, statement) {
+            if is_reserved(language, name) {
+                // Every name is a slice of `text`, so its byte offset is where it starts
+                let start = text[..name.as_ptr() as usize - text.as_ptr() as usize].chars().count();
+                let reserved = ReservedWord { name: name.to_uppercase(), language };
+                uses.push(ReservedUse { line: index + 1, columns: start..start + name.chars().count(), message: reserved.to_string() });
+            }
+        }
+    }
+    uses
+}
+
+/// The names `statement` assigns, as slices of it
+fn assigned_names(language: Language, statement: &str) -> Vec<&str> {
+    let mut words = statement.trim().splitn(2, char::is_whitespace);
+    let keyword = words.next().unwrap_or("").to_uppercase();
+    let args = words.next().unwrap_or("");
+    let before_equals = || args.split_once('=').map(|(target, _)| target).into_iter().collect::<Vec<_>>();
+    let targets = match (language, keyword.as_str()) {
+        (Language::Basic, "LET" | "FOR") => before_equals(),
+        (Language::Basic, "INPUT") => {
+            let (_, rest) = basic::split_input_prompt(args);
+            let upper = rest.to_ascii_uppercase();
+            vec![&rest[..upper.find(" AS ").or_else(|| upper.find(" RANGE ")).unwrap_or(rest.len())]]
+        }
+        (Language::Basic, "READ" | "DIM") => split_top_level(args),
+        (Language::Logo, "TO") => return args.split_whitespace().skip(1).map(|p| p.strip_prefix(':').unwrap_or(p)).collect(),
+        _ => Vec::new(),
+    };
+    targets
+        .into_iter()
+        .map(|target| {
+            let target = target.trim();
+            target.find('(').map_or(target, |open| target[..open].trim_end())
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+
+    #[test]
+    fn test_keywords_are_reserved_per_language() {
+        assert!(is_reserved(Language::Basic, "if") && is_reserved(Language::Logo, "FD"));
+        assert!(!is_reserved(Language::Basic, "FD") && !is_reserved(Language::Logo, "PRINT"));
+        assert!(!is_reserved(Language::Pilot, "PRINT") && !is_reserved(Language::Basic, "LINE$"));
+        assert!(!is_reserved(Language::Logo, "HEADING"));
+        assert_eq!(ReservedWord { name: "IF".into(), language: Language::Basic }.to_string(), "IF is a reserved word in BASIC");
+    }
+
+    #[test]
+    fn test_assigning_a_keyword_fails_at_run_time() {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program("10 LET IF = 5\n20 FOR NEXT = 1 TO 2\n30 DIM PRINT(3)\n40 PRINT 1 + LINE\n50 PRINT \"done\"").unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        for (line, word) in [(1, "IF"), (2, "NEXT"), (3, "PRINT"), (4, "LINE")] {
+            let message = format!("line {}: {} is a reserved word in BASIC", line, word);
+            assert!(output.iter().any(|l| l.contains(&message)), "{}: {:?}", word, output);
+        }
+        assert_eq!(output.last().map(String::as_str), Some("done"));
+        assert!(!interp.variables.contains_key("IF") && !interp.variables.contains_key("NEXT") && interp.arrays.is_empty());
+    }
+
+    #[test]
+    fn test_reserved_uses_are_found_before_running() {
+        let source = "10 LET IF = 5\n20 INPUT \"Age\"; PRINT\n30 READ A, GOTO$, DATA(1)\n40 LET TOTAL = IF\nTO BOX :FD :SIZE\nEND\nU:PRINT=5";
+        let found: Vec<(usize, std::ops::Range<usize>)> = reserved_uses(source).into_iter().map(|u| (u.line, u.columns)).collect();
+        assert_eq!(found, [(1, 7..9), (2, 16..21), (3, 18..22), (5, 8..10)]);
+        assert_eq!(reserved_uses("TO BOX :FD\nEND")[0].message, "FD is a reserved word in Logo");
+    }
+}
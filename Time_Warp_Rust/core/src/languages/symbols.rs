@@ -0,0 +1,153 @@
+//! Navigable symbols of a program, for the IDE's outline and Go to Symbol
+//!
+//! PILOT labels (`L:name` and `*name`), BASIC line numbers that a `GOTO` or
+//! `GOSUB` jumps to plus `DEF FN` definitions, and Logo `TO` procedures with
+//! their parameters. TempleCode programs get all of them.
+
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{pilot, Language};
+
+/// `GOTO 100`, `GOSUB 200`, `ON X GOTO 10, 20, 30`
+static JUMP_TARGETS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:GOTO|GOSUB)\s*(\d+(?:\s*,\s*\d+)*)").expect("Invalid regex pattern")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    /// PILOT `L:name` / `*name`
+    Label,
+    /// BASIC line number used as a GOTO/GOSUB target
+    LineNumber,
+    /// BASIC `DEF FN`
+    Function,
+    /// Logo `TO name :param ...`
+    Procedure,
+}
+
+impl SymbolKind {
+    fn belongs_to(self, language: Language) -> bool {
+        match language {
+            Language::TempleCode => true,
+            Language::Pilot => self == SymbolKind::Label,
+            Language::Basic => matches!(self, SymbolKind::LineNumber | SymbolKind::Function),
+            Language::Logo => self == SymbolKind::Procedure,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// As written: `START`, `100`, `FNAREA(R)`, `SQUARE :SIZE`
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based line of the definition
+    pub line: usize,
+}
+
+/// Split a leading BASIC line number off a trimmed line
+fn split_line_number(line: &str) -> (Option<usize>, &str) {
+    match line.split_once(char::is_whitespace) {
+        Some((first, rest)) => match first.parse() {
+            Ok(number) => (Some(number), rest.trim_start()),
+            Err(_) => (None, line),
+        },
+        None => (None, line),
+    }
+}
+
+/// Every line number some GOTO or GOSUB in `source` jumps to
+fn jump_targets(source: &str) -> HashSet<usize> {
+    JUMP_TARGETS
+        .captures_iter(source)
+        .flat_map(|c| c[1].split(',').filter_map(|n| n.trim().parse().ok()).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Symbols defined in `source`, in line order, keeping the kinds `language` uses
+pub fn scan_symbols(source: &str, language: Language) -> Vec<Symbol> {
+    let targets = if SymbolKind::LineNumber.belongs_to(language) { jump_targets(source) } else { HashSet::new() };
+    let mut symbols = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let (number, command) = split_line_number(line.trim());
+        let mut found = |name: String, kind: SymbolKind| {
+            if kind.belongs_to(language) {
+                symbols.push(Symbol { name, kind, line: index + 1 });
+            }
+        };
+        if let Some(number) = number.filter(|n| targets.contains(n)) {
+            found(number.to_string(), SymbolKind::LineNumber);
+        }
+        if let Some(label) = pilot::label_definition(command).filter(|l| !l.is_empty()) {
+            found(label.to_string(), SymbolKind::Label);
+        }
+        let mut words = command.split_whitespace();
+        let keyword = words.next().unwrap_or("");
+        if keyword.eq_ignore_ascii_case("DEF") {
+            let definition = command.trim_start()[3..].split('=').next().unwrap_or("").trim();
+            if definition.get(..2).is_some_and(|fn_| fn_.eq_ignore_ascii_case("FN")) {
+                found(definition.to_string(), SymbolKind::Function);
+            }
+        } else if keyword.eq_ignore_ascii_case("TO") {
+            let signature = words.collect::<Vec<_>>().join(" ");
+            if !signature.is_empty() {
+                found(signature, SymbolKind::Procedure);
+            }
+        }
+    }
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(source: &str, language: Language) -> Vec<(SymbolKind, String, usize)> {
+        scan_symbols(source, language).into_iter().map(|s| (s.kind, s.name, s.line)).collect()
+    }
+
+    #[test]
+    fn test_pilot_labels() {
+        let source = "L:START\nT:Hello\n*AGAIN\nJ:START\nT:*NAME* is here\nL:\n";
+        assert_eq!(
+            names(source, Language::Pilot),
+            vec![(SymbolKind::Label, "START".into(), 1), (SymbolKind::Label, "AGAIN".into(), 3)]
+        );
+    }
+
+    #[test]
+    fn test_basic_targets_and_functions() {
+        let source = "10 GOSUB 100\n20 ON X GOTO 40,50\n30 END\n40 PRINT 1\n50 PRINT 2\n\
+                      100 DEF FNSQ(X) = X * X\n110 RETURN\n120 GOTO 999";
+        assert_eq!(
+            names(source, Language::Basic),
+            vec![
+                (SymbolKind::LineNumber, "40".into(), 4),
+                (SymbolKind::LineNumber, "50".into(), 5),
+                (SymbolKind::LineNumber, "100".into(), 6),
+                (SymbolKind::Function, "FNSQ(X)".into(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_logo_procedures_with_parameters() {
+        let source = "to square :size\n  repeat 4 [fd :size rt 90]\nend\nTO STAR\nEND\nTO\nsquare 50";
+        assert_eq!(
+            names(source, Language::Logo),
+            vec![(SymbolKind::Procedure, "square :size".into(), 1), (SymbolKind::Procedure, "STAR".into(), 4)]
+        );
+    }
+
+    #[test]
+    fn test_language_filters_kinds() {
+        let source = "L:MENU\n10 GOTO 20\n20 DEF FNA(X)=X\nTO BOX\nEND";
+        assert_eq!(scan_symbols(source, Language::TempleCode).len(), 4);
+        assert_eq!(names(source, Language::Pilot), vec![(SymbolKind::Label, "MENU".into(), 1)]);
+        assert_eq!(names(source, Language::Logo), vec![(SymbolKind::Procedure, "BOX".into(), 4)]);
+        assert_eq!(scan_symbols(source, Language::Basic).len(), 2);
+    }
+}
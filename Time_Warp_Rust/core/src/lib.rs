@@ -0,0 +1,31 @@
+//! Time Warp interpreter core
+//!
+//! PILOT, BASIC, and Logo interpreters with a headless turtle canvas and no
+//! GUI dependencies, for embedding Time Warp in other Rust programs. The
+//! Time Warp IDE is built on this crate.
+//!
+//! [`Session`] is the simplest way in: pick a language, run a program, answer
+//! its INPUT prompts, then read the output, the drawing, and a [`RunReport`].
+//!
+//! ```
+//! use time_warp_core::{Language, Session};
+//!
+//! let mut session = Session::new(Language::Logo);
+//! session.run("REPEAT 4 [FORWARD 50 RIGHT 90]").unwrap();
+//! assert_eq!(session.canvas().lines().len(), 4);
+//! ```
+
+pub mod graphics;
+pub mod i18n;
+pub mod interpreter;
+pub mod languages;
+pub mod session;
+pub mod speech;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod utils;
+
+pub use graphics::{Rgba, TurtleCanvas};
+pub use interpreter::isolated::RunReport;
+pub use languages::Language;
+pub use session::Session;
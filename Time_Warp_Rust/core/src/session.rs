@@ -0,0 +1,165 @@
+//! Embedding API: run one program at a time and inspect the result
+//!
+//! A `Session` owns an interpreter and a headless canvas. `run` loads a
+//! program and executes it until it ends or stops at an INPUT / `A:` prompt;
+//! `provide_input` answers the prompt and carries on. Everything the program
+//! printed, drew, and stored stays readable until the next `run`.
+//!
+//! ```
+//! use time_warp_core::{Language, Session};
+//!
+//! let mut session = Session::new(Language::Basic);
+//! session.run("10 INPUT N\n20 PRINT N * 2").unwrap();
+//! assert!(session.waiting_for_input());
+//!
+//! session.provide_input("21").unwrap();
+//! assert_eq!(session.output(), ["42"]);
+//! assert!(session.report().completed());
+//! ```
+
+use anyhow::{anyhow, Result};
+
+use crate::graphics::TurtleCanvas;
+use crate::i18n::tr;
+use crate::interpreter::isolated::RunReport;
+use crate::interpreter::metadata::parse_metadata;
+use crate::interpreter::Interpreter;
+use crate::languages::Language;
+
+/// A headless Time Warp run: interpreter, canvas, and the last fatal error
+pub struct Session {
+    language: Language,
+    interpreter: Interpreter,
+    canvas: TurtleCanvas,
+    error: Option<String>,
+}
+
+impl Session {
+    /// A session whose programs are read as `language`
+    ///
+    /// `Language::TempleCode` detects the language of each line, as the IDE
+    /// does for `.tc` files. An `@lang` header in the program wins either way.
+    pub fn new(language: Language) -> Self {
+        Self { language, interpreter: Interpreter::new(), canvas: TurtleCanvas::new(), error: None }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Load `source` on a clear canvas and run it until it ends or asks for input
+    ///
+    /// Returns the load error or a fatal one (timeout, iteration limit);
+    /// ordinary runtime errors are reported in the output and the run goes on.
+    ///
+    /// ```
+    /// use time_warp_core::{Language, Session};
+    ///
+    /// let mut session = Session::new(Language::Pilot);
+    /// session.run("T:Hello\nT:World").unwrap();
+    /// assert_eq!(session.output(), ["Hello", "World"]);
+    /// ```
+    pub fn run(&mut self, source: &str) -> Result<()> {
+        self.canvas = TurtleCanvas::new();
+        self.error = None;
+        let result = self.interpreter.load_program(source).and_then(|()| {
+            if self.language != Language::TempleCode && parse_metadata(source).language.is_none() {
+                self.interpreter.metadata.language = Some(self.language);
+            }
+            self.interpreter.execute(&mut self.canvas).map(drop)
+        });
+        self.record(result)
+    }
+
+    /// Whether the program is paused at an INPUT / `A:` prompt
+    pub fn waiting_for_input(&self) -> bool {
+        self.interpreter.pending_input.is_some()
+    }
+
+    /// The paused program's prompt text
+    pub fn input_prompt(&self) -> Option<&str> {
+        self.interpreter.pending_input.as_ref().map(|req| req.prompt.as_str())
+    }
+
+    /// Answer the pending prompt and continue the run
+    pub fn provide_input(&mut self, answer: &str) -> Result<()> {
+        if !self.waiting_for_input() {
+            return Err(anyhow!(tr("error.not_waiting")));
+        }
+        self.interpreter.provide_input(answer);
+        let result = self.interpreter.execute(&mut self.canvas).map(drop);
+        self.record(result)
+    }
+
+    fn record(&mut self, result: Result<()>) -> Result<()> {
+        if let Err(e) = &result {
+            self.error = Some(e.to_string());
+        }
+        result
+    }
+
+    /// Lines printed so far (program output and error messages)
+    pub fn output(&self) -> &[String] {
+        &self.interpreter.output
+    }
+
+    /// The turtle and everything it drew
+    ///
+    /// ```
+    /// use time_warp_core::{Language, Session};
+    ///
+    /// let mut session = Session::new(Language::Logo);
+    /// session.run("SETPENCOLOR RED\nFORWARD 30").unwrap();
+    /// let line = &session.canvas().lines()[0];
+    /// assert_eq!((line.start.y, line.end.y), (0.0, -30.0));
+    /// assert_eq!(line.color.to_hex(), "#FF0000");
+    /// ```
+    pub fn canvas(&self) -> &TurtleCanvas {
+        &self.canvas
+    }
+
+    /// The interpreter, e.g. to set `limits` or a `SecurityPolicy` before `run`
+    pub fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    pub fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
+    /// Snapshot of the run: output, variables, drawing summary, and errors
+    pub fn report(&self) -> RunReport {
+        RunReport::capture(&self.interpreter, &self.canvas, self.error.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_applies_without_a_header() {
+        // PRINT is a BASIC keyword; read as PILOT it is not a command
+        let mut session = Session::new(Language::Pilot);
+        session.run("PRINT 5").unwrap();
+        assert!(session.output().iter().all(|l| l != "5"), "{:?}", session.output());
+
+        session.run("REM @lang basic\nPRINT 5").unwrap();
+        assert_eq!(session.output(), ["5"]);
+    }
+
+    #[test]
+    fn test_report_and_errors() {
+        let mut session = Session::new(Language::Basic);
+        assert!(session.provide_input("1").is_err());
+
+        session.run("10 LET X = 3\n20 PRINT X").unwrap();
+        let report = session.report();
+        assert!(report.completed());
+        assert_eq!(report.variables.get("X"), Some(&3.0));
+
+        session.interpreter_mut().limits.max_program_lines = 1;
+        assert!(session.run("10 PRINT 1\n20 PRINT 2").is_err());
+        assert!(session.report().error.is_some());
+    }
+}
@@ -0,0 +1,279 @@
+//! Text-to-speech output for Time Warp IDE
+//!
+//! Speech never blocks the interpreter: lines are pushed onto a [`SpeechQueue`]
+//! whose worker thread speaks them one at a time through a [`Speaker`] backend.
+//! Stopping a program cancels everything still queued (and the utterance in
+//! progress when the backend supports it).
+//!
+//! With the `tts` feature enabled, [`system_queue`] drives the platform speech
+//! command (`spd-say`, `espeak-ng`/`espeak`, macOS `say`, or PowerShell on
+//! Windows). Without the feature, or when no engine is installed, it returns
+//! `None` and the PILOT `V:` command falls back to logging `[voice] text`.
+//! The text is the program's, so it never reaches a command line where it
+//! could be read as an option or a script: it goes to the engine on its
+//! input, or after `--` for `spd-say`, which cannot read it there.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Lets a backend notice that its utterance was cancelled mid-speech
+pub struct CancelToken {
+    generation: Arc<AtomicU64>,
+    expected: u64,
+}
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) != self.expected
+    }
+}
+
+/// A speech engine that speaks one utterance at a time
+pub trait Speaker: Send + 'static {
+    /// Speak `text`, returning once finished or once `cancel` reports cancellation
+    fn speak(&mut self, text: &str, cancel: &CancelToken);
+}
+
+enum Job {
+    Say { text: String, generation: u64 },
+}
+
+/// Per-line speech queue running on a background thread
+pub struct SpeechQueue {
+    sender: Option<Sender<Job>>,
+    generation: Arc<AtomicU64>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl SpeechQueue {
+    pub fn new(mut speaker: Box<dyn Speaker>) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let generation = Arc::new(AtomicU64::new(0));
+        let worker_generation = generation.clone();
+        let worker = std::thread::spawn(move || {
+            while let Ok(Job::Say { text, generation }) = receiver.recv() {
+                let token = CancelToken { generation: worker_generation.clone(), expected: generation };
+                if !token.is_cancelled() {
+                    speaker.speak(&text, &token);
+                }
+            }
+        });
+        Self { sender: Some(sender), generation, worker: Some(worker) }
+    }
+
+    /// Queue a line to be spoken after anything already queued
+    pub fn speak(&self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        if let Some(sender) = &self.sender {
+            let generation = self.generation.load(Ordering::SeqCst);
+            let _ = sender.send(Job::Say { text: text.to_string(), generation });
+        }
+    }
+
+    /// Drop every queued line and interrupt the one being spoken
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Finish speaking everything queued, then stop the worker thread
+    pub fn shutdown(mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for SpeechQueue {
+    fn drop(&mut self) {
+        // Don't keep talking after the owner is gone
+        self.cancel();
+        self.sender.take();
+    }
+}
+
+/// Create a queue backed by the platform speech engine, if one is available
+#[cfg(feature = "tts")]
+pub fn system_queue() -> Option<SpeechQueue> {
+    system::CommandSpeaker::detect().map(|s| SpeechQueue::new(Box::new(s)))
+}
+
+/// Create a queue backed by the platform speech engine, if one is available
+#[cfg(not(feature = "tts"))]
+pub fn system_queue() -> Option<SpeechQueue> {
+    None
+}
+
+#[cfg(feature = "tts")]
+mod system {
+    use super::{CancelToken, Speaker};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    /// How an engine is given the text to speak
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum TextInput {
+        /// Written to its standard input
+        Stdin,
+        /// As its last argument, after `--` ends the options
+        AfterOptions,
+    }
+
+    /// Speaks by running an external speech command per utterance
+    pub struct CommandSpeaker {
+        program: &'static str,
+        args: &'static [&'static str],
+        input: TextInput,
+    }
+
+    impl CommandSpeaker {
+        pub fn detect() -> Option<Self> {
+            let candidates: &[(&'static str, &'static [&'static str], TextInput)] = if cfg!(target_os = "windows") {
+                &[(
+                    "powershell",
+                    &[
+                        "-NoProfile",
+                        "-Command",
+                        "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+                    ],
+                    TextInput::Stdin,
+                )]
+            } else if cfg!(target_os = "macos") {
+                // With no message, say reads one from its input
+                &[("say", &[], TextInput::Stdin)]
+            } else {
+                &[
+                    ("spd-say", &["-w"], TextInput::AfterOptions),
+                    ("espeak-ng", &["--stdin"], TextInput::Stdin),
+                    ("espeak", &["--stdin"], TextInput::Stdin),
+                ]
+            };
+            candidates
+                .iter()
+                .find(|(program, _, _)| on_path(program))
+                .map(|&(program, args, input)| Self { program, args, input })
+        }
+
+        /// The engine's command line for `text`, which is in it only after `--`
+        fn command(&self, text: &str) -> Command {
+            let mut command = Command::new(self.program);
+            command.args(self.args).stdout(Stdio::null()).stderr(Stdio::null());
+            match self.input {
+                TextInput::Stdin => command.stdin(Stdio::piped()),
+                TextInput::AfterOptions => command.arg("--").arg(text).stdin(Stdio::null()),
+            };
+            command
+        }
+    }
+
+    impl Speaker for CommandSpeaker {
+        fn speak(&mut self, text: &str, cancel: &CancelToken) {
+            let Ok(mut child) = self.command(text).spawn() else { return };
+            if let Some(mut stdin) = child.stdin.take() {
+                // Closing the pipe afterwards tells the engine the text is complete
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            loop {
+                if cancel.is_cancelled() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return;
+                }
+                match child.try_wait() {
+                    Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+                    _ => return,
+                }
+            }
+        }
+    }
+
+    fn on_path(program: &str) -> bool {
+        let Some(paths) = std::env::var_os("PATH") else { return false };
+        std::env::split_paths(&paths).any(|dir| {
+            dir.join(program).is_file() || dir.join(format!("{}.exe", program)).is_file()
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_text_is_never_an_option() {
+            let args = |speaker: CommandSpeaker| -> Vec<String> {
+                speaker.command("-w/home/u/.profile").get_args().map(|a| a.to_string_lossy().into_owned()).collect()
+            };
+            let spd = CommandSpeaker { program: "spd-say", args: &["-w"], input: TextInput::AfterOptions };
+            assert_eq!(args(spd), ["-w", "--", "-w/home/u/.profile"]);
+            let espeak = CommandSpeaker { program: "espeak", args: &["--stdin"], input: TextInput::Stdin };
+            assert_eq!(args(espeak), ["--stdin"]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records what would have been spoken
+    struct MockSpeaker {
+        spoken: Arc<Mutex<Vec<String>>>,
+        entered: Option<Sender<()>>,
+        release: Option<mpsc::Receiver<()>>,
+    }
+
+    impl Speaker for MockSpeaker {
+        fn speak(&mut self, text: &str, _cancel: &CancelToken) {
+            self.spoken.lock().unwrap().push(text.to_string());
+            // Block on the first utterance until the test releases it
+            if let (Some(entered), Some(release)) = (self.entered.take(), self.release.take()) {
+                let _ = entered.send(());
+                let _ = release.recv();
+            }
+        }
+    }
+
+    fn mock() -> (MockSpeaker, Arc<Mutex<Vec<String>>>) {
+        let spoken = Arc::new(Mutex::new(Vec::new()));
+        (MockSpeaker { spoken: spoken.clone(), entered: None, release: None }, spoken)
+    }
+
+    #[test]
+    fn test_queue_speaks_lines_in_order() {
+        let (speaker, spoken) = mock();
+        let queue = SpeechQueue::new(Box::new(speaker));
+        queue.speak("one");
+        queue.speak("   ");
+        queue.speak("two");
+        queue.shutdown();
+        assert_eq!(*spoken.lock().unwrap(), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_cancel_drops_pending_lines() {
+        let (mut speaker, spoken) = mock();
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        speaker.entered = Some(entered_tx);
+        speaker.release = Some(release_rx);
+
+        let queue = SpeechQueue::new(Box::new(speaker));
+        queue.speak("first");
+        entered_rx.recv().unwrap();
+        queue.speak("stale 1");
+        queue.speak("stale 2");
+        queue.cancel();
+        queue.speak("after stop");
+        release_tx.send(()).unwrap();
+        queue.shutdown();
+
+        assert_eq!(*spoken.lock().unwrap(), vec!["first", "after stop"]);
+    }
+}
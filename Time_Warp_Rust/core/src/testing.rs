@@ -0,0 +1,111 @@
+//! Helpers for testing language features: run a program, get its output
+//!
+//! Built for this crate's own tests, and for other crates with the `testing`
+//! feature (the IDE's integration tests turn it on). Each helper runs the
+//! program in a fresh interpreter and panics, naming the program, if it fails
+//! to load or stops with a fatal error, so a test reads as the program and
+//! what it should print:
+//!
+//! ```
+//! use time_warp_core::{run, run_turtle, run_with_input};
+//!
+//! run!("10 PRINT 1+1" => ["2"]);
+//! run_with_input!("A:NAME\nT:Hi *NAME*", ["alice"] => ["Hi alice"]);
+//! assert_eq!(run_turtle!("REPEAT 4 [FD 10 RT 90]").lines.len(), 4);
+//! ```
+//!
+//! Output is compared as [`normalize_transcript`] leaves it, the same lines
+//! `--run` prints and the Diff tab compares.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::graphics::TurtleState;
+use crate::interpreter::Interpreter;
+use crate::utils::transcript::normalize_transcript;
+
+/// A finished test run
+pub struct TestRun {
+    /// The interpreter, for reading variables and diagnostics after the run
+    pub interp: Interpreter,
+    pub turtle: TurtleState,
+    /// Output lines, normalized
+    pub output: Vec<String>,
+}
+
+/// Run `program`, answering INPUT and `A:` with `inputs` in order
+///
+/// Panics when the program does not load, stops with a fatal error, or asks
+/// for more answers than `inputs` holds.
+pub fn run_program(program: &str, inputs: &[&str]) -> TestRun {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let answers: Arc<Mutex<VecDeque<String>>> = Arc::new(Mutex::new(inputs.iter().map(|s| s.to_string()).collect()));
+    let remaining = Arc::clone(&answers);
+    let source = program.to_string();
+    interp.input_callback = Some(Box::new(move |prompt| {
+        remaining.lock().unwrap().pop_front().unwrap_or_else(|| panic!("no answer left for prompt {:?} in program:\n{}", prompt, source))
+    }));
+    if let Err(e) = interp.load_program(program) {
+        panic!("program failed to load: {:#}\n{}", e, program);
+    }
+    let output = match interp.execute(&mut turtle) {
+        Ok(output) => normalize_transcript(&output),
+        Err(e) => panic!("program stopped with a fatal error: {:#}\n{}", e, program),
+    };
+    let unused = answers.lock().unwrap().len();
+    assert_eq!(unused, 0, "{} answer(s) were never asked for in program:\n{}", unused, program);
+    TestRun { interp, turtle, output }
+}
+
+/// Output of a program: `run!(program)`, or assert it with `run!(program => ["line", ...])`
+#[macro_export]
+macro_rules! run {
+    ($program:expr) => {
+        $crate::testing::run_program($program, &[]).output
+    };
+    ($program:expr => [$($line:expr),* $(,)?]) => {
+        $crate::run_with_input!($program, [] => [$($line),*])
+    };
+}
+
+/// Output of a program given INPUT answers: `run_with_input!(program, ["alice", "7"])`,
+/// or assert it with `run_with_input!(program, ["alice"] => ["Hi alice"])`
+#[macro_export]
+macro_rules! run_with_input {
+    ($program:expr, [$($answer:expr),* $(,)?]) => {
+        $crate::testing::run_program($program, &[$($answer),*]).output
+    };
+    ($program:expr, [$($answer:expr),* $(,)?] => [$($line:expr),* $(,)?]) => {{
+        let program: &str = $program;
+        let expected: ::std::vec::Vec<&str> = ::std::vec![$($line),*];
+        let output = $crate::testing::run_program(program, &[$($answer),*]).output;
+        assert_eq!(output, expected, "output of program:\n{}", program);
+    }};
+}
+
+/// The turtle after running a program: `run_turtle!(program).lines.len()`
+#[macro_export]
+macro_rules! run_turtle {
+    ($program:expr) => {
+        $crate::testing::run_program($program, &[]).turtle
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_macros_run_each_language() {
+        run!("10 PRINT 1+1\n20 PRINT \"done\"  " => ["2", "done"]);
+        assert_eq!(run!("T:one\nE:\nT:two"), ["one"]);
+        run_with_input!("10 INPUT \"Name\"; N$\n20 INPUT \"Age\"; A\n30 PRINT N$, A + 1", ["alice", "7"] => ["alice 8"]);
+        let turtle = run_turtle!("REPEAT 3 [FD 10 RT 120]");
+        assert_eq!(turtle.lines.len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "no answer left")]
+    fn test_missing_answer_fails_the_test() {
+        run_with_input!("A:NAME\nA:AGE", ["alice"]);
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use thiserror::Error;
+
+use crate::i18n::{locale, tr, tr_in, Locale};
+
+// Custom error types for Time Warp IDE
+// Currently using anyhow::Result in most places, but these are available for typed errors
+#[allow(dead_code)]
+#[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum TimeWarpError {
+    #[error("Parse error: {0}")]
+    ParseError(String),
+    
+    #[error("Runtime error: {0}")]
+    RuntimeError(String),
+    
+    #[error("File error: {0}")]
+    FileError(#[from] std::io::Error),
+    
+    #[error("Expression error: {0}")]
+    ExpressionError(String),
+}
+
+/// BASIC runtime error classes with their Microsoft BASIC error numbers
+///
+/// The `Display` text is the modern message, in the current locale;
+/// [`BasicError::classic_message`] gives the retro `?SYNTAX ERROR IN 20`
+/// form used by OPTION ERRORS CLASSIC, which is always English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BasicError {
+    NextWithoutFor,
+    Syntax,
+    ReturnWithoutGosub,
+    OutOfData,
+    IllegalFunctionCall,
+    Overflow,
+    UndefinedLineNumber,
+    SubscriptOutOfRange,
+    DuplicateDefinition,
+    DivisionByZero,
+    TypeMismatch,
+    /// Validated INPUT ran out of retries (Time Warp extension; number from the unused range)
+    BadInput,
+    /// CHAIN could not read its file
+    FileNotFound,
+}
+
+impl fmt::Display for BasicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BasicError::NextWithoutFor => tr("basic.error.next_without_for"),
+            BasicError::Syntax => tr("basic.error.syntax"),
+            BasicError::ReturnWithoutGosub => tr("basic.error.return_without_gosub"),
+            BasicError::OutOfData => tr("basic.error.out_of_data"),
+            BasicError::IllegalFunctionCall => tr("basic.error.illegal_function_call"),
+            BasicError::Overflow => tr("basic.error.overflow"),
+            BasicError::UndefinedLineNumber => tr("basic.error.undefined_line_number"),
+            BasicError::SubscriptOutOfRange => tr("basic.error.subscript_out_of_range"),
+            BasicError::DuplicateDefinition => tr("basic.error.duplicate_definition"),
+            BasicError::DivisionByZero => tr("basic.error.division_by_zero"),
+            BasicError::TypeMismatch => tr("basic.error.type_mismatch"),
+            BasicError::BadInput => tr("basic.error.bad_input"),
+            BasicError::FileNotFound => tr("basic.error.file_not_found"),
+        })
+    }
+}
+
+impl std::error::Error for BasicError {}
+
+/// Error number and classic message for every [`BasicError`]
+pub const CLASSIC_BASIC_ERRORS: &[(BasicError, u8, &str)] = &[
+    (BasicError::NextWithoutFor, 1, "NEXT WITHOUT FOR"),
+    (BasicError::Syntax, 2, "SYNTAX"),
+    (BasicError::ReturnWithoutGosub, 3, "RETURN WITHOUT GOSUB"),
+    (BasicError::OutOfData, 4, "OUT OF DATA"),
+    (BasicError::IllegalFunctionCall, 5, "ILLEGAL FUNCTION CALL"),
+    (BasicError::Overflow, 6, "OVERFLOW"),
+    (BasicError::UndefinedLineNumber, 8, "UNDEFINED LINE NUMBER"),
+    (BasicError::SubscriptOutOfRange, 9, "SUBSCRIPT OUT OF RANGE"),
+    (BasicError::DuplicateDefinition, 10, "DUPLICATE DEFINITION"),
+    (BasicError::DivisionByZero, 11, "DIVISION BY ZERO"),
+    (BasicError::TypeMismatch, 13, "TYPE MISMATCH"),
+    (BasicError::BadInput, 31, "BAD INPUT"),
+    (BasicError::FileNotFound, 53, "FILE NOT FOUND"),
+];
+
+impl BasicError {
+    fn entry(self) -> &'static (BasicError, u8, &'static str) {
+        CLASSIC_BASIC_ERRORS
+            .iter()
+            .find(|(e, _, _)| *e == self)
+            .expect("every BasicError has a classic table entry")
+    }
+
+    /// Microsoft BASIC error number (the value of ERR)
+    pub fn code(self) -> u8 {
+        self.entry().1
+    }
+
+    #[allow(dead_code)]
+    pub fn from_code(code: u8) -> Option<Self> {
+        CLASSIC_BASIC_ERRORS.iter().find(|(_, c, _)| *c == code).map(|(e, _, _)| *e)
+    }
+
+    /// `?NEXT WITHOUT FOR ERROR IN 70`, or without ` IN n` for unnumbered lines
+    pub fn classic_message(self, line: Option<usize>) -> String {
+        match line {
+            Some(n) => format!("?{} ERROR IN {}", self.entry().2, n),
+            None => format!("?{} ERROR", self.entry().2),
+        }
+    }
+
+    /// Best-effort class for an untyped error message (expression errors etc.)
+    pub fn classify(message: &str) -> Self {
+        Self::classify_in(locale(), message)
+    }
+
+    /// [`classify`](Self::classify) for a message written in `locale`
+    pub fn classify_in(locale: Locale, message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let says = |text: &str| lower.contains(&fragment(text));
+        if says(tr_in(locale, "basic.error.division_by_zero")) {
+            BasicError::DivisionByZero
+        } else if says(tr_in(locale, "basic.error.next_without_for")) || says(tr_in(locale, "basic.next_mismatch")) {
+            BasicError::NextWithoutFor
+        } else if says(tr_in(locale, "basic.error.return_without_gosub")) {
+            BasicError::ReturnWithoutGosub
+        } else if says(tr_in(locale, "basic.error.subscript_out_of_range")) {
+            BasicError::SubscriptOutOfRange
+        } else if says(tr_in(locale, "basic.goto_failed")) || says(tr_in(locale, "basic.gosub_failed")) {
+            BasicError::UndefinedLineNumber
+        } else if says(tr_in(locale, "expr.missing_argument")) || lower.contains("domain") {
+            BasicError::IllegalFunctionCall
+        } else if says(tr_in(locale, "basic.error.overflow")) || lower.contains("infinite") {
+            BasicError::Overflow
+        } else {
+            BasicError::Syntax
+        }
+    }
+}
+
+/// The longest placeholder-free stretch of a message text, lowercased and
+/// without the punctuation around it: `"{0}: missing argument"` gives
+/// `"missing argument"`, the part every filled-in message contains
+fn fragment(text: &str) -> String {
+    let pieces = text.split(['{', '}']).step_by(2);
+    let longest = pieces.max_by_key(|piece| piece.chars().count()).unwrap_or("");
+    longest.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
 
+use crate::i18n::{tr, trf};
+
 /// Common command typos and their corrections
 static TYPO_SUGGESTIONS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
     let mut m = HashMap::new();
@@ -56,7 +58,7 @@ pub fn suggest_command(cmd: &str) -> Option<String> {
     
     // Check exact match in typo table
     if let Some(&correction) = TYPO_SUGGESTIONS.get(cmd_upper.as_str()) {
-        return Some(format!("Did you mean '{}'?", correction));
+        return Some(trf("hint.did_you_mean", &[&correction]));
     }
     
     // Check for similar-looking commands (Levenshtein distance ≤ 2)
@@ -68,7 +70,7 @@ pub fn suggest_command(cmd: &str) -> Option<String> {
     
     for &correct_cmd in &common_commands {
         if levenshtein_distance(&cmd_upper, correct_cmd) <= 2 {
-            return Some(format!("Did you mean '{}'?", correct_cmd));
+            return Some(trf("hint.did_you_mean", &[&correct_cmd]));
         }
     }
     
@@ -82,35 +84,36 @@ pub fn check_syntax_mistakes(line: &str) -> Vec<String> {
     // Check for unclosed quotes
     let quote_count = line.chars().filter(|&c| c == '"').count();
     if quote_count % 2 != 0 {
-        suggestions.push("Unclosed string quote (\")".to_string());
+        suggestions.push(tr("hint.unclosed_quote").to_string());
     }
     
     // Check for unmatched parentheses
     let open_paren = line.chars().filter(|&c| c == '(').count();
     let close_paren = line.chars().filter(|&c| c == ')').count();
     if open_paren > close_paren {
-        suggestions.push(format!("Missing {} closing parenthesis", open_paren - close_paren));
+        suggestions.push(trf("hint.missing_closing", &[&(open_paren - close_paren)]));
     } else if close_paren > open_paren {
-        suggestions.push(format!("Missing {} opening parenthesis", close_paren - open_paren));
+        suggestions.push(trf("hint.missing_opening", &[&(close_paren - open_paren)]));
     }
     
     // Check for missing variable in LET statement
     if line.trim().to_uppercase().starts_with("LET") && !line.contains('=') {
-        suggestions.push("LET statement requires '=' (e.g., LET X = 10)".to_string());
+        suggestions.push(tr("hint.let_needs_equals").to_string());
     }
     
     // Check for missing THEN in IF statement
     let line_upper = line.trim().to_uppercase();
     if line_upper.starts_with("IF") && !line_upper.contains("THEN") {
-        suggestions.push("IF statement requires THEN (e.g., IF X > 10 THEN PRINT \"Hi\")".to_string());
+        suggestions.push(tr("hint.if_needs_then").to_string());
     }
     
     // Check for PRINT without quotes for strings
     if line_upper.starts_with("PRINT") && !line.contains('"') && !line.contains(',') {
-        let after_print = line[5..].trim();
+        let after_print = line.trim().get(5..).unwrap_or("").trim();
         if !after_print.is_empty() && !after_print.chars().next().unwrap().is_ascii_digit() 
-            && !after_print.contains('+') && !after_print.contains('-') && !after_print.contains('*') {
-            suggestions.push("String literals in PRINT should be quoted (e.g., PRINT \"Hello\")".to_string());
+            && !after_print.contains('+') && !after_print.contains('-') && !after_print.contains('*')
+            && !after_print.contains('(') {
+            suggestions.push(tr("hint.print_quote").to_string());
         }
     }
     
@@ -0,0 +1,116 @@
+//! Byte order marks and line endings in program files
+//!
+//! Files saved by Windows Notepad start with a UTF-8 byte order mark and end
+//! their lines with CRLF; older Mac editors end them with a lone CR. Left in,
+//! the mark glues onto the first statement (`\u{feff}10 PRINT …` is not line
+//! 10) and a CR ends up in the last token of every line. Programs are
+//! therefore kept in memory without the mark and with `\n` endings, and the
+//! editor remembers the ending a file came with so Save writes it back the
+//! same way.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+/// The UTF-8 byte order mark
+pub const BOM: char = '\u{feff}';
+
+/// How a file ends its lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineEnding {
+    /// `\n` (Linux, macOS)
+    #[default]
+    Lf,
+    /// `\r\n` (Windows)
+    CrLf,
+    /// `\r` (classic Mac OS)
+    Cr,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    /// Name shown in Settings
+    pub fn name(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+
+    /// The ending of the first line break in `text`; `Lf` when there is none
+    pub fn detect(text: &str) -> LineEnding {
+        match text.find(['\r', '\n']) {
+            Some(at) if text[at..].starts_with("\r\n") => LineEnding::CrLf,
+            Some(at) if text[at..].starts_with('\r') => LineEnding::Cr,
+            _ => LineEnding::Lf,
+        }
+    }
+}
+
+/// A file's text as the editor and interpreter keep it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedText {
+    /// Without a byte order mark, every line ending `\n`
+    pub text: String,
+    /// The file started with a byte order mark
+    pub had_bom: bool,
+    /// The ending the file used
+    pub ending: LineEnding,
+}
+
+/// `text` without a leading byte order mark and with `\n` line endings
+pub fn normalize(text: &str) -> NormalizedText {
+    let (had_bom, text) = match text.strip_prefix(BOM) {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    NormalizedText { text: normalize_endings(text).into_owned(), had_bom, ending: LineEnding::detect(text) }
+}
+
+/// `text` with CRLF and lone CR endings made `\n`, and a leading byte order mark dropped
+pub fn normalize_source(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix(BOM).unwrap_or(text);
+    normalize_endings(text)
+}
+
+fn normalize_endings(text: &str) -> Cow<'_, str> {
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Editor text as Save writes it: each line ending `ending`, trailing spaces and tabs
+/// removed when `trim` is set
+pub fn for_save(text: &str, ending: LineEnding, trim: bool) -> String {
+    let lines = text.split('\n');
+    let lines: Vec<&str> = if trim { lines.map(|line| line.trim_end_matches([' ', '\t'])).collect() } else { lines.collect() };
+    lines.join(ending.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_save_round_trip() {
+        let file = "\u{feff}10 PRINT \"HI\"\r\n20 END\r\n";
+        let opened = normalize(file);
+        assert_eq!(opened, NormalizedText { text: "10 PRINT \"HI\"\n20 END\n".into(), had_bom: true, ending: LineEnding::CrLf });
+        assert_eq!(for_save(&opened.text, opened.ending, false), "10 PRINT \"HI\"\r\n20 END\r\n");
+        assert_eq!(normalize("T:a\rT:b").text, "T:a\nT:b");
+        assert_eq!(LineEnding::detect("T:a\rT:b"), LineEnding::Cr);
+        assert_eq!(LineEnding::detect("T:a"), LineEnding::Lf);
+        assert!(matches!(normalize_source("T:a\nT:b"), Cow::Borrowed(_)));
+        assert_eq!(for_save("T:a  \n\tT:b\t\n", LineEnding::Lf, true), "T:a\n\tT:b\n");
+    }
+}
@@ -0,0 +1,10 @@
+// Utility modules the interpreter needs
+pub mod error;
+pub mod error_hints;
+pub mod expr_eval;
+pub mod line_endings;
+pub mod text_wrap;
+pub mod transcript;
+
+// Re-export commonly used types
+pub use expr_eval::ExpressionEvaluator;
@@ -0,0 +1,133 @@
+//! Soft word-wrapping for program output (PILOT `TW:`, BASIC `WIDTH`)
+//!
+//! Lines break at spaces; continuation lines repeat the original leading
+//! indentation, and words longer than the available width are hard-broken.
+//! Widths count terminal columns, not bytes or characters: an emoji or a CJK
+//! character takes two columns, an accent combined with its letter none.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Columns `text` takes in a monospace font
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Wrap `text` to at most `width` columns per line
+///
+/// A `width` of 0 disables wrapping. Spacing between words on the same line
+/// is kept as written; the spaces at a break are dropped.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    if width == 0 || display_width(text) <= width {
+        return vec![text.to_string()];
+    }
+
+    let body = text.trim_start_matches([' ', '\t']);
+    let indent = &text[..text.len() - body.len()];
+    // An indent that leaves no room for text is not repeated
+    let cont_indent = if display_width(indent) < width { indent } else { "" };
+
+    let mut lines = Vec::new();
+    let mut current = String::from(indent);
+    let mut current_len = display_width(indent);
+    let mut at_line_start = true;
+
+    let mut rest = body;
+    while !rest.is_empty() {
+        let word_start = rest.find(|c: char| c != ' ' && c != '\t').unwrap_or(rest.len());
+        let gap = &rest[..word_start];
+        let after_gap = &rest[word_start..];
+        let word_end = after_gap.find([' ', '\t']).unwrap_or(after_gap.len());
+        let word = &after_gap[..word_end];
+        rest = &after_gap[word_end..];
+        if word.is_empty() {
+            break;
+        }
+
+        let gap_len = display_width(gap);
+        let word_len = display_width(word);
+        if !at_line_start {
+            if current_len + gap_len + word_len <= width {
+                current.push_str(gap);
+                current.push_str(word);
+                current_len += gap_len + word_len;
+                continue;
+            }
+            lines.push(std::mem::replace(&mut current, cont_indent.to_string()));
+            current_len = display_width(cont_indent);
+        }
+
+        // Start of a line: hard-break the word if it cannot fit
+        let mut rest_of_word = word;
+        while display_width(rest_of_word) > width.saturating_sub(current_len) {
+            let room = width.saturating_sub(current_len);
+            let mut used = 0;
+            // At least one character per line, so a wide one in a narrow width still moves on
+            let Some((cut, _)) = rest_of_word.char_indices().find(|&(i, c)| {
+                used += c.width().unwrap_or(0);
+                i > 0 && used > room
+            }) else {
+                break;
+            };
+            current.push_str(&rest_of_word[..cut]);
+            rest_of_word = &rest_of_word[cut..];
+            lines.push(std::mem::replace(&mut current, cont_indent.to_string()));
+            current_len = display_width(cont_indent);
+        }
+        current_len += display_width(rest_of_word);
+        current.push_str(rest_of_word);
+        at_line_start = false;
+    }
+    if !at_line_start || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_paragraph_at_20() {
+        let text = "The quick brown fox jumps over the lazy dog and keeps running far away.";
+        assert_eq!(
+            wrap_text(text, 20),
+            vec![
+                "The quick brown fox",
+                "jumps over the lazy",
+                "dog and keeps",
+                "running far away.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_indentation_and_breaks_long_words() {
+        assert_eq!(
+            wrap_text("    Indented lines stay indented when wrapped", 20),
+            vec!["    Indented lines", "    stay indented", "    when wrapped"]
+        );
+        assert_eq!(
+            wrap_text("Supercalifragilisticexpialidocious!", 10),
+            vec!["Supercalif", "ragilistic", "expialidoc", "ious!"]
+        );
+        assert_eq!(wrap_text("Hi  there    friend", 9), vec!["Hi  there", "friend"]);
+    }
+
+    #[test]
+    fn test_short_or_unwrapped_lines_unchanged() {
+        assert_eq!(wrap_text("short", 20), vec!["short"]);
+        assert_eq!(wrap_text("", 20), vec![""]);
+        assert_eq!(wrap_text("no wrapping at width zero", 0), vec!["no wrapping at width zero"]);
+        assert_eq!(wrap_text("Grüße aus München, Österreich", 12), vec!["Grüße aus", "München,", "Österreich"]);
+    }
+
+    #[test]
+    fn test_wide_characters_take_two_columns() {
+        assert_eq!(display_width("🎉 日本"), 7);
+        assert_eq!(display_width("Zoe\u{308}"), 3);
+        assert_eq!(wrap_text("🎉🎉🎉🎉 🎉🎉🎉 ab", 10), vec!["🎉🎉🎉🎉", "🎉🎉🎉 ab"]);
+        assert_eq!(wrap_text("日本語のテキスト", 6), vec!["日本語", "のテキ", "スト"]);
+        assert_eq!(wrap_text("🎉🎉", 1), vec!["🎉", "🎉"]);
+    }
+}
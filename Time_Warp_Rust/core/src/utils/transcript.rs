@@ -0,0 +1,28 @@
+//! Program output as lines to compare: the Diff tab, `--run`, and the test helpers
+//!
+//! The interpreter's output entries can hold several lines (an error and its
+//! hint) and keep trailing spaces from `PRINT` separators. Normalized, two
+//! runs that look the same in the Output panel compare equal.
+
+/// Split multi-line entries, drop trailing whitespace and trailing blank lines
+pub fn normalize_transcript(output: &[String]) -> Vec<String> {
+    let mut lines: Vec<String> = output
+        .iter()
+        .flat_map(|entry| entry.lines().map(|l| l.trim_end().to_string()).collect::<Vec<_>>())
+        .collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_transcript() {
+        let output = vec!["Hello  ".to_string(), "❌ Error at line 2: x\n   💡 Hint: y".to_string(), String::new(), " ".to_string()];
+        assert_eq!(normalize_transcript(&output), vec!["Hello", "❌ Error at line 2: x", "   💡 Hint: y"]);
+    }
+}
@@ -1,9 +1,22 @@
 use eframe::egui;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use crate::config::project::{self, Project, ProjectSettings};
+use crate::config::startup::{FirstView, Startup};
+use crate::config::Settings;
 use crate::interpreter::Interpreter;
-use crate::graphics::TurtleState;
+use crate::interpreter::lesson::DisabledStatements;
+use crate::interpreter::metadata::{parse_metadata, ProgramMetadata};
+use crate::languages::Language;
+use crate::graphics::{MeasureTool, Pos2, TurtleState};
+use crate::i18n::{self, tr, trf};
+use crate::graphics::history::CanvasHistory;
 use crate::ui::themes::Theme;
+use crate::ui::undo::{UndoStack, UndoStep};
+use crate::utils::line_endings::{self, LineEnding};
+
+/// The window's title; a running program's `TITLE` follows it
+pub const WINDOW_TITLE: &str = "Time Warp IDE - Unified";
 
 /// Main application state for Time Warp IDE
 /// 
@@ -29,6 +42,8 @@ pub struct TimeWarpApp {
     // File management
     pub file_buffers: HashMap<String, String>,
     pub file_modified: HashMap<String, bool>,
+    /// Language picked for a buffer in the status bar (overrides the language mode and extension)
+    pub buffer_languages: HashMap<String, Language>,
     pub open_files: Vec<String>,
     pub current_file_index: usize,
     pub last_file_path: Option<String>,
@@ -36,36 +51,173 @@ pub struct TimeWarpApp {
     pub file_tree: Vec<String>,
 
     // UI state
-    pub active_tab: usize, // 0 = Editor, 1 = Output & Graphics, 2 = Debug, 3 = Explorer, 4 = Help
+    pub active_tab: usize, // 0 = Editor, 1 = Output & Graphics, 2 = Debug, 3 = Explorer, 4 = Help, 5 = Diff, 6 = Welcome
     pub show_find_replace: bool,
     pub find_text: String,
     pub replace_text: String,
+    pub show_find_in_files: bool,
+    pub find_in_files: crate::ui::find_in_files::FindInFilesPanel,
+    /// Symbols of the current buffer for the Outline panel and Go to Symbol
+    pub outline: crate::ui::outline::OutlinePanel,
+    pub show_goto_symbol: bool,
+    /// 1-based line the editor should scroll to on its next frame
+    pub pending_goto_line: Option<usize>,
+    /// Folded editor blocks per file, by `FoldBlock::key`
+    pub folded_blocks: HashMap<String, HashSet<String>>,
     pub current_theme: Theme,
+    /// The user's own settings (saved globally); see `effective_settings` for what applies
+    pub settings: Settings,
+    /// `.timewarp.toml` of the current project folder, if it has one
+    pub project: Option<Project>,
+    /// What `effective_settings` last returned, kept until the settings or the project change
+    effective_settings: project::EffectiveSettingsCache,
+    /// Save as Project Defaults found a project file and is asking before replacing it
+    pub confirm_replace_project: bool,
+    /// Settings changed in the window but not yet written, while a slider is dragged or a field has focus
+    pub settings_unsaved: bool,
+    /// Folder last checked for a project file
+    project_checked: Option<std::path::PathBuf>,
+    pub show_settings: bool,
+    pub show_tutorial: bool,
+    pub show_import_share: bool,
+    pub import_share_text: String,
+    /// Link produced by Tools → Share, shown until dismissed
+    pub share_link: Option<String>,
+    pub show_export_path: bool,
+    /// Run waiting on the "program starts with CLEARSCREEN" confirmation
+    pub confirm_clear_run: bool,
+    /// Language pick waiting on the "buffer looks like another language" notice
+    pub language_switch: Option<crate::ui::language_switch::PendingSwitch>,
+    /// Logo buffer being redrawn as BASIC `LINE`s on a background thread
+    pub language_redraw: Option<std::thread::JoinHandle<anyhow::Result<String>>>,
+    pub export_path: crate::ui::export_path::ExportPathPanel,
+    /// Tools → Program Report window
+    pub show_program_report: bool,
+    pub program_report: crate::ui::program_report::ProgramReportPanel,
+    /// Tools → Run History window
+    pub show_run_history: bool,
+    /// View → Save Canvas as PNG window
+    pub canvas_export: crate::ui::canvas_export::CanvasExportPanel,
+    pub rename: crate::ui::rename::RenamePanel,
+    /// A project's timed assessment and its Start window
+    pub assessment: crate::ui::assessment::AssessmentPanel,
+    /// Edit → Evaluate Selection's result, until it fades
+    pub inline_result: Option<crate::ui::inline_eval::Annotation>,
+    /// Help → Welcome tab
+    pub welcome: crate::ui::welcome::WelcomePanel,
+    pub run_history: crate::ui::run_history::RunHistoryPanel,
+    pub model_compare: crate::ui::model_compare::ModelComparePanel,
+    /// Pinned transcript for the Diff tab
+    pub output_diff: crate::ui::output_diff::OutputDiffPanel,
+    pub show_package: bool,
+    /// View → Diagnostics Log window
+    pub show_diagnostics: bool,
+    pub package: crate::ui::package::PackagePanel,
+    /// Folder an opened `.twpak` was unpacked to; runs are sandboxed to it
+    pub pak_workspace: Option<std::path::PathBuf>,
+    /// Unpacked bundle folders by the tab showing their entry; closing the tab removes the folder
+    pub pak_folders: HashMap<String, tempfile::TempDir>,
+    pub tutorial: crate::ui::tutorial::TutorialPanel,
+    /// Help → Example Gallery window
+    pub show_gallery: bool,
+    pub gallery: crate::ui::gallery::GalleryPanel,
+    /// Restricted mode forced by `--restricted`; the Settings toggle is locked
+    pub policy_locked: bool,
+    /// Statements `--disable` turns off for this session, on top of the settings' list
+    pub session_disabled: Vec<String>,
+    /// Folder `--kiosk` keeps File ▸ Open and runs inside; the Explorer is hidden
+    pub kiosk_root: Option<std::path::PathBuf>,
+    /// Folder the IDE started in; programs opened from outside it are untrusted
+    pub workspace_root: Option<std::path::PathBuf>,
+    /// Tabs opened from outside the workspace: they run in Restricted mode until trusted
+    pub untrusted_files: HashSet<String>,
+    /// Canonical path of the file each tab was opened from or saved to (unsaved tabs have none)
+    pub file_paths: HashMap<String, std::path::PathBuf>,
+    /// Tabs whose file was not valid UTF-8 (unreadable bytes were replaced on opening)
+    pub lossy_files: HashSet<String>,
+    /// Tabs whose file began with a UTF-8 byte order mark (removed on opening)
+    pub bom_files: HashSet<String>,
+    /// Line endings each tab's file used, so Save writes them back the same way
+    pub file_line_endings: HashMap<String, LineEnding>,
+    /// Run the current tab on the next frame (`--autorun`, `autorun_on_open`)
+    pub autorun_pending: bool,
+    /// UI scale last pushed to egui, so pixels-per-point is only set on change
+    pub applied_ui_scale: Option<f32>,
+    /// Alt tap tracking; a lone Alt sets `focus_menu_bar` for the next menu bar draw
+    pub alt_tap: crate::ui::keyboard::AltTap,
+    pub focus_menu_bar: bool,
     
     // Execution state
     pub interpreter: Interpreter,
-    pub is_executing: bool,
+    /// Idle, running, waiting for input, between steps, or finished; Run, Step, and Stop consult it
+    pub execution: crate::ui::execution::ExecutionSession,
+    /// Run pressed while a program runs, waiting on the "restart it?" confirmation
+    pub confirm_restart_run: bool,
+    /// Editor tab the last run came from (its error lines are marked in the editor)
+    pub run_file: Option<String>,
+    /// Program text the last run started from (kept in the run history)
+    pub run_source: String,
+    /// Run in progress on a background thread; holds the real interpreter until it finishes
+    pub run_worker: Option<crate::interpreter::worker::RunWorker>,
+    /// Banner state for the last run (Output tab)
+    pub run_summary: Option<crate::ui::run_summary::RunSummary>,
+    /// When the last run started, RFC 3339 (a submission's report gives it)
+    pub run_started: String,
+    /// Time the current run has spent executing, across INPUT pauses
+    pub run_time: std::time::Duration,
+    /// Stop was pressed during the current run
+    pub run_stopped: bool,
+    /// Title and status bar message the running program set (`TITLE`, `TI:`, `STATUS`), until it ends
+    pub program_title: Option<String>,
+    pub program_status: Option<String>,
+    /// Title last given to the window
+    applied_window_title: String,
     pub error_message: Option<String>,
     
-    // Edit history (future features)
-    pub undo_history: Vec<String>,
-    pub undo_position: usize,
-    pub max_undo_steps: usize,
+    /// Edit history across tabs (Edit → Undo / Redo)
+    pub undo: UndoStack,
     
     // Graphics
     pub turtle_state: TurtleState,
+    /// Drawings saved before each run / Clear Graphics (View → Restore Previous Canvas)
+    pub canvas_history: CanvasHistory,
     pub turtle_zoom: f32,
     #[allow(dead_code)]
     pub turtle_pan: egui::Vec2,
     
     // Input prompt state
     pub input_buffer: String,
+    /// Direct-mode command line under the screen
+    pub direct_input: String,
+    /// Output line shown in full in its own window (Transcript ⤢)
+    pub full_line_view: Option<usize>,
+    /// Copy the direct-mode recording as `TO DRAWING … END`
+    pub wrap_recording: bool,
     
     // Keyboard state for INKEY$
     pub last_key_pressed: Option<String>,
 
     // UI options
     pub show_overlay_text: bool,
+    /// Output → Highlight variables: mark the values T:/PRINT took from variables
+    pub highlight_variables: bool,
+    /// Transcript → 🕑: each line's time from the start of the run, when it was recorded
+    pub show_output_times: bool,
+    /// Transcript → Replay in progress, and the speed the next one plays at
+    pub replay: Option<crate::ui::replay::Replay>,
+    pub replay_speed: f32,
+    /// Output → Ruler / Protractor: where canvas clicks go
+    pub measure_tool: MeasureTool,
+    /// Canvas clicks of the measurement in progress, in turtle coordinates
+    pub measure_points: Vec<Pos2>,
+    /// `Flash::serial` of the Logo FLASH on the canvas, and when it started to fade
+    pub flash_shown: Option<(u64, std::time::Instant)>,
+    /// The text screen last drawn and its texture, redrawn when the screen changes
+    pub text_screen_texture: Option<(crate::graphics::text_screen::TextScreen, egui::TextureHandle)>,
+    /// The low-memory canvas's backdrop as drawn: its serial, whether colors were CVD-safe, and the texture
+    pub backdrop_texture: Option<((u64, bool), egui::TextureHandle)>,
+    /// File → Export → Text Screen: pixels per font pixel in a PNG or SVG
+    pub screen_export_scale: u32,
     pub show_about_dialog: bool,
     
     // Debug state (future features)
@@ -77,10 +229,15 @@ pub struct TimeWarpApp {
 }
 
 impl TimeWarpApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
+    pub fn new(_cc: &eframe::CreationContext<'_>, startup: Startup) -> Self {
+        let settings = Settings::load();
+        i18n::set_locale(settings.locale);
+        let mut interpreter = Interpreter::new();
+        interpreter.speech = crate::speech::system_queue();
+        let mut app = Self {
             file_buffers: HashMap::new(),
             file_modified: HashMap::new(),
+            buffer_languages: HashMap::new(),
             open_files: vec!["untitled.pilot".to_string()],
             current_file_index: 0,
             last_file_path: None,
@@ -90,37 +247,307 @@ impl TimeWarpApp {
             show_find_replace: false,
             find_text: String::new(),
             replace_text: String::new(),
-            current_theme: Theme::default(),
+            show_find_in_files: false,
+            find_in_files: Default::default(),
+            outline: Default::default(),
+            show_goto_symbol: false,
+            pending_goto_line: None,
+            folded_blocks: HashMap::new(),
+            current_theme: settings.theme,
+            settings,
+            project: None,
+            effective_settings: Default::default(),
+            confirm_replace_project: false,
+            settings_unsaved: false,
+            project_checked: None,
+            show_settings: false,
+            show_tutorial: false,
+            show_import_share: false,
+            import_share_text: String::new(),
+            share_link: None,
+            show_export_path: false,
+            confirm_clear_run: false,
+            language_switch: None,
+            language_redraw: None,
+            export_path: Default::default(),
+            show_program_report: false,
+            program_report: Default::default(),
+            show_run_history: false,
+            canvas_export: Default::default(),
+            rename: Default::default(),
+            assessment: Default::default(),
+            inline_result: None,
+            welcome: Default::default(),
+            run_history: Default::default(),
+            model_compare: Default::default(),
+            output_diff: Default::default(),
+            show_package: false,
+            show_diagnostics: false,
+            package: Default::default(),
+            pak_workspace: None,
+            pak_folders: HashMap::new(),
+            tutorial: crate::ui::tutorial::TutorialPanel::default(),
+            show_gallery: false,
+            gallery: crate::ui::gallery::GalleryPanel::default(),
+            policy_locked: false,
+            session_disabled: Vec::new(),
+            kiosk_root: None,
+            workspace_root: std::env::current_dir().ok().map(|cwd| std::fs::canonicalize(&cwd).unwrap_or(cwd)),
+            untrusted_files: HashSet::new(),
+            file_paths: HashMap::new(),
+            lossy_files: HashSet::new(),
+            bom_files: HashSet::new(),
+            file_line_endings: HashMap::new(),
+            autorun_pending: false,
+            applied_ui_scale: None,
+            alt_tap: Default::default(),
+            focus_menu_bar: false,
             
-            interpreter: Interpreter::new(),
-            is_executing: false,
+            interpreter,
+            execution: Default::default(),
+            confirm_restart_run: false,
+            run_file: None,
+            run_source: String::new(),
+            run_worker: None,
+            run_summary: None,
+            run_started: String::new(),
+            run_time: std::time::Duration::ZERO,
+            run_stopped: false,
+            program_title: None,
+            program_status: None,
+            applied_window_title: WINDOW_TITLE.to_string(),
             error_message: None,
             
-            undo_history: Vec::new(),
-            undo_position: 0,
-            max_undo_steps: 100,
+            undo: UndoStack::new(100),
             
             turtle_state: TurtleState::new(),
+            canvas_history: CanvasHistory::default(),
             turtle_zoom: 1.0,
             turtle_pan: egui::Vec2::ZERO,
             
             input_buffer: String::new(),
+            direct_input: String::new(),
+            full_line_view: None,
+            wrap_recording: false,
             last_key_pressed: None,
 
             show_overlay_text: true,
+            highlight_variables: false,
+            show_output_times: false,
+            replay: None,
+            replay_speed: 1.0,
+            measure_tool: MeasureTool::Off,
+            measure_points: Vec::new(),
+            flash_shown: None,
+            text_screen_texture: None,
+            backdrop_texture: None,
+            screen_export_scale: 2,
             show_about_dialog: false,
             
             debug_mode: false,
             breakpoints: HashMap::new(),
             current_debug_line: None,
             step_mode: false,
+        };
+        app.open_startup(&startup);
+        // Loads the project file, if any, and applies the settings to the interpreter
+        app.refresh_project();
+        app.turtle_state.shape = app.settings.turtle_shape.clone();
+        if startup.first_view(&app.settings) == FirstView::Welcome {
+            crate::ui::welcome::show(&mut app);
         }
+        app
+    }
+    
+    /// Open the `--open` programs, pick the one to run, and enter kiosk mode
+    ///
+    /// The program to run (else the first one) ends up in the current tab.
+    fn open_startup(&mut self, startup: &Startup) {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        self.kiosk_root = startup.kiosk_root(&cwd).map(|root| std::fs::canonicalize(&root).unwrap_or(root));
+        // Tab and file path each program opened as (a bundle opens its entry program)
+        let mut opened = HashMap::new();
+        for path in &startup.open {
+            let result = if crate::utils::twpak::is_pak_path(path) { self.open_pak(path) } else { self.open_path(path) };
+            match result {
+                Ok(()) => {
+                    opened.insert(path.clone(), (self.current_file_index, self.last_file_path.clone()));
+                }
+                Err(e) => self.error_message = Some(trf("file.open_path_failed", &[&path.display(), &e])),
+            }
+        }
+        let run = startup.program_to_run(|program| {
+            let folder = cwd.join(program).parent().map(std::path::Path::to_path_buf).unwrap_or_default();
+            let project = ProjectSettings::load(&folder).ok().flatten();
+            project::effective_settings(&self.settings, project.as_ref(), false).autorun_on_open
+        });
+        let focus = run.or(startup.open.first().map(std::path::PathBuf::as_path));
+        if let Some((tab, file_path)) = focus.and_then(|focus| opened.remove(focus)) {
+            self.current_file_index = tab;
+            self.last_file_path = file_path;
+            self.autorun_pending = run.is_some();
+        }
+    }
+    
+    /// Store the active theme in settings and write them to disk
+    pub fn save_settings(&mut self) {
+        if !self.project_overrides("theme") {
+            self.settings.theme = self.current_theme;
+        }
+        if let Err(e) = self.settings.save() {
+            tracing::warn!("Failed to save settings: {}", e);
+        }
+    }
+    
+    /// Settings in effect: the user's, then the project file's, then `--restricted`
+    pub fn effective_settings(&self) -> Settings {
+        self.effective_settings.get(&self.settings, self.project.as_ref().map(|p| &p.settings), self.policy_locked)
+    }
+    
+    /// Whether the open project's file sets `key` (see `config::project::PROJECT_KEYS`)
+    pub fn project_overrides(&self, key: &str) -> bool {
+        self.project.as_ref().is_some_and(|p| p.settings.overrides(key))
+    }
+    
+    /// Push the effective settings into the interpreter and the theme
+    pub fn apply_settings(&mut self) {
+        let settings = self.effective_settings();
+        self.interpreter.speak_output = settings.text_to_speech;
+        self.interpreter.limits = settings.limits.clone();
+        self.interpreter.classic_errors = settings.classic_basic_errors;
+        self.interpreter.strict_warnings = settings.strict_warnings.clone();
+        self.interpreter.keep_variables = settings.keep_variables;
+        self.interpreter.angle_mode = settings.angle_mode;
+        self.interpreter.disabled = self.disabled_statements();
+        self.interpreter.honor_source_breakpoints = settings.honor_source_breakpoints;
+        self.interpreter.record_timing = settings.record_output_timing;
+        self.interpreter.slice_budget.fixed = settings.statements_per_slice;
+        self.current_theme = settings.theme;
+        i18n::set_locale(settings.locale);
+        // Refused while a program waits for input; run_program applies it next time
+        let _ = self.interpreter.set_policy(self.security_policy());
+    }
+    
+    /// Load the project file when the project folder changes; call once per frame
+    pub fn refresh_project(&mut self) {
+        let root = self.project_root();
+        if root != self.project_checked {
+            self.project_checked = root;
+            self.reload_project();
+            crate::ui::run_history::reopen(self);
+        }
+    }
+    
+    /// Read the project folder's `.timewarp.toml` again (after it was edited or saved)
+    pub fn reload_project(&mut self) {
+        self.project = None;
+        if let Some(root) = self.project_root() {
+            match ProjectSettings::load(&root) {
+                Ok(Some(settings)) => self.project = Some(Project { root, settings }),
+                Ok(None) => {}
+                Err(e) => self.error_message = Some(trf("settings.project.ignored", &[&format!("{:#}", e)])),
+            }
+        }
+        self.apply_settings();
+    }
+    
+    /// Directory searched by Find in Files: the last opened file's folder, else the working directory
+    pub fn project_root(&self) -> Option<std::path::PathBuf> {
+        self.last_file_path
+            .as_ref()
+            .and_then(|p| std::path::Path::new(p).parent().map(|d| d.to_path_buf()))
+            .or_else(|| std::env::current_dir().ok())
+    }
+    
+    /// Open a file from disk in a new tab, or switch to it if a tab with that name is open
+    ///
+    /// Files over `safe_open::MAX_OPEN_BYTES` are refused; a file from outside
+    /// the workspace opens untrusted, one that is not UTF-8 opens with its
+    /// unreadable bytes replaced, and a byte order mark is removed (each gets a
+    /// banner in the editor). Lines end in `\n` in the editor whatever the file
+    /// used; Save puts the file's own endings back ([`Self::text_for_save`]).
+    pub fn open_path(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        let filename = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Not a file: {}", path.display()))?
+            .to_string_lossy()
+            .to_string();
+        if let Some(idx) = self.open_files.iter().position(|f| *f == filename) {
+            self.current_file_index = idx;
+            return Ok(());
+        }
+        let opened = crate::utils::safe_open::read_text(path, crate::utils::safe_open::MAX_OPEN_BYTES)?;
+        // Leaving an unpacked bundle ends its sandbox
+        if self.pak_workspace.as_ref().is_some_and(|root| !path.starts_with(root)) {
+            self.pak_workspace = None;
+        }
+        if opened.lossy {
+            self.lossy_files.insert(filename.clone());
+        }
+        let normalized = line_endings::normalize(&opened.text);
+        if normalized.had_bom {
+            tracing::warn!("{} starts with a UTF-8 byte order mark; removed it", path.display());
+            self.bom_files.insert(filename.clone());
+        }
+        self.file_line_endings.insert(filename.clone(), normalized.ending);
+        if !self.in_workspace(path) {
+            tracing::info!("Opened {} from outside the workspace; it runs restricted until trusted", path.display());
+            self.untrusted_files.insert(filename.clone());
+        }
+        self.file_buffers.insert(filename.clone(), normalized.text);
+        self.file_paths.insert(filename.clone(), std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()));
+        self.open_files.push(filename);
+        self.current_file_index = self.open_files.len() - 1;
+        self.last_file_path = Some(path.to_string_lossy().to_string());
+        Ok(())
+    }
+    
+    /// Unpack a `.twpak` bundle to a temporary folder and open its entry program
+    ///
+    /// The folder becomes the sandbox workspace for runs until a file from
+    /// elsewhere is opened, and is removed when the entry's tab is closed.
+    pub fn open_pak(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        let mut pak = crate::utils::twpak::extract_to_temp(path)?;
+        tracing::info!(
+            "Opened bundle {} ({} file(s)) into {}",
+            pak.manifest.title.as_deref().unwrap_or(&pak.manifest.entry),
+            pak.manifest.files.len(),
+            pak.root.display()
+        );
+        self.pak_workspace = Some(pak.root.clone());
+        self.open_path(&pak.entry_path)?;
+        if let (Some(tab), Some(folder)) = (self.current_file().cloned(), pak.temp_dir.take()) {
+            self.pak_folders.insert(tab, folder);
+        }
+        self.active_tab = 0;
+        Ok(())
+    }
+    
+    /// Open a program decoded from a share link in a new editor tab
+    pub fn open_shared(&mut self, program: crate::utils::share::SharedProgram) {
+        let extension = program.extension();
+        self.open_unsaved("shared", extension, program.source);
+    }
+    
+    /// Show `source` in a new, unsaved editor tab named `<prefix>_<n>.<extension>`
+    pub fn open_unsaved(&mut self, prefix: &str, extension: &str, source: String) {
+        let filename = crate::ui::undo::unused_tab_name(&self.open_files, prefix, extension);
+        self.file_buffers.insert(filename.clone(), source);
+        self.open_files.push(filename);
+        self.current_file_index = self.open_files.len() - 1;
+        self.active_tab = 0;
     }
     
     pub fn current_file(&self) -> Option<&String> {
         self.open_files.get(self.current_file_index)
     }
     
+    /// `code` of tab `file` as Save writes it: in the line endings Settings picks, else the ones the file had
+    pub fn text_for_save(&self, file: &str, code: &str) -> String {
+        let ending = self.settings.line_ending.or_else(|| self.file_line_endings.get(file).copied()).unwrap_or_default();
+        line_endings::for_save(code, ending, self.settings.trim_trailing_whitespace)
+    }
+
     pub fn current_code(&self) -> String {
         self.current_file()
             .and_then(|f| self.file_buffers.get(f))
@@ -128,54 +555,143 @@ impl TimeWarpApp {
             .unwrap_or_default()
     }
     
+    /// Policy for the next run, honouring `--restricted`, an opened `.twpak`, `--kiosk`,
+    /// and an untrusted current tab
+    pub fn security_policy(&self) -> crate::interpreter::SecurityPolicy {
+        let mut policy = self.effective_settings().security_policy();
+        if let Some(root) = self.pak_workspace.as_ref().or(self.kiosk_root.as_ref()) {
+            policy.workspace_path = Some(root.clone());
+        }
+        if self.current_file().is_some_and(|f| self.untrusted_files.contains(f)) {
+            policy = policy.untrusted();
+        }
+        policy
+    }
+    
+    /// Statements the next run refuses: the settings' (or project's) list and `--disable`
+    pub fn disabled_statements(&self) -> DisabledStatements {
+        DisabledStatements::new(self.effective_settings().disabled_statements.iter().chain(&self.session_disabled))
+    }
+    
+    /// Whether `path` lies in the folder the IDE started in, the `--kiosk` folder, or an opened `.twpak`
+    pub fn in_workspace(&self, path: &std::path::Path) -> bool {
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        [&self.workspace_root, &self.kiosk_root, &self.pak_workspace].into_iter().flatten().any(|root| {
+            let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+            crate::interpreter::policy::path_in_workspace(&root, &path).is_some()
+        })
+    }
+    
+    /// Whether File ▸ Open may open `path`: anything, except outside the folder in kiosk mode
+    pub fn may_open(&self, path: &std::path::Path) -> bool {
+        let Some(root) = &self.kiosk_root else { return true };
+        let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        crate::interpreter::policy::path_in_workspace(root, &path).is_some()
+    }
+    
+    /// Turn Restricted mode on for this session without changing saved settings (`--restricted`)
+    pub fn lock_restricted(&mut self) {
+        self.policy_locked = true;
+        let _ = self.interpreter.set_policy(self.security_policy());
+    }
+    
+    /// Header directives (@title, @lang, ...) of the current buffer
+    pub fn current_metadata(&self) -> ProgramMetadata {
+        self.current_file()
+            .and_then(|f| self.file_buffers.get(f))
+            .map(|code| parse_metadata(code))
+            .unwrap_or_default()
+    }
+    
+    /// Language picked for the current buffer in the status bar, else the language mode setting
+    pub fn selected_language(&self) -> Option<Language> {
+        self.current_file()
+            .and_then(|f| self.buffer_languages.get(f).copied())
+            .or(self.effective_settings().language_mode)
+    }
+    
+    /// Language of the current buffer: its @lang header, else the selected language, else its file extension
+    pub fn current_language(&self) -> Language {
+        match self.current_file() {
+            Some(file) => self.language_of(file),
+            None => self.selected_language().unwrap_or(Language::Pilot),
+        }
+    }
+    
+    /// Language of open tab `file`, decided as for the current buffer
+    pub fn language_of(&self, file: &str) -> Language {
+        let header = self.file_buffers.get(file).and_then(|code| parse_metadata(code).language);
+        let selected = self.buffer_languages.get(file).copied().or(self.effective_settings().language_mode);
+        header.or(selected).unwrap_or_else(|| {
+            let ext = std::path::Path::new(file).extension().and_then(|e| e.to_str()).unwrap_or("pilot");
+            Language::from_extension(ext)
+        })
+    }
+    
+    /// Show the running program's title after the IDE's; both the title and status go when the run ends
+    fn apply_window_title(&mut self, ctx: &egui::Context) {
+        use crate::ui::execution::SessionState;
+        if matches!(self.execution.state(), SessionState::Idle | SessionState::Finished) {
+            self.program_title = None;
+            self.program_status = None;
+        }
+        let title = match &self.program_title {
+            Some(program) => format!("{} — {}", WINDOW_TITLE, program),
+            None => WINDOW_TITLE.to_string(),
+        };
+        if title != self.applied_window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.applied_window_title = title;
+        }
+    }
+    
+    /// Replace the current tab's text as one tr("undo.typing") undo step
     pub fn set_current_code(&mut self, code: String) {
         if let Some(file) = self.current_file().cloned() {
-            // Save to undo history before changing
-            let old_code = self.file_buffers.get(&file).cloned().unwrap_or_default();
-            if old_code != code {
-                self.push_undo_state(old_code);
-            }
-            self.file_buffers.insert(file.clone(), code);
-            self.file_modified.insert(file, true);
+            self.apply_buffer_edit(&file, code, tr("undo.typing"));
         }
     }
     
-    pub fn push_undo_state(&mut self, state: String) {
-        // Remove any states after current position
-        self.undo_history.truncate(self.undo_position);
-        
-        // Add new state
-        self.undo_history.push(state);
-        
-        // Maintain max history size
-        if self.undo_history.len() > self.max_undo_steps {
-            self.undo_history.remove(0);
-        } else {
-            self.undo_position = self.undo_history.len();
+    /// Replace the text of tab `file` as one undo step named `description` (e.g. "Replace All")
+    ///
+    /// Every change to an open buffer goes through here, so each edit is
+    /// undoable as a whole and marks its tab modified.
+    pub fn apply_buffer_edit(&mut self, file: &str, new_text: String, description: &str) {
+        if crate::ui::assessment::locked(self) {
+            return;
+        }
+        let before = self.file_buffers.get(file).cloned().unwrap_or_default();
+        if before == new_text {
+            return;
+        }
+        self.undo.record(UndoStep {
+            file: file.to_string(),
+            before,
+            after: new_text.clone(),
+            description: description.to_string(),
+        });
+        self.file_buffers.insert(file.to_string(), new_text);
+        self.file_modified.insert(file.to_string(), true);
+    }
+    
+    /// Put `text` back into tab `file` and show that tab
+    fn restore_buffer(&mut self, file: String, text: String) {
+        if let Some(idx) = self.open_files.iter().position(|f| *f == file) {
+            self.current_file_index = idx;
         }
+        self.file_buffers.insert(file.clone(), text);
+        self.file_modified.insert(file, true);
     }
     
     pub fn undo(&mut self) {
-        if self.undo_position > 0 {
-            self.undo_position -= 1;
-            if let Some(state) = self.undo_history.get(self.undo_position).cloned() {
-                if let Some(file) = self.current_file().cloned() {
-                    self.file_buffers.insert(file.clone(), state);
-                    self.file_modified.insert(file, true);
-                }
-            }
+        if let Some(step) = self.undo.undo().cloned() {
+            self.restore_buffer(step.file, step.before);
         }
     }
     
     pub fn redo(&mut self) {
-        if self.undo_position < self.undo_history.len() {
-            if let Some(state) = self.undo_history.get(self.undo_position).cloned() {
-                if let Some(file) = self.current_file().cloned() {
-                    self.file_buffers.insert(file.clone(), state);
-                    self.file_modified.insert(file, true);
-                }
-                self.undo_position += 1;
-            }
+        if let Some(step) = self.undo.redo().cloned() {
+            self.restore_buffer(step.file, step.after);
         }
     }
 }
@@ -199,12 +715,41 @@ impl eframe::App for TimeWarpApp {
             }
         });
         
+        // Project defaults follow the folder of the open file
+        self.refresh_project();
+        
+        // App-wide shortcuts (Alt menu focus, Ctrl+Tab, Ctrl+1..5, Escape, Ctrl+Shift+F)
+        crate::ui::keyboard::handle_shortcuts(self, ctx);
+        
+        // Lines drawn by a background run so far, and its result once done
+        crate::ui::menubar::poll_run_worker(self, ctx);
+        // An assessment's clock: stop the run and lock the editor when time is up
+        crate::ui::assessment::update(self, ctx);
+        if std::mem::take(&mut self.autorun_pending) {
+            crate::ui::menubar::request_run(self);
+        }
+        
+        self.apply_window_title(ctx);
+        
         // Apply theme
         self.current_theme.apply(ctx);
         
+        // Apply global UI scale (separate from editor font size)
+        if self.applied_ui_scale != Some(self.settings.ui_scale) {
+            let native = ctx.native_pixels_per_point().unwrap_or(1.0);
+            ctx.set_pixels_per_point(native * self.settings.ui_scale);
+            self.applied_ui_scale = Some(self.settings.ui_scale);
+        }
+        
         // Top menu bar
         crate::ui::menubar::render(self, ctx);
         
+        // Outline beside the editor (symbols are kept current for Go to Symbol either way)
+        crate::ui::outline::update(self, ctx);
+        if self.settings.show_outline && self.active_tab == 0 {
+            crate::ui::outline::render(self, ctx);
+        }
+        
         // Main content area
         egui::CentralPanel::default().show(ctx, |ui| {
             // Tab bar
@@ -217,8 +762,10 @@ impl eframe::App for TimeWarpApp {
                 0 => crate::ui::editor::render(self, ui),
                 1 => crate::ui::output::render(self, ui),
                 2 => crate::ui::debugger::render(self, ui),
-                3 => crate::ui::explorer::render(self, ui),
+                3 if self.kiosk_root.is_none() => crate::ui::explorer::render(self, ui),
                 4 => crate::ui::help::render(self, ui),
+                crate::ui::output_diff::DIFF_TAB => crate::ui::output_diff::render(self, ui),
+                crate::ui::welcome::WELCOME_TAB if self.welcome.open => crate::ui::welcome::render(self, ui),
                 _ => {}
             }
         });
@@ -231,14 +778,95 @@ impl eframe::App for TimeWarpApp {
             crate::ui::editor::render_find_replace(self, ctx);
         }
         
+        if self.show_find_in_files {
+            crate::ui::find_in_files::render(self, ctx);
+        }
+        
+        if self.show_goto_symbol {
+            crate::ui::outline::render_goto(self, ctx);
+        }
+        
+        if self.rename.target.is_some() {
+            crate::ui::rename::render(self, ctx);
+        }
+        
+        crate::ui::assessment::render_start(self, ctx);
+        
+        // Settings window
+        if self.show_settings {
+            crate::ui::settings::render(self, ctx);
+        }
+        
+        // Share link / import share dialogs
+        if self.share_link.is_some() {
+            crate::ui::share::render_link(self, ctx);
+        }
+        if self.show_import_share {
+            crate::ui::share::render_import(self, ctx);
+        }
+        
+        if self.confirm_clear_run {
+            crate::ui::menubar::render_run_confirmation(self, ctx);
+        }
+        
+        if self.confirm_restart_run {
+            crate::ui::menubar::render_restart_confirmation(self, ctx);
+        }
+        
+        if self.language_redraw.is_some() {
+            crate::ui::language_switch::poll_redraw(self, ctx);
+        }
+        if self.language_switch.is_some() {
+            crate::ui::language_switch::render_notice(self, ctx);
+        }
+        
+        if self.show_export_path {
+            crate::ui::export_path::render(self, ctx);
+        }
+        
+        if self.show_program_report {
+            crate::ui::program_report::render(self, ctx);
+        }
+        
+        if self.show_run_history {
+            crate::ui::run_history::render(self, ctx);
+        }
+        
+        if self.model_compare.open {
+            crate::ui::model_compare::render(self, ctx);
+        }
+        
+        if self.canvas_export.open {
+            crate::ui::canvas_export::render(self, ctx);
+        }
+        
+        if self.show_package {
+            crate::ui::package::render(self, ctx);
+        }
+        
+        if self.show_diagnostics {
+            crate::ui::diagnostics::render(self, ctx);
+        }
+        
+        // Tutorial window
+        if self.show_tutorial {
+            crate::ui::tutorial::render(self, ctx);
+        }
+        
+        if self.show_gallery {
+            crate::ui::gallery::render(self, ctx);
+        }
+        
         // Error notification
         if let Some(ref msg) = self.error_message.clone() {
-            egui::Window::new("Error")
+            egui::Window::new(tr("common.error"))
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
                     ui.colored_label(egui::Color32::RED, msg);
-                    if ui.button("OK").clicked() {
+                    let ok = ui.button(tr("common.ok"));
+                    crate::ui::keyboard::focus_on_open(&ok);
+                    if ok.clicked() {
                         self.error_message = None;
                     }
                 });
@@ -246,22 +874,24 @@ impl eframe::App for TimeWarpApp {
         
         // About dialog
         if self.show_about_dialog {
-            egui::Window::new("About Time Warp IDE")
+            egui::Window::new(tr("about.title"))
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
                     ui.vertical_centered(|ui| {
                         ui.heading("Time Warp IDE");
-                        ui.label(format!("Version {}", env!("CARGO_PKG_VERSION")));
+                        ui.label(trf("about.version", &[&env!("CARGO_PKG_VERSION")]));
                         ui.add_space(10.0);
-                        ui.label("Educational programming environment for TempleCode");
-                        ui.label("Combining BASIC, PILOT, and Logo");
+                        ui.label(tr("about.tagline"));
+                        ui.label(tr("about.languages"));
                         ui.add_space(10.0);
-                        ui.hyperlink_to("GitHub Repository", "https://github.com/James-HoneyBadger/Time_Warp");
+                        ui.hyperlink_to(tr("about.repository"), "https://github.com/James-HoneyBadger/Time_Warp");
                         ui.add_space(10.0);
                         ui.label("© 2025 James Temple");
                         ui.add_space(10.0);
-                        if ui.button("Close").clicked() {
+                        let close = ui.button(tr("common.close"));
+                        crate::ui::keyboard::focus_on_open(&close);
+                        if close.clicked() {
                             self.show_about_dialog = false;
                         }
                     });
@@ -0,0 +1,235 @@
+//! Persistent user preferences for Time Warp IDE
+//!
+//! Settings are stored as TOML in the platform config directory
+//! (`$XDG_CONFIG_HOME/time_warp/settings.toml`, falling back to
+//! `~/.config/time_warp/settings.toml`, or `%APPDATA%\time_warp` on Windows).
+//! Missing or unreadable files fall back to defaults so a broken config
+//! never prevents the IDE from starting. A project folder can override some
+//! of them with its own `.timewarp.toml` (see [`project`]).
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crate::graphics::retention::DEFAULT_LINE_BUDGET;
+use crate::graphics::{AngleMode, TurtleShape};
+use crate::i18n::Locale;
+use crate::interpreter::warnings::WarningKind;
+use crate::interpreter::{ExecutionLimits, ScreenMode, SecurityPolicy};
+use crate::languages::Language;
+use crate::ui::themes::Theme;
+use crate::utils::line_endings::LineEnding;
+use crate::utils::assessment::AssessmentSettings;
+use crate::utils::run_reporters::ReportSettings;
+
+pub mod project;
+pub mod startup;
+pub mod templates;
+
+/// Folder for files the IDE keeps for itself, such as cached example thumbnails
+///
+/// `$XDG_DATA_HOME/time_warp`, falling back to `~/.local/share/time_warp`, or
+/// `%LOCALAPPDATA%\time_warp` on Windows.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local").join("share")))?;
+    Some(base.join("time_warp"))
+}
+
+/// User-adjustable preferences shown in the Settings window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    /// Global UI zoom passed to `ctx.set_pixels_per_point` (independent of editor font size)
+    pub ui_scale: f32,
+    /// Language of the menus, panels, and run messages
+    pub locale: Locale,
+    /// Remap named Logo colors to the Okabe-Ito palette when drawing the canvas
+    pub cvd_safe_colors: bool,
+    /// Apply the on-screen color mapping to exported images as well
+    pub export_as_displayed: bool,
+    /// Draw the turtle into saved canvas images
+    pub export_turtle: bool,
+    /// Draw the ruler or protractor measurement into saved canvas images
+    pub export_measurements: bool,
+    /// Show the Outline panel beside the editor
+    pub show_outline: bool,
+    /// Open the Welcome tab whenever the IDE starts without programs to open
+    pub show_welcome: bool,
+    /// The Welcome tab has been shown once; until then it opens at startup whatever `show_welcome` says
+    pub welcome_seen: bool,
+    /// Speak every T:/PRINT line (requires the `tts` feature and a speech engine)
+    pub text_to_speech: bool,
+    /// Program size, loop span, and output caps applied to every run
+    pub limits: ExecutionLimits,
+    /// Report BASIC errors as `?SYNTAX ERROR IN 20` with classic error numbers
+    pub classic_basic_errors: bool,
+    /// Show `⚠️ Warning …` lines in the Output transcript and screen
+    pub show_warnings: bool,
+    /// Warnings reported as errors (strict mode)
+    pub strict_warnings: BTreeSet<WarningKind>,
+    /// Ask before running a program that opens with CLEARSCREEN over an existing drawing
+    pub confirm_clearscreen: bool,
+    /// Fold the oldest canvas lines into an image once more than `canvas_line_budget` are kept
+    pub low_memory_canvas: bool,
+    /// Lines the low-memory canvas keeps as lines
+    pub canvas_line_budget: usize,
+    /// Heading convention for Logo programs (SETANGLEMODE overrides it for one run)
+    pub angle_mode: AngleMode,
+    /// How the turtle is drawn (SETSHAPE overrides it for one run)
+    pub turtle_shape: TurtleShape,
+    /// Steps completed per tutorial id (where the Tutorial window resumes)
+    pub tutorial_progress: BTreeMap<String, usize>,
+    /// Classroom lockdown: apply `restricted_policy` to every run
+    pub restricted_mode: bool,
+    /// Permissions and limits used while Restricted mode is on
+    pub restricted_policy: SecurityPolicy,
+    /// Language for programs without an @lang header; `None` goes by file extension
+    pub language_mode: Option<Language>,
+    /// Screen every run starts in; `None` keeps whatever the last SCREEN set
+    pub screen_mode: Option<ScreenMode>,
+    /// Spaces the Tab key inserts in the editor
+    pub tab_width: usize,
+    /// Line endings Save writes; `None` keeps each file's own
+    pub line_ending: Option<LineEnding>,
+    /// Remove spaces and tabs at the ends of lines on Save
+    pub trim_trailing_whitespace: bool,
+    /// Switch back to the Editor tab when a run finishes without errors
+    pub return_to_editor: bool,
+    /// Carry every variable into the next run, not just the BASIC COMMON ones
+    pub keep_variables: bool,
+    /// Seed every run's random numbers start from; `None` picks a fresh one each run
+    pub random_seed: Option<u64>,
+    /// Run the first program given with `--open` as soon as the IDE starts
+    pub autorun_on_open: bool,
+    /// Statements a lesson turns off, e.g. `["GOTO", "GOSUB"]`; usually set by a project
+    pub disabled_statements: Vec<String>,
+    /// Stop runs at `REM BREAK`, `'BREAK`, `R:BREAK`, and `;BREAK` lines in the program
+    pub honor_source_breakpoints: bool,
+    /// Note when each output line appears and how long each prompt waits (the report's `timing`)
+    pub record_output_timing: bool,
+    /// Statements in each slice a run is sent to the IDE in; `None` fits them to a few milliseconds
+    pub statements_per_slice: Option<usize>,
+    /// Save Tools → Run History per project folder, so it is there next session
+    pub keep_run_history: bool,
+    /// Let BASIC `SHELL` run the programs in `shell_allowlist` (never in Restricted mode); no project can set it
+    pub allow_shell: bool,
+    /// Programs `SHELL` may run, by bare name; a project's `.timewarp.toml` can only shorten the list
+    pub shell_allowlist: Vec<String>,
+    /// Where runs are reported (a JSON-lines file, a dashboard URL); no project can set it
+    pub report: ReportSettings,
+    /// A timed assessment: its minutes, score variables, and submission folder; set by a project
+    pub assessment: AssessmentSettings,
+    /// Log filter in `RUST_LOG` syntax; `--log` or `RUST_LOG` win for one session
+    pub log_filter: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            ui_scale: 1.0,
+            locale: Locale::English,
+            cvd_safe_colors: false,
+            export_as_displayed: false,
+            export_turtle: false,
+            export_measurements: false,
+            show_outline: true,
+            show_welcome: false,
+            welcome_seen: false,
+            text_to_speech: false,
+            limits: ExecutionLimits::default(),
+            classic_basic_errors: false,
+            show_warnings: true,
+            strict_warnings: BTreeSet::new(),
+            confirm_clearscreen: false,
+            low_memory_canvas: false,
+            canvas_line_budget: DEFAULT_LINE_BUDGET,
+            angle_mode: AngleMode::default(),
+            turtle_shape: TurtleShape::default(),
+            tutorial_progress: BTreeMap::new(),
+            restricted_mode: false,
+            restricted_policy: SecurityPolicy::restricted(),
+            language_mode: None,
+            screen_mode: None,
+            tab_width: 4,
+            line_ending: None,
+            trim_trailing_whitespace: false,
+            return_to_editor: false,
+            keep_variables: false,
+            random_seed: None,
+            autorun_on_open: false,
+            disabled_statements: Vec::new(),
+            honor_source_breakpoints: false,
+            record_output_timing: false,
+            statements_per_slice: None,
+            keep_run_history: false,
+            allow_shell: false,
+            shell_allowlist: Vec::new(),
+            report: ReportSettings::default(),
+            assessment: AssessmentSettings::default(),
+            log_filter: "info".to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Smallest and largest UI scale offered by the Settings slider
+    pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.5;
+    /// Widest indent the Tab key can insert
+    pub const MAX_TAB_WIDTH: usize = 16;
+
+    /// Policy for the next run: the restricted one in Restricted mode, else everything
+    /// allowed, with shell commands only as `allow_shell` and `shell_allowlist` say
+    pub fn security_policy(&self) -> SecurityPolicy {
+        if self.restricted_mode {
+            SecurityPolicy { allow_shell: false, ..self.restricted_policy.clone() }
+        } else {
+            SecurityPolicy { allow_shell: self.allow_shell, shell_allowlist: self.shell_allowlist.clone(), ..SecurityPolicy::default() }
+        }
+    }
+
+    /// Location of the settings file, if a config directory can be determined
+    pub fn path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(base.join("time_warp").join("settings.toml"))
+    }
+
+    /// Load settings from disk, returning defaults when absent or invalid
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| Self::from_toml(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist settings to the config directory
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| anyhow::anyhow!("No config directory available"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut settings: Settings = toml::from_str(text)?;
+        settings.ui_scale = settings
+            .ui_scale
+            .clamp(*Self::UI_SCALE_RANGE.start(), *Self::UI_SCALE_RANGE.end());
+        settings.tab_width = settings.tab_width.clamp(1, Self::MAX_TAB_WIDTH);
+        Ok(settings)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+}
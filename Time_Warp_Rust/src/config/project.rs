@@ -0,0 +1,334 @@
+//! Per-project defaults from `.timewarp.toml`
+//!
+//! A project folder (the folder of the open program) can carry a
+//! `.timewarp.toml` whose values replace the user's own settings while that
+//! folder is open, e.g.
+//!
+//! ```toml
+//! language_mode = "basic"
+//! restricted_mode = true
+//! screen_mode = { mode = "text", cols = 80, rows = 25 }
+//!
+//! [limits]
+//! max_output_lines = 500
+//! ```
+//!
+//! BASIC `SHELL` only runs when the user's own settings file sets both
+//! `allow_shell = true` and `shell_allowlist = ["echo", "python"]`; a project
+//! can only shorten that list, since a folder from anywhere could otherwise
+//! run any program it names. Where runs are reported (see
+//! [`run_reporters`](crate::utils::run_reporters)) is also the user's own
+//! setting, so a folder cannot send every transcript to an address it picks.
+//! `disabled_statements = ["GOTO"]` turns statements off for a lesson (see
+//! [`lesson`](time_warp_core::interpreter::lesson)), and an `[assessment]`
+//! table makes it a timed one (see [`assessment`](crate::utils::assessment)).
+//!
+//! Precedence, lowest first: built-in defaults, the global settings file,
+//! the project file, and `--restricted` on the command line. A project can
+//! turn Restricted mode on and tighten its policy, never turn it off or
+//! loosen it: each permission needs both files to grant it, and each limit
+//! is the lower of the two. `limits` are merged field by field; every other
+//! key replaces the global value outright.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::Settings;
+
+/// Name of the project settings file in a project folder
+pub const PROJECT_FILE: &str = ".timewarp.toml";
+
+/// Settings a project file may set
+pub const PROJECT_KEYS: &[&str] = &[
+    "language_mode",
+    "screen_mode",
+    "limits",
+    "theme",
+    "restricted_mode",
+    "restricted_policy",
+    "tab_width",
+    "random_seed",
+    "autorun_on_open",
+    "disabled_statements",
+    "honor_source_breakpoints",
+    "record_output_timing",
+    "shell_allowlist",
+    "assessment",
+];
+
+/// Settings only the user's own file may set; a project file's are dropped
+const GLOBAL_ONLY_KEYS: &[&str] = &["allow_shell", "report"];
+
+/// Keys whose tables are merged into the global value instead of replacing it
+const MERGED_TABLES: &[&str] = &["limits", "restricted_policy"];
+
+/// The overrides read from one project file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSettings {
+    values: toml::Table,
+}
+
+impl ProjectSettings {
+    /// Parse a project file, rejecting unknown keys and values the settings cannot hold
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut values: toml::Table = toml::from_str(text)?;
+        values.retain(|key, _| {
+            let global_only = GLOBAL_ONLY_KEYS.contains(&key);
+            if global_only {
+                tracing::warn!("ignoring `{}` in a project file; only your own settings can set it", key);
+            }
+            !global_only
+        });
+        if let Some(key) = values.keys().find(|k| !PROJECT_KEYS.contains(&k.as_str())) {
+            anyhow::bail!("unknown setting `{}` (a project can set {})", key, PROJECT_KEYS.join(", "));
+        }
+        let project = Self { values };
+        project.apply(&Settings::default())?;
+        Ok(project)
+    }
+
+    /// The overridable values of `settings`, for Settings → Save as Project Defaults
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        let toml::Value::Table(mut all) = toml::Value::try_from(settings)? else {
+            anyhow::bail!("settings did not serialize to a table");
+        };
+        all.retain(|key, _| PROJECT_KEYS.contains(&key));
+        Ok(Self { values: all })
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(&self.values)?)
+    }
+
+    /// Read `<root>/.timewarp.toml`; `Ok(None)` when the folder has none
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(PROJECT_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(&path)?;
+        Self::from_toml(&text).with_context(|| format!("{}", path.display())).map(Some)
+    }
+
+    /// Whether the project sets `key` (one of [`PROJECT_KEYS`])
+    pub fn overrides(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// `global` with this project's values laid over it
+    pub fn apply(&self, global: &Settings) -> Result<Settings> {
+        let toml::Value::Table(mut merged) = toml::Value::try_from(global)? else {
+            anyhow::bail!("settings did not serialize to a table");
+        };
+        for (key, value) in &self.values {
+            match (merged.get_mut(key), value) {
+                (Some(toml::Value::Table(base)), toml::Value::Table(fields)) if MERGED_TABLES.contains(&key.as_str()) => {
+                    base.extend(fields.clone());
+                }
+                _ => {
+                    merged.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        let mut settings: Settings = toml::Value::Table(merged).try_into()?;
+        settings.tab_width = settings.tab_width.clamp(1, Settings::MAX_TAB_WIDTH);
+        settings.shell_allowlist.retain(|name| global.shell_allowlist.contains(name));
+        settings.restricted_mode |= global.restricted_mode;
+        settings.restricted_policy = global.restricted_policy.narrowed_by(&settings.restricted_policy);
+        Ok(settings)
+    }
+}
+
+/// A project folder and the overrides from its `.timewarp.toml`
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    pub settings: ProjectSettings,
+}
+
+impl Project {
+    pub fn path(&self) -> PathBuf {
+        self.root.join(PROJECT_FILE)
+    }
+}
+
+/// [`effective_settings`] remembered with the inputs it came from, so asking
+/// every frame costs a comparison rather than a TOML round trip
+#[derive(Debug, Default)]
+pub struct EffectiveSettingsCache {
+    last: std::cell::RefCell<Option<CachedSettings>>,
+}
+
+#[derive(Debug)]
+struct CachedSettings {
+    global: Settings,
+    project: Option<ProjectSettings>,
+    locked: bool,
+    effective: Settings,
+}
+
+impl EffectiveSettingsCache {
+    /// [`effective_settings`] for these inputs, computed again only when one of them changed
+    pub fn get(&self, global: &Settings, project: Option<&ProjectSettings>, locked: bool) -> Settings {
+        let mut last = self.last.borrow_mut();
+        match &*last {
+            Some(cached) if cached.locked == locked && cached.project.as_ref() == project && cached.global == *global => {
+                cached.effective.clone()
+            }
+            _ => {
+                let effective = effective_settings(global, project, locked);
+                *last = Some(CachedSettings { global: global.clone(), project: project.cloned(), locked, effective: effective.clone() });
+                effective
+            }
+        }
+    }
+}
+
+/// Settings in effect: `global`, then `project`, then the `--restricted` lock
+pub fn effective_settings(global: &Settings, project: Option<&ProjectSettings>, locked: bool) -> Settings {
+    let mut settings = project.and_then(|p| p.apply(global).ok()).unwrap_or_else(|| global.clone());
+    if locked {
+        settings.restricted_mode = true;
+        settings.restricted_policy = global.restricted_policy.clone();
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::{ScreenMode, SecurityPolicy};
+    use crate::languages::Language;
+    use crate::ui::themes::Theme;
+
+    fn global() -> Settings {
+        let mut settings = Settings { theme: Theme::GreenPhosphor, tab_width: 2, ..Settings::default() };
+        settings.limits.max_output_lines = 9000;
+        settings.limits.max_repeat_count = 123;
+        settings
+    }
+
+    #[test]
+    fn test_project_values_override_global_ones() {
+        let project = ProjectSettings::from_toml(
+            "language_mode = \"basic\"\nrestricted_mode = true\ntheme = \"AmberPhosphor\"\n\
+             screen_mode = { mode = \"text\", cols = 80, rows = 25 }\n",
+        )
+        .unwrap();
+        let settings = effective_settings(&global(), Some(&project), false);
+        assert_eq!(settings.language_mode, Some(Language::Basic));
+        assert_eq!(settings.screen_mode, Some(ScreenMode::Text { cols: 80, rows: 25 }));
+        assert_eq!(settings.theme, Theme::AmberPhosphor);
+        assert!(settings.restricted_mode);
+        // Keys the project leaves out keep the global values
+        assert_eq!(settings.tab_width, 2);
+        assert_eq!(settings.limits, global().limits);
+        assert!(project.overrides("theme") && !project.overrides("limits"));
+    }
+
+    #[test]
+    fn test_limits_and_policy_merge_field_by_field() {
+        let project = ProjectSettings::from_toml(
+            "[limits]\nmax_output_lines = 500\n[restricted_policy]\ntime_limit_secs = 5\n",
+        )
+        .unwrap();
+        let settings = effective_settings(&global(), Some(&project), false);
+        assert_eq!(settings.limits.max_output_lines, 500);
+        assert_eq!(settings.limits.max_repeat_count, 123, "unset limits stay global");
+        assert_eq!(settings.restricted_policy, SecurityPolicy { time_limit_secs: Some(5), ..global().restricted_policy });
+    }
+
+    #[test]
+    fn test_a_project_only_tightens_the_classroom_policy() {
+        let mut own = global();
+        own.restricted_mode = true;
+        own.restricted_policy = SecurityPolicy { allow_network: true, time_limit_secs: Some(20), ..SecurityPolicy::restricted() };
+        let project = ProjectSettings::from_toml(
+            "restricted_mode = false\n[restricted_policy]\nallow_file_io = true\nallow_network = false\ntime_limit_secs = 600\nmax_iterations = 1000\n",
+        )
+        .unwrap();
+        let settings = effective_settings(&own, Some(&project), false);
+        assert!(settings.restricted_mode, "a project cannot leave Restricted mode");
+        let policy = settings.security_policy();
+        assert!(!policy.allow_file_io && !policy.allow_network, "{:?}", policy);
+        assert_eq!((policy.time_limit_secs, policy.max_iterations), (Some(20), Some(1000)));
+
+        let tighter = ProjectSettings::from_toml("restricted_mode = true").unwrap();
+        assert!(effective_settings(&global(), Some(&tighter), false).restricted_mode);
+    }
+
+    #[test]
+    fn test_effective_settings_are_cached_until_an_input_changes() {
+        let cache = EffectiveSettingsCache::default();
+        let project = ProjectSettings::from_toml("tab_width = 8").unwrap();
+        let mut own = global();
+        assert_eq!(cache.get(&own, Some(&project), false).tab_width, 8);
+        assert_eq!(cache.get(&own, None, false).tab_width, 2);
+        own.theme = Theme::AmberPhosphor;
+        assert_eq!(cache.get(&own, None, false).theme, Theme::AmberPhosphor);
+        assert!(cache.get(&own, None, true).restricted_mode);
+    }
+
+    #[test]
+    fn test_command_line_lock_beats_the_project() {
+        let project = ProjectSettings::from_toml(
+            "restricted_mode = false\n[restricted_policy]\nallow_file_io = true\nallow_network = true\n",
+        )
+        .unwrap();
+        let settings = effective_settings(&global(), Some(&project), true);
+        assert!(settings.restricted_mode);
+        assert_eq!(settings.restricted_policy, global().restricted_policy);
+        assert_eq!(effective_settings(&global(), None, false), global());
+    }
+
+    #[test]
+    fn test_a_project_cannot_turn_on_shell_or_widen_its_list() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(PROJECT_FILE), "allow_shell = true\nshell_allowlist = [\"sh\", \"echo\"]\n").unwrap();
+        let project = ProjectSettings::load(dir.path()).unwrap().unwrap();
+        assert!(!project.overrides("allow_shell"));
+        let policy = effective_settings(&global(), Some(&project), false).security_policy();
+        assert!(policy.require_shell("sh", "SHELL").is_err());
+
+        let own = Settings { allow_shell: true, shell_allowlist: vec!["echo".into(), "python".into()], ..global() };
+        let settings = effective_settings(&own, Some(&project), false);
+        assert_eq!(settings.shell_allowlist, ["echo"], "a project only shortens the list");
+        assert!(settings.security_policy().require_shell("sh", "SHELL").is_err());
+        assert!(settings.security_policy().require_shell("echo", "SHELL").is_ok());
+    }
+
+    #[test]
+    fn test_reports_go_only_where_the_user_says() {
+        let project = ProjectSettings::from_toml("[report]\nurl = \"https://example.invalid/runs\"\ntoken = \"x\"\n").unwrap();
+        assert!(!project.overrides("report"));
+        let mut own = global();
+        own.report.file = Some("runs.jsonl".into());
+        assert_eq!(effective_settings(&own, Some(&project), false).report, own.report);
+    }
+
+    #[test]
+    fn test_bad_project_files_are_rejected() {
+        let unknown = ProjectSettings::from_toml("ui_scale = 2.0").unwrap_err();
+        assert!(unknown.to_string().contains("unknown setting `ui_scale`"), "{}", unknown);
+        assert!(ProjectSettings::from_toml("theme = \"Plaid\"").is_err());
+        assert!(ProjectSettings::from_toml("language_mode = \"cobol\"").is_err());
+        assert!(ProjectSettings::from_toml("screen_mode = { mode = \"text\" }").is_err());
+        assert_eq!(ProjectSettings::from_toml("tab_width = 99").unwrap().apply(&global()).unwrap().tab_width, Settings::MAX_TAB_WIDTH);
+    }
+
+    #[test]
+    fn test_saved_project_defaults_round_trip() {
+        let mut settings = global();
+        settings.language_mode = Some(Language::Logo);
+        settings.screen_mode = Some(ScreenMode::Graphics { width: 1600, height: 1200 });
+        let project = ProjectSettings::from_settings(&settings).unwrap();
+        assert!(!project.overrides("ui_scale") && !project.overrides("tutorial_progress"));
+        let reloaded = ProjectSettings::from_toml(&project.to_toml().unwrap()).unwrap();
+        let applied = effective_settings(&Settings::default(), Some(&reloaded), false);
+        assert_eq!(applied.language_mode, Some(Language::Logo));
+        assert_eq!(applied.screen_mode, settings.screen_mode);
+        assert_eq!(applied.limits, settings.limits);
+        assert_eq!((applied.theme, applied.tab_width), (Theme::GreenPhosphor, 2));
+        assert_eq!(applied.ui_scale, Settings::default().ui_scale);
+    }
+}
@@ -0,0 +1,167 @@
+//! What the IDE opens and runs when it starts (`--open`, `--autorun`, `--kiosk`)
+//!
+//! `time-warp --open lesson.bas notes.pilot --autorun lesson.bas` opens both
+//! programs in tabs and runs `lesson.bas` straight away. Without `--autorun`,
+//! the first program runs when its project's `.timewarp.toml` sets
+//! `autorun_on_open = true`. `--kiosk` is for classroom machines that should
+//! only show one lesson: the Explorer tab is hidden, and File ▸ Open and the
+//! workspace of every run are kept inside the folder of the first program
+//! (the working directory when nothing is opened).
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::Settings;
+
+const USAGE: &str = "Usage: --open <program>... [--autorun <program>] [--kiosk]";
+
+/// What the IDE shows when it opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirstView {
+    /// The Welcome tab, with its quick actions and demo
+    Welcome,
+    /// The editor, with the programs opened (or an empty one)
+    Editor,
+}
+
+/// The startup part of the command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Startup {
+    /// Programs to open in tabs, in order
+    pub open: Vec<PathBuf>,
+    /// Program to run once everything is open; always one of `open`
+    pub autorun: Option<PathBuf>,
+    pub kiosk: bool,
+}
+
+impl Startup {
+    /// Take `--open`, `--autorun`, and `--kiosk` out of `args`, leaving the rest
+    ///
+    /// `--open` takes every following argument up to the next `--` option. A
+    /// program named by `--autorun` but not by `--open` is opened last.
+    pub fn from_args(args: &mut Vec<String>) -> Result<Self> {
+        let mut startup = Self { kiosk: args.iter().any(|a| a == "--kiosk"), ..Self::default() };
+        args.retain(|a| a != "--kiosk");
+        if let Some(i) = args.iter().position(|a| a == "--open") {
+            let count = args[i + 1..].iter().take_while(|a| !a.starts_with("--")).count();
+            if count == 0 {
+                anyhow::bail!(USAGE);
+            }
+            startup.open = args.drain(i..=i + count).skip(1).map(PathBuf::from).collect();
+        }
+        if let Some(i) = args.iter().position(|a| a == "--autorun") {
+            let program = args.get(i + 1).filter(|a| !a.starts_with("--")).map(PathBuf::from).ok_or_else(|| anyhow::anyhow!(USAGE))?;
+            args.drain(i..=i + 1);
+            if !startup.open.contains(&program) {
+                startup.open.push(program.clone());
+            }
+            startup.autorun = Some(program);
+        }
+        Ok(startup)
+    }
+
+    /// The program to run once open: `--autorun`'s, else the first one if
+    /// `autorun_on_open` holds for it
+    pub fn program_to_run(&self, autorun_on_open: impl Fn(&Path) -> bool) -> Option<&Path> {
+        match &self.autorun {
+            Some(program) => Some(program),
+            None => self.open.first().map(PathBuf::as_path).filter(|first| autorun_on_open(first)),
+        }
+    }
+
+    /// What to show first, given the saved settings
+    ///
+    /// Programs to open, or kiosk mode, go straight to the editor: someone
+    /// picked what to work on. Otherwise the first launch shows the Welcome
+    /// tab, and later ones show it when Show Welcome at startup is on.
+    pub fn first_view(&self, settings: &Settings) -> FirstView {
+        if self.kiosk || !self.open.is_empty() {
+            FirstView::Editor
+        } else if !settings.welcome_seen || settings.show_welcome {
+            FirstView::Welcome
+        } else {
+            FirstView::Editor
+        }
+    }
+
+    /// Folder kiosk mode is kept inside, with relative paths taken from `cwd`;
+    /// `None` without `--kiosk`
+    pub fn kiosk_root(&self, cwd: &Path) -> Option<PathBuf> {
+        if !self.kiosk {
+            return None;
+        }
+        let folder = self.open.first().and_then(|first| cwd.join(first).parent().map(Path::to_path_buf));
+        Some(folder.unwrap_or_else(|| cwd.to_path_buf()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_open_and_autorun_are_taken_from_the_arguments() {
+        let mut rest = args("--restricted --open lesson.bas notes.pilot --autorun lesson.bas --log debug");
+        let startup = Startup::from_args(&mut rest).unwrap();
+        assert_eq!(startup.open, [PathBuf::from("lesson.bas"), PathBuf::from("notes.pilot")]);
+        assert_eq!(startup.program_to_run(|_| false), Some(Path::new("lesson.bas")));
+        assert!(!startup.kiosk);
+        assert_eq!(rest, args("--restricted --log debug"));
+
+        // An --autorun program that was not opened is opened last
+        let startup = Startup::from_args(&mut args("--open notes.pilot --autorun quiz.pilot")).unwrap();
+        assert_eq!(startup.open, [PathBuf::from("notes.pilot"), PathBuf::from("quiz.pilot")]);
+        assert_eq!(Startup::from_args(&mut args("--run x.bas")).unwrap(), Startup::default());
+    }
+
+    #[test]
+    fn test_autorun_on_open_runs_only_the_first_program() {
+        let startup = Startup::from_args(&mut args("--open lessons/one.bas two.bas")).unwrap();
+        assert_eq!(startup.program_to_run(|_| false), None);
+        assert_eq!(startup.program_to_run(|p| p.starts_with("lessons")), Some(Path::new("lessons/one.bas")));
+        assert_eq!(startup.program_to_run(|p| p == Path::new("two.bas")), None);
+        assert_eq!(Startup::default().program_to_run(|_| true), None);
+    }
+
+    #[test]
+    fn test_kiosk_root_is_the_first_programs_folder() {
+        let cwd = Path::new("/home/class");
+        let startup = Startup::from_args(&mut args("--kiosk --open lessons/one.bas /srv/two.bas")).unwrap();
+        assert_eq!(startup.kiosk_root(cwd), Some(PathBuf::from("/home/class/lessons")));
+        let absolute = Startup::from_args(&mut args("--open /srv/two.bas --kiosk")).unwrap();
+        assert_eq!(absolute.kiosk_root(cwd), Some(PathBuf::from("/srv")));
+        assert_eq!(Startup::from_args(&mut args("--kiosk")).unwrap().kiosk_root(cwd), Some(cwd.to_path_buf()));
+        assert_eq!(Startup::from_args(&mut args("--open a.bas")).unwrap().kiosk_root(cwd), None);
+    }
+
+    #[test]
+    fn test_missing_programs_are_usage_errors() {
+        assert!(Startup::from_args(&mut args("--open")).is_err());
+        assert!(Startup::from_args(&mut args("--open --autorun a.bas")).is_err());
+        assert!(Startup::from_args(&mut args("--autorun")).is_err());
+        assert!(Startup::from_args(&mut args("--open a.bas --autorun --kiosk")).is_err());
+    }
+
+    #[test]
+    fn test_first_view_welcomes_a_first_launch_only() {
+        let first_launch = Settings::default();
+        let returning = Settings { welcome_seen: true, ..Settings::default() };
+        let asked_for = Settings { show_welcome: true, ..returning.clone() };
+        let nothing = Startup::default();
+        assert_eq!(nothing.first_view(&first_launch), FirstView::Welcome);
+        assert_eq!(nothing.first_view(&returning), FirstView::Editor);
+        assert_eq!(nothing.first_view(&asked_for), FirstView::Welcome);
+
+        // Programs opened on the command line, or a kiosk, skip it even on a first launch
+        let opened = Startup::from_args(&mut args("--open lesson.bas")).unwrap();
+        let kiosk = Startup { kiosk: true, ..Startup::default() };
+        for startup in [opened, kiosk] {
+            assert_eq!(startup.first_view(&first_launch), FirstView::Editor);
+            assert_eq!(startup.first_view(&asked_for), FirstView::Editor);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Starting text for new files, one template per language
+//!
+//! File ▸ New fills a new tab from the template for its language: a numbered
+//! BASIC skeleton ending in `999 END`, a Logo `TO MAIN` procedure, a PILOT
+//! or TempleCode header comment. A template is looked for as `new.<ext>`
+//! (`new.bas`, `new.logo`, …) first in the project's `.timewarp/templates/`
+//! folder, then in `templates/` beside the settings file, and the built-in
+//! one is used when neither has it. `{name}` in a template becomes the new
+//! file's name and `{date}` today's date (`2024-09-01`).
+
+use std::path::{Path, PathBuf};
+
+use super::Settings;
+use crate::languages::Language;
+
+/// Folder of a project's templates, inside the project folder
+pub const PROJECT_TEMPLATES: &str = ".timewarp/templates";
+
+/// The built-in template for `language`
+pub fn embedded(language: Language) -> &'static str {
+    match language {
+        Language::Basic => "10 REM {name} - {date}\n20 PRINT \"Hello!\"\n999 END\n",
+        Language::Logo => "; {name} - {date}\nTO MAIN\n  FORWARD 50\nEND\n\nMAIN\n",
+        Language::Pilot => "R:{name} - {date}\nT:Hello!\nE:\n",
+        Language::TempleCode => "REM {name} - {date}\n",
+    }
+}
+
+/// `templates/` beside the settings file, if a config directory can be determined
+pub fn user_dir() -> Option<PathBuf> {
+    Settings::path().and_then(|path| path.parent().map(|dir| dir.join("templates")))
+}
+
+/// The template for `language`: the project's, else the user's, else the built-in one
+///
+/// A template file that cannot be read counts as missing.
+pub fn load(language: Language, project_root: Option<&Path>, user_dir: Option<&Path>) -> String {
+    let file = format!("new.{}", language.extension());
+    let project = project_root.map(|root| root.join(PROJECT_TEMPLATES));
+    project
+        .iter()
+        .map(PathBuf::as_path)
+        .chain(user_dir)
+        .find_map(|dir| std::fs::read_to_string(dir.join(&file)).ok())
+        .unwrap_or_else(|| embedded(language).to_string())
+}
+
+/// `template` with `{name}` and `{date}` filled in
+pub fn fill(template: &str, name: &str, date: &str) -> String {
+    template.replace("{name}", name).replace("{date}", date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_fall_back_from_project_to_user_to_built_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let (project, user) = (dir.path().join("project"), dir.path().join("user"));
+        std::fs::create_dir_all(project.join(PROJECT_TEMPLATES)).unwrap();
+        std::fs::create_dir_all(&user).unwrap();
+        std::fs::write(project.join(PROJECT_TEMPLATES).join("new.bas"), "10 REM project\n").unwrap();
+        std::fs::write(user.join("new.bas"), "10 REM user\n").unwrap();
+        std::fs::write(user.join("new.logo"), "; user\n").unwrap();
+
+        assert_eq!(load(Language::Basic, Some(&project), Some(&user)), "10 REM project\n");
+        assert_eq!(load(Language::Logo, Some(&project), Some(&user)), "; user\n");
+        assert_eq!(load(Language::Pilot, Some(&project), Some(&user)), embedded(Language::Pilot));
+        assert_eq!(load(Language::Basic, None, None), embedded(Language::Basic));
+
+        let basic = fill(embedded(Language::Basic), "untitled_1.bas", "2024-09-01");
+        assert!(basic.starts_with("10 REM untitled_1.bas - 2024-09-01\n") && basic.ends_with("999 END\n"), "{}", basic);
+        assert!(embedded(Language::Logo).contains("TO MAIN\n"));
+    }
+}
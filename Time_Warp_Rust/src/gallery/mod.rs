@@ -0,0 +1,317 @@
+//! Example gallery: the sample programs in `examples/`, with previews
+//!
+//! The examples ship embedded in the binary. Each is named and described by
+//! its own `@title`/`@description` header, falling back to its file name and
+//! its line in `examples/README.md` when it has none. An example that draws is previewed by its
+//! drawing: the first time the gallery opens, each example is run headlessly
+//! under tight limits and the canvas is rendered as a thumbnail, cached as a
+//! PNG in the data directory under a hash of the example's source. An edited
+//! example therefore hashes to a new name and is rendered again, and cache
+//! files no example uses any more are removed. An example that draws nothing
+//! is remembered by an empty marker file and previewed by its first lines.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use image::RgbaImage;
+
+use crate::graphics::TurtleState;
+use crate::interpreter::metadata::parse_metadata;
+use crate::interpreter::{Interpreter, SecurityPolicy};
+use crate::languages::Language;
+
+/// Size of a thumbnail, in pixels
+pub const THUMBNAIL_WIDTH: u32 = 128;
+pub const THUMBNAIL_HEIGHT: u32 = 96;
+
+/// Changed when thumbnails are drawn differently, so cached ones are replaced
+const RENDER_VERSION: u32 = 1;
+
+/// Source lines shown for an example that draws nothing
+const SNIPPET_LINES: usize = 6;
+
+/// Statements and seconds a preview run may take
+const PREVIEW_ITERATIONS: usize = 200_000;
+const PREVIEW_SECONDS: u64 = 2;
+
+/// Embedded examples, in gallery order
+const BUILTIN_SOURCES: &[(&str, &str)] = &[
+    ("logo_square.logo", include_str!("../../../examples/logo_square.logo")),
+    ("logo_star.logo", include_str!("../../../examples/logo_star.logo")),
+    ("logo_polygons.logo", include_str!("../../../examples/logo_polygons.logo")),
+    ("logo_house.logo", include_str!("../../../examples/logo_house.logo")),
+    ("logo_snowman.logo", include_str!("../../../examples/logo_snowman.logo")),
+    ("logo_flower.logo", include_str!("../../../examples/logo_flower.logo")),
+    ("logo_starburst.logo", include_str!("../../../examples/logo_starburst.logo")),
+    ("logo_spiral_walk.logo", include_str!("../../../examples/logo_spiral_walk.logo")),
+    ("logo_rainbow_spiral.logo", include_str!("../../../examples/logo_rainbow_spiral.logo")),
+    ("logo_petal_rosette.logo", include_str!("../../../examples/logo_petal_rosette.logo")),
+    ("logo_polygonal_rose.logo", include_str!("../../../examples/logo_polygonal_rose.logo")),
+    ("logo_spirograph.logo", include_str!("../../../examples/logo_spirograph.logo")),
+    ("logo_recursive_spiral.logo", include_str!("../../../examples/logo_recursive_spiral.logo")),
+    ("logo_fractal_tree.logo", include_str!("../../../examples/logo_fractal_tree.logo")),
+    ("logo_koch_snowflake.logo", include_str!("../../../examples/logo_koch_snowflake.logo")),
+    ("basic_graphics.bas", include_str!("../../../examples/basic_graphics.bas")),
+    ("basic_screen_modes.bas", include_str!("../../../examples/basic_screen_modes.bas")),
+    ("basic_countdown.bas", include_str!("../../../examples/basic_countdown.bas")),
+    ("basic_multiplication_table.bas", include_str!("../../../examples/basic_multiplication_table.bas")),
+    ("basic_guess.bas", include_str!("../../../examples/basic_guess.bas")),
+    ("basic_hangman.bas", include_str!("../../../examples/basic_hangman.bas")),
+    ("basic_rock_paper_scissors.bas", include_str!("../../../examples/basic_rock_paper_scissors.bas")),
+    ("basic_cls_locate.bas", include_str!("../../../examples/basic_cls_locate.bas")),
+    ("basic_inkey_demo.bas", include_str!("../../../examples/basic_inkey_demo.bas")),
+    ("basic_arrow_keys.bas", include_str!("../../../examples/basic_arrow_keys.bas")),
+    ("pilot_quiz.pilot", include_str!("../../../examples/pilot_quiz.pilot")),
+    ("pilot_simple_calculator.pilot", include_str!("../../../examples/pilot_simple_calculator.pilot")),
+    ("pilot_story_builder.pilot", include_str!("../../../examples/pilot_story_builder.pilot")),
+    ("pilot_quiz_competition.pilot", include_str!("../../../examples/pilot_quiz_competition.pilot")),
+    ("pilot_data_quiz.pilot", include_str!("../../../examples/pilot_data_quiz.pilot")),
+    ("pilot_adventure.pilot", include_str!("../../../examples/pilot_adventure.pilot")),
+    ("pilot_dragon_adventure.pilot", include_str!("../../../examples/pilot_dragon_adventure.pilot")),
+    ("pilot_screen_demo.pilot", include_str!("../../../examples/pilot_screen_demo.pilot")),
+];
+
+/// `examples/README.md`, which has a `` - `file` — description `` line per example (used when it has no header)
+const README: &str = include_str!("../../../examples/README.md");
+
+/// One sample program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    /// File name, e.g. `logo_flower.logo`
+    pub file: String,
+    /// From `@title`, else the file name: `logo_flower.logo` is "Flower"
+    pub title: String,
+    pub language: Language,
+    /// From `@description`, else `readme`; empty when there is neither
+    pub description: String,
+    pub source: String,
+}
+
+impl Example {
+    /// `readme` describes the example only when its header does not
+    pub fn new(file: &str, source: &str, readme: &str) -> Self {
+        let metadata = parse_metadata(source);
+        let (stem, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+        let title = metadata.title.unwrap_or_else(|| {
+            let name = stem.split_once('_').map_or(stem, |(_, name)| name);
+            let mut title = name.replace('_', " ");
+            if let Some(first) = title.get(..1) {
+                title.replace_range(..1, &first.to_uppercase());
+            }
+            title
+        });
+        Self {
+            file: file.to_string(),
+            title,
+            language: Language::from_extension(extension),
+            description: metadata.description.unwrap_or_else(|| readme.to_string()),
+            source: source.to_string(),
+        }
+    }
+
+    /// Name of the example's cache files: a hash of its source and the thumbnail size
+    pub fn cache_key(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        (self.source.as_str(), THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT, RENDER_VERSION).hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// The first lines of the source, for an example that draws nothing
+    pub fn snippet(&self) -> String {
+        let lines: Vec<&str> = self.source.lines().filter(|l| !l.trim().is_empty()).take(SNIPPET_LINES).collect();
+        lines.join("\n")
+    }
+
+    /// Run the example headlessly and render what it drew, `None` when it drew nothing
+    ///
+    /// No answers are given, so an example stops at its first question; file,
+    /// network, plugin, and shell access are refused.
+    pub fn render_thumbnail(&self) -> Option<RgbaImage> {
+        let mut interp = Interpreter::new();
+        let policy = SecurityPolicy {
+            max_iterations: Some(PREVIEW_ITERATIONS),
+            time_limit_secs: Some(PREVIEW_SECONDS),
+            ..SecurityPolicy::restricted()
+        };
+        interp.set_policy(policy).ok()?;
+        interp.load_program(&self.source).ok()?;
+        let mut turtle = TurtleState::new();
+        // A run cut short by a limit still previews what it drew
+        let _ = interp.execute(&mut turtle);
+        (!turtle.lines.is_empty()).then(|| turtle.render_thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT))
+    }
+}
+
+/// Every embedded example, described by its header or the README
+pub fn builtin_examples() -> Vec<Example> {
+    BUILTIN_SOURCES
+        .iter()
+        .map(|(file, source)| Example::new(file, source, readme_description(file).unwrap_or_default()))
+        .collect()
+}
+
+/// The README's description of `file`
+fn readme_description(file: &str) -> Option<&'static str> {
+    let entry = format!("- `{}`", file);
+    README.lines().find_map(|line| {
+        let rest = line.strip_prefix(&entry)?;
+        Some(rest.trim_start().trim_start_matches(['—', '-']).trim())
+    })
+}
+
+/// How an example is previewed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Preview {
+    Thumbnail(RgbaImage),
+    /// It drew nothing: show `Example::snippet`
+    Snippet,
+}
+
+/// Thumbnails kept between sessions, one file per example named by `Example::cache_key`
+pub struct ThumbnailCache {
+    dir: PathBuf,
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache in the data directory, if there is one
+    pub fn in_data_dir() -> Option<Self> {
+        crate::config::data_dir().map(|dir| Self::new(dir.join("thumbnails")))
+    }
+
+    fn thumbnail_path(&self, example: &Example) -> PathBuf {
+        self.dir.join(format!("{}.png", example.cache_key()))
+    }
+
+    /// Marks an example that drew nothing when it was last run
+    fn snippet_path(&self, example: &Example) -> PathBuf {
+        self.dir.join(format!("{}.none", example.cache_key()))
+    }
+
+    /// The cached preview of `example` as its source is now; `None` when it must be rendered
+    pub fn get(&self, example: &Example) -> Option<Preview> {
+        if self.snippet_path(example).exists() {
+            return Some(Preview::Snippet);
+        }
+        let image = image::open(self.thumbnail_path(example)).ok()?;
+        Some(Preview::Thumbnail(image.to_rgba8()))
+    }
+
+    pub fn put(&self, example: &Example, preview: &Preview) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        match preview {
+            Preview::Thumbnail(image) => image.save(self.thumbnail_path(example))?,
+            Preview::Snippet => std::fs::write(self.snippet_path(example), "")?,
+        }
+        Ok(())
+    }
+
+    /// The cached preview, or a freshly rendered one that is then cached
+    pub fn get_or_render(&self, example: &Example) -> Preview {
+        if let Some(preview) = self.get(example) {
+            return preview;
+        }
+        let preview = example.render_thumbnail().map_or(Preview::Snippet, Preview::Thumbnail);
+        if let Err(e) = self.put(example, &preview) {
+            tracing::warn!(file = %example.file, error = %e, "could not cache thumbnail");
+        }
+        preview
+    }
+
+    /// Delete cache files that belong to none of `examples` (older versions of them)
+    pub fn prune(&self, examples: &[Example]) -> Result<usize> {
+        let keys: Vec<String> = examples.iter().map(Example::cache_key).collect();
+        let mut removed = 0;
+        let Ok(entries) = std::fs::read_dir(&self.dir) else { return Ok(0) };
+        for entry in entries {
+            let path = entry?.path();
+            if !is_cache_file(&path) {
+                continue;
+            }
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            if !keys.iter().any(|key| key == stem) {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn is_cache_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("png" | "none"))
+}
+
+/// Previews of every example, from the cache where it has them, in `examples` order
+///
+/// Meant for a background thread: rendering runs every uncached example.
+pub fn load_previews(examples: &[Example], cache: Option<&ThumbnailCache>) -> Vec<Preview> {
+    if let Some(cache) = cache {
+        if let Err(e) = cache.prune(examples) {
+            tracing::warn!(error = %e, "could not prune thumbnail cache");
+        }
+    }
+    examples
+        .iter()
+        .map(|example| match cache {
+            Some(cache) => cache.get_or_render(example),
+            None => example.render_thumbnail().map_or(Preview::Snippet, Preview::Thumbnail),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_are_described_and_previewed() {
+        let examples = builtin_examples();
+        let square = examples.iter().find(|e| e.file == "logo_square.logo").unwrap();
+        assert_eq!((square.title.as_str(), square.language), ("Square", Language::Logo));
+        assert!(!square.description.is_empty());
+        let guess = examples.iter().find(|e| e.file == "basic_guess.bas").unwrap();
+        assert_eq!(guess.description, "Guess-the-number text game using INPUT and IF/THEN");
+        // The example's own header wins over its file name and README line
+        let quiz = examples.iter().find(|e| e.file == "pilot_data_quiz.pilot").unwrap();
+        assert_eq!(quiz.title, "Capital Cities Quiz");
+        assert_eq!(quiz.description, "D: lines hold the questions; QZ: asks them and keeps score");
+        let partial = Example::new("logo_box.logo", "; @description Four sides\nFD 10", "From the README");
+        assert_eq!((partial.title.as_str(), partial.description.as_str()), ("Box", "Four sides"));
+
+        let thumbnail = square.render_thumbnail().expect("the square draws");
+        assert_eq!(thumbnail.dimensions(), (THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT));
+        assert_eq!(guess.render_thumbnail(), None);
+        assert!(guess.snippet().lines().count() <= SNIPPET_LINES);
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ThumbnailCache::new(dir.path());
+        let mut example = Example::new("logo_box.logo", "REPEAT 4 [FD 50 RT 90]", "");
+        let text = Example::new("pilot_hi.pilot", "T:Hi", "");
+
+        assert_eq!(cache.get(&example), None);
+        let first = cache.get_or_render(&example);
+        assert!(matches!(&first, Preview::Thumbnail(img) if img.width() == THUMBNAIL_WIDTH));
+        assert_eq!(cache.get(&example), Some(first.clone()), "served from the cache");
+        assert_eq!(cache.get_or_render(&text), Preview::Snippet);
+        assert_eq!(cache.get(&text), Some(Preview::Snippet), "drawing nothing is remembered too");
+
+        // An edited example misses the cache, and the stale file is pruned
+        let stale = example.clone();
+        example.source = "REPEAT 3 [FD 50 RT 120]".into();
+        assert_ne!(example.cache_key(), stale.cache_key());
+        assert_eq!(cache.get(&example), None);
+        assert_ne!(cache.get_or_render(&example), first);
+        assert_eq!(cache.prune(&[example.clone(), text.clone()]).unwrap(), 1);
+        assert_eq!(cache.get(&stale), None);
+        assert!(cache.get(&example).is_some() && cache.get(&text).is_some());
+    }
+}
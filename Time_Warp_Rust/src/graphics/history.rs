@@ -0,0 +1,169 @@
+//! Canvas snapshots (View → Restore Previous Canvas)
+//!
+//! The drawing is captured before each run and before Clear Graphics so a
+//! stray `CS` never costs a student their picture. Snapshots hold the line and
+//! text lists in `Arc` slices, and only the newest few are kept.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use super::{Backdrop, Rgba, TurtleLine, TurtleState, TurtleText};
+
+/// Snapshots kept before the oldest is dropped
+pub const DEFAULT_CANVAS_HISTORY: usize = 10;
+
+/// The drawn primitives of a canvas at one moment
+#[derive(Debug, Clone)]
+pub struct CanvasSnapshot {
+    pub lines: Arc<[TurtleLine]>,
+    pub texts: Arc<[TurtleText]>,
+    pub bg_color: Rgba,
+    /// Lines folded into an image by the low-memory canvas
+    pub backdrop: Option<Backdrop>,
+}
+
+impl CanvasSnapshot {
+    pub fn capture(turtle: &TurtleState) -> Self {
+        Self {
+            lines: turtle.lines.as_slice().into(),
+            texts: turtle.texts.as_slice().into(),
+            bg_color: turtle.bg_color,
+            backdrop: turtle.backdrop.clone(),
+        }
+    }
+
+    /// Replace the turtle's drawing with this snapshot (position and pen are kept)
+    pub fn restore(&self, turtle: &mut TurtleState) {
+        turtle.lines = self.lines.to_vec();
+        turtle.texts = self.texts.to_vec();
+        turtle.bg_color = self.bg_color;
+        turtle.backdrop = self.backdrop.clone();
+        turtle.flash = None;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.texts.is_empty() && self.backdrop.is_none()
+    }
+}
+
+/// Bounded stack of canvas snapshots, newest last
+#[derive(Debug)]
+pub struct CanvasHistory {
+    snapshots: VecDeque<CanvasSnapshot>,
+    capacity: usize,
+}
+
+impl Default for CanvasHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_CANVAS_HISTORY)
+    }
+}
+
+impl CanvasHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self { snapshots: VecDeque::new(), capacity: capacity.max(1) }
+    }
+
+    /// Capture `turtle`'s drawing; an empty canvas is not worth keeping
+    ///
+    /// Returns whether a snapshot was stored. The oldest snapshot is dropped
+    /// once the history is full.
+    pub fn push(&mut self, turtle: &TurtleState) -> bool {
+        let snapshot = CanvasSnapshot::capture(turtle);
+        if snapshot.is_empty() {
+            return false;
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<CanvasSnapshot> {
+        self.snapshots.pop_back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+/// Whether the first statement of `source` that affects the drawing is `CLEARSCREEN`/`CS`
+///
+/// Line numbers, blank lines, comments, and non-drawing statements are skipped.
+/// Used to offer a confirmation before a run wipes a canvas the student still wants.
+pub fn starts_with_clearscreen(source: &str) -> bool {
+    const DRAWING: &[&str] = &[
+        "FORWARD", "FD", "BACK", "BK", "BACKWARD", "SETXY", "HOME", "SETBGCOLOR",
+        "REPEAT", "LINE", "CIRCLE", "PLOT", "CHART",
+    ];
+    for line in source.lines() {
+        let mut words = line.split_whitespace().peekable();
+        if words.peek().is_some_and(|w| w.chars().all(|c| c.is_ascii_digit())) {
+            words.next();
+        }
+        let Some(first) = words.next() else { continue };
+        let first = first.to_uppercase();
+        match first.as_str() {
+            "CLEARSCREEN" | "CS" => return true,
+            w if DRAWING.contains(&w) => return false,
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawing(segments: usize) -> TurtleState {
+        let mut turtle = TurtleState::new();
+        for _ in 0..segments {
+            turtle.forward(10.0);
+        }
+        turtle
+    }
+
+    #[test]
+    fn test_push_pop_restores_latest_first() {
+        let mut history = CanvasHistory::default();
+        assert!(!history.push(&TurtleState::new()), "empty canvas is skipped");
+        assert!(history.push(&drawing(1)));
+        assert!(history.push(&drawing(2)));
+
+        let mut turtle = TurtleState::new();
+        history.pop().unwrap().restore(&mut turtle);
+        assert_eq!(turtle.lines.len(), 2);
+        history.pop().unwrap().restore(&mut turtle);
+        assert_eq!(turtle.lines.len(), 1);
+        assert!(history.pop().is_none());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = CanvasHistory::new(3);
+        for n in 1..=5 {
+            history.push(&drawing(n));
+        }
+        assert_eq!(history.len(), 3);
+        let kept: Vec<usize> = std::iter::from_fn(|| history.pop()).map(|s| s.lines.len()).collect();
+        assert_eq!(kept, vec![5, 4, 3]);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_starts_with_clearscreen() {
+        assert!(starts_with_clearscreen("; square\n\nCS\nFD 10"));
+        assert!(starts_with_clearscreen("TO SQ\nEND\nclearscreen"));
+        assert!(starts_with_clearscreen("10 PRINT \"HI\"\n20 CS"));
+        assert!(!starts_with_clearscreen("FD 10\nCS"));
+        assert!(!starts_with_clearscreen("10 LINE 0,0,10,10\n20 CS"));
+        assert!(!starts_with_clearscreen("T:No drawing here"));
+    }
+}
@@ -1,14 +1,17 @@
-// Re-export main modules for testing
+// Re-export main modules for testing; the interpreter and languages come from time_warp_core
+pub use time_warp_core::{i18n, interpreter, languages, speech};
+
 pub mod app;
 pub mod audio;
+pub mod config;
+pub mod gallery;
 pub mod game;
 pub mod graphics;
-pub mod interpreter;
 pub mod iot;
-pub mod languages;
 pub mod compiler;
 pub mod ml;
 pub mod plugins;
+pub mod tutorial;
 pub mod ui;
 pub mod utils;
 
@@ -2,8 +2,7 @@ use anyhow::Result;
 use eframe::egui;
 
 mod app;
-mod interpreter;
-mod languages;
+mod config;
 mod graphics;
 mod ui;
 mod utils;
@@ -17,22 +16,63 @@ mod ml;
 #[cfg(feature = "plugins")]
 mod plugins;
 
+mod gallery;
 mod game;
 mod iot;
+mod tutorial;
 
 use app::TimeWarpApp;
+use time_warp_core::{i18n, interpreter, languages, speech};
 use std::fs;
 use std::path::PathBuf;
 use time_warp_unified::compiler::TempleCodeCompiler;
 
 fn main() -> Result<()> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    // Lightweight CLI: --compile <input> [-o <output>]
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    // --log <filter>: log filter for this session, ahead of RUST_LOG and Settings → Diagnostics
+    let log_flag = match args.iter().position(|a| a == "--log") {
+        Some(i) => {
+            let filter = args.get(i + 1).cloned().ok_or_else(|| anyhow::anyhow!("Usage: --log <filter>"))?;
+            args.drain(i..=i + 1);
+            Some(filter)
+        }
+        None => None,
+    };
+    let log_filter = log_flag
+        .or_else(|| std::env::var("RUST_LOG").ok())
+        .unwrap_or_else(|| config::Settings::load().log_filter);
+    utils::diagnostics::init(&log_filter);
 
     tracing::info!("Starting Time Warp Unified v{}", env!("CARGO_PKG_VERSION"));
 
-    // Lightweight CLI: --compile <input> [-o <output>]
-    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    // --restricted: classroom lockdown (Settings → Classroom) for this session
+    let restricted = args.iter().any(|a| a == "--restricted");
+    args.retain(|a| a != "--restricted");
+    // --fast-clock: TIMER/DATE$/TIME$ from a fake clock that advances per statement
+    let fast_clock = args.iter().any(|a| a == "--fast-clock");
+    args.retain(|a| a != "--fast-clock");
+    // --debug: --run stops at REM BREAK lines and waits for Enter
+    let debug = args.iter().any(|a| a == "--debug");
+    args.retain(|a| a != "--debug");
+    // --seed <n>: random numbers for --run start from seed n (the one a run reports repeats it)
+    let seed = match args.iter().position(|a| a == "--seed") {
+        Some(i) => {
+            let seed = args.get(i + 1).and_then(|s| s.parse::<u64>().ok()).ok_or_else(|| anyhow::anyhow!("Usage: --seed <number>"))?;
+            args.drain(i..=i + 1);
+            Some(seed)
+        }
+        None => None,
+    };
+    // --disable GOTO,GOSUB: statements this session's runs refuse, on top of the settings' list
+    let disabled: Vec<String> = match args.iter().position(|a| a == "--disable") {
+        Some(i) => {
+            let list = args.get(i + 1).cloned().ok_or_else(|| anyhow::anyhow!("Usage: --disable <statement,statement…>"))?;
+            args.drain(i..=i + 1);
+            list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+        }
+        None => Vec::new(),
+    };
     if !args.is_empty() && args[0] == "--compile" {
         if args.len() < 2 { return Err(anyhow::anyhow!("Usage: --compile <input> [-o <output>]")); }
         let input = PathBuf::from(&args[1]);
@@ -53,6 +93,51 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // --export-path <program> <out.csv|out.json> [--simplify <tolerance>]
+    if !args.is_empty() && args[0] == "--export-path" {
+        return export_path(&args[1..], restricted);
+    }
+
+    // --export-screen <program> <out.png|out.svg|out.html> [--scale <n>]
+    if !args.is_empty() && args[0] == "--export-screen" {
+        return export_screen(&args[1..], restricted);
+    }
+
+    // --export-png <program> <out.png> [--export-png-scale <n>] [--transparent]
+    if !args.is_empty() && args[0] == "--export-png" {
+        return export_png(&args[1..], restricted);
+    }
+
+    // --run <program|bundle.twpak>: run headlessly, answering INPUT from stdin
+    if !args.is_empty() && args[0] == "--run" {
+        let input = args.get(1).ok_or_else(|| anyhow::anyhow!("Usage: --run <program|bundle.twpak>"))?;
+        return run_headless(&PathBuf::from(input), restricted, fast_clock, debug, seed, &disabled);
+    }
+
+    // --format <program>: print the program formatted for the language of its extension
+    if !args.is_empty() && args[0] == "--format" {
+        let input = args.get(1).ok_or_else(|| anyhow::anyhow!("Usage: --format <program>"))?;
+        return format_file(&PathBuf::from(input));
+    }
+
+    // --analyze <program>: print the Program Report's statistics as JSON
+    if !args.is_empty() && args[0] == "--analyze" {
+        let input = args.get(1).ok_or_else(|| anyhow::anyhow!("Usage: --analyze <program>"))?;
+        return analyze_file(&PathBuf::from(input));
+    }
+
+    // --open <programs…> [--autorun <program>] [--kiosk]: what the IDE opens and runs first
+    let startup = config::startup::Startup::from_args(&mut args)?;
+
+    // --import-share <link>: open a shared program in a new tab at startup
+    let shared = match args.iter().position(|a| a == "--import-share") {
+        Some(i) => {
+            let link = args.get(i + 1).ok_or_else(|| anyhow::anyhow!("Usage: --import-share <link>"))?;
+            Some(utils::share::decode_share(link).map_err(|e| anyhow::anyhow!("Cannot import share: {}", e))?)
+        }
+        None => None,
+    };
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1400.0, 900.0])
@@ -62,17 +147,217 @@ fn main() -> Result<()> {
     };
 
     eframe::run_native(
-        "Time Warp IDE - Unified",
+        app::WINDOW_TITLE,
         options,
         Box::new(|cc| {
             // Don't configure custom fonts - use egui defaults
             // configure_fonts(&cc.egui_ctx);
-            Ok(Box::new(TimeWarpApp::new(cc)))
+            let mut app = TimeWarpApp::new(cc, startup);
+            if restricted {
+                app.lock_restricted();
+            }
+            app.session_disabled = disabled;
+            if let Some(program) = shared {
+                app.open_shared(program);
+            }
+            Ok(Box::new(app))
         }),
     )
     .map_err(|e| anyhow::anyhow!("Failed to start application: {}", e))
 }
 
+/// Print `input` as Edit → Format Document would lay it out
+fn format_file(input: &std::path::Path) -> Result<()> {
+    let src = fs::read_to_string(input)?;
+    let ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
+    print!("{}", languages::format::format_source(&src, languages::Language::from_extension(ext)));
+    Ok(())
+}
+
+/// Print the statistics Tools → Program Report shows for `input`, as JSON
+///
+/// The language is the program's `@lang` header, else its extension.
+fn analyze_file(input: &std::path::Path) -> Result<()> {
+    let src = fs::read_to_string(input)?;
+    let ext = input.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let language = interpreter::metadata::parse_metadata(&src).language.unwrap_or_else(|| languages::Language::from_extension(ext));
+    let stats = languages::analysis::analyze_program(&src, language);
+    println!("{}", utils::data_export::program_report_to_json(&stats, None));
+    Ok(())
+}
+
+/// Run a program headlessly and write its turtle path as CSV or JSON
+fn export_path(args: &[String], restricted: bool) -> Result<()> {
+    let usage = || anyhow::anyhow!("Usage: --export-path <program> <out.csv|out.json> [--simplify <tolerance>]");
+    let (input, output) = match args {
+        [input, output, ..] => (PathBuf::from(input), PathBuf::from(output)),
+        _ => return Err(usage()),
+    };
+    let tolerance = match args.iter().position(|a| a == "--simplify") {
+        Some(i) => Some(args.get(i + 1).and_then(|t| t.parse::<f32>().ok()).ok_or_else(usage)?),
+        None => None,
+    };
+
+    let src = fs::read_to_string(&input)?;
+    let mut interp = interpreter::Interpreter::new();
+    let mut turtle = graphics::TurtleState::new();
+    if restricted {
+        interp.set_policy(config::Settings::load().restricted_policy)?;
+    }
+    interp.set_program_file(&input);
+    interp.load_program(&src)?;
+    interp.execute(&mut turtle)?;
+
+    let (lines, merged) = match tolerance {
+        Some(t) => graphics::simplify_path(&turtle.lines, t),
+        None => (turtle.lines.clone(), 0),
+    };
+    let is_json = output.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("json"));
+    let text = if is_json { utils::data_export::path_to_json(&lines) } else { utils::data_export::path_to_csv(&lines) };
+    fs::write(&output, text)?;
+    println!("✅ Wrote {} segment(s) ({} merged) to {}", lines.len(), merged, output.display());
+    Ok(())
+}
+
+/// Run a program headlessly and save its text screen as PNG, SVG, or HTML
+///
+/// The screen is in the theme from the settings, as the Output tab shows it.
+fn export_screen(args: &[String], restricted: bool) -> Result<()> {
+    let usage = || anyhow::anyhow!("Usage: --export-screen <program> <out.png|out.svg|out.html> [--scale <n>]");
+    let (input, output) = match args {
+        [input, output, ..] => (PathBuf::from(input), PathBuf::from(output)),
+        _ => return Err(usage()),
+    };
+    let scale = match args.iter().position(|a| a == "--scale") {
+        Some(i) => args.get(i + 1).and_then(|s| s.parse::<u32>().ok()).filter(|s| (1..=16).contains(s)).ok_or_else(usage)?,
+        None => 2,
+    };
+
+    let settings = config::Settings::load();
+    let src = fs::read_to_string(&input)?;
+    let mut interp = interpreter::Interpreter::new();
+    let mut turtle = graphics::TurtleState::new();
+    if restricted {
+        interp.set_policy(settings.restricted_policy.clone())?;
+    }
+    interp.set_program_file(&input);
+    interp.load_program(&src)?;
+    interp.execute(&mut turtle)?;
+
+    let screen = utils::screen_export::text_screen(&interp, &settings.theme.colors(), settings.show_warnings)
+        .ok_or_else(|| anyhow::anyhow!("{} does not switch to text mode (SCREEN 0)", input.display()))?;
+    let title = input.file_name().map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    utils::screen_export::export_text_screen(&screen, &output, scale, &title)?;
+    println!("✅ Wrote the {}x{} text screen to {}", screen.cols, screen.rows, output.display());
+    Ok(())
+}
+
+/// Run a program headlessly and save its canvas as a PNG, `--export-png-scale` pixels per turtle step
+fn export_png(args: &[String], restricted: bool) -> Result<()> {
+    let usage = || anyhow::anyhow!("Usage: --export-png <program> <out.png> [--export-png-scale <n>] [--transparent]");
+    let (input, output) = match args {
+        [input, output, ..] => (PathBuf::from(input), PathBuf::from(output)),
+        _ => return Err(usage()),
+    };
+    let scale = match args.iter().position(|a| a == "--export-png-scale") {
+        Some(i) => args.get(i + 1).and_then(|s| s.parse::<u32>().ok()).filter(|s| (1..=16).contains(s)).ok_or_else(usage)?,
+        None => 1,
+    };
+    let transparent = args.iter().any(|a| a == "--transparent");
+
+    let settings = config::Settings::load();
+    let src = fs::read_to_string(&input)?;
+    let mut interp = interpreter::Interpreter::new();
+    let mut turtle = graphics::TurtleState::new();
+    if restricted {
+        interp.set_policy(settings.restricted_policy.clone())?;
+    }
+    interp.set_program_file(&input);
+    interp.load_program(&src)?;
+    interp.execute(&mut turtle)?;
+
+    let size = graphics::raster::ExportSize::Scale(scale);
+    let options = graphics::PngOptions { show_turtle: settings.export_turtle, size, transparent, ..Default::default() };
+    turtle.save_png_with(&output.to_string_lossy(), options, |c| c)?;
+    let (width, height) = turtle.export_size(size);
+    println!("✅ Wrote the {}x{} canvas to {}", width, height, output.display());
+    Ok(())
+}
+
+/// Run a program or `.twpak` bundle without the GUI and print its output
+///
+/// A bundle is unpacked to a temporary folder that becomes the sandbox
+/// workspace, so its file statements cannot reach outside it.
+fn run_headless(input: &std::path::Path, restricted: bool, fast_clock: bool, debug: bool, seed: Option<u64>, disabled: &[String]) -> Result<()> {
+    // Shell commands stay off in Restricted mode whatever the settings file says
+    let mut policy = if restricted {
+        interpreter::SecurityPolicy { allow_shell: false, ..config::Settings::load().restricted_policy }
+    } else {
+        interpreter::SecurityPolicy::default()
+    };
+    // Kept until the run ends; dropping it removes the unpacked folder
+    let pak = if utils::twpak::is_pak_path(input) { Some(utils::twpak::extract_to_temp(input)?) } else { None };
+    if let Some(pak) = &pak {
+        policy.workspace_path = Some(pak.root.clone());
+    }
+    let program = pak.as_ref().map_or_else(|| input.to_path_buf(), |pak| pak.entry_path.clone());
+
+    let src = fs::read_to_string(&program)?;
+    let mut interp = interpreter::Interpreter::new();
+    let mut turtle = graphics::TurtleState::new();
+    interp.set_policy(policy)?;
+    interp.run_seed = seed;
+    // Source breakpoints only stop a headless run with --debug
+    interp.honor_source_breakpoints = debug;
+    if fast_clock {
+        interp.set_clock(interpreter::clock::FakeClock::fast_forward());
+    }
+    interp.input_callback = Some(Box::new(|prompt| {
+        use std::io::Write;
+        print!("{} ", prompt.trim_end());
+        let _ = std::io::stdout().flush();
+        let mut line = String::new();
+        let _ = std::io::stdin().read_line(&mut line);
+        line.trim_end_matches(['\r', '\n']).to_string()
+    }));
+    interp.set_program_file(&program);
+    interp.load_program(&src)?;
+    // The settings file's `[report]` table, and the program folder's `disabled_statements` over the settings file's
+    let folder = program.parent().map(std::path::Path::to_path_buf).unwrap_or_default();
+    let project = config::project::ProjectSettings::load(&folder).ok().flatten();
+    let settings = config::project::effective_settings(&config::Settings::load(), project.as_ref(), false);
+    interp.disabled = interpreter::lesson::DisabledStatements::new(settings.disabled_statements.iter().chain(disabled));
+    interp.record_timing = settings.record_output_timing;
+    let reporters = utils::run_reporters::from_settings(&settings.report, interp.policy(), Some(&folder));
+    let name = program.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    interp.start_report(&name, reporters);
+    // At each breakpoint, print the output so far and wait for Enter
+    let mut printed = 0;
+    let result = loop {
+        let result = interp.execute(&mut turtle);
+        match (&result, interp.breakpoints.paused_at) {
+            (Ok(output), Some(_)) => {
+                for line in utils::transcript::normalize_transcript(&output[printed..]) {
+                    println!("{}", line);
+                }
+                printed = output.len();
+                eprint!("⏸ Breakpoint before line {} — press Enter to continue ", interp.source_line());
+                let _ = std::io::stdin().read_line(&mut String::new());
+            }
+            _ => break result,
+        }
+    };
+    interp.finish_report(&turtle, result.as_ref().err().map(|e| e.to_string()));
+    for line in utils::transcript::normalize_transcript(&result?[printed..]) {
+        println!("{}", line);
+    }
+    // On stderr, so the program's output stays as it was
+    if interp.rng.borrow().used() {
+        eprintln!("Random seed {} (repeat this run with --seed {})", interp.random_seed(), interp.random_seed());
+    }
+    Ok(())
+}
+
 fn load_icon() -> egui::IconData {
     // Simple 32x32 icon with Time Warp theme colors
     // Blue-teal gradient background with "TW" text representation
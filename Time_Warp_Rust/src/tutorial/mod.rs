@@ -0,0 +1,246 @@
+//! Guided tutorials with per-step validation
+//!
+//! Tutorials ship as TOML files embedded in the binary (`tutorial/data/`).
+//! Each step has an instruction, starter code for the scratch buffer, and a
+//! list of [`Check`]s. Pressing Check runs the scratch buffer with
+//! [`Interpreter::run_isolated`] and [`TutorialStep::validate`] interprets the
+//! checks against the resulting [`RunReport`].
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::interpreter::isolated::{IsolatedRunOptions, RunReport};
+use crate::interpreter::Interpreter;
+
+/// Embedded tutorial sources, in menu order
+const BUILTIN_SOURCES: &[&str] = &[
+    include_str!("data/pilot.toml"),
+    include_str!("data/basic.toml"),
+    include_str!("data/logo.toml"),
+];
+
+/// Tolerance used when comparing numeric variables
+const VALUE_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tutorial {
+    /// Stable key used to store progress in settings
+    pub id: String,
+    pub title: String,
+    /// Language the tutorial teaches ("PILOT", "BASIC", "Logo")
+    pub language: String,
+    pub steps: Vec<TutorialStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TutorialStep {
+    pub instruction: String,
+    #[serde(default)]
+    pub starter_code: String,
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// Known-good answer, shown on request and used by tests
+    pub solution: String,
+    /// Answers fed to A:/INPUT when the step is checked
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    pub checks: Vec<Check>,
+}
+
+/// One validation rule evaluated against a [`RunReport`]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Check {
+    /// Some output line contains `text` (case-insensitive)
+    OutputContains { text: String },
+    /// Whole output equals `text`, ignoring whitespace differences
+    OutputMatches { text: String },
+    /// The turtle drew at least `count` line segments
+    MinLinesDrawn { count: usize },
+    /// Numeric variable `name` equals `value`
+    VariableEquals { name: String, value: f64 },
+    /// String variable `name` equals `value` (case-insensitive)
+    StringEquals { name: String, value: String },
+    /// No error lines in the output and the run finished
+    NoErrors,
+}
+
+impl Check {
+    /// `Ok(())` when the report satisfies the rule, otherwise a message for the student
+    pub fn evaluate(&self, report: &RunReport) -> std::result::Result<(), String> {
+        match self {
+            Check::OutputContains { text } => {
+                let needle = text.to_lowercase();
+                if report.output.iter().any(|l| l.to_lowercase().contains(&needle)) {
+                    Ok(())
+                } else {
+                    Err(format!("Expected the output to contain \"{}\"", text))
+                }
+            }
+            Check::OutputMatches { text } => {
+                if report.output_matches(text) {
+                    Ok(())
+                } else {
+                    Err(format!("Expected the output to be:\n{}", text.trim()))
+                }
+            }
+            Check::MinLinesDrawn { count } => {
+                if report.lines_drawn >= *count {
+                    Ok(())
+                } else {
+                    Err(format!("Expected at least {} lines drawn, found {}", count, report.lines_drawn))
+                }
+            }
+            Check::VariableEquals { name, value } => match lookup(&report.variables, name) {
+                Some(v) if (v - value).abs() < VALUE_EPSILON => Ok(()),
+                Some(v) => Err(format!("Expected {} to be {}, but it is {}", name, value, v)),
+                None => Err(format!("Expected a variable named {}", name)),
+            },
+            Check::StringEquals { name, value } => match lookup(&report.string_variables, name) {
+                Some(v) if v.trim().eq_ignore_ascii_case(value.trim()) => Ok(()),
+                Some(v) => Err(format!("Expected {} to be \"{}\", but it is \"{}\"", name, value, v)),
+                None => Err(format!("Expected a text variable named {}", name)),
+            },
+            Check::NoErrors => {
+                if !report.completed() {
+                    Err(match &report.error {
+                        Some(e) => format!("The program stopped with an error: {}", e),
+                        None => "The program is still waiting for input".to_string(),
+                    })
+                } else if let Some(line) = report.output.iter().find(|l| l.starts_with('❌')) {
+                    Err(format!("The program reported an error: {}", line))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Variable names are case-insensitive in every language
+fn lookup<'a, V>(vars: &'a HashMap<String, V>, name: &str) -> Option<&'a V> {
+    vars.get(name)
+        .or_else(|| vars.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v))
+}
+
+/// Result of checking one step: the messages of every failed rule
+#[derive(Debug, Clone, Default)]
+pub struct StepOutcome {
+    pub failures: Vec<String>,
+}
+
+impl StepOutcome {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl TutorialStep {
+    /// Evaluate every check against `report`
+    pub fn validate(&self, report: &RunReport) -> StepOutcome {
+        StepOutcome {
+            failures: self.checks.iter().filter_map(|c| c.evaluate(report).err()).collect(),
+        }
+    }
+
+    /// Run `code` in isolation with this step's inputs and validate the result
+    pub fn check(&self, code: &str) -> (RunReport, StepOutcome) {
+        let options = IsolatedRunOptions { inputs: self.inputs.clone(), ..IsolatedRunOptions::default() };
+        let report = Interpreter::run_isolated(code, options);
+        let outcome = self.validate(&report);
+        (report, outcome)
+    }
+}
+
+impl Tutorial {
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let tutorial: Tutorial = toml::from_str(text)?;
+        if tutorial.steps.is_empty() {
+            return Err(anyhow::anyhow!("Tutorial '{}' has no steps", tutorial.id));
+        }
+        Ok(tutorial)
+    }
+}
+
+/// All tutorials shipped with the IDE
+///
+/// The embedded files are covered by tests, so a parse failure here is a bug;
+/// broken entries are logged and skipped rather than crashing the UI.
+pub fn builtin_tutorials() -> Vec<Tutorial> {
+    BUILTIN_SOURCES
+        .iter()
+        .filter_map(|src| match Tutorial::from_toml(src) {
+            Ok(t) => Some(t),
+            Err(e) => {
+                tracing::warn!("Skipping built-in tutorial: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_tutorials_cover_each_language() {
+        let tutorials = builtin_tutorials();
+        assert_eq!(tutorials.len(), BUILTIN_SOURCES.len());
+        for language in ["PILOT", "BASIC", "Logo"] {
+            assert!(tutorials.iter().any(|t| t.language == language), "no {} tutorial", language);
+        }
+    }
+
+    #[test]
+    fn test_builtin_solutions_pass_and_starters_fail() {
+        for tutorial in builtin_tutorials() {
+            for (i, step) in tutorial.steps.iter().enumerate() {
+                let (_, outcome) = step.check(&step.solution);
+                assert!(outcome.passed(), "{} step {}: {:?}", tutorial.id, i + 1, outcome.failures);
+                let (_, outcome) = step.check(&step.starter_code);
+                assert!(!outcome.passed(), "{} step {}: starter code already passes", tutorial.id, i + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_checks_against_report() {
+        let mut report = RunReport {
+            output: vec!["Hello, Ada".to_string()],
+            lines_drawn: 3,
+            ..RunReport::default()
+        };
+        report.variables.insert("N".to_string(), 7.0);
+
+        assert!(Check::OutputContains { text: "hello".into() }.evaluate(&report).is_ok());
+        assert!(Check::OutputMatches { text: "Hello,   Ada\n".into() }.evaluate(&report).is_ok());
+        assert!(Check::MinLinesDrawn { count: 4 }.evaluate(&report).is_err());
+        assert!(Check::VariableEquals { name: "n".into(), value: 7.0 }.evaluate(&report).is_ok());
+        assert!(Check::VariableEquals { name: "M".into(), value: 7.0 }.evaluate(&report).is_err());
+        assert!(Check::NoErrors.evaluate(&report).is_ok());
+
+        report.output.push("❌ Error at line 2: oops".to_string());
+        assert!(Check::NoErrors.evaluate(&report).is_err());
+    }
+
+    #[test]
+    fn test_parse_check_spec() {
+        let tutorial = Tutorial::from_toml(
+            r#"
+            id = "t"
+            title = "T"
+            language = "Logo"
+            [[steps]]
+            instruction = "Draw"
+            solution = "FD 10"
+            checks = [{ type = "min_lines_drawn", count = 1 }, { type = "variable_equals", name = "N", value = 7 }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(tutorial.steps[0].checks[0], Check::MinLinesDrawn { count: 1 });
+        assert_eq!(tutorial.steps[0].checks[1], Check::VariableEquals { name: "N".into(), value: 7.0 });
+        assert!(Tutorial::from_toml("id = \"e\"\ntitle = \"E\"\nlanguage = \"BASIC\"\nsteps = []").is_err());
+    }
+}
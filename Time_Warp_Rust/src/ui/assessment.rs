@@ -0,0 +1,159 @@
+//! Assessment mode's controls: Start, the countdown in the menu bar, and Submit
+//!
+//! The countdown is an [`Assessment`] and the bundle a [`Submission`]; this
+//! module asks for the student's identifier, shows the clock, stops the run
+//! when time is up, and writes the submission into the project's submit
+//! folder, never over an earlier one.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::isolated::RunReport;
+use crate::utils::assessment::{countdown, Assessment, AssessmentPhase};
+use crate::utils::run_reporters::{now, RunSummary};
+use crate::utils::submission::{Submission, SubmissionInfo};
+
+/// Time left below which the clock turns red
+const LAST_MINUTE: Duration = Duration::from_secs(60);
+
+/// The assessment while the settings ask for one, and the Start window
+#[derive(Default)]
+pub struct AssessmentPanel {
+    pub state: Option<Assessment>,
+    /// The Start window is open
+    asking: bool,
+    student: String,
+}
+
+/// Whether the time is up or the work submitted, so the editor and Run refuse changes
+pub fn locked(app: &TimeWarpApp) -> bool {
+    app.assessment.state.as_ref().is_some_and(Assessment::locked)
+}
+
+/// Keep the assessment in step with the settings and the clock; called every frame
+pub fn update(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(limit) = app.effective_settings().assessment.limit() else {
+        app.assessment.state = None;
+        return;
+    };
+    let state = app.assessment.state.get_or_insert_with(|| Assessment::new(limit));
+    if state.tick(Instant::now()) {
+        crate::ui::menubar::stop_program(app);
+        app.error_message = Some(tr("assessment.time_up").to_string());
+    } else if state.phase() == AssessmentPhase::Running {
+        ctx.request_repaint_after(Duration::from_millis(250));
+    }
+}
+
+/// The menu bar's part: Start, or the clock and Submit (laid out right to left)
+pub fn render_controls(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let Some(state) = &app.assessment.state else { return };
+    let phase = state.phase();
+    let remaining = state.remaining(Instant::now()).unwrap_or_default();
+    if phase == AssessmentPhase::Ready {
+        app.assessment.asking |= ui.button(tr("assessment.start")).clicked();
+        return;
+    }
+    if phase == AssessmentPhase::Submitted {
+        ui.label(tr("assessment.done"));
+        return;
+    }
+    let running = app.run_worker.is_some();
+    let button = ui.add_enabled(!running, egui::Button::new(tr("assessment.submit")));
+    if button.on_disabled_hover_text(tr("assessment.stop_first")).clicked() {
+        submit(app);
+    }
+    let error = ui.visuals().error_fg_color;
+    if phase == AssessmentPhase::TimeUp {
+        ui.colored_label(error, tr("assessment.over"));
+    } else {
+        let color = if remaining < LAST_MINUTE { error } else { ui.visuals().strong_text_color() };
+        ui.label(egui::RichText::new(format!("⏱ {}", countdown(remaining))).monospace().color(color));
+    }
+}
+
+/// Start window: the student's identifier and Start
+pub fn render_start(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    if !app.assessment.asking {
+        return;
+    }
+    let Some(state) = &mut app.assessment.state else { return };
+    let mut open = true;
+    let mut start = false;
+    let panel = &mut app.assessment.student;
+    egui::Window::new(tr("assessment.title")).open(&mut open).collapsible(false).show(ctx, |ui| {
+        ui.label(trf("assessment.intro", &[&(state.limit().as_secs() / 60)]));
+        ui.label(tr("assessment.student"));
+        let field = ui.text_edit_singleline(panel);
+        crate::ui::keyboard::focus_on_open(&field);
+        let ready = !panel.trim().is_empty();
+        let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        start = ui.add_enabled(ready, egui::Button::new(tr("assessment.start"))).clicked() || (ready && entered);
+    });
+    if start {
+        state.start(panel, Instant::now());
+    }
+    if !open || start {
+        app.assessment.asking = false;
+    }
+}
+
+/// Pack the open buffers and the last run, and write them into the submit folder
+fn submit(app: &mut TimeWarpApp) {
+    let Some(state) = &app.assessment.state else { return };
+    let settings = app.effective_settings().assessment;
+    let root = app.project.as_ref().map(|p| p.root.clone()).or_else(|| app.project_root()).unwrap_or_default();
+    let lesson = root.file_name().map_or_else(|| "lesson".into(), |n| n.to_string_lossy().into_owned());
+
+    // The current tab first, so opening the submission runs it
+    let current = app.current_file().cloned();
+    let mut buffers: Vec<(String, String)> = Vec::new();
+    for file in current.iter().chain(app.open_files.iter().filter(|f| Some(*f) != current.as_ref())) {
+        if let Some(text) = app.file_buffers.get(file) {
+            buffers.push((file.clone(), text.clone()));
+        }
+    }
+    let run = app.run_summary.is_some().then(|| {
+        let report = RunReport::capture(&app.interpreter, &app.turtle_state, None);
+        RunSummary::new(app.run_file.as_deref().unwrap_or_default(), &app.run_started, &report)
+    });
+    let info = SubmissionInfo {
+        student: state.student().to_string(),
+        lesson,
+        submitted: now(),
+        seconds_used: state.used(Instant::now()).as_secs(),
+        time_up: state.phase() == AssessmentPhase::TimeUp,
+        scores: Submission::scores(run.as_ref(), &settings.score_variables()),
+    };
+    let submission = Submission { info, buffers, run };
+    let folder = root.join(settings.submit_folder());
+    let written = submission.pack().map_err(anyhow::Error::from).and_then(|bytes| {
+        std::fs::create_dir_all(&folder)?;
+        let path = unused_path(folder.join(submission.file_name()));
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    });
+    match written {
+        Ok(path) => {
+            if let Some(state) = &mut app.assessment.state {
+                state.submitted(Instant::now());
+            }
+            app.error_message = Some(trf("assessment.submitted", &[&path.display()]));
+        }
+        Err(e) => app.error_message = Some(trf("assessment.submit_failed", &[&e])),
+    }
+}
+
+/// `path`, or the first of `name-2.twpak`, `name-3.twpak`, … not taken yet
+fn unused_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_default();
+    (2..).map(|n| path.with_file_name(format!("{}-{}.{}", stem, n, extension))).find(|p| !p.exists()).unwrap_or(path)
+}
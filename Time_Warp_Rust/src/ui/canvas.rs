@@ -1,5 +1,6 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::graphics::{color32, paint_turtle};
 
 /// Legacy canvas render function - DEPRECATED
 /// Use crate::ui::screen::render() for unified screen rendering instead
@@ -15,7 +16,8 @@ pub fn render_canvas(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
         ui.label(format!("Zoom: {:.2}x", app.turtle_zoom));
         ui.label(format!("Pan: ({:.0}, {:.0})", app.turtle_pan.x, app.turtle_pan.y));
         ui.label(format!("Pos: ({:.0}, {:.0})", app.turtle_state.x, app.turtle_state.y));
-        ui.label(format!("Heading: {:.0}°", app.turtle_state.heading));
+        let heading = app.interpreter.angle_mode.heading(app.turtle_state.heading);
+        ui.label(format!("Heading: {:.0}° ({})", heading, app.interpreter.angle_mode.name()));
     });
 
     ui.separator();
@@ -54,7 +56,7 @@ pub fn render_canvas(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
     );
 
     // Background
-    painter.rect_filled(response.rect, 0.0, app.turtle_state.bg_color);
+    painter.rect_filled(response.rect, 0.0, color32(app.turtle_state.bg_color));
 
     // Grid
     let grid_spacing = 50.0;
@@ -85,16 +87,13 @@ pub fn render_canvas(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
     for line in &app.turtle_state.lines {
         let start = to_screen * line.start;
         let end = to_screen * line.end;
-        painter.line_segment([start, end], egui::Stroke::new(line.width * app.turtle_zoom, line.color));
+        painter.line_segment([start, end], egui::Stroke::new(line.width * app.turtle_zoom, color32(line.color)));
     }
 
     // Draw turtle cursor
     if app.turtle_state.visible {
         let pos = to_screen * egui::pos2(app.turtle_state.x, app.turtle_state.y);
         let size = 10.0 * app.turtle_zoom;
-        painter.circle_filled(pos, size, app.current_theme.accent());
-        let angle = app.turtle_state.heading.to_radians();
-        let dir = egui::vec2(angle.sin(), -angle.cos()) * size * 1.5;
-        painter.line_segment([pos, pos + dir], egui::Stroke::new(2.0, app.current_theme.text()));
+        paint_turtle(&painter, &app.turtle_state, pos, size, app.current_theme.accent(), app.current_theme.text());
     }
 }
@@ -0,0 +1,96 @@
+//! View ▸ Save Canvas as PNG: the image's size and background
+//!
+//! The canvas can be saved at its own size, at 2× or 4× for print or a
+//! high-DPI screen, or at an exact number of pixels, and with a transparent
+//! background. The drawing is rendered again at that size, so lines stay
+//! sharp (see [`PngOptions`]). The choices last for the session.
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::graphics::raster::{ExportSize, MAX_EXPORT_SIDE};
+use crate::graphics::{color32, rgba, PngOptions};
+use crate::i18n::{tr, trf};
+use crate::ui::themes::cvd_safe_color;
+
+/// The Save Canvas window and the choices made in it
+pub struct CanvasExportPanel {
+    pub open: bool,
+    pub size: ExportSize,
+    /// Width and height offered under Custom
+    custom: (u32, u32),
+    pub transparent: bool,
+}
+
+impl Default for CanvasExportPanel {
+    fn default() -> Self {
+        Self { open: false, size: ExportSize::Canvas, custom: (1920, 1080), transparent: false }
+    }
+}
+
+/// Save Canvas window: 1×, 2×, 4×, or custom pixels, and the background
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.canvas_export.open;
+    let mut save = false;
+    let panel = &mut app.canvas_export;
+    egui::Window::new(tr("canvas_export.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("canvas_export.size"));
+                ui.radio_value(&mut panel.size, ExportSize::Canvas, "1×");
+                ui.radio_value(&mut panel.size, ExportSize::Scale(2), "2×");
+                ui.radio_value(&mut panel.size, ExportSize::Scale(4), "4×");
+                let custom = ExportSize::Pixels(panel.custom.0, panel.custom.1);
+                if ui.radio(matches!(panel.size, ExportSize::Pixels(..)), tr("canvas_export.custom")).clicked() {
+                    panel.size = custom;
+                }
+            });
+            if matches!(panel.size, ExportSize::Pixels(..)) {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut panel.custom.0).range(1..=MAX_EXPORT_SIDE).suffix(tr("settings.screen.px_wide")));
+                    ui.add(egui::DragValue::new(&mut panel.custom.1).range(1..=MAX_EXPORT_SIDE).suffix(tr("settings.screen.px_high")));
+                });
+                panel.size = ExportSize::Pixels(panel.custom.0, panel.custom.1);
+            }
+            ui.checkbox(&mut panel.transparent, tr("canvas_export.transparent"));
+            let (width, height) = app.turtle_state.export_size(panel.size);
+            ui.label(trf("canvas_export.pixels", &[&width, &height]));
+            ui.separator();
+            save = ui.button(tr("canvas_export.save")).clicked();
+        });
+    app.canvas_export.open = open && !save;
+    if save {
+        save_canvas_as_png(app);
+    }
+}
+
+fn save_canvas_as_png(app: &mut TimeWarpApp) {
+    if let Some(path) = rfd::FileDialog::new()
+        .add_filter(tr("file.filter.png"), &["png"])
+        .set_file_name("turtle_canvas.png")
+        .save_file()
+    {
+        let mapped = app.settings.cvd_safe_colors && app.settings.export_as_displayed;
+        let measurement = app.measure_tool.measure(&app.measure_points).filter(|_| app.settings.export_measurements);
+        let options = PngOptions {
+            show_turtle: app.settings.export_turtle,
+            measurement,
+            size: app.canvas_export.size,
+            transparent: app.canvas_export.transparent,
+        };
+        let result = app.turtle_state.save_png_with(&path.to_string_lossy(), options, |c| {
+            if mapped { rgba(cvd_safe_color(color32(c))) } else { c }
+        });
+        match result {
+            Ok(_) => {
+                app.error_message = Some(trf("canvas.saved", &[&path.display()]));
+            }
+            Err(e) => {
+                app.error_message = Some(trf("canvas.save_failed", &[&e]));
+            }
+        }
+    }
+}
@@ -1,13 +1,238 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::arrays::BasicArray;
+use crate::interpreter::Interpreter;
+use crate::interpreter::var_types::VarType;
+use crate::utils::data_export;
 
-pub fn render(_app: &TimeWarpApp, ui: &mut egui::Ui) {
-    ui.heading("Debugger");
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.heading(tr("debugger.title"));
     ui.separator();
-    
-    ui.label("Debugger features coming soon:");
-    ui.label("• Breakpoints");
-    ui.label("• Step execution");
-    ui.label("• Variable inspector");
-    ui.label("• Call stack");
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        render_breakpoints(app, ui);
+        render_variables(app, ui);
+        render_answer_history(app, ui);
+        ui.add_space(10.0);
+        render_series(app, ui);
+    });
+}
+
+/// Variables panel: current numeric and string variables and arrays, with export buttons
+fn render_variables(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.heading(tr("debugger.variables"));
+        if ui.button(tr("debugger.export_csv")).clicked() {
+            let csv = data_export::variables_to_csv(&app.interpreter);
+            save_text(app, "variables.csv", "CSV", "csv", &csv);
+        }
+        if ui.button(tr("debugger.export_json")).clicked() {
+            let json = data_export::variables_to_json(&app.interpreter);
+            save_text(app, "variables.json", "JSON", "json", &json);
+        }
+    });
+
+    let mut numbers: Vec<_> = app.interpreter.variables.iter().collect();
+    numbers.sort_by(|a, b| a.0.cmp(b.0));
+    let mut strings: Vec<_> = app.interpreter.string_variables.iter().collect();
+    strings.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut arrays: Vec<_> = app.interpreter.arrays.values().collect();
+    arrays.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if numbers.is_empty() && strings.is_empty() && arrays.is_empty() {
+        ui.label(tr("debugger.no_variables"));
+        return;
+    }
+    let interp = &app.interpreter;
+    let mut jump = None;
+    egui::Grid::new("variables_grid").striped(true).num_columns(4).show(ui, |ui| {
+        let rows = numbers.into_iter().map(|(name, value)| (name, interp.var_type(name), value.to_string()))
+            .chain(strings.into_iter().map(|(name, value)| (name, VarType::String, format!("\"{}\"", value))));
+        for (name, var_type, value) in rows {
+            if interp.is_kept(name) {
+                ui.monospace(format!("📌 {}", name)).on_hover_text(tr("debugger.kept"));
+            } else {
+                ui.monospace(name);
+            }
+            ui.weak(var_type.name());
+            ui.monospace(value);
+            match interp.assigned_line(name) {
+                Some(line) => {
+                    if ui.link(trf("debugger.set_at", &[&line])).on_hover_text(trf("debugger.set_at.hover", &[&line])).clicked() {
+                        jump = Some(line);
+                    }
+                }
+                None => {
+                    ui.label("");
+                }
+            }
+            ui.end_row();
+        }
+    });
+    for array in arrays {
+        render_array(ui, array, interp.keep_variables);
+    }
+    if let Some(line) = jump {
+        app.active_tab = 0;
+        app.pending_goto_line = Some(line);
+    }
+}
+
+/// The editor's hover over `word` at a breakpoint: the variable's value, and the line that last set it
+pub fn inspect_text(interp: &Interpreter, word: &str) -> Option<String> {
+    let name = word.to_uppercase();
+    let value = match (interp.variables.get(&name), interp.string_variables.get(&name)) {
+        (Some(number), _) => number.to_string(),
+        (None, Some(text)) => format!("\"{}\"", text),
+        (None, None) => return None,
+    };
+    Some(match interp.assigned_line(&name) {
+        Some(line) => trf("debugger.inspect.set_at", &[&name, &value, &line]),
+        None => trf("debugger.inspect", &[&name, &value]),
+    })
+}
+
+/// Breakpoints the program's `REM BREAK` lines set, with one switch for all of them; a click shows the line
+fn render_breakpoints(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let breakpoints = &mut app.interpreter.breakpoints;
+    if breakpoints.from_source().is_empty() {
+        return;
+    }
+    let mut jump = None;
+    egui::CollapsingHeader::new(trf("debugger.breakpoints", &[&breakpoints.from_source().len()]))
+        .id_salt("breakpoints")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.checkbox(&mut breakpoints.source_enabled, tr("debugger.breakpoints.enabled"));
+            for breakpoint in breakpoints.from_source() {
+                let marker = if breakpoints.paused_at == Some(breakpoint.line) { "▶" } else { "●" };
+                ui.horizontal(|ui| {
+                    if ui.link(trf("debugger.breakpoints.line", &[&marker, &breakpoint.source_line])).clicked() {
+                        jump = Some(breakpoint.source_line);
+                    }
+                    ui.weak(tr("debugger.breakpoints.from_source"));
+                });
+            }
+        });
+    if let Some(line) = jump {
+        app.active_tab = 0;
+        app.pending_goto_line = Some(line);
+    }
+}
+
+/// PILOT answers of this run, most recent (`%ANS1`) first, under a collapsible header
+fn render_answer_history(app: &TimeWarpApp, ui: &mut egui::Ui) {
+    let history = &app.interpreter.answer_history;
+    if history.is_empty() {
+        return;
+    }
+    egui::CollapsingHeader::new(trf("debugger.answer_history", &[&history.len()]))
+        .id_salt("answer_history")
+        .show(ui, |ui| {
+            egui::Grid::new("answer_history_grid").striped(true).num_columns(2).show(ui, |ui| {
+                for (index, answer) in history.iter().enumerate() {
+                    ui.monospace(format!("%ANS{}", index + 1));
+                    ui.monospace(format!("\"{}\"", answer));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+/// Elements shown in an array's one-line summary
+const ARRAY_PEEK: usize = 5;
+
+/// One array: its dimensions and first elements, with a grid of every element for 2-D arrays
+///
+/// Arrays are kept for the next run only with Keep variables between runs (`kept`).
+fn render_array(ui: &mut egui::Ui, array: &BasicArray, kept: bool) {
+    let summary = format!("{}{} = {}", if kept { "📌 " } else { "" }, array.shape(), array.peek(ARRAY_PEEK));
+    let [rows, cols] = array.extents()[..] else {
+        ui.monospace(summary);
+        return;
+    };
+    egui::CollapsingHeader::new(egui::RichText::new(summary).monospace())
+        .id_salt(("array", &array.name))
+        .show(ui, |ui| {
+            egui::ScrollArea::horizontal().id_salt(("array_grid", &array.name)).show(ui, |ui| {
+                egui::Grid::new(("array_cells", &array.name)).striped(true).show(ui, |ui| {
+                    ui.label("");
+                    for col in 0..cols {
+                        ui.strong((array.base + col).to_string());
+                    }
+                    ui.end_row();
+                    for row in 0..rows {
+                        ui.strong((array.base + row).to_string());
+                        for col in 0..cols {
+                            ui.monospace(array.display(row * cols + col));
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+}
+
+/// Series recorded with LOGVAR, shown as quick line charts
+fn render_series(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        ui.heading(tr("debugger.series"));
+        if !app.interpreter.recorded_series.is_empty() && ui.button(tr("debugger.export_csv")).clicked() {
+            let csv = data_export::series_to_csv(&app.interpreter.recorded_series);
+            save_text(app, "series.csv", "CSV", "csv", &csv);
+        }
+    });
+
+    if app.interpreter.recorded_series.is_empty() {
+        ui.label(tr("debugger.series_hint"));
+        return;
+    }
+    let mut names: Vec<_> = app.interpreter.recorded_series.keys().cloned().collect();
+    names.sort();
+    for name in names {
+        let values = &app.interpreter.recorded_series[&name];
+        egui::CollapsingHeader::new(trf("debugger.series_values", &[&name, &values.len()]))
+            .default_open(true)
+            .show(ui, |ui| draw_line_chart(ui, values, app.current_theme.accent()));
+    }
+}
+
+fn draw_line_chart(ui: &mut egui::Ui, values: &[f64], color: egui::Color32) {
+    let (response, painter) = ui.allocate_painter(egui::vec2(ui.available_width().min(480.0), 140.0), egui::Sense::hover());
+    let rect = response.rect.shrink(4.0);
+    painter.rect_stroke(response.rect, 2.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
+    if values.is_empty() {
+        return;
+    }
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = if (max - min).abs() < f64::EPSILON { 1.0 } else { max - min };
+    let steps = (values.len().max(2) - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let x = rect.left() + rect.width() * (i as f32 / steps);
+            let y = rect.bottom() - rect.height() * ((v - min) / range) as f32;
+            egui::pos2(x, y)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(2.0, color)));
+    let font = egui::TextStyle::Small.resolve(ui.style());
+    painter.text(rect.left_top(), egui::Align2::LEFT_TOP, trf("debugger.max", &[&max]), font.clone(), egui::Color32::GRAY);
+    painter.text(rect.left_bottom(), egui::Align2::LEFT_BOTTOM, trf("debugger.min", &[&min]), font, egui::Color32::GRAY);
+}
+
+pub fn save_text(app: &mut TimeWarpApp, default_name: &str, filter: &str, ext: &str, contents: &str) {
+    if let Some(path) = rfd::FileDialog::new()
+        .add_filter(filter, &[ext])
+        .set_file_name(default_name)
+        .save_file()
+    {
+        if let Err(e) = std::fs::write(&path, contents) {
+            app.error_message = Some(trf("debugger.export_failed", &[&e]));
+        }
+    }
 }
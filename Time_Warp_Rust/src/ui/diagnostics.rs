@@ -0,0 +1,72 @@
+use eframe::egui;
+use tracing::Level;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::utils::diagnostics::{diagnostics, LogEntry};
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::from_rgb(230, 80, 80),
+        Level::WARN => egui::Color32::from_rgb(230, 170, 60),
+        Level::INFO => egui::Color32::from_rgb(100, 180, 240),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+/// One entry as a line of text, for Copy
+fn entry_text(entry: &LogEntry) -> String {
+    if entry.spans.is_empty() {
+        format!("{:>5} {}", entry.level, entry.message)
+    } else {
+        format!("{:>5} {}: {}", entry.level, entry.spans, entry.message)
+    }
+}
+
+/// Diagnostics Log window: the latest events under the current log filter
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_diagnostics;
+    let buffer = diagnostics().map(|d| &d.buffer);
+    let entries = buffer.map(|b| b.entries()).unwrap_or_default();
+
+    egui::Window::new(tr("diagnostics.title"))
+        .open(&mut open)
+        .default_size([640.0, 360.0])
+        .show(ctx, |ui| {
+            let Some(buffer) = buffer else {
+                ui.label(tr("diagnostics.off"));
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(trf("diagnostics.count", &[&entries.len(), &app.settings.log_filter]));
+                if ui.button(tr("common.copy")).clicked() {
+                    let text: Vec<String> = entries.iter().map(entry_text).collect();
+                    ui.ctx().copy_text(text.join("\n"));
+                }
+                if ui.button(tr("common.clear")).clicked() {
+                    buffer.clear();
+                }
+            });
+            ui.label(tr("diagnostics.change_filter"));
+            ui.separator();
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            egui::ScrollArea::both().stick_to_bottom(true).auto_shrink([false, false]).show_rows(
+                ui,
+                row_height,
+                entries.len(),
+                |ui, rows| {
+                    for entry in &entries[rows] {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(level_color(entry.level), egui::RichText::new(format!("{:>5}", entry.level)).monospace());
+                            if !entry.spans.is_empty() {
+                                ui.label(egui::RichText::new(&entry.spans).monospace().weak());
+                            }
+                            ui.label(egui::RichText::new(&entry.message).monospace());
+                        });
+                    }
+                },
+            );
+        });
+
+    app.show_diagnostics = open;
+}
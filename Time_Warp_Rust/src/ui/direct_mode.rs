@@ -0,0 +1,58 @@
+//! Direct mode on the Output tab: type a statement, press Enter, watch it run
+//!
+//! Logo statements typed here are recorded (see `interpreter::direct`), and
+//! the recording can be opened in a new tab as a Logo program or as BASIC
+//! `LINE` statements.
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+
+/// Name of the procedure "Copy as program" wraps the recording in
+const PROCEDURE_NAME: &str = "DRAWING";
+
+/// Command line and recording controls; disabled while a program runs or waits for input
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let busy = app.execution.is_busy();
+    ui.horizontal(|ui| {
+        ui.label("›");
+        let response = ui.add_enabled(
+            !busy,
+            egui::TextEdit::singleline(&mut app.direct_input)
+                .hint_text(tr("direct.hint"))
+                .desired_width(240.0),
+        );
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let command = std::mem::take(&mut app.direct_input);
+            // Keep the drawing restorable, as Run does, in case the command clears it
+            app.canvas_history.push(&app.turtle_state);
+            // Errors are reported in the output like a program's
+            let _ = app.interpreter.execute_direct(&command, &mut app.turtle_state);
+            response.request_focus();
+        }
+
+        let journal = &app.interpreter.journal;
+        let recorded = journal.kept_commands().count();
+        let mut program = None;
+        ui.add_enabled_ui(recorded > 0, |ui| {
+            if ui.button(tr("direct.copy_program")).on_hover_text(tr("direct.copy_program.hover")).clicked() {
+                let procedure = app.wrap_recording.then_some(PROCEDURE_NAME);
+                program = Some(("logo", journal.logo_program(procedure)));
+            }
+            ui.checkbox(&mut app.wrap_recording, trf("direct.wrap", &[&PROCEDURE_NAME]));
+            if ui.button(tr("direct.copy_basic")).on_hover_text(tr("direct.copy_basic.hover")).clicked() {
+                program = Some(("bas", journal.basic_program()));
+            }
+        });
+        let cleared = ui.add_enabled(!journal.is_empty(), egui::Button::new(tr("direct.clear"))).clicked();
+        ui.weak(if recorded == 1 { tr("direct.recorded_one").to_string() } else { trf("direct.recorded", &[&recorded]) });
+
+        if let Some((extension, source)) = program {
+            app.open_unsaved("recording", extension, source);
+        }
+        if cleared {
+            app.interpreter.journal.clear();
+        }
+    });
+}
@@ -1,23 +1,50 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::lesson;
+use crate::languages::reserved;
+use crate::ui::debugger;
+use crate::ui::folding;
+use crate::ui::line_edit;
+use crate::ui::output_diff::DIFF_TAB;
+use crate::ui::welcome::WELCOME_TAB;
+
+/// Left margin of the code editor, where fold markers are drawn
+const FOLD_GUTTER_WIDTH: f32 = 16.0;
+
+/// The code editor's widget id, where egui keeps its cursor
+fn editor_id() -> egui::Id {
+    egui::Id::new("code_editor")
+}
 
 pub fn render_tab_bar(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
     ui.horizontal(|ui| {
-        if ui.selectable_label(app.active_tab == 0, "📝 Editor").clicked() {
+        if ui.selectable_label(app.active_tab == 0, tr("tab.editor")).clicked() {
             app.active_tab = 0;
         }
-        if ui.selectable_label(app.active_tab == 1, "📊 Output & Graphics").clicked() {
+        if ui.selectable_label(app.active_tab == 1, tr("tab.output")).clicked() {
             app.active_tab = 1;
         }
-        if ui.selectable_label(app.active_tab == 2, "🐛 Debug").clicked() {
+        if ui.selectable_label(app.active_tab == 2, tr("tab.debug")).clicked() {
             app.active_tab = 2;
         }
-        if ui.selectable_label(app.active_tab == 3, "📁 Explorer").clicked() {
+        if app.kiosk_root.is_none() && ui.selectable_label(app.active_tab == 3, tr("tab.explorer")).clicked() {
             app.active_tab = 3;
         }
-        if ui.selectable_label(app.active_tab == 4, "❓ Help").clicked() {
+        if ui.selectable_label(app.active_tab == 4, tr("tab.help")).clicked() {
             app.active_tab = 4;
         }
+        if app.output_diff.pinned.is_some() && ui.selectable_label(app.active_tab == DIFF_TAB, tr("tab.diff")).clicked() {
+            app.active_tab = DIFF_TAB;
+        }
+        if app.welcome.open {
+            if ui.selectable_label(app.active_tab == WELCOME_TAB, tr("tab.welcome")).clicked() {
+                app.active_tab = WELCOME_TAB;
+            }
+            if ui.small_button("✖").on_hover_text(tr("welcome.close")).clicked() {
+                crate::ui::welcome::close(app);
+            }
+        }
     });
 }
 
@@ -48,13 +75,25 @@ pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
             let file = app.open_files.remove(idx);
             app.file_buffers.remove(&file);
             app.file_modified.remove(&file);
+            app.undo.forget(&file);
+            app.folded_blocks.remove(&file);
+            app.untrusted_files.remove(&file);
+            app.file_paths.remove(&file);
+            app.lossy_files.remove(&file);
+            app.bom_files.remove(&file);
+            app.file_line_endings.remove(&file);
+            if let Some(folder) = app.pak_folders.remove(&file) {
+                if app.pak_workspace.as_deref() == Some(folder.path()) {
+                    app.pak_workspace = None;
+                }
+            }
             if app.current_file_index >= app.open_files.len() && app.current_file_index > 0 {
                 app.current_file_index -= 1;
             }
         }
         
         if ui.button("➕").clicked() {
-            let filename = format!("untitled_{}.pilot", app.open_files.len());
+            let filename = crate::ui::undo::unused_tab_name(&app.open_files, "untitled", "pilot");
             app.file_buffers.insert(filename.clone(), String::new());
             app.open_files.push(filename);
             app.current_file_index = app.open_files.len() - 1;
@@ -62,49 +101,314 @@ pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
     });
     
     ui.separator();
+    render_open_notices(app, ui);
     
     // Code editor
     let mut code = app.current_code();
+    let file = app.current_file().cloned().unwrap_or_default();
     
+    let goto_line = app.pending_goto_line.take();
+    if let Some(line) = goto_line {
+        folding::reveal_lines(app, &file, [line]);
+    }
+    let folded = app.folded_blocks.get(&file).cloned().unwrap_or_default();
+    let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let color = ui.visuals().override_text_color.unwrap_or_else(|| ui.visuals().widgets.inactive.text_color());
+        let mut job = folding::layout_job(text, &folded, font_id, color);
+        job.wrap.max_width = wrap_width;
+        ui.fonts(|f| f.layout_job(job))
+    };
+    let editor_id = editor_id();
+    // An assessment whose time is up takes no more changes
+    let locked = crate::ui::assessment::locked(app);
+    // Line shortcuts record their own undo step; plain typing is recorded below
+    if !locked {
+        line_edit::handle_shortcuts(app, ui, editor_id, &mut code);
+    }
+    let indented = !locked && indent_with_spaces(ui, editor_id, &mut code, app.effective_settings().tab_width);
+    // Statements the lesson disabled and reserved words used as names, with why
+    let flagged: Vec<(usize, std::ops::Range<usize>, String)> = lesson::disabled_uses(&code, &app.disabled_statements())
+        .into_iter()
+        .map(|u| (u.line, u.columns, u.message))
+        .chain(reserved::reserved_uses(&code).into_iter().map(|u| (u.line, u.columns, u.message)))
+        .collect();
     egui::ScrollArea::vertical().show(ui, |ui| {
-        let response = ui.add(
-            egui::TextEdit::multiline(&mut code)
-                .font(egui::TextStyle::Monospace)
-                .desired_width(f32::INFINITY)
-                .desired_rows(30)
-                .code_editor()
-        );
+        let mut output = egui::TextEdit::multiline(&mut code)
+            .id(editor_id)
+            .font(egui::TextStyle::Monospace)
+            .desired_width(f32::INFINITY)
+            .desired_rows(30)
+            .code_editor()
+            .interactive(!locked)
+            .margin(egui::Margin { left: FOLD_GUTTER_WIDTH, right: 4.0, top: 2.0, bottom: 2.0 })
+            .layouter(&mut layouter)
+            .show(ui);
+        // Row of a 1-based line in screen coordinates (rows of folded lines have no height)
+        let line_rect = |code: &str, line: usize| {
+            let cursor = egui::text::CCursor::new(line_offset(code, line));
+            output.galley.pos_from_ccursor(cursor).translate(output.galley_pos.to_vec2())
+        };
+        
+        // Jump requested by Find in Files: place the cursor and scroll the line into view
+        if let Some(line) = goto_line {
+            let cursor = egui::text::CCursor::new(line_offset(&code, line));
+            output.state.cursor.set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+            output.state.store(ui.ctx(), output.response.id);
+            output.response.request_focus();
+            ui.scroll_to_rect(line_rect(&code, line), Some(egui::Align::Center));
+        }
+        
+        // Faint band over statements that raised errors in the last run of this file
+        if app.run_worker.is_none() && app.run_file.as_ref() == app.current_file() {
+            let rect = output.response.rect;
+            for span in &app.interpreter.error_spans {
+                let top = line_rect(&code, *span.start()).top();
+                let bottom = line_rect(&code, *span.end()).bottom();
+                let band = egui::Rect::from_x_y_ranges(rect.x_range(), top..=bottom);
+                ui.painter().rect_filled(band, 0.0, egui::Color32::from_rgba_unmultiplied(230, 90, 90, 36));
+            }
+        }
+        
+        // Wavy underline under each flagged word, with the reason on hover
+        for (index, (line, columns, message)) in flagged.iter().enumerate() {
+            let offset = line_offset(&code, *line);
+            let at = |column: usize| output.galley.pos_from_ccursor(egui::text::CCursor::new(offset + column)).translate(output.galley_pos.to_vec2());
+            let (start, end) = (at(columns.start), at(columns.end));
+            if start.height() <= 0.0 {
+                continue;
+            }
+            let word = egui::Rect::from_min_max(start.left_top(), egui::pos2(end.left(), start.bottom()));
+            paint_squiggle(ui.painter(), word.left()..=word.right(), word.bottom() - 1.0, egui::Color32::from_rgb(220, 60, 60));
+            ui.interact(word, output.response.id.with(("flagged", index)), egui::Sense::hover()).on_hover_text(message);
+        }
+        
+        // Edit → Evaluate Selection's result after the line it came from
+        if let Some((annotation, opacity)) = crate::ui::inline_eval::current(app, ui.ctx()).filter(|(a, _)| a.file == file) {
+            let end = line_offset(&code, annotation.line) + code.lines().nth(annotation.line - 1).unwrap_or("").chars().count();
+            let at = output.galley.pos_from_ccursor(egui::text::CCursor::new(end)).translate(output.galley_pos.to_vec2());
+            if at.height() > 0.0 {
+                ui.painter().text(
+                    egui::pos2(at.right() + 12.0, at.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    format!("⇒ {}", annotation.result.text),
+                    egui::TextStyle::Monospace.resolve(ui.style()),
+                    annotation.color(ui, opacity),
+                );
+            }
+        }
+        
+        // Paused at a breakpoint, a variable under the pointer shows its value and where it was set
+        if app.interpreter.breakpoints.paused_at.is_some() && app.run_file.as_ref() == app.current_file() {
+            if let Some(pointer) = output.response.hover_pos() {
+                let index = output.galley.cursor_from_pos(pointer - output.galley_pos).ccursor.index;
+                if let Some(text) = word_at(&code, index).and_then(|word| debugger::inspect_text(&app.interpreter, word)) {
+                    output.response.clone().on_hover_text_at_pointer(text);
+                }
+            }
+        }
         
-        if response.changed() {
+        // Fold markers in the gutter, and a summary after each folded opener
+        let mut toggled = None;
+        for block in folding::fold_blocks(&code) {
+            let is_folded = folded.contains(&block.key);
+            let row = line_rect(&code, block.start);
+            let center = egui::pos2(output.response.rect.left() + FOLD_GUTTER_WIDTH / 2.0, row.center().y);
+            let marker = egui::Rect::from_center_size(center, egui::vec2(FOLD_GUTTER_WIDTH, row.height()));
+            let response = ui.interact(marker, output.response.id.with(&block.key), egui::Sense::click());
+            let color = if response.hovered() { ui.visuals().strong_text_color() } else { ui.visuals().weak_text_color() };
+            folding::paint_marker(ui.painter(), center, is_folded, color);
+            if response.clicked() {
+                toggled = Some(block.key.clone());
+            }
+            if is_folded {
+                let opener_end = line_offset(&code, block.start) + code.lines().nth(block.start - 1).unwrap_or("").chars().count();
+                let end = output.galley.pos_from_ccursor(egui::text::CCursor::new(opener_end)).translate(output.galley_pos.to_vec2());
+                ui.painter().text(
+                    egui::pos2(end.right() + 12.0, row.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    trf("editor.folded_lines", &[&(block.end - block.start)]),
+                    egui::TextStyle::Monospace.resolve(ui.style()),
+                    ui.visuals().weak_text_color(),
+                );
+            }
+        }
+        if let Some(key) = toggled {
+            folding::toggle(app, &file, &key);
+        }
+        
+        if output.response.changed() || indented {
             app.set_current_code(code);
+            // Line numbers may have shifted; the markers return with the next run
+            app.interpreter.error_spans.clear();
+            app.inline_result = None;
         }
     });
 }
 
+/// A zigzag line along `x` at height `y`, as under a misspelled word
+fn paint_squiggle(painter: &egui::Painter, x: std::ops::RangeInclusive<f32>, y: f32, color: egui::Color32) {
+    const STEP: f32 = 3.0;
+    let mut points = Vec::new();
+    let mut left = *x.start();
+    let mut up = false;
+    while left < *x.end() {
+        points.push(egui::pos2(left, if up { y - STEP / 2.0 } else { y + STEP / 2.0 }));
+        left += STEP;
+        up = !up;
+    }
+    points.push(egui::pos2(*x.end(), y));
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, color)));
+}
+
+/// Replace the editor's selection with `tab_width` spaces when Tab is pressed in it
+///
+/// Runs before the editor is shown, so the editor never sees the key and
+/// inserts no tab character. Returns whether `code` changed.
+fn indent_with_spaces(ui: &egui::Ui, id: egui::Id, code: &mut String, tab_width: usize) -> bool {
+    if !ui.memory(|m| m.has_focus(id)) || !ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+        return false;
+    }
+    let Some(mut state) = egui::TextEdit::load_state(ui.ctx(), id) else { return false };
+    let Some(range) = state.cursor.char_range() else { return false };
+    let [start, end] = range.sorted();
+    let byte = |c: egui::text::CCursor| code.char_indices().nth(c.index).map_or(code.len(), |(b, _)| b);
+    let (from, to) = (byte(start), byte(end));
+    code.replace_range(from..to, &" ".repeat(tab_width));
+    let cursor = egui::text::CCursor::new(start.index + tab_width);
+    state.cursor.set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+    state.store(ui.ctx(), id);
+    true
+}
+
+/// Banners for a tab opened from outside the workspace or with its bytes replaced
+fn render_open_notices(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let Some(file) = app.current_file().cloned() else { return };
+    if app.untrusted_files.contains(&file) {
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(ui.visuals().warn_fg_color, trf("editor.untrusted", &[&file]));
+            if ui.button(tr("editor.trust")).clicked() {
+                app.untrusted_files.remove(&file);
+            }
+        });
+    }
+    if app.lossy_files.contains(&file) {
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(ui.visuals().warn_fg_color, trf("editor.lossy", &[&file]));
+            if ui.button(tr("common.dismiss")).clicked() {
+                app.lossy_files.remove(&file);
+            }
+        });
+    }
+    if app.bom_files.contains(&file) {
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(ui.visuals().warn_fg_color, trf("editor.bom", &[&file]));
+            if ui.button(tr("common.dismiss")).clicked() {
+                app.bom_files.remove(&file);
+            }
+        });
+    }
+}
+
+/// Insert a picked file's text at the editor's cursor as one undo step (Edit → Insert File at Cursor)
+///
+/// Without a cursor the text goes at the end. Text inserted at the start of
+/// a line gets a line break after it, so the line it lands on stays intact.
+pub fn insert_file_at_cursor(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(file) = app.current_file().cloned() else { return };
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(tr("file.filter.programs"), &["pilot", "pil", "bas", "basic", "logo", "lgo", "tc"])
+        .add_filter(tr("file.filter.all"), &["*"])
+        .pick_file()
+    else {
+        return;
+    };
+    let mut text = match crate::utils::safe_open::read_text(&path, crate::utils::safe_open::MAX_OPEN_BYTES) {
+        Ok(opened) => crate::utils::line_endings::normalize(&opened.text).text,
+        Err(e) => {
+            app.error_message = Some(trf("editor.insert_failed", &[&path.display(), &e]));
+            return;
+        }
+    };
+    let mut code = app.current_code();
+    let mut state = egui::TextEdit::load_state(ctx, editor_id()).unwrap_or_default();
+    let at = state.cursor.char_range().map_or(code.chars().count(), |range| range.sorted()[0].index);
+    let byte = code.char_indices().nth(at).map_or(code.len(), |(b, _)| b);
+    if (byte == 0 || code[..byte].ends_with('\n')) && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    code.insert_str(byte, &text);
+    app.apply_buffer_edit(&file, code, tr("undo.insert_file"));
+    let cursor = egui::text::CCursor::new(at + text.chars().count());
+    state.cursor.set_char_range(Some(egui::text::CCursorRange::one(cursor)));
+    state.store(ctx, editor_id());
+}
+
+/// Byte offset in `code` of the editor's cursor (the start of a selection)
+pub fn cursor_byte(ctx: &egui::Context, code: &str) -> usize {
+    let state = egui::TextEdit::load_state(ctx, editor_id()).unwrap_or_default();
+    let at = state.cursor.char_range().map_or(0, |range| range.sorted()[0].index);
+    code.char_indices().nth(at).map_or(code.len(), |(b, _)| b)
+}
+
+/// The editor's selection, or the cursor's whole line when nothing is selected, with the 1-based line it ends on
+pub fn selection_or_line(ctx: &egui::Context, code: &str) -> Option<(String, usize)> {
+    let state = egui::TextEdit::load_state(ctx, editor_id())?;
+    let [start, end] = state.cursor.char_range()?.sorted().map(|c| code.char_indices().nth(c.index).map_or(code.len(), |(b, _)| b));
+    let (start, end) = if start == end {
+        (code[..start].rfind('\n').map_or(0, |n| n + 1), code[start..].find('\n').map_or(code.len(), |n| start + n))
+    } else {
+        (start, end)
+    };
+    let text = &code[start..end];
+    let line = code[..start + text.trim_end().len()].matches('\n').count() + 1;
+    Some((text.to_string(), line))
+}
+
+/// Char offset of the start of 1-based `line`
+fn line_offset(code: &str, line: usize) -> usize {
+    code.lines().take(line.saturating_sub(1)).map(|l| l.chars().count() + 1).sum()
+}
+
+/// The name (with any `$ % ! #` suffix) at or just before char `index`
+fn word_at(code: &str, index: usize) -> Option<&str> {
+    let is_name = |c: char| c.is_alphanumeric() || c == '_';
+    let chars: Vec<(usize, char)> = code.char_indices().collect();
+    let at = [Some(index), index.checked_sub(1)].into_iter().flatten().find(|&i| chars.get(i).is_some_and(|&(_, c)| is_name(c)))?;
+    let start = (0..=at).rev().take_while(|&i| is_name(chars[i].1)).last()?;
+    let mut end = (at..chars.len()).take_while(|&i| is_name(chars[i].1)).last()? + 1;
+    if chars.get(end).is_some_and(|&(_, c)| matches!(c, '$' | '%' | '!' | '#')) {
+        end += 1;
+    }
+    let byte = |i: usize| chars.get(i).map_or(code.len(), |&(b, _)| b);
+    let word = &code[byte(start)..byte(end)];
+    word.starts_with(|c: char| !c.is_ascii_digit()).then_some(word)
+}
+
 pub fn render_find_replace(app: &mut TimeWarpApp, ctx: &egui::Context) {
     let mut should_find = false;
     let mut should_replace = false;
     let mut should_replace_all = false;
     
-    egui::Window::new("Find/Replace")
+    egui::Window::new(tr("find.title"))
         .open(&mut app.show_find_replace)
         .show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label("Find:");
+                ui.label(tr("find.find"));
                 ui.text_edit_singleline(&mut app.find_text);
             });
             ui.horizontal(|ui| {
-                ui.label("Replace:");
+                ui.label(tr("find.replace_with"));
                 ui.text_edit_singleline(&mut app.replace_text);
             });
             ui.horizontal(|ui| {
-                if ui.button("Find Next").clicked() {
+                if ui.button(tr("find.next")).clicked() {
                     should_find = true;
                 }
-                if ui.button("Replace").clicked() {
+                if ui.button(tr("find.replace")).clicked() {
                     should_replace = true;
                 }
-                if ui.button("Replace All").clicked() {
+                if ui.button(tr("find.replace_all")).clicked() {
                     should_replace_all = true;
                 }
             });
@@ -126,10 +430,12 @@ fn find_next(app: &mut TimeWarpApp) {
         return;
     }
     let code = app.current_code();
-    if code.contains(&app.find_text) {
-        app.error_message = Some(format!("Found '{}' in code", app.find_text));
+    if let Some(pos) = code.find(&app.find_text) {
+        // Show the match, unfolding the block around it
+        app.pending_goto_line = Some(code[..pos].matches('\n').count() + 1);
+        app.error_message = Some(trf("find.found", &[&app.find_text]));
     } else {
-        app.error_message = Some(format!("'{}' not found", app.find_text));
+        app.error_message = Some(trf("find.not_found", &[&app.find_text]));
     }
 }
 
@@ -140,10 +446,12 @@ fn replace_current(app: &mut TimeWarpApp) {
     let code = app.current_code();
     if let Some(pos) = code.find(&app.find_text) {
         let new_code = format!("{}{}{}", &code[..pos], &app.replace_text, &code[pos + app.find_text.len()..]);
-        app.set_current_code(new_code);
-        app.error_message = Some("Replaced one occurrence".to_string());
+        if let Some(file) = app.current_file().cloned() {
+            app.apply_buffer_edit(&file, new_code, tr("find.replace"));
+        }
+        app.error_message = Some(tr("find.replaced_one").to_string());
     } else {
-        app.error_message = Some(format!("'{}' not found", app.find_text));
+        app.error_message = Some(trf("find.not_found", &[&app.find_text]));
     }
 }
 
@@ -155,9 +463,11 @@ fn replace_all(app: &mut TimeWarpApp) {
     let count = code.matches(&app.find_text).count();
     if count > 0 {
         let new_code = code.replace(&app.find_text, &app.replace_text);
-        app.set_current_code(new_code);
-        app.error_message = Some(format!("Replaced {} occurrence(s)", count));
+        if let Some(file) = app.current_file().cloned() {
+            app.apply_buffer_edit(&file, new_code, tr("find.replace_all"));
+        }
+        app.error_message = Some(trf("find.replaced_many", &[&count]));
     } else {
-        app.error_message = Some(format!("'{}' not found", app.find_text));
+        app.error_message = Some(trf("find.not_found", &[&app.find_text]));
     }
 }
@@ -0,0 +1,191 @@
+//! Where the current run is, and which run controls apply to it
+//!
+//! Run, Step, Stop, F5, and an answered INPUT prompt all go through
+//! [`ExecutionSession`], so a second Run can never load a program into an
+//! interpreter that is still running on its worker or paused at a prompt.
+//! Run while a program runs asks before restarting it; Run while one waits
+//! for input or sits between steps aborts that run first.
+
+/// State of the run in the IDE
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionState {
+    /// Nothing has run yet
+    #[default]
+    Idle,
+    /// Running on the worker thread
+    Running,
+    /// Paused at an INPUT, `A:`, or `INPUT$` prompt
+    WaitingInput,
+    /// Stepping, between two steps, or stopped at a breakpoint
+    Paused,
+    /// The last run ended, was stopped, or was aborted
+    Finished,
+}
+
+/// Something that happens to a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// A program is loaded and handed to the worker
+    Start,
+    /// Run ▸ Step ran a step that did not end the program
+    Step,
+    /// The program stopped at a prompt
+    AwaitInput,
+    /// The program stopped at a breakpoint
+    Break,
+    /// The prompt was answered, or Continue pressed at a breakpoint, and the worker carries on
+    Resume,
+    /// The program ended, stopped, or failed
+    Finish,
+    /// A paused program was thrown away (Stop, or Run to start again)
+    Abort,
+}
+
+/// What Run does in the current state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunAction {
+    Start,
+    /// Ask first, then stop the running program and start again
+    ConfirmRestart,
+    /// Abort the paused program, then start
+    AbortThenStart,
+}
+
+/// The state a run moves to on `event`, or `None` when `event` cannot happen in `state`
+pub fn next_state(state: SessionState, event: SessionEvent) -> Option<SessionState> {
+    use SessionEvent::*;
+    use SessionState::*;
+    match (state, event) {
+        (Idle | Finished, Start) => Some(Running),
+        (Idle | Finished | Paused, Step) => Some(Paused),
+        (Running | Paused, AwaitInput) => Some(WaitingInput),
+        (Running, Break) => Some(Paused),
+        (WaitingInput | Paused, Resume) => Some(Running),
+        (Running | Paused, Finish) => Some(Finished),
+        (WaitingInput | Paused, Abort) => Some(Finished),
+        _ => None,
+    }
+}
+
+/// The run in progress, if any, and a restart asked for while it runs
+#[derive(Debug, Default)]
+pub struct ExecutionSession {
+    state: SessionState,
+    restart_pending: bool,
+}
+
+impl ExecutionSession {
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    /// Move on for `event`; an event the state does not allow changes nothing and returns false
+    pub fn apply(&mut self, event: SessionEvent) -> bool {
+        match next_state(self.state, event) {
+            Some(state) => {
+                self.state = state;
+                true
+            }
+            None => {
+                tracing::debug!(state = ?self.state, event = ?event, "run event ignored");
+                false
+            }
+        }
+    }
+
+    pub fn run_action(&self) -> RunAction {
+        match self.state {
+            SessionState::Idle | SessionState::Finished => RunAction::Start,
+            SessionState::Running => RunAction::ConfirmRestart,
+            SessionState::WaitingInput | SessionState::Paused => RunAction::AbortThenStart,
+        }
+    }
+
+    /// Whether a program is running or waiting for input (direct mode and Step wait)
+    pub fn is_busy(&self) -> bool {
+        matches!(self.state, SessionState::Running | SessionState::WaitingInput)
+    }
+
+    pub fn can_step(&self) -> bool {
+        next_state(self.state, SessionEvent::Step).is_some()
+    }
+
+    pub fn can_stop(&self) -> bool {
+        matches!(self.state, SessionState::Running | SessionState::WaitingInput | SessionState::Paused)
+    }
+
+    /// Start again once the running program has stopped
+    pub fn request_restart(&mut self) {
+        self.restart_pending = self.state == SessionState::Running;
+    }
+
+    /// Whether a restart was asked for; clears the request
+    pub fn take_restart(&mut self) -> bool {
+        std::mem::take(&mut self.restart_pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SessionEvent::*;
+    use SessionState::*;
+
+    const STATES: [SessionState; 5] = [Idle, Running, WaitingInput, Paused, Finished];
+    const EVENTS: [SessionEvent; 7] = [Start, Step, AwaitInput, Break, Resume, Finish, Abort];
+
+    #[test]
+    fn test_transition_table() {
+        let legal = [
+            (Idle, Start, Running),
+            (Finished, Start, Running),
+            (Idle, Step, Paused),
+            (Finished, Step, Paused),
+            (Paused, Step, Paused),
+            (Running, AwaitInput, WaitingInput),
+            (Paused, AwaitInput, WaitingInput),
+            (Running, Break, Paused),
+            (WaitingInput, Resume, Running),
+            (Paused, Resume, Running),
+            (Running, Finish, Finished),
+            (Paused, Finish, Finished),
+            (WaitingInput, Abort, Finished),
+            (Paused, Abort, Finished),
+        ];
+        for state in STATES {
+            for event in EVENTS {
+                let expected = legal.iter().find(|(s, e, _)| *s == state && *e == event).map(|&(_, _, to)| to);
+                assert_eq!(next_state(state, event), expected, "{:?} on {:?}", event, state);
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_never_starts_over_a_live_run() {
+        let mut session = ExecutionSession::default();
+        assert_eq!(session.run_action(), RunAction::Start);
+        assert!(session.apply(Start));
+        assert_eq!(session.run_action(), RunAction::ConfirmRestart);
+        assert!(!session.apply(Start), "a second Start while running is refused");
+        assert_eq!(session.state(), Running);
+        assert!(!session.can_step());
+
+        assert!(session.apply(AwaitInput));
+        assert_eq!(session.run_action(), RunAction::AbortThenStart);
+        assert!(session.is_busy() && session.can_stop());
+        assert!(session.apply(Abort));
+        assert_eq!(session.run_action(), RunAction::Start);
+        assert!(!session.can_stop());
+    }
+
+    #[test]
+    fn test_restart_is_only_pending_while_running() {
+        let mut session = ExecutionSession::default();
+        session.request_restart();
+        assert!(!session.take_restart());
+        session.apply(Start);
+        session.request_restart();
+        assert!(session.take_restart());
+        assert!(!session.take_restart(), "taken once");
+    }
+}
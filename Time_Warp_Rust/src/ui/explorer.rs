@@ -1,12 +1,13 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::i18n::tr;
 
 pub fn render(_app: &TimeWarpApp, ui: &mut egui::Ui) {
-    ui.heading("File Explorer");
+    ui.heading(tr("explorer.title"));
     ui.separator();
     
-    ui.label("File explorer coming soon:");
-    ui.label("• Project tree view");
-    ui.label("• File operations");
-    ui.label("• Drag and drop");
+    ui.label(tr("explorer.soon"));
+    ui.label(tr("explorer.tree"));
+    ui.label(tr("explorer.operations"));
+    ui.label(tr("explorer.drag_drop"));
 }
@@ -0,0 +1,72 @@
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::graphics::simplify_path;
+use crate::ui::debugger::save_text;
+use crate::utils::data_export::{path_to_csv, path_to_json};
+
+/// Export Path options: optional collinear-segment merging
+pub struct ExportPathPanel {
+    pub simplify: bool,
+    /// Largest deviation (turtle units) still treated as a straight line
+    pub tolerance: f32,
+}
+
+impl Default for ExportPathPanel {
+    fn default() -> Self {
+        Self { simplify: true, tolerance: 0.5 }
+    }
+}
+
+/// Export Path window: segment counts, simplify option, CSV/JSON save
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_export_path;
+    // Some(true) = JSON, Some(false) = CSV
+    let mut export: Option<bool> = None;
+
+    let panel = &mut app.export_path;
+    let total = app.turtle_state.lines.len();
+    let (lines, merged) = if panel.simplify {
+        simplify_path(&app.turtle_state.lines, panel.tolerance)
+    } else {
+        (app.turtle_state.lines.clone(), 0)
+    };
+
+    egui::Window::new(tr("export_path.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr("export_path.about"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut panel.simplify, tr("export_path.simplify"));
+                ui.add_enabled(
+                    panel.simplify,
+                    egui::DragValue::new(&mut panel.tolerance).range(0.0..=10.0).speed(0.05).prefix(tr("export_path.tolerance")),
+                );
+            });
+            if panel.simplify {
+                ui.label(trf("export_path.simplified", &[&total, &lines.len(), &merged]));
+            } else {
+                ui.label(trf("export_path.segments", &[&total]));
+            }
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(!lines.is_empty(), |ui| {
+                    if ui.button(tr("debugger.export_csv")).clicked() {
+                        export = Some(false);
+                    }
+                    if ui.button(tr("debugger.export_json")).clicked() {
+                        export = Some(true);
+                    }
+                });
+            });
+        });
+
+    app.show_export_path = open;
+    match export {
+        Some(true) => save_text(app, "path.json", "JSON", "json", &path_to_json(&lines)),
+        Some(false) => save_text(app, "path.csv", "CSV", "csv", &path_to_csv(&lines)),
+        None => {}
+    }
+}
@@ -0,0 +1,247 @@
+use eframe::egui;
+use std::sync::mpsc::{self, Receiver};
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::utils::workspace_search::{
+    plan_replacements, search_workspace, FileReplacement, FileResults, OpenBuffer, SearchOptions, SearchSource,
+};
+
+/// Messages from the background search thread
+enum SearchEvent {
+    Found(FileResults),
+    Failed(String),
+    Done,
+}
+
+/// State of the Find in Files window
+#[derive(Default)]
+pub struct FindInFilesPanel {
+    pub options: SearchOptions,
+    pub replacement: String,
+    pub results: Vec<FileResults>,
+    pub error: Option<String>,
+    /// Pending replace-in-files preview; the flag selects the file for Apply
+    pub plans: Vec<(FileReplacement, bool)>,
+    receiver: Option<Receiver<SearchEvent>>,
+}
+
+impl FindInFilesPanel {
+    pub fn is_searching(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Drain results streamed by the search thread
+    fn poll(&mut self) {
+        let Some(rx) = &self.receiver else { return };
+        let mut finished = false;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                SearchEvent::Found(result) => self.results.push(result),
+                SearchEvent::Failed(e) => self.error = Some(e),
+                SearchEvent::Done => finished = true,
+            }
+        }
+        if finished {
+            self.receiver = None;
+        }
+    }
+}
+
+fn buffer_snapshot(app: &TimeWarpApp) -> Vec<OpenBuffer> {
+    app.open_files
+        .iter()
+        .map(|name| OpenBuffer {
+            name: name.clone(),
+            path: app.file_paths.get(name).cloned(),
+            text: app.file_buffers.get(name).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn start_search(app: &mut TimeWarpApp) {
+    let buffers = buffer_snapshot(app);
+    let root = app.project_root();
+    let options = app.find_in_files.options.clone();
+    let (tx, rx) = mpsc::channel();
+
+    let panel = &mut app.find_in_files;
+    panel.results.clear();
+    panel.plans.clear();
+    panel.error = None;
+    panel.receiver = Some(rx);
+    std::thread::spawn(move || {
+        let outcome = search_workspace(&buffers, root.as_deref(), &options, |result| {
+            tx.send(SearchEvent::Found(result)).is_ok()
+        });
+        if let Err(e) = outcome {
+            let _ = tx.send(SearchEvent::Failed(e.to_string()));
+        }
+        let _ = tx.send(SearchEvent::Done);
+    });
+}
+
+fn preview_replace(app: &mut TimeWarpApp) {
+    let buffers = buffer_snapshot(app);
+    let root = app.project_root();
+    let panel = &mut app.find_in_files;
+    match plan_replacements(&buffers, root.as_deref(), &panel.options, &panel.replacement) {
+        Ok(plans) => {
+            panel.error = None;
+            panel.plans = plans.into_iter().map(|p| (p, true)).collect();
+        }
+        Err(e) => panel.error = Some(e.to_string()),
+    }
+}
+
+fn apply_replace(app: &mut TimeWarpApp) {
+    let plans = std::mem::take(&mut app.find_in_files.plans);
+    let mut changed = 0;
+    let mut failures = Vec::new();
+    for (plan, selected) in plans {
+        if !selected {
+            continue;
+        }
+        match &plan.source {
+            SearchSource::Buffer(name) => {
+                app.apply_buffer_edit(name, plan.replaced, tr("undo.replace_in_files"));
+                changed += plan.count;
+            }
+            SearchSource::File(path) => match std::fs::write(path, &plan.replaced) {
+                Ok(()) => changed += plan.count,
+                Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+            },
+        }
+    }
+    app.error_message = Some(if failures.is_empty() {
+        trf("find.replaced_many", &[&changed])
+    } else {
+        trf("find.replaced_with_failures", &[&changed, &failures.join("\n")])
+    });
+    start_search(app);
+}
+
+/// Switch to (or open) the result's file and jump to `line`
+fn open_result(app: &mut TimeWarpApp, source: &SearchSource, line: usize) {
+    match source {
+        SearchSource::Buffer(name) => {
+            if let Some(idx) = app.open_files.iter().position(|f| f == name) {
+                app.current_file_index = idx;
+            }
+        }
+        SearchSource::File(path) => {
+            if let Err(e) = app.open_path(path) {
+                app.error_message = Some(trf("file.open_path_failed", &[&path.display(), &e]));
+                return;
+            }
+        }
+    }
+    app.active_tab = 0;
+    app.pending_goto_line = Some(line);
+}
+
+/// Find in Files window: query/options, streamed results, and replace preview
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    app.find_in_files.poll();
+    if app.find_in_files.is_searching() {
+        ctx.request_repaint();
+    }
+
+    let mut open = app.show_find_in_files;
+    let mut search = false;
+    let mut preview = false;
+    let mut apply = false;
+    let mut goto: Option<(SearchSource, usize)> = None;
+
+    egui::Window::new(tr("find_in_files.title"))
+        .open(&mut open)
+        .default_width(560.0)
+        .show(ctx, |ui| {
+            let panel = &mut app.find_in_files;
+            egui::Grid::new("find_in_files_grid").num_columns(2).show(ui, |ui| {
+                ui.label(tr("find.find"));
+                let response = ui.add(egui::TextEdit::singleline(&mut panel.options.query).desired_width(360.0));
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    search = true;
+                }
+                ui.end_row();
+                ui.label(tr("find.replace_with"));
+                ui.add(egui::TextEdit::singleline(&mut panel.replacement).desired_width(360.0));
+                ui.end_row();
+                ui.label(tr("find_in_files.files"));
+                ui.add(egui::TextEdit::singleline(&mut panel.options.file_glob).hint_text("*.bas, *.logo").desired_width(360.0));
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut panel.options.case_sensitive, tr("find_in_files.match_case"));
+                ui.checkbox(&mut panel.options.regex, tr("find_in_files.regex"));
+                ui.separator();
+                if ui.add_enabled(!panel.is_searching(), egui::Button::new(tr("find_in_files.search"))).clicked() {
+                    search = true;
+                }
+                if ui.button(tr("find_in_files.preview")).clicked() {
+                    preview = true;
+                }
+                if panel.is_searching() {
+                    ui.spinner();
+                }
+            });
+            if let Some(e) = &panel.error {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                if !panel.plans.is_empty() {
+                    ui.strong(tr("find_in_files.preview_heading"));
+                    for (plan, selected) in &mut panel.plans {
+                        ui.checkbox(selected, trf("find_in_files.plan", &[&plan.source.display_name(), &plan.count]));
+                        for (line, before, after) in plan.changes.iter().take(20) {
+                            ui.monospace(format!("  {:>4}: {}", line, before));
+                            ui.monospace(format!("     → {}", after));
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("find_in_files.apply")).clicked() {
+                            apply = true;
+                        }
+                        if ui.button(tr("common.cancel")).clicked() {
+                            panel.plans.clear();
+                        }
+                    });
+                    return;
+                }
+
+                let total: usize = panel.results.iter().map(|r| r.matches.len()).sum();
+                if !panel.results.is_empty() || !panel.is_searching() {
+                    ui.label(trf("find_in_files.matches", &[&total, &panel.results.len()]));
+                }
+                for result in &panel.results {
+                    egui::CollapsingHeader::new(format!("{} ({})", result.source.display_name(), result.matches.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for m in &result.matches {
+                                let label = format!("{:>4}: {}", m.line, m.preview);
+                                if ui.selectable_label(false, egui::RichText::new(label).monospace()).clicked() {
+                                    goto = Some((result.source.clone(), m.line));
+                                }
+                            }
+                        });
+                }
+            });
+        });
+
+    app.show_find_in_files = open;
+    if search && !app.find_in_files.options.query.is_empty() {
+        start_search(app);
+    }
+    if preview {
+        preview_replace(app);
+    }
+    if apply {
+        apply_replace(app);
+    }
+    if let Some((source, line)) = goto {
+        open_result(app, &source, line);
+    }
+}
@@ -0,0 +1,231 @@
+//! Code folding in the editor
+//!
+//! Foldable blocks are Logo `TO … END` procedures, multi-line BASIC
+//! `DEF FN … FNEND` functions and regions between `REM {` and `REM }`
+//! comments. Folding is only a view: the editor's layouter gives the hidden
+//! lines zero height, and the buffer text is never changed. Folded blocks are
+//! remembered per file by [`FoldBlock::key`], so a fold survives edits to
+//! the lines around it.
+
+use std::collections::{HashMap, HashSet};
+
+use eframe::egui::{self, text::LayoutJob, Color32, FontId, TextFormat};
+
+use crate::app::TimeWarpApp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldKind {
+    /// Logo `TO name` … `END`
+    Procedure,
+    /// BASIC `DEF FN` without `=` … `FNEND` / `END DEF`
+    Function,
+    /// `REM {` … `REM }`
+    Region,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldBlock {
+    pub kind: FoldKind,
+    /// 1-based line of the opener, which stays visible when folded
+    pub start: usize,
+    /// 1-based line of the closer
+    pub end: usize,
+    /// The opener without its BASIC line number, normalised, with `#n` on the nth repeat
+    pub key: String,
+}
+
+impl FoldBlock {
+    /// Whether folding this block hides 1-based `line`
+    pub fn hides(&self, line: usize) -> bool {
+        line > self.start && line <= self.end
+    }
+}
+
+/// A line's statement without its BASIC line number, upper-cased with single spaces
+fn statement(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let body = trimmed.trim_start_matches(|c: char| c.is_ascii_digit());
+    let body = if body.len() < trimmed.len() && !body.is_empty() && !body.starts_with(char::is_whitespace) {
+        trimmed
+    } else {
+        body
+    };
+    body.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase()
+}
+
+/// `{` or `}` for `REM {` / `REM }` region markers
+fn region_marker(stmt: &str) -> Option<char> {
+    stmt.strip_prefix("REM")?.trim_start().chars().next().filter(|c| matches!(c, '{' | '}'))
+}
+
+fn opener(stmt: &str) -> Option<FoldKind> {
+    if stmt.starts_with("TO ") {
+        Some(FoldKind::Procedure)
+    } else if stmt.starts_with("DEF FN") && !stmt.contains('=') {
+        Some(FoldKind::Function)
+    } else if region_marker(stmt) == Some('{') {
+        Some(FoldKind::Region)
+    } else {
+        None
+    }
+}
+
+fn closes(stmt: &str, kind: FoldKind) -> bool {
+    match kind {
+        FoldKind::Procedure => stmt == "END",
+        FoldKind::Function => stmt == "FNEND" || stmt == "END DEF",
+        FoldKind::Region => region_marker(stmt) == Some('}'),
+    }
+}
+
+/// Every foldable block in `code`, ordered by first line
+///
+/// A closer ends the innermost open block of its kind; blocks opened inside
+/// it and never closed are dropped, as are blocks still open at the end.
+pub fn fold_blocks(code: &str) -> Vec<FoldBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Vec<(FoldKind, usize, String)> = Vec::new();
+    let mut repeats: HashMap<String, usize> = HashMap::new();
+    for (index, line) in code.lines().enumerate() {
+        let stmt = statement(line);
+        if let Some(pos) = open.iter().rposition(|(kind, ..)| closes(&stmt, *kind)) {
+            if let Some((kind, start, key)) = open.drain(pos..).next() {
+                blocks.push(FoldBlock { kind, start, end: index + 1, key });
+            }
+        } else if let Some(kind) = opener(&stmt) {
+            let count = repeats.entry(stmt.clone()).or_insert(0);
+            *count += 1;
+            let key = if *count == 1 { stmt } else { format!("{}#{}", stmt, count) };
+            open.push((kind, index + 1, key));
+        }
+    }
+    blocks.sort_by_key(|b| b.start);
+    blocks
+}
+
+/// Editor layout of `code` with the lines inside folded blocks collapsed to zero height
+pub fn layout_job(code: &str, folded: &HashSet<String>, font_id: FontId, color: Color32) -> LayoutJob {
+    let hiding: Vec<FoldBlock> = fold_blocks(code).into_iter().filter(|b| folded.contains(&b.key)).collect();
+    let visible = TextFormat::simple(font_id, color);
+    let hidden = TextFormat { color: Color32::TRANSPARENT, line_height: Some(0.0), ..visible.clone() };
+    let mut job = LayoutJob::default();
+    for (index, line) in code.split_inclusive('\n').enumerate() {
+        let format = if hiding.iter().any(|b| b.hides(index + 1)) { &hidden } else { &visible };
+        job.append(line, 0.0, format.clone());
+    }
+    // The empty last line (or empty buffer) keeps its height so the cursor shows there
+    if code.is_empty() || code.ends_with('\n') {
+        job.append("", 0.0, visible);
+    }
+    job
+}
+
+/// Fold or unfold the block with `key` in `file`
+pub fn toggle(app: &mut TimeWarpApp, file: &str, key: &str) {
+    let folded = app.folded_blocks.entry(file.to_string()).or_default();
+    if !folded.remove(key) {
+        folded.insert(key.to_string());
+    }
+}
+
+/// Unfold every block in `file` that hides one of `lines` (1-based)
+///
+/// Used when the cursor is sent somewhere: go to line, a search match, error markers.
+pub fn reveal_lines(app: &mut TimeWarpApp, file: &str, lines: impl IntoIterator<Item = usize>) {
+    let (Some(folded), Some(code)) = (app.folded_blocks.get_mut(file), app.file_buffers.get(file)) else {
+        return;
+    };
+    if folded.is_empty() {
+        return;
+    }
+    let blocks = fold_blocks(code);
+    for line in lines {
+        for block in blocks.iter().filter(|b| b.hides(line)) {
+            folded.remove(&block.key);
+        }
+    }
+}
+
+/// ▸ (folded) or ▾ (open) marker centred on `center`
+pub fn paint_marker(painter: &egui::Painter, center: egui::Pos2, folded: bool, color: Color32) {
+    let rect = egui::Rect::from_center_size(center, egui::vec2(8.0, 8.0));
+    let points = if folded {
+        vec![rect.left_top(), rect.right_center(), rect.left_bottom()]
+    } else {
+        vec![rect.left_top(), rect.right_top(), rect.center_bottom()]
+    };
+    painter.add(egui::Shape::convex_polygon(points, color, egui::Stroke::NONE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(code: &str) -> Vec<(FoldKind, usize, usize)> {
+        fold_blocks(code).iter().map(|b| (b.kind, b.start, b.end)).collect()
+    }
+
+    #[test]
+    fn test_procedures_functions_and_regions() {
+        let code = "to square :size\n  repeat 4 [fd :size rt 90]\nend\nsquare 50\n";
+        assert_eq!(spans(code), vec![(FoldKind::Procedure, 1, 3)]);
+
+        let code = "10 REM { Setup\n20 DEF FNSQ(X) = X * X\n30 DEF FNAREA(R)\n40 FNAREA = 3.14 * R * R\n50 FNEND\n60 REM }\n70 END";
+        assert_eq!(spans(code), vec![(FoldKind::Region, 1, 6), (FoldKind::Function, 3, 5)]);
+        // A program-ending BASIC END does not close anything
+        assert_eq!(fold_blocks(code)[0].key, "REM { SETUP");
+    }
+
+    #[test]
+    fn test_nested_blocks() {
+        let code = "REM {\nTO A\nREM {\nFD 10\nREM }\nEND\nTO B\nEND\nREM }";
+        assert_eq!(
+            spans(code),
+            vec![
+                (FoldKind::Region, 1, 9),
+                (FoldKind::Procedure, 2, 6),
+                (FoldKind::Region, 3, 5),
+                (FoldKind::Procedure, 7, 8),
+            ]
+        );
+        let block = &fold_blocks(code)[1];
+        assert!(!block.hides(2) && block.hides(3) && block.hides(6) && !block.hides(7));
+    }
+
+    #[test]
+    fn test_unterminated_blocks_are_not_foldable() {
+        assert!(spans("TO SPIRAL :N\nFD :N\nRT 20").is_empty());
+        assert!(spans("REM }\nEND\nFNEND").is_empty(), "closers without openers");
+        // The procedure is closed past the region opened inside it, which is dropped
+        assert_eq!(spans("TO A\nREM {\nFD 1\nEND\nREM {"), vec![(FoldKind::Procedure, 1, 4)]);
+        assert_eq!(spans("REM {\nTO A\nFD 1\nREM }"), vec![(FoldKind::Region, 1, 4)]);
+    }
+
+    #[test]
+    fn test_keys_ignore_line_numbers_and_number_repeats() {
+        let code = "10 REM {\n20 REM }\n30  rem   {\n40 REM }\n100 TO STAR\n110 END";
+        let keys: Vec<String> = fold_blocks(code).into_iter().map(|b| b.key).collect();
+        assert_eq!(keys, vec!["REM {", "REM {#2", "TO STAR"]);
+        // Renumbering keeps the keys
+        let renumbered = code.replace("10 ", "15 ").replace("30 ", "35 ");
+        assert_eq!(fold_blocks(&renumbered).into_iter().map(|b| b.key).collect::<Vec<_>>(), keys);
+        assert!(spans("REMARK {\nREM }").is_empty());
+        assert!(spans("TOTAL = 1\nEND").is_empty());
+    }
+
+    #[test]
+    fn test_layout_hides_only_folded_lines() {
+        let code = "TO A\nFD 1\nEND\nTO B\nEND\n";
+        let folded: HashSet<String> = ["TO A".to_string()].into();
+        let job = layout_job(code, &folded, FontId::monospace(14.0), Color32::WHITE);
+        assert_eq!(job.text, code, "the text is laid out unchanged");
+        let heights: Vec<Option<f32>> = job.sections.iter().map(|s| s.format.line_height).collect();
+        assert_eq!(heights, vec![None, Some(0.0), Some(0.0), None, None, None]);
+
+        let fonts = egui::epaint::text::Fonts::new(1.0, 1024, egui::FontDefinitions::default());
+        let galley = fonts.layout_job(job);
+        let rows: Vec<f32> = galley.rows.iter().map(|r| r.rect.height()).collect();
+        assert!(rows[0] > 0.0 && rows[1] == 0.0 && rows[2] == 0.0 && rows[3] > 0.0, "{:?}", rows);
+        assert_eq!(rows.len(), 6);
+    }
+}
@@ -0,0 +1,112 @@
+use eframe::egui;
+use std::thread::JoinHandle;
+
+use crate::app::TimeWarpApp;
+use crate::gallery::{builtin_examples, load_previews, Example, Preview, ThumbnailCache, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH};
+use crate::i18n::tr;
+
+/// State of the Example Gallery window
+///
+/// Previews are loaded (and rendered when not cached) on a background thread
+/// the first time the window opens; until then each example shows a spinner.
+pub struct GalleryPanel {
+    pub examples: Vec<Example>,
+    loading: Option<JoinHandle<Vec<Preview>>>,
+    /// One per example once loading is done: a texture, or `None` for a source snippet
+    previews: Vec<Option<egui::TextureHandle>>,
+}
+
+impl Default for GalleryPanel {
+    fn default() -> Self {
+        Self { examples: builtin_examples(), loading: None, previews: Vec::new() }
+    }
+}
+
+impl GalleryPanel {
+    /// Start loading previews, unless they are loading or loaded
+    fn start_loading(&mut self) {
+        if self.loading.is_some() || !self.previews.is_empty() {
+            return;
+        }
+        let examples = self.examples.clone();
+        self.loading = Some(std::thread::spawn(move || load_previews(&examples, ThumbnailCache::in_data_dir().as_ref())));
+    }
+
+    /// Turn finished previews into textures; true while still loading
+    fn poll(&mut self, ctx: &egui::Context) -> bool {
+        if !self.loading.as_ref().is_some_and(|handle| handle.is_finished()) {
+            return self.loading.is_some();
+        }
+        let previews = self.loading.take().and_then(|handle| handle.join().ok()).unwrap_or_default();
+        self.previews = previews
+            .into_iter()
+            .zip(&self.examples)
+            .map(|(preview, example)| match preview {
+                Preview::Thumbnail(image) => {
+                    let size = [image.width() as usize, image.height() as usize];
+                    let pixels = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+                    Some(ctx.load_texture(format!("gallery/{}", example.file), pixels, egui::TextureOptions::LINEAR))
+                }
+                Preview::Snippet => None,
+            })
+            .collect();
+        false
+    }
+}
+
+/// Example Gallery window: a preview, title, and description per example, and Open
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_gallery;
+    let panel = &mut app.gallery;
+    panel.start_loading();
+    let loading = panel.poll(ctx);
+    if loading {
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+    let thumbnail_size = egui::vec2(THUMBNAIL_WIDTH as f32, THUMBNAIL_HEIGHT as f32);
+    let mut chosen = None;
+
+    egui::Window::new(tr("gallery.title"))
+        .open(&mut open)
+        .default_width(520.0)
+        .default_height(480.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, example) in panel.examples.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let (rect, _) = ui.allocate_exact_size(thumbnail_size, egui::Sense::hover());
+                        match panel.previews.get(index) {
+                            Some(Some(texture)) => {
+                                egui::Image::new((texture.id(), thumbnail_size)).paint_at(ui, rect);
+                            }
+                            Some(None) => {
+                                ui.painter().rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+                                let snippet = egui::RichText::new(example.snippet()).monospace().size(8.0);
+                                ui.put(rect.shrink(4.0), egui::Label::new(snippet).truncate());
+                            }
+                            None => {
+                                ui.put(rect, egui::Spinner::new());
+                            }
+                        }
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong(&example.title);
+                                ui.weak(example.language.name());
+                            });
+                            ui.label(&example.description);
+                            if ui.button(tr("gallery.open")).clicked() {
+                                chosen = Some(index);
+                            }
+                        });
+                    });
+                    ui.separator();
+                }
+            });
+        });
+
+    app.show_gallery = open;
+    if let Some(example) = chosen.and_then(|index| app.gallery.examples.get(index).cloned()) {
+        let (stem, extension) = example.file.rsplit_once('.').unwrap_or((&example.file, ""));
+        app.open_unsaved(stem, extension, example.source.clone());
+    }
+}
@@ -13,35 +13,73 @@ pub fn render(_app: &TimeWarpApp, ui: &mut egui::Ui) {
         ui.label("When a program requests input (BASIC INPUT or PILOT A:), the IDE shows an 📝 prompt in the Output tab.");
         ui.label("Type your response and press Enter or click Submit to resume execution. The value is stored as a number if possible, otherwise as text.");
         ui.add_space(10.0);
+        ui.heading("Multi-File Programs");
+        ui.label("CALLFILE \"file\" - Run another file (any language) as a subprogram, sharing variables, procedures, and the turtle");
+        ui.add_space(10.0);
         
         ui.heading("PILOT Language");
         ui.label("T:text - Display text");
+        ui.label("TW:n - Word-wrap T: output at n columns (TW:0 = off)");
+        ui.label("TI:name - Program title in the window title bar while it runs");
+        ui.label("V:text - Speak text aloud (shown as [voice] text when speech is unavailable)");
         ui.label("A:var - Accept input");
+        ui.label("AN: - Ask the last A: again, repeating its question");
+        ui.label("%ANS1, %ANS2, ... - The latest answers in T: text (T:You said %ANS1)");
         ui.label("U:var=value - Set variable");
+        ui.label("D:question|answer1,answer2|feedback - Quiz question for QZ:");
+        ui.label("D:message / D:YESNO question - Message box; Yes sets the match flag");
+        ui.label("QZ: / QZ:SHUFFLE [seed] - Ask the D: questions and score them in RIGHT and WRONG");
+        ui.label("M:pattern / M:%80 pattern - Match the last answer (contains it, or spelled 80% alike)");
+        ui.label("MC:EXACT -FOLD -PUNCT -SPACE - How M: and QZ: compare answers (bare MC: resets)");
         ui.label("C:condition - Compute condition");
         ui.label("Y:condition - Execute if true");
         ui.label("N:condition - Execute if false");
         ui.label("J:label - Jump to label");
         ui.label("L:label - Define label");
-        ui.label("E: - End program");
+        ui.label("E: or END - End program");
         ui.label("SCREEN mode[, w, h] - Set screen (BASIC-style command; Logo follows selected mode)");
         ui.add_space(10.0);
         
         ui.heading("BASIC Language");
         ui.label("PRINT \"text\" - Display text");
         ui.label("INPUT var - Get user input (blocking)");
+        ui.label("INPUT \"Age\"; AGE AS NUMBER RANGE 1 TO 120 - Re-prompt until a valid number is entered");
         ui.label("LET var$ = INKEY$ - Get key press (non-blocking)");
         ui.label("PRINT INKEY$ - Print last key pressed if any");
+        ui.label("TIMER - Seconds since start (e.g. PRINT TIMER - T1)");
+        ui.label("DATE$ / TIME$ - Date as MM-DD-YYYY, time as HH:MM:SS");
+        ui.label("EVERY s GOSUB line / ONTICK GOSUB line - Call a subroutine every s seconds / every frame (… OFF stops)");
+        ui.label("DELTA - Seconds between the last two EVERY/ONTICK runs (X = X + SPEED * DELTA)");
         ui.label("SCREEN mode[, w, h] - Set text/graphics screen (0=text, 1=640x480, 2=1024x768)");
         ui.label("CLS - Clear text screen and reset cursor");
         ui.label("LOCATE row, col - Move text cursor (1-based)");
         ui.label("LET var = value - Set variable");
         ui.label("GOTO line - Jump to line number");
-        ui.label("IF condition THEN command - Conditional");
+        ui.label("IF condition THEN command [ELSE command] - Conditional");
+        ui.label("Line ending in \" _\" - Statement continues on the next line");
         ui.label("FOR var = start TO end - Loop");
         ui.label("NEXT var - End loop");
         ui.label("GOSUB line - Call subroutine");
         ui.label("RETURN - Return from subroutine");
+        ui.label("DIM A(10), N$(10), GRID(3, 3) - Number and string arrays, one or two dimensions");
+        ui.label("OPTION BASE 0|1 - Lowest array subscript (before the first DIM)");
+        ui.label("OPTION COORDS SCREEN|CENTERED - LINE/CIRCLE (0,0) at the top-left (as after SCREEN 1) or centre");
+        ui.label("COUNT% / X! / Y# / NAME$ - Integer, single, double, and string variables");
+        ui.label("SHELL \"command\" - Run a program the project allowlists; exit code in SHELLRESULT");
+        ui.label("MSGBOX \"text\" [, YESNO, var] - Message box; var = 1 for Yes, 0 for No");
+        ui.label("TITLE \"name\" / STATUS \"text\" - Window title / status bar message while the program runs");
+        ui.label("DEFINT I-N (DEFSNG, DEFDBL, DEFSTR) - Type of names without a suffix, by first letter");
+        ui.label("READ var, A(I) / DATA 1, \"two\" / RESTORE - Read values listed in DATA lines");
+        ui.label("LOGVAR var - Record var's value each time (see Debug → Recorded Series)");
+        ui.label("PLOT var / PLOT Y VS X - Chart recorded series on the canvas");
+        ui.label("PRINT USING \"$$#,###.##\"; X - Format numbers (# digit, . point, , thousands, $$ dollar, trailing - sign)");
+        ui.label("WIDTH n - Word-wrap PRINT output at n columns (WIDTH 0 = off)");
+        ui.label("CHAIN \"file\"[, ALL] - Run another program in place of this one (COMMON vars carry over)");
+        ui.label("COMMON var, var$ - Variables kept by CHAIN and the next run");
+        ui.label("CLEAR - Forget all variables and arrays, COMMON ones too");
+        ui.label("RANDOMIZE [n] - Start RND over from seed n (the run summary shows each run's seed)");
+        ui.label("MERGE \"file\" - Add a library's numbered lines (same numbers are replaced)");
+        ui.label("OPTION ERRORS CLASSIC|MODERN - Retro ?SYNTAX ERROR IN 20 messages (ERR/ERL hold the last error)");
         ui.label("END - End program");
         ui.add_space(10.0);
         
@@ -52,14 +90,24 @@ pub fn render(_app: &TimeWarpApp, ui: &mut egui::Ui) {
         ui.label("RIGHT n - Turn right n degrees");
         ui.label("PENUP - Lift pen");
         ui.label("PENDOWN - Lower pen");
-        ui.label("CLEARSCREEN - Clear graphics");
-        ui.label("HOME - Return to center");
-        ui.label("SETXY x y - Set position");
+        ui.label("CLEARSCREEN - Clear graphics and return home");
+        ui.label("CLEAN - Clear graphics, turtle stays put");
+        ui.label("HOME - Return to center (draws a line if the pen is down)");
+        ui.label("SETXY x y / SETPOS [x y] - Set position");
+        ui.label("SETX x / SETY y - Move along one axis");
+        ui.label("SETHEADING n / SETHEADING TOWARDS x y - Set heading");
+        ui.label("HEADING, TOWARDS x y - Report heading (HEADING also in expressions)");
+        ui.label("ARC angle radius - Arc around the turtle");
+        ui.label("SETANGLEMODE COMPASS|MATH - 0 = north clockwise, or 0 = east counter-clockwise");
+        ui.label("SETSHAPE \"TURTLE - CIRCLE, TRIANGLE, TURTLE, ARROW, ROCKET, or a points file");
+        ui.label("SETCOLORCYCLE [RED YELLOW BLUE] - Each segment takes the next color ([] turns it off)");
+        ui.label("FLASH n - Light up the last n segments for a moment");
+        ui.label("PATHCOUNT - Segments drawn so far (also usable in expressions)");
         ui.label("REPEAT n [commands] - Repeat commands");
         ui.add_space(10.0);
         
         ui.heading("Example Programs");
-        ui.label("See the examples/ directory for 32 sample programs in each language.");
+        ui.label("See the examples/ directory for 32 sample programs in each language, or Help → Example Gallery for previews.");
         ui.label("PILOT: pilot_quiz.pilot, pilot_adventure.pilot, pilot_dragon_adventure.pilot, ...");
         ui.label("BASIC: basic_guess.bas, basic_hangman.bas, basic_inkey_demo.bas, basic_screen_modes.bas, ...");
         ui.label("Logo: logo_star.logo, logo_flower.logo, logo_snowman.logo, logo_koch_snowflake.logo, ...");
@@ -0,0 +1,81 @@
+//! Evaluate Selection (Ctrl+Enter): a result shown at the end of its line for a few seconds
+//!
+//! The editor's selection, or the cursor's line when nothing is selected, goes
+//! to [`Interpreter::evaluate_selection`](crate::interpreter::Interpreter::evaluate_selection),
+//! which decides whether it is an expression, a statement, or a block. The
+//! result is drawn after the line and in the status bar, and fades out after
+//! [`SHOW_SECONDS`] or as soon as the code is edited.
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::tr;
+use crate::interpreter::inline::InlineResult;
+
+/// How long a result stays, its fade included
+pub const SHOW_SECONDS: f64 = 4.0;
+
+/// The last part of `SHOW_SECONDS`, over which it fades
+const FADE_SECONDS: f64 = 1.0;
+
+/// A result and the line it belongs to
+pub struct Annotation {
+    pub file: String,
+    /// 1-based line the selection ended on
+    pub line: usize,
+    pub result: InlineResult,
+    shown_at: f64,
+}
+
+impl Annotation {
+    /// How opaque it is at `now`: 1 until it starts to fade, `None` once gone
+    pub fn opacity(&self, now: f64) -> Option<f32> {
+        let left = self.shown_at + SHOW_SECONDS - now;
+        (left > 0.0).then(|| (left / FADE_SECONDS).min(1.0) as f32)
+    }
+
+    /// Color for the result, error red when it failed
+    pub fn color(&self, ui: &egui::Ui, opacity: f32) -> egui::Color32 {
+        let color = if self.result.failed { ui.visuals().error_fg_color } else { ui.visuals().weak_text_color() };
+        color.gamma_multiply(opacity)
+    }
+}
+
+/// Evaluate the editor's selection, or say why not while a program runs
+pub fn evaluate(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(file) = app.current_file().cloned() else { return };
+    let code = app.current_code();
+    let Some((selection, line)) = crate::ui::editor::selection_or_line(ctx, &code) else { return };
+    let result = if app.execution.is_busy() {
+        InlineResult { text: tr("inline.busy").to_string(), failed: true }
+    } else {
+        let Some(result) = app.interpreter.evaluate_selection(&selection, &mut app.turtle_state) else { return };
+        result
+    };
+    app.inline_result = Some(Annotation { file, line, result, shown_at: ctx.input(|i| i.time) });
+}
+
+/// The result while it lasts, forgetting it once it has faded
+pub fn current<'a>(app: &'a mut TimeWarpApp, ctx: &egui::Context) -> Option<(&'a Annotation, f32)> {
+    let now = ctx.input(|i| i.time);
+    let Some(opacity) = app.inline_result.as_ref().and_then(|annotation| annotation.opacity(now)) else {
+        app.inline_result = None;
+        return None;
+    };
+    ctx.request_repaint();
+    app.inline_result.as_ref().map(|annotation| (annotation, opacity))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_fade_then_go() {
+        let annotation = Annotation { file: "a.bas".into(), line: 1, result: InlineResult { text: "9".into(), failed: false }, shown_at: 10.0 };
+        assert_eq!(annotation.opacity(10.0), Some(1.0));
+        assert_eq!(annotation.opacity(12.5), Some(1.0));
+        assert!(annotation.opacity(13.5).is_some_and(|o| (o - 0.5).abs() < 1e-6));
+        assert_eq!(annotation.opacity(14.0), None);
+    }
+}
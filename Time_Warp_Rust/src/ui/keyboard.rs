@@ -0,0 +1,252 @@
+//! Keyboard navigation for the whole window
+//!
+//! - Alt (pressed and released on its own) focuses the menu bar; arrows move
+//!   between menus and items, Enter opens or activates.
+//! - Ctrl+Tab / Ctrl+Shift+Tab cycle the open editor tabs.
+//! - Ctrl+1..5 switch the main tabs (Editor, Output, Debug, Explorer, Help);
+//!   `--kiosk` leaves out the Explorer.
+//! - Ctrl+Shift+O opens Go to Symbol, F2 Rename Symbol.
+//! - Ctrl+Enter in the editor evaluates the selection or the cursor's line.
+//! - Escape closes the topmost dialog.
+//!
+//! Shortcuts are consumed here, before any widget sees the keys, so a focused
+//! text editor does not also receive the Tab.
+
+use eframe::egui;
+use crate::app::TimeWarpApp;
+
+/// Number of main tabs reachable with Ctrl+1..5
+pub const MAIN_TAB_COUNT: usize = 5;
+
+/// Next tab index when cycling through `count` tabs, wrapping at either end
+///
+/// A `current` index left past the end (after tabs were closed) counts as the
+/// last tab. Returns `None` when there are no tabs.
+pub fn cycle_tab(current: usize, count: usize, backwards: bool) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let current = current.min(count - 1);
+    Some(if backwards { (current + count - 1) % count } else { (current + 1) % count })
+}
+
+/// Main tab selected by Ctrl+<digit>
+fn main_tab_for_key(key: egui::Key) -> Option<usize> {
+    [egui::Key::Num1, egui::Key::Num2, egui::Key::Num3, egui::Key::Num4, egui::Key::Num5]
+        .iter()
+        .position(|k| *k == key)
+        .filter(|tab| *tab < MAIN_TAB_COUNT)
+}
+
+/// Detects Alt pressed and released with no other key in between
+#[derive(Debug, Default)]
+pub struct AltTap {
+    held: bool,
+    combined: bool,
+}
+
+impl AltTap {
+    /// Feed one frame's state; true on the frame a lone Alt is released
+    pub fn update(&mut self, alt_down: bool, other_key_pressed: bool) -> bool {
+        if alt_down {
+            if !self.held {
+                self.held = true;
+                self.combined = false;
+            }
+            self.combined |= other_key_pressed;
+            return false;
+        }
+        let tapped = self.held && !self.combined && !other_key_pressed;
+        self.held = false;
+        tapped
+    }
+}
+
+/// Handle app-wide shortcuts; call once per frame before drawing panels
+pub fn handle_shortcuts(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let (alt_down, other_key) = ctx.input(|i| {
+        let other = i.events.iter().any(|e| {
+            matches!(e, egui::Event::Key { pressed: true, .. } | egui::Event::PointerButton { pressed: true, .. })
+        });
+        (i.modifiers.alt, other)
+    });
+    if app.alt_tap.update(alt_down, other_key) {
+        app.focus_menu_bar = true;
+    }
+
+    // F5: Run (asks before restarting a running program)
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F5)) {
+        crate::ui::menubar::request_run(app);
+    }
+
+    // Ctrl+Shift+F: Find in Files
+    let find_in_files = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::F);
+    if ctx.input_mut(|i| i.consume_shortcut(&find_in_files)) {
+        app.show_find_in_files = true;
+    }
+
+    // Ctrl+Shift+O: Go to Symbol
+    let goto_symbol = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::O);
+    if ctx.input_mut(|i| i.consume_shortcut(&goto_symbol)) {
+        app.outline.query.clear();
+        app.show_goto_symbol = true;
+    }
+
+    // F2: Rename Symbol
+    if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F2)) {
+        crate::ui::rename::open(app, ctx);
+    }
+
+    // Ctrl+Enter: Evaluate Selection (only on the Editor tab, where it would otherwise insert a line)
+    if app.active_tab == 0 && ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter)) {
+        crate::ui::inline_eval::evaluate(app, ctx);
+    }
+
+    // Ctrl+Shift+Tab before Ctrl+Tab: consume_shortcut ignores extra Shift
+    let previous_file = egui::KeyboardShortcut::new(egui::Modifiers::CTRL | egui::Modifiers::SHIFT, egui::Key::Tab);
+    let next_file = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Tab);
+    let backwards = if ctx.input_mut(|i| i.consume_shortcut(&previous_file)) {
+        Some(true)
+    } else if ctx.input_mut(|i| i.consume_shortcut(&next_file)) {
+        Some(false)
+    } else {
+        None
+    };
+    if let Some(backwards) = backwards {
+        if let Some(index) = cycle_tab(app.current_file_index, app.open_files.len(), backwards) {
+            app.current_file_index = index;
+            app.active_tab = 0;
+        }
+    }
+
+    let main_tab = ctx.input_mut(|i| {
+        let pressed = i.events.iter().find_map(|e| match e {
+            egui::Event::Key { key, pressed: true, modifiers, .. } if modifiers.command_only() => main_tab_for_key(*key).map(|tab| (*key, tab)),
+            _ => None,
+        });
+        pressed.map(|(key, tab)| {
+            i.consume_key(egui::Modifiers::COMMAND, key);
+            tab
+        })
+    });
+    // Kiosk mode has no Explorer
+    if let Some(tab) = main_tab.filter(|&tab| tab != 3 || app.kiosk_root.is_none()) {
+        app.active_tab = tab;
+    }
+
+    if ctx.input(|i| i.key_pressed(egui::Key::Escape)) && close_topmost_dialog(app) {
+        ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+    }
+}
+
+/// Close the dialog drawn last (the one on top); false if none is open
+pub fn close_topmost_dialog(app: &mut TimeWarpApp) -> bool {
+    if app.show_about_dialog {
+        app.show_about_dialog = false;
+    } else if app.error_message.is_some() {
+        app.error_message = None;
+    } else if app.show_gallery {
+        app.show_gallery = false;
+    } else if app.show_tutorial {
+        app.show_tutorial = false;
+    } else if app.show_diagnostics {
+        app.show_diagnostics = false;
+    } else if app.show_package {
+        app.show_package = false;
+    } else if app.canvas_export.open {
+        app.canvas_export.open = false;
+    } else if app.model_compare.open {
+        app.model_compare.open = false;
+    } else if app.show_run_history {
+        app.show_run_history = false;
+    } else if app.show_program_report {
+        app.show_program_report = false;
+    } else if app.show_export_path {
+        app.show_export_path = false;
+    } else if app.confirm_clear_run {
+        app.confirm_clear_run = false;
+    } else if app.confirm_restart_run {
+        app.confirm_restart_run = false;
+    } else if app.language_switch.is_some() {
+        app.language_switch = None;
+    } else if app.show_import_share {
+        app.show_import_share = false;
+    } else if app.share_link.is_some() {
+        app.share_link = None;
+    } else if app.show_settings {
+        app.show_settings = false;
+    } else if app.show_goto_symbol {
+        app.show_goto_symbol = false;
+    } else if app.rename.target.is_some() {
+        app.rename.target = None;
+    } else if app.show_find_in_files {
+        app.show_find_in_files = false;
+    } else if app.show_find_replace {
+        app.show_find_replace = false;
+    } else if app.full_line_view.is_some() {
+        app.full_line_view = None;
+    } else {
+        return false;
+    }
+    true
+}
+
+/// Focus a dialog's default button on the frame the dialog appears
+///
+/// Enter then activates it. Focus is only taken once per opening, so Tab can
+/// still move to the dialog's other widgets.
+pub fn focus_on_open(response: &egui::Response) {
+    let ctx = &response.ctx;
+    let pass = ctx.cumulative_pass_nr();
+    let last_shown = ctx.data_mut(|d| {
+        let last = d.get_temp::<u64>(response.id);
+        d.insert_temp(response.id, pass);
+        last
+    });
+    if last_shown.is_none_or(|last| last + 1 < pass) {
+        response.request_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_tab_wraps_both_ways() {
+        assert_eq!(cycle_tab(0, 3, false), Some(1));
+        assert_eq!(cycle_tab(2, 3, false), Some(0));
+        assert_eq!(cycle_tab(0, 3, true), Some(2));
+        assert_eq!(cycle_tab(1, 3, true), Some(0));
+    }
+
+    #[test]
+    fn test_cycle_tab_after_tabs_closed() {
+        assert_eq!(cycle_tab(0, 0, false), None, "no tabs open");
+        assert_eq!(cycle_tab(0, 1, false), Some(0));
+        assert_eq!(cycle_tab(0, 1, true), Some(0));
+        // Index left past the end counts as the last tab
+        assert_eq!(cycle_tab(4, 2, false), Some(0));
+        assert_eq!(cycle_tab(4, 2, true), Some(0));
+        assert_eq!(cycle_tab(4, 3, true), Some(1));
+    }
+
+    #[test]
+    fn test_main_tab_keys() {
+        assert_eq!(main_tab_for_key(egui::Key::Num1), Some(0));
+        assert_eq!(main_tab_for_key(egui::Key::Num5), Some(4));
+        assert_eq!(main_tab_for_key(egui::Key::Num6), None);
+    }
+
+    #[test]
+    fn test_alt_tap_ignores_alt_combinations() {
+        let mut alt = AltTap::default();
+        assert!(!alt.update(true, false));
+        assert!(alt.update(false, false), "lone Alt tap");
+        assert!(!alt.update(false, false), "reported once");
+
+        assert!(!alt.update(true, false));
+        assert!(!alt.update(true, true), "Alt+F held");
+        assert!(!alt.update(false, false), "not a tap after a combination");
+    }
+}
@@ -0,0 +1,223 @@
+//! Language picker in the status bar, and the notice when a pick disagrees with the buffer
+//!
+//! Picking a language changes how the current buffer runs. When the buffer
+//! holds code detected as another language, [`switch_actions`] offers ways
+//! out instead of switching silently: keep the text and switch anyway,
+//! start a new tab in the chosen language and leave this one alone, or, for
+//! Logo picked as BASIC, redraw what the program draws as BASIC `LINE`s. That
+//! last one is not a translation: the Logo program runs on a background
+//! thread, isolated and restricted, and only the segments it drew carry over.
+
+use std::thread::JoinHandle;
+
+use anyhow::Result;
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::direct::basic_drawing;
+use crate::interpreter::isolated::{IsolatedRunOptions, ISOLATED_MAX_ITERATIONS};
+use crate::interpreter::Interpreter;
+use crate::languages::Language;
+
+/// Languages offered by the picker
+const LANGUAGES: [Language; 4] = [Language::Pilot, Language::Basic, Language::Logo, Language::TempleCode];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchAction {
+    /// Run the buffer as the chosen language, text unchanged
+    KeepAndSwitch,
+    /// Run the Logo buffer and write its drawing as BASIC `LINE`s in a new tab
+    RedrawAsBasic,
+    /// Open an empty tab in the chosen language
+    OpenNewTab,
+}
+
+/// A pick waiting on the notice
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingSwitch {
+    pub file: String,
+    pub detected: Language,
+    pub selected: Language,
+    pub actions: Vec<SwitchAction>,
+}
+
+/// Whether a buffer detected as `from` can be redrawn in `to`: Logo, as BASIC
+fn can_redraw(from: Language, to: Language) -> bool {
+    from == Language::Logo && to == Language::Basic
+}
+
+/// What to offer when `selected` is picked for a buffer detected as `detected`
+///
+/// Empty when the switch needs no confirmation: the buffer is empty, its
+/// language cannot be told, it already matches, or the pick is TempleCode,
+/// which runs every language.
+pub fn switch_actions(detected: Option<Language>, selected: Language, buffer_empty: bool) -> Vec<SwitchAction> {
+    let Some(detected) = detected else { return Vec::new() };
+    if buffer_empty || detected == selected || selected == Language::TempleCode {
+        return Vec::new();
+    }
+    let mut actions = vec![SwitchAction::KeepAndSwitch];
+    if can_redraw(detected, selected) {
+        actions.push(SwitchAction::RedrawAsBasic);
+    }
+    actions.push(SwitchAction::OpenNewTab);
+    actions
+}
+
+/// Run a Logo program isolated and restricted, and write what it drew as BASIC `LINE`s
+fn redraw_as_basic(source: &str) -> Result<String> {
+    let report = Interpreter::run_isolated(&format!("; @lang logo\n{}", source), IsolatedRunOptions::default());
+    if let Some(error) = report.error {
+        anyhow::bail!(error);
+    }
+    if let Some(error) = report.errors.first() {
+        anyhow::bail!("{}", error);
+    }
+    if report.waiting_for_input {
+        anyhow::bail!("the program asks for input, so its drawing cannot be redrawn");
+    }
+    if report.output.iter().any(|line| line.contains(tr("warning.max_iterations"))) {
+        anyhow::bail!("the program did not finish within {} steps", ISOLATED_MAX_ITERATIONS);
+    }
+    Ok(basic_drawing(&report.lines, "Drawing redrawn from Logo"))
+}
+
+/// Open the redrawn program once the background run has finished; call every frame
+pub fn poll_redraw(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    if !app.language_redraw.as_ref().is_some_and(JoinHandle::is_finished) {
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        return;
+    }
+    let Some(handle) = app.language_redraw.take() else { return };
+    match handle.join().unwrap_or_else(|_| Err(anyhow::anyhow!("the run panicked"))) {
+        Ok(program) => {
+            app.open_unsaved("redrawn", Language::Basic.extension(), program);
+            if let Some(file) = app.current_file().cloned() {
+                set_language(app, file, Language::Basic);
+            }
+        }
+        Err(e) => app.error_message = Some(trf("language_switch.redraw_failed", &[&e])),
+    }
+}
+
+/// Run the current buffer as `language` from now on
+fn set_language(app: &mut TimeWarpApp, file: String, language: Language) {
+    app.buffer_languages.insert(file, language);
+}
+
+/// The picker's choice: switch at once, or raise the notice when the buffer disagrees
+pub fn request(app: &mut TimeWarpApp, selected: Language) {
+    let Some(file) = app.current_file().cloned() else { return };
+    let source = app.current_code();
+    let detected = Interpreter::detect_language(&source);
+    let actions = switch_actions(detected, selected, source.trim().is_empty());
+    match detected {
+        Some(detected) if !actions.is_empty() => {
+            app.language_switch = Some(PendingSwitch { file, detected, selected, actions });
+        }
+        _ => set_language(app, file, selected),
+    }
+}
+
+/// Status bar picker; fixed when the buffer names its language with an @lang header
+pub fn render_picker(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let current = app.current_language();
+    let from_header = app.current_metadata().language.is_some();
+    let mut selected = current;
+    ui.add_enabled_ui(!from_header && app.current_file().is_some(), |ui| {
+        egui::ComboBox::from_id_salt("buffer_language")
+            .selected_text(trf("language_switch.current", &[&current.name()]))
+            .show_ui(ui, |ui| {
+                for language in LANGUAGES {
+                    ui.selectable_value(&mut selected, language, language.name());
+                }
+            });
+    })
+    .response
+    .on_disabled_hover_text(tr("language_switch.locked"));
+    if selected != current {
+        request(app, selected);
+    }
+}
+
+/// Non-modal notice for a pick that disagrees with the buffer's code
+pub fn render_notice(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(pending) = app.language_switch.clone() else { return };
+    let mut chosen = None;
+    let mut open = true;
+    egui::Window::new(tr("language_switch.title"))
+        .collapsible(false)
+        .resizable(false)
+        .open(&mut open)
+        .show(ctx, |ui| {
+            ui.label(trf("language_switch.mismatch", &[&pending.detected.name(), &pending.selected.name()]));
+            ui.horizontal(|ui| {
+                for action in &pending.actions {
+                    let label = match action {
+                        SwitchAction::KeepAndSwitch => trf("language_switch.keep", &[&pending.selected.name()]),
+                        SwitchAction::RedrawAsBasic => tr("language_switch.redraw").to_string(),
+                        SwitchAction::OpenNewTab => trf("language_switch.new_tab", &[&pending.selected.name()]),
+                    };
+                    let busy = *action == SwitchAction::RedrawAsBasic && app.language_redraw.is_some();
+                    let button = ui.add_enabled(!busy, egui::Button::new(label));
+                    let button = if *action == SwitchAction::RedrawAsBasic { button.on_hover_text(tr("language_switch.redraw.hover")) } else { button };
+                    if button.clicked() {
+                        chosen = Some(*action);
+                    }
+                }
+            });
+        });
+    if !open {
+        app.language_switch = None;
+    }
+    let Some(action) = chosen else { return };
+    app.language_switch = None;
+    let extension = pending.selected.extension();
+    match action {
+        SwitchAction::KeepAndSwitch => set_language(app, pending.file, pending.selected),
+        SwitchAction::RedrawAsBasic => {
+            let source = app.file_buffers.get(&pending.file).cloned().unwrap_or_default();
+            app.language_redraw = Some(std::thread::spawn(move || redraw_as_basic(&source)));
+        }
+        SwitchAction::OpenNewTab => app.open_unsaved("untitled", extension, String::new()),
+    }
+    // A new tab runs as its extension says, even under a global language mode
+    if action == SwitchAction::OpenNewTab {
+        if let Some(file) = app.current_file().cloned() {
+            set_language(app, file, pending.selected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_needs_no_notice_when_nothing_disagrees() {
+        assert!(switch_actions(Some(Language::Basic), Language::Logo, true).is_empty(), "empty buffer");
+        assert!(switch_actions(None, Language::Logo, false).is_empty(), "undetectable code");
+        assert!(switch_actions(Some(Language::Logo), Language::Logo, false).is_empty());
+        assert!(switch_actions(Some(Language::Pilot), Language::TempleCode, false).is_empty());
+    }
+
+    #[test]
+    fn test_disagreement_offers_conversion_only_where_possible() {
+        use SwitchAction::*;
+        assert_eq!(switch_actions(Some(Language::Basic), Language::Logo, false), vec![KeepAndSwitch, OpenNewTab]);
+        assert_eq!(switch_actions(Some(Language::Logo), Language::Basic, false), vec![KeepAndSwitch, RedrawAsBasic, OpenNewTab]);
+        assert_eq!(switch_actions(Some(Language::TempleCode), Language::Pilot, false), vec![KeepAndSwitch, OpenNewTab]);
+    }
+
+    #[test]
+    fn test_logo_is_redrawn_as_basic_lines() {
+        let program = redraw_as_basic("REPEAT 2 [FD 10 RT 90]").unwrap();
+        assert_eq!(program, "10 REM Drawing redrawn from Logo\n20 LINE 0, 0, 0, -10\n30 LINE 0, -10, 10, -10\n40 END\n");
+        // Isolated with tight caps, so an endless program gives up instead of hanging the redraw
+        let started = std::time::Instant::now();
+        assert!(redraw_as_basic("TO SPIN\nRT 1\nSPIN\nEND\nSPIN").is_err());
+        assert!(redraw_as_basic("TO SPIN\nFD 1\nSPIN2\nEND\nTO SPIN2\nRT 1\nSPIN\nEND\nREPEAT 1000 [SPIN]").is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(5), "{:?}", started.elapsed());
+    }
+}
@@ -0,0 +1,308 @@
+//! Whole-line editing in the code editor
+//!
+//! - Alt+Up / Alt+Down move the cursor's line, or every line the selection
+//!   touches, past the line above or below.
+//! - Tab on a selection spanning lines indents each of them by the tab width;
+//!   Shift+Tab unindents the cursor's line or the selected lines.
+//! - Ctrl+D duplicates the cursor's line (or the selected lines) below itself.
+//!
+//! A selection that ends at the start of a line does not take that line
+//! along. The transforms are pure functions on the buffer and a selection in
+//! char offsets; the editor applies each result as one undo step and keeps
+//! the selection on the same text.
+
+use eframe::egui::{self, text::{CCursor, CCursorRange}};
+
+use crate::app::TimeWarpApp;
+use crate::i18n::tr;
+
+/// A selection in char offsets: where it started and where the cursor is (equal for a plain cursor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub anchor: usize,
+    pub cursor: usize,
+}
+
+impl Selection {
+    pub fn new(anchor: usize, cursor: usize) -> Self {
+        Self { anchor, cursor }
+    }
+
+    fn sorted(&self) -> (usize, usize) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+}
+
+/// The buffer after a line operation, and the selection to show in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineEdit {
+    pub text: String,
+    pub selection: Selection,
+}
+
+/// 0-based line and column (in chars) of a char offset; offsets past the end clamp to it
+fn position(lines: &[&str], offset: usize) -> (usize, usize) {
+    let mut start = 0;
+    for (index, line) in lines.iter().enumerate() {
+        let len = line.chars().count();
+        if offset <= start + len || index + 1 == lines.len() {
+            return (index, (offset - start.min(offset)).min(len));
+        }
+        start += len + 1;
+    }
+    (0, 0)
+}
+
+/// Char offset of 0-based `line`, `column`
+fn offset<S: AsRef<str>>(lines: &[S], line: usize, column: usize) -> usize {
+    lines[..line].iter().map(|l| l.as_ref().chars().count() + 1).sum::<usize>() + column
+}
+
+/// First and last 0-based line the selection touches, leaving out a last line it only reaches the start of
+fn line_span(lines: &[&str], selection: Selection) -> (usize, usize) {
+    let (start, end) = selection.sorted();
+    let first = position(lines, start).0;
+    let (last, column) = position(lines, end);
+    if column == 0 && last > first {
+        (first, last - 1)
+    } else {
+        (first, last)
+    }
+}
+
+/// Whether the selection covers text on more than one line
+pub fn spans_lines(text: &str, selection: Selection) -> bool {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (first, last) = line_span(&lines, selection);
+    last > first
+}
+
+/// Move the selected lines one line up or down; `None` at the top or bottom of the buffer
+pub fn move_lines(text: &str, selection: Selection, up: bool) -> Option<LineEdit> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (first, last) = line_span(&lines, selection);
+    if (up && first == 0) || (!up && last + 1 >= lines.len()) {
+        return None;
+    }
+    let mut moved = lines.clone();
+    // The line passed over takes the block's other side, and the selection moves by its length
+    let shift = if up {
+        let above = moved.remove(first - 1);
+        moved.insert(last, above);
+        -(above.chars().count() as isize + 1)
+    } else {
+        let below = moved.remove(last + 1);
+        moved.insert(first, below);
+        below.chars().count() as isize + 1
+    };
+    let map = |offset: usize| (offset as isize + shift) as usize;
+    Some(LineEdit { text: moved.join("\n"), selection: Selection::new(map(selection.anchor), map(selection.cursor)) })
+}
+
+/// Indent each selected line that has text by `tab_width` spaces
+///
+/// A selection end at the start of a line stays there, so the selection
+/// grows to take in the new indentation.
+pub fn indent_lines(text: &str, selection: Selection, tab_width: usize) -> Option<LineEdit> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (first, last) = line_span(&lines, selection);
+    let indent = " ".repeat(tab_width);
+    let added = |line: usize| if (first..=last).contains(&line) && !lines[line].is_empty() { tab_width } else { 0 };
+    if tab_width == 0 || (first..=last).all(|line| added(line) == 0) {
+        return None;
+    }
+    let indented: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| if added(index) > 0 { format!("{}{}", indent, line) } else { line.to_string() })
+        .collect();
+    let map = |at: usize| {
+        let (line, column) = position(&lines, at);
+        offset(&indented, line, if column > 0 { column + added(line) } else { 0 })
+    };
+    Some(LineEdit { text: indented.join("\n"), selection: Selection::new(map(selection.anchor), map(selection.cursor)) })
+}
+
+/// Leading chars to remove from `line` to take it back one tab stop
+///
+/// Spaces count one column each and a tab reaches the next tab stop, so
+/// `"  \tX"` loses all three with a tab width of 4, and `"\t  X"` only the tab.
+fn unindent_width(line: &str, tab_width: usize) -> usize {
+    let mut width = 0;
+    let mut removed = 0;
+    for c in line.chars() {
+        if width >= tab_width {
+            break;
+        }
+        match c {
+            ' ' => width += 1,
+            '\t' => width = tab_width,
+            _ => break,
+        }
+        removed += 1;
+    }
+    removed
+}
+
+/// Take each selected line back one tab stop; `None` when none is indented
+pub fn unindent_lines(text: &str, selection: Selection, tab_width: usize) -> Option<LineEdit> {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (first, last) = line_span(&lines, selection);
+    let removed = |line: usize| if (first..=last).contains(&line) { unindent_width(lines[line], tab_width) } else { 0 };
+    if (first..=last).all(|line| removed(line) == 0) {
+        return None;
+    }
+    let unindented: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| line.chars().skip(removed(index)).collect())
+        .collect();
+    let map = |at: usize| {
+        let (line, column) = position(&lines, at);
+        offset(&unindented, line, column.saturating_sub(removed(line)))
+    };
+    Some(LineEdit { text: unindented.join("\n"), selection: Selection::new(map(selection.anchor), map(selection.cursor)) })
+}
+
+/// Copy the selected lines below themselves and select the same text in the copy
+pub fn duplicate_lines(text: &str, selection: Selection) -> LineEdit {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (first, last) = line_span(&lines, selection);
+    let block = &lines[first..=last];
+    let shift = block.iter().map(|line| line.chars().count() + 1).sum::<usize>();
+    let duplicated: Vec<&str> = lines[..=last].iter().chain(block).chain(&lines[last + 1..]).copied().collect();
+    LineEdit { text: duplicated.join("\n"), selection: Selection::new(selection.anchor + shift, selection.cursor + shift) }
+}
+
+/// Line operation asked for by a key press this frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    MoveUp,
+    MoveDown,
+    Indent,
+    Unindent,
+    Duplicate,
+}
+
+/// Apply the line shortcut pressed in the focused editor as one undo step, and to `code`
+///
+/// Runs before the editor is shown, so the editor never sees the keys. A
+/// plain Tab without a selection spanning lines is left for the editor's own
+/// Tab handling.
+pub fn handle_shortcuts(app: &mut TimeWarpApp, ui: &egui::Ui, id: egui::Id, code: &mut String) {
+    if !ui.memory(|m| m.has_focus(id)) {
+        return;
+    }
+    let Some(mut state) = egui::TextEdit::load_state(ui.ctx(), id) else { return };
+    let Some(range) = state.cursor.char_range() else { return };
+    let selection = Selection::new(range.secondary.index, range.primary.index);
+    let multi_line = spans_lines(code, selection);
+    let operation = ui.input_mut(|i| {
+        if i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowUp) {
+            Some(Operation::MoveUp)
+        } else if i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowDown) {
+            Some(Operation::MoveDown)
+        } else if i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab) {
+            Some(Operation::Unindent)
+        } else if multi_line && i.consume_key(egui::Modifiers::NONE, egui::Key::Tab) {
+            Some(Operation::Indent)
+        } else if i.consume_key(egui::Modifiers::COMMAND, egui::Key::D) {
+            Some(Operation::Duplicate)
+        } else {
+            None
+        }
+    });
+    let Some(operation) = operation else { return };
+    let tab_width = app.effective_settings().tab_width;
+    let (edit, description) = match operation {
+        Operation::MoveUp => (move_lines(code, selection, true), "undo.move_lines"),
+        Operation::MoveDown => (move_lines(code, selection, false), "undo.move_lines"),
+        Operation::Indent => (indent_lines(code, selection, tab_width), "undo.indent"),
+        Operation::Unindent => (unindent_lines(code, selection, tab_width), "undo.unindent"),
+        Operation::Duplicate => (Some(duplicate_lines(code, selection)), "undo.duplicate_lines"),
+    };
+    let Some(edit) = edit else { return };
+    let Some(file) = app.current_file().cloned() else { return };
+    app.apply_buffer_edit(&file, edit.text.clone(), tr(description));
+    *code = edit.text;
+    state.cursor.set_char_range(Some(CCursorRange {
+        secondary: CCursor::new(edit.selection.anchor),
+        primary: CCursor::new(edit.selection.cursor),
+    }));
+    state.store(ui.ctx(), id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(text: &str, anchor: usize, cursor: usize) -> LineEdit {
+        LineEdit { text: text.to_string(), selection: Selection::new(anchor, cursor) }
+    }
+
+    #[test]
+    fn test_move_lines_at_the_edges() {
+        let text = "a\nbb\nccc";
+        // Cursor in "bb"
+        assert_eq!(move_lines(text, Selection::new(3, 3), true), Some(edit("bb\na\nccc", 1, 1)));
+        assert_eq!(move_lines(text, Selection::new(3, 3), false), Some(edit("a\nccc\nbb", 7, 7)));
+        // The first line cannot go up, nor the last line down
+        assert_eq!(move_lines(text, Selection::new(0, 1), true), None);
+        assert_eq!(move_lines(text, Selection::new(6, 8), false), None);
+        assert_eq!(move_lines(text, Selection::new(8, 8), true), Some(edit("a\nccc\nbb", 5, 5)));
+    }
+
+    #[test]
+    fn test_move_lines_keeps_a_selection_ending_at_a_newline() {
+        let text = "a\nbb\nccc\nd";
+        // "a\nbb\n" selected backwards: the cursor at the start of "ccc" does not take that line along
+        let moved = move_lines(text, Selection::new(5, 0), false).unwrap();
+        assert_eq!(moved, edit("ccc\na\nbb\nd", 9, 4));
+        assert_eq!(move_lines(&moved.text, moved.selection, true), Some(edit(text, 5, 0)));
+        // A trailing newline leaves an empty last line to move past
+        assert_eq!(move_lines("a\nb\n", Selection::new(2, 2), false), Some(edit("a\n\nb", 3, 3)));
+    }
+
+    #[test]
+    fn test_indent_lines_skips_empty_lines_and_keeps_the_selection() {
+        let text = "TO SQUARE\n\nFD 10\nEND";
+        // From inside "TO SQUARE" to the start of "END"
+        let indented = indent_lines(text, Selection::new(3, 17), 4).unwrap();
+        assert_eq!(indented, edit("    TO SQUARE\n\n    FD 10\nEND", 7, 25));
+        // A selection from the start of a line takes in the new indentation
+        assert_eq!(indent_lines("x\ny", Selection::new(0, 3), 2), Some(edit("  x\n  y", 0, 7)));
+        assert_eq!(indent_lines("\n\n", Selection::new(0, 2), 2), None, "nothing to indent");
+    }
+
+    #[test]
+    fn test_unindent_lines_with_mixed_tabs_and_spaces() {
+        assert_eq!(unindent_width("      X", 4), 4);
+        assert_eq!(unindent_width("  \tX", 4), 3);
+        assert_eq!(unindent_width("\t  X", 4), 1);
+        assert_eq!(unindent_width(" X", 4), 1);
+        assert_eq!(unindent_width("X", 4), 0);
+
+        let text = "\tFD 10\n      RT 90\n  \tPU";
+        let all = text.chars().count();
+        assert_eq!(unindent_lines(text, Selection::new(0, all), 4), Some(edit("FD 10\n  RT 90\nPU", 0, 16)));
+        // A cursor inside the removed indentation goes to the start of the text
+        assert_eq!(unindent_lines("    FD 10", Selection::new(2, 2), 4), Some(edit("FD 10", 0, 0)));
+        assert_eq!(unindent_lines("FD 10\n  RT 90", Selection::new(1, 1), 4), None, "the cursor's line has no indent");
+    }
+
+    #[test]
+    fn test_duplicate_lines() {
+        // The cursor's line, first and last
+        assert_eq!(duplicate_lines("a\nb", Selection::new(1, 1)), edit("a\na\nb", 3, 3));
+        assert_eq!(duplicate_lines("a\nb", Selection::new(3, 3)), edit("a\nb\nb", 5, 5));
+        // Selected lines, ending at the start of the next one, are copied below themselves
+        assert_eq!(duplicate_lines("a\nb\nc", Selection::new(0, 4)), edit("a\nb\na\nb\nc", 4, 8));
+    }
+
+    #[test]
+    fn test_spans_lines() {
+        assert!(!spans_lines("a\nb", Selection::new(0, 1)));
+        assert!(!spans_lines("a\nb", Selection::new(0, 2)), "ends at the start of the next line");
+        assert!(spans_lines("a\nb", Selection::new(1, 3)));
+    }
+}
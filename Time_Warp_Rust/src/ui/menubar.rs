@@ -1,153 +1,338 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::config::templates;
+use crate::graphics::history::starts_with_clearscreen;
+use crate::i18n::{tr, trf};
+use crate::graphics::{rgba, CoordinateMode};
+use crate::interpreter::window::ProgramEvent;
+use crate::interpreter::ScreenMode;
+use crate::languages::Language;
+use crate::interpreter::worker::{RunWorker, STREAM_INTERVAL};
+use crate::ui::execution::{RunAction, SessionEvent, SessionState};
 use crate::ui::themes::Theme;
+use crate::utils::run_reporters;
+use crate::utils::screen_export::{export_text_screen, text_screen, EXPORT_SCALES};
 
 pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
     egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
         egui::menu::bar(ui, |ui| {
             // File menu
-            ui.menu_button("File", |ui| {
-                if ui.button("📄 New").clicked() {
-                    new_file(app);
-                    ui.close_menu();
-                }
-                if ui.button("📂 Open...").clicked() {
+            let file_menu = ui.menu_button(tr("menu.file"), |ui| {
+                ui.menu_button(tr("menu.file.new"), |ui| {
+                    if ui.button(tr("menu.file.new.file")).clicked() {
+                        new_file(app, None);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    for language in [Language::Pilot, Language::Basic, Language::Logo, Language::TempleCode] {
+                        if ui.button(trf("menu.file.new.language", &[&language.name()])).clicked() {
+                            new_file(app, Some(language));
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if ui.button(tr("menu.file.open")).clicked() {
                     open_file(app);
                     ui.close_menu();
                 }
-                if ui.button("💾 Save").clicked() {
+                if ui.button(tr("menu.file.save")).clicked() {
                     save_file(app);
                     ui.close_menu();
                 }
-                if ui.button("💾 Save As...").clicked() {
+                if ui.button(tr("menu.file.save_as")).clicked() {
                     save_file_as(app);
                     ui.close_menu();
                 }
                 ui.separator();
-                if ui.button("❌ Exit").clicked() {
+                ui.menu_button(tr("menu.file.export"), |ui| {
+                    let text_mode = matches!(app.interpreter.screen_mode, ScreenMode::Text { .. });
+                    let item = ui.add_enabled(text_mode, egui::Button::new(tr("menu.file.export.text_screen")));
+                    if item.on_disabled_hover_text(tr("menu.file.export.text_screen.disabled")).clicked() {
+                        save_text_screen(app);
+                        ui.close_menu();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(tr("menu.file.export.scale"));
+                        for scale in EXPORT_SCALES {
+                            ui.selectable_value(&mut app.screen_export_scale, scale, format!("{}×", scale));
+                        }
+                    });
+                });
+                if ui.button(tr("menu.file.package")).clicked() {
+                    crate::ui::package::open(app);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(tr("menu.file.exit")).clicked() {
                     std::process::exit(0);
                 }
             });
+            // A lone Alt tap focuses the menu bar; arrows and Enter take it from there
+            if std::mem::take(&mut app.focus_menu_bar) {
+                file_menu.response.request_focus();
+            }
             
             // Edit menu
-            ui.menu_button("Edit", |ui| {
-                if ui.button("↶ Undo").clicked() {
+            ui.menu_button(tr("menu.edit"), |ui| {
+                let undo_label = match app.undo.undo_description() {
+                    Some(description) => trf("menu.edit.undo_named", &[&description]),
+                    None => tr("menu.edit.undo").to_string(),
+                };
+                if ui.add_enabled(app.undo.undo_description().is_some(), egui::Button::new(undo_label)).clicked() {
                     undo(app);
                     ui.close_menu();
                 }
-                if ui.button("↷ Redo").clicked() {
+                let redo_label = match app.undo.redo_description() {
+                    Some(description) => trf("menu.edit.redo_named", &[&description]),
+                    None => tr("menu.edit.redo").to_string(),
+                };
+                if ui.add_enabled(app.undo.redo_description().is_some(), egui::Button::new(redo_label)).clicked() {
                     redo(app);
                     ui.close_menu();
                 }
                 ui.separator();
-                if ui.button("🔍 Find/Replace").clicked() {
+                if ui.add_enabled(app.current_file().is_some(), egui::Button::new(tr("menu.edit.insert_file"))).clicked() {
+                    crate::ui::editor::insert_file_at_cursor(app, ui.ctx());
+                    ui.close_menu();
+                }
+                if ui.add_enabled(app.current_file().is_some(), egui::Button::new(tr("menu.edit.format"))).clicked() {
+                    format_document(app);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(tr("menu.edit.find_replace")).clicked() {
                     app.show_find_replace = !app.show_find_replace;
                     ui.close_menu();
                 }
+                if ui.add(egui::Button::new(tr("menu.edit.find_in_files")).shortcut_text("Ctrl+Shift+F")).clicked() {
+                    app.show_find_in_files = true;
+                    ui.close_menu();
+                }
+                if ui.add(egui::Button::new(tr("menu.edit.goto_symbol")).shortcut_text("Ctrl+Shift+O")).clicked() {
+                    app.outline.query.clear();
+                    app.show_goto_symbol = true;
+                    ui.close_menu();
+                }
+                if ui.add(egui::Button::new(tr("menu.edit.rename")).shortcut_text("F2")).clicked() {
+                    crate::ui::rename::open(app, ui.ctx());
+                    ui.close_menu();
+                }
+                if ui.add(egui::Button::new(tr("menu.edit.evaluate")).shortcut_text("Ctrl+Enter")).clicked() {
+                    crate::ui::inline_eval::evaluate(app, ui.ctx());
+                    ui.close_menu();
+                }
             });
             
             // Run menu
-            ui.menu_button("Run", |ui| {
-                if ui.button("▶️  Run Program").clicked() {
-                    run_program(app);
+            ui.menu_button(tr("menu.run"), |ui| {
+                if ui.button(tr("menu.run.run")).clicked() {
+                    request_run(app);
                     ui.close_menu();
                 }
-                if ui.button("⏸️ Step").clicked() {
+                if ui.add_enabled(app.execution.can_step(), egui::Button::new(tr("menu.run.step"))).clicked() {
                     step_program(app);
                     ui.close_menu();
                 }
-                if ui.button("⏹️ Stop").clicked() {
+                let at_breakpoint = app.execution.state() == SessionState::Paused && app.interpreter.breakpoints.paused_at.is_some();
+                if ui.add_enabled(at_breakpoint, egui::Button::new(tr("menu.run.continue"))).clicked() {
+                    continue_program(app);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(app.execution.can_stop(), egui::Button::new(tr("menu.run.stop"))).clicked() {
                     stop_program(app);
                     ui.close_menu();
                 }
             });
             
             // View menu
-            ui.menu_button("View", |ui| {
-                ui.menu_button("🎨 Theme", |ui| {
-                    for theme in Theme::all() {
-                        if ui.selectable_label(app.current_theme == theme, theme.name()).clicked() {
-                            app.current_theme = theme;
-                            ui.close_menu();
+            ui.menu_button(tr("menu.view"), |ui| {
+                // A project's theme wins until the project is closed
+                let theme_locked = app.project_overrides("theme");
+                ui.add_enabled_ui(!theme_locked, |ui| {
+                    ui.menu_button(tr("menu.view.theme"), |ui| {
+                        for theme in Theme::all() {
+                            if ui.selectable_label(app.current_theme == theme, theme.name()).clicked() {
+                                app.current_theme = theme;
+                                app.save_settings();
+                                ui.close_menu();
+                            }
                         }
-                    }
-                });
+                    });
+                })
+                .response
+                .on_disabled_hover_text(tr("project.set_by_file"));
+                if ui.button(tr("menu.view.settings")).clicked() {
+                    app.show_settings = true;
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut app.settings.show_outline, tr("menu.view.outline")).changed() {
+                    app.save_settings();
+                }
+                if ui.button(tr("menu.view.diagnostics")).clicked() {
+                    app.show_diagnostics = true;
+                    ui.close_menu();
+                }
                 ui.separator();
-                if ui.button("🐢 Clear Graphics").clicked() {
+                if ui.button(tr("menu.view.clear_graphics")).clicked() {
+                    app.canvas_history.push(&app.turtle_state);
                     app.turtle_state.clear();
                     ui.close_menu();
                 }
-                if ui.button("💾 Save Canvas as PNG...").clicked() {
-                    save_canvas_as_png(app);
+                let restore = egui::Button::new(trf("menu.view.restore_canvas", &[&app.canvas_history.len()]));
+                if ui.add_enabled(!app.canvas_history.is_empty(), restore).clicked() {
+                    if let Some(snapshot) = app.canvas_history.pop() {
+                        snapshot.restore(&mut app.turtle_state);
+                        app.active_tab = 1;
+                    }
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.view.save_png")).clicked() {
+                    app.canvas_export.open = true;
+                    ui.close_menu();
+                }
+            });
+            
+            // Tools menu
+            ui.menu_button(tr("menu.tools"), |ui| {
+                if ui.button(tr("menu.tools.share")).clicked() {
+                    crate::ui::share::share_current(app, ui.ctx());
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.tools.import_share")).clicked() {
+                    app.show_import_share = true;
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(tr("menu.tools.export_path")).clicked() {
+                    app.show_export_path = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.tools.program_report")).clicked() {
+                    app.show_program_report = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.tools.run_history")).clicked() {
+                    app.show_run_history = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.tools.compare_model")).clicked() {
+                    app.model_compare.open = true;
                     ui.close_menu();
                 }
             });
             
             // Help menu
-            ui.menu_button("Help", |ui| {
-                if ui.button("📖 Documentation").clicked() {
+            ui.menu_button(tr("menu.help"), |ui| {
+                if ui.button(tr("menu.help.documentation")).clicked() {
                     app.active_tab = 4; // Help tab
                     ui.close_menu();
                 }
-                if ui.button("ℹ️ About").clicked() {
+                if ui.button(tr("menu.help.welcome")).clicked() {
+                    crate::ui::welcome::show(app);
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.help.tutorials")).clicked() {
+                    app.show_tutorial = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.help.examples")).clicked() {
+                    app.show_gallery = true;
+                    ui.close_menu();
+                }
+                if ui.button(tr("menu.help.about")).clicked() {
                     show_about(app);
                     ui.close_menu();
                 }
             });
+            
+            // Assessment clock and Submit, at the far right
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                crate::ui::assessment::render_controls(app, ui);
+            });
         });
     });
 }
 
-fn new_file(app: &mut TimeWarpApp) {
-    let filename = format!("untitled_{}.pilot", app.open_files.len());
-    app.file_buffers.insert(filename.clone(), String::new());
+/// Open a tab started from the template for `language`, or for the selected language (PILOT without one)
+pub fn new_file(app: &mut TimeWarpApp, language: Option<Language>) {
+    let chosen = language.or(app.selected_language()).unwrap_or(Language::Pilot);
+    let filename = crate::ui::undo::unused_tab_name(&app.open_files, "untitled", chosen.extension());
+    let template = templates::load(chosen, app.project_root().as_deref(), templates::user_dir().as_deref());
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    app.file_buffers.insert(filename.clone(), templates::fill(&template, &filename, &date));
+    if language.is_some() {
+        // A language picked here beats the default one
+        app.buffer_languages.insert(filename.clone(), chosen);
+    }
     app.open_files.push(filename);
     app.current_file_index = app.open_files.len() - 1;
 }
 
 fn open_file(app: &mut TimeWarpApp) {
-    if let Some(path) = rfd::FileDialog::new()
+    let mut dialog = rfd::FileDialog::new()
         .add_filter("PILOT", &["pilot", "pil"])
         .add_filter("BASIC", &["bas", "basic"])
         .add_filter("Logo", &["logo", "lgo"])
-        .add_filter("All", &["*"])
-        .pick_file()
-    {
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            let filename = path.file_name().unwrap().to_string_lossy().to_string();
-            app.file_buffers.insert(filename.clone(), content);
-            app.open_files.push(filename);
-            app.current_file_index = app.open_files.len() - 1;
-            app.last_file_path = Some(path.to_string_lossy().to_string());
+        .add_filter(tr("file.filter.bundle"), &[crate::utils::twpak::PAK_EXTENSION])
+        .add_filter(tr("file.filter.all"), &["*"]);
+    if let Some(root) = &app.kiosk_root {
+        dialog = dialog.set_directory(root);
+    }
+    if let Some(path) = dialog.pick_file() {
+        if !app.may_open(&path) {
+            let root = app.kiosk_root.as_deref().unwrap_or(&path);
+            app.error_message = Some(trf("file.kiosk_only", &[&root.display()]));
+            return;
+        }
+        let opened = if crate::utils::twpak::is_pak_path(&path) { app.open_pak(&path) } else { app.open_path(&path) };
+        if let Err(e) = opened {
+            app.error_message = Some(trf("file.open_failed", &[&e]));
         }
     }
 }
 
 fn save_file(app: &mut TimeWarpApp) {
-    if let Some(ref path) = app.last_file_path {
-        let code = app.current_code();
-        let _ = std::fs::write(path, code);
-        if let Some(file) = app.current_file().cloned() {
-            app.file_modified.insert(file, false);
-        }
+    if let Some(path) = app.last_file_path.clone() {
+        let file = app.current_file().cloned().unwrap_or_default();
+        let _ = std::fs::write(&path, app.text_for_save(&file, &app.current_code()));
+        app.file_modified.insert(file, false);
+        reload_if_project_file(app, std::path::Path::new(&path));
     } else {
         save_file_as(app);
     }
 }
 
+/// Suggested name for Save As: the tab's name with the extension of the language it runs as
+fn default_file_name(app: &TimeWarpApp) -> String {
+    let stem = app
+        .current_file()
+        .and_then(|f| std::path::Path::new(f).file_stem())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string());
+    format!("{}.{}", stem, app.current_language().extension())
+}
+
 fn save_file_as(app: &mut TimeWarpApp) {
     if let Some(path) = rfd::FileDialog::new()
         .add_filter("PILOT", &["pilot"])
         .add_filter("BASIC", &["bas"])
         .add_filter("Logo", &["logo"])
+        .set_file_name(default_file_name(app))
         .save_file()
     {
-        let code = app.current_code();
-        let _ = std::fs::write(&path, code);
+        let file = app.current_file().cloned().unwrap_or_default();
+        let _ = std::fs::write(&path, app.text_for_save(&file, &app.current_code()));
         app.last_file_path = Some(path.to_string_lossy().to_string());
-        if let Some(file) = app.current_file().cloned() {
-            app.file_modified.insert(file, false);
-        }
+        app.file_paths.insert(file.clone(), std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone()));
+        app.file_modified.insert(file, false);
+        reload_if_project_file(app, &path);
+    }
+}
+
+/// Saving the project file from the editor applies it at once
+fn reload_if_project_file(app: &mut TimeWarpApp, path: &std::path::Path) {
+    if path.file_name().is_some_and(|name| name == crate::config::project::PROJECT_FILE) {
+        app.reload_project();
     }
 }
 
@@ -159,114 +344,334 @@ fn redo(app: &mut TimeWarpApp) {
     app.redo();
 }
 
+/// Lay the current tab out canonically for its language, as one undo step
+fn format_document(app: &mut TimeWarpApp) {
+    if let Some(file) = app.current_file().cloned() {
+        let formatted = crate::languages::format::format_source(&app.current_code(), app.current_language());
+        app.apply_buffer_edit(&file, formatted, tr("undo.format_document"));
+    }
+}
+
+/// Run (Run ▸ Run Program, F5), or first ask when the program would open by wiping a drawing (Settings → Canvas)
+pub fn request_run(app: &mut TimeWarpApp) {
+    if crate::ui::assessment::locked(app) {
+        app.error_message = Some(tr("assessment.locked").to_string());
+        return;
+    }
+    if !clear_for_run(app) {
+        return;
+    }
+    let wipes_drawing = app.turtle_state.total_lines() > 0 || !app.turtle_state.texts.is_empty();
+    if app.settings.confirm_clearscreen && wipes_drawing && starts_with_clearscreen(&app.current_code()) {
+        app.confirm_clear_run = true;
+    } else {
+        run_program(app);
+    }
+}
+
+/// Confirmation shown by `request_run`; the drawing is snapshotted either way
+pub fn render_run_confirmation(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut run = false;
+    let mut cancel = false;
+    egui::Window::new(tr("run.confirm_clear.title"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr("run.confirm_clear.body"));
+            ui.label(tr("run.confirm_clear.restore"));
+            ui.checkbox(&mut app.settings.confirm_clearscreen, tr("run.confirm_clear.ask"));
+            ui.horizontal(|ui| {
+                let run_button = ui.button(tr("run.confirm_clear.run"));
+                crate::ui::keyboard::focus_on_open(&run_button);
+                run = run_button.clicked();
+                cancel = ui.button(tr("common.cancel")).clicked();
+            });
+        });
+    if run || cancel {
+        app.confirm_clear_run = false;
+        if !app.settings.confirm_clearscreen {
+            app.save_settings();
+        }
+    }
+    if run {
+        run_program(app);
+    }
+}
+
+/// Make way for a new run: a paused one is aborted, and a running one asks before restarting
+///
+/// False when the new run has to wait for that answer.
+fn clear_for_run(app: &mut TimeWarpApp) -> bool {
+    match app.execution.run_action() {
+        RunAction::Start => true,
+        RunAction::AbortThenStart => {
+            abort_run(app);
+            true
+        }
+        RunAction::ConfirmRestart => {
+            app.confirm_restart_run = true;
+            false
+        }
+    }
+}
+
+/// Confirmation shown by `request_run` while a program is running
+pub fn render_restart_confirmation(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut restart = false;
+    let mut cancel = false;
+    egui::Window::new(tr("run.confirm_restart.title"))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr("run.confirm_restart.body"));
+            ui.horizontal(|ui| {
+                let restart_button = ui.button(tr("run.confirm_restart.restart"));
+                crate::ui::keyboard::focus_on_open(&restart_button);
+                restart = restart_button.clicked();
+                cancel = ui.button(tr("common.cancel")).clicked();
+            });
+        });
+    if restart || cancel {
+        app.confirm_restart_run = false;
+    }
+    if restart {
+        if app.execution.state() == SessionState::Running {
+            // poll_run_worker starts the new run once the worker has stopped
+            stop_program(app);
+            app.execution.request_restart();
+        } else {
+            // The run ended while the question was up
+            request_run(app);
+        }
+    }
+}
+
+/// Throw away a run paused at an input prompt or between steps
+fn abort_run(app: &mut TimeWarpApp) {
+    app.interpreter.finish_report(&app.turtle_state, None);
+    app.interpreter.abort();
+    app.input_buffer.clear();
+    app.step_mode = false;
+    app.current_debug_line = None;
+    app.execution.apply(SessionEvent::Abort);
+}
+
 fn run_program(app: &mut TimeWarpApp) {
-    app.is_executing = true;
+    // Something may have started while a confirmation was up
+    if !clear_for_run(app) {
+        return;
+    }
     let code = app.current_code();
+    app.run_file = app.current_file().cloned();
+    app.run_source = code.clone();
+    app.run_started = run_reporters::now();
+    app.run_time = std::time::Duration::ZERO;
+    app.run_stopped = false;
     
-    // Clear previous output and graphics
-    app.interpreter.output.clear();
+    // Clear previous output and graphics (keeping the drawing restorable)
+    app.interpreter.clear_output();
+    app.canvas_history.push(&app.turtle_state);
     app.turtle_state.clear();
+    app.turtle_state.accent_color = rgba(app.current_theme.accent());
+    app.turtle_state.line_budget = app.settings.low_memory_canvas.then_some(app.settings.canvas_line_budget);
+    // OPTION ERRORS in a previous run must not outlive it
+    app.interpreter.classic_errors = app.settings.classic_basic_errors;
+    app.interpreter.angle_mode = app.settings.angle_mode;
+    app.turtle_state.shape = app.settings.turtle_shape.clone();
+    // Likewise a SCREEN, OPTION COORDS, or SETCOLORCYCLE in a previous run
+    app.turtle_state.coordinate_mode = CoordinateMode::Centered;
+    app.turtle_state.set_color_cycle(Vec::new());
+    app.interpreter.run_seed = app.effective_settings().random_seed;
+    app.interpreter.disabled = app.disabled_statements();
+    app.interpreter.honor_source_breakpoints = app.effective_settings().honor_source_breakpoints;
+    app.interpreter.record_timing = app.effective_settings().record_output_timing;
+    app.interpreter.slice_budget.fixed = app.settings.statements_per_slice;
     
     // Transfer any pending key press to interpreter for INKEY$
     if app.last_key_pressed.is_some() {
         app.interpreter.last_key_pressed = app.last_key_pressed.take();
     }
     
+    app.interpreter.set_program_file(std::path::Path::new(app.run_file.as_deref().unwrap_or_default()));
     if let Err(e) = app.interpreter.load_program(&code) {
-        app.error_message = Some(format!("Failed to load program: {}", e));
-        app.is_executing = false;
+        app.error_message = Some(trf("run.load_failed", &[&e]));
+        crate::ui::run_summary::record(app, Some(e.to_string()));
         return;
     }
-    
-    match app.interpreter.execute(&mut app.turtle_state) {
-        Ok(_output) => {
-            app.active_tab = 1; // Switch to output tab
-        }
-        Err(e) => {
-            app.error_message = Some(format!("Execution error: {}", e));
-        }
+    // The status bar pick, or project or global defaults, for programs that do not choose for themselves
+    let settings = app.effective_settings();
+    if app.interpreter.metadata.language.is_none() {
+        app.interpreter.metadata.language = app.selected_language();
     }
+    if let Some(mode) = settings.screen_mode {
+        app.interpreter.screen_mode = mode;
+        (app.turtle_state.canvas_width, app.turtle_state.canvas_height) = mode.canvas_size();
+    }
+    // Loading ended any paused run, so the policy can be replaced now
+    let policy = app.security_policy();
+    let _ = app.interpreter.set_policy(policy.clone());
+    let reporters = run_reporters::from_settings(&settings.report, &policy, app.project_root().as_deref());
+    let program = app.run_file.clone().unwrap_or_default();
+    app.interpreter.start_report(&program, reporters);
+    app.active_tab = 1; // Watch the drawing appear
+    app.execution.apply(SessionEvent::Start);
+    start_run_worker(app);
+}
 
+/// Continue the loaded program on a background thread (a fresh run, or after INPUT)
+///
+/// The interpreter moves to the worker; `app.interpreter` is a blank stand-in
+/// until `poll_run_worker` takes it back.
+pub fn start_run_worker(app: &mut TimeWarpApp) {
+    let interpreter = std::mem::take(&mut app.interpreter);
+    // The stand-in shows the output so far, and the worker's slices add to it
+    app.interpreter.output = interpreter.output.clone();
+    app.run_worker = Some(RunWorker::spawn(interpreter, app.turtle_state.clone()));
+}
 
-    // If execution is waiting for input, keep executing flag set so UI can resume
-    if app.interpreter.pending_input.is_none() {
-        app.is_executing = false;
-    } else {
+/// Stream a background run's new turtle lines onto the canvas; finish the run once the worker is done
+pub fn poll_run_worker(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(worker) = app.run_worker.as_ref() else { return };
+    worker.drain_into(&mut app.turtle_state);
+    worker.drain_output_into(&mut app.interpreter.output);
+    for event in worker.take_events() {
+        match event {
+            ProgramEvent::Title(title) => app.program_title = title,
+            ProgramEvent::Status(status) => app.program_status = status,
+        }
+    }
+    if !worker.is_finished() {
+        ctx.request_repaint_after(STREAM_INTERVAL);
+        return;
+    }
+    let Some(worker) = app.run_worker.take() else { return };
+    let run = worker.finish();
+    app.interpreter = run.interpreter;
+    app.turtle_state = run.turtle;
+    app.run_time += run.elapsed;
+    let fatal_error = run.result.err().map(|e| e.to_string());
+    if let Some(e) = &fatal_error {
+        app.error_message = Some(trf("run.execution_error", &[&e]));
+    }
+    crate::ui::run_summary::record(app, fatal_error.clone());
+    // Error markers must not end up inside folded blocks
+    if let Some(file) = app.run_file.clone() {
+        let lines: Vec<usize> = app.interpreter.error_spans.iter().flat_map(|span| span.clone()).collect();
+        crate::ui::folding::reveal_lines(app, &file, lines);
+    }
+
+    // Stop pressed just as the program reached a prompt: nothing is left to answer
+    if app.run_stopped && app.interpreter.pending_input.is_some() {
+        app.interpreter.abort();
+    }
+    if app.interpreter.pending_input.is_some() {
+        app.execution.apply(SessionEvent::AwaitInput);
         app.active_tab = 1;
+    } else if app.interpreter.breakpoints.paused_at.is_some() {
+        app.execution.apply(SessionEvent::Break);
+        app.current_debug_line = Some(app.interpreter.source_line());
+    } else {
+        app.interpreter.finish_report(&app.turtle_state, fatal_error);
+        crate::ui::run_history::record(app);
+        app.execution.apply(SessionEvent::Finish);
+    }
+    if app.execution.take_restart() {
+        request_run(app);
+    }
+}
+
+/// Run ▸ Continue: carry on from the breakpoint the run stopped at, on the worker again
+pub fn continue_program(app: &mut TimeWarpApp) {
+    if app.interpreter.breakpoints.paused_at.is_none() || !app.execution.apply(SessionEvent::Resume) {
+        return;
     }
+    app.current_debug_line = None;
+    start_run_worker(app);
 }
 
+/// Run ▸ Step: start a program in step mode, or take its next step
 fn step_program(app: &mut TimeWarpApp) {
-    // Enable step mode and execute one line
+    let starting = match app.execution.state() {
+        SessionState::Idle | SessionState::Finished => true,
+        SessionState::Paused => false,
+        SessionState::Running | SessionState::WaitingInput => return,
+    };
+    if starting {
+        let code = app.current_code();
+        let file = app.current_file().cloned().unwrap_or_default();
+        app.interpreter.set_program_file(std::path::Path::new(&file));
+        if let Err(e) = app.interpreter.load_program(&code) {
+            app.error_message = Some(trf("run.load_error", &[&e]));
+            return;
+        }
+    }
     app.step_mode = true;
     app.debug_mode = true;
+    app.execution.apply(SessionEvent::Step);
     
-    if !app.is_executing {
-        // Start execution in step mode
-        app.is_executing = true;
-        let code = app.current_code();
-        
-        match app.interpreter.load_program(&code) {
-            Ok(_) => {
-                // Execute just one line
-                match app.interpreter.execute(&mut app.turtle_state) {
-                    Ok(_) => {
-                        app.current_debug_line = Some(app.interpreter.current_line);
-                        app.is_executing = false; // Pause after one step
-                    }
-                    Err(e) => {
-                        app.error_message = Some(format!("Step error: {}", e));
-                        app.is_executing = false;
-                        app.step_mode = false;
-                    }
-                }
-            }
-            Err(e) => {
-                app.error_message = Some(format!("Load error: {}", e));
+    match app.interpreter.execute(&mut app.turtle_state) {
+        Ok(_) => {
+            app.current_debug_line = Some(app.interpreter.current_line);
+            app.program_title = app.interpreter.program_title.clone();
+            app.program_status = app.interpreter.program_status.clone();
+            if app.interpreter.pending_input.is_some() {
+                // Answering the prompt carries on as a normal run
+                app.execution.apply(SessionEvent::AwaitInput);
+                app.active_tab = 1;
+            } else if app.interpreter.current_line >= app.interpreter.program_lines.len() {
+                app.execution.apply(SessionEvent::Finish);
                 app.step_mode = false;
             }
         }
-    } else {
-        // Continue stepping through execution
-        match app.interpreter.execute(&mut app.turtle_state) {
-            Ok(_) => {
-                app.current_debug_line = Some(app.interpreter.current_line);
-                if app.interpreter.current_line >= app.interpreter.program_lines.len() {
-                    app.is_executing = false;
-                    app.step_mode = false;
-                }
-            }
-            Err(e) => {
-                app.error_message = Some(format!("Step error: {}", e));
-                app.is_executing = false;
-                app.step_mode = false;
-            }
+        Err(e) => {
+            app.error_message = Some(trf("run.step_error", &[&e]));
+            app.execution.apply(SessionEvent::Finish);
+            app.step_mode = false;
         }
     }
 }
 
-fn stop_program(app: &mut TimeWarpApp) {
-    app.is_executing = false;
+pub fn stop_program(app: &mut TimeWarpApp) {
+    match app.execution.state() {
+        // The worker finishes its current statement; poll_run_worker then restores the state
+        SessionState::Running => {
+            if let Some(worker) = &app.run_worker {
+                worker.stop();
+            }
+            app.run_stopped = true;
+        }
+        // Stopped at an INPUT prompt: no worker will report back
+        SessionState::WaitingInput => {
+            app.run_stopped = true;
+            abort_run(app);
+            crate::ui::run_summary::record(app, None);
+        }
+        SessionState::Paused => abort_run(app),
+        SessionState::Idle | SessionState::Finished => {}
+    }
+    app.interpreter.cancel_speech();
 }
 
 fn show_about(app: &mut TimeWarpApp) {
     app.show_about_dialog = true;
 }
 
-fn save_canvas_as_png(app: &mut TimeWarpApp) {
-    if let Some(path) = rfd::FileDialog::new()
-        .add_filter("PNG Image", &["png"])
-        .set_file_name("turtle_canvas.png")
+/// File → Export → Text Screen: the text-mode screen as PNG, SVG, or HTML
+fn save_text_screen(app: &mut TimeWarpApp) {
+    let Some(screen) = text_screen(&app.interpreter, &app.current_theme.colors(), app.settings.show_warnings) else { return };
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(tr("file.filter.png"), &["png"])
+        .add_filter(tr("file.filter.svg"), &["svg"])
+        .add_filter(tr("file.filter.html"), &["html", "htm"])
+        .set_file_name("screen.png")
         .save_file()
-    {
-        match app.turtle_state.save_png(&path.to_string_lossy()) {
-            Ok(_) => {
-                app.error_message = Some(format!("Canvas saved to {}", path.display()));
-            }
-            Err(e) => {
-                app.error_message = Some(format!("Failed to save PNG: {}", e));
-            }
-        }
-    }
+    else {
+        return;
+    };
+    let title = app.current_file().cloned().unwrap_or_else(|| tr("menu.file.export.text_screen").to_string());
+    app.error_message = Some(match export_text_screen(&screen, &path, app.screen_export_scale, &title) {
+        Ok(()) => trf("screen.exported", &[&path.display()]),
+        Err(e) => trf("file.save_failed", &[&path.display(), &e]),
+    });
 }
 
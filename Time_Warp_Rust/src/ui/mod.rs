@@ -1,10 +1,38 @@
 pub mod themes;
+pub mod transcript;
+pub mod replay;
 pub mod menubar;
 pub mod statusbar;
 pub mod editor;
+pub mod folding;
+pub mod line_edit;
 pub mod output;
 pub mod canvas;
+pub mod canvas_export;
 pub mod screen;
 pub mod debugger;
+pub mod direct_mode;
 pub mod explorer;
 pub mod help;
+pub mod settings;
+pub mod tutorial;
+pub mod undo;
+pub mod share;
+pub mod find_in_files;
+pub mod gallery;
+pub mod export_path;
+pub mod program_report;
+pub mod keyboard;
+pub mod language_switch;
+pub mod output_diff;
+pub mod run_history;
+pub mod run_summary;
+pub mod welcome;
+pub mod execution;
+pub mod outline;
+pub mod rename;
+pub mod inline_eval;
+pub mod package;
+pub mod assessment;
+pub mod diagnostics;
+pub mod model_compare;
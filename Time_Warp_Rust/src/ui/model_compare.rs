@@ -0,0 +1,157 @@
+//! Tools ▸ Compare With Model: the current tab and a model answer side by side
+//!
+//! Pick the model's file and type the answers both programs get, one per
+//! line; Compare runs them on a background thread (see [`Comparison`]) and
+//! shows each program's drawing, small and at the same scale, over its
+//! transcript. Lines and segments the other program did not produce are
+//! highlighted.
+
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::graphics::raster::thumbnail_view;
+use crate::graphics::{color32, Rect};
+use crate::i18n::{tr, trf};
+use crate::utils::model_compare::{CompareOptions, ComparedRun, Comparison, DEFAULT_SEED};
+use crate::utils::output_diff::DiffLine;
+use crate::utils::safe_open::{read_text, MAX_OPEN_BYTES};
+
+/// Size of each small canvas in points
+const CANVAS_SIZE: egui::Vec2 = egui::vec2(220.0, 220.0);
+
+/// Color of lines and segments only one program produced
+const ONLY_HERE: egui::Color32 = egui::Color32::from_rgb(230, 90, 90);
+
+/// The picked model, the answers, and the last comparison
+#[derive(Default)]
+pub struct ModelComparePanel {
+    pub open: bool,
+    model: Option<PathBuf>,
+    answers: String,
+    running: Option<JoinHandle<Comparison>>,
+    /// The last comparison, with the student's tab name
+    result: Option<(String, Comparison)>,
+    /// Tab name of the run in progress
+    running_file: String,
+}
+
+/// Run the current tab and the model on a background thread
+fn start(app: &mut TimeWarpApp) {
+    let Some(path) = app.model_compare.model.clone() else { return };
+    let model = match read_text(&path, MAX_OPEN_BYTES) {
+        Ok(opened) => crate::utils::line_endings::normalize(&opened.text).text,
+        Err(e) => {
+            app.error_message = Some(trf("compare.read_failed", &[&path.display(), &e]));
+            return;
+        }
+    };
+    let student = app.current_code();
+    let options = CompareOptions::new(&app.model_compare.answers, app.effective_settings().random_seed.unwrap_or(DEFAULT_SEED));
+    app.model_compare.running_file = app.current_file().cloned().unwrap_or_default();
+    app.model_compare.running = Some(std::thread::spawn(move || Comparison::run(&student, &model, &options)));
+}
+
+/// Compare With Model window
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let panel = &mut app.model_compare;
+    if panel.running.as_ref().is_some_and(|handle| handle.is_finished()) {
+        let file = std::mem::take(&mut panel.running_file);
+        panel.result = panel.running.take().and_then(|handle| handle.join().ok()).map(|comparison| (file, comparison));
+    }
+    let running = panel.running.is_some();
+    if running {
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+    let mut open = panel.open;
+    let mut compare = false;
+    egui::Window::new(tr("compare.title")).open(&mut open).default_size([620.0, 560.0]).show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            if ui.button(tr("compare.choose")).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter(tr("file.filter.programs"), &["pilot", "pil", "bas", "basic", "logo", "lgo", "tc"])
+                    .add_filter(tr("file.filter.all"), &["*"])
+                    .pick_file()
+                {
+                    panel.model = Some(path);
+                }
+            }
+            match &panel.model {
+                Some(path) => ui.monospace(path.display().to_string()),
+                None => ui.weak(tr("compare.none_chosen")),
+            };
+        });
+        ui.label(tr("compare.answers"));
+        ui.add(egui::TextEdit::multiline(&mut panel.answers).desired_rows(3).hint_text(tr("compare.answers.hint")).code_editor());
+        ui.horizontal(|ui| {
+            compare = ui.add_enabled(panel.model.is_some() && !running, egui::Button::new(tr("compare.run"))).clicked();
+            if running {
+                ui.spinner();
+            }
+        });
+        ui.separator();
+        let Some((file, comparison)) = &panel.result else { return };
+        render_comparison(ui, file, comparison);
+    });
+    app.model_compare.open = open;
+    if compare {
+        start(app);
+    }
+}
+
+/// The summary, then the model and the student in two columns
+fn render_comparison(ui: &mut egui::Ui, file: &str, comparison: &Comparison) {
+    if comparison.matches() {
+        ui.label(tr("compare.same"));
+    } else {
+        ui.label(trf("compare.counts", &[&comparison.output.removed, &comparison.output.added, &comparison.differing_segments()]));
+    }
+    ui.label(&comparison.output.turtle_summary);
+    let view = comparison.view_bounds();
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        ui.columns(2, |columns| {
+            columns[0].strong(tr("compare.model"));
+            render_side(&mut columns[0], &comparison.model, view, comparison.transcript.iter().filter_map(|line| match line {
+                DiffLine::Same(text) => Some((text, false)),
+                DiffLine::Removed(text) => Some((text, true)),
+                DiffLine::Added(_) => None,
+            }));
+            columns[1].strong(trf("compare.student", &[&file]));
+            render_side(&mut columns[1], &comparison.student, view, comparison.transcript.iter().filter_map(|line| match line {
+                DiffLine::Same(text) => Some((text, false)),
+                DiffLine::Added(text) => Some((text, true)),
+                DiffLine::Removed(_) => None,
+            }));
+        });
+    });
+}
+
+/// One program's small canvas and transcript; `lines` are its transcript lines, flagged when only it printed them
+fn render_side<'a>(ui: &mut egui::Ui, run: &ComparedRun, view: Option<Rect>, lines: impl Iterator<Item = (&'a String, bool)>) {
+    let (response, painter) = ui.allocate_painter(CANVAS_SIZE, egui::Sense::hover());
+    painter.rect_filled(response.rect, 4.0, ui.visuals().extreme_bg_color);
+    if let Some(view) = view {
+        let to_screen = egui::emath::RectTransform::from_to(thumbnail_view(view, CANVAS_SIZE.x as u32, CANVAS_SIZE.y as u32), response.rect);
+        let mut only_here = vec![false; run.report.lines.len()];
+        for &i in &run.unmatched {
+            only_here[i] = true;
+        }
+        // Matched segments first, so the highlighted ones stay on top
+        let matched = run.report.lines.iter().zip(&only_here).filter(|(_, only)| !**only).map(|(line, _)| (line, color32(line.color)));
+        let unmatched = run.unmatched.iter().map(|&i| (&run.report.lines[i], ONLY_HERE));
+        for (line, color) in matched.chain(unmatched) {
+            painter.line_segment([to_screen * line.start, to_screen * line.end], egui::Stroke::new(line.width, color));
+        }
+    }
+    if let Some(error) = &run.report.error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    } else if run.report.waiting_for_input {
+        ui.colored_label(ui.visuals().warn_fg_color, tr("compare.waiting"));
+    }
+    for (text, only_here) in lines {
+        let text = egui::RichText::new(text).monospace();
+        ui.label(if only_here { text.color(ONLY_HERE) } else { text });
+    }
+}
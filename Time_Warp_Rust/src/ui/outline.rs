@@ -0,0 +1,224 @@
+//! Outline panel and Go to Symbol (Ctrl+Shift+O)
+//!
+//! Both list the symbols `scan_symbols` finds in the current buffer: PILOT
+//! labels, BASIC jump targets and `DEF FN`s, Logo procedures. The list is
+//! rescanned once typing pauses, and picking a symbol moves the editor there.
+
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::languages::symbols::{scan_symbols, Symbol, SymbolKind};
+use crate::languages::Language;
+
+/// Quiet time after the last edit before the symbols are rescanned
+const RESCAN_DELAY: Duration = Duration::from_millis(300);
+
+/// Symbols of the current buffer, shared by the Outline panel and Go to Symbol
+#[derive(Default)]
+pub struct OutlinePanel {
+    pub symbols: Vec<Symbol>,
+    /// Go to Symbol search text
+    pub query: String,
+    file: String,
+    language: Option<Language>,
+    /// Text the symbols were scanned from
+    source: String,
+    /// Edited text waiting for the rescan, and when it was first seen
+    pending: Option<(String, Instant)>,
+}
+
+impl OutlinePanel {
+    /// Rescan `source` once it has stayed unchanged for [`RESCAN_DELAY`]
+    ///
+    /// Switching to another file or language scans at once. Returns how long
+    /// to wait before calling again while a rescan is pending.
+    pub fn refresh(&mut self, file: &str, language: Language, source: &str, now: Instant) -> Option<Duration> {
+        let same_buffer = self.file == file && self.language == Some(language);
+        if same_buffer {
+            if self.source == source {
+                self.pending = None;
+                return None;
+            }
+            match &self.pending {
+                Some((text, since)) if text == source => {
+                    let waited = now.duration_since(*since);
+                    if waited < RESCAN_DELAY {
+                        return Some(RESCAN_DELAY - waited);
+                    }
+                }
+                _ => {
+                    self.pending = Some((source.to_string(), now));
+                    return Some(RESCAN_DELAY);
+                }
+            }
+        }
+        self.symbols = scan_symbols(source, language);
+        self.file = file.to_string();
+        self.language = Some(language);
+        self.source = source.to_string();
+        self.pending = None;
+        None
+    }
+}
+
+/// How well `query` matches `name`: its characters in order, ignoring case
+///
+/// Runs of adjacent characters and matches at the start of a word score
+/// higher. `None` when some character of the query is missing.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + name[next..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 10;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// Symbols matching `query`, best first (all of them, in line order, for an empty query)
+pub fn matching_symbols<'a>(symbols: &'a [Symbol], query: &str) -> Vec<&'a Symbol> {
+    let mut scored: Vec<(i32, &Symbol)> =
+        symbols.iter().filter_map(|s| fuzzy_score(query, &s.name).map(|score| (score, s))).collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, s)| s).collect()
+}
+
+fn icon(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Label => "🏷",
+        SymbolKind::LineNumber => "#",
+        SymbolKind::Function => "ƒ",
+        SymbolKind::Procedure => "🐢",
+    }
+}
+
+fn symbol_button(ui: &mut egui::Ui, symbol: &Symbol) -> egui::Response {
+    ui.selectable_label(false, format!("{} {}", icon(symbol.kind), symbol.name))
+        .on_hover_text(trf("outline.line", &[&symbol.line]))
+}
+
+/// Show the editor at 1-based `line`
+fn go_to(app: &mut TimeWarpApp, line: usize) {
+    app.pending_goto_line = Some(line);
+    app.active_tab = 0;
+}
+
+/// Keep the symbol list in step with the current buffer; call once per frame
+pub fn update(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let file = app.current_file().cloned().unwrap_or_default();
+    let language = app.current_language();
+    let code = app.current_code();
+    if let Some(wait) = app.outline.refresh(&file, language, &code, Instant::now()) {
+        ctx.request_repaint_after(wait);
+    }
+}
+
+/// Right-hand Outline panel; draw before the central panel
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut target = None;
+    egui::SidePanel::right("outline").resizable(true).default_width(180.0).show(ctx, |ui| {
+        ui.heading(tr("outline.title"));
+        ui.separator();
+        if app.outline.symbols.is_empty() {
+            ui.weak(tr("outline.empty"));
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for symbol in &app.outline.symbols {
+                if symbol_button(ui, symbol).clicked() {
+                    target = Some(symbol.line);
+                }
+            }
+        });
+    });
+    if let Some(line) = target {
+        go_to(app, line);
+    }
+}
+
+/// Go to Symbol: fuzzy search over the outline's symbols
+pub fn render_goto(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_goto_symbol;
+    let mut target = None;
+    egui::Window::new(tr("outline.goto_title")).open(&mut open).collapsible(false).show(ctx, |ui| {
+        let search = ui.text_edit_singleline(&mut app.outline.query);
+        crate::ui::keyboard::focus_on_open(&search);
+        let matches = matching_symbols(&app.outline.symbols, &app.outline.query);
+        if search.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            target = matches.first().map(|s| s.line);
+        }
+        if matches.is_empty() {
+            ui.weak(tr("outline.no_match"));
+        }
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for symbol in matches {
+                if symbol_button(ui, symbol).clicked() {
+                    target = Some(symbol.line);
+                }
+            }
+        });
+    });
+    app.show_goto_symbol = open;
+    if let Some(line) = target {
+        app.show_goto_symbol = false;
+        go_to(app, line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescan_waits_for_typing_to_pause() {
+        let mut panel = OutlinePanel::default();
+        let start = Instant::now();
+        assert_eq!(panel.refresh("a.pilot", Language::Pilot, "L:ONE", start), None, "new file scans at once");
+        assert_eq!(panel.symbols.len(), 1);
+
+        let source = "L:ONE\nL:TWO";
+        assert_eq!(panel.refresh("a.pilot", Language::Pilot, source, start), Some(RESCAN_DELAY));
+        let later = start + Duration::from_millis(200);
+        assert_eq!(panel.refresh("a.pilot", Language::Pilot, source, later), Some(Duration::from_millis(100)));
+        assert_eq!(panel.symbols.len(), 1, "still the old list");
+        // Another keystroke restarts the wait
+        let edited = "L:ONE\nL:TWO\nL:T";
+        assert_eq!(panel.refresh("a.pilot", Language::Pilot, edited, later), Some(RESCAN_DELAY));
+        assert!(panel.refresh("a.pilot", Language::Pilot, edited, later + RESCAN_DELAY).is_none());
+        assert_eq!(panel.symbols.len(), 3);
+
+        assert!(panel.refresh("b.logo", Language::Logo, "TO BOX\nEND", later).is_none());
+        assert_eq!(panel.symbols[0].name, "BOX");
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_runs_and_word_starts() {
+        assert!(fuzzy_score("sq", "SQUARE :SIZE").is_some());
+        assert!(fuzzy_score("qs", "SQUARE").is_none(), "order matters");
+        assert!(fuzzy_score("sz", "SQUARE :SIZE").is_some());
+        assert!(fuzzy_score("squ", "SQUARE").unwrap() > fuzzy_score("sqe", "SQUARE").unwrap());
+        assert!(fuzzy_score("size", "SIZE").unwrap() > fuzzy_score("size", "SEIZE").unwrap());
+        assert_eq!(fuzzy_score("", "ANY"), Some(0));
+    }
+
+    #[test]
+    fn test_matching_symbols_best_first() {
+        let symbols = scan_symbols("L:LOOP\nL:OPEN_DOOR\nL:LOOK", Language::Pilot);
+        let names = |query| matching_symbols(&symbols, query).into_iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names(""), vec!["LOOP", "OPEN_DOOR", "LOOK"]);
+        assert_eq!(names("look"), vec!["LOOK"]);
+        assert_eq!(names("door"), vec!["OPEN_DOOR"]);
+        assert_eq!(names("lo")[..2], ["LOOP", "LOOK"]);
+    }
+}
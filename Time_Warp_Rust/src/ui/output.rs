@@ -1,28 +1,156 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::graphics::MeasureTool;
+use crate::i18n::{tr, trf};
+use crate::interpreter::dialog::DialogAnswer;
+use crate::interpreter::InputKind;
+use crate::ui::execution::{SessionEvent, SessionState};
+
+/// Hand the answer to the paused program and carry on with the run
+///
+/// A prompt left over from a run that has since been stopped takes nothing.
+fn submit_input(app: &mut TimeWarpApp, value: &str) {
+    app.input_buffer.clear();
+    if app.execution.state() != SessionState::WaitingInput {
+        return;
+    }
+    app.interpreter.provide_input(value);
+    app.execution.apply(SessionEvent::Resume);
+    crate::ui::menubar::start_run_worker(app);
+}
+
+/// A program's message box (`MSGBOX`, `D:`): OK, or Yes and No; Enter picks OK or Yes
+fn render_dialog(app: &mut TimeWarpApp, ctx: &egui::Context, message: &str, yes_no: bool) {
+    let frame = egui::Frame::window(&ctx.style()).stroke(egui::Stroke::new(2.0, crate::ui::run_summary::WAITING_COLOR));
+    egui::Window::new(tr("dialog.title"))
+        .frame(frame)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+        .show(ctx, |ui| {
+            ui.label(format!("💬 {}", message));
+            let enter = ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.horizontal(|ui| {
+                if yes_no {
+                    if ui.button(tr("dialog.yes")).clicked() || enter {
+                        submit_input(app, DialogAnswer::Yes.as_str());
+                    } else if ui.button(tr("dialog.no")).clicked() {
+                        submit_input(app, DialogAnswer::No.as_str());
+                    }
+                } else if ui.button(tr("common.ok")).clicked() || enter {
+                    submit_input(app, DialogAnswer::Ok.as_str());
+                }
+            });
+        });
+}
+
+/// `INPUT$(n)`: keys go to the program as they are typed, with no text field or Enter
+fn render_key_input(app: &mut TimeWarpApp, ui: &mut egui::Ui, count: usize) {
+    let typed: Vec<String> = ui.input(|i| {
+        i.events
+            .iter()
+            .filter_map(|e| match e {
+                egui::Event::Text(text) => Some(text.clone()),
+                egui::Event::Key { key: egui::Key::Enter, pressed: true, .. } => Some("\r".to_string()),
+                _ => None,
+            })
+            .collect()
+    });
+    for text in typed {
+        app.input_buffer.push_str(&text);
+    }
+    let got = app.input_buffer.chars().count();
+    if got >= count {
+        let keys: String = app.input_buffer.chars().take(count).collect();
+        submit_input(app, &keys);
+        return;
+    }
+    if count == 1 {
+        ui.label(tr("input.press_key"));
+    } else {
+        ui.label(trf("input.press_keys", &[&count, &got]));
+    }
+    if ui.button(tr("common.cancel")).clicked() {
+        submit_input(app, "");
+    }
+}
+
+/// Ruler and protractor toggles, with the readout once a measurement is complete
+fn render_measure_tools(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        let before = app.measure_tool;
+        for (tool, label, hover) in [
+            (MeasureTool::Ruler, tr("measure.ruler"), tr("measure.ruler.hover")),
+            (MeasureTool::Protractor, tr("measure.protractor"), tr("measure.protractor.hover")),
+        ] {
+            if ui.selectable_label(app.measure_tool == tool, label).on_hover_text(hover).clicked() {
+                app.measure_tool = if app.measure_tool == tool { MeasureTool::Off } else { tool };
+            }
+        }
+        if app.measure_tool != before {
+            app.measure_points.clear();
+        }
+        match app.measure_tool.measure(&app.measure_points) {
+            Some(measurement) => {
+                let readout = measurement.readout();
+                ui.monospace(&readout);
+                if ui.small_button(tr("common.copy")).clicked() {
+                    ui.ctx().copy_text(readout);
+                }
+            }
+            None => {
+                ui.weak(app.measure_tool.hint(app.measure_points.len()));
+            }
+        }
+    });
+}
 
 pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    // Bottom panel first, so the screen gets the space left above it
+    crate::ui::transcript::render(app, ui);
     // Unified output screen (text + graphics)
     ui.vertical(|ui| {
-        ui.heading("Unified Screen");
+        crate::ui::run_summary::render(app, ui);
+        ui.heading(tr("output.heading"));
         ui.horizontal(|ui| {
-            ui.checkbox(&mut app.show_overlay_text, "Overlay text in graphics");
+            ui.checkbox(&mut app.show_overlay_text, tr("output.overlay_text"));
+            ui.checkbox(&mut app.highlight_variables, tr("output.highlight_variables"))
+                .on_hover_text(tr("output.highlight_variables.hover"));
+            crate::ui::output_diff::render_pin_button(app, ui);
         });
+        render_measure_tools(app, ui);
+        crate::ui::direct_mode::render(app, ui);
         ui.separator();
         crate::ui::screen::render(app, ui);
     });
 
     // If interpreter is waiting for input, show a prompt overlay
-    if let Some(req) = app.interpreter.pending_input.clone() {
-        egui::Window::new("Input Required")
+    if let Some(InputKind::Dialog { yes_no }) = app.interpreter.pending_input.as_ref().map(|req| req.kind) {
+        let message = app.interpreter.pending_input.as_ref().map(|req| req.prompt.clone()).unwrap_or_default();
+        render_dialog(app, ui.ctx(), &message, yes_no);
+    } else if let Some(req) = app.interpreter.pending_input.clone() {
+        // Outlined in the banner's waiting color so the prompt is hard to miss
+        let frame = egui::Frame::window(ui.style()).stroke(egui::Stroke::new(2.0, crate::ui::run_summary::WAITING_COLOR));
+        egui::Window::new(tr("input.title"))
+            .frame(frame)
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
             .show(ui.ctx(), |ui| {
+                if let InputKind::Keys(count) = req.kind {
+                    render_key_input(app, ui, count);
+                    return;
+                }
                 ui.label(format!("📝 {}", req.prompt));
+                if let Some(validation) = &req.validation {
+                    if let Some(feedback) = &validation.feedback {
+                        let tries = app.interpreter.limits.max_input_retries + 1 - validation.failures;
+                        ui.colored_label(egui::Color32::from_rgb(230, 160, 60), trf("input.tries_left", &[&feedback, &tries]));
+                    }
+                }
                 let response = ui.add(
                     egui::TextEdit::singleline(&mut app.input_buffer)
-                        .hint_text("Type here and press Enter")
+                        .hint_text(tr("input.hint"))
                         .desired_width(300.0)
                 );
                 
@@ -31,35 +159,13 @@ pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
                 let should_submit = enter_pressed && response.has_focus();
                 
                 ui.horizontal(|ui| {
-                    if ui.button("Submit").clicked() || should_submit {
+                    if ui.button(tr("input.submit")).clicked() || should_submit {
                         let value = app.input_buffer.clone();
-                        app.input_buffer.clear();
-                        app.interpreter.provide_input(&value);
-                        // Resume execution if we were running
-                        if app.is_executing {
-                            if let Err(e) = app.interpreter.execute(&mut app.turtle_state) {
-                                app.error_message = Some(format!("Execution error: {}", e));
-                                app.is_executing = false;
-                            } else {
-                                // If still waiting, remain executing; else stop
-                                if app.interpreter.pending_input.is_none() {
-                                    app.is_executing = false;
-                                }
-                            }
-                        }
+                        submit_input(app, &value);
                     }
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(tr("common.cancel")).clicked() {
                         // Treat cancel as empty input
-                        app.input_buffer.clear();
-                        app.interpreter.provide_input("");
-                        if app.is_executing {
-                            if let Err(e) = app.interpreter.execute(&mut app.turtle_state) {
-                                app.error_message = Some(format!("Execution error: {}", e));
-                                app.is_executing = false;
-                            } else if app.interpreter.pending_input.is_none() {
-                                app.is_executing = false;
-                            }
-                        }
+                        submit_input(app, "");
                     }
                 });
                 // Request focus on first frame
@@ -0,0 +1,86 @@
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::utils::output_diff::{DiffLine, OutputDiff, TranscriptSnapshot};
+
+/// Main tab index of the Diff tab (shown while an output is pinned)
+pub const DIFF_TAB: usize = 5;
+
+/// Pinned run and the diff against the latest output
+#[derive(Default)]
+pub struct OutputDiffPanel {
+    pub pinned: Option<TranscriptSnapshot>,
+    /// Last diff, reused until the current output changes
+    cached: Option<(TranscriptSnapshot, OutputDiff)>,
+}
+
+impl OutputDiffPanel {
+    pub fn pin(&mut self, snapshot: TranscriptSnapshot) {
+        self.pinned = Some(snapshot);
+        self.cached = None;
+    }
+
+    pub fn unpin(&mut self) {
+        self.pinned = None;
+        self.cached = None;
+    }
+
+    fn diff(&mut self, current: TranscriptSnapshot) -> Option<&OutputDiff> {
+        let pinned = self.pinned.as_ref()?;
+        if self.cached.as_ref().is_none_or(|(seen, _)| *seen != current) {
+            let diff = OutputDiff::between(pinned, &current);
+            self.cached = Some((current, diff));
+        }
+        self.cached.as_ref().map(|(_, diff)| diff)
+    }
+}
+
+/// tr("diff.pin") / tr("diff.unpin") controls for the Output tab
+pub fn render_pin_button(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    if ui.button(tr("diff.pin")).on_hover_text(tr("diff.pin.hover")).clicked() {
+        let snapshot = TranscriptSnapshot::capture(&app.interpreter.output, &app.turtle_state);
+        app.output_diff.pin(snapshot);
+    }
+    if app.output_diff.pinned.is_some() && ui.button(tr("diff.unpin")).clicked() {
+        app.output_diff.unpin();
+        if app.active_tab == DIFF_TAB {
+            app.active_tab = 1;
+        }
+    }
+}
+
+/// Diff tab: unified diff of the pinned and current transcripts (read-only)
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.heading(tr("diff.title"));
+    let current = TranscriptSnapshot::capture(&app.interpreter.output, &app.turtle_state);
+    let Some(diff) = app.output_diff.diff(current) else {
+        ui.label(tr("diff.empty"));
+        return;
+    };
+
+    if diff.is_identical() {
+        ui.label(tr("diff.identical"));
+    } else {
+        ui.label(trf("diff.counts", &[&diff.added, &diff.removed]));
+    }
+    ui.label(&diff.turtle_summary);
+    ui.separator();
+
+    let added = egui::Color32::from_rgb(90, 200, 110);
+    let removed = egui::Color32::from_rgb(230, 90, 90);
+    let header = ui.visuals().weak_text_color();
+    egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+        for hunk in &diff.hunks {
+            ui.label(egui::RichText::new(hunk.header()).monospace().color(header));
+            for line in &hunk.lines {
+                let text = match line {
+                    DiffLine::Same(text) => egui::RichText::new(format!("  {}", text)),
+                    DiffLine::Removed(text) => egui::RichText::new(format!("- {}", text)).color(removed),
+                    DiffLine::Added(text) => egui::RichText::new(format!("+ {}", text)).color(added),
+                };
+                ui.label(text.monospace());
+            }
+        }
+    });
+}
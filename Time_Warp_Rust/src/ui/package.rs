@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::utils::twpak::{build_pak, collect_project, PAK_EXTENSION};
+
+/// Most files offered in the Package Project list
+const MAX_LISTED_FILES: usize = 200;
+/// Subfolder levels searched for files to offer
+const MAX_LISTED_DEPTH: usize = 2;
+
+/// Package Project options: the entry program and which files go with it
+#[derive(Default)]
+pub struct PackagePanel {
+    root: PathBuf,
+    entry: String,
+    /// Files in the program's folder, with whether each is included
+    files: Vec<(String, bool)>,
+}
+
+/// Files under `root` as `/`-separated relative paths, skipping hidden entries
+fn list_files(root: &Path, dir: &Path, depth: usize, out: &mut Vec<String>) {
+    let Ok(read) = std::fs::read_dir(dir) else { return };
+    let mut paths: Vec<PathBuf> = read.flatten().map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if out.len() >= MAX_LISTED_FILES {
+            return;
+        }
+        let hidden = path.file_name().and_then(|n| n.to_str()).is_none_or(|n| n.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            if depth < MAX_LISTED_DEPTH {
+                list_files(root, &path, depth + 1, out);
+            }
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let name: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+            out.push(name.join("/"));
+        }
+    }
+}
+
+/// Open the Package Project window for the current program (it must be saved)
+pub fn open(app: &mut TimeWarpApp) {
+    let saved = app.last_file_path.as_ref().map(PathBuf::from).filter(|path| {
+        path.file_name().and_then(|n| n.to_str()) == app.current_file().map(String::as_str)
+    });
+    let Some((root, entry)) = saved.and_then(|path| {
+        Some((path.parent()?.to_path_buf(), path.file_name()?.to_string_lossy().into_owned()))
+    }) else {
+        app.error_message = Some(tr("package.save_first").to_string());
+        return;
+    };
+
+    let included = collect_project(&root, &entry);
+    let mut listed = Vec::new();
+    list_files(&root, &root, 0, &mut listed);
+    for name in &included {
+        if !listed.contains(name) {
+            listed.push(name.clone());
+        }
+    }
+    listed.retain(|name| *name != entry);
+    let files = listed.into_iter().map(|name| {
+        let checked = included.contains(&name);
+        (name, checked)
+    }).collect();
+
+    app.package = PackagePanel { root, entry, files };
+    app.show_package = true;
+}
+
+/// Package Project window: file checklist and the .twpak save
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_package;
+    let mut package = false;
+    let unsaved = app.current_file().is_some_and(|f| app.file_modified.get(f).copied().unwrap_or(false));
+    let panel = &mut app.package;
+
+    egui::Window::new(tr("package.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(trf("package.entry", &[&panel.entry]));
+            if unsaved {
+                ui.colored_label(ui.visuals().warn_fg_color, tr("package.unsaved"));
+            }
+            ui.label(tr("package.include"));
+            egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                if panel.files.is_empty() {
+                    ui.weak(tr("package.no_files"));
+                }
+                for (name, included) in &mut panel.files {
+                    ui.checkbox(included, name.as_str());
+                }
+            });
+            ui.separator();
+            package = ui.button(tr("package.save")).clicked();
+        });
+    app.show_package = open;
+
+    if package {
+        let panel = &app.package;
+        let files: Vec<String> = panel.files.iter().filter(|(_, on)| *on).map(|(name, _)| name.clone()).collect();
+        let stem = Path::new(&panel.entry).file_stem().and_then(|s| s.to_str()).unwrap_or("project");
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("file.filter.bundle"), &[PAK_EXTENSION])
+            .set_file_name(format!("{}.{}", stem, PAK_EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+        match build_pak(&panel.root, &panel.entry, &files).and_then(|bytes| Ok(std::fs::write(&path, bytes)?)) {
+            Ok(()) => {
+                app.show_package = false;
+                app.error_message = Some(trf("package.done", &[&(files.len() + 1), &path.display()]));
+            }
+            Err(e) => app.error_message = Some(trf("package.failed", &[&e])),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Tools ▸ Program Report: statistics of the current tab for grading
+//!
+//! The report is worked out again whenever the tab's text changes. Export
+//! JSON saves it with a summary of the tab's last run.
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::isolated::RunReport;
+use crate::interpreter::program_hash;
+use crate::languages::analysis::{analyze_program, ProgramStats};
+use crate::ui::debugger::save_text;
+use crate::utils::data_export::program_report_to_json;
+
+/// The report and the program text it was made from
+#[derive(Default)]
+pub struct ProgramReportPanel {
+    source_hash: String,
+    stats: Option<ProgramStats>,
+}
+
+/// Program Report window: counts, nesting, variables, unreachable lines, JSON export
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let code = app.current_code();
+    let language = app.current_language();
+    let hash = format!("{}:{}", program_hash(&code), language.name());
+    if app.program_report.stats.is_none() || app.program_report.source_hash != hash {
+        app.program_report.stats = Some(analyze_program(&code, language));
+        app.program_report.source_hash = hash;
+    }
+    let Some(stats) = app.program_report.stats.clone() else { return };
+
+    let mut open = app.show_program_report;
+    let mut goto_line = None;
+    let mut export = false;
+    egui::Window::new(tr("report.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .show(ctx, |ui| {
+            let file = app.current_file().cloned().unwrap_or_default();
+            ui.strong(format!("{} ({})", file, stats.language.name()));
+            egui::Grid::new("program_report_totals").num_columns(2).show(ui, |ui| {
+                ui.label(tr("report.statements"));
+                ui.label(stats.statements.to_string());
+                ui.end_row();
+                ui.label(tr("report.nesting"));
+                ui.label(stats.max_nesting.to_string());
+                ui.end_row();
+                if let Some(longest) = stats.longest_line {
+                    ui.label(tr("report.longest_line"));
+                    if ui.link(trf("report.line_chars", &[&longest.line, &longest.chars])).clicked() {
+                        goto_line = Some(longest.line);
+                    }
+                    ui.end_row();
+                }
+                ui.label(tr("report.variables"));
+                ui.label(names(&stats.variables));
+                ui.end_row();
+                ui.label(tr("report.unused"));
+                ui.label(names(&stats.unused_variables));
+                ui.end_row();
+                ui.label(tr("report.unreachable"));
+                ui.horizontal_wrapped(|ui| {
+                    if stats.unreachable_lines.is_empty() {
+                        ui.label(tr("report.none"));
+                    }
+                    for &line in &stats.unreachable_lines {
+                        if ui.link(line.to_string()).clicked() {
+                            goto_line = Some(line);
+                        }
+                    }
+                });
+                ui.end_row();
+            });
+            ui.separator();
+            ui.label(tr("report.constructs"));
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("program_report_constructs").num_columns(2).striped(true).show(ui, |ui| {
+                    for (construct, count) in &stats.constructs {
+                        let label = if construct == "procedure call" { tr("report.procedure_call") } else { construct.as_str() };
+                        ui.monospace(label);
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+            ui.separator();
+            if ui.button(tr("debugger.export_json")).clicked() {
+                export = true;
+            }
+        });
+    app.show_program_report = open;
+
+    if let Some(line) = goto_line {
+        app.pending_goto_line = Some(line);
+        app.active_tab = 0;
+    }
+    if export {
+        // The last run goes with the report when it was this tab's, and has finished
+        let ran_here = app.run_worker.is_none() && app.run_summary.is_some() && app.run_file.as_ref() == app.current_file();
+        let run = ran_here.then(|| RunReport::capture(&app.interpreter, &app.turtle_state, None));
+        save_text(app, "report.json", "JSON", "json", &program_report_to_json(&stats, run.as_ref()));
+    }
+}
+
+/// `names` as a comma-separated list, or "none"
+fn names(names: &[String]) -> String {
+    if names.is_empty() {
+        tr("report.none").to_string()
+    } else {
+        trf("report.count_names", &[&names.len(), &names.join(", ")])
+    }
+}
@@ -0,0 +1,113 @@
+//! Edit ▸ Rename Symbol: a variable, label, line number, or procedure renamed everywhere it is used
+//!
+//! The symbol is the one under the editor's cursor, and its references are
+//! found by [`rename`](crate::languages::rename), which skips strings and
+//! comments. The window lists each occurrence before anything changes.
+//! Variables and procedures can be renamed across every open tab; labels,
+//! line numbers, and a procedure's inputs belong to one program. Each tab's
+//! rename is one undo step.
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::languages::rename::{self, Occurrence, Target, TargetKind};
+use crate::languages::Language;
+
+/// Occurrences the preview lists before it stops
+const MAX_PREVIEW: usize = 200;
+
+/// The Rename Symbol window
+#[derive(Default)]
+pub struct RenamePanel {
+    /// The symbol being renamed and the tab it was picked in; the window is open while there is one
+    pub target: Option<(String, Target)>,
+    new_name: String,
+    /// Rename in every open tab, not just that one
+    all_tabs: bool,
+}
+
+/// Start renaming the symbol at the editor's cursor, or say there is none
+pub fn open(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(file) = app.current_file().cloned() else { return };
+    let code = app.current_code();
+    let at = crate::ui::editor::cursor_byte(ctx, &code);
+    let language = app.current_language();
+    let Some(target) = rename::symbol_at(&code, language, at) else {
+        app.error_message = Some(tr("rename.no_symbol").to_string());
+        return;
+    };
+    let written = rename::references(&code, language, &target).into_iter().find(|o| o.range.contains(&at) || o.range.end == at);
+    app.rename.new_name = written.map_or_else(|| target.name.clone(), |o| code[o.range].to_string());
+    app.rename.target = Some((file, target));
+}
+
+/// Whether a rename of `target` may reach other tabs
+fn shared(target: &Target) -> bool {
+    target.scope.is_none() && matches!(target.kind, TargetKind::Variable | TargetKind::Procedure)
+}
+
+/// Rename Symbol window: the new name, every occurrence, and Rename
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some((file, target)) = app.rename.target.clone() else { return };
+    let files = if shared(&target) && app.rename.all_tabs { app.open_files.clone() } else { vec![file] };
+    let buffers: Vec<(String, Language, String)> = files
+        .into_iter()
+        .filter_map(|f| Some((f.clone(), app.language_of(&f), app.file_buffers.get(&f)?.clone())))
+        .collect();
+    let found: Vec<Vec<Occurrence>> = buffers.iter().map(|(_, language, code)| rename::references(code, *language, &target)).collect();
+    let total: usize = found.iter().map(Vec::len).sum();
+
+    let mut open = true;
+    let mut apply = false;
+    let panel = &mut app.rename;
+    egui::Window::new(tr("rename.title")).open(&mut open).collapsible(false).show(ctx, |ui| {
+        ui.label(trf("rename.symbol", &[&target.name]));
+        let field = ui.text_edit_singleline(&mut panel.new_name);
+        crate::ui::keyboard::focus_on_open(&field);
+        if shared(&target) {
+            ui.checkbox(&mut panel.all_tabs, tr("rename.all_tabs"));
+        }
+        let check = buffers.iter().try_for_each(|(_, language, code)| rename::check_name(code, *language, &target, &panel.new_name));
+        if let Err(e) = &check {
+            ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+        }
+        let tabs = found.iter().filter(|o| !o.is_empty()).count();
+        ui.label(trf("rename.count", &[&total, &tabs]));
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            let occurrences = buffers.iter().zip(&found).flat_map(|((name, _, code), found)| found.iter().map(move |o| (name, code, o)));
+            for (name, code, occurrence) in occurrences.take(MAX_PREVIEW) {
+                preview(ui, name, code, occurrence);
+            }
+        });
+        ui.separator();
+        let ready = check.is_ok() && total > 0;
+        let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        apply = ui.add_enabled(ready, egui::Button::new(tr("rename.apply"))).clicked() || (ready && entered);
+    });
+    if apply {
+        let new_name = app.rename.new_name.clone();
+        for (name, language, code) in &buffers {
+            if let Ok(renamed) = rename::rename(code, *language, &target, &new_name) {
+                app.apply_buffer_edit(name, renamed, tr("undo.rename"));
+            }
+        }
+    }
+    if !open || apply {
+        app.rename.target = None;
+    }
+}
+
+/// One occurrence: where it is, and its line with the name picked out
+fn preview(ui: &mut egui::Ui, file: &str, code: &str, occurrence: &Occurrence) {
+    let start = code[..occurrence.range.start].rfind('\n').map_or(0, |n| n + 1);
+    let end = code[occurrence.range.end..].find('\n').map_or(code.len(), |n| occurrence.range.end + n);
+    let file = std::path::Path::new(file).file_name().map_or_else(|| file.into(), |n| n.to_string_lossy());
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        ui.weak(format!("{}:{}  ", file, occurrence.line));
+        ui.monospace(code[start..occurrence.range.start].trim_start());
+        ui.label(egui::RichText::new(&code[occurrence.range.clone()]).monospace().strong().background_color(ui.visuals().selection.bg_fill));
+        ui.monospace(&code[occurrence.range.end..end]);
+    });
+}
@@ -0,0 +1,118 @@
+//! Replay: the last run's transcript played back at the pace it was written
+//!
+//! A run made with Settings → Record output timing can be replayed in the
+//! transcript panel: its lines, and the answers typed (marked ⌨), appear at
+//! their recorded times, waits at prompts included, at half, normal, double,
+//! or four times the speed. Stop, or the last line, ends the replay. The
+//! same times make the panel's 🕑 gutter.
+
+use std::time::{Duration, Instant};
+
+use crate::interpreter::Interpreter;
+
+/// Speeds offered for a replay
+pub const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// A line of the transcript, and when it appeared
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayLine {
+    pub at: Duration,
+    pub text: String,
+    /// An answer typed at a prompt, not program output
+    pub answer: bool,
+}
+
+/// A replay in progress
+#[derive(Debug, Clone)]
+pub struct Replay {
+    lines: Vec<ReplayLine>,
+    speed: f32,
+    started: Instant,
+}
+
+/// The run's output lines and answers in the order they appeared; `None` without timing
+pub fn replay_lines(interp: &Interpreter) -> Option<Vec<ReplayLine>> {
+    let timing = &interp.timing;
+    if interp.output.is_empty() || timing.output.len() != interp.output.len() {
+        return None;
+    }
+    let mut answers = interp.answers.iter().zip(&timing.inputs).peekable();
+    let mut lines = Vec::new();
+    for (index, (text, &at)) in interp.output.iter().zip(&timing.output).enumerate() {
+        while let Some(((_, answer), input)) = answers.next_if(|((before, _), _)| *before <= index) {
+            lines.push(ReplayLine { at: input.answered, text: answer.clone(), answer: true });
+        }
+        lines.push(ReplayLine { at, text: text.clone(), answer: false });
+    }
+    lines.extend(answers.map(|((_, answer), input)| ReplayLine { at: input.answered, text: answer.clone(), answer: true }));
+    Some(lines)
+}
+
+/// `+12.34s`, for the time gutter
+pub fn time_label(at: Duration) -> String {
+    format!("+{:.2}s", at.as_secs_f64())
+}
+
+impl Replay {
+    pub fn start(lines: Vec<ReplayLine>, speed: f32) -> Self {
+        Self { lines, speed, started: Instant::now() }
+    }
+
+    pub fn lines(&self) -> &[ReplayLine] {
+        &self.lines
+    }
+
+    /// Lines showing `elapsed` into the replay
+    pub fn shown_at(&self, elapsed: Duration) -> usize {
+        let recorded = elapsed.mul_f32(self.speed);
+        self.lines.partition_point(|line| line.at <= recorded)
+    }
+
+    /// Real time from `elapsed` until the next line shows; `None` once all have
+    pub fn until_next(&self, elapsed: Duration) -> Option<Duration> {
+        let next = self.lines.get(self.shown_at(elapsed))?;
+        Some(next.at.div_f32(self.speed).saturating_sub(elapsed))
+    }
+
+    /// Time since the replay started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::interpreter::clock::FakeClock;
+
+    #[test]
+    fn test_replay_shows_lines_at_their_scaled_times() {
+        let mut interp = Interpreter::new();
+        interp.set_clock(FakeClock::fast_forward());
+        interp.record_timing = true;
+        interp.input_callback = Some(Box::new(|_| "Ada".to_string()));
+        interp.load_program("T:Name?\nA:NAME\nT:Hi *NAME*").unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+
+        let lines = replay_lines(&interp).unwrap();
+        let texts: Vec<(&str, bool)> = lines.iter().map(|l| (l.text.as_str(), l.answer)).collect();
+        assert_eq!(texts, [("Name?", false), ("Ada", true), ("Hi Ada", false)]);
+        assert!(lines.windows(2).all(|pair| pair[0].at <= pair[1].at));
+
+        // One millisecond per statement; at double speed the last line shows after half its time
+        let last = lines[2].at;
+        assert_eq!(last, Duration::from_millis(3));
+        let replay = Replay::start(lines, 2.0);
+        assert_eq!(replay.shown_at(Duration::ZERO), 0);
+        assert_eq!(replay.shown_at(last / 2), 3);
+        assert_eq!(replay.until_next(Duration::ZERO), Some(Duration::from_micros(500)));
+        assert_eq!(replay.until_next(last), None);
+        assert_eq!(time_label(Duration::from_millis(1250)), "+1.25s");
+
+        interp.record_timing = false;
+        interp.load_program("T:Name?").unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+        assert_eq!(replay_lines(&interp), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! Tools ▸ Run History: the session's last runs, to see what changed
+//!
+//! Picking a run shows its transcript read-only. Compare pins it for the
+//! Diff tab against the current output, Restore puts its program text back
+//! into its tab when the tab now holds something else, and Use Seed makes
+//! later runs draw its random numbers again (see [`run_history`]).
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::ui::output_diff::DIFF_TAB;
+use crate::ui::run_summary::RunSummary;
+use crate::utils::run_history::{self, RunHistory, RunRecord};
+
+/// The kept runs and the one picked
+#[derive(Default)]
+pub struct RunHistoryPanel {
+    pub history: RunHistory,
+    selected: Option<usize>,
+}
+
+/// File the history is saved to, when Settings → Keep run history is on
+fn history_file(app: &TimeWarpApp) -> Option<std::path::PathBuf> {
+    if !app.settings.keep_run_history {
+        return None;
+    }
+    app.project_root().and_then(|root| run_history::file_for(&root))
+}
+
+/// Read the project folder's saved runs, when they are kept; call when the folder changes
+pub fn reopen(app: &mut TimeWarpApp) {
+    if let Some(path) = history_file(app) {
+        app.run_history.history = RunHistory::load(&path);
+        app.run_history.selected = None;
+    }
+}
+
+/// Keep the run that just ended
+pub fn record(app: &mut TimeWarpApp) {
+    let file = app.run_file.clone().unwrap_or_default();
+    let interp = &app.interpreter;
+    let mut run = RunRecord::new(&file, &app.run_source, &interp.output, &app.turtle_state);
+    run.seed = interp.rng.borrow().used().then(|| interp.random_seed());
+    if let Some(summary) = &app.run_summary {
+        run.summary = summary.text();
+        run.failed = matches!(summary, RunSummary::Failed { .. });
+    }
+    app.run_history.history.push(run);
+    // The same run stays picked
+    app.run_history.selected = app.run_history.selected.map(|i| i + 1).filter(|&i| i < app.run_history.history.len());
+    save(app);
+}
+
+/// Write the runs to the project folder's history file, when they are kept
+pub fn save(app: &TimeWarpApp) {
+    if let Some(path) = history_file(app) {
+        if let Err(e) = app.run_history.history.save(&path) {
+            tracing::warn!(path = %path.display(), error = %e, "could not save the run history");
+        }
+    }
+}
+
+/// Run History window: the runs on the left, the picked one's transcript on the right
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_run_history;
+    let mut compare = None;
+    let mut restore = None;
+    let mut use_seed = None;
+    let mut clear = false;
+    egui::Window::new(tr("history.title"))
+        .open(&mut open)
+        .default_size([640.0, 360.0])
+        .show(ctx, |ui| {
+            if app.run_history.history.is_empty() {
+                ui.label(tr("history.empty"));
+                return;
+            }
+            ui.horizontal(|ui| {
+                ui.label(trf("history.count", &[&app.run_history.history.len()]));
+                clear = ui.button(tr("history.clear")).clicked();
+            });
+            ui.separator();
+            egui::SidePanel::left("run_history_list").resizable(true).default_width(240.0).show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, run) in app.run_history.history.runs().enumerate() {
+                        let mark = if run.failed { "❌" } else { "✅" };
+                        let label = format!("{} {} {}", mark, run.at, run.file);
+                        let picked = app.run_history.selected == Some(i);
+                        if ui.selectable_label(picked, label).on_hover_text(&run.summary).clicked() {
+                            app.run_history.selected = Some(i);
+                        }
+                    }
+                });
+            });
+            let Some(index) = app.run_history.selected else {
+                ui.label(tr("history.pick"));
+                return;
+            };
+            let Some(run) = app.run_history.history.get(index) else { return };
+            ui.label(&run.summary);
+            if let Some(seed) = run.seed {
+                ui.label(trf("summary.seed", &[&seed]));
+            }
+            ui.horizontal(|ui| {
+                if ui.button(tr("history.compare")).on_hover_text(tr("history.compare.hover")).clicked() {
+                    compare = Some(run.snapshot());
+                }
+                let buffer = app.file_buffers.get(&run.file);
+                let changed = buffer.is_some_and(|text| run.differs_from(text));
+                let restorable = run.source.is_some() && changed;
+                let hover = match (&run.source, buffer) {
+                    (None, _) => tr("history.restore.too_long"),
+                    (_, None) => tr("history.restore.closed"),
+                    _ if !changed => tr("history.restore.same"),
+                    _ => tr("history.restore.hover"),
+                };
+                let button = ui.add_enabled(restorable, egui::Button::new(tr("history.restore")));
+                if button.on_hover_text(hover).on_disabled_hover_text(hover).clicked() {
+                    restore = run.source.clone().map(|source| (run.file.clone(), source));
+                }
+                if let Some(seed) = run.seed {
+                    if ui.button(tr("history.use_seed")).on_hover_text(tr("history.use_seed.hover")).clicked() {
+                        use_seed = Some(seed);
+                    }
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                for line in &run.transcript {
+                    ui.label(egui::RichText::new(line).monospace());
+                }
+            });
+        });
+    app.show_run_history = open;
+
+    if let Some(snapshot) = compare {
+        app.output_diff.pin(snapshot);
+        app.active_tab = DIFF_TAB;
+    }
+    if let Some((file, source)) = restore {
+        if let Some(idx) = app.open_files.iter().position(|f| *f == file) {
+            app.current_file_index = idx;
+        }
+        app.apply_buffer_edit(&file, source, tr("undo.restore_run"));
+        app.active_tab = 0;
+    }
+    if let Some(seed) = use_seed {
+        app.settings.random_seed = Some(seed);
+        app.save_settings();
+    }
+    if clear {
+        app.run_history.history.clear();
+        app.run_history.selected = None;
+        if let Some(path) = history_file(app) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Banner at the top of the Output tab summarizing the last run
+//!
+//! [`summarize`] maps a [`RunReport`] to one of four states — finished,
+//! failed, waiting for input, or stopped — so it can be tested without a UI;
+//! a run stopped at a breakpoint gets its own banner. The banner is rebuilt
+//! whenever a run ends or pauses.
+
+use eframe::egui;
+use std::time::Duration;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::interpreter::isolated::RunReport;
+use crate::interpreter::warnings::Severity;
+
+/// Banner (and input prompt outline) color while a run waits for input
+pub const WAITING_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 150, 40);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunSummary {
+    /// `seed` repeats the run's random numbers, when it drew any
+    Finished { output_lines: usize, elapsed: Duration, segments: usize, warnings: usize, seed: Option<u64> },
+    /// `message` is the first error reported; `line` the first source line that failed, when it is in the run file
+    Failed { message: String, line: Option<usize> },
+    WaitingForInput,
+    Stopped { output_lines: usize, elapsed: Duration },
+    /// Stopped before source line `line` by a breakpoint; Continue carries on
+    AtBreakpoint { line: usize },
+}
+
+/// The banner state for a run that produced `report` after running for `elapsed`
+///
+/// Stop wins over everything; then a pending INPUT; then any error, fatal or
+/// reported by the interpreter, described by the first one reported; otherwise
+/// the run finished. Output a program printed itself never counts as an error.
+pub fn summarize(report: &RunReport, elapsed: Duration, stopped: bool) -> RunSummary {
+    let output_lines = report.output.len();
+    if stopped {
+        return RunSummary::Stopped { output_lines, elapsed };
+    }
+    if report.waiting_for_input {
+        return RunSummary::WaitingForInput;
+    }
+    let line = report.error_spans.first().map(|span| *span.start());
+    let message = report
+        .errors
+        .first()
+        .cloned()
+        .or_else(|| report.error.clone())
+        .or_else(|| line.map(|_| tr("summary.reported_error").to_string()));
+    if let Some(message) = message {
+        return RunSummary::Failed { message, line };
+    }
+    let warnings = report.diagnostics.iter().filter(|d| d.severity == Severity::Warning).count();
+    RunSummary::Finished { output_lines, elapsed, segments: report.lines_drawn, warnings, seed: report.random_seed }
+}
+
+/// `one` or `many` (message texts) with `{0}` filled in by `count`
+fn plural(count: usize, one: &str, many: &str) -> String {
+    if count == 1 { one } else { many }.replace("{0}", &count.to_string())
+}
+
+fn seconds(elapsed: Duration) -> String {
+    format!("{:.2} s", elapsed.as_secs_f64())
+}
+
+impl RunSummary {
+    /// One-line description shown in the banner
+    pub fn text(&self) -> String {
+        match self {
+            RunSummary::Finished { output_lines, elapsed, segments, warnings, seed } => {
+                let mut text = trf("summary.finished", &[
+                    &seconds(*elapsed),
+                    &plural(*output_lines, tr("summary.output_line"), tr("summary.output_lines")),
+                    &plural(*segments, tr("summary.segment"), tr("summary.segments")),
+                ]);
+                if *warnings > 0 {
+                    text.push_str(&format!(", {}", plural(*warnings, tr("summary.warning"), tr("summary.warnings"))));
+                }
+                if let Some(seed) = seed {
+                    text.push_str(&format!(", {}", trf("summary.seed", &[seed])));
+                }
+                text
+            }
+            RunSummary::Failed { message, .. } => format!("❌ {}", message),
+            RunSummary::WaitingForInput => tr("summary.waiting").to_string(),
+            RunSummary::AtBreakpoint { line } => trf("summary.breakpoint", &[line]),
+            RunSummary::Stopped { output_lines, elapsed } => trf("summary.stopped", &[
+                &seconds(*elapsed),
+                &plural(*output_lines, tr("summary.output_line"), tr("summary.output_lines")),
+            ]),
+        }
+    }
+
+    fn color(&self) -> egui::Color32 {
+        match self {
+            RunSummary::Finished { .. } => egui::Color32::from_rgb(60, 150, 80),
+            RunSummary::Failed { .. } => egui::Color32::from_rgb(200, 60, 60),
+            RunSummary::WaitingForInput | RunSummary::AtBreakpoint { .. } => WAITING_COLOR,
+            RunSummary::Stopped { .. } => egui::Color32::from_rgb(120, 120, 130),
+        }
+    }
+}
+
+/// Build the banner for the run that just ended or paused
+///
+/// With Settings → Return to the editor after a successful run, a finished
+/// run switches back to the Editor tab.
+pub fn record(app: &mut TimeWarpApp, fatal_error: Option<String>) {
+    let report = RunReport::capture(&app.interpreter, &app.turtle_state, fatal_error);
+    let summary = match app.interpreter.breakpoints.paused_at {
+        Some(_) => RunSummary::AtBreakpoint { line: app.interpreter.source_line() },
+        None => summarize(&report, app.run_time, app.run_stopped),
+    };
+    if app.settings.return_to_editor && matches!(summary, RunSummary::Finished { .. }) {
+        app.active_tab = 0;
+    }
+    app.run_summary = Some(summary);
+}
+
+/// Show the editor at `line` of the file that ran
+fn jump_to(app: &mut TimeWarpApp, line: usize) {
+    if let Some(idx) = app.run_file.as_ref().and_then(|file| app.open_files.iter().position(|f| f == file)) {
+        app.current_file_index = idx;
+    }
+    app.active_tab = 0;
+    app.pending_goto_line = Some(line);
+}
+
+/// The banner, in the state's color; nothing before the first run or during one
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    if app.execution.state() == crate::ui::execution::SessionState::Running {
+        return;
+    }
+    let Some(summary) = app.run_summary.clone() else { return };
+    let color = summary.color();
+    let mut jump = None;
+    let mut carry_on = false;
+    egui::Frame::none()
+        .fill(color.gamma_multiply(0.25))
+        .stroke(egui::Stroke::new(1.5, color))
+        .rounding(4.0)
+        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                // The running program's TITLE, while it waits for input
+                if let Some(title) = &app.program_title {
+                    ui.heading(title);
+                    ui.separator();
+                }
+                ui.label(egui::RichText::new(summary.text()).strong());
+                if let RunSummary::Failed { line: Some(line), .. } | RunSummary::AtBreakpoint { line } = summary {
+                    if ui.button(trf("summary.go_to_line", &[&line])).clicked() {
+                        jump = Some(line);
+                    }
+                }
+                if matches!(summary, RunSummary::AtBreakpoint { .. }) && ui.button(tr("menu.run.continue")).clicked() {
+                    carry_on = true;
+                }
+            });
+        });
+    if let Some(line) = jump {
+        jump_to(app, line);
+    }
+    if carry_on {
+        crate::ui::menubar::continue_program(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::warnings::{Diagnostic, WarningKind};
+
+    fn report(output: &[&str]) -> RunReport {
+        RunReport { output: output.iter().map(|l| l.to_string()).collect(), ..RunReport::default() }
+    }
+
+    fn run(source: &str) -> RunSummary {
+        let report = crate::interpreter::Interpreter::run_isolated(source, Default::default());
+        summarize(&report, Duration::ZERO, false)
+    }
+
+    #[test]
+    fn test_clean_run_finishes() {
+        let mut finished = report(&["Hello", "World"]);
+        finished.lines_drawn = 4;
+        let summary = summarize(&finished, Duration::from_millis(1500), false);
+        assert_eq!(summary, RunSummary::Finished { output_lines: 2, elapsed: Duration::from_millis(1500), segments: 4, warnings: 0, seed: None });
+        assert_eq!(summary.text(), "✅ Finished in 1.50 s — 2 output lines, 4 segments drawn");
+
+        // Warnings are counted, but the run still finished
+        let diagnostic = Diagnostic { severity: Severity::Warning, kind: WarningKind::UndefinedVariable, line: 2, message: "X".into() };
+        finished.diagnostics = vec![diagnostic.clone(), Diagnostic { line: 5, ..diagnostic }];
+        let summary = summarize(&finished, Duration::from_millis(1500), false);
+        assert_eq!(summary.text(), "✅ Finished in 1.50 s — 2 output lines, 4 segments drawn, 2 warnings");
+
+        // A run that drew random numbers names the seed that repeats it
+        finished.random_seed = Some(1234);
+        let summary = summarize(&finished, Duration::from_millis(1500), false);
+        assert!(summary.text().ends_with(", 2 warnings, random seed 1234"), "{}", summary.text());
+    }
+
+    #[test]
+    fn test_first_error_and_its_line() {
+        let mut failed = report(&["ok", "❌ Error at line 3: Unknown command\n   💡 Hint: x", "❌ Error at line 5: y"]);
+        failed.errors = vec!["Error at line 3: Unknown command".into(), "Error at line 5: y".into()];
+        failed.error_spans = vec![3..=3, 5..=5];
+        let summary = summarize(&failed, Duration::ZERO, false);
+        assert_eq!(summary, RunSummary::Failed { message: "Error at line 3: Unknown command".into(), line: Some(3) });
+        assert_eq!(summary.text(), "❌ Error at line 3: Unknown command");
+
+        assert_eq!(
+            run("10 PRINT 1\n20 GOTO 99"),
+            RunSummary::Failed { message: "GOTO 99 failed: line not found".into(), line: Some(2) }
+        );
+        let classic = crate::interpreter::isolated::IsolatedRunOptions { classic_errors: true, ..Default::default() };
+        let classic = crate::interpreter::Interpreter::run_isolated("10 FOR I = 1 TO 2\n20 NEXT J", classic);
+        assert_eq!(
+            summarize(&classic, Duration::ZERO, false),
+            RunSummary::Failed { message: "?NEXT WITHOUT FOR ERROR IN 20".into(), line: Some(2) }
+        );
+
+        // The reported line describes a fatal error best; a load error has only the fatal one
+        let mut fatal = report(&["❌ Error: Execution timeout (10 seconds exceeded)"]);
+        fatal.errors = vec!["Error: Execution timeout (10 seconds exceeded)".into()];
+        fatal.error = Some("Execution timeout exceeded".into());
+        assert_eq!(
+            summarize(&fatal, Duration::ZERO, false),
+            RunSummary::Failed { message: "Error: Execution timeout (10 seconds exceeded)".into(), line: None }
+        );
+        fatal.errors.clear();
+        assert!(matches!(summarize(&fatal, Duration::ZERO, false), RunSummary::Failed { message, .. } if message == "Execution timeout exceeded"));
+    }
+
+    #[test]
+    fn test_printed_error_lookalikes_are_not_errors() {
+        assert!(matches!(run("10 PRINT \"❌ not really\"\n20 PRINT \"?SYNTAX ERROR\""), RunSummary::Finished { output_lines: 2, .. }));
+        assert!(matches!(run("T:❌ Error at line 1: just text"), RunSummary::Finished { .. }));
+    }
+
+    #[test]
+    fn test_stop_and_input_outrank_errors() {
+        let mut paused = report(&["❌ Error at line 1: x"]);
+        paused.waiting_for_input = true;
+        assert_eq!(summarize(&paused, Duration::ZERO, false), RunSummary::WaitingForInput);
+        assert_eq!(
+            summarize(&paused, Duration::from_secs(2), true),
+            RunSummary::Stopped { output_lines: 1, elapsed: Duration::from_secs(2) }
+        );
+        assert_eq!(
+            RunSummary::Stopped { output_lines: 1, elapsed: Duration::from_secs(2) }.text(),
+            "⏹ Stopped after 2.00 s — 1 output line"
+        );
+    }
+}
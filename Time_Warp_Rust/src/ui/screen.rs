@@ -1,29 +1,62 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
 use crate::interpreter::ScreenMode;
+use crate::graphics::raster::MEASURE_COLOR;
+use crate::graphics::{color32, paint_turtle, rgba, MeasureTool, Rgba};
+use crate::interpreter::warnings::{is_error_line, is_warning_line};
+use crate::ui::themes::cvd_safe_color;
+use crate::ui::transcript::{ERROR_COLOR, WARNING_COLOR};
+use crate::graphics::text_screen::SCREEN_SCALE;
+use crate::utils::screen_export::text_screen;
+
+/// How long a Logo FLASH takes to fade away
+const FLASH_FADE: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// Glow drawn under flashed segments
+const FLASH_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 240, 120);
 
 /// Unified screen renderer: draws text and graphics in a single canvas based on current SCREEN mode
 pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
     // Determine desired logical size
-    let (desired_w, desired_h) = match app.interpreter.screen_mode {
-        ScreenMode::Graphics { width, height } => (width as f32, height as f32),
-        ScreenMode::Text { cols, rows } => {
-            let char_w = 10.0f32;
-            let char_h = 18.0f32;
-            (cols as f32 * char_w, rows as f32 * char_h)
+    let text_screen = text_screen(&app.interpreter, &app.current_theme.colors(), app.settings.show_warnings);
+    let (desired_w, desired_h) = match (&text_screen, app.interpreter.screen_mode) {
+        (Some(screen), _) => {
+            let (width, height) = screen.size(SCREEN_SCALE);
+            (width as f32, height as f32)
         }
+        (None, ScreenMode::Graphics { width, height }) => (width as f32, height as f32),
+        (None, ScreenMode::Text { .. }) => (0.0, 0.0),
     };
 
     // Allocate painter
     let desired = egui::vec2(desired_w, desired_h);
-    let (response, painter) = ui.allocate_painter(desired, egui::Sense::hover());
+    let measuring = app.measure_tool != MeasureTool::Off && matches!(app.interpreter.screen_mode, ScreenMode::Graphics { .. });
+    let sense = if measuring { egui::Sense::click() } else { egui::Sense::hover() };
+    let (response, painter) = ui.allocate_painter(desired, sense);
+
+    // CVD-safe remapping happens here only; stored turtle colors stay as authored
+    let cvd_safe = app.settings.cvd_safe_colors;
+    let display_color = |c: Rgba| if cvd_safe { cvd_safe_color(color32(c)) } else { color32(c) };
 
     // Background
     match app.interpreter.screen_mode {
-        ScreenMode::Graphics { .. } => { painter.rect_filled(response.rect, 0.0, app.turtle_state.bg_color); }
+        ScreenMode::Graphics { .. } => { painter.rect_filled(response.rect, 0.0, display_color(app.turtle_state.bg_color)); }
         ScreenMode::Text { .. } => { painter.rect_filled(response.rect, 0.0, app.current_theme.background()); }
     }
 
+    // Errors in red; warnings in yellow, or left out (Settings → Warnings)
+    let show_warnings = app.settings.show_warnings;
+    let text_color = app.current_theme.text();
+    let line_color = |line: &str| {
+        if is_warning_line(line) {
+            WARNING_COLOR
+        } else if is_error_line(line) {
+            ERROR_COLOR
+        } else {
+            text_color
+        }
+    };
+
     // Draw content based on mode
     match app.interpreter.screen_mode {
         ScreenMode::Graphics { .. } => {
@@ -34,20 +67,31 @@ pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
             );
             let to_screen = egui::emath::RectTransform::from_to(world, response.rect);
 
+            paint_backdrop(app, &painter, to_screen);
+            paint_flash(app, &painter, to_screen);
             // Lines
             for line in &app.turtle_state.lines {
                 let p0 = to_screen * line.start;
                 let p1 = to_screen * line.end;
-                painter.line_segment([p0, p1], egui::Stroke::new(line.width, line.color));
+                painter.line_segment([p0, p1], egui::Stroke::new(line.width, display_color(line.color)));
+            }
+            // Text labels (e.g. PLOT axes)
+            for text in &app.turtle_state.texts {
+                painter.text(
+                    to_screen * text.pos,
+                    egui::Align2::LEFT_TOP,
+                    &text.text,
+                    egui::FontId::monospace(text.size),
+                    display_color(text.color),
+                );
             }
             // Turtle cursor
             if app.turtle_state.visible {
                 let pos = to_screen * egui::pos2(app.turtle_state.x, app.turtle_state.y);
-                let size = 8.0;
-                painter.circle_filled(pos, size, app.current_theme.accent());
-                let angle = app.turtle_state.heading.to_radians();
-                let dir = egui::vec2(angle.sin(), -angle.cos()) * size * 1.5;
-                painter.line_segment([pos, pos + dir], egui::Stroke::new(2.0, app.current_theme.text()));
+                paint_turtle(&painter, &app.turtle_state, pos, 8.0, app.current_theme.accent(), app.current_theme.text());
+            }
+            if measuring {
+                paint_measurement(app, &painter, &response, to_screen);
             }
             // Optional overlay recent text output (last 10 lines)
             if app.show_overlay_text {
@@ -56,35 +100,112 @@ pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
                 if start < app.interpreter.output.len() {
                     let margin = 8.0;
                     let mut y = response.rect.top() + margin;
-                    for line in &app.interpreter.output[start..] {
+                    for line in app.interpreter.output[start..].iter().filter(|l| show_warnings || !is_warning_line(l)) {
                         let pos = egui::pos2(response.rect.left() + margin, y);
                         painter.text(
                             pos,
                             egui::Align2::LEFT_TOP,
                             line,
                             egui::TextStyle::Monospace.resolve(ui.style()),
-                            app.current_theme.text(),
+                            line_color(line),
                         );
                         y += ui.text_style_height(&egui::TextStyle::Monospace);
                     }
                 }
             }
         }
-        ScreenMode::Text { cols: _, rows: _ } => {
-            // Draw text buffer in a monospace grid
-            let margin = 8.0;
-            let mut y = response.rect.top() + margin;
-            for line in &app.interpreter.text_lines {
-                let pos = egui::pos2(response.rect.left() + margin, y);
-                painter.text(
-                    pos,
-                    egui::Align2::LEFT_TOP,
-                    line,
-                    egui::TextStyle::Monospace.resolve(ui.style()),
-                    app.current_theme.text(),
-                );
-                y += ui.text_style_height(&egui::TextStyle::Monospace);
-                if y > response.rect.bottom() - margin { break; }
+        ScreenMode::Text { .. } => {
+            // The same image File → Export → Text Screen saves, redrawn only when the screen changes
+            let Some(screen) = text_screen else { return };
+            let stale = app.text_screen_texture.as_ref().is_none_or(|(shown, _)| *shown != screen);
+            if stale {
+                let image = screen.render_image(SCREEN_SCALE);
+                let size = [image.width() as usize, image.height() as usize];
+                let image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+                let texture = ui.ctx().load_texture("text_screen", image, egui::TextureOptions::NEAREST);
+                app.text_screen_texture = Some((screen, texture));
+            }
+            if let Some((_, texture)) = &app.text_screen_texture {
+                let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                painter.image(texture.id(), response.rect, uv, egui::Color32::WHITE);
+            }
+        }
+    }
+}
+
+/// Glow under the segments of the last Logo FLASH, fading over `FLASH_FADE`
+///
+/// The fade starts the first frame a FLASH is seen, so one made while the
+/// Output tab was hidden still shows when it comes back.
+fn paint_flash(app: &mut TimeWarpApp, painter: &egui::Painter, to_screen: egui::emath::RectTransform) {
+    let Some(flash) = app.turtle_state.flash.clone() else { return };
+    let started = match app.flash_shown {
+        Some((serial, started)) if serial == flash.serial => started,
+        _ => {
+            let now = std::time::Instant::now();
+            app.flash_shown = Some((flash.serial, now));
+            now
+        }
+    };
+    let left = 1.0 - started.elapsed().as_secs_f32() / FLASH_FADE.as_secs_f32();
+    if left <= 0.0 {
+        return;
+    }
+    let color = FLASH_COLOR.gamma_multiply(left);
+    for line in app.turtle_state.flashed_lines() {
+        painter.line_segment([to_screen * line.start, to_screen * line.end], egui::Stroke::new(line.width + 8.0, color));
+    }
+    painter.ctx().request_repaint();
+}
+
+/// The lines the low-memory canvas folded into an image, under the lines still kept
+///
+/// The image goes to the GPU once per fold rather than every frame.
+fn paint_backdrop(app: &mut TimeWarpApp, painter: &egui::Painter, to_screen: egui::emath::RectTransform) {
+    let Some(backdrop) = &app.turtle_state.backdrop else { return };
+    let cvd_safe = app.settings.cvd_safe_colors;
+    let key = (backdrop.serial, cvd_safe);
+    if app.backdrop_texture.as_ref().is_none_or(|(shown, _)| *shown != key) {
+        let image = backdrop.mapped_image(|c| if cvd_safe { rgba(cvd_safe_color(color32(c))) } else { c });
+        let size = [image.width() as usize, image.height() as usize];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+        app.backdrop_texture = Some((key, painter.ctx().load_texture("canvas_backdrop", image, egui::TextureOptions::LINEAR)));
+    }
+    if let Some((_, texture)) = &app.backdrop_texture {
+        let rect = egui::Rect::from_two_pos(to_screen * backdrop.world.min, to_screen * backdrop.world.max);
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        painter.image(texture.id(), rect, uv, egui::Color32::WHITE);
+    }
+}
+
+/// Take a ruler or protractor click and draw the measurement over the canvas
+///
+/// Clicks are stored in turtle coordinates, so the overlay stays on the
+/// drawing when the canvas is resized. A click after a finished measurement
+/// starts the next one.
+fn paint_measurement(app: &mut TimeWarpApp, painter: &egui::Painter, response: &egui::Response, to_screen: egui::emath::RectTransform) {
+    if let Some(click) = response.interact_pointer_pos().filter(|_| response.clicked()) {
+        if app.measure_points.len() >= app.measure_tool.clicks() {
+            app.measure_points.clear();
+        }
+        app.measure_points.push(to_screen.inverse() * click);
+    }
+    let stroke = egui::Stroke::new(2.0, color32(MEASURE_COLOR));
+    for &point in &app.measure_points {
+        painter.circle_stroke(to_screen * point, 4.0, stroke);
+    }
+    match app.measure_tool.measure(&app.measure_points) {
+        Some(measurement) => {
+            for (start, end) in measurement.segments() {
+                painter.line_segment([to_screen * start, to_screen * end], stroke);
+            }
+            let anchor = to_screen * measurement.anchor() + egui::vec2(6.0, 6.0);
+            painter.text(anchor, egui::Align2::LEFT_TOP, measurement.readout(), egui::FontId::monospace(14.0), stroke.color);
+        }
+        // Rubber band from the last click to the pointer
+        None => {
+            if let (Some(&last), Some(hover)) = (app.measure_points.last(), response.hover_pos()) {
+                painter.line_segment([to_screen * last, hover], egui::Stroke::new(1.0, stroke.color));
             }
         }
     }
@@ -0,0 +1,539 @@
+use eframe::egui;
+use crate::app::TimeWarpApp;
+use crate::config::project::{ProjectSettings, PROJECT_FILE};
+use crate::config::Settings;
+use crate::graphics::retention::MIN_LINE_BUDGET;
+use crate::graphics::{AngleMode, TurtleShape};
+use crate::i18n::{tr, trf, Locale};
+use crate::interpreter::slicing;
+use crate::interpreter::warnings::WarningKind;
+use crate::interpreter::{LimitPolicy, ScreenMode};
+use crate::languages::Language;
+use crate::ui::themes::Theme;
+use crate::utils::line_endings::LineEnding;
+
+/// Buttons of the Project section, handled once the window is drawn
+#[derive(Default)]
+struct ProjectActions {
+    save_defaults: bool,
+    /// Replace the project file Save as Project Defaults found
+    replace: bool,
+    keep: bool,
+    edit: bool,
+    reload: bool,
+}
+
+/// tr("settings.project_mark") beside a value the project file sets
+fn project_mark(ui: &mut egui::Ui, overridden: bool) {
+    if overridden {
+        ui.label(egui::RichText::new(tr("settings.project_mark")).small().color(ui.visuals().warn_fg_color))
+            .on_hover_text(trf("settings.project_mark.hover", &[&PROJECT_FILE]));
+    }
+}
+
+/// Edit the user's `value`, or show the project's `effective` value read-only when the project sets it
+fn overridable<T: Clone>(
+    ui: &mut egui::Ui,
+    overridden: bool,
+    value: &mut T,
+    effective: &T,
+    add: impl FnOnce(&mut egui::Ui, &mut T),
+) {
+    if overridden {
+        let mut shown = effective.clone();
+        ui.add_enabled_ui(false, |ui| add(ui, &mut shown));
+    } else {
+        add(ui, value);
+    }
+}
+
+fn language_name(language: Option<Language>) -> &'static str {
+    match language {
+        None => tr("settings.language.from_extension"),
+        Some(Language::TempleCode) => tr("settings.language.templecode"),
+        Some(Language::Pilot) => "PILOT",
+        Some(Language::Basic) => tr("settings.basic"),
+        Some(Language::Logo) => "Logo",
+    }
+}
+
+fn language_mode_ui(ui: &mut egui::Ui, mode: &mut Option<Language>) {
+    egui::ComboBox::from_id_salt("language_mode")
+        .selected_text(language_name(*mode))
+        .show_ui(ui, |ui| {
+            for language in [None, Some(Language::TempleCode), Some(Language::Pilot), Some(Language::Basic), Some(Language::Logo)] {
+                ui.selectable_value(mode, language, language_name(language));
+            }
+        });
+}
+
+fn line_ending_ui(ui: &mut egui::Ui, ending: &mut Option<LineEnding>) {
+    let name = |ending: Option<LineEnding>| ending.map_or(tr("settings.line_ending.keep"), LineEnding::name);
+    egui::ComboBox::from_id_salt("line_ending").selected_text(name(*ending)).show_ui(ui, |ui| {
+        for choice in [None, Some(LineEnding::Lf), Some(LineEnding::CrLf), Some(LineEnding::Cr)] {
+            ui.selectable_value(ending, choice, name(choice));
+        }
+    });
+}
+
+fn screen_mode_ui(ui: &mut egui::Ui, mode: &mut Option<ScreenMode>) {
+    ui.horizontal(|ui| {
+        if ui.radio(mode.is_none(), tr("settings.screen.as_program")).clicked() {
+            *mode = None;
+        }
+        if ui.radio(matches!(mode, Some(ScreenMode::Text { .. })), tr("settings.screen.text")).clicked() {
+            *mode = Some(ScreenMode::Text { cols: 80, rows: 25 });
+        }
+        if ui.radio(matches!(mode, Some(ScreenMode::Graphics { .. })), tr("settings.screen.graphics")).clicked() {
+            *mode = Some(ScreenMode::Graphics { width: 800, height: 600 });
+        }
+    });
+    match mode {
+        Some(ScreenMode::Text { cols, rows }) => {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(cols).range(20..=200).suffix(tr("settings.screen.columns")));
+                ui.add(egui::DragValue::new(rows).range(5..=100).suffix(tr("settings.screen.rows")));
+            });
+        }
+        Some(ScreenMode::Graphics { width, height }) => {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(width).range(100..=4096).suffix(tr("settings.screen.px_wide")));
+                ui.add(egui::DragValue::new(height).range(100..=4096).suffix(tr("settings.screen.px_high")));
+            });
+        }
+        None => {}
+    }
+}
+
+/// Pick a points file for a custom turtle shape
+fn random_seed_ui(ui: &mut egui::Ui, seed: &mut Option<u64>) {
+    let mut fixed = seed.is_some();
+    if ui.checkbox(&mut fixed, tr("settings.seed.same")).changed() {
+        *seed = fixed.then_some(seed.unwrap_or(1));
+    }
+    if let Some(value) = seed {
+        ui.add(egui::DragValue::new(value));
+    }
+}
+
+/// Statements per slice: fitted to the slice time, or a fixed number
+fn slice_statements_ui(ui: &mut egui::Ui, statements: &mut Option<usize>) {
+    let mut fixed = statements.is_some();
+    if ui.checkbox(&mut fixed, tr("settings.slice_statements.fixed")).changed() {
+        *statements = fixed.then_some(statements.unwrap_or(1000));
+    }
+    if let Some(value) = statements {
+        ui.add(egui::DragValue::new(value).range(1..=slicing::MAX_STATEMENTS));
+    }
+}
+
+/// The disabled statements as one comma-separated line, kept as typed while it has focus
+fn disabled_statements_ui(ui: &mut egui::Ui, names: &mut Vec<String>) {
+    let id = ui.id().with("disabled_statements");
+    let mut text = ui.data_mut(|d| d.get_temp::<String>(id)).unwrap_or_else(|| names.join(", "));
+    let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text(tr("settings.disabled_statements.hint")));
+    if response.changed() {
+        *names = text.split(',').map(|n| n.trim().to_uppercase()).filter(|n| !n.is_empty()).collect();
+    }
+    if response.has_focus() {
+        ui.data_mut(|d| d.insert_temp(id, text));
+    } else {
+        ui.data_mut(|d| d.remove::<String>(id));
+    }
+}
+
+fn load_shape(app: &mut TimeWarpApp) {
+    let Some(path) = rfd::FileDialog::new().add_filter(tr("settings.turtle_shape.points_filter"), &["pts", "txt"]).pick_file() else { return };
+    match std::fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|text| TurtleShape::from_points_text(&text)) {
+        Ok(shape) => app.settings.turtle_shape = shape,
+        Err(e) => app.error_message = Some(trf("settings.turtle_shape.failed", &[&path.display(), &e])),
+    }
+}
+
+/// Write the settings in effect to `<project folder>/.timewarp.toml` and use them from now on
+///
+/// A file already there is only replaced when `replace` says the user agreed; until then this asks.
+fn save_project_defaults(app: &mut TimeWarpApp, replace: bool) -> anyhow::Result<()> {
+    let root = app.project_root().ok_or_else(|| anyhow::anyhow!(tr("settings.project.no_folder")))?;
+    let path = root.join(PROJECT_FILE);
+    app.confirm_replace_project = path.exists() && !replace;
+    if app.confirm_replace_project {
+        return Ok(());
+    }
+    let text = ProjectSettings::from_settings(&app.effective_settings())?.to_toml()?;
+    std::fs::write(path, text)?;
+    app.reload_project();
+    Ok(())
+}
+
+/// Settings window: theme, accessibility options, and UI scale
+///
+/// Values set by the project's `.timewarp.toml` are shown read-only with a
+/// tr("settings.project_mark") mark; edits here change only the user's own settings.
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let before = app.settings.clone();
+    let theme_before = app.current_theme;
+    let mut open = app.show_settings;
+    let effective = app.effective_settings();
+    let project_path = app.project.as_ref().map(|p| p.path());
+    let project_root = app.project_root();
+    let confirm_replace = app.confirm_replace_project;
+    let overrides = |key: &str| app.project_overrides(key);
+    let (theme_set, limits_set, tab_set) = (overrides("theme"), overrides("limits"), overrides("tab_width"));
+    let (language_set, screen_set) = (overrides("language_mode"), overrides("screen_mode"));
+    let (seed_set, autorun_set) = (overrides("random_seed"), overrides("autorun_on_open"));
+    let (disabled_set, breakpoints_set) = (overrides("disabled_statements"), overrides("honor_source_breakpoints"));
+    let timing_set = overrides("record_output_timing");
+    let (restricted_set, policy_set) = (overrides("restricted_mode"), overrides("restricted_policy"));
+    let mut actions = ProjectActions::default();
+    let mut apply_log_filter = false;
+    let mut keep_history_changed = false;
+
+    egui::Window::new(tr("settings.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .vscroll(true)
+        .show(ctx, |ui| {
+            ui.heading(tr("settings.project"));
+            match &project_path {
+                Some(path) => {
+                    ui.label(trf("settings.project.using", &[&path.display()]));
+                    ui.label(tr("settings.project.marked"));
+                    ui.horizontal(|ui| {
+                        actions.edit = ui.button(tr("settings.project.edit")).clicked();
+                        actions.reload = ui.button(tr("settings.project.reload")).clicked();
+                    });
+                }
+                None => {
+                    ui.label(match &project_root {
+                        Some(root) => trf("settings.project.missing", &[&PROJECT_FILE, &root.display()]),
+                        None => tr("settings.project.none").to_string(),
+                    });
+                }
+            }
+            actions.save_defaults = ui
+                .add_enabled(project_root.is_some(), egui::Button::new(tr("settings.project.save_defaults")))
+                .on_hover_text(tr("settings.project.save_defaults.hover"))
+                .clicked();
+            if confirm_replace {
+                ui.horizontal(|ui| {
+                    ui.colored_label(ui.visuals().warn_fg_color, trf("settings.project.replace", &[&PROJECT_FILE]));
+                    actions.replace = ui.button(tr("settings.project.replace.confirm")).clicked();
+                    actions.keep = ui.button(tr("common.cancel")).clicked();
+                });
+            }
+            ui.separator();
+
+            ui.heading(tr("settings.appearance"));
+            ui.horizontal(|ui| {
+                // While the project sets the theme, current_theme already shows its value
+                ui.add_enabled_ui(!theme_set, |ui| {
+                    egui::ComboBox::from_label(tr("settings.theme"))
+                        .selected_text(app.current_theme.name())
+                        .show_ui(ui, |ui| {
+                            for theme in Theme::all() {
+                                ui.selectable_value(&mut app.current_theme, theme, theme.name());
+                            }
+                        });
+                });
+                project_mark(ui, theme_set);
+            });
+            ui.add(
+                egui::Slider::new(&mut app.settings.ui_scale, Settings::UI_SCALE_RANGE)
+                    .text(tr("settings.ui_scale"))
+                    .step_by(0.05),
+            );
+            egui::ComboBox::from_label(tr("settings.language"))
+                .selected_text(app.settings.locale.name())
+                .show_ui(ui, |ui| {
+                    for locale in Locale::ALL {
+                        ui.selectable_value(&mut app.settings.locale, locale, locale.name());
+                    }
+                });
+            ui.label(tr("settings.language.note"));
+            ui.checkbox(&mut app.settings.show_welcome, tr("welcome.show_at_startup"));
+            ui.separator();
+
+            ui.heading(tr("settings.accessibility"));
+            ui.checkbox(&mut app.settings.cvd_safe_colors, tr("settings.cvd_safe"));
+            ui.add_enabled(
+                app.settings.cvd_safe_colors,
+                egui::Checkbox::new(&mut app.settings.export_as_displayed, tr("settings.export_as_displayed")),
+            );
+            ui.label(tr("settings.export_as_displayed.note"));
+            ui.add_space(6.0);
+            ui.checkbox(&mut app.settings.text_to_speech, tr("settings.tts"));
+            if app.interpreter.speech.is_none() {
+                ui.label(tr("settings.tts.none"));
+            }
+            ui.separator();
+
+            ui.heading(tr("settings.canvas"));
+            ui.checkbox(&mut app.settings.confirm_clearscreen, tr("settings.confirm_clearscreen"));
+            ui.checkbox(&mut app.settings.export_turtle, tr("settings.export_turtle"));
+            ui.checkbox(&mut app.settings.export_measurements, tr("settings.export_measurements"));
+            ui.label(tr("settings.canvas.restore_note"));
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut app.settings.low_memory_canvas, tr("settings.low_memory_canvas"));
+                let budget = egui::DragValue::new(&mut app.settings.canvas_line_budget).range(MIN_LINE_BUDGET..=10_000_000).speed(1000.0);
+                ui.add_enabled(app.settings.low_memory_canvas, budget);
+                ui.label(tr("settings.low_memory_canvas.lines"));
+            });
+            ui.label(tr("settings.low_memory_canvas.note"));
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.logo_headings"));
+                ui.radio_value(&mut app.settings.angle_mode, AngleMode::Compass, tr("settings.angle.compass"));
+                ui.radio_value(&mut app.settings.angle_mode, AngleMode::Math, tr("settings.angle.math"));
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.turtle_shape"));
+                egui::ComboBox::from_id_salt("turtle_shape")
+                    .selected_text(app.settings.turtle_shape.name())
+                    .show_ui(ui, |ui| {
+                        for shape in TurtleShape::BUILT_IN {
+                            let name = shape.name();
+                            ui.selectable_value(&mut app.settings.turtle_shape, shape, name);
+                        }
+                    });
+                if ui.button(tr("settings.turtle_shape.load")).on_hover_text(tr("settings.turtle_shape.load.hover")).clicked() {
+                    load_shape(app);
+                }
+            });
+            ui.separator();
+
+            ui.heading(tr("settings.basic"));
+            ui.checkbox(&mut app.settings.classic_basic_errors, tr("settings.basic.classic_errors"));
+            ui.label(tr("settings.basic.classic_errors.note"));
+            ui.separator();
+
+            ui.heading(tr("settings.warnings"));
+            ui.checkbox(&mut app.settings.show_warnings, tr("settings.warnings.show"));
+            ui.label(tr("settings.warnings.strict"));
+            for kind in WarningKind::ALL {
+                let mut strict = app.settings.strict_warnings.contains(&kind);
+                if ui.checkbox(&mut strict, kind.label()).changed() {
+                    if strict {
+                        app.settings.strict_warnings.insert(kind);
+                    } else {
+                        app.settings.strict_warnings.remove(&kind);
+                    }
+                }
+            }
+            ui.separator();
+
+            ui.heading(tr("settings.programs"));
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.language_mode"));
+                overridable(ui, language_set, &mut app.settings.language_mode, &effective.language_mode, language_mode_ui);
+                project_mark(ui, language_set);
+            });
+            ui.label(tr("settings.language_mode.note"));
+            ui.checkbox(&mut app.settings.return_to_editor, tr("settings.return_to_editor"));
+            ui.checkbox(&mut app.settings.keep_variables, tr("settings.keep_variables"))
+                .on_hover_text(tr("settings.keep_variables.hover"));
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.start_screen"));
+                project_mark(ui, screen_set);
+            });
+            overridable(ui, screen_set, &mut app.settings.screen_mode, &effective.screen_mode, screen_mode_ui);
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.tab_width"));
+                overridable(ui, tab_set, &mut app.settings.tab_width, &effective.tab_width, |ui, width| {
+                    ui.add(egui::DragValue::new(width).range(1..=Settings::MAX_TAB_WIDTH).suffix(tr("settings.tab_width.spaces")));
+                });
+                project_mark(ui, tab_set);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.line_ending"));
+                line_ending_ui(ui, &mut app.settings.line_ending);
+            });
+            ui.checkbox(&mut app.settings.trim_trailing_whitespace, tr("settings.trim_trailing_whitespace"));
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.random_seed"));
+                overridable(ui, seed_set, &mut app.settings.random_seed, &effective.random_seed, random_seed_ui);
+                project_mark(ui, seed_set);
+            });
+            ui.label(tr("settings.random_seed.note"));
+            ui.horizontal(|ui| {
+                overridable(ui, autorun_set, &mut app.settings.autorun_on_open, &effective.autorun_on_open, |ui, on| {
+                    ui.checkbox(on, tr("settings.autorun_on_open"));
+                });
+                project_mark(ui, autorun_set);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.disabled_statements"));
+                overridable(ui, disabled_set, &mut app.settings.disabled_statements, &effective.disabled_statements, disabled_statements_ui);
+                project_mark(ui, disabled_set);
+            });
+            ui.label(tr("settings.disabled_statements.note"));
+            ui.horizontal(|ui| {
+                overridable(ui, breakpoints_set, &mut app.settings.honor_source_breakpoints, &effective.honor_source_breakpoints, |ui, on| {
+                    ui.checkbox(on, tr("settings.honor_source_breakpoints")).on_hover_text(tr("settings.honor_source_breakpoints.hover"));
+                });
+                project_mark(ui, breakpoints_set);
+            });
+            ui.separator();
+
+            ui.heading(tr("settings.classroom"));
+            if app.policy_locked {
+                ui.add_enabled(false, egui::Checkbox::new(&mut true, tr("settings.restricted")));
+                ui.label(tr("settings.restricted.locked"));
+            } else {
+                ui.horizontal(|ui| {
+                    overridable(ui, restricted_set, &mut app.settings.restricted_mode, &effective.restricted_mode, |ui, on| {
+                        ui.checkbox(on, tr("settings.restricted"));
+                    });
+                    project_mark(ui, restricted_set);
+                });
+            }
+            if effective.restricted_mode {
+                project_mark(ui, policy_set && !app.policy_locked);
+                let locked = app.policy_locked || policy_set;
+                let mut shown = effective.restricted_policy.clone();
+                let policy = if locked { &mut shown } else { &mut app.settings.restricted_policy };
+                ui.add_enabled_ui(!locked, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut policy.allow_file_io, tr("settings.restricted.files"));
+                        ui.checkbox(&mut policy.allow_network, tr("settings.restricted.network"));
+                        ui.checkbox(&mut policy.allow_plugins, tr("settings.restricted.plugins"));
+                    });
+                    egui::Grid::new("policy_grid").num_columns(2).show(ui, |ui| {
+                        ui.label(tr("settings.restricted.workspace"));
+                        let mut workspace = policy.workspace_path.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+                        if ui.add(egui::TextEdit::singleline(&mut workspace).hint_text(tr("settings.restricted.anywhere"))).changed() {
+                            policy.workspace_path = (!workspace.trim().is_empty()).then(|| workspace.trim().into());
+                        }
+                        ui.end_row();
+                        ui.label(tr("settings.restricted.time_limit"));
+                        let mut secs = policy.time_limit().as_secs();
+                        if ui.add(egui::DragValue::new(&mut secs).range(1..=600)).changed() {
+                            policy.time_limit_secs = Some(secs);
+                        }
+                        ui.end_row();
+                        ui.label(tr("settings.restricted.max_statements"));
+                        let mut iterations = policy.iteration_limit();
+                        if ui.add(egui::DragValue::new(&mut iterations).range(100..=100_000_000)).changed() {
+                            policy.max_iterations = Some(iterations);
+                        }
+                        ui.end_row();
+                    });
+                });
+                ui.label(tr("settings.restricted.next_run"));
+            }
+            ui.horizontal(|ui| {
+                overridable(ui, timing_set, &mut app.settings.record_output_timing, &effective.record_output_timing, |ui, on| {
+                    ui.checkbox(on, tr("settings.record_output_timing")).on_hover_text(tr("settings.record_output_timing.hover"));
+                });
+                project_mark(ui, timing_set);
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.slice_statements")).on_hover_text(tr("settings.slice_statements.hover"));
+                slice_statements_ui(ui, &mut app.settings.statements_per_slice);
+            });
+            if ui.checkbox(&mut app.settings.keep_run_history, tr("settings.keep_run_history")).on_hover_text(tr("settings.keep_run_history.hover")).changed() {
+                keep_history_changed = true;
+            }
+            ui.separator();
+
+            ui.heading(tr("settings.diagnostics"));
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.log_filter"));
+                ui.add(egui::TextEdit::singleline(&mut app.settings.log_filter).hint_text("info").desired_width(220.0));
+                if ui.button(tr("common.apply")).clicked() {
+                    apply_log_filter = true;
+                }
+            });
+            ui.label(tr("settings.log_filter.note"));
+            if ui.button(tr("settings.diagnostics.open")).clicked() {
+                app.show_diagnostics = true;
+            }
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.heading(tr("settings.limits"));
+                project_mark(ui, limits_set);
+            });
+            overridable(ui, limits_set, &mut app.settings.limits, &effective.limits, |ui, limits| {
+                egui::Grid::new("limits_grid").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("settings.limits.max_program_bytes"));
+                    ui.add(egui::DragValue::new(&mut limits.max_program_bytes).range(1024..=64 * 1024 * 1024));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_program_lines"));
+                    ui.add(egui::DragValue::new(&mut limits.max_program_lines).range(10..=1_000_000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_repeat_count"));
+                    ui.add(egui::DragValue::new(&mut limits.max_repeat_count).range(1..=100_000_000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_for_iterations"));
+                    ui.add(egui::DragValue::new(&mut limits.max_for_iterations).range(1..=100_000_000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_output_lines"));
+                    ui.add(egui::DragValue::new(&mut limits.max_output_lines).range(10..=10_000_000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_output_line_chars"));
+                    ui.add(egui::DragValue::new(&mut limits.max_output_line_chars).range(100..=1_000_000).suffix(tr("settings.limits.chars")));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.input_retries"));
+                    ui.add(egui::DragValue::new(&mut limits.max_input_retries).range(0..=100));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.answer_history"));
+                    ui.add(egui::DragValue::new(&mut limits.max_answer_history).range(0..=1000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_bracket_depth"));
+                    ui.add(egui::DragValue::new(&mut limits.max_bracket_depth).range(1..=256));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.max_paren_depth"));
+                    ui.add(egui::DragValue::new(&mut limits.max_paren_depth).range(1..=1000));
+                    ui.end_row();
+                    ui.label(tr("settings.limits.oversized_loops"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut limits.loop_policy, LimitPolicy::WarnAndClamp, tr("settings.limits.warn_and_clamp"));
+                        ui.radio_value(&mut limits.loop_policy, LimitPolicy::Error, tr("settings.limits.error"));
+                    });
+                    ui.end_row();
+                });
+            });
+        });
+
+    app.show_settings = open;
+    // Shown at once; the next run starts with it too (SETSHAPE changes it for one run)
+    if app.settings.turtle_shape != before.turtle_shape {
+        app.turtle_state.shape = app.settings.turtle_shape.clone();
+    }
+    // Written once the edit is finished, not on every frame of a slider drag or every key typed
+    app.settings_unsaved |= app.settings != before || app.current_theme != theme_before;
+    let editing = ctx.input(|i| i.pointer.any_down()) || ctx.wants_keyboard_input();
+    if app.settings_unsaved && (!editing || !open) {
+        app.save_settings();
+        app.settings_unsaved = false;
+    }
+    app.apply_settings();
+    if keep_history_changed && app.settings.keep_run_history {
+        crate::ui::run_history::save(app);
+    }
+    if apply_log_filter {
+        if let Some(diagnostics) = crate::utils::diagnostics::diagnostics() {
+            if let Err(e) = diagnostics.set_filter(&app.settings.log_filter) {
+                app.error_message = Some(trf("settings.log_filter.invalid", &[&e]));
+            }
+        }
+    }
+    if actions.keep || !open {
+        app.confirm_replace_project = false;
+    }
+    if actions.save_defaults || actions.replace {
+        if let Err(e) = save_project_defaults(app, actions.replace) {
+            app.error_message = Some(trf("settings.project.save_failed", &[&format!("{:#}", e)]));
+        }
+    }
+    if actions.reload {
+        app.reload_project();
+    }
+    if let Some(path) = project_path.filter(|_| actions.edit) {
+        match app.open_path(&path) {
+            Ok(()) => app.active_tab = 0,
+            Err(e) => app.error_message = Some(trf("file.open_path_failed", &[&path.display(), &format!("{:#}", e)])),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use eframe::egui;
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::utils::share::{decode_share, encode_share};
+
+/// Encode the current buffer as a share link and copy it to the clipboard
+pub fn share_current(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    match encode_share(&app.current_code(), app.current_language()) {
+        Ok(link) => {
+            ctx.copy_text(link.clone());
+            app.share_link = Some(link);
+        }
+        Err(e) => app.error_message = Some(trf("share.failed", &[&e])),
+    }
+}
+
+/// Shows the link just copied by Tools → Share
+pub fn render_link(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(mut link) = app.share_link.clone() else { return };
+    let mut open = true;
+    egui::Window::new(tr("share.title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if let Some(title) = app.current_metadata().title {
+                ui.strong(title);
+            }
+            ui.label(tr("share.copied"));
+            ui.add(egui::TextEdit::singleline(&mut link).desired_width(420.0));
+        });
+    if !open {
+        app.share_link = None;
+    }
+}
+
+/// Import Share dialog: paste a link and open it in a new tab
+pub fn render_import(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_import_share;
+    let mut import = false;
+    egui::Window::new(tr("share.import_title"))
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr("share.paste"));
+            let response = ui.add(egui::TextEdit::singleline(&mut app.import_share_text).desired_width(420.0));
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                import = true;
+            }
+            if ui.button(tr("share.import")).clicked() {
+                import = true;
+            }
+        });
+
+    if import {
+        match decode_share(&app.import_share_text) {
+            Ok(program) => {
+                app.open_shared(program);
+                app.import_share_text.clear();
+                open = false;
+            }
+            Err(e) => app.error_message = Some(trf("share.import_failed", &[&e])),
+        }
+    }
+    app.show_import_share = open;
+}
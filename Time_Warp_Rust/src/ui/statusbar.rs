@@ -1,32 +1,72 @@
 use eframe::egui;
 use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::ui::execution::SessionState;
 
-pub fn render(app: &TimeWarpApp, ctx: &egui::Context) {
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
     egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
         ui.horizontal(|ui| {
-            ui.label(format!("File: {}", app.current_file().unwrap_or(&"None".to_string())));
+            ui.label(trf("status.file", &[&app.current_file().map_or(tr("status.no_file"), |f| f.as_str())]));
             ui.separator();
             
-            let lang_name = if let Some(file) = app.current_file() {
-                let ext = std::path::Path::new(file)
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("pilot");
-                crate::languages::Language::from_extension(ext).name().to_string()
-            } else {
-                "PILOT".to_string()
-            };
-            ui.label(format!("Language: {}", lang_name));
+            if let Some(title) = app.current_metadata().title {
+                ui.strong(title);
+                ui.separator();
+            }
+            
+            crate::ui::language_switch::render_picker(app, ui);
             ui.separator();
             
-            ui.label(format!("Theme: {}", app.current_theme.name()));
+            ui.label(trf("status.theme", &[&app.current_theme.name()]));
             ui.separator();
             
-            if app.is_executing {
-                ui.spinner();
-                ui.label("Executing...");
-            } else {
-                ui.label("Ready");
+            if app.interpreter.policy().is_restricted() {
+                ui.label(tr("status.restricted")).on_hover_text(tr("status.restricted.hover"));
+                ui.separator();
+            }
+            
+            if app.turtle_state.backdrop.is_some() {
+                let folded = app.turtle_state.folded_lines();
+                ui.label(trf("status.low_memory", &[&folded])).on_hover_text(tr("status.low_memory.hover"));
+                ui.separator();
+            }
+            
+            match app.execution.state() {
+                SessionState::Running => {
+                    ui.spinner();
+                    ui.label(tr("status.executing"));
+                }
+                SessionState::WaitingInput => {
+                    ui.label(tr("status.waiting_input"));
+                }
+                SessionState::Paused => {
+                    ui.label(tr("status.paused"));
+                }
+                SessionState::Idle | SessionState::Finished => {
+                    ui.label(tr("status.ready"));
+                }
+            }
+            
+            // Statements per second, measured at the worker's slice ends
+            let pace = match app.execution.state() {
+                SessionState::Running => app.run_worker.as_ref().and_then(|worker| worker.statements_per_second()),
+                _ => app.interpreter.statements_per_second(),
+            };
+            if let Some(pace) = pace {
+                ui.separator();
+                ui.label(trf("status.pace", &[&format!("{:.0}", pace)])).on_hover_text(tr("status.pace.hover"));
+            }
+            
+            // Edit → Evaluate Selection's result, while it shows in the editor
+            if let Some((annotation, opacity)) = crate::ui::inline_eval::current(app, ctx) {
+                ui.separator();
+                ui.label(egui::RichText::new(&annotation.result.text).color(annotation.color(ui, opacity)));
+            }
+            
+            // Set by the program with STATUS while it runs
+            if let Some(status) = &app.program_status {
+                ui.separator();
+                ui.strong(status).on_hover_text(tr("status.program.hover"));
             }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
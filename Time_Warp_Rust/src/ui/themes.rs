@@ -1,6 +1,10 @@
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+use crate::ui::transcript::{ERROR_COLOR, VARIABLE_COLOR, WARNING_COLOR};
+use crate::utils::html_export::ThemeColors;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum Theme {
     AmberPhosphor,
     GreenPhosphor,
@@ -11,6 +15,7 @@ pub enum Theme {
     Dracula,
     Monokai,
     SolarizedDark,
+    HighContrast,
 }
 
 impl Theme {
@@ -24,6 +29,7 @@ impl Theme {
             Theme::Dracula,
             Theme::Monokai,
             Theme::SolarizedDark,
+            Theme::HighContrast,
         ]
     }
     
@@ -37,6 +43,7 @@ impl Theme {
             Theme::Dracula => "Dracula",
             Theme::Monokai => "Monokai",
             Theme::SolarizedDark => "Solarized Dark",
+            Theme::HighContrast => "High Contrast",
         }
     }
     
@@ -50,6 +57,7 @@ impl Theme {
             Theme::Dracula => egui::Color32::from_rgb(40, 42, 54),
             Theme::Monokai => egui::Color32::from_rgb(39, 40, 34),
             Theme::SolarizedDark => egui::Color32::from_rgb(0, 43, 54),
+            Theme::HighContrast => egui::Color32::BLACK,
         }
     }
     
@@ -63,6 +71,7 @@ impl Theme {
             Theme::Dracula => egui::Color32::from_rgb(248, 248, 242),
             Theme::Monokai => egui::Color32::from_rgb(248, 248, 240),
             Theme::SolarizedDark => egui::Color32::from_rgb(131, 148, 150),
+            Theme::HighContrast => egui::Color32::WHITE,
         }
     }
     
@@ -76,6 +85,7 @@ impl Theme {
             Theme::Dracula => egui::Color32::from_rgb(139, 233, 253),
             Theme::Monokai => egui::Color32::from_rgb(102, 217, 239),
             Theme::SolarizedDark => egui::Color32::from_rgb(38, 139, 210),
+            Theme::HighContrast => egui::Color32::from_rgb(255, 255, 0),
         }
     }
     
@@ -89,6 +99,28 @@ impl Theme {
             Theme::Dracula => egui::Color32::from_rgb(68, 71, 90),
             Theme::Monokai => egui::Color32::from_rgb(49, 50, 44),
             Theme::SolarizedDark => egui::Color32::from_rgb(7, 54, 66),
+            Theme::HighContrast => egui::Color32::BLACK,
+        }
+    }
+    
+    /// The transcript's colors in this theme, for the HTML export
+    pub fn colors(&self) -> ThemeColors {
+        ThemeColors {
+            background: self.background(),
+            text: self.text(),
+            accent: self.accent(),
+            panel: self.panel(),
+            warning: WARNING_COLOR,
+            error: ERROR_COLOR,
+            variable: VARIABLE_COLOR,
+        }
+    }
+
+    /// Width of the selection outline; High Contrast uses a thick border
+    pub fn selection_stroke_width(&self) -> f32 {
+        match self {
+            Theme::HighContrast => 3.0,
+            _ => 1.0,
         }
     }
     
@@ -99,10 +131,91 @@ impl Theme {
         style.visuals.extreme_bg_color = self.background();
         style.visuals.faint_bg_color = self.panel();
         style.visuals.selection.bg_fill = self.accent().linear_multiply(0.3);
-        style.visuals.selection.stroke = egui::Stroke::new(1.0, self.accent());
+        style.visuals.selection.stroke = egui::Stroke::new(self.selection_stroke_width(), self.accent());
+        
+        if *self == Theme::HighContrast {
+            // Pure black/white surfaces with bold outlines on every widget state
+            style.visuals.selection.bg_fill = egui::Color32::WHITE;
+            style.visuals.selection.stroke = egui::Stroke::new(3.0, egui::Color32::BLACK);
+            style.visuals.panel_fill = egui::Color32::BLACK;
+            style.visuals.window_fill = egui::Color32::BLACK;
+            style.visuals.window_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+            let widgets = &mut style.visuals.widgets;
+            for w in [&mut widgets.inactive, &mut widgets.hovered, &mut widgets.active, &mut widgets.open] {
+                w.bg_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+                w.bg_fill = egui::Color32::BLACK;
+                w.weak_bg_fill = egui::Color32::BLACK;
+            }
+            widgets.hovered.bg_stroke = egui::Stroke::new(3.0, self.accent());
+            widgets.active.bg_stroke = egui::Stroke::new(3.0, self.accent());
+        }
         
         ctx.set_style(style);
     }
 }
 
+/// Okabe-Ito palette, distinguishable under the common color-vision deficiencies
+pub mod okabe_ito {
+    use eframe::egui::Color32;
+
+    #[allow(dead_code)]
+    pub const BLACK: Color32 = Color32::from_rgb(0, 0, 0);
+    pub const ORANGE: Color32 = Color32::from_rgb(230, 159, 0);
+    pub const SKY_BLUE: Color32 = Color32::from_rgb(86, 180, 233);
+    pub const BLUISH_GREEN: Color32 = Color32::from_rgb(0, 158, 115);
+    pub const YELLOW: Color32 = Color32::from_rgb(240, 228, 66);
+    pub const BLUE: Color32 = Color32::from_rgb(0, 114, 178);
+    pub const VERMILLION: Color32 = Color32::from_rgb(213, 94, 0);
+    pub const REDDISH_PURPLE: Color32 = Color32::from_rgb(204, 121, 167);
+}
+
+/// Map a named Logo color to its CVD-safe (Okabe-Ito) counterpart
+///
+/// Only the exact RGB values produced by the named colors (RED, GREEN, ...) are
+/// remapped; black, white, grays and arbitrary RGB/hex colors pass through
+/// unchanged. Applied at render time so stored turtle lines keep the authored colors.
+pub fn cvd_safe_color(color: egui::Color32) -> egui::Color32 {
+    match (color.r(), color.g(), color.b()) {
+        (255, 0, 0) => okabe_ito::VERMILLION,
+        (0, 255, 0) => okabe_ito::BLUISH_GREEN,
+        (0, 0, 255) => okabe_ito::BLUE,
+        (255, 255, 0) => okabe_ito::YELLOW,
+        (0, 255, 255) => okabe_ito::SKY_BLUE,
+        (255, 0, 255) | (128, 0, 128) | (255, 192, 203) => okabe_ito::REDDISH_PURPLE,
+        (255, 165, 0) | (165, 42, 42) => okabe_ito::ORANGE,
+        _ => color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cvd_maps_named_colors() {
+        assert_eq!(cvd_safe_color(egui::Color32::from_rgb(255, 0, 0)), okabe_ito::VERMILLION);
+        assert_eq!(cvd_safe_color(egui::Color32::from_rgb(0, 255, 0)), okabe_ito::BLUISH_GREEN);
+        assert_eq!(cvd_safe_color(egui::Color32::from_rgb(0, 0, 255)), okabe_ito::BLUE);
+        assert_eq!(cvd_safe_color(egui::Color32::from_rgb(255, 165, 0)), okabe_ito::ORANGE);
+    }
+
+    #[test]
+    fn test_cvd_keeps_neutral_and_custom_colors() {
+        assert_eq!(cvd_safe_color(egui::Color32::WHITE), egui::Color32::WHITE);
+        assert_eq!(cvd_safe_color(egui::Color32::BLACK), egui::Color32::BLACK);
+        let custom = egui::Color32::from_rgb(12, 34, 56);
+        assert_eq!(cvd_safe_color(custom), custom);
+    }
+
+    #[test]
+    fn test_cvd_red_green_distinguishable() {
+        // Red and green must not collapse to similar luminance once mapped
+        let luma = |c: egui::Color32| 0.299 * c.r() as f32 + 0.587 * c.g() as f32 + 0.114 * c.b() as f32;
+        let red = cvd_safe_color(egui::Color32::from_rgb(255, 0, 0));
+        let green = cvd_safe_color(egui::Color32::from_rgb(0, 255, 0));
+        assert_ne!(red, green);
+        assert!((luma(red) - luma(green)).abs() > 1.0);
+    }
+}
+
 // Default is derived; ModernDark marked as the default variant
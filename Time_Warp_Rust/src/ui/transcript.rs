@@ -0,0 +1,268 @@
+//! Transcript panel under the screen: every output line, scrollable
+//!
+//! Only the rows in view are laid out (`ScrollArea::show_rows`), and each row
+//! shows at most [`ROW_CHARS`] characters of its first line, so a long
+//! transcript or a huge line costs no more per frame than a short one. Lines
+//! cut by the output line length limit, or too long for a row, open in full
+//! with ⤢. Copy All and Save write the text as the program wrote it; Save HTML
+//! writes a page in the theme's colors, with the answers typed and the drawing.
+//! Errors are red, warnings yellow, and Settings → Warnings can hide warnings. With Output →
+//! Highlight variables, values a line took from variables are underlined in
+//! [`VARIABLE_COLOR`], and hovering one names the variable. When the run was
+//! timed (Settings → Record output timing), 🕑 adds each line's time in a
+//! gutter and ▶ replays the run at its own pace (see [`replay`]).
+
+use eframe::egui;
+
+use crate::app::TimeWarpApp;
+use crate::graphics::{color32, rgba};
+use crate::i18n::{tr, trf};
+use crate::interpreter::segments::Segment;
+use crate::interpreter::warnings::{is_error_line, is_warning_line};
+use crate::ui::replay::{self, Replay};
+use crate::ui::themes::cvd_safe_color;
+use crate::utils::html_export::{self, HtmlOptions};
+
+/// Characters of a line shown in its row; the rest is a click away
+const ROW_CHARS: usize = 400;
+
+/// Text color of `⚠️ Warning …` lines, here and on the text screen
+pub const WARNING_COLOR: egui::Color32 = egui::Color32::from_rgb(225, 185, 50);
+
+/// Text color of error lines, here and on the text screen
+pub const ERROR_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 80, 80);
+
+/// Color of values taken from variables, under Highlight variables
+pub const VARIABLE_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 190, 230);
+
+/// Hover text for a value taken from variable `name`
+fn variable_tooltip(name: &str, value: &str) -> String {
+    format!("{} = {:?}", name, value)
+}
+
+/// A line's parts in a row, variable values underlined with a tooltip
+fn segments_row(ui: &mut egui::Ui, segments: &[Segment]) {
+    ui.spacing_mut().item_spacing.x = 0.0;
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => {
+                ui.label(egui::RichText::new(text).monospace());
+            }
+            Segment::Variable { name, value } => {
+                ui.label(egui::RichText::new(value).monospace().underline().color(VARIABLE_COLOR))
+                    .on_hover_text(variable_tooltip(name, value));
+            }
+        }
+    }
+}
+
+/// Output indexes of the rows to show: all of them, or all but warnings
+fn shown_rows(output: &[String], show_warnings: bool) -> Option<Vec<usize>> {
+    (!show_warnings).then(|| (0..output.len()).filter(|&i| !is_warning_line(&output[i])).collect())
+}
+
+/// The row text for an output line, and whether anything was left out
+fn row_text(line: &str) -> (String, bool) {
+    let first = line.lines().next().unwrap_or_default();
+    let shown: String = first.chars().take(ROW_CHARS).collect();
+    let elided = shown.len() < line.len();
+    (shown, elided)
+}
+
+fn save_transcript(app: &mut TimeWarpApp) {
+    if let Some(path) = rfd::FileDialog::new().add_filter(tr("file.filter.text"), &["txt"]).set_file_name("output.txt").save_file() {
+        if let Err(e) = std::fs::write(&path, app.interpreter.full_output()) {
+            app.error_message = Some(trf("file.save_failed", &[&path.display(), &e]));
+        }
+    }
+}
+
+/// The run as a themed HTML page (see [`html_export`])
+fn save_transcript_html(app: &mut TimeWarpApp) {
+    let Some(path) = rfd::FileDialog::new().add_filter(tr("file.filter.html"), &["html", "htm"]).set_file_name("output.html").save_file() else {
+        return;
+    };
+    let title = app.current_file().cloned().unwrap_or_else(|| tr("transcript.title").to_string());
+    let options = HtmlOptions { title, show_warnings: app.settings.show_warnings };
+    let mapped = app.settings.cvd_safe_colors && app.settings.export_as_displayed;
+    let events = html_export::output_events(&app.interpreter);
+    let html = html_export::export_html(&events, &app.turtle_state, &app.current_theme.colors(), &options, |c| {
+        if mapped { rgba(cvd_safe_color(color32(c))) } else { c }
+    });
+    if let Err(e) = std::fs::write(&path, html) {
+        app.error_message = Some(trf("file.save_failed", &[&path.display(), &e]));
+    }
+}
+
+/// Resizable panel at the bottom of the Output tab
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    egui::TopBottomPanel::bottom("transcript").resizable(true).default_height(160.0).show_inside(ui, |ui| {
+        let count = app.interpreter.output.len();
+        let shown = shown_rows(&app.interpreter.output, app.settings.show_warnings);
+        let rows = shown.as_ref().map_or(count, Vec::len);
+        ui.horizontal(|ui| {
+            ui.strong(tr("transcript.title"));
+            ui.weak(if count == 1 { tr("transcript.line").to_string() } else { trf("transcript.lines", &[&count]) });
+            if rows < count {
+                ui.weak(trf("transcript.hidden", &[&(count - rows)]));
+            }
+            if ui.add_enabled(count > 0, egui::Button::new(tr("transcript.copy_all"))).clicked() {
+                ui.ctx().copy_text(app.interpreter.full_output());
+            }
+            if ui.add_enabled(count > 0, egui::Button::new(tr("transcript.save"))).clicked() {
+                save_transcript(app);
+            }
+            if ui.add_enabled(count > 0, egui::Button::new(tr("transcript.save_html"))).clicked() {
+                save_transcript_html(app);
+            }
+            replay_controls(app, ui);
+        });
+        if app.replay.is_some() {
+            render_replay(app, ui);
+            return;
+        }
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace).max(ui.spacing().interact_size.y);
+        let mut open = None;
+        egui::ScrollArea::vertical().auto_shrink(false).stick_to_bottom(true).show_rows(ui, row_height, rows, |ui, rows| {
+            for row in rows {
+                let index = shown.as_ref().map_or(row, |shown| shown[row]);
+                let line = &app.interpreter.output[index];
+                let (text, elided) = row_text(line);
+                let segments = app.interpreter.output_segments(index).filter(|_| app.highlight_variables && !elided);
+                let mut text = egui::RichText::new(text).monospace();
+                if is_warning_line(line) {
+                    text = text.color(WARNING_COLOR);
+                } else if is_error_line(line) {
+                    text = text.color(ERROR_COLOR);
+                }
+                ui.horizontal(|ui| {
+                    if let Some(at) = app.interpreter.timing.output.get(index).filter(|_| app.show_output_times) {
+                        ui.label(egui::RichText::new(replay::time_label(*at)).monospace().weak());
+                    }
+                    if (elided || app.interpreter.is_truncated(index))
+                        && ui.small_button("⤢").on_hover_text(tr("transcript.show_full")).clicked()
+                    {
+                        open = Some(index);
+                    }
+                    match segments {
+                        Some(segments) => segments_row(ui, segments),
+                        None => {
+                            ui.add(egui::Label::new(text).truncate());
+                        }
+                    }
+                });
+            }
+        });
+        if open.is_some() {
+            app.full_line_view = open;
+        }
+    });
+    render_full_line(app, ui.ctx());
+}
+
+/// 🕑 and ▶ Replay at its speed, or ⏹ while a replay runs
+fn replay_controls(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let timed = app.interpreter.timing.output.len() == app.interpreter.output.len() && !app.interpreter.output.is_empty();
+    ui.add_enabled(timed, egui::Checkbox::new(&mut app.show_output_times, tr("transcript.times")))
+        .on_hover_text(tr("transcript.times.hover"))
+        .on_disabled_hover_text(tr("transcript.untimed"));
+    if app.replay.is_some() {
+        if ui.button(tr("transcript.replay_stop")).clicked() {
+            app.replay = None;
+        }
+        return;
+    }
+    if ui.add_enabled(timed, egui::Button::new(tr("transcript.replay"))).on_disabled_hover_text(tr("transcript.untimed")).clicked() {
+        app.replay = replay::replay_lines(&app.interpreter).map(|lines| Replay::start(lines, app.replay_speed));
+    }
+    egui::ComboBox::from_id_salt("replay_speed").width(50.0).selected_text(format!("{}×", app.replay_speed)).show_ui(ui, |ui| {
+        for speed in replay::SPEEDS {
+            ui.selectable_value(&mut app.replay_speed, speed, format!("{}×", speed));
+        }
+    });
+}
+
+/// The replay's lines so far, the next one scheduled; the last one ends it
+fn render_replay(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    let Some(replay) = &app.replay else { return };
+    let elapsed = replay.elapsed();
+    let shown = replay.shown_at(elapsed);
+    egui::ScrollArea::vertical().auto_shrink(false).stick_to_bottom(true).show(ui, |ui| {
+        for line in &replay.lines()[..shown] {
+            ui.horizontal(|ui| {
+                if app.show_output_times {
+                    ui.label(egui::RichText::new(replay::time_label(line.at)).monospace().weak());
+                }
+                let (text, _) = row_text(&line.text);
+                let text = if line.answer { format!("⌨ {}", text) } else { text };
+                let mut text = egui::RichText::new(text).monospace();
+                if line.answer {
+                    text = text.italics();
+                } else if is_warning_line(&line.text) {
+                    text = text.color(WARNING_COLOR);
+                } else if is_error_line(&line.text) {
+                    text = text.color(ERROR_COLOR);
+                }
+                ui.add(egui::Label::new(text).truncate());
+            });
+        }
+    });
+    match replay.until_next(elapsed) {
+        Some(wait) => ui.ctx().request_repaint_after(wait),
+        None => app.replay = None,
+    }
+}
+
+/// Window with one output line in full, as the program wrote it
+fn render_full_line(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let Some(index) = app.full_line_view else { return };
+    let Some(mut full) = app.interpreter.full_output_line(index) else {
+        app.full_line_view = None;
+        return;
+    };
+    let mut open = true;
+    egui::Window::new(trf("transcript.line_window", &[&(index + 1)])).open(&mut open).default_width(600.0).show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.weak(trf("transcript.characters", &[&full.chars().count()]));
+            if ui.button(tr("common.copy")).clicked() {
+                ui.ctx().copy_text(full.to_string());
+            }
+        });
+        egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+            // A &str buffer is read-only but still selectable
+            ui.add(egui::TextEdit::multiline(&mut full).code_editor().desired_width(f32::INFINITY));
+        });
+    });
+    if !open {
+        app.full_line_view = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_show_the_start_of_long_lines() {
+        assert_eq!(row_text("Hello"), ("Hello".to_string(), false));
+        assert_eq!(row_text("❌ Error\n   💡 Hint"), ("❌ Error".to_string(), true));
+        let (text, elided) = row_text(&"✓".repeat(ROW_CHARS + 1));
+        assert_eq!((text.chars().count(), elided), (ROW_CHARS, true));
+    }
+
+    #[test]
+    fn test_variable_tooltip_shows_the_raw_value() {
+        assert_eq!(variable_tooltip("NAME$", "Ada "), "NAME$ = \"Ada \"");
+        assert_eq!(variable_tooltip("N", "3"), "N = \"3\"");
+    }
+
+    #[test]
+    fn test_warnings_filter_hides_only_warnings() {
+        let output: Vec<String> = ["1", "⚠️ Warning at line 2: X is used before it is set, so it reads as 0", "⚠️ must be a number", "0"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(shown_rows(&output, true), None);
+        assert_eq!(shown_rows(&output, false), Some(vec![0, 2, 3]));
+    }
+}
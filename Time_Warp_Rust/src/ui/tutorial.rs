@@ -0,0 +1,169 @@
+use eframe::egui;
+use std::collections::BTreeMap;
+
+use crate::app::TimeWarpApp;
+use crate::i18n::{tr, trf};
+use crate::tutorial::{builtin_tutorials, StepOutcome, Tutorial};
+
+/// State of the Tutorial window: current tutorial/step and the scratch buffer
+pub struct TutorialPanel {
+    pub tutorials: Vec<Tutorial>,
+    pub selected: usize,
+    pub step: usize,
+    pub scratch: String,
+    pub outcome: Option<StepOutcome>,
+    pub output: Vec<String>,
+    pub show_solution: bool,
+    /// Resume position is taken from saved progress on first open
+    started: bool,
+}
+
+impl Default for TutorialPanel {
+    fn default() -> Self {
+        Self {
+            tutorials: builtin_tutorials(),
+            selected: 0,
+            step: 0,
+            scratch: String::new(),
+            outcome: None,
+            output: Vec::new(),
+            show_solution: false,
+            started: false,
+        }
+    }
+}
+
+impl TutorialPanel {
+    /// Switch tutorials, resuming at the first step not yet completed
+    pub fn select(&mut self, index: usize, progress: &BTreeMap<String, usize>) {
+        self.selected = index;
+        let Some(tutorial) = self.tutorials.get(index) else { return };
+        let done = progress.get(&tutorial.id).copied().unwrap_or(0);
+        self.go_to(done.min(tutorial.steps.len() - 1));
+    }
+
+    /// Show step `step` with its starter code in the scratch buffer
+    pub fn go_to(&mut self, step: usize) {
+        self.step = step;
+        self.scratch = self
+            .tutorials
+            .get(self.selected)
+            .and_then(|t| t.steps.get(step))
+            .map(|s| s.starter_code.clone())
+            .unwrap_or_default();
+        self.outcome = None;
+        self.output.clear();
+        self.show_solution = false;
+    }
+}
+
+/// Tutorial window: instruction, scratch editor, Check, and step navigation
+pub fn render(app: &mut TimeWarpApp, ctx: &egui::Context) {
+    let mut open = app.show_tutorial;
+    if !app.tutorial.started {
+        app.tutorial.started = true;
+        app.tutorial.select(app.tutorial.selected, &app.settings.tutorial_progress);
+    }
+    let mut passed_step = None;
+
+    egui::Window::new(tr("tutorial.title"))
+        .open(&mut open)
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            let panel = &mut app.tutorial;
+            let Some(tutorial) = panel.tutorials.get(panel.selected).cloned() else {
+                ui.label(tr("tutorial.none"));
+                return;
+            };
+            let completed = app.settings.tutorial_progress.get(&tutorial.id).copied().unwrap_or(0);
+
+            let mut chosen = panel.selected;
+            egui::ComboBox::from_label(tr("tutorial.title"))
+                .selected_text(&tutorial.title)
+                .show_ui(ui, |ui| {
+                    for (i, t) in panel.tutorials.iter().enumerate() {
+                        ui.selectable_value(&mut chosen, i, format!("{} ({})", t.title, t.language));
+                    }
+                });
+            if chosen != panel.selected {
+                panel.select(chosen, &app.settings.tutorial_progress);
+                return;
+            }
+
+            let step = &tutorial.steps[panel.step];
+            ui.horizontal(|ui| {
+                ui.strong(trf("tutorial.step", &[&(panel.step + 1), &tutorial.steps.len()]));
+                ui.label(trf("tutorial.completed", &[&completed.min(tutorial.steps.len())]));
+            });
+            ui.add(egui::ProgressBar::new(completed as f32 / tutorial.steps.len() as f32));
+            ui.add_space(6.0);
+            ui.label(&step.instruction);
+            if let Some(hint) = &step.hint {
+                ui.collapsing(tr("tutorial.hint"), |ui| ui.label(hint));
+            }
+            ui.add_space(6.0);
+
+            ui.add(
+                egui::TextEdit::multiline(&mut panel.scratch)
+                    .code_editor()
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY),
+            );
+
+            ui.horizontal(|ui| {
+                if ui.button(tr("tutorial.check")).clicked() {
+                    let (report, outcome) = step.check(&panel.scratch);
+                    if outcome.passed() {
+                        passed_step = Some((tutorial.id.clone(), panel.step + 1));
+                    }
+                    panel.output = report.output;
+                    panel.outcome = Some(outcome);
+                }
+                if ui.button(tr("tutorial.reset")).clicked() {
+                    panel.go_to(panel.step);
+                }
+                ui.toggle_value(&mut panel.show_solution, tr("tutorial.show_solution"));
+                ui.separator();
+                if ui.add_enabled(panel.step > 0, egui::Button::new(tr("tutorial.previous"))).clicked() {
+                    panel.go_to(panel.step - 1);
+                }
+                let can_advance = panel.step + 1 < tutorial.steps.len()
+                    && (panel.step < completed || panel.outcome.as_ref().is_some_and(|o| o.passed()));
+                if ui.add_enabled(can_advance, egui::Button::new(tr("tutorial.next"))).clicked() {
+                    panel.go_to(panel.step + 1);
+                }
+            });
+
+            if panel.show_solution {
+                ui.label(egui::RichText::new(&step.solution).monospace());
+            }
+
+            if let Some(outcome) = &panel.outcome {
+                if outcome.passed() {
+                    let done = panel.step + 1 == tutorial.steps.len();
+                    let msg = if done { tr("tutorial.complete") } else { tr("tutorial.correct") };
+                    ui.colored_label(egui::Color32::from_rgb(80, 200, 120), msg);
+                } else {
+                    for failure in &outcome.failures {
+                        ui.colored_label(egui::Color32::from_rgb(230, 90, 90), format!("✘ {}", failure));
+                    }
+                }
+            }
+            if !panel.output.is_empty() {
+                ui.collapsing(tr("tutorial.output"), |ui| {
+                    for line in &panel.output {
+                        ui.monospace(line);
+                    }
+                });
+            }
+        });
+
+    app.show_tutorial = open;
+    if let Some((id, done)) = passed_step {
+        let entry = app.settings.tutorial_progress.entry(id).or_insert(0);
+        if done > *entry {
+            *entry = done;
+            app.save_settings();
+        }
+    }
+}
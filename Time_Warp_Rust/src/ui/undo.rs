@@ -0,0 +1,142 @@
+//! Undo history shared by every editor tab
+//!
+//! Each step remembers the tab it changed, the text before and after, and
+//! what made the change ("Typing", "Replace All", …), so Edit → Undo can name
+//! the step and put back the right tab even when the change was made to a tab
+//! in the background. Edits go through `TimeWarpApp::apply_buffer_edit`.
+
+/// One change to one tab
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoStep {
+    pub file: String,
+    pub before: String,
+    pub after: String,
+    pub description: String,
+}
+
+/// Steps in the order they were made; those past `position` were undone and can be redone
+#[derive(Debug, Clone)]
+pub struct UndoStack {
+    steps: Vec<UndoStep>,
+    position: usize,
+    max_steps: usize,
+}
+
+impl UndoStack {
+    pub fn new(max_steps: usize) -> Self {
+        Self { steps: Vec::new(), position: 0, max_steps }
+    }
+
+    /// Add a step, dropping whatever could have been redone and the oldest step past `max_steps`
+    pub fn record(&mut self, step: UndoStep) {
+        self.steps.truncate(self.position);
+        self.steps.push(step);
+        if self.steps.len() > self.max_steps {
+            self.steps.remove(0);
+        }
+        self.position = self.steps.len();
+    }
+
+    /// Step back; the caller restores its `before` text
+    pub fn undo(&mut self) -> Option<&UndoStep> {
+        self.position = self.position.checked_sub(1)?;
+        self.steps.get(self.position)
+    }
+
+    /// Step forward again; the caller restores its `after` text
+    pub fn redo(&mut self) -> Option<&UndoStep> {
+        let step = self.steps.get(self.position)?;
+        self.position += 1;
+        Some(step)
+    }
+
+    /// What Undo would take back, for the Edit menu
+    pub fn undo_description(&self) -> Option<&str> {
+        self.position.checked_sub(1).and_then(|i| self.steps.get(i)).map(|s| s.description.as_str())
+    }
+
+    /// What Redo would put back, for the Edit menu
+    pub fn redo_description(&self) -> Option<&str> {
+        self.steps.get(self.position).map(|s| s.description.as_str())
+    }
+
+    /// Drop the steps of a tab that was closed
+    pub fn forget(&mut self, file: &str) {
+        let undone_before = self.steps[..self.position].iter().filter(|s| s.file == file).count();
+        self.steps.retain(|s| s.file != file);
+        self.position -= undone_before;
+    }
+}
+
+/// `<prefix>_<n>.<extension>` for the first `n` from `open_files.len()` that no open tab has
+///
+/// Steps are kept by tab name, so a new tab must not take the name of one
+/// still open: the two would share one buffer and one history.
+pub fn unused_tab_name(open_files: &[String], prefix: &str, extension: &str) -> String {
+    (open_files.len()..)
+        .map(|n| format!("{}_{}.{}", prefix, n, extension))
+        .find(|name| !open_files.contains(name))
+        .expect("some number is free")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(file: &str, before: &str, after: &str, description: &str) -> UndoStep {
+        UndoStep { file: file.into(), before: before.into(), after: after.into(), description: description.into() }
+    }
+
+    #[test]
+    fn test_replace_all_is_one_named_step() {
+        let mut stack = UndoStack::new(100);
+        stack.record(step("a.bas", "", "10 PRINT X", "Typing"));
+        stack.record(step("a.bas", "10 PRINT X", "10 PRINT Y", "Replace All"));
+        assert_eq!(stack.undo_description(), Some("Replace All"));
+
+        let undone = stack.undo().cloned().unwrap();
+        assert_eq!((undone.file.as_str(), undone.before.as_str()), ("a.bas", "10 PRINT X"));
+        assert_eq!((stack.undo_description(), stack.redo_description()), (Some("Typing"), Some("Replace All")));
+        assert_eq!(stack.redo().map(|s| s.after.as_str()), Some("10 PRINT Y"));
+        assert!(stack.redo().is_none());
+
+        // A new edit after an undo drops the redo
+        stack.undo();
+        stack.record(step("a.bas", "10 PRINT X", "10 PRINT Z", "Typing"));
+        assert_eq!(stack.redo_description(), None);
+    }
+
+    #[test]
+    fn test_history_is_capped_and_closed_tabs_are_forgotten() {
+        let mut stack = UndoStack::new(2);
+        for n in 0..3 {
+            stack.record(step(if n == 1 { "b.logo" } else { "a.pilot" }, "", &n.to_string(), "Typing"));
+        }
+        assert_eq!(stack.steps.len(), 2);
+        stack.forget("b.logo");
+        assert_eq!(stack.undo().map(|s| s.after.as_str()), Some("2"));
+        assert!(stack.undo().is_none());
+    }
+
+    #[test]
+    fn test_empty_history_has_nothing_to_undo_or_redo() {
+        let mut stack = UndoStack::new(100);
+        assert!(stack.undo().is_none() && stack.redo().is_none());
+        assert_eq!((stack.undo_description(), stack.redo_description()), (None, None));
+        stack.forget("a.bas");
+        stack.record(step("a.bas", "", "10 END", "Typing"));
+        assert!(stack.redo().is_none(), "nothing was undone");
+        assert!(stack.undo().is_some());
+        assert!(stack.undo().is_none());
+        assert_eq!(stack.redo_description(), Some("Typing"));
+    }
+
+    #[test]
+    fn test_new_tabs_never_share_an_open_tab_name() {
+        // untitled_0 was closed, leaving two tabs, one of them untitled_2
+        let open = vec!["untitled_1.bas".to_string(), "untitled_2.bas".to_string()];
+        assert_eq!(unused_tab_name(&open, "untitled", "bas"), "untitled_3.bas");
+        assert_eq!(unused_tab_name(&open, "untitled", "pilot"), "untitled_2.pilot");
+        assert_eq!(unused_tab_name(&[], "shared", "logo"), "shared_0.logo");
+    }
+}
@@ -0,0 +1,154 @@
+//! Welcome tab: where a first launch starts, and Help ▸ Welcome later
+//!
+//! Big buttons start a BASIC program, open the Logo tutorial, or open the
+//! Example Gallery. Beside them a small Logo program draws itself over and
+//! over; it runs once, on a background thread with tight limits (see
+//! [`Interpreter::run_isolated`]), so startup never waits for it, and the tab
+//! then reveals its lines a few at a time. Whether the tab opens at startup
+//! is decided by [`Startup::first_view`](crate::config::startup::Startup::first_view).
+
+use eframe::egui;
+use std::thread::JoinHandle;
+
+use crate::app::TimeWarpApp;
+use crate::graphics::raster::thumbnail_view;
+use crate::graphics::{color32, Rect, TurtleLine};
+use crate::i18n::tr;
+use crate::interpreter::isolated::IsolatedRunOptions;
+use crate::interpreter::{ExecutionLimits, Interpreter};
+use crate::languages::Language;
+
+/// Main tab index of the Welcome tab (shown until it is closed)
+pub const WELCOME_TAB: usize = 6;
+
+/// The program the demo draws
+const DEMO: &str = "SETCOLORCYCLE [RED ORANGE YELLOW GREEN BLUE PURPLE]\nREPEAT 36 [REPEAT 4 [FORWARD 60 RIGHT 90] RIGHT 10]";
+
+/// Size of the demo canvas in points
+const DEMO_SIZE: egui::Vec2 = egui::vec2(240.0, 240.0);
+
+/// Lines the demo reveals per second
+const LINES_PER_SECOND: f64 = 40.0;
+
+/// Lines' worth of time the finished drawing stays before it starts again
+const HOLD_LINES: usize = 80;
+
+/// Whether the tab is open, and its demo
+#[derive(Default)]
+pub struct WelcomePanel {
+    pub open: bool,
+    running: Option<JoinHandle<Vec<TurtleLine>>>,
+    /// The demo's lines once it has run, and when they started showing
+    demo: Option<(Vec<TurtleLine>, f64)>,
+}
+
+/// Run the demo program with limits far below a snippet's
+fn run_demo() -> Vec<TurtleLine> {
+    let limits = ExecutionLimits { max_program_lines: 20, max_repeat_count: 100, max_for_iterations: 100, max_output_lines: 20, ..ExecutionLimits::default() };
+    Interpreter::run_isolated(DEMO, IsolatedRunOptions { limits, ..IsolatedRunOptions::default() }).lines
+}
+
+/// Open the Welcome tab and switch to it
+pub fn show(app: &mut TimeWarpApp) {
+    app.welcome.open = true;
+    app.active_tab = WELCOME_TAB;
+    if !app.settings.welcome_seen {
+        app.settings.welcome_seen = true;
+        app.save_settings();
+    }
+}
+
+/// Close the Welcome tab, going back to the editor if it was showing
+pub fn close(app: &mut TimeWarpApp) {
+    app.welcome.open = false;
+    if app.active_tab == WELCOME_TAB {
+        app.active_tab = 0;
+    }
+}
+
+/// Welcome tab: the quick actions and the demo
+pub fn render(app: &mut TimeWarpApp, ui: &mut egui::Ui) {
+    ui.heading(tr("welcome.title"));
+    ui.label(tr("welcome.intro"));
+    ui.add_space(16.0);
+    let mut new_basic = false;
+    let mut tutorial = false;
+    let mut example = false;
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            let big = |ui: &mut egui::Ui, text: &str, hover: &str| {
+                let button = egui::Button::new(egui::RichText::new(text).size(18.0)).min_size(egui::vec2(280.0, 48.0));
+                ui.add(button).on_hover_text(hover).clicked()
+            };
+            new_basic = big(ui, tr("welcome.new_basic"), tr("welcome.new_basic.hover"));
+            tutorial = big(ui, tr("welcome.logo_tutorial"), tr("welcome.logo_tutorial.hover"));
+            example = big(ui, tr("welcome.example"), tr("welcome.example.hover"));
+        });
+        ui.add_space(32.0);
+        ui.vertical(|ui| {
+            render_demo(&mut app.welcome, ui);
+            ui.weak(tr("welcome.demo"));
+        });
+    });
+    ui.add_space(16.0);
+    ui.separator();
+    if ui.checkbox(&mut app.settings.show_welcome, tr("welcome.show_at_startup")).changed() {
+        app.save_settings();
+    }
+
+    if new_basic {
+        crate::ui::menubar::new_file(app, Some(Language::Basic));
+        app.active_tab = 0;
+    }
+    if tutorial {
+        if let Some(index) = app.tutorial.tutorials.iter().position(|t| t.language == "Logo") {
+            app.tutorial.select(index, &app.settings.tutorial_progress);
+        }
+        app.show_tutorial = true;
+    }
+    if example {
+        app.show_gallery = true;
+    }
+}
+
+/// The demo canvas: a spinner until the program has run, then its lines a few at a time
+fn render_demo(panel: &mut WelcomePanel, ui: &mut egui::Ui) {
+    let now = ui.input(|i| i.time);
+    if panel.demo.is_none() && panel.running.is_none() {
+        panel.running = Some(std::thread::spawn(run_demo));
+    }
+    if panel.running.as_ref().is_some_and(|handle| handle.is_finished()) {
+        let lines = panel.running.take().and_then(|handle| handle.join().ok()).unwrap_or_default();
+        panel.demo = Some((lines, now));
+    }
+    let (response, painter) = ui.allocate_painter(DEMO_SIZE, egui::Sense::hover());
+    painter.rect_filled(response.rect, 4.0, ui.visuals().extreme_bg_color);
+    let Some((lines, started)) = &panel.demo else {
+        ui.put(response.rect, egui::Spinner::new());
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+        return;
+    };
+    let Some(bounds) = lines.iter().map(|l| Rect::from_two_pos(l.start, l.end)).reduce(|a, b| a.union(b)) else {
+        return;
+    };
+    let view = thumbnail_view(bounds, DEMO_SIZE.x as u32, DEMO_SIZE.y as u32);
+    let to_screen = egui::emath::RectTransform::from_to(view, response.rect);
+    let step = ((now - started) * LINES_PER_SECOND) as usize;
+    let shown = (step % (lines.len() + HOLD_LINES)).min(lines.len());
+    for line in &lines[..shown] {
+        painter.line_segment([to_screen * line.start, to_screen * line.end], egui::Stroke::new(line.width, color32(line.color)));
+    }
+    ui.ctx().request_repaint_after(std::time::Duration::from_millis(25));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_draws_within_its_limits() {
+        let lines = run_demo();
+        assert_eq!(lines.len(), 36 * 4);
+        assert!(lines.iter().any(|l| l.color != lines[0].color), "the demo should cycle colors");
+    }
+}
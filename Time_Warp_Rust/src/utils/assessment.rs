@@ -0,0 +1,219 @@
+//! Assessment mode: a timed lesson that ends by locking the editor
+//!
+//! A project turns it on in its `.timewarp.toml`:
+//!
+//! ```toml
+//! [assessment]
+//! minutes = 20
+//! score_variables = ["RIGHT", "WRONG", "SCORE"]   # RIGHT and WRONG when left out
+//! submit_folder = "submissions"                   # relative to the project folder
+//! ```
+//!
+//! The student gives an identifier and presses Start, which starts the
+//! countdown. When it runs out, [`Assessment::tick`] says so once, so the IDE
+//! can stop a running program, and from then on the editor is locked and Run
+//! refused. Submit (before or after the time is up) packs the work into a
+//! [`Submission`](crate::utils::submission::Submission); after that the
+//! assessment is over. Nothing here needs a network.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::languages::pilot::quiz::{RIGHT_VAR, WRONG_VAR};
+
+/// The `assessment` setting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssessmentSettings {
+    /// Minutes the student has after Start; `None` leaves assessment mode off
+    pub minutes: Option<u32>,
+    /// Variables recorded as the lesson's score; the `QZ:` quiz's when empty
+    pub score_variables: Vec<String>,
+    /// Folder submissions are written to, relative to the project folder; `submissions` when unset
+    pub submit_folder: Option<PathBuf>,
+}
+
+impl AssessmentSettings {
+    /// The time allowed, when assessment mode is on
+    pub fn limit(&self) -> Option<Duration> {
+        self.minutes.map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    /// The score variables, uppercased
+    pub fn score_variables(&self) -> Vec<String> {
+        if self.score_variables.is_empty() {
+            return vec![RIGHT_VAR.to_string(), WRONG_VAR.to_string()];
+        }
+        self.score_variables.iter().map(|name| name.trim().to_uppercase()).collect()
+    }
+
+    pub fn submit_folder(&self) -> PathBuf {
+        self.submit_folder.clone().unwrap_or_else(|| PathBuf::from("submissions"))
+    }
+}
+
+/// Where an assessment is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AssessmentPhase {
+    /// Waiting for the student to press Start
+    #[default]
+    Ready,
+    /// The countdown is running
+    Running,
+    /// The time ran out; the editor is locked until the work is submitted
+    TimeUp,
+    Submitted,
+}
+
+/// An assessment's countdown and who is taking it
+#[derive(Debug, Clone)]
+pub struct Assessment {
+    phase: AssessmentPhase,
+    limit: Duration,
+    student: String,
+    started: Option<Instant>,
+    /// When it was submitted or ran out, so the time used stops counting
+    ended: Option<Instant>,
+}
+
+impl Assessment {
+    pub fn new(limit: Duration) -> Self {
+        Self { phase: AssessmentPhase::Ready, limit, student: String::new(), started: None, ended: None }
+    }
+
+    pub fn phase(&self) -> AssessmentPhase {
+        self.phase
+    }
+
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// The identifier given at Start
+    pub fn student(&self) -> &str {
+        &self.student
+    }
+
+    /// Start the countdown for `student`; false, changing nothing, once started or without an identifier
+    pub fn start(&mut self, student: &str, now: Instant) -> bool {
+        let student = student.trim();
+        if self.phase != AssessmentPhase::Ready || student.is_empty() {
+            return false;
+        }
+        self.student = student.to_string();
+        self.started = Some(now);
+        self.phase = AssessmentPhase::Running;
+        true
+    }
+
+    /// Time left at `now` while the countdown runs
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let started = self.started.filter(|_| self.phase == AssessmentPhase::Running)?;
+        Some(self.limit.saturating_sub(now.saturating_duration_since(started)))
+    }
+
+    /// Time used since Start, up to the submission or the end of the countdown
+    pub fn used(&self, now: Instant) -> Duration {
+        let Some(started) = self.started else { return Duration::ZERO };
+        self.ended.unwrap_or(now).saturating_duration_since(started).min(self.limit)
+    }
+
+    /// Move to [`AssessmentPhase::TimeUp`] once the countdown has run out; true only at that moment
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if self.remaining(now) != Some(Duration::ZERO) {
+            return false;
+        }
+        self.phase = AssessmentPhase::TimeUp;
+        self.ended = Some(now);
+        true
+    }
+
+    /// Whether the editor refuses changes and Run is refused
+    pub fn locked(&self) -> bool {
+        matches!(self.phase, AssessmentPhase::TimeUp | AssessmentPhase::Submitted)
+    }
+
+    pub fn can_submit(&self) -> bool {
+        matches!(self.phase, AssessmentPhase::Running | AssessmentPhase::TimeUp)
+    }
+
+    /// Record that the work was submitted at `now`; false when it could not be
+    pub fn submitted(&mut self, now: Instant) -> bool {
+        if !self.can_submit() {
+            return false;
+        }
+        self.ended.get_or_insert(now);
+        self.phase = AssessmentPhase::Submitted;
+        true
+    }
+}
+
+/// `duration` as the countdown shows it: `mm:ss`, or `h:mm:ss` from an hour
+pub fn countdown(duration: Duration) -> String {
+    // A started second still counts, so the clock reads 00:00 only when the time is up
+    let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    match seconds / 3600 {
+        0 => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_countdown_locks_when_time_runs_out() {
+        let t0 = Instant::now();
+        let at = |seconds: u64| t0 + Duration::from_secs(seconds);
+        let mut assessment = Assessment::new(Duration::from_secs(60));
+        assert!(!assessment.start("  ", t0), "an identifier is needed");
+        assert_eq!(assessment.remaining(t0), None);
+        assert!(!assessment.tick(at(90)), "the clock has not started");
+
+        assert!(assessment.start(" ada ", t0));
+        assert!(!assessment.start("bob", at(1)), "only once");
+        assert_eq!(assessment.student(), "ada");
+        assert_eq!(assessment.remaining(at(45)), Some(Duration::from_secs(15)));
+        assert!(!assessment.tick(at(59)) && !assessment.locked());
+        assert!(assessment.tick(at(61)), "time up is reported once");
+        assert!(!assessment.tick(at(62)));
+        assert_eq!(assessment.phase(), AssessmentPhase::TimeUp);
+        assert!(assessment.locked() && assessment.can_submit());
+        assert_eq!(assessment.used(at(500)), Duration::from_secs(60));
+
+        assert!(assessment.submitted(at(70)));
+        assert!(!assessment.submitted(at(71)), "only once");
+        assert_eq!(assessment.phase(), AssessmentPhase::Submitted);
+        assert!(assessment.locked() && !assessment.can_submit());
+    }
+
+    #[test]
+    fn test_submitting_early_stops_the_clock() {
+        let t0 = Instant::now();
+        let mut assessment = Assessment::new(Duration::from_secs(600));
+        assert!(!assessment.submitted(t0), "not before Start");
+        assessment.start("ada", t0);
+        assert!(assessment.submitted(t0 + Duration::from_secs(125)));
+        assert_eq!(assessment.used(t0 + Duration::from_secs(400)), Duration::from_secs(125));
+        assert!(!assessment.tick(t0 + Duration::from_secs(700)), "a submitted assessment never times out");
+        assert_eq!(assessment.remaining(t0), None);
+    }
+
+    #[test]
+    fn test_countdown_format_and_settings() {
+        assert_eq!(countdown(Duration::from_secs(0)), "00:00");
+        assert_eq!(countdown(Duration::from_millis(59_200)), "01:00");
+        assert_eq!(countdown(Duration::from_secs(20 * 60 + 5)), "20:05");
+        assert_eq!(countdown(Duration::from_secs(3600 + 62)), "1:01:02");
+
+        let settings: AssessmentSettings = toml::from_str("minutes = 20\nscore_variables = [\"score\"]").unwrap();
+        assert_eq!(settings.limit(), Some(Duration::from_secs(1200)));
+        assert_eq!(settings.score_variables(), ["SCORE"]);
+        assert_eq!(settings.submit_folder(), PathBuf::from("submissions"));
+        assert_eq!(AssessmentSettings::default().limit(), None);
+        assert_eq!(AssessmentSettings::default().score_variables(), [RIGHT_VAR, WRONG_VAR]);
+    }
+}
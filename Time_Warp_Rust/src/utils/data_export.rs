@@ -0,0 +1,179 @@
+//! CSV/JSON export of interpreter variables, recorded series, turtle paths,
+//! and program reports
+//!
+//! Used by the Variables panel so students can take simulation results into a
+//! spreadsheet. Output is sorted by name so exports are stable between runs;
+//! turtle paths keep drawing order.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::graphics::TurtleLine;
+use crate::interpreter::isolated::RunReport;
+use crate::interpreter::Interpreter;
+use crate::languages::analysis::ProgramStats;
+
+/// Quote a CSV field when it contains a separator, quote, or newline
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Current variables as `name,type,value` rows
+pub fn variables_to_csv(interp: &Interpreter) -> String {
+    let mut csv = String::from("name,type,value\n");
+    for (name, value) in sorted(&interp.variables) {
+        csv.push_str(&format!("{},number,{}\n", csv_field(name), value));
+    }
+    for (name, value) in sorted(&interp.string_variables) {
+        csv.push_str(&format!("{},string,{}\n", csv_field(name), csv_field(value)));
+    }
+    csv
+}
+
+/// Current variables as a JSON object `{ "numbers": {...}, "strings": {...} }`
+pub fn variables_to_json(interp: &Interpreter) -> String {
+    let doc = serde_json::json!({
+        "numbers": sorted(&interp.variables),
+        "strings": sorted(&interp.string_variables),
+    });
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
+
+/// Recorded series as columns: `step,A,B,...`; shorter series leave blanks
+pub fn series_to_csv(series: &HashMap<String, Vec<f64>>) -> String {
+    let columns = sorted(series);
+    let mut csv = String::from("step");
+    for name in columns.keys() {
+        csv.push(',');
+        csv.push_str(&csv_field(name));
+    }
+    csv.push('\n');
+    let rows = columns.values().map(|v| v.len()).max().unwrap_or(0);
+    for i in 0..rows {
+        csv.push_str(&(i + 1).to_string());
+        for values in columns.values() {
+            csv.push(',');
+            if let Some(v) = values.get(i) {
+                csv.push_str(&v.to_string());
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Turtle segments in drawing order as `x1,y1,x2,y2,color,width` rows
+/// 
+/// Coordinates are turtle coordinates (origin at the center, y grows downward
+/// on screen); colors are `#RRGGBB`.
+pub fn path_to_csv(lines: &[TurtleLine]) -> String {
+    let mut csv = String::from("x1,y1,x2,y2,color,width\n");
+    for line in lines {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            line.start.x, line.start.y, line.end.x, line.end.y, line.color.to_hex(), line.width
+        ));
+    }
+    csv
+}
+
+/// Turtle segments as a JSON array of `{x1, y1, x2, y2, color, width}` objects
+pub fn path_to_json(lines: &[TurtleLine]) -> String {
+    let segments: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            serde_json::json!({
+                "x1": line.start.x, "y1": line.start.y,
+                "x2": line.end.x, "y2": line.end.y,
+                "color": line.color.to_hex(), "width": line.width,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&segments).unwrap_or_default()
+}
+
+/// Program statistics as JSON `{ "program": {...}, "run": {...} }`
+///
+/// `run` summarizes the program's last run, or is `null` when it has not run.
+pub fn program_report_to_json(stats: &ProgramStats, run: Option<&RunReport>) -> String {
+    let run = run.map(|run| {
+        serde_json::json!({
+            "completed": run.completed(),
+            "output_lines": run.output.len(),
+            "lines_drawn": run.lines_drawn,
+            "error_lines": run.error_spans.iter().map(|span| *span.start()).collect::<Vec<_>>(),
+            "warnings": run.diagnostics.len(),
+            "random_seed": run.random_seed,
+        })
+    });
+    let doc = serde_json::json!({ "program": stats, "run": run });
+    serde_json::to_string_pretty(&doc).unwrap_or_default()
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> BTreeMap<&String, &V> {
+    map.iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::{pos2, Rgba};
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_series_csv_columns() {
+        let mut series = HashMap::new();
+        series.insert("T".to_string(), vec![0.0, 0.5, 1.0]);
+        series.insert("X".to_string(), vec![1.0, 2.0]);
+        assert_eq!(series_to_csv(&series), "step,T,X\n1,0,1\n2,0.5,2\n3,1,\n");
+    }
+
+    #[test]
+    fn test_path_csv_and_json() {
+        let lines = vec![TurtleLine {
+            start: pos2(0.0, 0.0),
+            end: pos2(0.0, -50.0),
+            color: Rgba::from_rgb(255, 128, 0),
+            width: 2.0,
+        }];
+        assert_eq!(path_to_csv(&lines), "x1,y1,x2,y2,color,width\n0,0,0,-50,#FF8000,2\n");
+        let json: serde_json::Value = serde_json::from_str(&path_to_json(&lines)).unwrap();
+        assert_eq!(json[0]["y2"], -50.0);
+        assert_eq!(json[0]["color"], "#FF8000");
+    }
+
+    #[test]
+    fn test_variables_csv_and_json() {
+        let mut interp = Interpreter::new();
+        interp.variables.insert("X".to_string(), 3.0);
+        interp.string_variables.insert("NAME$".to_string(), "Ada, L".to_string());
+        assert_eq!(variables_to_csv(&interp), "name,type,value\nX,number,3\nNAME$,string,\"Ada, L\"\n");
+        let json: serde_json::Value = serde_json::from_str(&variables_to_json(&interp)).unwrap();
+        assert_eq!(json["numbers"]["X"], 3.0);
+        assert_eq!(json["strings"]["NAME$"], "Ada, L");
+    }
+
+    #[test]
+    fn test_program_report_json() {
+        let stats = crate::languages::analysis::analyze_program("10 PRINT 1\n20 END\n30 PRINT 2", crate::languages::Language::Basic);
+        let json: serde_json::Value = serde_json::from_str(&program_report_to_json(&stats, None)).unwrap();
+        assert_eq!(json["program"]["language"], "basic");
+        assert_eq!(json["program"]["constructs"]["PRINT"], 2);
+        assert_eq!(json["program"]["unreachable_lines"][0], 3);
+        assert!(json["run"].is_null());
+        let run = RunReport { output: vec!["1".into()], error_spans: vec![3..=3], ..RunReport::default() };
+        let json: serde_json::Value = serde_json::from_str(&program_report_to_json(&stats, Some(&run))).unwrap();
+        assert_eq!(json["run"]["output_lines"], 1);
+        assert_eq!(json["run"]["error_lines"][0], 3);
+        assert_eq!(json["run"]["completed"], true);
+    }
+}
@@ -0,0 +1,170 @@
+//! Logging setup and the in-app Diagnostics log
+//!
+//! The interpreter core emits a `run` span per program run (language and
+//! program fingerprint) with debug events for jumps, errors, input requests,
+//! and limits hit, plus a trace event per executed line. [`init`] sends them
+//! to stderr and to a [`LogBuffer`], a ring of the most recent events that
+//! View → Diagnostics Log shows, under one filter (`RUST_LOG` syntax, e.g.
+//! `time_warp_core=debug`) that Settings can change while the IDE runs.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+/// Events kept for the Diagnostics log
+pub const LOG_CAPACITY: usize = 500;
+
+/// One event as the Diagnostics log shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub level: Level,
+    /// Enclosing spans with their fields, outermost first: `run{language=BASIC program=…}`
+    pub spans: String,
+    /// The message followed by the other fields: `jump from=20 to=10`
+    pub message: String,
+}
+
+/// A tracing layer keeping the last `capacity` events
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))), capacity }
+    }
+
+    /// The kept events, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.entries.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+}
+
+/// Writes an event's or span's fields as `message name=value …`
+#[derive(Default)]
+struct FieldText(String);
+
+impl Visit for FieldText {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, "{}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        // Strings unquoted, as the Display (`%`) fields are
+        self.record_debug(field, &format_args!("{}", value));
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for LogBuffer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = FieldText::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut spans = String::new();
+        for span in ctx.event_scope(event).into_iter().flat_map(|scope| scope.from_root()) {
+            let extensions = span.extensions();
+            let fields = extensions.get::<FieldText>().map_or("", |f| f.0.as_str());
+            let _ = write!(spans, "{}{}{{{}}}", if spans.is_empty() { "" } else { ":" }, span.name(), fields);
+        }
+        let mut message = FieldText::default();
+        event.record(&mut message);
+        self.push(LogEntry { level: *event.metadata().level(), spans, message: message.0 });
+    }
+}
+
+/// The filter in use and the events kept for the Diagnostics log
+pub struct Diagnostics {
+    pub buffer: LogBuffer,
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+impl Diagnostics {
+    /// Use `directives` (e.g. `info,time_warp_core=debug`) from now on
+    pub fn set_filter(&self, directives: &str) -> anyhow::Result<()> {
+        let filter = EnvFilter::try_new(directives)?;
+        self.filter.reload(filter)?;
+        Ok(())
+    }
+}
+
+static DIAGNOSTICS: OnceLock<Diagnostics> = OnceLock::new();
+
+/// Install logging to stderr and the Diagnostics log, filtered by `directives`
+///
+/// An invalid filter falls back to `info` rather than losing the log.
+pub fn init(directives: &str) {
+    let filter = EnvFilter::try_new(directives).unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+    let buffer = LogBuffer::new(LOG_CAPACITY);
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(buffer.clone())
+        .try_init();
+    if installed.is_ok() {
+        let _ = DIAGNOSTICS.set(Diagnostics { buffer, filter: handle });
+    }
+}
+
+/// The logging installed by [`init`], if any
+pub fn diagnostics() -> Option<&'static Diagnostics> {
+    DIAGNOSTICS.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_keeps_the_latest_events_with_their_span() {
+        let buffer = LogBuffer::new(2);
+        tracing::subscriber::with_default(Registry::default().with(buffer.clone()), || {
+            let span = tracing::debug_span!("run", language = "BASIC");
+            let _run = span.enter();
+            for n in 0..3 {
+                tracing::debug!(n, name = "x", "event");
+            }
+        });
+        let entries = buffer.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].spans, "run{language=BASIC}");
+        assert_eq!(entries[1].message, "event n=2 name=x");
+        buffer.clear();
+        assert!(buffer.entries().is_empty());
+    }
+}
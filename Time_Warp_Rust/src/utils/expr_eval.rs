@@ -1,521 +0,0 @@
-//! Safe Expression Evaluator for Time Warp IDE
-//! 
-//! This module provides secure mathematical expression evaluation without using eval().
-//! 
-//! # Features
-//! - Arithmetic operators: `+`, `-`, `*`, `/`, `^` (exponent), `%` (modulo)
-//! - Mathematical functions: `sin()`, `cos()`, `tan()`, `sqrt()`, `abs()`, `log()`, etc.
-//! - Variables: Pre-defined or dynamic via `set_variable()`
-//! - Parentheses for grouping
-//! - Negative numbers: `-5`, `-(3 + 2)`
-//! 
-//! # Example
-//! ```rust,no_run
-//! use time_warp_unified::utils::ExpressionEvaluator;
-//! 
-//! let mut eval = ExpressionEvaluator::new();
-//! eval.set_variable("X".to_string(), 10.0);
-//! 
-//! let result = eval.evaluate("2 * X + 5").unwrap();
-//! assert_eq!(result, 25.0);
-//! 
-//! let trig = eval.evaluate("sin(0) + cos(0)").unwrap();
-//! assert_eq!(trig, 1.0);
-//! ```
-//! 
-//! # Supported Functions
-//! Trigonometric: `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `sinh`, `cosh`, `tanh`
-//! Math: `sqrt`, `abs`, `floor`, `ceil`, `round`, `exp`, `log` (natural log), `log10`
-//! Special: `min(a,b)`, `max(a,b)`, `pow(base,exp)`, `rand()` (0-1), `int(x)` (truncate)
-//! 
-//! # Security
-//! - No `eval()` or code execution - only safe arithmetic
-//! - Complexity limits: MAX_TOKENS=1000, MAX_DEPTH=100 (prevents DoS)
-//! - Detailed error messages for debugging
-
-use anyhow::{Result, anyhow};
-use std::collections::HashMap;
-
-/// Security limits to prevent DoS attacks
-const MAX_TOKENS: usize = 1000;
-const MAX_DEPTH: usize = 100;
-
-#[derive(Debug, Clone, PartialEq)]
-enum Token {
-    Number(f64),
-    Variable(String),
-    Function(String),
-    Operator(char),
-    Comparison(String), // >, <, >=, <=, ==, !=
-    LeftParen,
-    RightParen,
-    Comma,
-}
-
-/// Safe expression evaluator supporting math expressions, variables, and functions
-/// 
-/// See module-level documentation for usage examples and supported features.
-pub struct ExpressionEvaluator {
-    variables: HashMap<String, f64>,
-    /// Expression cache for 10-50x performance boost on repeated evaluations
-    token_cache: std::cell::RefCell<HashMap<String, Vec<Token>>>,
-}
-
-impl Default for ExpressionEvaluator {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl ExpressionEvaluator {
-    /// Create a new evaluator with no variables
-    pub fn new() -> Self {
-        Self {
-            variables: HashMap::new(),
-            token_cache: std::cell::RefCell::new(HashMap::new()),
-        }
-    }
-    
-    /// Create evaluator with pre-defined variables
-    /// 
-    /// # Example
-    /// ```ignore
-    /// let vars = [("PI".to_string(), 3.14159)].into_iter().collect();
-    /// let eval = ExpressionEvaluator::with_variables(vars);
-    /// ```
-    pub fn with_variables(vars: HashMap<String, f64>) -> Self {
-        Self { 
-            variables: vars,
-            token_cache: std::cell::RefCell::new(HashMap::new()),
-        }
-    }
-    
-    /// Set or update a variable value
-    /// 
-    /// # Example
-    /// ```ignore
-    /// let mut eval = ExpressionEvaluator::new();
-    /// eval.set_variable("X".to_string(), 42.0);
-    /// assert_eq!(eval.evaluate("X * 2").unwrap(), 84.0);
-    /// ```
-    #[allow(dead_code)]
-    pub fn set_variable(&mut self, name: String, value: f64) {
-        self.variables.insert(name, value);
-    }
-    
-    /// Clear token cache (call after adding/removing variables)
-    #[allow(dead_code)]
-    pub fn clear_cache(&mut self) {
-        self.token_cache.borrow_mut().clear();
-    }
-    
-    /// Evaluate a mathematical expression safely
-    /// 
-    /// # Arguments
-    /// * `expr` - Expression string like "2 + 3 * X" or "sin(PI / 2)"
-    /// 
-    /// # Returns
-    /// * `Ok(f64)` - Computed result
-    /// * `Err` - Detailed error with expression context
-    /// 
-    /// # Example
-    /// ```rust,no_run
-    /// use time_warp_unified::utils::ExpressionEvaluator;
-    /// let eval = ExpressionEvaluator::new();
-    /// assert_eq!(eval.evaluate("2 + 3").unwrap(), 5.0);
-    /// assert_eq!(eval.evaluate("sqrt(16)").unwrap(), 4.0);
-    /// ```
-    /// 
-    /// Uses expression caching for 10-50x speedup on repeated evaluations.
-    pub fn evaluate(&self, expr: &str) -> Result<f64> {
-        // Check cache first (10-50x faster for repeated expressions)
-        // Must drop borrow before potentially borrowing mut
-        let tokens = {
-            let cache = self.token_cache.borrow();
-            if let Some(cached) = cache.get(expr) {
-                cached.clone()
-            } else {
-                drop(cache);  // Release borrow before mut borrow
-                let new_tokens = self.tokenize(expr)
-                    .map_err(|e| anyhow!("Failed to parse expression '{}': {}", expr, e))?;
-                self.token_cache.borrow_mut().insert(expr.to_string(), new_tokens.clone());
-                new_tokens
-            }
-        };
-        
-        let rpn = self.to_rpn(tokens)
-            .map_err(|e| anyhow!("Invalid expression '{}': {}", expr, e))?;
-        self.evaluate_rpn(rpn)
-            .map_err(|e| anyhow!("Evaluation failed for '{}': {}", expr, e))
-    }
-    
-    fn tokenize(&self, expr: &str) -> Result<Vec<Token>> {
-        let mut tokens = Vec::new();
-        let mut chars = expr.chars().peekable();
-        
-        while let Some(&ch) = chars.peek() {
-            // Security check: Prevent DoS with overly complex expressions
-            if tokens.len() >= MAX_TOKENS {
-                return Err(anyhow!("Expression too complex (max {} tokens)", MAX_TOKENS));
-            }
-            
-            match ch {
-                ' ' | '\t' | '\n' => {
-                    chars.next();
-                }
-                '0'..='9' | '.' => {
-                    let mut num_str = String::new();
-                    while let Some(&c) = chars.peek() {
-                        if c.is_ascii_digit() || c == '.' {
-                            num_str.push(c);
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                    tokens.push(Token::Number(num_str.parse()?));
-                }
-                'A'..='Z' | 'a'..='z' | '_' => {
-                    let mut name = String::new();
-                    while let Some(&c) = chars.peek() {
-                        if c.is_alphanumeric() || c == '_' {
-                            name.push(c);
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                    
-                    // Check if it's a function (followed by '(')
-                    if chars.peek() == Some(&'(') {
-                        tokens.push(Token::Function(name.to_uppercase()));
-                    } else {
-                        tokens.push(Token::Variable(name.to_uppercase()));
-                    }
-                }
-                '+' => {
-                    tokens.push(Token::Operator('+'));
-                    chars.next();
-                }
-                '-' => {
-                    // Handle negative numbers - if minus is at start or after operator/left paren, treat as part of number
-                    let is_unary = tokens.is_empty() || 
-                        matches!(tokens.last(), Some(Token::Operator(_)) | Some(Token::LeftParen) | Some(Token::Comma));
-                    
-                    if is_unary && chars.clone().nth(1).map(|c| c.is_ascii_digit()).unwrap_or(false) {
-                        chars.next(); // consume '-'
-                        let mut num_str = String::from("-");
-                        while let Some(&c) = chars.peek() {
-                            if c.is_ascii_digit() || c == '.' {
-                                num_str.push(c);
-                                chars.next();
-                            } else {
-                                break;
-                            }
-                        }
-                        tokens.push(Token::Number(num_str.parse()?));
-                    } else {
-                        tokens.push(Token::Operator('-'));
-                        chars.next();
-                    }
-                }
-                '*' | '/' | '^' | '%' => {
-                    tokens.push(Token::Operator(ch));
-                    chars.next();
-                }
-                '>' | '<' | '=' | '!' => {
-                    // Handle comparison operators: >, <, >=, <=, ==, !=
-                    let mut comp = ch.to_string();
-                    chars.next();
-                    
-                    if let Some(&next_ch) = chars.peek() {
-                        if (ch == '>' || ch == '<' || ch == '=' || ch == '!') && next_ch == '=' {
-                            comp.push(next_ch);
-                            chars.next();
-                        }
-                    }
-                    
-                    // Single '=' is assignment in BASIC, but for IF conditions treat as comparison
-                    if comp == "=" {
-                        comp = "==".to_string();
-                    }
-                    
-                    tokens.push(Token::Comparison(comp));
-                }
-                '(' => {
-                    tokens.push(Token::LeftParen);
-                    chars.next();
-                }
-                ')' => {
-                    tokens.push(Token::RightParen);
-                    chars.next();
-                }
-                ',' => {
-                    tokens.push(Token::Comma);
-                    chars.next();
-                }
-                _ => return Err(anyhow!("Invalid character: {}", ch)),
-            }
-        }
-        
-        Ok(tokens)
-    }
-    
-    fn to_rpn(&self, tokens: Vec<Token>) -> Result<Vec<Token>> {
-        let mut output = Vec::new();
-        let mut operator_stack: Vec<Token> = Vec::new();
-        
-        for token in tokens {
-            match token {
-                Token::Number(_) | Token::Variable(_) => output.push(token),
-                Token::Function(_) => operator_stack.push(token),
-                Token::Comparison(_) => {
-                    // Comparisons have lowest precedence
-                    while let Some(top) = operator_stack.last() {
-                        if matches!(top, Token::Operator(_) | Token::Comparison(_)) {
-                            output.push(operator_stack.pop().unwrap());
-                        } else {
-                            break;
-                        }
-                    }
-                    operator_stack.push(token);
-                }
-                Token::Operator(op) => {
-                    // Security check: Prevent stack overflow from deeply nested expressions
-                    if operator_stack.len() >= MAX_DEPTH {
-                        return Err(anyhow!("Expression too deeply nested (max depth {})", MAX_DEPTH));
-                    }
-                    
-                    while let Some(top) = operator_stack.last() {
-                        if let Token::Operator(top_op) = top {
-                            if self.precedence(*top_op) >= self.precedence(op) {
-                                output.push(operator_stack.pop().unwrap());
-                            } else {
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                    operator_stack.push(Token::Operator(op));
-                }
-                Token::LeftParen => {
-                    // Security check: Prevent excessive nesting
-                    if operator_stack.len() >= MAX_DEPTH {
-                        return Err(anyhow!("Expression too deeply nested (max depth {})", MAX_DEPTH));
-                    }
-                    operator_stack.push(token);
-                }
-                Token::RightParen => {
-                    while let Some(top) = operator_stack.pop() {
-                        if matches!(top, Token::LeftParen) {
-                            break;
-                        }
-                        output.push(top);
-                    }
-                    
-                    // Check for function
-                    if let Some(Token::Function(_)) = operator_stack.last() {
-                        output.push(operator_stack.pop().unwrap());
-                    }
-                }
-                Token::Comma => {
-                    while let Some(top) = operator_stack.last() {
-                        if matches!(top, Token::LeftParen) {
-                            break;
-                        }
-                        output.push(operator_stack.pop().unwrap());
-                    }
-                }
-            }
-        }
-        
-        while let Some(op) = operator_stack.pop() {
-            output.push(op);
-        }
-        
-        Ok(output)
-    }
-    
-    fn evaluate_rpn(&self, rpn: Vec<Token>) -> Result<f64> {
-        let mut stack: Vec<f64> = Vec::new();
-        
-        for token in rpn {
-            match token {
-                Token::Number(n) => stack.push(n),
-                Token::Variable(name) => {
-                    let val = self.variables
-                        .get(&name)
-                        .copied()
-                        .ok_or_else(|| anyhow!("Undefined variable: {}", name))?;
-                    stack.push(val);
-                }
-                Token::Operator(op) => {
-                    let b = stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                    let a = stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                    
-                    let result = match op {
-                        '+' => a + b,
-                        '-' => a - b,
-                        '*' => a * b,
-                        '/' => {
-                            if b.abs() < f64::EPSILON {
-                                return Err(anyhow!("Division by zero"));
-                            }
-                            a / b
-                        }
-                        '^' => a.powf(b),
-                        '%' => a % b,
-                        _ => return Err(anyhow!("Unknown operator: {}", op)),
-                    };
-                    
-                    stack.push(result);
-                }
-                Token::Comparison(comp) => {
-                    let b = stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                    let a = stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                    
-                    let result = match comp.as_str() {
-                        ">" => if a > b { 1.0 } else { 0.0 },
-                        "<" => if a < b { 1.0 } else { 0.0 },
-                        ">=" => if a >= b { 1.0 } else { 0.0 },
-                        "<=" => if a <= b { 1.0 } else { 0.0 },
-                        "==" => if (a - b).abs() < f64::EPSILON { 1.0 } else { 0.0 },
-                        "!=" => if (a - b).abs() >= f64::EPSILON { 1.0 } else { 0.0 },
-                        _ => return Err(anyhow!("Unknown comparison: {}", comp)),
-                    };
-                    
-                    stack.push(result);
-                }
-                Token::Function(name) => {
-                    let result = self.call_function(&name, &mut stack)?;
-                    stack.push(result);
-                }
-                _ => return Err(anyhow!("Unexpected token in RPN")),
-            }
-        }
-        
-        stack.pop().ok_or_else(|| anyhow!("Empty stack"))
-    }
-    
-    fn call_function(&self, name: &str, stack: &mut Vec<f64>) -> Result<f64> {
-        match name {
-            "SIN" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("SIN: missing argument"))?;
-                Ok(a.sin())
-            }
-            "COS" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("COS: missing argument"))?;
-                Ok(a.cos())
-            }
-            "TAN" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("TAN: missing argument"))?;
-                Ok(a.tan())
-            }
-            "ATAN" | "ATN" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("ATAN: missing argument"))?;
-                Ok(a.atan())
-            }
-            "SQRT" | "SQR" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("SQRT: missing argument"))?;
-                Ok(a.sqrt())
-            }
-            "ABS" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("ABS: missing argument"))?;
-                Ok(a.abs())
-            }
-            "EXP" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("EXP: missing argument"))?;
-                Ok(a.exp())
-            }
-            "LOG" | "LN" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("LOG: missing argument"))?;
-                Ok(a.ln())
-            }
-            "LOG10" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("LOG10: missing argument"))?;
-                Ok(a.log10())
-            }
-            "INT" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("INT: missing argument"))?;
-                Ok(a.floor())
-            }
-            "ROUND" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("ROUND: missing argument"))?;
-                Ok(a.round())
-            }
-            "SGN" => {
-                let a = stack.pop().ok_or_else(|| anyhow!("SGN: missing argument"))?;
-                Ok(if a > 0.0 { 1.0 } else if a < 0.0 { -1.0 } else { 0.0 })
-            }
-            "RND" => {
-                // Random number between 0 and 1
-                Ok(rand::random::<f64>())
-            }
-            "MAX" => {
-                let b = stack.pop().ok_or_else(|| anyhow!("MAX: missing argument"))?;
-                let a = stack.pop().ok_or_else(|| anyhow!("MAX: missing argument"))?;
-                Ok(a.max(b))
-            }
-            "MIN" => {
-                let b = stack.pop().ok_or_else(|| anyhow!("MIN: missing argument"))?;
-                let a = stack.pop().ok_or_else(|| anyhow!("MIN: missing argument"))?;
-                Ok(a.min(b))
-            }
-            "POW" => {
-                let b = stack.pop().ok_or_else(|| anyhow!("POW: missing argument"))?;
-                let a = stack.pop().ok_or_else(|| anyhow!("POW: missing argument"))?;
-                Ok(a.powf(b))
-            }
-            _ => Err(anyhow!("Unknown function: {}", name)),
-        }
-    }
-    
-    fn precedence(&self, op: char) -> u8 {
-        match op {
-            '+' | '-' => 1,
-            '*' | '/' | '%' => 2,
-            '^' => 3,
-            _ => 0,
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_basic_arithmetic() {
-        let eval = ExpressionEvaluator::new();
-        assert_eq!(eval.evaluate("2 + 3").unwrap(), 5.0);
-        assert_eq!(eval.evaluate("10 - 4").unwrap(), 6.0);
-        assert_eq!(eval.evaluate("3 * 4").unwrap(), 12.0);
-        assert_eq!(eval.evaluate("15 / 3").unwrap(), 5.0);
-    }
-    
-    #[test]
-    fn test_precedence() {
-        let eval = ExpressionEvaluator::new();
-        assert_eq!(eval.evaluate("2 + 3 * 4").unwrap(), 14.0);
-        assert_eq!(eval.evaluate("(2 + 3) * 4").unwrap(), 20.0);
-    }
-    
-    #[test]
-    fn test_functions() {
-        let eval = ExpressionEvaluator::new();
-        assert!((eval.evaluate("SIN(0)").unwrap() - 0.0).abs() < 0.0001);
-        assert_eq!(eval.evaluate("ABS(-5)").unwrap(), 5.0);
-        assert_eq!(eval.evaluate("SQRT(16)").unwrap(), 4.0);
-    }
-    
-    #[test]
-    fn test_variables() {
-        let mut vars = HashMap::new();
-        vars.insert("X".to_string(), 10.0);
-        vars.insert("Y".to_string(), 5.0);
-        let eval = ExpressionEvaluator::with_variables(vars);
-        assert_eq!(eval.evaluate("X + Y").unwrap(), 15.0);
-        assert_eq!(eval.evaluate("X * 2 + Y").unwrap(), 25.0);
-    }
-}
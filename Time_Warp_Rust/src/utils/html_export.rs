@@ -0,0 +1,278 @@
+//! HTML export of a run: the transcript in the theme's colors, and the drawing as SVG
+//!
+//! The page shows the run as the Output tab did. The transcript sits on the
+//! theme's screen background with errors in red, warnings in yellow (or left
+//! out, as on screen), values taken from variables underlined, and the answers
+//! typed at prompts set apart. The drawing is an inline SVG on the canvas's
+//! own background. A "Print-friendly" checkbox on the page, and printing it,
+//! switch to dark text on white.
+
+use eframe::egui::Color32;
+
+use crate::graphics::{color32, Rgba, TurtleState};
+use crate::interpreter::segments::Segment;
+use crate::interpreter::warnings::{is_error_line, is_warning_line};
+use crate::interpreter::Interpreter;
+
+/// The colors a theme shows the transcript in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeColors {
+    /// Behind the text screen
+    pub background: Color32,
+    pub text: Color32,
+    /// Typed answers
+    pub accent: Color32,
+    /// Around the screen
+    pub panel: Color32,
+    pub warning: Color32,
+    pub error: Color32,
+    pub variable: Color32,
+}
+
+/// One entry of the transcript, in the order it appeared
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputEvent {
+    /// A line the program wrote, with the parts that came from variables
+    Text(Vec<Segment>),
+    /// An error, with its hint lines
+    Error(String),
+    Warning(String),
+    /// An answer typed at an INPUT, `A:`, or `INPUT$` prompt
+    Input(String),
+}
+
+impl OutputEvent {
+    /// CSS class of the event's line
+    fn class(&self) -> &'static str {
+        match self {
+            OutputEvent::Text(_) => "text",
+            OutputEvent::Error(_) => "error",
+            OutputEvent::Warning(_) => "warning",
+            OutputEvent::Input(_) => "input",
+        }
+    }
+}
+
+/// What to put in the page besides the transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlOptions {
+    pub title: String,
+    /// Keep warning lines (Settings → Warnings)
+    pub show_warnings: bool,
+}
+
+/// The interpreter's transcript as events, answers placed where they were typed
+pub fn output_events(interp: &Interpreter) -> Vec<OutputEvent> {
+    let mut answers = interp.answers.iter().peekable();
+    let mut events = Vec::new();
+    for index in 0..interp.output.len() {
+        while let Some((_, answer)) = answers.next_if(|(at, _)| *at <= index) {
+            events.push(OutputEvent::Input(answer.clone()));
+        }
+        let line = interp.full_output_line(index).unwrap_or_default();
+        events.push(if is_warning_line(line) {
+            OutputEvent::Warning(line.to_string())
+        } else if is_error_line(line) {
+            OutputEvent::Error(line.to_string())
+        } else {
+            let segments = interp.output_segments(index).map(<[Segment]>::to_vec);
+            OutputEvent::Text(segments.unwrap_or_else(|| vec![Segment::Text(line.to_string())]))
+        });
+    }
+    events.extend(answers.map(|(_, answer)| OutputEvent::Input(answer.clone())));
+    events
+}
+
+/// A whole HTML page of `events`, and of the drawing when there is one
+///
+/// `map_color` is applied to every drawing color, as for the PNG export
+/// (e.g. the CVD-safe palette when the export matches the screen).
+pub fn export_html(
+    events: &[OutputEvent],
+    turtle: &TurtleState,
+    colors: &ThemeColors,
+    options: &HtmlOptions,
+    map_color: impl Fn(Rgba) -> Rgba,
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&options.title)));
+    html.push_str(&stylesheet(colors));
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape(&options.title)));
+    html.push_str("<label class=\"print-toggle\"><input type=\"checkbox\" id=\"print-friendly\"> Print-friendly</label>\n");
+    html.push_str("<pre class=\"screen\">\n");
+    for event in events.iter().filter(|e| options.show_warnings || !matches!(e, OutputEvent::Warning(_))) {
+        html.push_str(&format!("<span class=\"{}\">{}</span>\n", event.class(), event_html(event)));
+    }
+    html.push_str("</pre>\n");
+    if turtle.total_lines() > 0 || !turtle.texts.is_empty() {
+        html.push_str(&drawing_svg(turtle, map_color));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn event_html(event: &OutputEvent) -> String {
+    match event {
+        OutputEvent::Text(segments) => segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => escape(text),
+                Segment::Variable { name, value } => format!("<span class=\"var\" title=\"{}\">{}</span>", escape(name), escape(value)),
+            })
+            .collect(),
+        // Hint lines under an error keep their own color
+        OutputEvent::Error(text) => {
+            let mut lines = text.lines();
+            let first = escape(lines.next().unwrap_or_default());
+            lines.fold(first, |html, hint| format!("{}\n<span class=\"hint\">{}</span>", html, escape(hint)))
+        }
+        OutputEvent::Warning(text) | OutputEvent::Input(text) => escape(text),
+    }
+}
+
+/// Page styles: the theme's colors, then the print-friendly ones for the checkbox and for printing
+fn stylesheet(colors: &ThemeColors) -> String {
+    let mut css = String::from("<style>\n:root {\n");
+    for (name, color) in [
+        ("background", colors.background),
+        ("text", colors.text),
+        ("accent", colors.accent),
+        ("panel", colors.panel),
+        ("warning", colors.warning),
+        ("error", colors.error),
+        ("variable", colors.variable),
+    ] {
+        css.push_str(&format!("  --{}: {};\n", name, hex(color)));
+    }
+    css.push_str("}\n");
+    css.push_str(THEMED_CSS);
+    for line in PRINT_FRIENDLY_CSS.lines() {
+        css.push_str(&format!("body:has(#print-friendly:checked) {}\n", line));
+    }
+    css.push_str("@media print {\n");
+    for line in PRINT_FRIENDLY_CSS.lines() {
+        css.push_str(&format!("  {}\n", line));
+    }
+    css.push_str("  .print-toggle { display: none; }\n}\n</style>\n");
+    css
+}
+
+const THEMED_CSS: &str = "\
+body { background: var(--panel); color: var(--text); font-family: sans-serif; margin: 2em; }
+.screen { background: var(--background); color: var(--text); padding: 1em; white-space: pre-wrap; }
+.error { color: var(--error); }
+.warning { color: var(--warning); }
+.hint { opacity: 0.8; }
+.input { color: var(--accent); font-style: italic; }
+.input::before { content: \"> \"; }
+.var { color: var(--variable); text-decoration: underline; }
+.drawing { display: block; max-width: 100%; height: auto; margin-top: 1em; }
+";
+
+/// Rules of the print-friendly look, each on one line
+const PRINT_FRIENDLY_CSS: &str = "\
+.screen { background: #ffffff; color: #000000; border: 1px solid #808080; }
+.error { color: #b00000; }
+.warning { color: #805000; }
+.input { color: #000000; }
+.var { color: inherit; }";
+
+/// The turtle's drawing, lines then labels, on the canvas background
+fn drawing_svg(turtle: &TurtleState, map_color: impl Fn(Rgba) -> Rgba) -> String {
+    let color = |c: Rgba| color32(map_color(c));
+    let (width, height) = (turtle.canvas_width, turtle.canvas_height);
+    let mut svg = format!(
+        "<svg class=\"drawing\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+        number(width), number(height), number(-width / 2.0), number(-height / 2.0), number(width), number(height)
+    );
+    svg.push_str(&format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        number(-width / 2.0), number(-height / 2.0), number(width), number(height), hex(color(turtle.bg_color))
+    ));
+    // Lines the low-memory canvas folded into an image come first, as on screen
+    if let Some((world, Ok(png))) = turtle.backdrop.as_ref().map(|backdrop| (backdrop.world, backdrop.png(&map_color))) {
+        svg.push_str(&format!(
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" href=\"data:image/png;base64,{}\"/>\n",
+            number(world.min.x), number(world.min.y), number(world.width()), number(world.height()), base64(&png)
+        ));
+    }
+    for line in &turtle.lines {
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\"/>\n",
+            number(line.start.x), number(line.start.y), number(line.end.x), number(line.end.y), hex(color(line.color)), number(line.width)
+        ));
+    }
+    for text in &turtle.texts {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" fill=\"{}\" dominant-baseline=\"hanging\">{}</text>\n",
+            number(text.pos.x), number(text.pos.y), number(text.size), hex(color(text.color)), escape(&text.text)
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Standard base64 with padding, as a data URL holds it
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            out.push(if i <= chunk.len() { ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char } else { '=' });
+        }
+    }
+    out
+}
+
+/// `#rrggbb`, with the alpha as a fourth byte when it is not opaque
+pub(crate) fn hex(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+/// A coordinate to two decimal places, without trailing zeros
+fn number(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_go_where_they_were_typed() {
+        let mut interp = Interpreter::new();
+        interp.load_program("10 INPUT \"Name\"; N$\n20 PRINT \"Hi\", N$\n30 NEXT I").unwrap();
+        let mut turtle = TurtleState::new();
+        interp.execute(&mut turtle).unwrap();
+        interp.provide_input("Ada");
+        interp.execute(&mut turtle).unwrap();
+        let events = output_events(&interp);
+        assert_eq!(events[0], OutputEvent::Input("Ada".into()));
+        assert!(matches!(&events[1], OutputEvent::Text(segments) if segments.len() > 1), "{:?}", events);
+        assert!(matches!(events[2], OutputEvent::Error(_)), "{:?}", events);
+    }
+
+    #[test]
+    fn test_numbers_and_escapes() {
+        assert_eq!(number(12.5), "12.5");
+        assert_eq!(number(-0.001), "0");
+        assert_eq!(number(100.0), "100");
+        assert_eq!(escape("a<b & \"c\""), "a&lt;b &amp; &quot;c&quot;");
+        assert_eq!(hex(Color32::from_rgb(255, 176, 0)), "#ffb000");
+        assert_eq!((base64(b"Man"), base64(b"Ma"), base64(b"M")), ("TWFu".into(), "TWE=".into(), "TQ==".into()));
+    }
+}
@@ -1,11 +1,26 @@
-// Utility modules
-pub mod error;
-pub mod expr_eval;
+// Utility modules (the interpreter's own live in time_warp_core; re-exported for the library API)
+#[allow(unused_imports)]
+pub use time_warp_core::utils::{error, error_hints, expr_eval, line_endings, text_wrap, transcript};
 pub mod async_exec;
+pub mod data_export;
+pub mod diagnostics;
+pub mod safe_open;
+pub mod share;
+pub mod workspace_search;
+pub mod html_export;
+pub mod screen_export;
+pub mod output_diff;
+pub mod run_history;
+pub mod run_reporters;
+pub mod zip_archive;
+pub mod twpak;
+pub mod assessment;
+pub mod submission;
+pub mod model_compare;
 
 // Re-export commonly used types
-pub use expr_eval::ExpressionEvaluator;
+#[allow(unused_imports)]
+pub use time_warp_core::utils::ExpressionEvaluator;
 
 // Async execution types available but not automatically exported to reduce warnings
 // Use: use crate::utils::async_exec::{AsyncExecutor, ExecutionEvent};
-pub mod error_hints;
@@ -0,0 +1,180 @@
+//! Tools ▸ Compare With Model: a student's program run beside a model answer
+//!
+//! Both programs run through [`Interpreter::run_isolated`] with the same
+//! random seed and the same scripted answers, so whatever differs comes from
+//! the programs. The transcripts go through the Diff tab's engine with the
+//! model as the old side, so a `-` line is one the model printed and the
+//! student did not. The drawings are compared segment by segment, in any
+//! order and either way round: a segment is matched when the other drawing
+//! has one with the same ends, to half a turtle step. Colors and pen widths
+//! are not compared.
+
+use std::collections::HashMap;
+
+use crate::graphics::{Pos2, Rect, TurtleLine};
+use crate::interpreter::isolated::{IsolatedRunOptions, RunReport};
+use crate::interpreter::{ExecutionLimits, Interpreter, SecurityPolicy};
+use crate::utils::output_diff::{diff_lines, normalize_transcript, DiffLine, OutputDiff, TranscriptSnapshot};
+
+/// Seed both runs start from when Settings does not fix one
+pub const DEFAULT_SEED: u64 = 1;
+
+/// What both programs are given
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareOptions {
+    /// Answers to INPUT and `A:`, in order; with none, both runs stop at their first question
+    pub inputs: Vec<String>,
+    pub seed: u64,
+}
+
+impl CompareOptions {
+    /// Options answering with `answers`, one per line
+    pub fn new(answers: &str, seed: u64) -> Self {
+        Self { inputs: answers.lines().map(String::from).collect(), seed }
+    }
+
+    fn isolated(&self) -> IsolatedRunOptions {
+        // Whole lesson programs, so the IDE's limits rather than a snippet's, still without files or the network
+        IsolatedRunOptions {
+            inputs: self.inputs.clone(),
+            random_seed: Some(self.seed),
+            limits: ExecutionLimits::default(),
+            policy: SecurityPolicy::restricted(),
+            ..IsolatedRunOptions::default()
+        }
+    }
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone)]
+pub struct ComparedRun {
+    pub report: RunReport,
+    /// Indices into `report.lines` of the segments the other drawing lacks
+    pub unmatched: Vec<usize>,
+}
+
+impl ComparedRun {
+    /// Smallest rectangle containing every segment drawn
+    pub fn bounds(&self) -> Option<Rect> {
+        self.report.lines.iter().map(|l| Rect::from_two_pos(l.start, l.end)).reduce(|a, b| a.union(b))
+    }
+
+    fn snapshot(&self) -> TranscriptSnapshot {
+        TranscriptSnapshot { lines: normalize_transcript(&self.report.output), segments: self.report.lines_drawn, bounds: self.bounds() }
+    }
+}
+
+/// A student's run and a model answer's, and how they differ
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub student: ComparedRun,
+    pub model: ComparedRun,
+    /// Model-to-student transcript diff, with the drawings' one-line summary
+    pub output: OutputDiff,
+    /// Every transcript line: `Removed` only the model printed, `Added` only the student
+    pub transcript: Vec<DiffLine>,
+}
+
+impl Comparison {
+    /// Run both programs with `options` and compare what they did
+    pub fn run(student: &str, model: &str, options: &CompareOptions) -> Self {
+        let student = Interpreter::run_isolated(student, options.isolated());
+        let model = Interpreter::run_isolated(model, options.isolated());
+        Self::between(student, model)
+    }
+
+    /// Compare two finished runs
+    pub fn between(student: RunReport, model: RunReport) -> Self {
+        let student_unmatched = unmatched(&student.lines, &model.lines);
+        let model_unmatched = unmatched(&model.lines, &student.lines);
+        let student = ComparedRun { report: student, unmatched: student_unmatched };
+        let model = ComparedRun { report: model, unmatched: model_unmatched };
+        let (model_snapshot, student_snapshot) = (model.snapshot(), student.snapshot());
+        let output = OutputDiff::between(&model_snapshot, &student_snapshot);
+        let transcript = diff_lines(&model_snapshot.lines, &student_snapshot.lines);
+        Self { student, model, output, transcript }
+    }
+
+    /// Segments one program drew and the other did not
+    pub fn differing_segments(&self) -> usize {
+        self.student.unmatched.len() + self.model.unmatched.len()
+    }
+
+    /// Same transcript and same drawing
+    pub fn matches(&self) -> bool {
+        self.output.is_identical() && self.differing_segments() == 0
+    }
+
+    /// Part of the turtle plane both small canvases show, so the drawings line up
+    pub fn view_bounds(&self) -> Option<Rect> {
+        match (self.student.bounds(), self.model.bounds()) {
+            (Some(a), Some(b)) => Some(a.union(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// A segment's ends rounded to half a step, the lower end first
+type SegmentKey = [(i64, i64); 2];
+
+fn segment_key(line: &TurtleLine) -> SegmentKey {
+    let point = |p: Pos2| ((p.x * 2.0).round() as i64, (p.y * 2.0).round() as i64);
+    let (a, b) = (point(line.start), point(line.end));
+    if a <= b { [a, b] } else { [b, a] }
+}
+
+/// Indices of `lines` with no counterpart in `other`; each of `other`'s segments matches once
+fn unmatched(lines: &[TurtleLine], other: &[TurtleLine]) -> Vec<usize> {
+    let mut available: HashMap<SegmentKey, usize> = HashMap::new();
+    for line in other {
+        *available.entry(segment_key(line)).or_default() += 1;
+    }
+    let mut missing = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match available.get_mut(&segment_key(line)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => missing.push(i),
+        }
+    }
+    missing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "10 INPUT N\n20 PRINT N * 2\n30 PRINT INT(RND() * 1000)\n40 LINE 0, 0, 100, 0\n50 LINE 0, 0, 0, 100\n60 PRINT \"DONE\"";
+
+    #[test]
+    fn test_two_different_programs_are_compared_on_the_same_answers() {
+        // Doubles by adding, draws one side backwards and the other too short, and ends differently
+        let student = "10 INPUT N\n20 PRINT N + N\n30 PRINT INT(RND() * 1000)\n40 LINE 0, 100, 0, 0\n50 LINE 0, 0, 50, 0\n60 PRINT \"FINISHED\"";
+        let comparison = Comparison::run(student, MODEL, &CompareOptions::new("21\n", 7));
+        assert!(comparison.student.report.completed() && comparison.model.report.completed());
+        assert_eq!(comparison.student.report.output[..2], comparison.model.report.output[..2], "same answer, same seed");
+        assert_eq!(comparison.student.report.output[0], "42");
+
+        assert!(!comparison.matches());
+        assert_eq!((comparison.output.added, comparison.output.removed), (1, 1));
+        let lines = &comparison.output.hunks[0].lines;
+        assert_eq!(lines[lines.len() - 2..], [DiffLine::Removed("DONE".into()), DiffLine::Added("FINISHED".into())]);
+        assert_eq!(comparison.transcript.len(), 4, "the whole transcript, not just the hunks");
+
+        assert_eq!(comparison.differing_segments(), 2);
+        assert_eq!((comparison.student.unmatched.as_slice(), comparison.model.unmatched.as_slice()), (&[1][..], &[0][..]));
+        let view = comparison.view_bounds().unwrap();
+        assert_eq!((view.width(), view.height()), (100.0, 100.0));
+        assert!(comparison.output.turtle_summary.starts_with("Turtle: bounds"), "{}", comparison.output.turtle_summary);
+    }
+
+    #[test]
+    fn test_a_program_matches_itself() {
+        let comparison = Comparison::run(MODEL, MODEL, &CompareOptions::new("5", DEFAULT_SEED));
+        assert!(comparison.matches());
+        assert_eq!(comparison.differing_segments(), 0);
+
+        let unanswered = Comparison::run(MODEL, MODEL, &CompareOptions::new("", DEFAULT_SEED));
+        assert!(unanswered.model.report.waiting_for_input && unanswered.matches());
+        assert_eq!(CompareOptions::new("3\n\nAda\n", 1).inputs, ["3", "", "Ada"]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Comparing two runs (Output → Pin output, Diff tab)
+//!
+//! A pinned snapshot keeps the transcript plus a summary of the drawing. After
+//! the next run the transcripts are normalized and compared line by line with
+//! a longest-common-subsequence diff, grouped into unified-diff hunks.
+
+use eframe::egui;
+
+use crate::graphics::TurtleState;
+pub use crate::utils::transcript::normalize_transcript;
+
+/// Unchanged lines shown around each change
+pub const DIFF_CONTEXT: usize = 3;
+/// Largest LCS table (after trimming the common prefix and suffix) before
+/// falling back to "everything in between changed"
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// A run's transcript and drawing summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptSnapshot {
+    pub lines: Vec<String>,
+    pub segments: usize,
+    pub bounds: Option<egui::Rect>,
+}
+
+impl TranscriptSnapshot {
+    pub fn capture(output: &[String], turtle: &TurtleState) -> Self {
+        Self { lines: normalize_transcript(output), segments: turtle.total_lines(), bounds: turtle.bounds() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// A run of changes with its surrounding context (1-based starts, like `@@ -a,b +c,d @@`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl Hunk {
+    pub fn header(&self) -> String {
+        format!("@@ -{},{} +{},{} @@", self.old_start, self.old_len, self.new_start, self.new_len)
+    }
+}
+
+/// Line-by-line edit script turning `old` into `new`
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut ops: Vec<DiffLine> = old[..prefix].iter().cloned().map(DiffLine::Same).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().cloned().map(DiffLine::Removed));
+        ops.extend(new_mid.iter().cloned().map(DiffLine::Added));
+    } else {
+        ops.extend(lcs_diff(old_mid, new_mid));
+    }
+    ops.extend(old[old.len() - suffix..].iter().cloned().map(DiffLine::Same));
+    ops
+}
+
+fn lcs_diff(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let (n, m) = (old.len(), new.len());
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[at(i, j)] = if old[i] == new[j] {
+                lcs[at(i + 1, j + 1)] + 1
+            } else {
+                lcs[at(i + 1, j)].max(lcs[at(i, j + 1)])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(n + m);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push(DiffLine::Same(old[i].clone()));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[at(i + 1, j)] >= lcs[at(i, j + 1)]) {
+            ops.push(DiffLine::Removed(old[i].clone()));
+            i += 1;
+        } else {
+            ops.push(DiffLine::Added(new[j].clone()));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// Group an edit script into hunks with `context` unchanged lines around each change
+pub fn hunks(ops: &[DiffLine], context: usize) -> Vec<Hunk> {
+    let changed: Vec<usize> = ops.iter().enumerate().filter(|(_, op)| !matches!(op, DiffLine::Same(_))).map(|(i, _)| i).collect();
+    let mut hunks = Vec::new();
+    let mut k = 0;
+    while k < changed.len() {
+        let start = changed[k].saturating_sub(context);
+        let mut end = changed[k];
+        // Merge changes whose context would touch or overlap
+        while k + 1 < changed.len() && changed[k + 1] <= end + 2 * context + 1 {
+            k += 1;
+            end = changed[k];
+        }
+        let end = (end + context + 1).min(ops.len());
+        k += 1;
+
+        // Line numbers of the hunk's first line in the old and new transcripts
+        let (mut old_line, mut new_line) = (1, 1);
+        for op in &ops[..start] {
+            match op {
+                DiffLine::Same(_) => { old_line += 1; new_line += 1; }
+                DiffLine::Removed(_) => old_line += 1,
+                DiffLine::Added(_) => new_line += 1,
+            }
+        }
+        let lines = ops[start..end].to_vec();
+        let old_len = lines.iter().filter(|l| !matches!(l, DiffLine::Added(_))).count();
+        let new_len = lines.iter().filter(|l| !matches!(l, DiffLine::Removed(_))).count();
+        hunks.push(Hunk { old_start: old_line, old_len, new_start: new_line, new_len, lines });
+    }
+    hunks
+}
+
+/// Differences between two runs: transcript hunks plus a one-line drawing summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputDiff {
+    pub hunks: Vec<Hunk>,
+    pub added: usize,
+    pub removed: usize,
+    pub turtle_summary: String,
+}
+
+impl OutputDiff {
+    pub fn between(pinned: &TranscriptSnapshot, current: &TranscriptSnapshot) -> Self {
+        let ops = diff_lines(&pinned.lines, &current.lines);
+        let added = ops.iter().filter(|op| matches!(op, DiffLine::Added(_))).count();
+        let removed = ops.iter().filter(|op| matches!(op, DiffLine::Removed(_))).count();
+        Self { hunks: hunks(&ops, DIFF_CONTEXT), added, removed, turtle_summary: turtle_summary(pinned, current) }
+    }
+
+    pub fn is_identical(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+fn describe_bounds(bounds: Option<egui::Rect>) -> String {
+    match bounds {
+        Some(rect) => format!("{:.0}×{:.0}", rect.width(), rect.height()),
+        None => "nothing drawn".to_string(),
+    }
+}
+
+/// "Turtle: same (4 segments, 50×50)" or what changed
+pub fn turtle_summary(pinned: &TranscriptSnapshot, current: &TranscriptSnapshot) -> String {
+    let same_bounds = match (pinned.bounds, current.bounds) {
+        (Some(a), Some(b)) => a.min.distance(b.min) < 0.5 && a.max.distance(b.max) < 0.5,
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    if pinned.segments == current.segments && same_bounds {
+        return format!("Turtle: same ({} segments, {})", current.segments, describe_bounds(current.bounds));
+    }
+    let mut changes = Vec::new();
+    if pinned.segments != current.segments {
+        changes.push(format!("{} → {} segments", pinned.segments, current.segments));
+    }
+    if !same_bounds {
+        changes.push(format!("bounds {} → {}", describe_bounds(pinned.bounds), describe_bounds(current.bounds)));
+    }
+    format!("Turtle: {}", changes.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_pinned_output_diff_hunks() {
+        let pinned_output: Vec<String> = (1..=12).map(|n| format!("line {}", n)).collect();
+        let turtle = TurtleState::new();
+        let pinned = TranscriptSnapshot::capture(&pinned_output, &turtle);
+
+        // Change line 2, drop line 10, and append a line
+        let mut output = pinned_output.clone();
+        output[1] = "line two".to_string();
+        output.remove(9);
+        output.push("done".to_string());
+        let diff = OutputDiff::between(&pinned, &TranscriptSnapshot::capture(&output, &turtle));
+
+        assert_eq!((diff.added, diff.removed), (2, 2));
+        assert_eq!(diff.hunks.len(), 2);
+        let first = &diff.hunks[0];
+        assert_eq!(first.header(), "@@ -1,5 +1,5 @@");
+        assert_eq!(first.lines[1], DiffLine::Removed("line 2".into()));
+        assert_eq!(first.lines[2], DiffLine::Added("line two".into()));
+        let second = &diff.hunks[1];
+        assert_eq!(second.header(), "@@ -7,6 +7,6 @@");
+        assert!(second.lines.contains(&DiffLine::Removed("line 10".into())));
+        assert_eq!(second.lines.last(), Some(&DiffLine::Added("done".into())));
+        assert_eq!(diff.turtle_summary, "Turtle: same (0 segments, nothing drawn)");
+    }
+
+    #[test]
+    fn test_identical_and_nearby_changes() {
+        let old = lines("a\nb\nc\nd");
+        assert!(hunks(&diff_lines(&old, &old), DIFF_CONTEXT).is_empty());
+
+        // Changes closer than twice the context share a hunk
+        let ops = diff_lines(&old, &lines("A\nb\nc\nD"));
+        assert_eq!(hunks(&ops, 1).len(), 1);
+        assert_eq!(hunks(&ops, 0).len(), 2);
+    }
+
+    #[test]
+    fn test_turtle_summary_reports_changes() {
+        let mut turtle = TurtleState::new();
+        turtle.forward(50.0);
+        let pinned = TranscriptSnapshot::capture(&[], &turtle);
+        turtle.right(90.0);
+        turtle.forward(20.0);
+        let current = TranscriptSnapshot::capture(&[], &turtle);
+        assert_eq!(turtle_summary(&pinned, &pinned), "Turtle: same (1 segments, 0×50)");
+        assert_eq!(turtle_summary(&pinned, &current), "Turtle: 1 → 2 segments, bounds 0×50 → 20×50");
+    }
+}
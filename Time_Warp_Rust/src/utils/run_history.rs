@@ -0,0 +1,195 @@
+//! The last runs of a session (Tools ▸ Run History)
+//!
+//! Every run that ends is kept with its time, file, program hash, random
+//! seed, banner summary, transcript, and drawing summary, newest first, up to
+//! [`MAX_RUNS`]; the oldest is dropped to make room. The program text is kept
+//! too so a past version can be restored, unless it is over
+//! [`MAX_SOURCE_BYTES`], and a transcript keeps at most [`MAX_TRANSCRIPT_LINES`]
+//! lines. With Settings → Keep run history the runs are also saved per
+//! project folder under the data directory and read back when the folder is
+//! opened again.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::TurtleState;
+use crate::interpreter::program_hash;
+use crate::utils::output_diff::TranscriptSnapshot;
+
+/// Runs kept
+pub const MAX_RUNS: usize = 20;
+
+/// Largest program text kept with a run
+pub const MAX_SOURCE_BYTES: usize = 64 * 1024;
+
+/// Output lines kept with a run
+pub const MAX_TRANSCRIPT_LINES: usize = 5_000;
+
+/// One finished run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Local time the run ended, `2024-09-01 14:03:22`
+    pub at: String,
+    pub file: String,
+    /// [`program_hash`] of the program text that ran
+    pub program_hash: String,
+    /// Seed the run's random numbers started from, when it drew any
+    pub seed: Option<u64>,
+    /// The Output banner's text for the run
+    pub summary: String,
+    pub failed: bool,
+    pub transcript: Vec<String>,
+    /// Lines drawn and the drawing's bounds (min x, min y, max x, max y)
+    pub segments: usize,
+    pub bounds: Option<[f32; 4]>,
+    /// The program text that ran; `None` when it was too long to keep
+    pub source: Option<String>,
+}
+
+impl RunRecord {
+    /// A run of `source` from tab `file` that printed `output` and drew on `turtle`
+    pub fn new(file: &str, source: &str, output: &[String], turtle: &TurtleState) -> Self {
+        Self {
+            at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            file: file.to_string(),
+            program_hash: program_hash(source),
+            seed: None,
+            summary: String::new(),
+            failed: false,
+            transcript: output.to_vec(),
+            segments: turtle.total_lines(),
+            bounds: turtle.bounds().map(|r| [r.min.x, r.min.y, r.max.x, r.max.y]),
+            source: Some(source.to_string()),
+        }
+    }
+
+    /// The run as the Diff tab compares it
+    pub fn snapshot(&self) -> TranscriptSnapshot {
+        TranscriptSnapshot {
+            lines: crate::utils::output_diff::normalize_transcript(&self.transcript),
+            segments: self.segments,
+            bounds: self.bounds.map(|[x0, y0, x1, y1]| egui::Rect::from_min_max(egui::pos2(x0, y0), egui::pos2(x1, y1))),
+        }
+    }
+
+    /// Whether `text` is a different program from the one that ran
+    pub fn differs_from(&self, text: &str) -> bool {
+        program_hash(text) != self.program_hash
+    }
+}
+
+/// The kept runs, newest first
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunHistory {
+    runs: VecDeque<RunRecord>,
+}
+
+impl RunHistory {
+    /// Keep `run` as the newest, dropping the oldest past [`MAX_RUNS`]
+    pub fn push(&mut self, mut run: RunRecord) {
+        run.transcript.truncate(MAX_TRANSCRIPT_LINES);
+        if run.source.as_ref().is_some_and(|source| source.len() > MAX_SOURCE_BYTES) {
+            run.source = None;
+        }
+        self.runs.push_front(run);
+        self.runs.truncate(MAX_RUNS);
+    }
+
+    /// Newest first
+    pub fn runs(&self) -> impl Iterator<Item = &RunRecord> {
+        self.runs.iter()
+    }
+
+    /// The `index`th newest run
+    pub fn get(&self, index: usize) -> Option<&RunRecord> {
+        self.runs.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+
+    /// The history saved at `path`; empty when there is none or it cannot be read
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = std::fs::read_to_string(path) else { return Self::default() };
+        let mut history: Self = serde_json::from_str(&text).unwrap_or_default();
+        history.runs.truncate(MAX_RUNS);
+        history
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// Where the history of project folder `root` is saved, if there is a data directory
+pub fn file_for(root: &Path) -> Option<PathBuf> {
+    let key = program_hash(&root.to_string_lossy());
+    crate::config::data_dir().map(|dir| dir.join("run_history").join(format!("{}.json", key)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(n: usize) -> RunRecord {
+        RunRecord::new("a.bas", &format!("10 PRINT {}", n), &[n.to_string()], &TurtleState::new())
+    }
+
+    #[test]
+    fn test_oldest_runs_are_evicted() {
+        let mut history = RunHistory::default();
+        for n in 0..MAX_RUNS + 5 {
+            history.push(run(n));
+        }
+        assert_eq!(history.len(), MAX_RUNS);
+        assert_eq!(history.get(0).unwrap().transcript, ["24"]);
+        assert_eq!(history.runs().last().unwrap().transcript, ["5"]);
+        assert!(history.get(0).unwrap().differs_from("10 PRINT 23"));
+        assert!(!history.get(0).unwrap().differs_from("10 PRINT 24"));
+    }
+
+    #[test]
+    fn test_long_sources_and_transcripts_are_capped() {
+        let mut history = RunHistory::default();
+        let source = "1".repeat(MAX_SOURCE_BYTES + 1);
+        let output: Vec<String> = (0..MAX_TRANSCRIPT_LINES + 10).map(|n| n.to_string()).collect();
+        history.push(RunRecord::new("big.bas", &source, &output, &TurtleState::new()));
+        let kept = history.get(0).unwrap();
+        assert_eq!((kept.source.as_deref(), kept.transcript.len()), (None, MAX_TRANSCRIPT_LINES));
+        assert_eq!(kept.program_hash, program_hash(&source), "the hash is still of the whole program");
+    }
+
+    #[test]
+    fn test_history_survives_a_save() {
+        let mut turtle = TurtleState::new();
+        turtle.forward(50.0);
+        let mut history = RunHistory::default();
+        history.push(RunRecord { seed: Some(7), ..RunRecord::new("a.logo", "FORWARD 50", &["done  ".to_string()], &turtle) });
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history").join("runs.json");
+        history.save(&path).unwrap();
+        let loaded = RunHistory::load(&path);
+        drop(dir);
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.get(0).unwrap().snapshot(), TranscriptSnapshot::capture(&["done  ".to_string()], &turtle));
+        assert!(RunHistory::load(&path).is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! Where the IDE and `--run` report runs: a JSON-lines file, and a dashboard URL
+//!
+//! The `report` setting in the user's own `settings.toml` names them; a
+//! project's `.timewarp.toml` cannot, or any lesson folder could send its
+//! runs anywhere:
+//!
+//! ```toml
+//! [report]
+//! file = "runs.jsonl"                      # relative to the project folder
+//! url = "https://dashboard.example/runs"   # needs the `net-report` feature
+//! token = "secret"                         # sent as `Authorization: Bearer secret`
+//! ```
+//!
+//! The file gets one [`RunEvent`] per line as the run goes: its start, each
+//! output line and diagnostic, and a [`RunSummary`] at the end. With Record
+//! output timing on, the summary also has a `timing` object: the millisecond
+//! each output line appeared at, and each answer with how long its prompt
+//! waited. The URL gets
+//! only the summary, POSTed as JSON on a thread of its own once the run has
+//! ended, and retried a few times; a report that cannot be delivered is noted
+//! in the Diagnostics log and never holds up or fails the run. Nothing is
+//! sent while the security policy turns network access off (Restricted mode).
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::interpreter::isolated::RunReport;
+use crate::interpreter::reporter::Reporter;
+use crate::interpreter::timing::RunTiming;
+use crate::interpreter::warnings::{Diagnostic, Severity};
+use crate::interpreter::{Permission, SecurityPolicy};
+use crate::languages::pilot::quiz::{RIGHT_VAR, WRONG_VAR};
+
+/// The `report` setting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportSettings {
+    /// JSON-lines file each run is appended to
+    pub file: Option<PathBuf>,
+    /// Dashboard each finished run is POSTed to
+    pub url: Option<String>,
+    /// Bearer token for `url`
+    pub token: Option<String>,
+}
+
+/// A `QZ:` quiz's result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub right: f64,
+    pub wrong: f64,
+}
+
+/// A finished run, as the dashboard receives it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub program: String,
+    /// RFC 3339 start time
+    pub started: String,
+    pub output: Vec<String>,
+    /// Fatal error that ended the run early
+    pub error: Option<String>,
+    /// It ended without a fatal error and was not left waiting for an answer
+    pub completed: bool,
+    /// `RIGHT` and `WRONG` after a quiz, else `None`
+    pub score: Option<Score>,
+    pub variables: BTreeMap<String, f64>,
+    pub string_variables: BTreeMap<String, String>,
+    pub lines_drawn: usize,
+    pub warnings: usize,
+    /// Left out unless the run recorded its timing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TimingSummary>,
+}
+
+/// When a run's output appeared and how long its prompts waited, in milliseconds from its start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingSummary {
+    /// One per line of `output`
+    pub output_ms: Vec<u64>,
+    pub inputs: Vec<InputWait>,
+}
+
+/// One answer and its prompt's wait
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputWait {
+    pub answer: String,
+    pub asked_ms: u64,
+    pub answered_ms: u64,
+    pub wait_ms: u64,
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+impl TimingSummary {
+    pub fn new(timing: &RunTiming) -> Self {
+        let inputs = timing.inputs.iter().map(|input| InputWait {
+            answer: input.answer.clone(),
+            asked_ms: millis(input.asked),
+            answered_ms: millis(input.answered),
+            wait_ms: millis(input.wait()),
+        });
+        Self { output_ms: timing.output.iter().copied().map(millis).collect(), inputs: inputs.collect() }
+    }
+}
+
+impl RunSummary {
+    pub fn new(program: &str, started: &str, report: &RunReport) -> Self {
+        let score = match (report.variables.get(RIGHT_VAR), report.variables.get(WRONG_VAR)) {
+            (Some(&right), Some(&wrong)) => Some(Score { right, wrong }),
+            _ => None,
+        };
+        Self {
+            program: program.to_string(),
+            started: started.to_string(),
+            output: report.output.clone(),
+            error: report.error.clone(),
+            completed: report.completed(),
+            score,
+            variables: report.variables.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            string_variables: report.string_variables.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            lines_drawn: report.lines_drawn,
+            warnings: report.diagnostics.len(),
+            timing: report.timing.as_ref().map(TimingSummary::new),
+        }
+    }
+}
+
+/// One line of a JSON-lines report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    RunStart { program: String, started: String },
+    Output { line: String },
+    Diagnostic { severity: String, kind: String, line: usize, message: String },
+    RunEnd(RunSummary),
+}
+
+impl RunEvent {
+    fn diagnostic(diagnostic: &Diagnostic) -> Self {
+        let severity = match diagnostic.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        RunEvent::Diagnostic {
+            severity: severity.to_string(),
+            kind: format!("{:?}", diagnostic.kind),
+            line: diagnostic.line,
+            message: diagnostic.message.clone(),
+        }
+    }
+}
+
+/// The local time as reports give it, RFC 3339 to the second
+pub fn now() -> String {
+    chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+/// Appends every event of a run to a file, one JSON object per line
+pub struct JsonLinesReporter {
+    path: PathBuf,
+    file: Option<File>,
+    program: String,
+    started: String,
+}
+
+impl JsonLinesReporter {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), file: None, program: String::new(), started: String::new() }
+    }
+
+    fn write(&mut self, event: &RunEvent) {
+        let Some(file) = self.file.as_mut() else { return };
+        let written = serde_json::to_string(event).map_err(std::io::Error::from).and_then(|line| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            tracing::warn!(path = %self.path.display(), error = %e, "run report not written");
+            // Once is enough to say so
+            self.file = None;
+        }
+    }
+}
+
+impl Reporter for JsonLinesReporter {
+    fn on_run_start(&mut self, program: &str) {
+        self.program = program.to_string();
+        self.started = now();
+        self.file = match std::fs::OpenOptions::new().create(true).append(true).open(&self.path) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                tracing::warn!(path = %self.path.display(), error = %e, "run report file not opened");
+                None
+            }
+        };
+        self.write(&RunEvent::RunStart { program: self.program.clone(), started: self.started.clone() });
+    }
+
+    fn on_output(&mut self, line: &str) {
+        self.write(&RunEvent::Output { line: line.to_string() });
+    }
+
+    fn on_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.write(&RunEvent::diagnostic(diagnostic));
+    }
+
+    fn on_run_end(&mut self, report: &RunReport) {
+        self.write(&RunEvent::RunEnd(RunSummary::new(&self.program, &self.started, report)));
+    }
+}
+
+/// POSTs each finished run's [`RunSummary`] to a dashboard
+#[cfg(feature = "net-report")]
+pub struct HttpReporter {
+    url: String,
+    token: Option<String>,
+    program: String,
+    started: String,
+}
+
+/// Attempts made to deliver a report, and the wait before the second
+#[cfg(feature = "net-report")]
+const HTTP_ATTEMPTS: u32 = 3;
+#[cfg(feature = "net-report")]
+const HTTP_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+#[cfg(feature = "net-report")]
+impl HttpReporter {
+    pub fn new(url: &str, token: Option<&str>) -> Self {
+        Self { url: url.to_string(), token: token.map(str::to_string), program: String::new(), started: String::new() }
+    }
+
+    /// POST `body`, trying again after 0.5 s and 1 s; the last error when every attempt failed
+    pub fn post(url: &str, token: Option<&str>, body: &str) -> anyhow::Result<()> {
+        let mut delay = HTTP_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            let mut request = ureq::post(url).timeout(std::time::Duration::from_secs(10)).set("Content-Type", "application/json");
+            if let Some(token) = token {
+                request = request.set("Authorization", &format!("Bearer {}", token));
+            }
+            match request.send_string(body) {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt >= HTTP_ATTEMPTS => return Err(e.into()),
+                Err(e) => tracing::debug!(url, attempt, error = %e, "run report failed, retrying"),
+            }
+            std::thread::sleep(delay);
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(feature = "net-report")]
+impl Reporter for HttpReporter {
+    fn on_run_start(&mut self, program: &str) {
+        self.program = program.to_string();
+        self.started = now();
+    }
+
+    fn on_run_end(&mut self, report: &RunReport) {
+        let summary = RunSummary::new(&self.program, &self.started, report);
+        let Ok(body) = serde_json::to_string(&summary) else { return };
+        let (url, token) = (self.url.clone(), self.token.clone());
+        // Fire and forget: the run (and the UI) never waits for the dashboard
+        std::thread::spawn(move || {
+            if let Err(e) = HttpReporter::post(&url, token.as_deref(), &body) {
+                tracing::warn!(url, error = %e, "run report not delivered");
+            }
+        });
+    }
+}
+
+/// The reporters `settings` asks for; a relative `file` is taken from `base`
+///
+/// A `url` is skipped (and the Diagnostics log says why) when `policy`
+/// refuses network access or this build has no `net-report` feature.
+pub fn from_settings(settings: &ReportSettings, policy: &SecurityPolicy, base: Option<&Path>) -> Vec<Box<dyn Reporter>> {
+    let mut reporters: Vec<Box<dyn Reporter>> = Vec::new();
+    if let Some(file) = &settings.file {
+        let path = match base {
+            Some(base) if file.is_relative() => base.join(file),
+            _ => file.clone(),
+        };
+        reporters.push(Box::new(JsonLinesReporter::new(path)));
+    }
+    if let Some(url) = &settings.url {
+        if !policy.allows(Permission::Network) {
+            tracing::warn!(url, "run report not sent: network access is off");
+        } else {
+            #[cfg(feature = "net-report")]
+            reporters.push(Box::new(HttpReporter::new(url, settings.token.as_deref())));
+            #[cfg(not(feature = "net-report"))]
+            tracing::warn!(url, "run report not sent: built without the net-report feature");
+        }
+    }
+    reporters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::interpreter::Interpreter;
+
+    fn reported_run(source: &str, answers: &[&str], reporters: Vec<Box<dyn Reporter>>) {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        let mut answers = answers.iter().map(|a| a.to_string()).collect::<Vec<_>>().into_iter();
+        interp.input_callback = Some(Box::new(move |_| answers.next().unwrap_or_default()));
+        interp.load_program(source).unwrap();
+        interp.start_report("quiz.pilot", reporters);
+        let error = interp.execute(&mut turtle).err().map(|e| e.to_string());
+        interp.finish_report(&turtle, error);
+    }
+
+    #[test]
+    fn test_json_lines_report_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runs.jsonl");
+        let quiz = "D:2+2?|4|\nD:3+3?|6|\nQZ:";
+        reported_run(quiz, &["4", "5"], vec![Box::new(JsonLinesReporter::new(&path))]);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<RunEvent> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert!(matches!(&events[0], RunEvent::RunStart { program, .. } if program == "quiz.pilot"));
+        assert!(events.iter().any(|e| matches!(e, RunEvent::Output { line } if line == "2+2?")));
+        let Some(RunEvent::RunEnd(summary)) = events.last() else { panic!("{:?}", events) };
+        assert_eq!(summary.score, Some(Score { right: 1.0, wrong: 1.0 }));
+        assert!(summary.completed && summary.error.is_none());
+        assert_eq!(summary.output.len(), events.iter().filter(|e| matches!(e, RunEvent::Output { .. })).count());
+
+        // Field names are the schema a dashboard reads
+        let last: serde_json::Value = serde_json::from_str(text.lines().last().unwrap()).unwrap();
+        let mut keys: Vec<&str> = last.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, [
+            "completed", "error", "event", "lines_drawn", "output", "program", "score", "started", "string_variables", "variables", "warnings",
+        ]);
+    }
+
+    #[test]
+    fn test_timing_is_reported_only_when_recorded() {
+        use crate::interpreter::timing::InputTiming;
+        let mut timing = RunTiming::default();
+        timing.output = vec![Duration::from_millis(5), Duration::from_millis(2_005)];
+        timing.inputs.push(InputTiming { answer: "4".into(), asked: Duration::from_millis(5), answered: Duration::from_millis(2_000) });
+        let report = RunReport { output: vec!["2+2?".into(), "Right!".into()], timing: Some(timing), ..RunReport::default() };
+        let summary = serde_json::to_value(RunSummary::new("quiz.pilot", "", &report)).unwrap();
+        assert_eq!(summary["timing"], serde_json::json!({
+            "output_ms": [5, 2005],
+            "inputs": [{ "answer": "4", "asked_ms": 5, "answered_ms": 2000, "wait_ms": 1995 }],
+        }));
+        let untimed = RunReport { timing: None, ..report };
+        assert!(serde_json::to_value(RunSummary::new("quiz.pilot", "", &untimed)).unwrap().get("timing").is_none());
+    }
+
+    #[test]
+    fn test_urls_respect_the_network_policy() {
+        let settings = ReportSettings { url: Some("http://127.0.0.1:9/runs".into()), ..ReportSettings::default() };
+        assert!(from_settings(&settings, &SecurityPolicy::restricted(), None).is_empty());
+        let expected = usize::from(cfg!(feature = "net-report"));
+        assert_eq!(from_settings(&settings, &SecurityPolicy::default(), None).len(), expected);
+    }
+
+    #[cfg(feature = "net-report")]
+    #[test]
+    fn test_http_report_is_posted_with_its_token() {
+        use std::io::{BufRead, BufReader, Read};
+        use std::net::TcpListener;
+
+        // A one-request HTTP server that hands back what it received
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/runs", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let (mut headers, mut length) = (Vec::new(), 0);
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_string();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                headers.push(line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader.get_mut().write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+            (headers, String::from_utf8(body).unwrap())
+        });
+
+        let summary = RunSummary::new("quiz.pilot", "2026-01-01T09:00:00+00:00", &RunReport::default());
+        HttpReporter::post(&url, Some("s3cret"), &serde_json::to_string(&summary).unwrap()).unwrap();
+        let (headers, body) = server.join().unwrap();
+        assert!(headers[0].starts_with("POST /runs "), "{:?}", headers);
+        assert!(headers.iter().any(|h| h == "Authorization: Bearer s3cret"), "{:?}", headers);
+        assert_eq!(serde_json::from_str::<RunSummary>(&body).unwrap(), summary);
+    }
+}
@@ -0,0 +1,84 @@
+//! Reading program files that may not be programs at all
+//!
+//! File ▸ Open, Find in Files, and `--open` can be pointed at anything: a
+//! multi-gigabyte download, a binary, a text file in another encoding. The
+//! size is checked before any of the file is read, and no more than the limit
+//! is ever read, so a huge (or endless) file fails at once instead of hanging
+//! the IDE. Bytes that are not UTF-8 become U+FFFD rather than an error, and
+//! [`OpenedText::lossy`] says so, for the editor's warning banner.
+
+use std::io::Read;
+use std::path::Path;
+
+use thiserror::Error;
+
+/// Largest file (in bytes) opened in the editor
+pub const MAX_OPEN_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum OpenError {
+    #[error("File is too large to open ({size} bytes, limit {limit})")]
+    TooLarge { size: u64, limit: u64 },
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Text of a file read by [`read_text`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenedText {
+    pub text: String,
+    /// Some bytes were not UTF-8 and were replaced with U+FFFD
+    pub lossy: bool,
+}
+
+/// Read `path` as text, refusing files over `limit` bytes
+pub fn read_text(path: &Path, limit: u64) -> Result<OpenedText, OpenError> {
+    let file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    if size > limit {
+        return Err(OpenError::TooLarge { size, limit });
+    }
+    // Devices and pipes report no size, and a file can grow after the check
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(limit + 1).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(OpenError::TooLarge { size: bytes.len() as u64, limit });
+    }
+    Ok(decode(bytes))
+}
+
+/// `bytes` as text, with invalid UTF-8 replaced
+pub fn decode(bytes: Vec<u8>) -> OpenedText {
+    match String::from_utf8(bytes) {
+        Ok(text) => OpenedText { text, lossy: false },
+        Err(e) => OpenedText { text: String::from_utf8_lossy(e.as_bytes()).into_owned(), lossy: true },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_utf8_is_replaced_and_flagged() {
+        assert_eq!(decode(b"10 PRINT \"HI\"".to_vec()), OpenedText { text: "10 PRINT \"HI\"".into(), lossy: false });
+        let latin1 = decode(b"T:Ol\xe1!".to_vec());
+        assert_eq!(latin1.text, "T:Ol\u{FFFD}!");
+        assert!(latin1.lossy);
+        // Text that merely contains U+FFFD was not changed by opening it
+        assert!(!decode("T:\u{FFFD}".as_bytes().to_vec()).lossy);
+    }
+
+    #[test]
+    fn test_size_is_checked_before_reading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("program.bas");
+        std::fs::write(&path, b"10 PRINT 1\n20 END\n").unwrap();
+        let opened = read_text(&path, 18);
+        let too_large = read_text(&path, 17);
+        drop(dir);
+        assert_eq!(opened.unwrap().text, "10 PRINT 1\n20 END\n");
+        assert!(matches!(too_large, Err(OpenError::TooLarge { size: 18, limit: 17 })));
+        assert!(matches!(read_text(&path, MAX_OPEN_BYTES), Err(OpenError::Io(_))));
+    }
+}
@@ -0,0 +1,140 @@
+//! Text screen export: PNG, SVG, or HTML, chosen by the file's extension
+//!
+//! File ▸ Export ▸ Text Screen and `--export-screen` save the text-mode
+//! screen as the Output tab shows it ([`text_screen`]): the theme's
+//! background, each row in its color, warnings left out when Settings hides
+//! them. A PNG is the on-screen image at the chosen scale. SVG places every
+//! character on its cell, and HTML is a `<pre>` grid, so both stay text that
+//! can be copied and keep the layout `LOCATE` gave it.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::graphics::font::{CELL_HEIGHT, GLYPH_HEIGHT};
+use crate::graphics::text_screen::{cell_origin, ScreenLine, TextScreen, MARGIN, SCREEN_SCALE};
+use crate::graphics::{color32, rgba};
+use crate::interpreter::warnings::{is_error_line, is_warning_line};
+use crate::interpreter::{Interpreter, ScreenMode};
+use crate::utils::html_export::{escape, hex, ThemeColors};
+
+/// Scales offered for a PNG export
+pub const EXPORT_SCALES: [u32; 4] = [1, 2, 3, 4];
+
+/// The text screen in `colors`, or `None` outside text mode
+pub fn text_screen(interp: &Interpreter, colors: &ThemeColors, show_warnings: bool) -> Option<TextScreen> {
+    let ScreenMode::Text { cols, rows } = interp.screen_mode else { return None };
+    let lines = interp
+        .text_lines
+        .iter()
+        .map(|line| {
+            let (text, color) = if is_warning_line(line) {
+                (if show_warnings { line.clone() } else { String::new() }, colors.warning)
+            } else if is_error_line(line) {
+                (line.clone(), colors.error)
+            } else {
+                (line.clone(), colors.text)
+            };
+            ScreenLine { text, color: rgba(color) }
+        })
+        .collect();
+    Some(TextScreen { cols, rows, lines, background: rgba(colors.background) })
+}
+
+/// The screen as SVG text, each character at its cell, `scale` pixels per font pixel
+pub fn screen_svg(screen: &TextScreen, scale: u32) -> String {
+    let (width, height) = screen.size(scale);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        width, height, width, height
+    );
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", width, height, hex(color32(screen.background))));
+    for (row, line) in screen.lines.iter().take(screen.rows as usize).enumerate() {
+        let text: Vec<char> = line.text.chars().take(screen.cols as usize).collect();
+        if text.iter().all(|c| c.is_whitespace()) {
+            continue;
+        }
+        // One x per character pins each to its cell whatever the font's advance
+        let xs: Vec<String> = (0..text.len() as u32).map(|col| (cell_origin(row as u32, col).0 * scale).to_string()).collect();
+        let baseline = (cell_origin(row as u32, 0).1 + 1 + GLYPH_HEIGHT) * scale;
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" fill=\"{}\" xml:space=\"preserve\">{}</text>\n",
+            xs.join(" "),
+            baseline,
+            CELL_HEIGHT * scale,
+            hex(color32(line.color)),
+            escape(&text.iter().collect::<String>())
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The screen as an HTML page: a `<pre>` of `cols` x `rows` characters on the background
+pub fn screen_html(screen: &TextScreen, title: &str) -> String {
+    let mut rows = Vec::new();
+    for row in 0..screen.rows as usize {
+        let row_html = match screen.lines.get(row) {
+            Some(line) => {
+                let text: String = line.text.chars().take(screen.cols as usize).collect();
+                format!("<span style=\"color:{}\">{}</span>", hex(color32(line.color)), escape(&text))
+            }
+            None => String::new(),
+        };
+        rows.push(row_html);
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body style=\"margin:0\">\n\
+         <pre style=\"display:inline-block;margin:0;padding:{}px;background:{};font:16px/{}px monospace;width:{}ch\">{}</pre>\n</body>\n</html>\n",
+        escape(title),
+        MARGIN * SCREEN_SCALE,
+        hex(color32(screen.background)),
+        CELL_HEIGHT * SCREEN_SCALE,
+        screen.cols,
+        rows.join("\n")
+    )
+}
+
+/// Save `screen` to `path` as PNG, SVG, or HTML by its extension (PNG when it has none of those)
+pub fn export_text_screen(screen: &TextScreen, path: &Path, scale: u32, title: &str) -> Result<()> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match extension.as_str() {
+        "svg" => std::fs::write(path, screen_svg(screen, scale))?,
+        "html" | "htm" => std::fs::write(path, screen_html(screen, title))?,
+        _ => screen.save_png(&path.to_string_lossy(), scale)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::TurtleState;
+    use crate::ui::themes::Theme;
+
+    fn screen_after(program: &str) -> TextScreen {
+        let mut interp = Interpreter::new();
+        interp.load_program(program).unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+        text_screen(&interp, &Theme::default().colors(), true).unwrap()
+    }
+
+    #[test]
+    fn test_svg_and_html_keep_the_grid() {
+        let screen = screen_after("10 SCREEN 0, 10, 3\n20 CLS\n30 LOCATE 2, 4\n40 PRINT \"<*>\"");
+        let svg = screen_svg(&screen, 2);
+        // Row 2, columns 4 to 6: x from the margin, 12 pixels a cell at scale 2
+        assert!(svg.contains("<text x=\"8 20 32 44 56 68\" y=\"44\""), "{}", svg);
+        assert!(svg.contains(">   &lt;*&gt;</text>"), "{}", svg);
+        let html = screen_html(&screen, "Art");
+        assert!(html.contains("width:10ch") && html.contains("\n<span style=\"color:") && html.contains("   &lt;*&gt;</span>\n"), "{}", html);
+    }
+
+    #[test]
+    fn test_no_screen_outside_text_mode() {
+        let mut interp = Interpreter::new();
+        interp.load_program("10 PRINT \"hi\"").unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap();
+        assert_eq!(text_screen(&interp, &Theme::default().colors(), true), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Shareable program links
+//!
+//! A program is packed as `timewarp://code/<payload>?lang=<language>` where the
+//! payload is a format version byte followed by the deflated UTF-8 source,
+//! base64url-encoded without padding. A program with an `@title` header also
+//! gets a percent-encoded `&title=` so the link is recognisable when pasted;
+//! decoding ignores it. Decoding checks sizes before and after inflating so a
+//! hostile link cannot exhaust memory.
+
+use std::io::{Read, Write};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use thiserror::Error;
+
+use crate::interpreter::metadata::parse_metadata;
+use crate::languages::Language;
+
+pub const SHARE_PREFIX: &str = "timewarp://code/";
+/// Bumped whenever the payload layout changes
+pub const SHARE_FORMAT_VERSION: u8 = 1;
+/// Largest program (in bytes) that can be shared or imported
+pub const MAX_SHARED_SOURCE_BYTES: usize = 64 * 1024;
+/// Largest encoded payload accepted, checked before decoding
+pub const MAX_SHARE_PAYLOAD_CHARS: usize = 96 * 1024;
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ShareError {
+    #[error("Not a Time Warp share link (expected it to start with {SHARE_PREFIX})")]
+    NotAShareLink,
+    #[error("Share link is empty")]
+    Empty,
+    #[error("Share link is too large ({0} characters, limit {MAX_SHARE_PAYLOAD_CHARS})")]
+    PayloadTooLarge(usize),
+    #[error("Program is too large to share ({0} bytes, limit {MAX_SHARED_SOURCE_BYTES})")]
+    SourceTooLarge(usize),
+    #[error("Share link is damaged (invalid characters)")]
+    InvalidEncoding,
+    #[error("Share link was made by a newer Time Warp (format {0})")]
+    UnsupportedVersion(u8),
+    #[error("Share link is damaged (could not decompress)")]
+    Corrupt,
+    #[error("Shared program is not valid UTF-8 text")]
+    InvalidText,
+}
+
+/// A program decoded from a share link
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedProgram {
+    pub source: String,
+    pub language: Language,
+}
+
+impl SharedProgram {
+    /// File name extension for opening the program in a new tab
+    pub fn extension(&self) -> &'static str {
+        self.language.extension()
+    }
+}
+
+/// Build a share link for `source`
+pub fn encode_share(source: &str, language: Language) -> Result<String, ShareError> {
+    if source.len() > MAX_SHARED_SOURCE_BYTES {
+        return Err(ShareError::SourceTooLarge(source.len()));
+    }
+    let mut encoder = DeflateEncoder::new(vec![SHARE_FORMAT_VERSION], Compression::best());
+    // Writing to a Vec cannot fail
+    encoder.write_all(source.as_bytes()).map_err(|_| ShareError::Corrupt)?;
+    let bytes = encoder.finish().map_err(|_| ShareError::Corrupt)?;
+    let mut link = format!(
+        "{}{}?lang={}",
+        SHARE_PREFIX,
+        base64url_encode(&bytes),
+        language.name().to_lowercase()
+    );
+    if let Some(title) = parse_metadata(source).title.filter(|t| !t.is_empty()) {
+        link.push_str("&title=");
+        link.push_str(&percent_encode(&title));
+    }
+    Ok(link)
+}
+
+/// Parse a share link back into a program
+///
+/// Surrounding whitespace is ignored; a missing `lang` defaults to PILOT.
+pub fn decode_share(link: &str) -> Result<SharedProgram, ShareError> {
+    let rest = link.trim().strip_prefix(SHARE_PREFIX).ok_or(ShareError::NotAShareLink)?;
+    let (payload, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if payload.is_empty() {
+        return Err(ShareError::Empty);
+    }
+    if payload.len() > MAX_SHARE_PAYLOAD_CHARS {
+        return Err(ShareError::PayloadTooLarge(payload.len()));
+    }
+    let language = query
+        .split('&')
+        .find_map(|kv| kv.strip_prefix("lang="))
+        .map(Language::from_extension)
+        .unwrap_or(Language::Pilot);
+
+    let bytes = base64url_decode(payload).ok_or(ShareError::InvalidEncoding)?;
+    let (&version, compressed) = bytes.split_first().ok_or(ShareError::Empty)?;
+    if version != SHARE_FORMAT_VERSION {
+        return Err(ShareError::UnsupportedVersion(version));
+    }
+
+    // Read one byte past the limit to detect oversized (or bomb) payloads
+    let mut inflated = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(MAX_SHARED_SOURCE_BYTES as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| ShareError::Corrupt)?;
+    if inflated.len() > MAX_SHARED_SOURCE_BYTES {
+        return Err(ShareError::SourceTooLarge(inflated.len()));
+    }
+    let source = String::from_utf8(inflated).map_err(|_| ShareError::InvalidText)?;
+    Ok(SharedProgram { source, language })
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn base64url_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        // 1 input byte -> 2 chars, 2 -> 3, 3 -> 4 (no padding)
+        for i in 0..=chunk.len() {
+            out.push(BASE64URL[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64url_decode(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let v = BASE64URL.iter().position(|&b| b == c)? as u32;
+            n |= v << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_unicode_and_long_lines() {
+        let long_line = format!("T:{}", "ab→ü ".repeat(2000));
+        let programs = [
+            ("T:Hello, 世界 👋\nA:NAME\nT:Hi *NAME*", Language::Pilot),
+            ("10 PRINT \"Grüße\"\n20 END", Language::Basic),
+            ("REPEAT 4 [FORWARD 50 RIGHT 90]", Language::Logo),
+            (long_line.as_str(), Language::TempleCode),
+            ("", Language::Logo),
+        ];
+        for (source, language) in programs {
+            let link = encode_share(source, language).unwrap();
+            assert!(link.starts_with(SHARE_PREFIX));
+            let decoded = decode_share(&format!("  {}\n", link)).unwrap();
+            assert_eq!(decoded, SharedProgram { source: source.to_string(), language });
+        }
+    }
+
+    #[test]
+    fn test_link_shape() {
+        let link = encode_share("FD 10", Language::Logo).unwrap();
+        assert!(link.ends_with("?lang=logo"));
+        let payload = &link[SHARE_PREFIX.len()..link.find('?').unwrap()];
+        assert!(payload.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(base64url_decode(payload).unwrap()[0], SHARE_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn test_link_carries_title() {
+        let source = "REM @title Bouncing Ball & Co\n10 PRINT 1";
+        let link = encode_share(source, Language::Basic).unwrap();
+        assert!(link.ends_with("?lang=basic&title=Bouncing%20Ball%20%26%20Co"), "{}", link);
+        assert_eq!(decode_share(&link).unwrap(), SharedProgram { source: source.to_string(), language: Language::Basic });
+    }
+
+    #[test]
+    fn test_base64url_lengths() {
+        for len in 0..8 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 250) as u8).collect();
+            assert_eq!(base64url_decode(&base64url_encode(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_malformed_links_fail_gracefully() {
+        assert_eq!(decode_share("https://example.com"), Err(ShareError::NotAShareLink));
+        assert_eq!(decode_share("timewarp://code/?lang=logo"), Err(ShareError::Empty));
+        assert_eq!(decode_share("timewarp://code/abc$def"), Err(ShareError::InvalidEncoding));
+        assert_eq!(decode_share("timewarp://code/AQID!"), Err(ShareError::InvalidEncoding));
+        // Valid base64 but not deflate data
+        assert_eq!(decode_share(&format!("{}{}", SHARE_PREFIX, base64url_encode(&[1, 0xFF, 0xFF, 0xFF]))), Err(ShareError::Corrupt));
+        let future = format!("{}{}", SHARE_PREFIX, base64url_encode(&[9, 1, 2]));
+        assert_eq!(decode_share(&future), Err(ShareError::UnsupportedVersion(9)));
+    }
+
+    #[test]
+    fn test_size_limits() {
+        let huge = "x".repeat(MAX_SHARED_SOURCE_BYTES + 1);
+        assert_eq!(encode_share(&huge, Language::Pilot), Err(ShareError::SourceTooLarge(huge.len())));
+
+        // Highly compressible bomb: small link, oversized program
+        let mut encoder = DeflateEncoder::new(vec![SHARE_FORMAT_VERSION], Compression::best());
+        encoder.write_all(huge.as_bytes()).unwrap();
+        let link = format!("{}{}", SHARE_PREFIX, base64url_encode(&encoder.finish().unwrap()));
+        assert!(matches!(decode_share(&link), Err(ShareError::SourceTooLarge(_))));
+
+        let long = format!("{}{}", SHARE_PREFIX, "A".repeat(MAX_SHARE_PAYLOAD_CHARS + 4));
+        assert!(matches!(decode_share(&long), Err(ShareError::PayloadTooLarge(_))));
+    }
+}
@@ -0,0 +1,133 @@
+//! Assessment submissions: a student's work packed into one `.twpak`
+//!
+//! Every open buffer goes in as it stands, saved or not, with the current
+//! tab as the bundle's entry, so opening a submission runs what the student
+//! was working on. Beside the programs, under `submission/`, go the last
+//! run's output (`transcript.txt`) and its [`RunSummary`] (`report.json`),
+//! and `submission.json`: who submitted, for which lesson, when, the time
+//! used, and the lesson's score variables as the last run left them.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::run_reporters::RunSummary;
+use crate::utils::twpak::{pack, PakError, PAK_EXTENSION};
+use crate::utils::zip_archive::ZipEntry;
+
+/// Folder in the bundle holding everything but the programs
+pub const SUBMISSION_DIR: &str = "submission";
+
+/// Contents of `submission/submission.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmissionInfo {
+    /// The identifier given at Start
+    pub student: String,
+    /// The project folder's name
+    pub lesson: String,
+    /// RFC 3339 submission time
+    pub submitted: String,
+    pub seconds_used: u64,
+    /// Submitted after the time ran out
+    pub time_up: bool,
+    /// The score variables the last run set; ones it never set are left out
+    pub scores: BTreeMap<String, f64>,
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Vec<u8>, PakError> {
+    serde_json::to_vec_pretty(value).map_err(|e| PakError::BadManifest(e.to_string()))
+}
+
+/// A student's work, ready to pack
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub info: SubmissionInfo,
+    /// Every open buffer as its tab name and text, the current tab first
+    pub buffers: Vec<(String, String)>,
+    /// The last run, when there was one
+    pub run: Option<RunSummary>,
+}
+
+impl Submission {
+    /// The values of `names` among a run's variables
+    pub fn scores(run: Option<&RunSummary>, names: &[String]) -> BTreeMap<String, f64> {
+        let Some(run) = run else { return BTreeMap::new() };
+        names.iter().filter_map(|name| Some((name.clone(), *run.variables.get(name)?))).collect()
+    }
+
+    /// File name for the bundle: `<student>-<lesson>.twpak`, with anything but letters, digits, `-`, and `_` made `_`
+    pub fn file_name(&self) -> String {
+        let clean = |text: &str| -> String {
+            text.trim().chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+        };
+        format!("{}-{}.{}", clean(&self.info.student), clean(&self.info.lesson), PAK_EXTENSION)
+    }
+
+    /// The bundle's bytes
+    pub fn pack(&self) -> Result<Vec<u8>, PakError> {
+        let mut entries: Vec<ZipEntry> =
+            self.buffers.iter().map(|(name, text)| ZipEntry { name: name.clone(), data: text.as_bytes().to_vec() }).collect();
+        let file = |name: &str, data: Vec<u8>| ZipEntry { name: format!("{}/{}", SUBMISSION_DIR, name), data };
+        entries.push(file("submission.json", json(&self.info)?));
+        if let Some(run) = &self.run {
+            let mut transcript = run.output.join("\n");
+            transcript.push('\n');
+            entries.push(file("transcript.txt", transcript.into_bytes()));
+            entries.push(file("report.json", json(run)?));
+        }
+        pack(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::isolated::RunReport;
+    use crate::utils::twpak::read_pak;
+
+    fn submission() -> Submission {
+        let report = RunReport {
+            output: vec!["Score: 4 out of 5".to_string()],
+            variables: [("RIGHT".to_string(), 4.0), ("WRONG".to_string(), 1.0)].into_iter().collect(),
+            ..RunReport::default()
+        };
+        let run = RunSummary::new("quiz.pilot", "2026-10-16T09:00:00+00:00", &report);
+        let names = ["RIGHT".to_string(), "SCORE".to_string()];
+        let info = SubmissionInfo {
+            student: "Ada L.".to_string(),
+            lesson: "week 3".to_string(),
+            submitted: "2026-10-16T09:20:00+00:00".to_string(),
+            seconds_used: 1200,
+            time_up: true,
+            scores: Submission::scores(Some(&run), &names),
+        };
+        let buffers = vec![("quiz.pilot".to_string(), "T:Hello".to_string()), ("notes.bas".to_string(), "10 PRINT 1".to_string())];
+        Submission { info, buffers, run: Some(run) }
+    }
+
+    #[test]
+    fn test_a_submission_is_a_bundle_of_the_work_and_its_run() {
+        let submission = submission();
+        assert_eq!(submission.info.scores, [("RIGHT".to_string(), 4.0)].into_iter().collect(), "SCORE was never set");
+        assert_eq!(submission.file_name(), "Ada_L_-week_3.twpak");
+
+        let (manifest, entries) = read_pak(&submission.pack().unwrap()).unwrap();
+        assert_eq!((manifest.entry.as_str(), manifest.language.as_str()), ("quiz.pilot", "pilot"));
+        let file = |name: &str| String::from_utf8(entries.iter().find(|e| e.name == name).unwrap().data.clone()).unwrap();
+        assert_eq!(file("notes.bas"), "10 PRINT 1");
+        assert_eq!(file("submission/transcript.txt"), "Score: 4 out of 5\n");
+        let info: SubmissionInfo = serde_json::from_str(&file("submission/submission.json")).unwrap();
+        assert_eq!(info, submission.info);
+        let report: RunSummary = serde_json::from_str(&file("submission/report.json")).unwrap();
+        assert_eq!(report.score.map(|s| s.right), Some(4.0));
+    }
+
+    #[test]
+    fn test_a_submission_without_a_run_has_no_transcript() {
+        let submission = Submission { run: None, ..submission() };
+        assert!(Submission::scores(None, &["RIGHT".to_string()]).is_empty());
+        let (_, entries) = read_pak(&submission.pack().unwrap()).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["quiz.pilot", "notes.bas", "submission/submission.json"]);
+    }
+}
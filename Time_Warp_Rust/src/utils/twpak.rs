@@ -0,0 +1,302 @@
+//! `.twpak` project bundles (File → Package Project, opening a `.twpak`, `--run`)
+//!
+//! A bundle is a zip archive whose `manifest.json` names the entry program,
+//! its language, every included file, and the program's header metadata.
+//! Included files keep their paths relative to the project folder, so
+//! `CALLFILE "lib/shapes.logo"` works the same after unpacking. Opening a
+//! bundle extracts it to a fresh temporary folder that becomes the sandbox
+//! workspace for the run, and is removed when its tab is closed. Only the
+//! files the manifest lists are extracted, and never a `.timewarp.toml`: it
+//! would become the project file of a folder the IDE trusts.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::project::PROJECT_FILE;
+use crate::interpreter::metadata::parse_metadata;
+use crate::interpreter::subprogram::file_argument;
+use crate::languages::Language;
+use crate::utils::zip_archive::{read_zip, write_zip, ZipEntry, ZipError};
+
+pub const PAK_EXTENSION: &str = "twpak";
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// Bumped whenever the manifest layout changes
+pub const PAK_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum PakError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a valid .twpak bundle: {0}")]
+    Zip(#[from] ZipError),
+    #[error("Bundle has no {MANIFEST_NAME}")]
+    MissingManifest,
+    #[error("Bundle manifest is invalid: {0}")]
+    BadManifest(String),
+    #[error("Bundle was made by a newer Time Warp (format {0}, this version reads {PAK_FORMAT_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("Bundle path '{0}' is not allowed (it must stay inside the project folder)")]
+    UnsafePath(String),
+    #[error("Bundle lists '{0}' but does not contain it")]
+    MissingFile(String),
+}
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PakManifest {
+    pub version: u32,
+    /// Program opened and run first (a path within the bundle)
+    pub entry: String,
+    /// Language name of the entry program (`pilot`, `basic`, `logo`, `templecode`)
+    pub language: String,
+    /// Every file in the bundle, including the entry
+    pub files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A bundle unpacked to disk
+#[derive(Debug)]
+pub struct ExtractedPak {
+    /// Folder holding the files (the sandbox workspace)
+    pub root: PathBuf,
+    pub manifest: PakManifest,
+    pub entry_path: PathBuf,
+    /// Owns `root` when it is a temporary folder, which goes when this is dropped
+    pub temp_dir: Option<tempfile::TempDir>,
+}
+
+/// Check that a bundle path is relative and cannot leave the project folder
+pub fn safe_relative_path(name: &str) -> Result<PathBuf, PakError> {
+    let unsafe_path = || PakError::UnsafePath(name.to_string());
+    if name.is_empty() || name.contains('\\') || name.contains(':') || name.starts_with('/') {
+        return Err(unsafe_path());
+    }
+    let path = PathBuf::from(name);
+    if path.components().all(|c| matches!(c, Component::Normal(_))) {
+        Ok(path)
+    } else {
+        Err(unsafe_path())
+    }
+}
+
+/// Files a program loads by name: `CALLFILE`, `CHAIN`, `MERGE`, and `R:LOAD` arguments
+pub fn referenced_files(source: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in source.lines() {
+        let line = line.trim().trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let name = match keyword.to_uppercase().as_str() {
+            "CALLFILE" | "CHAIN" | "MERGE" => file_argument(rest),
+            "R:LOAD" => rest.split_whitespace().next().unwrap_or(""),
+            _ => continue,
+        };
+        if !name.is_empty() && !files.iter().any(|f| f == name) {
+            files.push(name.to_string());
+        }
+    }
+    files
+}
+
+/// The entry plus every file it references, transitively, that exists under `root`
+pub fn collect_project(root: &Path, entry: &str) -> Vec<String> {
+    let mut files = vec![entry.to_string()];
+    let mut next = 0;
+    while next < files.len() {
+        let source = std::fs::read_to_string(root.join(&files[next])).unwrap_or_default();
+        next += 1;
+        for name in referenced_files(&source) {
+            if safe_relative_path(&name).is_ok() && root.join(&name).is_file() && !files.contains(&name) {
+                files.push(name);
+            }
+        }
+    }
+    files
+}
+
+fn language_name(entry: &str, source: &str) -> String {
+    let language = parse_metadata(source).language.unwrap_or_else(|| {
+        Language::from_extension(Path::new(entry).extension().and_then(|e| e.to_str()).unwrap_or(""))
+    });
+    language.name().to_lowercase()
+}
+
+/// Pack `entry` and `files` (paths relative to `root`) into bundle bytes
+pub fn build_pak(root: &Path, entry: &str, files: &[String]) -> Result<Vec<u8>, PakError> {
+    let mut names = vec![entry.to_string()];
+    names.extend(files.iter().filter(|f| *f != entry).cloned());
+
+    let mut entries = Vec::with_capacity(names.len() + 1);
+    for name in &names {
+        safe_relative_path(name)?;
+        if name == MANIFEST_NAME {
+            return Err(PakError::UnsafePath(name.clone()));
+        }
+        let data = std::fs::read(root.join(name))
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", name, e)))?;
+        entries.push(ZipEntry { name: name.clone(), data });
+    }
+    pack(entries)
+}
+
+/// Bundle bytes holding `entries`, the first of them the entry program
+pub fn pack(mut entries: Vec<ZipEntry>) -> Result<Vec<u8>, PakError> {
+    let Some(entry) = entries.first() else { return Err(PakError::BadManifest("no entry program".to_string())) };
+    for name in entries.iter().map(|e| &e.name) {
+        safe_relative_path(name)?;
+        if name == MANIFEST_NAME {
+            return Err(PakError::UnsafePath(name.clone()));
+        }
+    }
+    let source = String::from_utf8_lossy(&entry.data).into_owned();
+    let metadata = parse_metadata(&source);
+    let manifest = PakManifest {
+        version: PAK_FORMAT_VERSION,
+        entry: entry.name.clone(),
+        language: language_name(&entry.name, &source),
+        files: entries.iter().map(|e| e.name.clone()).collect(),
+        title: metadata.title,
+        author: metadata.author,
+        description: metadata.description,
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| PakError::BadManifest(e.to_string()))?;
+    entries.insert(0, ZipEntry { name: MANIFEST_NAME.to_string(), data: json });
+    Ok(write_zip(&entries)?)
+}
+
+/// Read and validate bundle bytes: a supported manifest, safe paths, nothing missing
+pub fn read_pak(bytes: &[u8]) -> Result<(PakManifest, Vec<ZipEntry>), PakError> {
+    let mut entries = read_zip(bytes)?;
+    let manifest_index = entries.iter().position(|e| e.name == MANIFEST_NAME).ok_or(PakError::MissingManifest)?;
+    let manifest_entry = entries.remove(manifest_index);
+    let manifest: PakManifest =
+        serde_json::from_slice(&manifest_entry.data).map_err(|e| PakError::BadManifest(e.to_string()))?;
+    if manifest.version == 0 {
+        return Err(PakError::BadManifest("version must be at least 1".to_string()));
+    }
+    if manifest.version > PAK_FORMAT_VERSION {
+        return Err(PakError::UnsupportedVersion(manifest.version));
+    }
+    for entry in &entries {
+        safe_relative_path(&entry.name)?;
+    }
+    safe_relative_path(&manifest.entry)?;
+    for name in manifest.files.iter().chain(std::iter::once(&manifest.entry)) {
+        if !entries.iter().any(|e| e.name == *name) {
+            return Err(PakError::MissingFile(name.clone()));
+        }
+    }
+    Ok((manifest, entries))
+}
+
+/// Whether `name` is a project settings file in some folder of the bundle
+fn is_project_file(name: &str) -> bool {
+    Path::new(name).file_name().is_some_and(|file| file == PROJECT_FILE)
+}
+
+/// Unpack bundle bytes into `dest` (created if needed)
+///
+/// Entries the manifest does not list, and any `.timewarp.toml`, are left out.
+pub fn extract_pak(bytes: &[u8], dest: &Path) -> Result<ExtractedPak, PakError> {
+    let (manifest, entries) = read_pak(bytes)?;
+    if is_project_file(&manifest.entry) {
+        return Err(PakError::UnsafePath(manifest.entry));
+    }
+    for entry in &entries {
+        let listed = entry.name == manifest.entry || manifest.files.contains(&entry.name);
+        if !listed || is_project_file(&entry.name) {
+            tracing::warn!("Left '{}' out of the unpacked bundle", entry.name);
+            continue;
+        }
+        let path = dest.join(safe_relative_path(&entry.name)?);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &entry.data)?;
+    }
+    let entry_path = dest.join(&manifest.entry);
+    Ok(ExtractedPak { root: dest.to_path_buf(), manifest, entry_path, temp_dir: None })
+}
+
+/// Unpack a `.twpak` file into a new, uniquely named folder under the system temp directory
+///
+/// The folder is removed when the returned bundle's `temp_dir` is dropped.
+pub fn extract_to_temp(pak: &Path) -> Result<ExtractedPak, PakError> {
+    let stem = pak.file_stem().and_then(|s| s.to_str()).unwrap_or("bundle");
+    let dir = tempfile::Builder::new().prefix(&format!("time_warp_pak_{}_", stem)).tempdir()?;
+    let bytes = std::fs::read(pak)?;
+    let extracted = extract_pak(&bytes, dir.path())?;
+    Ok(ExtractedPak { temp_dir: Some(dir), ..extracted })
+}
+
+pub fn is_pak_path(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case(PAK_EXTENSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert!(safe_relative_path("lesson.bas").is_ok());
+        assert!(safe_relative_path("lib/shapes.logo").is_ok());
+        for bad in ["", "/etc/passwd", "../up.txt", "lib/../../x", "C:/x", "a\\b", "./a"] {
+            assert!(matches!(safe_relative_path(bad), Err(PakError::UnsafePath(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_referenced_files() {
+        let source = "10 CALLFILE \"lib/shapes.logo\"\n20 CHAIN \"menu.bas\", ALL\nR:LOAD scores.txt\ncallfile lib/shapes.logo\nT:CHAIN\n5 MERGE \"lib.bas\"";
+        assert_eq!(referenced_files(source), vec!["lib/shapes.logo", "menu.bas", "scores.txt", "lib.bas"]);
+    }
+
+    #[test]
+    fn test_manifest_is_validated() {
+        let pak = |manifest: &str, files: &[&str]| {
+            let mut entries = vec![ZipEntry { name: MANIFEST_NAME.into(), data: manifest.as_bytes().to_vec() }];
+            entries.extend(files.iter().map(|f| ZipEntry { name: f.to_string(), data: b"T:hi".to_vec() }));
+            write_zip(&entries).unwrap()
+        };
+        let manifest = |version: u32, entry: &str| {
+            format!(r#"{{"version":{},"entry":"{}","language":"pilot","files":["{}"]}}"#, version, entry, entry)
+        };
+
+        assert!(read_pak(&pak(&manifest(1, "a.pilot"), &["a.pilot"])).is_ok());
+        assert!(matches!(read_pak(&pak(&manifest(2, "a.pilot"), &["a.pilot"])), Err(PakError::UnsupportedVersion(2))));
+        assert!(matches!(read_pak(&pak(&manifest(1, "b.pilot"), &["a.pilot"])), Err(PakError::MissingFile(_))));
+        assert!(matches!(read_pak(&pak(&manifest(1, "a.pilot"), &["a.pilot", "../evil.sh"])), Err(PakError::UnsafePath(_))));
+        assert!(matches!(read_pak(&pak("{}", &["a.pilot"])), Err(PakError::BadManifest(_))));
+        assert!(matches!(read_pak(&write_zip(&[]).unwrap()), Err(PakError::MissingManifest)));
+    }
+
+    #[test]
+    fn test_only_listed_programs_are_unpacked() {
+        let manifest = r#"{"version":1,"entry":"a.pilot","language":"pilot","files":["a.pilot","lib/.timewarp.toml"]}"#;
+        let entries: Vec<ZipEntry> = [(MANIFEST_NAME, manifest), ("a.pilot", "T:hi"), ("lib/.timewarp.toml", "allow_shell = true"), ("run.sh", "rm -rf ~")]
+            .iter()
+            .map(|(name, text)| ZipEntry { name: name.to_string(), data: text.as_bytes().to_vec() })
+            .collect();
+        let dir = tempfile::tempdir().unwrap();
+        let pak = extract_pak(&write_zip(&entries).unwrap(), dir.path()).unwrap();
+        assert!(pak.entry_path.is_file());
+        assert!(!dir.path().join("run.sh").exists(), "not in the manifest");
+        assert!(!dir.path().join("lib/.timewarp.toml").exists(), "never a project file");
+
+        let path = dir.path().join("lesson.twpak");
+        std::fs::write(&path, pack(vec![ZipEntry { name: "a.pilot".into(), data: b"T:hi".to_vec() }]).unwrap()).unwrap();
+        let opened = extract_to_temp(&path).unwrap();
+        let root = opened.root.clone();
+        assert!(root.join("a.pilot").is_file());
+        assert_ne!(root, extract_to_temp(&path).unwrap().root, "each open gets its own folder");
+        drop(opened);
+        assert!(!root.exists(), "removed with the bundle");
+    }
+}
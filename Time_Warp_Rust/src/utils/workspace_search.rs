@@ -0,0 +1,438 @@
+//! Find/replace across open buffers and project files
+//!
+//! The engine is UI-free: [`search_workspace`] walks the open buffers and then
+//! the program files under the project root, handing each file's matches to a
+//! callback as soon as they are found (the Find in Files panel runs it on a
+//! worker thread and streams results). An open buffer shadows the file it was
+//! opened from, matched by canonical path, so unsaved edits are what gets
+//! searched; a file elsewhere that happens to share its name is still searched.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use regex::{NoExpand, Regex, RegexBuilder};
+
+/// Extensions of files searched under the project root
+pub const PROGRAM_EXTENSIONS: &[&str] = &["pilot", "pil", "bas", "basic", "logo", "lgo", "tc", "temple", "templecode"];
+/// Files larger than this are skipped
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Stop walking after this many candidate files
+const MAX_FILES: usize = 5_000;
+/// Preview lines are cut to this many characters
+const PREVIEW_CHARS: usize = 160;
+
+/// An open editor buffer to search
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenBuffer {
+    /// Tab name
+    pub name: String,
+    /// Canonical path of the file it was opened from or saved to; `None` for an unsaved tab
+    pub path: Option<PathBuf>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchOptions {
+    pub query: String,
+    pub case_sensitive: bool,
+    pub regex: bool,
+    /// Comma-separated file name patterns (`*` and `?`); empty matches all
+    pub file_glob: String,
+}
+
+/// Where a searched text came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchSource {
+    /// Open editor buffer, by name
+    Buffer(String),
+    /// File on disk that is not open
+    File(PathBuf),
+}
+
+impl SearchSource {
+    pub fn display_name(&self) -> String {
+        match self {
+            SearchSource::Buffer(name) => name.clone(),
+            SearchSource::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    /// 1-based line number
+    pub line: usize,
+    /// Byte offset of the match within the line
+    pub column: usize,
+    pub preview: String,
+}
+
+/// All matches in one buffer or file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileResults {
+    pub source: SearchSource,
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Compiled query shared by search and replace
+pub struct Matcher {
+    regex: Regex,
+    literal: bool,
+}
+
+impl Matcher {
+    pub fn new(options: &SearchOptions) -> Result<Self> {
+        if options.query.is_empty() {
+            return Err(anyhow::anyhow!("Search query is empty"));
+        }
+        let pattern = if options.regex { options.query.clone() } else { regex::escape(&options.query) };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid regular expression: {}", e))?;
+        Ok(Self { regex, literal: !options.regex })
+    }
+
+    /// Every match in `text`, one entry per occurrence
+    pub fn find_in(&self, text: &str) -> Vec<SearchMatch> {
+        let mut matches = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            for m in self.regex.find_iter(line) {
+                if m.as_str().is_empty() {
+                    continue;
+                }
+                matches.push(SearchMatch { line: i + 1, column: m.start(), preview: preview(line) });
+            }
+        }
+        matches
+    }
+
+    /// Replace every match; `$1`-style groups expand only in regex mode
+    pub fn replace_in(&self, text: &str, replacement: &str) -> (String, usize) {
+        let count = self.regex.find_iter(text).filter(|m| !m.as_str().is_empty()).count();
+        if count == 0 {
+            return (text.to_string(), 0);
+        }
+        let replaced = if self.literal {
+            self.regex.replace_all(text, NoExpand(replacement)).into_owned()
+        } else {
+            self.regex.replace_all(text, replacement).into_owned()
+        };
+        (replaced, count)
+    }
+
+    /// Each stretch of lines a replacement changes, as (1-based first line, before, after)
+    ///
+    /// Built from the matches themselves, so a replacement that adds or removes
+    /// line breaks still shows each change beside the lines it came from.
+    pub fn changed_lines(&self, text: &str, replacement: &str) -> Vec<(usize, String, String)> {
+        let mut changes = Vec::new();
+        // Byte range of whole lines the current change covers, and its text after replacing up to `cursor`
+        let mut span: Option<(usize, usize)> = None;
+        let mut after = String::new();
+        let mut cursor = 0;
+        for caps in self.regex.captures_iter(text) {
+            let Some(m) = caps.get(0).filter(|m| !m.as_str().is_empty()) else { continue };
+            let start = text[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+            let end = text[m.end()..].find('\n').map_or(text.len(), |i| m.end() + i);
+            span = match span {
+                Some((first, last)) if start <= last => Some((first, last.max(end))),
+                previous => {
+                    if let Some((first, last)) = previous {
+                        after.push_str(&text[cursor..last]);
+                        changes.push(change(text, first, last, &after));
+                    }
+                    after.clear();
+                    cursor = start;
+                    Some((start, end))
+                }
+            };
+            after.push_str(&text[cursor..m.start()]);
+            if self.literal {
+                after.push_str(replacement);
+            } else {
+                caps.expand(replacement, &mut after);
+            }
+            cursor = m.end();
+        }
+        if let Some((first, last)) = span {
+            after.push_str(&text[cursor..last]);
+            changes.push(change(text, first, last, &after));
+        }
+        changes
+    }
+}
+
+/// One preview entry for the lines `text[first..last]` becoming `after`
+fn change(text: &str, first: usize, last: usize, after: &str) -> (usize, String, String) {
+    let line = text[..first].matches('\n').count() + 1;
+    (line, preview(&text[first..last].replace('\n', " ⏎ ")), preview(&after.replace('\n', " ⏎ ")))
+}
+
+fn preview(line: &str) -> String {
+    let trimmed = line.trim();
+    if trimmed.chars().count() > PREVIEW_CHARS {
+        format!("{}…", trimmed.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Match a file name against comma-separated `*`/`?` patterns (case-insensitive)
+pub fn glob_matches(globs: &str, name: &str) -> bool {
+    let patterns: Vec<&str> = globs.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+    if patterns.is_empty() {
+        return true;
+    }
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    patterns.iter().any(|p| wildcard(&p.to_lowercase().chars().collect::<Vec<_>>(), &name))
+}
+
+fn wildcard(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| wildcard(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && wildcard(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && wildcard(rest, &name[1..]),
+    }
+}
+
+/// Program files under `root` (recursively) whose names pass `file_glob`
+///
+/// Hidden directories and `target/` are skipped; results are sorted by path.
+pub fn project_files(root: &Path, file_glob: &str) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(kind) = entry.file_type() else { continue };
+            if kind.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    dirs.push(path);
+                }
+            } else if kind.is_file() && is_program_file(&path) && glob_matches(file_glob, &name) {
+                files.push(path);
+                if files.len() >= MAX_FILES {
+                    files.sort();
+                    return files;
+                }
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn is_program_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| PROGRAM_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Search `buffers` and then the files under `root` that are not open,
+/// calling `on_result` for every buffer/file with at least one match
+///
+/// Stops early when `on_result` returns `false`.
+pub fn search_workspace(
+    buffers: &[OpenBuffer],
+    root: Option<&Path>,
+    options: &SearchOptions,
+    mut on_result: impl FnMut(FileResults) -> bool,
+) -> Result<()> {
+    let matcher = Matcher::new(options)?;
+    for buffer in buffers {
+        if !glob_matches(&options.file_glob, &buffer.name) {
+            continue;
+        }
+        let matches = matcher.find_in(&buffer.text);
+        if !matches.is_empty() && !on_result(FileResults { source: SearchSource::Buffer(buffer.name.clone()), matches }) {
+            return Ok(());
+        }
+    }
+    let Some(root) = root else { return Ok(()) };
+    for path in project_files(root, &options.file_glob) {
+        let canonical = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if buffers.iter().any(|buffer| buffer.path.as_ref() == Some(&canonical)) {
+            continue;
+        }
+        if std::fs::metadata(&path).map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(&path) else { continue };
+        let matches = matcher.find_in(&text);
+        if !matches.is_empty() && !on_result(FileResults { source: SearchSource::File(path), matches }) {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Proposed replace-in-files change for one buffer or file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileReplacement {
+    pub source: SearchSource,
+    pub original: String,
+    pub replaced: String,
+    pub count: usize,
+    /// What the preview shows, from [`Matcher::changed_lines`]
+    pub changes: Vec<(usize, String, String)>,
+}
+
+/// Compute (but do not apply) replacements for every searched buffer/file
+pub fn plan_replacements(
+    buffers: &[OpenBuffer],
+    root: Option<&Path>,
+    options: &SearchOptions,
+    replacement: &str,
+) -> Result<Vec<FileReplacement>> {
+    let matcher = Matcher::new(options)?;
+    let mut plans = Vec::new();
+    search_workspace(buffers, root, options, |result| {
+        let original = match &result.source {
+            SearchSource::Buffer(name) => buffers.iter().find(|b| b.name == *name).map(|b| b.text.clone()),
+            SearchSource::File(path) => std::fs::read_to_string(path).ok(),
+        };
+        if let Some(original) = original {
+            let (replaced, count) = matcher.replace_in(&original, replacement);
+            if count > 0 {
+                let changes = matcher.changed_lines(&original, replacement);
+                plans.push(FileReplacement { source: result.source, original, replaced, count, changes });
+            }
+        }
+        true
+    })?;
+    Ok(plans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch project folder with a few programs, a text file, and a `.git` folder
+    fn temp_project() -> tempfile::TempDir {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("lessons")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join("hello.pilot"), "T:Hello\nT:hello again\n").unwrap();
+        std::fs::write(dir.join("lessons/square.logo"), "REPEAT 4 [FORWARD 50 RIGHT 90]\n").unwrap();
+        std::fs::write(dir.join("lessons/loop.bas"), "10 PRINT \"HELLO\"\n20 GOTO 10\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "hello from a text file\n").unwrap();
+        std::fs::write(dir.join(".git/HEAD.pilot"), "T:hello\n").unwrap();
+        temp
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    fn collect(buffers: &[OpenBuffer], root: &Path, options: &SearchOptions) -> Vec<FileResults> {
+        let mut results = Vec::new();
+        search_workspace(buffers, Some(root), options, |r| {
+            results.push(r);
+            true
+        })
+        .unwrap();
+        results
+    }
+
+    #[test]
+    fn test_walk_finds_program_files_only() {
+        let dir = temp_project();
+        let root = dir.path();
+        let names: Vec<String> = project_files(root, "").iter().map(|p| file_name(p)).collect();
+        assert_eq!(names, vec!["hello.pilot", "loop.bas", "square.logo"]);
+        let names: Vec<String> = project_files(root, "*.bas, sq*").iter().map(|p| file_name(p)).collect();
+        assert_eq!(names, vec!["loop.bas", "square.logo"]);
+    }
+
+    #[test]
+    fn test_search_buffers_shadow_files() {
+        let dir = temp_project();
+        let root = dir.path();
+        let buffers = vec![OpenBuffer {
+            name: "hello.pilot".into(),
+            path: Some(std::fs::canonicalize(root.join("hello.pilot")).unwrap()),
+            text: "T:Goodbye\nT:HELLO unsaved\n".into(),
+        }];
+        let options = SearchOptions { query: "hello".into(), ..SearchOptions::default() };
+
+        let results = collect(&buffers, root, &options);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].source, SearchSource::Buffer("hello.pilot".into()));
+        assert_eq!(results[0].matches, vec![SearchMatch { line: 2, column: 2, preview: "T:HELLO unsaved".into() }]);
+        assert!(matches!(&results[1].source, SearchSource::File(p) if p.ends_with("loop.bas")));
+
+        let case = SearchOptions { case_sensitive: true, ..options.clone() };
+        assert!(collect(&buffers, root, &case).is_empty());
+
+        // A tab that only shares a file's name, from elsewhere or unsaved, hides nothing
+        let elsewhere = tempfile::tempdir().unwrap();
+        std::fs::write(elsewhere.path().join("loop.bas"), "").unwrap();
+        for path in [Some(std::fs::canonicalize(elsewhere.path().join("loop.bas")).unwrap()), None] {
+            let buffers = vec![OpenBuffer { name: "loop.bas".into(), path, text: String::new() }];
+            let results = collect(&buffers, root, &options);
+            assert!(results.iter().any(|r| matches!(&r.source, SearchSource::File(p) if p.ends_with("loop.bas"))), "{:?}", results);
+        }
+    }
+
+    #[test]
+    fn test_regex_search_and_invalid_pattern() {
+        let dir = temp_project();
+        let root = dir.path();
+        let options = SearchOptions { query: r"FORWARD \d+".into(), regex: true, ..SearchOptions::default() };
+        let results = collect(&[], root, &options);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].matches[0].column, 10);
+
+        let bad = SearchOptions { query: "(".into(), regex: true, ..SearchOptions::default() };
+        assert!(search_workspace(&[], Some(root), &bad, |_| true).is_err());
+    }
+
+    #[test]
+    fn test_plan_replacements_previews_without_writing() {
+        let dir = temp_project();
+        let root = dir.path();
+        let options = SearchOptions { query: "hello".into(), file_glob: "*.pilot".into(), ..SearchOptions::default() };
+        let plans = plan_replacements(&[], Some(root), &options, "Hi $1").unwrap();
+
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].count, 2);
+        assert_eq!(plans[0].replaced, "T:Hi $1\nT:Hi $1 again\n");
+        assert_eq!(plans[0].changes[1], (2, "T:hello again".into(), "T:Hi $1 again".into()));
+        assert_eq!(std::fs::read_to_string(root.join("hello.pilot")).unwrap(), "T:Hello\nT:hello again\n");
+    }
+
+    #[test]
+    fn test_preview_follows_replacements_that_add_or_remove_lines() {
+        let text = "T:one\nT:two\nT:three\nT:four\n";
+        let joined = Matcher::new(&SearchOptions { query: r"two\nT:".into(), regex: true, ..SearchOptions::default() }).unwrap();
+        assert_eq!(joined.changed_lines(text, "two, "), vec![(2, "T:two ⏎ T:three".into(), "T:two, three".into())]);
+
+        let split = Matcher::new(&SearchOptions { query: "T:".into(), ..SearchOptions::default() }).unwrap();
+        let changes = split.changed_lines(text, "R:\nT:");
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[3], (4, "T:four".into(), "R: ⏎ T:four".into()));
+
+        let groups = Matcher::new(&SearchOptions { query: r"T:(\w+)".into(), regex: true, ..SearchOptions::default() }).unwrap();
+        assert_eq!(groups.changed_lines("T:one T:two\nR:x", "[$1]"), vec![(1, "T:one T:two".into(), "[one] [two]".into())]);
+    }
+
+    #[test]
+    fn test_regex_replacement_expands_groups() {
+        let options = SearchOptions { query: r"FORWARD (\d+)".into(), regex: true, ..SearchOptions::default() };
+        let matcher = Matcher::new(&options).unwrap();
+        assert_eq!(matcher.replace_in("FORWARD 50 FORWARD 7", "FD $1"), ("FD 50 FD 7".to_string(), 2));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("", "a.bas"));
+        assert!(glob_matches("*.BAS", "loop.bas"));
+        assert!(glob_matches("l??p.*", "loop.bas"));
+        assert!(!glob_matches("*.logo", "loop.bas"));
+    }
+}
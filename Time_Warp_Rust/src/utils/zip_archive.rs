@@ -0,0 +1,257 @@
+//! Minimal zip reading and writing for `.twpak` bundles
+//!
+//! Writes deflated entries with a central directory, readable by any unzip
+//! tool. Reads stored and deflated entries from single-disk archives without
+//! zip64 or encryption, which covers what Time Warp writes and what common
+//! tools produce for small lesson folders. Sizes are capped before and while
+//! inflating so a hostile archive cannot exhaust memory.
+
+use std::io::{Read, Write};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use thiserror::Error;
+
+/// Most entries read from one archive
+pub const MAX_ZIP_ENTRIES: usize = 1000;
+/// Most bytes unpacked from one archive, all entries together
+pub const MAX_UNPACKED_BYTES: u64 = 64 * 1024 * 1024;
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_DIRECTORY_SIG: u32 = 0x0605_4b50;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+/// Bit 11: file names are UTF-8
+const FLAG_UTF8: u16 = 0x0800;
+/// 1980-01-01 00:00, the earliest DOS date (entries carry no meaningful timestamp)
+const DOS_DATE: u16 = 0x0021;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ZipError {
+    #[error("not a zip archive")]
+    NotAZip,
+    #[error("archive is damaged ({0})")]
+    Corrupt(&'static str),
+    #[error("entry '{0}' uses an unsupported compression method ({1})")]
+    UnsupportedMethod(String, u16),
+    #[error("archive has too many entries (limit {MAX_ZIP_ENTRIES})")]
+    TooManyEntries,
+    #[error("archive unpacks to more than {} MB", MAX_UNPACKED_BYTES / (1024 * 1024))]
+    TooLarge,
+    #[error("entry '{0}' failed its checksum")]
+    ChecksumMismatch(String),
+}
+
+/// One file in an archive: its `/`-separated name and contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZipEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(data);
+    crc.sum()
+}
+
+fn put_u16(out: &mut Vec<u8>, v: u16) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+/// Build a zip archive holding `entries`, in order
+pub fn write_zip(entries: &[ZipEntry]) -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for entry in entries {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&entry.data)?;
+        let compressed = encoder.finish()?;
+        let too_big = |n: usize| u32::try_from(n).map_err(|_| std::io::Error::other("entry too large for zip"));
+        let (size, compressed_size, offset) = (too_big(entry.data.len())?, too_big(compressed.len())?, too_big(out.len())?);
+        let crc = crc32(&entry.data);
+        let name = entry.name.as_bytes();
+
+        // Fields shared by the local and central headers, from "version needed" to "extra length"
+        let mut common = Vec::new();
+        put_u16(&mut common, 20);
+        put_u16(&mut common, FLAG_UTF8);
+        put_u16(&mut common, METHOD_DEFLATED);
+        put_u16(&mut common, 0);
+        put_u16(&mut common, DOS_DATE);
+        put_u32(&mut common, crc);
+        put_u32(&mut common, compressed_size);
+        put_u32(&mut common, size);
+        put_u16(&mut common, name.len() as u16);
+        put_u16(&mut common, 0);
+
+        put_u32(&mut out, LOCAL_HEADER_SIG);
+        out.extend_from_slice(&common);
+        out.extend_from_slice(name);
+        out.extend_from_slice(&compressed);
+
+        put_u32(&mut central, CENTRAL_HEADER_SIG);
+        put_u16(&mut central, 20);
+        central.extend_from_slice(&common);
+        put_u16(&mut central, 0); // comment length
+        put_u16(&mut central, 0); // disk number
+        put_u16(&mut central, 0); // internal attributes
+        put_u32(&mut central, 0); // external attributes
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name);
+    }
+    let directory_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    put_u32(&mut out, END_OF_DIRECTORY_SIG);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, entries.len() as u16);
+    put_u16(&mut out, entries.len() as u16);
+    put_u32(&mut out, central.len() as u32);
+    put_u32(&mut out, directory_offset);
+    put_u16(&mut out, 0);
+    Ok(out)
+}
+
+/// Little-endian field reader over a byte slice
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn at(bytes: &'a [u8], pos: usize) -> Self {
+        Self { bytes, pos }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ZipError> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len()).ok_or(ZipError::Corrupt("truncated"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, ZipError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().expect("2 bytes")))
+    }
+
+    fn u32(&mut self) -> Result<u32, ZipError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().expect("4 bytes")))
+    }
+}
+
+/// Read every entry of a zip archive (directories are skipped)
+pub fn read_zip(bytes: &[u8]) -> Result<Vec<ZipEntry>, ZipError> {
+    // The end-of-directory record is the last 22 bytes plus an optional comment
+    let search_from = bytes.len().saturating_sub(22 + u16::MAX as usize);
+    let eocd = (search_from..bytes.len().saturating_sub(21))
+        .rev()
+        .find(|&i| bytes[i..i + 4] == END_OF_DIRECTORY_SIG.to_le_bytes())
+        .ok_or(ZipError::NotAZip)?;
+    let mut cursor = Cursor::at(bytes, eocd + 10);
+    let count = cursor.u16()? as usize;
+    let _directory_size = cursor.u32()?;
+    let directory_offset = cursor.u32()? as usize;
+    if count > MAX_ZIP_ENTRIES {
+        return Err(ZipError::TooManyEntries);
+    }
+
+    let mut entries = Vec::with_capacity(count);
+    let mut unpacked: u64 = 0;
+    let mut cursor = Cursor::at(bytes, directory_offset);
+    for _ in 0..count {
+        if cursor.u32()? != CENTRAL_HEADER_SIG {
+            return Err(ZipError::Corrupt("bad directory entry"));
+        }
+        cursor.take(4)?; // versions
+        let flags = cursor.u16()?;
+        let method = cursor.u16()?;
+        cursor.take(4)?; // time, date
+        let crc = cursor.u32()?;
+        let compressed_size = cursor.u32()? as usize;
+        let size = cursor.u32()? as u64;
+        let name_len = cursor.u16()? as usize;
+        let extra_len = cursor.u16()? as usize;
+        let comment_len = cursor.u16()? as usize;
+        cursor.take(8)?; // disk, attributes
+        let local_offset = cursor.u32()? as usize;
+        let name = cursor.take(name_len)?;
+        cursor.take(extra_len + comment_len)?;
+        if flags & 0x0001 != 0 {
+            return Err(ZipError::Corrupt("encrypted entry"));
+        }
+        let name = String::from_utf8_lossy(name).into_owned();
+        if name.ends_with('/') {
+            continue;
+        }
+
+        unpacked += size;
+        if unpacked > MAX_UNPACKED_BYTES {
+            return Err(ZipError::TooLarge);
+        }
+        let mut local = Cursor::at(bytes, local_offset);
+        if local.u32()? != LOCAL_HEADER_SIG {
+            return Err(ZipError::Corrupt("bad local header"));
+        }
+        local.take(22)?;
+        let local_name_len = local.u16()? as usize;
+        let local_extra_len = local.u16()? as usize;
+        local.take(local_name_len + local_extra_len)?;
+        let raw = local.take(compressed_size)?;
+
+        let data = match method {
+            METHOD_STORED => raw.to_vec(),
+            METHOD_DEFLATED => {
+                let mut data = Vec::with_capacity(size.min(MAX_UNPACKED_BYTES) as usize);
+                DeflateDecoder::new(raw)
+                    .take(size + 1)
+                    .read_to_end(&mut data)
+                    .map_err(|_| ZipError::Corrupt("could not decompress"))?;
+                data
+            }
+            other => return Err(ZipError::UnsupportedMethod(name, other)),
+        };
+        if data.len() as u64 != size {
+            return Err(ZipError::Corrupt("size mismatch"));
+        }
+        if crc32(&data) != crc {
+            return Err(ZipError::ChecksumMismatch(name));
+        }
+        entries.push(ZipEntry { name, data });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zip_round_trip() {
+        let entries = vec![
+            ZipEntry { name: "manifest.json".into(), data: b"{}".to_vec() },
+            ZipEntry { name: "lib/shapes.logo".into(), data: b"REPEAT 4 [FD 10 RT 90]\n".repeat(50) },
+            ZipEntry { name: "empty.txt".into(), data: Vec::new() },
+        ];
+        let bytes = write_zip(&entries).unwrap();
+        assert_eq!(&bytes[..4], b"PK\x03\x04");
+        assert_eq!(read_zip(&bytes).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_damaged_archives_are_rejected() {
+        assert_eq!(read_zip(b"not a zip at all"), Err(ZipError::NotAZip));
+        let mut bytes = write_zip(&[ZipEntry { name: "a.txt".into(), data: b"hello".to_vec() }]).unwrap();
+        // Flip a bit inside the compressed data
+        bytes[30 + "a.txt".len()] ^= 0x01;
+        assert!(read_zip(&bytes).is_err());
+        let truncated = &bytes[..bytes.len() - 5];
+        assert!(read_zip(truncated).is_err());
+    }
+}
@@ -306,3 +306,181 @@ fn test_infinite_loop_protection() {
     let output = result.unwrap();
     assert!(output.iter().any(|s| s.contains("Maximum iterations") || s.contains("⚠️")));
 }
+
+#[test]
+fn test_program_line_limit() {
+    let mut interp = Interpreter::new();
+    interp.limits.max_program_lines = 100;
+
+    let program = "T:x\n".repeat(101);
+    let err = interp.load_program(&program).unwrap_err().to_string();
+    assert!(err.contains("limit of 100"), "{}", err);
+    assert!(err.contains("Max program lines"), "{}", err);
+}
+
+#[test]
+fn test_program_byte_limit() {
+    let mut interp = Interpreter::new();
+    interp.limits.max_program_bytes = 1024;
+
+    let program = format!("T:{}", "x".repeat(2000));
+    let err = interp.load_program(&program).unwrap_err().to_string();
+    assert!(err.contains("Max program bytes"), "{}", err);
+}
+
+#[test]
+fn test_repeat_count_clamped() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_repeat_count = 10;
+
+    interp.load_program("REPEAT 1000000000 [FORWARD 1]").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(turtle.lines.len(), 10);
+    assert!(output.iter().any(|s| s.contains("REPEAT count") && s.contains("Max REPEAT count")));
+}
+
+#[test]
+fn test_repeat_count_error_policy() {
+    use time_warp_unified::interpreter::LimitPolicy;
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_repeat_count = 10;
+    interp.limits.loop_policy = LimitPolicy::Error;
+
+    interp.load_program("REPEAT 11 [FORWARD 1]").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert!(turtle.lines.is_empty());
+    assert!(output.iter().any(|s| s.starts_with("❌") && s.contains("Max REPEAT count")));
+}
+
+#[test]
+fn test_for_span_clamped() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_for_iterations = 5;
+
+    let program = r#"
+10 FOR I = 1 TO 1000000000
+20 PRINT I
+30 NEXT I
+"#;
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    let numbers: Vec<i32> = output.iter().filter_map(|s| s.trim().parse().ok()).collect();
+    assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+    assert!(output.iter().any(|s| s.contains("Max FOR iterations")));
+}
+
+#[test]
+fn test_output_line_limit() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_output_lines = 20;
+
+    let program = r#"
+10 FOR I = 1 TO 100
+20 PRINT I
+30 NEXT I
+"#;
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output.len(), 21);
+    assert!(output[20].contains("Max output lines"));
+}
+
+#[test]
+fn test_long_output_lines_are_cut_with_full_text_kept() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_output_line_chars = 100;
+
+    // Multi-byte characters must not be split
+    let full = "ab✓de".repeat(50);
+    interp.load_program(&format!("T:{}\nT:short", full)).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output[0], format!("{}… [150 more characters]", "ab✓de".repeat(20)));
+    assert!(interp.is_truncated(0) && !interp.is_truncated(1));
+    assert_eq!(interp.full_output_line(0), Some(full.as_str()));
+    assert_eq!(interp.full_output_line(1), Some("short"));
+    assert_eq!(interp.full_output(), format!("{}\nshort", full));
+
+    // A new run forgets the cut lines along with the output
+    interp.load_program("T:again").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert!(!interp.is_truncated(0));
+    assert_eq!(interp.full_output(), "again");
+}
+
+/// Run `program`, which must finish well within a second
+fn run_quickly(interp: &mut Interpreter, turtle: &mut TurtleState, program: &str) -> Vec<String> {
+    let started = std::time::Instant::now();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(turtle).unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(1), "took {:?}", started.elapsed());
+    output
+}
+
+#[test]
+fn test_deep_repeat_nesting_fails_instead_of_overflowing() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    let nested = |depth: usize, inner: &str| (0..depth).fold(inner.to_string(), |body, _| format!("REPEAT 1 [{}]", body));
+
+    let brackets = format!("REPEAT 2 [{}FD 1{}]", "[".repeat(500), "]".repeat(500));
+    let output = run_quickly(&mut interp, &mut turtle, &brackets);
+    assert!(output[0].contains("REPEAT bracket depth 501 exceeds the limit of 64"), "{:?}", output);
+    let output = run_quickly(&mut interp, &mut turtle, &nested(5000, "FD 1"));
+    assert!(output[0].contains("REPEAT bracket depth 5000"), "{:?}", output);
+    assert!(turtle.lines.is_empty());
+
+    // REPEATs nested through a recursive procedure count too
+    let dive = format!("TO DIVE :N\n{}\nEND\nDIVE 100", nested(8, "FD 1 DIVE :N - 1"));
+    let output = run_quickly(&mut interp, &mut turtle, &dive);
+    assert!(output.iter().any(|l| l.contains("REPEAT bracket depth 72 exceeds the limit of 64")), "{:?}", output);
+
+    interp.limits.max_bracket_depth = 100;
+    turtle = TurtleState::default();
+    assert!(run_quickly(&mut interp, &mut turtle, &nested(100, "FD 1")).is_empty());
+    assert_eq!(turtle.lines.len(), 1);
+}
+
+#[test]
+fn test_deep_parentheses_fail_instead_of_falling_back() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    let parens = |depth: usize, inner: &str| format!("{}{}{}", "(".repeat(depth), inner, ")".repeat(depth));
+
+    for statement in ["PRINT {}", "LET A = {}", "IF {} THEN PRINT 1", "PRINT LEN{}"] {
+        let program = format!("10 {}\n20 PRINT \"done\"", statement.replace("{}", &parens(10_000, "1")));
+        let output = run_quickly(&mut interp, &mut turtle, &program);
+        assert!(output[0].contains("Parenthesis depth 10000 exceeds the limit of 256"), "{}: {:?}", statement, &output[0][..80]);
+        assert_eq!(output[1], "done");
+    }
+    let output = run_quickly(&mut interp, &mut turtle, &format!("FD {}", parens(10_000, "1")));
+    assert!(output[0].contains("Parenthesis depth 10000"), "{:?}", &output[0][..80]);
+
+    // Parentheses inside text are not nesting
+    let output = run_quickly(&mut interp, &mut turtle, &format!("10 PRINT \"{}\"", "(".repeat(300)));
+    assert_eq!(output, ["(".repeat(300)]);
+    interp.limits.max_paren_depth = 2;
+    let output = run_quickly(&mut interp, &mut turtle, "10 PRINT ((1 + 2))\n20 PRINT (((3)))");
+    assert_eq!(output[0], "3");
+    assert!(output[1].contains("Parenthesis depth 3 exceeds the limit of 2"), "{:?}", output);
+}
+
+#[test]
+fn test_long_if_chains_run_without_recursing() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    let chain = format!("10 {}PRINT \"deep\"\n20 IF 0 THEN PRINT \"never\" ELSE IF 1 THEN PRINT \"else\"", "IF 1 THEN ".repeat(2000));
+    assert_eq!(run_quickly(&mut interp, &mut turtle, &chain), ["deep", "else"]);
+
+    let stats = time_warp_unified::languages::analysis::analyze_program(&chain, time_warp_unified::languages::Language::Basic);
+    assert_eq!(stats.constructs.get("IF"), Some(&2002));
+}
@@ -0,0 +1,4 @@
+﻿10 PRINT "HELLO"
+20 LET X = 2
+30 PRINT X * 21
+40 END
@@ -2,43 +2,35 @@
 /// 
 /// Tests high-level workflows: program loading, execution, UI state
 
-use time_warp_unified::interpreter::Interpreter;
-use time_warp_unified::graphics::TurtleState;
+use time_warp_unified::interpreter::{Interpreter, SecurityPolicy};
+use time_warp_unified::interpreter::isolated::IsolatedRunOptions;
+use time_warp_unified::graphics::{TurtleShape, TurtleState};
+use time_warp_unified::interpreter::clock::{Clock, FakeClock};
+use time_warp_unified::interpreter::warnings::WarningKind;
+use time_warp_unified::languages::Language;
+use time_warp_core::{run, run_turtle, run_with_input};
+use std::time::Duration;
 
 #[test]
 fn test_pilot_hello_world() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    
-    let program = "T:Hello, World!\nE:";
-    interp.load_program(program).unwrap();
-    
-    let output = interp.execute(&mut turtle).unwrap();
-    assert_eq!(output.len(), 1);
-    assert_eq!(output[0], "Hello, World!");
+    run!("T:Hello, World!\nE:" => ["Hello, World!"]);
+}
+
+#[test]
+fn test_pilot_classic_star_labels() {
+    let program = "J:*SKIP\nT:Skipped\n*SKIP\nT:After the label\nJ:END_\nT:Also skipped\nL:END_";
+    run!(program => ["After the label"]);
 }
 
 #[test]
 fn test_basic_for_loop() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    
     let program = r#"
 10 FOR I = 1 TO 3
 20 PRINT I
 30 NEXT I
 40 END
 "#;
-    
-    interp.load_program(program).unwrap();
-    let output = interp.execute(&mut turtle).unwrap();
-    
-    // Should print 1, 2, 3 (check content, not count - may have extra lines)
-    let numbers: Vec<&str> = output.iter()
-        .map(|s| s.trim())
-        .filter(|s| s.parse::<i32>().is_ok())
-        .collect();
-    assert_eq!(numbers, vec!["1", "2", "3"]);
+    run!(program => ["1", "2", "3"]);
 }
 
 #[test]
@@ -103,25 +95,15 @@ fn test_basic_if_then_goto() {
 
 #[test]
 fn test_logo_repeat_square() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    
-    let program = "REPEAT 4 [FORWARD 50 RIGHT 90]";
-    interp.load_program(program).unwrap();
-    
-    interp.execute(&mut turtle).unwrap();
-    
+    let turtle = run_turtle!("REPEAT 4 [FORWARD 50 RIGHT 90]");
     // After 4 sides with 90 degree turns, turtle should be back near start
-    // Square: 4 sides of 50 units each
+    assert_eq!(turtle.lines.len(), 4);
     assert!((turtle.x.abs()) < 5.0, "Turtle x={}", turtle.x);
     assert!((turtle.y.abs()) < 5.0, "Turtle y={}", turtle.y);
 }
 
 #[test]
 fn test_basic_gosub_return() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    
     let program = r#"
 10 PRINT "Start"
 20 GOSUB 50
@@ -130,26 +112,99 @@ fn test_basic_gosub_return() {
 50 PRINT "Subroutine"
 60 RETURN
 "#;
-    
-    interp.load_program(program).unwrap();
+    run!(program => ["Start", "Subroutine", "End"]);
+}
+
+#[test]
+fn test_basic_on_goto_and_on_gosub() {
+    let program = "10 FOR I = 0 TO 4\n20 ON I GOTO 60, 80, 100\n30 PRINT I, \"none\"\n40 NEXT I\n50 END\n60 PRINT \"one\"\n70 GOTO 40\n80 PRINT \"two\"\n90 GOTO 40\n100 PRINT \"three\"\n110 GOTO 40";
+    run!(program => ["0 none", "one", "two", "three", "4 none"]);
+    // The selector rounds, and RETURN comes back to the line after the ON
+    let program = "10 LET K = 1.6\n20 ON K GOSUB 100, 200\n30 PRINT \"back\"\n40 ON K * 2 GOSUB 100, 200\n50 PRINT \"done\"\n60 END\n100 PRINT \"first\"\n110 RETURN\n200 PRINT \"second\"\n210 RETURN";
+    run!(program => ["second", "back", "done"]);
+}
+
+#[test]
+fn test_basic_colon_statements_run_in_order() {
+    run!("10 LET X = 1 : PRINT X : LET X = X + 1 : PRINT X, \"A:B\"\n20 REM done: really" => ["1", "2 A:B"]);
+    // A GOTO leaves the rest of its line
+    run!("10 PRINT \"one\" : GOTO 30 : PRINT \"skipped\"\n20 PRINT \"never\"\n30 PRINT \"three\" : END : PRINT \"after END\"" => ["one", "three"]);
+    // Everything after THEN is the IF's, run or skipped together
+    let program = "10 LET X = 1\n20 IF X > 0 THEN PRINT \"YES\" : GOTO 40\n30 PRINT \"no\"\n40 IF X < 0 THEN PRINT \"neg\" : PRINT \"also skipped\"\n50 PRINT \"end\"";
+    run!(program => ["YES", "end"]);
+}
+
+#[test]
+fn test_basic_colon_statements_loop_return_and_wait_within_a_line() {
+    let program = "10 FOR I = 1 TO 3 : PRINT I : NEXT I\n20 GOSUB 100 : PRINT \"back\"\n30 END\n100 PRINT \"sub\" : RETURN";
+    run!(program => ["1", "2", "3", "sub", "back"]);
+
+    // A run paused at an INPUT carries on with the statement after it
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("10 INPUT N : PRINT N * 2 : GOTO 30\n20 PRINT \"skipped\"\n30 PRINT \"done\"").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert!(interp.pending_input.is_some());
+    interp.provide_input("21");
     let output = interp.execute(&mut turtle).unwrap();
-    
-    // Check execution order: Start, Subroutine, End
-    let relevant: Vec<&str> = output.iter()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && !s.starts_with('❌'))
-        .collect();
-    assert!(relevant.len() >= 3);
-    assert!(relevant[0].contains("Start"));
-    assert!(relevant[1].contains("Subroutine"));
-    assert!(relevant[2].contains("End"));
+    assert_eq!(output[output.len() - 2..], ["42", "done"]);
 }
 
 #[test]
-fn test_pilot_conditional_yes_no() {
+fn test_basic_on_with_a_negative_selector_is_an_error() {
     let mut interp = Interpreter::new();
     let mut turtle = TurtleState::default();
-    
+    interp.load_program("10 ON -1 GOTO 30\n20 PRINT \"after\"\n30 END").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("ON -1 is outside 0 to 255"), "{:?}", output);
+    assert_eq!(output[1], "after");
+}
+
+#[test]
+fn test_basic_do_loops_test_before_or_after_the_body() {
+    // LOOP UNTIL tests after the body, so it runs once even when the condition already holds
+    run!("10 LET X = 5\n20 DO\n30 PRINT X\n40 LOOP UNTIL X > 0\n50 PRINT \"done\"" => ["5", "done"]);
+    // DO WHILE tests first, so a false condition skips the body entirely
+    run!("10 LET X = 5\n20 DO WHILE X < 0\n30 PRINT X\n40 LOOP\n50 PRINT \"done\"" => ["done"]);
+    run!("10 LET I = 0\n20 DO UNTIL I = 3\n30 LET I = I + 1\n40 PRINT I\n50 LOOP" => ["1", "2", "3"]);
+    run!("10 LET I = 0\n20 DO\n30 LET I = I + 1\n40 LOOP WHILE I < 4\n50 PRINT I" => ["4"]);
+}
+
+#[test]
+fn test_basic_exit_leaves_the_innermost_loop() {
+    // EXIT FOR leaves the loop variable where it was
+    run!("10 FOR I = 1 TO 10\n20 IF I = 4 THEN EXIT FOR\n30 NEXT I\n40 PRINT I" => ["4"]);
+    let program = "10 FOR I = 1 TO 2\n20 DO\n30 FOR J = 1 TO 5\n40 IF J = 2 THEN EXIT DO\n50 NEXT J\n60 LOOP\n70 PRINT I * 10 + J\n80 NEXT I\n90 PRINT \"done\"";
+    run!(program => ["12", "22", "done"]);
+}
+
+#[test]
+fn test_basic_string_functions() {
+    run!("10 PRINT MID$(\"TIMEWARP\", 5, 4)" => ["WARP"]);
+    let program = "10 LET A$ = \"HELLO\"\n20 LET B$ = LEFT$(A$, 2) + RIGHT$(A$, 1) + \"!\"\n30 PRINT B$\n40 IF LEFT$(A$, 1) = \"H\" THEN PRINT \"yes\"\n50 PRINT LEN(A$ + B$)";
+    run!(program => ["HEO!", "yes", "9"]);
+    // LEN of a string variable bounds a FOR loop
+    run!("10 LET A$ = \"ABC\"\n20 FOR I = 1 TO LEN(A$)\n30 PRINT MID$(A$, I, 1)\n40 NEXT I" => ["A", "B", "C"]);
+}
+
+#[test]
+fn test_basic_whole_numbers_and_mod_of_negatives() {
+    run!("10 LET X = -2.5\n20 PRINT INT(X)\n30 PRINT FIX(X)\n40 PRINT CINT(X)\n50 PRINT -7 MOD 3\n60 PRINT 7 MOD -3" => ["-3", "-2", "-3", "-1", "1"]);
+}
+
+#[test]
+fn test_basic_exit_outside_a_loop_is_an_error() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.load_program("10 EXIT FOR\n20 LOOP\n30 PRINT \"after\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("EXIT FOR outside a FOR loop")), "{:?}", output);
+    assert!(output.iter().any(|l| l.contains("LOOP without DO")), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("after"));
+}
+
+#[test]
+fn test_pilot_conditional_yes_no() {
     let program = r#"
 U:X=10
 C:X>5
@@ -159,19 +214,11 @@ N:
 T:X is not greater than 5
 E:
 "#;
-    
-    interp.load_program(program).unwrap();
-    let output = interp.execute(&mut turtle).unwrap();
-    
-    assert_eq!(output.len(), 1);
-    assert_eq!(output[0], "X is greater than 5");
+    run!(program => ["X is greater than 5"]);
 }
 
 #[test]
 fn test_mixed_language_detection() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    
     // Mix PILOT, BASIC, and Logo commands
     let program = r#"
 T:Starting
@@ -179,14 +226,7 @@ PRINT "BASIC says hello"
 FORWARD 10
 T:Done
 "#;
-    
-    interp.load_program(program).unwrap();
-    let output = interp.execute(&mut turtle).unwrap();
-    
-    assert_eq!(output.len(), 3);
-    assert_eq!(output[0], "Starting");
-    assert_eq!(output[1], "BASIC says hello");
-    assert_eq!(output[2], "Done");
+    run!(program => ["Starting", "BASIC says hello", "Done"]);
 }
 
 #[test]
@@ -206,6 +246,23 @@ fn test_execution_timeout_protection() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_pilot_jump_loop_without_state_change_stops_early() {
+    let report = Interpreter::run_isolated("*A\nJ:*A", IsolatedRunOptions::default());
+    assert_eq!(report.error.as_deref(), Some("Infinite loop detected between lines 1–2 (no state change)"));
+    assert_eq!(report.output, ["❌ Error: Infinite loop detected between lines 1–2 (no state change)"]);
+
+    // Loops whose state moves on, or that wait on the clock, are left to the statement limit
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.set_policy(SecurityPolicy { max_iterations: Some(6000), ..SecurityPolicy::default() }).unwrap();
+    for program in ["U:N=0\n*A\nU:N=N+1\nJ:*A", "*W\nY:TIMER<999\nJ:*W"] {
+        interp.load_program(program).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        assert_eq!(output.last().map(String::as_str), Some("⚠️ Warning: Maximum iterations reached"), "{}", program);
+    }
+}
+
 #[test]
 fn test_error_recovery() {
     let mut interp = Interpreter::new();
@@ -228,40 +285,43 @@ fn test_error_recovery() {
 }
 
 #[test]
-fn test_basic_input_numeric_via_callback() {
+fn test_basic_defint_and_type_suffixes() {
+    use time_warp_unified::interpreter::var_types::VarType;
     let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    // Provide input via callback
-    let mut answers = vec!["42".to_string()].into_iter();
-    interp.input_callback = Some(Box::new(move |_| answers.next().unwrap_or_default()));
-
-    let program = r#"
-10 INPUT A
-20 PRINT A
-30 END
+    let mut turtle = TurtleState::new();
+    let code = r#"
+10 DEFINT I-N
+20 LET I = 2.7
+30 LET COUNT% = 7 / 2
+40 LET X = 2.7
+50 PRINT I, COUNT%, X
+60 DEFSTR S
+70 LET S = "hello"
+80 LET Y# = 1 / 4
+90 LET K = 40000
+100 LET COUNT% = "many"
 "#;
-    interp.load_program(program).unwrap();
+    interp.load_program(code).unwrap();
     let output = interp.execute(&mut turtle).unwrap();
-    // Should print 42
-    assert!(output.iter().any(|s| s.trim() == "42"));
+
+    assert_eq!(output[0], "3 4 2.7");
+    assert_eq!(interp.variables["I"], 3.0);
+    assert_eq!(interp.string_variables["S"], "hello");
+    assert_eq!(interp.variables["Y#"], 0.25);
+    assert_eq!((interp.var_type("I"), interp.var_type("X"), interp.var_type("S")), (VarType::Integer, VarType::Single, VarType::String));
+    assert!(output.iter().any(|l| l.contains("Overflow")), "{:?}", output);
+    assert!(output.iter().any(|l| l.contains("Type mismatch")), "{:?}", output);
+    assert_eq!(interp.variables["COUNT%"], 4.0);
 }
 
 #[test]
-fn test_basic_input_string_via_callback() {
-    let mut interp = Interpreter::new();
-    let mut turtle = TurtleState::default();
-    let mut answers = vec!["Alice".to_string()].into_iter();
-    interp.input_callback = Some(Box::new(move |_| answers.next().unwrap_or_default()));
+fn test_basic_input_numeric_via_callback() {
+    run_with_input!("10 INPUT A\n20 PRINT A + 1\n30 END", ["42"] => ["43"]);
+}
 
-    let program = r#"
-10 INPUT NAME
-20 PRINT NAME
-30 END
-"#;
-    interp.load_program(program).unwrap();
-    let output = interp.execute(&mut turtle).unwrap();
-    // Should print Alice
-    assert!(output.iter().any(|s| s.contains("Alice")));
+#[test]
+fn test_basic_input_string_via_callback() {
+    run_with_input!("10 INPUT NAME\n20 PRINT NAME\n30 END", ["Alice"] => ["Alice"]);
 }
 
 #[test]
@@ -287,6 +347,110 @@ E:
     assert!(output.iter().any(|s| s.contains("Match")));
 }
 
+#[test]
+fn test_pilot_answer_history_and_ask_again() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    let program = r#"
+T:Capital of France?
+A:CITY
+T:Not quite.
+AN:
+T:Your favourite colour?
+A:COLOUR
+T:You said %ANS3, then %ANS2, then %ANS1. %ANS4 is still unset.
+"#;
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap();
+    for answer in ["Lyon", "Paris", "blue"] {
+        assert!(interp.pending_input.is_some(), "waiting for {}", answer);
+        interp.provide_input(answer);
+        interp.execute(&mut turtle).unwrap();
+    }
+    // AN: repeats the question and fills the same variable
+    assert_eq!(interp.output, [
+        "Capital of France?",
+        "Not quite.",
+        "Capital of France?",
+        "Your favourite colour?",
+        "You said Lyon, then Paris, then blue. %ANS4 is still unset.",
+    ]);
+    assert_eq!(interp.string_variables.get("CITY").map(String::as_str), Some("Paris"));
+    assert_eq!(interp.answer_history.iter().collect::<Vec<_>>(), ["blue", "Paris", "Lyon"]);
+
+    // The history starts over each run, and AN: needs an A: before it
+    interp.load_program("AN:\nT:%ANS1").unwrap();
+    assert!(interp.answer_history.is_empty());
+    interp.execute(&mut turtle).unwrap();
+    assert!(interp.output[0].contains("AN: has no earlier A: to ask again"), "{:?}", interp.output);
+    assert_eq!(interp.output[1], "%ANS1");
+}
+
+#[test]
+fn test_pilot_answer_history_is_bounded() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::default();
+    interp.limits.max_answer_history = 2;
+    let mut answers = ["one", "two", "three"].into_iter();
+    interp.input_callback = Some(Box::new(move |_| answers.next().unwrap_or_default().to_string()));
+    interp.load_program("A:X\nA:X\nA:X\nT:*X* %ANS1 %ANS2 %ANS3").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, ["three three two %ANS3"]);
+}
+
+#[test]
+fn test_disabled_statements_are_refused_and_the_run_goes_on() {
+    use time_warp_unified::interpreter::lesson::DisabledStatements;
+    let program = "10 PRINT \"A\"\n20 GOTO 40\n30 PRINT \"B\"\n40 IF 1 = 1 THEN 60\n50 PRINT \"C\"\n60 PRINT \"D\"";
+    let mut turtle = TurtleState::default();
+    let mut interp = Interpreter::new();
+    interp.load_program(program).unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["A", "D"]);
+
+    // The same program in a lesson without GOTO: each jump is an error and the next line runs
+    let mut interp = Interpreter::new();
+    interp.disabled = DisabledStatements::new(["GOTO"]);
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    let refused: Vec<_> = output.iter().filter(|l| l.contains("GOTO is disabled for this lesson — use FOR … NEXT instead")).collect();
+    assert_eq!(refused.len(), 2, "{:?}", output);
+    let printed: Vec<_> = output.iter().filter(|l| l.len() == 1).collect();
+    assert_eq!(printed, ["A", "B", "C", "D"]);
+
+    // Logo SETPOS is SETXY; PILOT J: is refused by its command
+    let mut interp = Interpreter::new();
+    interp.disabled = DisabledStatements::new(["SETXY", "J:"]);
+    interp.load_program("SETPOS 50 50\nFORWARD 10").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("SETPOS is disabled for this lesson — use FORWARD and RIGHT instead")), "{:?}", output);
+    assert_eq!((turtle.x, turtle.y), (0.0, -10.0));
+    interp.load_program("J:*END\nT:not skipped\n*END").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("J: is disabled for this lesson")) && output.contains(&"not skipped".to_string()), "{:?}", output);
+}
+
+#[test]
+fn test_keywords_are_reserved_only_in_their_own_language() {
+    let mut turtle = TurtleState::default();
+    let mut interp = Interpreter::new();
+    // PILOT has no word keywords, so a PILOT program may call a variable PRINT
+    interp.load_program("U:PRINT=5\nU:TOTAL=PRINT+1\nT:*PRINT* and *TOTAL*").unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["5 and 6"]);
+
+    // BASIC may not, in its own statements, even in the same program
+    interp.load_program("U:PRINT=5\nLET X = PRINT + 1\nLET IF = 2\nT:*PRINT*").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("PRINT is a reserved word in BASIC"), "{:?}", output);
+    assert!(output[1].contains("IF is a reserved word in BASIC"), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("5"));
+
+    // Logo refuses a command as a procedure input, but not a BASIC keyword
+    interp.load_program("TO BOX :FD\nFORWARD :FD\nEND\nTO STEP :PRINT\nFORWARD :PRINT\nEND\nSTEP 10").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("FD is a reserved word in Logo")), "{:?}", output);
+    assert_eq!(turtle.lines.len(), 1);
+}
+
 #[test]
 fn test_wait_for_input_and_resume_without_callback() {
     let mut interp = Interpreter::new();
@@ -311,6 +475,139 @@ fn test_wait_for_input_and_resume_without_callback() {
     assert!(output2.iter().any(|s| s.trim() == "7"));
 }
 
+#[test]
+fn test_abort_then_rerun_gives_clean_output() {
+    // Paused at INPUT inside a FOR loop and a GOSUB
+    let program = "10 FOR I = 1 TO 2\n20 GOSUB 100\n30 NEXT I\n40 END\n100 INPUT \"Name\"; N$\n110 PRINT N$\n120 RETURN";
+    let loaded = |source: &str| {
+        let mut interp = Interpreter::new();
+        interp.load_program(source).unwrap();
+        interp
+    };
+    let answered = |interp: &mut Interpreter, turtle: &mut TurtleState| {
+        for name in ["Ada", "Bo"] {
+            interp.execute(turtle).unwrap();
+            interp.provide_input(name);
+        }
+        interp.execute(turtle).unwrap()
+    };
+    let clean = answered(&mut loaded(program), &mut TurtleState::new());
+    assert_eq!(clean, ["Ada", "Bo"]);
+
+    let mut interp = loaded(program);
+    let mut turtle = TurtleState::new();
+    interp.execute(&mut turtle).unwrap();
+    assert!(interp.pending_input.is_some());
+    interp.abort();
+    assert!(interp.pending_input.is_none() && interp.gosub_stack.is_empty() && interp.for_stack.is_empty());
+    // An answer arriving after the abort goes nowhere, and nothing resumes
+    interp.provide_input("Late");
+    assert!(!interp.string_variables.contains_key("N$"));
+    assert!(interp.execute(&mut turtle).unwrap().is_empty());
+    assert!(interp.set_policy(SecurityPolicy::default()).is_ok(), "no run left in progress");
+
+    interp.load_program(program).unwrap();
+    assert_eq!(answered(&mut interp, &mut turtle), clean);
+}
+
+#[test]
+fn test_input_dollar_menu_takes_single_keys() {
+    use time_warp_unified::interpreter::InputKind;
+    let menu = r#"REM @lang basic
+10 CLS
+20 LOCATE 2, 4
+30 PRINT "1) Draw  2) Quit"
+40 LET K$ = INPUT$(1)
+50 IF K$ = "1" THEN GOTO 80
+60 PRINT "Bye"
+70 END
+80 PRINT "Drawing"
+"#;
+    // The callback path takes the first key of the scripted answer
+    for (keys, branch) in [("1", "Drawing"), ("2x", "Bye")] {
+        let options = IsolatedRunOptions { inputs: vec![keys.to_string()], ..IsolatedRunOptions::default() };
+        let report = Interpreter::run_isolated(menu, options);
+        assert_eq!(report.output.last().map(String::as_str), Some(branch), "{:?}", report.output);
+    }
+    // INPUT$(3) reads on across answers until three keys arrived
+    let options = IsolatedRunOptions { inputs: vec!["ab".to_string(), "cd".to_string()], ..IsolatedRunOptions::default() };
+    let report = Interpreter::run_isolated("10 LET W$ = INPUT$(3)\n20 PRINT W$", options);
+    assert_eq!(report.output, ["abc"]);
+    let report = Interpreter::run_isolated("10 LET W$ = INPUT$(0)", IsolatedRunOptions::default());
+    assert!(report.output[0].contains("INPUT$ needs 1 to 255 keys"), "{:?}", report.output);
+
+    // Without a callback the UI is asked for keys, not a line
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(menu).unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!(interp.pending_input.as_ref().map(|req| req.kind), Some(InputKind::Keys(1)));
+    interp.provide_input("2");
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output.last().map(String::as_str), Some("Bye"));
+    assert_eq!(interp.string_variables.get("K$").map(String::as_str), Some("2"));
+
+    interp.load_program("10 INPUT \"Name\"; N$").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!(interp.pending_input.as_ref().map(|req| req.kind), Some(InputKind::Line));
+}
+
+#[test]
+fn test_accented_input_and_emoji_output() {
+    let mut turtle = TurtleState::default();
+
+    // A: and INPUT keep accented answers, and *NAME* puts them back
+    let mut interp = Interpreter::new();
+    interp.load_program("T:Name?\nA:NAME\nT:Bonjour *NAME*!\nE:").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    interp.provide_input("Zoë Ñúñez");
+    assert_eq!(interp.execute(&mut turtle).unwrap(), vec!["Name?", "Bonjour Zoë Ñúñez!"]);
+
+    let mut interp = Interpreter::new();
+    interp.load_program("10 INPUT N$\n20 PRINT \"🎉 \", N$\n30 PRINT \"Grüße 日本\"").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    interp.provide_input("José");
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.contains(&"🎉  José".to_string()), "{:?}", output);
+    assert!(output.contains(&"Grüße 日本".to_string()), "{:?}", output);
+
+    // Lines whose second byte falls inside a character used to panic
+    let mut interp = Interpreter::new();
+    interp.load_program("T:Ünïcödé 😀\nTü: hallo\n😀\nT:still running").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec![
+        "Ünïcödé 😀",
+        "Unknown PILOT command: Tü: hallo",
+        "Unknown PILOT command: 😀",
+        "still running",
+    ]);
+    let mut interp = Interpreter::new();
+    interp.load_program("REPEAT 2 [é]").unwrap();
+    assert!(interp.execute(&mut turtle).is_ok());
+
+    // BASIC keywords after text that changes length when upper-cased (ı → I, ß → SS)
+    let mut interp = Interpreter::new();
+    interp.load_program(concat!(
+        "10 LET A$ = \"ı\"\n",
+        "20 IF A$ = \"ı\" THEN PRINT \"YES\"\n",
+        "30 IF A$ = \"ıııııé\" THEN PRINT \"NO\" ELSE PRINT \"ELSE\"\n",
+        "40 FOR I = LEN(\"ßı\") TO LEN(\"ßıı\") STEP LEN(\"ı\")\n",
+        "50 PRINT I\n",
+        "60 NEXT I\n",
+        "70 EVERY LEN(\"ßß\") GOSUB 100\n",
+        "80 END\n",
+        "100 RETURN",
+    )).unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), vec!["YES", "ELSE", "2", "3"]);
+    let ticker = interp.ticker.as_ref().expect("EVERY scheduled a handler");
+    assert_eq!((ticker.interval, ticker.handler), (std::time::Duration::from_secs(2), 100));
+
+    // Emoji take two columns when output wraps
+    let mut interp = Interpreter::new();
+    interp.load_program("10 WIDTH 10\n20 PRINT \"🎉🎉🎉🎉 🎉🎉🎉 ab\"").unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), vec!["🎉🎉🎉🎉", "🎉🎉🎉 ab"]);
+}
+
 #[test]
 fn test_logo_procedures() {
     let mut interp = Interpreter::new();
@@ -338,6 +635,13 @@ SQUARE
     
     // Verify lines were drawn (should be 4 lines for square)
     assert_eq!(turtle.lines.len(), 4);
+
+    // A procedure that never stops fails at the depth limit instead of overflowing the stack
+    interp.load_program("TO SPIN\nFORWARD 1\nSPIN\nEND\nSPIN\nFORWARD 5").unwrap();
+    let mut turtle = TurtleState::new();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(turtle.lines.len(), 201, "{:?}", output);
+    assert!(output[0].contains("more than 200 deep"), "{:?}", output);
 }
 
 #[test]
@@ -357,9 +661,9 @@ FORWARD 10
     
     // Verify colors changed (first line red, second blue)
     assert_eq!(turtle.lines.len(), 2);
-    use eframe::egui;
-    assert_eq!(turtle.lines[0].color, egui::Color32::from_rgb(255, 0, 0)); // RED
-    assert_eq!(turtle.lines[1].color, egui::Color32::from_rgb(0, 0, 255)); // BLUE
+    use time_warp_unified::graphics::Rgba;
+    assert_eq!(turtle.lines[0].color, Rgba::from_rgb(255, 0, 0)); // RED
+    assert_eq!(turtle.lines[1].color, Rgba::from_rgb(0, 0, 255)); // BLUE
 }
 
 #[test]
@@ -379,9 +683,60 @@ FORWARD 10
     
     // Verify hex colors parsed correctly
     assert_eq!(turtle.lines.len(), 2);
-    use eframe::egui;
-    assert_eq!(turtle.lines[0].color, egui::Color32::from_rgb(255, 0, 0)); // #FF0000
-    assert_eq!(turtle.lines[1].color, egui::Color32::from_rgb(0, 0, 255)); // #00F -> #0000FF
+    use time_warp_unified::graphics::Rgba;
+    assert_eq!(turtle.lines[0].color, Rgba::from_rgb(255, 0, 0)); // #FF0000
+    assert_eq!(turtle.lines[1].color, Rgba::from_rgb(0, 0, 255)); // #00F -> #0000FF
+}
+
+#[test]
+fn test_logo_color_cycle_and_flash() {
+    use time_warp_unified::graphics::Rgba;
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let code = r#"
+SETCOLORCYCLE [RED YELLOW #00F]
+REPEAT 4 [FORWARD 10 RIGHT 90]
+FLASH 2
+SETCOLORCYCLE []
+FORWARD 10
+SETCOLORCYCLE [GREEN WHITE]
+FORWARD 10
+SETCOLOR RED
+FORWARD 10
+SETCOLORCYCLE [RED PLAID]
+"#;
+    interp.load_program(code).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    let colors: Vec<Rgba> = turtle.lines.iter().map(|l| l.color).collect();
+    let (red, yellow, blue) = (Rgba::from_rgb(255, 0, 0), Rgba::from_rgb(255, 255, 0), Rgba::from_rgb(0, 0, 255));
+    let green = Rgba::from_rgb(0, 255, 0);
+    // The fourth side starts the cycle over; [] and SETCOLOR go back to the pen color
+    assert_eq!(colors, [red, yellow, blue, red, Rgba::WHITE, green, red]);
+    assert_eq!(turtle.flash.as_ref().map(|f| f.lines.clone()), Some(2..4));
+    assert!(output.iter().any(|l| l.contains("'PLAID' is not a color")), "{:?}", output);
+}
+
+#[test]
+fn test_bare_end_stops_every_language() {
+    let run = |code: &str| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program(code).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        (output, turtle.lines.len())
+    };
+    assert_eq!(run("10 PRINT 1\n20 END\n30 PRINT 2").0, ["1"]);
+    assert_eq!(run("PRINT 1\nEND\nPRINT 2").0, ["1"]);
+    // In PILOT, END is the same as E:
+    assert_eq!(run("T:one\nEND\nT:two").0, ["one"]);
+    assert_eq!(run("T:one\nE:\nT:two").0, ["one"]);
+    assert_eq!(run("FORWARD 10\nend\nFORWARD 20").1, 1);
+
+    // The END that closes a TO does not end the program
+    let (output, lines) = run("TO SIDE\nFORWARD 10\nEND\nSIDE\nSIDE\nEND\nFORWARD 99");
+    assert!(output.iter().all(|l| !l.starts_with('❌')), "{:?}", output);
+    assert_eq!(lines, 2);
 }
 
 #[test]
@@ -417,6 +772,42 @@ CIRCLE 0, 0, 50
     assert_eq!(turtle.lines.len(), 36);
 }
 
+#[test]
+fn test_basic_line_in_centered_and_screen_coordinates() {
+    use time_warp_unified::graphics::CoordinateMode;
+    let line = |code: &str| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.load_program(code).unwrap();
+        interp.execute(&mut turtle).unwrap();
+        assert_eq!(turtle.lines.len(), 1);
+        let (half_w, half_h) = (turtle.canvas_width / 2.0, turtle.canvas_height / 2.0);
+        (turtle.lines[0].start, turtle.lines[0].end, half_w, half_h, turtle.coordinate_mode)
+    };
+
+    // Centered: (0, 0) is the middle of the canvas
+    let (start, end, _, _, mode) = line("10 LINE 0, 0, 100, 50");
+    assert_eq!(mode, CoordinateMode::Centered);
+    assert_eq!((start.x, start.y), (0.0, 0.0));
+    assert_eq!((end.x, end.y), (100.0, 50.0));
+
+    // Screen: (0, 0) is the top-left corner and y grows downward
+    for code in ["10 OPTION COORDS SCREEN\n20 LINE 0, 0, 100, 50", "10 SCREEN 1\n20 LINE 0, 0, 100, 50"] {
+        let (start, end, half_w, half_h, mode) = line(code);
+        assert_eq!(mode, CoordinateMode::ScreenTopLeft, "{}", code);
+        assert_eq!((start.x, start.y), (-half_w, -half_h), "{}", code);
+        assert_eq!((end.x, end.y), (100.0 - half_w, 50.0 - half_h), "{}", code);
+    }
+
+    // Logo's turtle is not moved by the BASIC setting
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("FORWARD 10").unwrap();
+    turtle.coordinate_mode = CoordinateMode::ScreenTopLeft;
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!((turtle.lines[0].start.x, turtle.lines[0].start.y), (0.0, 0.0));
+}
+
 #[test]
 fn test_logo_nested_repeat() {
     let mut interp = Interpreter::new();
@@ -439,18 +830,17 @@ fn test_basic_inkey_with_callback() {
     let mut turtle = TurtleState::new();
     
     // Set up callback to simulate key presses
-    use std::cell::RefCell;
-    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
     
-    let key_sequence = Rc::new(RefCell::new(vec!["a", "b", ""]));
-    let index = Rc::new(RefCell::new(0));
+    let key_sequence = Arc::new(Mutex::new(vec!["a", "b", ""]));
+    let index = Arc::new(Mutex::new(0));
     
     let seq_clone = key_sequence.clone();
     let idx_clone = index.clone();
     
     interp.inkey_callback = Some(Box::new(move || {
-        let mut idx = idx_clone.borrow_mut();
-        let seq = seq_clone.borrow();
+        let mut idx = idx_clone.lock().unwrap();
+        let seq = seq_clone.lock().unwrap();
         
         if *idx < seq.len() {
             let result = if seq[*idx].is_empty() {
@@ -482,3 +872,1409 @@ fn test_basic_inkey_with_callback() {
     assert!(output.iter().any(|s| s.contains("b")));
 }
 
+
+#[test]
+fn test_pilot_voice_degrades_without_engine() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    interp.load_program("U:N=3\nV:You have *N* tries").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output, vec!["[voice] You have 3 tries"]);
+}
+
+#[test]
+fn test_speech_mode_speaks_text_and_voice_lines() {
+    use std::sync::{Arc, Mutex};
+    use time_warp_unified::speech::{CancelToken, Speaker, SpeechQueue};
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+    impl Speaker for Recorder {
+        fn speak(&mut self, text: &str, _cancel: &CancelToken) {
+            self.0.lock().unwrap().push(text.to_string());
+        }
+    }
+
+    let spoken = Arc::new(Mutex::new(Vec::new()));
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.speech = Some(SpeechQueue::new(Box::new(Recorder(spoken.clone()))));
+    interp.speak_output = true;
+
+    interp.load_program("T:Hello\nV:Secret\n10 PRINT \"Bye\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    // V: speaks without printing
+    assert_eq!(output, vec!["Hello", "Bye"]);
+    interp.speech.take().unwrap().shutdown();
+    assert_eq!(*spoken.lock().unwrap(), vec!["Hello", "Secret", "Bye"]);
+}
+
+#[test]
+fn test_logvar_records_for_loop_series() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = r#"
+10 FOR I = 1 TO 5
+20 LOGVAR I
+30 NEXT I
+"#;
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(interp.recorded_series.get("I"), Some(&vec![1.0, 2.0, 3.0, 4.0, 5.0]));
+}
+
+#[test]
+fn test_logvar_in_logo_repeat() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    interp.load_program("LET N = 2\nREPEAT 3 [LOGVAR :N]").unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(interp.recorded_series.get("N"), Some(&vec![2.0, 2.0, 2.0]));
+}
+
+#[test]
+fn test_plot_logged_series_fits_canvas() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = r#"
+10 FOR T = 0 TO 9
+20 LET X = T * T
+30 LOGVAR T
+40 LOGVAR X
+50 NEXT T
+60 PLOT X
+70 PLOT X VS T
+"#;
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    // Two charts: 2 axes + 9 segments each
+    assert_eq!(turtle.lines.len(), 2 * (2 + 9));
+    let half_w = turtle.canvas_width / 2.0;
+    let half_h = turtle.canvas_height / 2.0;
+    for line in &turtle.lines {
+        for p in [line.start, line.end] {
+            assert!(p.x >= -half_w && p.x <= half_w, "x out of canvas: {:?}", p);
+            assert!(p.y >= -half_h && p.y <= half_h, "y out of canvas: {:?}", p);
+        }
+    }
+    for text in &turtle.texts {
+        assert!(text.pos.x >= -half_w && text.pos.y >= -half_h);
+    }
+    assert!(turtle.texts.iter().any(|t| t.text == "81"));
+    assert!(turtle.texts.iter().any(|t| t.text == "X VS T"));
+}
+
+#[test]
+fn test_plot_unknown_series_errors() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    interp.load_program("10 PLOT Y").unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert!(interp.output.iter().any(|l| l.contains("no recorded series 'Y'")));
+    assert!(turtle.lines.is_empty());
+}
+
+#[test]
+fn test_classic_basic_error_messages() {
+    use time_warp_unified::utils::error::BasicError;
+
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = r#"
+10 OPTION ERRORS CLASSIC
+20 NEXT I
+30 GOTO 999
+40 RETURN
+60 DIM A
+"#;
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(interp.output, vec![
+        "?NEXT WITHOUT FOR ERROR IN 20",
+        "?UNDEFINED LINE NUMBER ERROR IN 30",
+        "?RETURN WITHOUT GOSUB ERROR IN 40",
+        "?SYNTAX ERROR IN 60",
+    ]);
+    assert_eq!(interp.variables.get("ERR"), Some(&2.0));
+    assert_eq!(interp.variables.get("ERL"), Some(&60.0));
+    assert_eq!(BasicError::from_code(11), Some(BasicError::DivisionByZero));
+    assert_eq!(BasicError::DivisionByZero.classic_message(Some(50)), "?DIVISION BY ZERO ERROR IN 50");
+}
+
+#[test]
+fn test_modern_basic_errors_unchanged() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    interp.load_program("10 GOTO 999\n20 RETURN").unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(interp.output, vec!["❌ GOTO 999 failed: line not found", "RETURN without GOSUB"]);
+    assert_eq!(interp.variables.get("ERR"), Some(&3.0));
+}
+
+#[test]
+fn test_logo_pathcount_reports_segments() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = r#"
+LOGVAR PATHCOUNT
+REPEAT 4 [FORWARD 10 RIGHT 90]
+LOGVAR PATHCOUNT
+PENUP
+FORWARD 20
+REPEAT PATHCOUNT [RIGHT 90]
+PATHCOUNT
+"#;
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(interp.recorded_series["PATHCOUNT"], vec![0.0, 4.0]);
+    assert!((turtle.heading - 0.0).abs() < 0.01);
+    assert!(output.iter().any(|l| l == "PATHCOUNT 4"), "output: {:?}", output);
+}
+
+#[test]
+fn test_logo_setx_sety_move_along_one_axis() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("SETX 100\nSETY -50\nPENUP\nSETX 0").unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    assert_eq!((turtle.x, turtle.y), (0.0, -50.0));
+    assert_eq!(turtle.lines.len(), 2);
+    assert_eq!((turtle.lines[0].end.x, turtle.lines[0].end.y), (100.0, 0.0));
+    assert_eq!((turtle.lines[1].end.x, turtle.lines[1].end.y), (100.0, -50.0));
+}
+
+#[test]
+fn test_logo_print_type_and_show() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let program = "LET SIDE = 20\nPRINT :SIDE * 4 + 1\nTYPE \"Side\nTYPE :SIDE\nPRINT \"cm\nSHOW [FD   10 [RT 90]]\nPRINT [a list]\nREPEAT 2 [TYPE 1]\nFORWARD 10\nPRINT \"BASIC\"";
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output, ["81", "Side20cm", "[FD 10 [RT 90]]", "a list", "11", "BASIC"]);
+    assert_eq!(turtle.lines.len(), 1);
+
+    // A line TYPE leaves open is written when the program ends
+    interp.load_program("TYPE \"end").unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["end"]);
+    interp.load_program("SHOW [1 2").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("Unbalanced brackets in SHOW"), "{:?}", output);
+}
+
+#[test]
+fn test_logo_setpos_accepts_list_form() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("LET X = 30\nSETPOS [100 50]\nSETXY [-20 10]\nREPEAT 2 [SETPOS [:X 0] SETXY 0 0]\nSETPOS 7").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!((turtle.x, turtle.y), (0.0, 0.0));
+    assert_eq!(turtle.lines.len(), 6);
+    assert_eq!((turtle.lines[1].end.x, turtle.lines[1].end.y), (-20.0, 10.0));
+    assert_eq!((turtle.lines[2].end.x, turtle.lines[2].end.y), (30.0, 0.0));
+    assert!(output.iter().any(|l| l.contains("SETPOS needs x and y")), "{:?}", output);
+}
+
+#[test]
+fn test_logo_clean_keeps_position_clearscreen_goes_home() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("FORWARD 40\nRIGHT 90\nCLEAN").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert!(turtle.lines.is_empty());
+    assert_eq!((turtle.x, turtle.y, turtle.heading), (0.0, -40.0, 90.0));
+
+    interp.load_program("FORWARD 40\nCLEARSCREEN").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert!(turtle.lines.is_empty(), "no line left from the trip home");
+    assert_eq!((turtle.x, turtle.y, turtle.heading), (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_text_width_wraps_pilot_and_basic_output() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = r#"
+TW:20
+T:The quick brown fox jumps over the *N* lazy dogs and keeps running.
+TW:0
+T:Unwrapped again after TW:0 turns wrapping off.
+"#;
+    interp.load_program(program).unwrap();
+    interp.variables.insert("N".to_string(), 3.0);
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(
+        output,
+        vec![
+            "The quick brown fox",
+            "jumps over the 3",
+            "lazy dogs and keeps",
+            "running.",
+            "Unwrapped again after TW:0 turns wrapping off.",
+        ]
+    );
+
+    let program = "10 WIDTH 12\n20 PRINT \"  Indented text wraps here\"\n30 SCREEN 0, 16, 25\n40 WIDTH 0\n";
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(&output[..3], &["  Indented", "  text wraps", "  here"]);
+    // Without an explicit WIDTH, text mode wraps at its column count
+    interp.load_program("10 SCREEN 0, 16, 25\n20 PRINT \"Sixteen columns is narrow\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(&output[1..], &["Sixteen columns", "is narrow"]);
+}
+
+#[test]
+fn test_basic_print_using() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    let program = "10 LET PRICE = 1234.5\n20 PRINT USING \"##.##\"; 3.14159\n30 PRINT USING \"Total: $$#,###.##\"; PRICE\n\
+                   40 LET F$ = \"[###-]\"\n50 print using F$; -7; 8\n60 PRINT USING \"##\"; 123\n";
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec![" 3.14", "Total:  $1,234.50", "[  7-][  8 ]", "%123"]);
+
+    // Commas inside a function call belong to it, and a separator inside the picture is text
+    interp.load_program("10 LET A$ = \"HELLO\"\n20 PRINT USING \"##\"; MAX(1,2)\n30 PRINT USING \"#;#\"; LEN(LEFT$(A$,2)), MIN(3, 4)\n").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec![" 2", "2;3"]);
+
+    // Strings cannot be formatted as numbers
+    interp.load_program("10 OPTION ERRORS CLASSIC\n20 PRINT USING \"###\"; \"ABC\"\n30 PRINT USING \"###\"; LEFT$(A$,2)\n").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec!["?TYPE MISMATCH ERROR IN 20", "?TYPE MISMATCH ERROR IN 30"]);
+}
+
+#[test]
+fn test_metadata_header_lang_overrides_detection() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+
+    // END is also a Logo keyword; @lang basic makes it stop the program
+    let program = "10 REM @title Stopper\n20 PRINT \"A\"\n30 END\n40 PRINT \"B\"";
+    interp.load_program(&format!("REM @lang basic\n{}", program)).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec!["A"]);
+    assert_eq!(interp.metadata.title.as_deref(), Some("Stopper"));
+
+    interp.load_program("; @title Square\n; @author Ms. Rivera\nREPEAT 4 [FD 10 RT 90]\nR:@lang pilot").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.is_empty(), "header lines are silent: {:?}", output);
+    assert_eq!(turtle.lines.len(), 4);
+    assert_eq!(interp.metadata.author.as_deref(), Some("Ms. Rivera"));
+    assert_eq!(interp.metadata.language, None);
+}
+
+#[test]
+fn test_security_policy_blocks_each_permission() {
+    let cases = [
+        ("R:SAVE scores.txt", SecurityPolicy { allow_file_io: false, ..SecurityPolicy::default() }),
+        ("R:MQTT connect", SecurityPolicy { allow_network: false, ..SecurityPolicy::default() }),
+        ("R:PLUGIN weather", SecurityPolicy { allow_plugins: false, ..SecurityPolicy::default() }),
+    ];
+    for (statement, policy) in cases {
+        let program = format!("{}\nT:after", statement);
+        let mut turtle = TurtleState::new();
+
+        let mut open = Interpreter::new();
+        open.load_program(&program).unwrap();
+        let output = open.execute(&mut turtle).unwrap();
+        assert!(!output.iter().any(|l| l.contains("disabled by policy")), "{:?}", output);
+
+        let mut locked = Interpreter::new();
+        locked.set_policy(policy).unwrap();
+        locked.load_program(&program).unwrap();
+        let output = locked.execute(&mut turtle).unwrap();
+        assert!(output[0].contains("is disabled by policy"), "{}: {:?}", statement, output);
+        assert_eq!(output.last().map(String::as_str), Some("after"));
+    }
+}
+
+#[test]
+fn test_security_policy_workspace_and_iteration_limit() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let policy = SecurityPolicy {
+        workspace_path: Some("/class".into()),
+        max_iterations: Some(50),
+        ..SecurityPolicy::default()
+    };
+    interp.set_policy(policy).unwrap();
+    assert!(interp.policy().is_restricted());
+
+    interp.load_program("R:LOAD ../../etc/passwd\nL:LOOP\nJ:LOOP").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("outside the workspace"), "{:?}", output);
+    assert!(output.iter().any(|l| l.contains("Maximum iterations")));
+}
+
+#[test]
+fn test_security_policy_fixed_while_waiting_for_input() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("A:NAME\nT:Hi *NAME*").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert!(interp.pending_input.is_some());
+    assert!(interp.set_policy(SecurityPolicy::restricted()).is_err());
+    assert!(!interp.policy().is_restricted());
+}
+
+#[test]
+fn test_validated_input_reprompts_until_in_range() {
+    use std::sync::{Arc, Mutex};
+
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let prompts = Arc::new(Mutex::new(Vec::new()));
+    let seen = prompts.clone();
+    let mut answers = vec!["abc", "200", "42"].into_iter().map(String::from);
+    interp.input_callback = Some(Box::new(move |prompt| {
+        seen.lock().unwrap().push(prompt.to_string());
+        answers.next().unwrap_or_default()
+    }));
+
+    let program = "10 INPUT \"Age (1-120)\"; AGE AS NUMBER RANGE 1 TO 120\n20 PRINT AGE";
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(*prompts.lock().unwrap(), vec!["Age (1-120)? "; 3]);
+    assert_eq!(interp.variables["AGE"], 42.0);
+    assert_eq!(output, vec!["⚠️ must be between 1 and 120", "⚠️ must be between 1 and 120", "42"]);
+}
+
+#[test]
+fn test_validated_input_raises_after_retries() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let mut calls = 0;
+    interp.input_callback = Some(Box::new(move |_| {
+        calls += 1;
+        format!("x{}", calls)
+    }));
+    interp.limits.max_input_retries = 2;
+
+    let program = "10 INPUT N AS NUMBER\n20 PRINT ERR; ERL";
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output.iter().filter(|l| l.starts_with("⚠️")).count(), 2);
+    assert!(output[2].contains("INPUT N: no valid answer after 3 tries (must be a number)"), "{:?}", output);
+    assert_eq!(interp.variables["ERR"], 31.0);
+    assert_eq!(interp.variables["ERL"], 10.0);
+}
+
+#[test]
+fn test_validated_input_ui_path_counts_retries() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.limits.max_input_retries = 1;
+
+    interp.load_program("10 INPUT \"Pick\", P RANGE 1 TO 3\n20 PRINT P").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    let request = interp.pending_input.clone().unwrap();
+    assert_eq!(request.prompt, "Pick");
+    assert_eq!(request.validation.unwrap().min, Some(1.0));
+
+    interp.provide_input("9");
+    let feedback = interp.pending_input.as_ref().and_then(|r| r.validation.as_ref()?.feedback.clone());
+    assert_eq!(feedback.as_deref(), Some("must be between 1 and 3"));
+    interp.execute(&mut turtle).unwrap();
+    assert!(interp.pending_input.is_some(), "still waiting after one wrong answer");
+
+    interp.provide_input("0");
+    assert!(interp.pending_input.is_none());
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("INPUT P: no valid answer after 2 tries")), "{:?}", output);
+
+    // A right answer on the UI path assigns the number
+    interp.load_program("10 INPUT P RANGE 1 TO 3\n20 PRINT P").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    interp.provide_input("2");
+    assert_eq!(interp.execute(&mut turtle).unwrap().last().map(String::as_str), Some("2"));
+}
+
+/// Run a Logo program and return the drawn segment endpoints, rounded
+fn drawn_segments(interp: &mut Interpreter, program: &str) -> Vec<[i32; 4]> {
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap();
+    turtle.lines.iter()
+        .map(|l| [l.start.x, l.start.y, l.end.x, l.end.y].map(|v| v.round() as i32))
+        .collect()
+}
+
+#[test]
+fn test_math_angle_mode_draws_same_square() {
+    let compass = drawn_segments(&mut Interpreter::new(), "SETHEADING 90\nREPEAT 4 [FORWARD 50 LEFT 90]");
+    assert_eq!(compass[0], [0, 0, 50, 0]);
+
+    // Math mode: east is 0, so the same square starts from SETHEADING 0
+    let mut interp = Interpreter::new();
+    interp.angle_mode = time_warp_unified::graphics::AngleMode::Math;
+    assert_eq!(drawn_segments(&mut interp, "SETHEADING 0\nREPEAT 4 [FORWARD 50 LEFT 90]"), compass);
+
+    // SETANGLEMODE switches within a program
+    let switched = drawn_segments(&mut Interpreter::new(), "SETANGLEMODE MATH\nSETHEADING 0\nREPEAT 4 [FORWARD 50 LEFT 90]");
+    assert_eq!(switched, compass);
+}
+
+#[test]
+fn test_math_angle_mode_reports_heading_and_towards() {
+    let mut turtle = TurtleState::new();
+    let mut interp = Interpreter::new();
+    interp.load_program("HEADING\nRIGHT 90\nHEADING\nTOWARDS 0 -10\nSETANGLEMODE MATH\nHEADING\nLEFT 45\nHEADING\nTOWARDS 0 -10\nSETHEADING TOWARDS -10 0\nHEADING\nLOGVAR HEADING").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    let reports: Vec<&str> = output.iter().map(String::as_str)
+        .filter(|l| l.starts_with("HEADING ") || l.starts_with("TOWARDS ")).collect();
+    assert_eq!(reports, vec![
+        "HEADING 0", "HEADING 90", "TOWARDS 0",
+        "HEADING 0", "HEADING 45", "TOWARDS 90", "HEADING 180",
+    ]);
+    assert!((turtle.heading - 270.0).abs() < 0.01, "stored as a compass bearing: {}", turtle.heading);
+    assert_eq!(interp.recorded_series["HEADING"], vec![180.0]);
+
+    assert!(interp.load_program("SETANGLEMODE RADIANS").is_ok());
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("SETANGLEMODE expects COMPASS or MATH")), "{:?}", output);
+}
+
+#[test]
+fn test_arc_follows_angle_mode() {
+    // Compass: positive ARC sweeps clockwise from north towards east
+    let compass = drawn_segments(&mut Interpreter::new(), "ARC 90 20");
+    assert_eq!(compass.first().map(|s| [s[0], s[1]]), Some([0, -20]));
+    assert_eq!(compass.last().map(|s| [s[2], s[3]]), Some([20, 0]));
+
+    // Math: the same quarter circle is drawn from east sweeping counter-clockwise back to north
+    let math = drawn_segments(&mut Interpreter::new(), "SETANGLEMODE MATH\nSETHEADING 0\nARC 90 20");
+    assert_eq!(math.first().map(|s| [s[0], s[1]]), Some([20, 0]));
+    assert_eq!(math.last().map(|s| [s[2], s[3]]), Some([0, -20]));
+    assert_eq!(math.len(), compass.len());
+}
+
+#[test]
+fn test_setshape_picks_built_in_and_workspace_shapes() {
+    let (mut interp, _dir) = workspace_interpreter(&[("Kite.pts", "# kite\n0 2\n1 0\n0 -1\n-1 0\n")]);
+    let mut turtle = TurtleState::new();
+    interp.load_program("SETSHAPE \"rocket").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!(turtle.shape, TurtleShape::Rocket);
+
+    // File names keep their case; the points are scaled to the built-in size
+    interp.load_program("SETSHAPE Kite.pts").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!(turtle.shape, TurtleShape::Custom(vec![[0.0, -1.0], [0.5, 0.0], [0.0, 0.5], [-0.5, 0.0]]));
+
+    for (program, error) in [
+        ("SETSHAPE \"DRAGON", "SETSHAPE expects CIRCLE, TRIANGLE, TURTLE, ARROW, ROCKET"),
+        ("SETSHAPE missing.pts", "SETSHAPE cannot read"),
+        ("SETSHAPE ../outside.pts", "outside"),
+    ] {
+        interp.load_program(program).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        assert!(output.iter().any(|l| l.contains(error)), "{}: {:?}", program, output);
+    }
+    assert!(matches!(turtle.shape, TurtleShape::Custom(_)), "a failed SETSHAPE keeps the shape");
+}
+
+/// Interpreter confined to a scratch workspace folder holding `files`; the folder goes with the `TempDir`
+fn workspace_interpreter(files: &[(&str, &str)]) -> (Interpreter, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    for (file, source) in files {
+        std::fs::write(dir.path().join(file), source).unwrap();
+    }
+    let mut interp = Interpreter::new();
+    interp.set_policy(SecurityPolicy { workspace_path: Some(dir.path().to_path_buf()), ..SecurityPolicy::default() }).unwrap();
+    (interp, dir)
+}
+
+#[test]
+fn test_callfile_shares_variables_and_returns() {
+    let (mut interp, _dir) = workspace_interpreter(&[
+        ("shapes.logo", "TO SQUARE :S\nREPEAT 4 [FD :S RT 90]\nEND\nSQUARE 10\nLET SIDES = 4"),
+    ]);
+    let mut turtle = TurtleState::new();
+    interp.load_program("10 LET SIDES = 0\n20 CALLFILE \"shapes.logo\"\n30 PRINT SIDES\n40 SQUARE 5\n50 GOTO 70\n60 PRINT \"SKIPPED\"\n70 PRINT \"DONE\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output, vec!["4", "DONE"]);
+    // The callee's procedure stays defined, and the caller's line numbers still work
+    assert_eq!(turtle.lines.len(), 8);
+}
+
+#[test]
+fn test_callfile_recursion_is_limited() {
+    let (mut interp, _dir) = workspace_interpreter(&[
+        ("a.pilot", "LET DEPTH = DEPTH + 1\nCALLFILE \"b.pilot\""),
+        ("b.pilot", "CALLFILE a.pilot"),
+    ]);
+    let mut turtle = TurtleState::new();
+    interp.load_program("LET DEPTH = 0\nCALLFILE \"a.pilot\"\nT:after").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert!(output.iter().any(|l| l.contains("nested more than 8 files deep (a.pilot → b.pilot")), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("after"));
+    assert_eq!(interp.variables["DEPTH"], 4.0);
+
+    interp.load_program("CALLFILE \"../outside.logo\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.iter().any(|l| l.contains("outside the workspace")), "{:?}", output);
+}
+
+#[test]
+fn test_chain_keeps_common_variables() {
+    let (mut interp, _dir) = workspace_interpreter(&[
+        ("menu.bas", "10 PRINT SCORE\n20 PRINT NAME$\n30 LET SEEN = LIVES"),
+    ]);
+    let mut turtle = TurtleState::new();
+    interp.load_program("10 COMMON SCORE, NAME$\n20 LET SCORE = 42\n30 LET NAME$ = \"ADA\"\n40 LET LIVES = 3\n50 CHAIN \"menu.bas\"\n60 PRINT \"NOT REACHED\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[..2], ["42", "ADA"]);
+    assert!(!output.iter().any(|l| l == "NOT REACHED"));
+    // LIVES was not COMMON, so it reads as an unset 0
+    assert_eq!(interp.variables["SEEN"], 0.0);
+    assert!(output.iter().any(|l| l.ends_with("LIVES is used before it is set, so it reads as 0")), "{:?}", output);
+
+    interp.load_program("10 LET LIVES = 3\n20 CHAIN \"menu.bas\", ALL").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    assert_eq!(interp.variables["SEEN"], 3.0);
+
+    interp.classic_errors = true;
+    interp.load_program("10 CHAIN \"missing.bas\"\n20 PRINT ERR").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec!["?FILE NOT FOUND ERROR IN 10", "53"]);
+}
+
+#[test]
+fn test_callfile_and_chain_find_files_beside_the_program_not_the_current_folder() {
+    let program = tempfile::tempdir().unwrap();
+    let elsewhere = tempfile::tempdir().unwrap();
+    std::fs::create_dir(program.path().join("lib")).unwrap();
+    for (file, source) in [
+        ("main.bas", "10 PRINT \"MAIN\"\n20 CALLFILE \"lib/shapes.pilot\"\n30 CHAIN \"next.bas\""),
+        // A called file's own CALLFILEs resolve beside it
+        ("lib/shapes.pilot", "T:SHAPES\nCALLFILE \"helper.pilot\""),
+        ("lib/helper.pilot", "T:HELPER"),
+        ("next.bas", "10 PRINT \"NEXT\""),
+    ] {
+        std::fs::write(program.path().join(file), source).unwrap();
+    }
+    let run = std::process::Command::new(env!("CARGO_BIN_EXE_time-warp"))
+        .arg("--run")
+        .arg(program.path().join("main.bas"))
+        .current_dir(elsewhere.path())
+        .env("HOME", elsewhere.path())
+        .env("XDG_CONFIG_HOME", elsewhere.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    let lines: Vec<&str> = stdout.lines().filter(|l| ["MAIN", "SHAPES", "HELPER", "NEXT"].contains(&l.trim())).collect();
+    assert_eq!(lines, ["MAIN", "SHAPES", "HELPER", "NEXT"], "{}{}", stdout, String::from_utf8_lossy(&run.stderr));
+}
+
+#[test]
+fn test_merge_adds_library_subroutines_by_line_number() {
+    let (mut interp, _dir) = workspace_interpreter(&[
+        ("lib.bas", "@lang basic\n5 PRINT \"BEFORE MERGE\"\n25 PRINT \"FROM LIB 25\"\n9000 PRINT \"HELLO FROM LIB\"\n9010 RETURN\n9100 LET AREA = W * H : RETURN\n"),
+        ("loose.bas", "9000 PRINT \"OK\"\nPRINT \"NO NUMBER\"\n"),
+    ]);
+    let mut turtle = TurtleState::new();
+    interp.load_program("REM @lang basic\n10 MERGE \"lib.bas\"\n20 PRINT \"MAIN 20\"\n30 LET W = 3\n40 LET H = 4\n50 GOSUB 9000\n60 GOSUB 9100\n70 PRINT AREA\n80 END\n9000 PRINT \"OLD 9000\" : PRINT \"OLD TOO\"\n9010 RETURN").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec![
+        "⚠️ MERGE \"lib.bas\" replaced lines 9000, 9010", "MAIN 20", "FROM LIB 25", "HELLO FROM LIB", "12",
+    ]);
+    let numbers: Vec<usize> = interp.program_lines.iter().filter_map(|(num, _)| *num).collect();
+    // Both statements of the old 9000 were replaced; the merged 9100 keeps its two
+    assert_eq!(numbers, vec![5, 10, 20, 25, 30, 40, 50, 60, 70, 80, 9000, 9010, 9100, 9100]);
+
+    interp.load_program("10 MERGE \"loose.bas\"\n20 PRINT \"AFTER\"").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("MERGE \"loose.bas\" line 2: only numbered lines can be merged"), "{:?}", output);
+    assert!(!interp.program_lines.iter().any(|(num, _)| *num == Some(9000)), "nothing merged from a bad file");
+}
+
+// ============================================================================
+// .twpak bundles
+// ============================================================================
+
+#[test]
+fn test_twpak_round_trip_runs_entry() {
+    use time_warp_unified::utils::twpak::{build_pak, collect_project, extract_pak, read_pak};
+
+    let base = tempfile::tempdir().unwrap();
+    let project = base.path().join("project");
+    std::fs::create_dir_all(project.join("lib")).unwrap();
+    std::fs::write(
+        project.join("lesson.bas"),
+        "REM @title Squares\n10 LET N = 3\n20 CALLFILE \"lib/square.bas\"\n30 PRINT A\n",
+    ).unwrap();
+    std::fs::write(project.join("lib/square.bas"), "10 LET A = N * N\n").unwrap();
+    std::fs::write(project.join("notes.txt"), "not referenced").unwrap();
+
+    let files = collect_project(&project, "lesson.bas");
+    assert_eq!(files, vec!["lesson.bas", "lib/square.bas"]);
+    let bytes = build_pak(&project, "lesson.bas", &files).unwrap();
+
+    let (manifest, _) = read_pak(&bytes).unwrap();
+    assert_eq!(manifest.entry, "lesson.bas");
+    assert_eq!(manifest.language, "basic");
+    assert_eq!(manifest.title.as_deref(), Some("Squares"));
+    assert_eq!(manifest.files, vec!["lesson.bas", "lib/square.bas"]);
+
+    let pak = extract_pak(&bytes, &base.path().join("unpacked")).unwrap();
+    assert!(!pak.root.join("notes.txt").exists());
+    let mut interp = Interpreter::new();
+    interp.set_policy(SecurityPolicy { workspace_path: Some(pak.root.clone()), ..SecurityPolicy::default() }).unwrap();
+    let mut turtle = TurtleState::new();
+    interp.load_program(&std::fs::read_to_string(&pak.entry_path).unwrap()).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output.last().map(String::as_str), Some("9"), "{:?}", output);
+}
+
+#[test]
+fn test_twpak_rejects_escaping_entries() {
+    use time_warp_unified::utils::twpak::{build_pak, PakError};
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.pilot"), "T:hi").unwrap();
+    let result = build_pak(dir.path(), "main.pilot", &["../secret.txt".to_string()]);
+    assert!(matches!(result, Err(PakError::UnsafePath(_))));
+}
+
+// ============================================================================
+// Line continuation (` _`)
+// ============================================================================
+
+#[test]
+fn test_continued_if_then_else() {
+    let program = "10 LET X = 7\n20 IF X > 5 _\n   THEN PRINT \"BIG\" _\n   ELSE PRINT \"SMALL\"\n30 LET X = 2\n40 IF X > 5 THEN PRINT \"BIG\" ELSE 60\n50 PRINT \"SKIPPED\"\n60 PRINT \"DONE\"";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output, vec!["BIG", "DONE"]);
+    assert_eq!(interp.program_lines.len(), 6);
+    assert_eq!(interp.source_lines[1], 2..=4);
+    assert_eq!(interp.source_lines[2], 5..=5);
+}
+
+#[test]
+fn test_broken_continued_statement_reports_first_line() {
+    let program = "10 PRINT \"A\"\n20 FOR I = 1 _\n   5\n30 PRINT \"B\"";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output.len(), 3, "{:?}", output);
+    assert_eq!(output[1], "❌ Error at line 2: FOR missing TO");
+    assert_eq!(output[2], "B");
+    assert_eq!(interp.error_spans, vec![2..=3]);
+    assert_eq!(interp.variables.get("ERL"), Some(&20.0));
+}
+
+#[test]
+fn test_underscore_without_continuation() {
+    let program = "10 LET NAME_ = 4\n20 PRINT \"UNDER _\"\n30 PRINT NAME_";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(interp.program_lines.len(), 3);
+    assert_eq!(output[0], "UNDER _");
+
+    use time_warp_unified::interpreter::continued_line;
+    assert_eq!(continued_line("PRINT X + _"), Some("PRINT X + "));
+    assert_eq!(continued_line("PRINT \"A _"), None, "inside a string");
+    assert_eq!(continued_line("LET A_"), None, "part of a name");
+}
+
+const QUIZ: &str = "T:Quiz time\nQZ:\nT:Final *RIGHT*/*WRONG*\nE:\n\
+D:Capital of France?|Paris|Paris sits on the Seine.\n\
+D:2 + 2?|4,four\n\
+D:Largest planet?|Jupiter|Jupiter is a gas giant.";
+
+#[test]
+fn test_quiz_scores_scripted_answers() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    let mut answers = vec!["it's paris".to_string(), "Four".to_string(), "Saturn".to_string()].into_iter();
+    interp.input_callback = Some(Box::new(move |_| answers.next().unwrap_or_default()));
+    interp.load_program(QUIZ).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+
+    assert_eq!(output, vec![
+        "Quiz time",
+        "Capital of France?", "Correct!", "Paris sits on the Seine.",
+        "2 + 2?", "Correct!",
+        "Largest planet?", "Not quite. The answer is Jupiter.", "Jupiter is a gas giant.",
+        "Score: 2 out of 3",
+        "Final 2/1",
+    ]);
+    assert_eq!(interp.variables.get("RIGHT"), Some(&2.0));
+}
+
+#[test]
+fn test_quiz_pauses_for_each_answer_without_callback() {
+    // QZ: on the first line re-runs at line 0 after every answer
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(QUIZ.trim_start_matches("T:Quiz time\n")).unwrap();
+    let mut output = interp.execute(&mut turtle).unwrap();
+    for answer in ["London", "4", "jupiter"] {
+        assert!(interp.pending_input.is_some());
+        interp.provide_input(answer);
+        output = interp.execute(&mut turtle).unwrap();
+    }
+
+    assert!(interp.pending_input.is_none());
+    assert_eq!(output[0], "Capital of France?");
+    assert_eq!(output[1], "Not quite. The answer is Paris.");
+    assert_eq!(output.iter().filter(|l| *l == "Correct!").count(), 2);
+    assert_eq!(output[output.len() - 2..], ["Score: 2 out of 3", "Final 2/1"]);
+}
+
+#[test]
+fn test_quiz_shuffle_is_seedable() {
+    let questions = |seed: u64| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.input_callback = Some(Box::new(|_| String::new()));
+        let program = QUIZ.replace("QZ:", &format!("QZ:SHUFFLE {}", seed));
+        interp.load_program(&program).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        output.into_iter().filter(|l| l.ends_with('?')).collect::<Vec<_>>()
+    };
+
+    let first = questions(7);
+    assert_eq!(first.len(), 3);
+    assert_eq!(first, questions(7), "same seed, same order");
+    assert!((0..20).any(|seed| questions(seed) != first), "other seeds reorder");
+}
+
+#[test]
+fn test_run_seed_repeats_random_output_and_drawing() {
+    let run = |seed: Option<u64>| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.input_callback = Some(Box::new(|_| String::new()));
+        interp.run_seed = seed;
+        let program = "10 FOR I = 1 TO 5\n20 PRINT INT(RND() * 1000)\n30 LINE 0, 0, RND() * 100, RND() * 100\n40 NEXT I";
+        interp.load_program(program).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        let ends: Vec<(f32, f32)> = turtle.lines.iter().map(|l| (l.end.x, l.end.y)).collect();
+        (output, ends, interp.random_seed())
+    };
+
+    let (output, drawing, seed) = run(Some(42));
+    assert_eq!(seed, 42);
+    assert_eq!(output.len(), 5);
+    assert_eq!(run(Some(42)), (output.clone(), drawing.clone(), 42), "same seed, same run");
+    assert_ne!(run(Some(43)).0, output, "another seed, other numbers");
+
+    // Without a fixed seed each run picks its own, and that seed repeats it
+    let (free_output, free_drawing, free_seed) = run(None);
+    assert_eq!(run(Some(free_seed)), (free_output, free_drawing, free_seed));
+
+    // RANDOMIZE n restarts the numbers, whatever the run's seed; QZ:SHUFFLE draws from the same generator
+    let randomized = |seed: u64| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.run_seed = Some(seed);
+        interp.load_program("10 RANDOMIZE 5\n20 PRINT INT(RND() * 1000)").unwrap();
+        interp.execute(&mut turtle).unwrap()
+    };
+    assert_eq!(randomized(1), randomized(2));
+    let report = Interpreter::run_isolated(
+        &QUIZ.replace("QZ:", "QZ:SHUFFLE"),
+        IsolatedRunOptions { random_seed: Some(9), inputs: vec![String::new(); 3], ..IsolatedRunOptions::default() },
+    );
+    assert_eq!(report.random_seed, Some(9));
+    assert_eq!(Interpreter::run_isolated("T:no dice", IsolatedRunOptions::default()).random_seed, None);
+}
+
+#[test]
+fn test_quiz_without_questions_is_an_error() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("QZ:\nD:broken line|\nT:after").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output[0].contains("QZ: no D: questions"), "{:?}", output);
+    assert!(output[1].contains("D: needs question|answer"), "{:?}", output);
+    assert_eq!(output[2], "after");
+}
+
+/// Output of a BASIC program timing a `count`-pass loop on a one-second-per-statement clock
+fn timed_loop(count: usize) -> Vec<String> {
+    let start = chrono::NaiveDate::from_ymd_opt(1985, 7, 4).unwrap().and_hms_opt(9, 30, 0).unwrap();
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.set_clock(FakeClock::new(start, Duration::from_secs(1)));
+    let program = format!(
+        "10 LET T1 = TIMER\n20 FOR I = 1 TO {}\n30 NEXT I\n40 PRINT TIMER - T1\n50 LET D$ = DATE$\n60 PRINT D$\n70 PRINT TIME$",
+        count
+    );
+    interp.load_program(&program).unwrap();
+    interp.execute(&mut turtle).unwrap()
+}
+
+/// A clock whose statements take `steps` milliseconds in turn, like frames of uneven length
+struct JitterClock {
+    steps: Vec<u64>,
+    next: usize,
+    elapsed: Duration,
+}
+
+impl Clock for JitterClock {
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn now(&self) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::default() + self.elapsed
+    }
+
+    fn tick(&mut self) {
+        self.elapsed += Duration::from_millis(self.steps[self.next % self.steps.len()]);
+        self.next += 1;
+    }
+}
+
+/// Runs of a 50 ms EVERY handler, the sum of its DELTAs, and the seconds it was scheduled for
+fn ticked(steps: &[u64]) -> (f64, f64, f64) {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.set_clock(JitterClock { steps: steps.to_vec(), next: 0, elapsed: Duration::ZERO });
+    interp.load_program("REM @lang basic\n5 LET RUNS = 0\n6 LET TOTAL = 0\n10 EVERY 0.05 GOSUB 1000\n20 LET T0 = TIMER\n30 FOR I = 1 TO 400\n40 NEXT I\n50 LET T1 = TIMER\n60 EVERY OFF\n70 PRINT RUNS\n80 PRINT TOTAL\n90 PRINT T1 - T0\n100 END\n1000 LET RUNS = RUNS + 1\n1010 LET TOTAL = TOTAL + DELTA\n1020 RETURN").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    let value = |i: usize| output[i].parse::<f64>().unwrap_or_else(|_| panic!("{:?}", output));
+    (value(0), value(1), value(2))
+}
+
+#[test]
+fn test_every_keeps_time_on_jittery_frames() {
+    // Statements of 2-15 ms: every 50 ms owed is run, and the DELTAs add up to the time covered
+    let (runs, total, elapsed) = ticked(&[2, 9, 4, 15]);
+    assert!(runs >= 100.0, "{} runs in {} s", runs, elapsed);
+    assert!((runs - (elapsed / 0.05).floor()).abs() <= 1.0, "{} runs in {} s", runs, elapsed);
+    assert!((total - runs * 0.05).abs() < 0.05, "DELTAs sum to {} over {} runs", total, runs);
+    assert_eq!(ticked(&[2, 9, 4, 15]), (runs, total, elapsed), "repeatable on a scripted clock");
+
+    // A 1 s stall every 40 statements owes 20 runs, but only 5 are made up each time
+    let mut stalled = vec![5; 39];
+    stalled.push(1000);
+    let (runs, total, elapsed) = ticked(&stalled);
+    let stalls = (elapsed / 1.195).floor();
+    assert!(runs < elapsed / 0.05 / 2.0, "{} runs in {} s", runs, elapsed);
+    assert!(runs >= stalls * 5.0, "{} runs after {} stalls", runs, stalls);
+    assert!(total > runs * 0.05, "DELTA still counts the dropped time: {} over {} runs", total, runs);
+}
+
+#[test]
+fn test_timer_measures_a_loop_on_a_fake_clock() {
+    let ten = timed_loop(10);
+    let twenty = timed_loop(20);
+    assert_eq!(ten, timed_loop(10), "a fake clock makes runs repeatable");
+    let seconds = |output: &[String]| output[0].parse::<f64>().unwrap();
+    // FOR, ten NEXTs, and the PRINT after T1 was read; each extra pass is one more NEXT
+    assert_eq!(seconds(&ten), 12.0);
+    assert_eq!(seconds(&twenty), 22.0);
+    // Sixteen statements have run by the last PRINT
+    assert_eq!(ten[1..], ["07-04-1985", "09:30:16"]);
+}
+
+#[test]
+fn test_clock_strings_work_inside_string_expressions() {
+    let start = chrono::NaiveDate::from_ymd_opt(1985, 7, 4).unwrap().and_hms_opt(12, 5, 0).unwrap();
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.set_clock(FakeClock::new(start, Duration::ZERO));
+    interp.load_program("10 PRINT \"Today: \" + DATE$\n20 IF LEFT$(TIME$,2) = \"12\" THEN PRINT \"NOON\"\n30 LET Y$ = RIGHT$(DATE$, 4)\n40 PRINT Y$").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output, vec!["Today: 07-04-1985", "NOON", "1985"]);
+}
+
+#[test]
+fn test_real_clock_functions_are_well_formed() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("10 PRINT DATE$\n20 PRINT TIME$\n30 PRINT TIMER >= 0").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    let shape = |text: &str| text.chars().map(|c| if c.is_ascii_digit() { '9' } else { c }).collect::<String>();
+    assert_eq!(shape(&output[0]), "99-99-9999");
+    assert_eq!(shape(&output[1]), "99:99:99");
+    assert_eq!(output[2], "-1");
+}
+
+#[test]
+fn test_detect_language_by_majority_of_statements() {
+    assert_eq!(Interpreter::detect_language("10 PRINT \"HI\"\n20 GOTO 10\n30 END"), Some(Language::Basic));
+    assert_eq!(Interpreter::detect_language("TO SQUARE\nREPEAT 4 [FD 50 RT 90]\nEND\nSQUARE"), Some(Language::Logo));
+    assert_eq!(Interpreter::detect_language("R: Greeting\nT:Hello\nA:$NAME\nT:Hi *NAME*"), Some(Language::Pilot));
+    assert_eq!(Interpreter::detect_language("REM @lang logo\nPRINT 1"), Some(Language::Logo));
+    // Nothing that names a language, or an even split
+    assert_eq!(Interpreter::detect_language("SQUARE\n\n"), None);
+    assert_eq!(Interpreter::detect_language("T:Hi\nFD 10"), None);
+}
+
+/// Output of `program` answering every question or `A:` with `answer`
+fn answered(program: &str, answer: &'static str) -> Vec<String> {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.input_callback = Some(Box::new(move |_| answer.to_string()));
+    interp.load_program(program).unwrap();
+    interp.execute(&mut turtle).unwrap()
+}
+
+#[test]
+fn test_quiz_spelling_tolerance_and_exact_matching() {
+    let tolerant = "QZ:\nE:\nD:Capital of France?|%80 Paris";
+    assert_eq!(answered(tolerant, "pariss"), vec!["Capital of France?", "Correct!", "Score: 1 out of 1"]);
+    assert_eq!(answered(tolerant, "Rome")[1], "Not quite. The answer is Paris.");
+
+    let exact = "MC:EXACT\nQZ:\nE:\nD:Capital of France?|Paris";
+    assert_eq!(answered(exact, "pariss")[1], "Not quite. The answer is Paris.");
+    assert_eq!(answered(exact, "  PARIS. ")[1], "Correct!");
+    // Without EXACT the answer only has to contain the pattern
+    assert_eq!(answered("QZ:\nE:\nD:Capital of France?|Paris", "pariss")[1], "Correct!");
+}
+
+#[test]
+fn test_match_control_applies_to_m() {
+    // T: right after M: prints only on a match
+    let program = "A:CITY\nM:paris\nT:contains\nMC:EXACT -FOLD\nM:paris\nT:exact\nMC:\nM:%80 PARIS\nT:close";
+    assert_eq!(answered(program, "Pariss!"), vec!["contains", "close"]);
+    assert!(answered("MC:SHOUT", "x")[0].contains("MC: unknown option 'SHOUT'"));
+}
+
+#[test]
+fn test_runs_are_traced_with_jumps_and_errors() {
+    use time_warp_unified::utils::diagnostics::LogBuffer;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let buffer = LogBuffer::new(100);
+    let program = "REM @lang basic\n10 GOTO 30\n20 PRINT \"skipped\"\n30 FOR I = 1\n40 END";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(buffer.clone()), || {
+        interp.load_program(program).unwrap();
+        let _ = interp.execute(&mut turtle);
+    });
+
+    let entries = buffer.entries();
+    let run = format!("program={}", interp.program_hash);
+    assert!(entries.iter().all(|e| e.spans.starts_with("run{language=BASIC") && e.spans.contains(&run)), "{:?}", entries);
+    // Lines are counted in the source, as the editor shows them
+    assert!(entries.iter().any(|e| e.message == "jump from=2 to=4"), "{:?}", entries);
+    assert!(entries.iter().any(|e| e.message.starts_with("error line=4")), "{:?}", entries);
+}
+
+#[test]
+fn test_tic_tac_toe_grid_in_a_two_dimensional_array() {
+    // Moves are READ into a 3x3 grid; a string array turns cells into marks
+    let program = "REM @lang basic
+10 DIM B(2, 2), S$(2)
+20 LET S$(0) = \".\"
+30 LET S$(1) = \"X\"
+40 LET S$(2) = \"O\"
+50 FOR M = 1 TO 5
+60 READ P, R, C
+70 LET B(R, C) = P
+80 NEXT M
+90 DATA 1, 0, 0, 2, 1, 1, 1, 0, 1, 2, 2, 2, 1, 0, 2
+100 FOR R = 0 TO 2
+110 PRINT S$(B(R, 0)), S$(B(R, 1)), S$(B(R, 2))
+120 IF B(R, 0) * B(R, 1) * B(R, 2) = 1 THEN PRINT \"X wins row\", R
+130 NEXT R
+140 LET B(3, 0) = 1
+150 END";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[..4], ["X X X", "X wins row 0", ". O .", ". . O"]);
+    assert_eq!(output[4], "❌ Error at line 15: Subscript out of range: B(3, 0) (dimension 1 runs 0 to 2)");
+    let grid = &interp.arrays["B"];
+    assert_eq!((grid.extents(), grid.peek(4)), (vec![3, 3], "1, 1, 1, 0, …".to_string()));
+}
+
+#[test]
+fn test_option_base_1_with_read_and_input_into_elements() {
+    let program = "REM @lang basic
+10 OPTION BASE 1
+20 DIM N$(3), T(3)
+30 FOR I = 1 TO 3
+40 READ N$(I), T(I)
+50 NEXT I
+60 DATA \"Ada\", 90, \"Grace\", 85, \"Alan\", 70
+70 INPUT \"Who else\"; N$(3)
+80 PRINT N$(1), T(1), N$(3), T(3)
+90 PRINT T(0)
+100 OPTION BASE 0
+110 READ X";
+    let output = answered(program, "Barbara");
+    assert_eq!(output[0], "Ada 90 Barbara 70");
+    assert_eq!(output[1], "❌ Error at line 10: Subscript out of range: T(0) (dimension 1 runs 1 to 3)");
+    assert_eq!(output[2], "❌ Error at line 11: OPTION BASE must come before the first DIM");
+    assert!(output[3].ends_with("Out of DATA"), "{:?}", output);
+}
+
+#[test]
+fn test_read_mixed_data_lines_and_restore() {
+    let program = "REM @lang basic
+10 READ NAME$, AGE, CITY$
+20 READ SCORE
+30 PRINT NAME$, AGE, CITY$, SCORE
+40 RESTORE 110
+50 READ CITY$, SCORE
+60 RESTORE
+70 READ FIRST$
+80 PRINT FIRST$, CITY$, SCORE
+85 RESTORE 120
+90 READ A, B, C
+95 PRINT \"STILL RUNNING\"
+100 DATA \"Ada\", 36
+110 DATA London, 97
+120 DATA 1, 2";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[0], "Ada 36 London 97");
+    assert_eq!(output[1], "Ada London 97");
+    assert!(output[2].ends_with("Out of DATA"), "{:?}", output);
+    assert_eq!(output[3], "STILL RUNNING", "running out of DATA is not fatal");
+}
+
+#[test]
+fn test_warnings_do_not_stop_the_run() {
+    let program = "REM @lang basic
+10 PRINT COUNT + 1
+20 LET NAME = \"Ada\"
+30 FOR I = 1 TO 2
+40 PRINT I
+50 NEXT I
+60 LET PASS = PASS + 1
+70 IF PASS < 2 THEN 40
+80 PRINT \"END\"";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[..5], [
+        "1",
+        "⚠️ Warning at line 2: COUNT is used before it is set, so it reads as 0",
+        "⚠️ Warning at line 3: NAME gets the text \"Ada\"; name it NAME$ to hold text",
+        "1",
+        "2",
+    ]);
+    // PASS is reported once: reading it set it to 0
+    assert_eq!(output[5], "⚠️ Warning at line 7: PASS is used before it is set, so it reads as 0");
+    assert_eq!(output[6], "⚠️ Warning at line 8: GOTO 40 jumps into the body of `FOR I = 1 TO 2`, which is not running");
+    assert!(output[8].starts_with("❌ Error at line 6: NEXT without FOR"), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("END"));
+    assert_eq!(interp.diagnostics.len(), 4);
+    assert_eq!(interp.error_spans, vec![6..=6]);
+
+    interp.set_policy(SecurityPolicy { max_iterations: Some(50), ..SecurityPolicy::default() }).unwrap();
+    interp.load_program("REM @lang basic\n10 LET X = X + 1\n20 GOTO 10").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert!(output.contains(&"⚠️ Warning at line 2: 80% of the 50 statement limit used; the run stops at the limit".to_string()), "{:?}", output);
+    assert_eq!(output.last().map(String::as_str), Some("⚠️ Warning: Maximum iterations reached"));
+}
+
+#[test]
+fn test_strict_mode_promotes_chosen_warnings_to_errors() {
+    use time_warp_unified::interpreter::warnings::{Severity, WarningKind};
+    let program = "REM @lang basic\n10 PRINT COUNT + 1\n20 LET NAME = \"Ada\"\n30 PRINT \"END\"";
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.strict_warnings = [WarningKind::UndefinedVariable, WarningKind::StringForNumber].into();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[0], "❌ Error at line 2: COUNT is used before it is set (strict mode)");
+    assert!(output[1].starts_with("❌ Error at line 3: NAME gets the text \"Ada\"; name it NAME$ to hold text (strict mode)"), "{:?}", output);
+    assert_eq!(output[2], "END");
+    assert!(!interp.string_variables.contains_key("NAME"));
+    let promoted: Vec<_> = interp.diagnostics.iter().map(|d| (d.severity, d.kind)).collect();
+    assert_eq!(promoted, [(Severity::Error, WarningKind::UndefinedVariable), (Severity::Error, WarningKind::StringForNumber)]);
+    assert_eq!(interp.error_spans, vec![2..=2, 3..=3]);
+
+    // Strict about something else: the rest are still warnings
+    interp.strict_warnings = [WarningKind::JumpIntoLoop].into();
+    interp.load_program(program).unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output.iter().filter(|l| l.starts_with("⚠️ Warning")).count(), 2, "{:?}", output);
+}
+
+#[test]
+fn test_common_variables_outlive_loading_the_next_program() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.load_program("REM @lang basic\n10 COMMON TOTAL, NAME$\n20 LET TOTAL = 42\n30 LET NAME$ = \"Ada\"\n40 LET SCRATCH = 7").unwrap();
+    interp.execute(&mut turtle).unwrap();
+
+    interp.load_program("REM @lang basic\n10 PRINT TOTAL\n20 PRINT NAME$\n30 PRINT SCRATCH").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[..3], ["42", "Ada", "0"]);
+    assert!(output[3].contains("SCRATCH is used before it is set"), "{:?}", output);
+    assert!(interp.is_kept("TOTAL") && !interp.is_kept("SCRATCH"));
+
+    // CLEAR forgets the values and the COMMON list
+    interp.load_program("REM @lang basic\n10 CLEAR\n20 PRINT TOTAL").unwrap();
+    let output = interp.execute(&mut turtle).unwrap();
+    assert_eq!(output[0], "0");
+    assert!(output[1].contains("TOTAL is used before it is set"), "{:?}", output);
+    interp.load_program("REM @lang basic\n10 PRINT NAME$").unwrap();
+    assert!(!interp.string_variables.contains_key("NAME$") && !interp.is_kept("NAME$"));
+}
+
+#[test]
+fn test_keep_variables_carries_everything_into_the_next_run() {
+    let mut interp = Interpreter::new();
+    let mut turtle = TurtleState::new();
+    interp.keep_variables = true;
+    interp.load_program("REM @lang basic\n10 DIM D(2)\n20 LET D(1) = 5\n30 LET N = 3").unwrap();
+    interp.execute(&mut turtle).unwrap();
+    interp.load_program("REM @lang basic\n10 LET N = N + D(1)\n20 PRINT N").unwrap();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["8"]);
+    assert!(interp.is_kept("N"));
+}
+
+#[test]
+fn test_formatting_does_not_change_what_a_program_does() {
+    use time_warp_unified::languages::format::format_source;
+    let run = |source: &str| {
+        let mut interp = Interpreter::new();
+        let mut turtle = TurtleState::new();
+        interp.input_callback = Some(Box::new(|_| "7".to_string()));
+        interp.load_program(source).unwrap();
+        let output = interp.execute(&mut turtle).unwrap();
+        (output, turtle.lines.len(), turtle.x, turtle.y)
+    };
+    let programs = [
+        (
+            "10 let N=3\n20 for I=1 to N\n30 for J=I to N step 2\n40 print I*J,  -J+1, int(J/2)\n50 next J\n60 next I\n70 input \"Guess\"; G\n80 if G>=7 then print \"ok\" else goto 100\n90   print   \"Hello,   world\"\n100 print done!\n110 dim A(3)\n120 let A(1)=N*-2\n130 print A(1) + 1\n",
+            Language::Basic,
+        ),
+        ("to square :size\nrepeat 4 [forward   :size  right 90]\nend\nsquare 40\nrepeat 3 [ _\nfd 20 _\nrt 120 _\n]\nsetxy  10   20\n", Language::Logo),
+        ("T:   Hello\nA:   name\nM:  7\nY:   match\nN:   no match\nC:   x=1\nJ:   done\nT: skipped\n*done\nT:Bye  *name*\n", Language::Pilot),
+        ("T:  mixed\nlet x=2+3\nprint x*2\nfd   x\nrt 90\n", Language::TempleCode),
+    ];
+    for (source, language) in programs {
+        let formatted = format_source(source, language);
+        assert_ne!(formatted, source);
+        assert_eq!(run(&formatted), run(source), "{}", formatted);
+        assert_eq!(format_source(&formatted, language), formatted);
+    }
+}
+
+#[test]
+fn test_html_export_matches_the_fixture_in_each_theme() {
+    use time_warp_unified::graphics::{Pos2, Rgba, TurtleLine, TurtleText};
+    use time_warp_unified::ui::themes::Theme;
+    use time_warp_unified::utils::html_export::{export_html, output_events, HtmlOptions};
+
+    // Expected page, with `{name}` for each theme color
+    const FIXTURE: &str = include_str!("fixtures/transcript.html");
+    let program = "10 INPUT \"Name\"; N$\n20 PRINT \"Hi <\", N$\n30 PRINT Q\n40 NEXT I";
+    let mut run = time_warp_core::testing::run_program(program, &["Ada & Bo"]);
+    run.turtle.canvas_width = 200.0;
+    run.turtle.canvas_height = 100.0;
+    run.turtle.lines.push(TurtleLine { start: Pos2::new(0.0, 0.0), end: Pos2::new(30.0, -12.5), color: Rgba::RED, width: 2.0 });
+    run.turtle.texts.push(TurtleText { pos: Pos2::new(-50.0, 20.0), text: "A&B".into(), color: Rgba::WHITE, size: 14.0 });
+    let events = output_events(&run.interp);
+    let options = HtmlOptions { title: "Run <1>".into(), show_warnings: true };
+    for theme in [Theme::AmberPhosphor, Theme::ModernLight] {
+        let colors = theme.colors();
+        let mut expected = FIXTURE.to_string();
+        for (name, color) in [
+            ("background", colors.background),
+            ("text", colors.text),
+            ("accent", colors.accent),
+            ("panel", colors.panel),
+            ("warning", colors.warning),
+            ("error", colors.error),
+            ("variable", colors.variable),
+        ] {
+            let [r, g, b, _] = color.to_srgba_unmultiplied();
+            expected = expected.replace(&format!("{{{}}}", name), &format!("#{:02x}{:02x}{:02x}", r, g, b));
+        }
+        assert_eq!(export_html(&events, &run.turtle, &colors, &options, |c| c), expected, "{}", theme.name());
+    }
+    let hidden = export_html(&events, &run.turtle, &Theme::AmberPhosphor.colors(), &HtmlOptions { show_warnings: false, ..options }, |c| c);
+    assert!(!hidden.contains("class=\"warning\""));
+}
+
+#[test]
+fn test_notepad_bom_and_crlf_load_cleanly_and_save_back_as_crlf() {
+    use time_warp_unified::utils::line_endings::{for_save, normalize, LineEnding};
+    use time_warp_unified::utils::safe_open::decode;
+
+    let bytes = include_bytes!("fixtures/notepad_bom_crlf.bas");
+    // The interpreter takes the file as it is
+    let raw = decode(bytes.to_vec()).text;
+    let mut interp = Interpreter::new();
+    interp.load_program(&raw).unwrap();
+    let mut turtle = TurtleState::new();
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["HELLO", "42"]);
+
+    // The editor keeps it clean and remembers the endings
+    let opened = normalize(&raw);
+    assert!(opened.had_bom);
+    assert_eq!(opened.ending, LineEnding::CrLf);
+    assert!(!opened.text.contains(['\r', '\u{feff}']));
+    run!(&opened.text => ["HELLO", "42"]);
+    assert_eq!(for_save(&opened.text, opened.ending, false).as_bytes(), &bytes[3..]);
+}
+
+#[test]
+fn test_msgbox_and_pilot_dialog_branch_on_the_answer() {
+    let basic = "10 MSGBOX \"Saved\"\n20 MSGBOX \"Again?\", YESNO, A\n30 IF A = 1 THEN PRINT \"again\"\n40 MSGBOX \"Sure?\", YESNO, S$\n50 PRINT S$";
+    run_with_input!(basic, ["", "yes", "n"] => ["[dialog] \"Saved\": no answer, chose OK", "again", "NO"]);
+    let pilot = "D:YESNO Play again?\nT:Here we go\nD:YESNO Quit?\nT:Bye\nD:Thanks";
+    run_with_input!(pilot, ["no", "y", "ok"] => ["Bye"]);
+    run_with_input!(pilot, ["yes", "no", "ok"] => ["Here we go"]);
+    // Quiz questions are still D: lines with a |
+    run_with_input!("D:2+2?|4\nQZ:", ["4"] => ["2+2?", "Correct!", "Score: 1 out of 1"]);
+}
+
+#[test]
+fn test_dialog_waits_for_the_ide() {
+    use time_warp_unified::interpreter::InputKind;
+
+    let mut interp = Interpreter::new();
+    interp.load_program("10 MSGBOX \"Go on?\", YESNO, GO\n20 PRINT GO").unwrap();
+    let mut turtle = TurtleState::new();
+    interp.execute(&mut turtle).unwrap();
+    let req = interp.pending_input.clone().expect("the dialog is pending");
+    assert_eq!(req.kind, InputKind::Dialog { yes_no: true });
+    assert_eq!(req.prompt, "Go on?");
+    interp.provide_input("YES");
+    assert_eq!(interp.execute(&mut turtle).unwrap(), ["1"]);
+}
+
+#[test]
+fn test_shell_is_refused_unless_the_policy_allowlists_it() {
+    let output = run!("10 SHELL \"echo hi\"\n20 PRINT \"after\"");
+    assert!(output[0].contains("SHELL is disabled by policy (shell commands are off"), "{:?}", output);
+    assert_eq!(output[1], "after");
+
+    let mut interp = Interpreter::new();
+    let policy = SecurityPolicy { allow_shell: true, shell_allowlist: vec!["echo".into()], ..SecurityPolicy::restricted() };
+    interp.set_policy(policy).unwrap();
+    interp.load_program("10 SHELL \"rm -rf x\"\n20 SHELL \"/bin/echo hi\"").unwrap();
+    let output = interp.execute(&mut TurtleState::new()).unwrap();
+    assert!(output[0].contains("'rm' is not in the shell allowlist"), "{:?}", output);
+    assert!(output[1].contains("'/bin/echo' is not in the shell allowlist"), "{:?}", output);
+    assert!(!interp.variables.contains_key("SHELLRESULT"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shell_runs_an_allowlisted_command() {
+    let run_with = |timeout: u64, program: &str| {
+        let mut interp = Interpreter::new();
+        let policy = SecurityPolicy {
+            allow_shell: true,
+            shell_allowlist: vec!["echo".into(), "sleep".into()],
+            shell_timeout_secs: Some(timeout),
+            workspace_path: Some(std::env::temp_dir()),
+            ..SecurityPolicy::default()
+        };
+        interp.set_policy(policy).unwrap();
+        interp.load_program(program).unwrap();
+        interp.execute(&mut TurtleState::new()).unwrap()
+    };
+    assert_eq!(run_with(10, "10 SHELL \"echo hello   big world\"\n20 PRINT SHELLRESULT"), ["hello big world", "0"]);
+    let output = run_with(0, "10 SHELL \"sleep 5\"\n20 PRINT SHELLRESULT");
+    assert!(output[0].contains("SHELL 'sleep' was stopped after 0 seconds"), "{:?}", output);
+    assert_eq!(output[1], "-1");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shell_does_not_wait_for_what_a_command_leaves_running() {
+    // The background sleep keeps the command's output open after the script ends or is killed
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("quick.sh"), "sleep 30 &\necho started\n").unwrap();
+    std::fs::write(dir.path().join("slow.sh"), "echo waiting\nsleep 30 &\nsleep 30\n").unwrap();
+    let run_with = |timeout: u64, program: &str| {
+        let mut interp = Interpreter::new();
+        let policy = SecurityPolicy {
+            allow_shell: true,
+            shell_allowlist: vec!["sh".into()],
+            shell_timeout_secs: Some(timeout),
+            workspace_path: Some(dir.path().to_path_buf()),
+            ..SecurityPolicy::default()
+        };
+        interp.set_policy(policy).unwrap();
+        interp.load_program(program).unwrap();
+        let started = std::time::Instant::now();
+        let output = interp.execute(&mut TurtleState::new()).unwrap();
+        (output, started.elapsed())
+    };
+    let (output, took) = run_with(10, "10 SHELL \"sh quick.sh\"\n20 PRINT SHELLRESULT");
+    assert_eq!(output, ["started", "0"]);
+    assert!(took < std::time::Duration::from_secs(5), "{:?}", took);
+    let (output, took) = run_with(1, "10 SHELL \"sh slow.sh\"\n20 PRINT SHELLRESULT");
+    assert_eq!(output[0], "waiting");
+    assert!(output[1].contains("SHELL 'sh' was stopped after 1 seconds"), "{:?}", output);
+    assert!(took < std::time::Duration::from_secs(5), "{:?}", took);
+}
+
+/// `(2>1)` is -1 in BASIC and any nonzero condition holds; NaN never does, with a warning
+#[test]
+fn test_truth_convention_in_basic() {
+    let program = "10 PRINT (2>1)\n20 IF -1 THEN PRINT \"minus one\"\n30 IF 0.0001 THEN PRINT \"small\"\n40 IF 0 THEN PRINT \"zero\"\n50 IF SQR(-1) THEN PRINT \"nan\" ELSE PRINT \"not nan\"\n60 PRINT NOT (2>1)\n70 PRINT 12 AND 10\n80 IF (1>0) AND (2>1) THEN PRINT \"both\"";
+    let run = time_warp_core::testing::run_program(program, &[]);
+    let lines: Vec<&str> = run.output.iter().map(|l| l.trim()).filter(|l| !l.starts_with("⚠️")).collect();
+    assert_eq!(lines, ["-1", "minus one", "small", "not nan", "0", "8", "both"]);
+    let kinds: Vec<_> = run.interp.diagnostics.iter().map(|d| (d.kind, d.line)).collect();
+    assert_eq!(kinds, [(WarningKind::NanCondition, 5)]);
+}
+
+/// `(2>1)` is 1 in PILOT, and C:, Y:, and N: take any nonzero number as true
+#[test]
+fn test_truth_convention_in_pilot() {
+    let condition = |c: &str| time_warp_core::testing::run_program(&format!("C:{}", c), &[]).interp.stored_condition;
+    assert_eq!(condition("-1"), Some(true));
+    assert_eq!(condition("0.0001"), Some(true));
+    assert_eq!(condition("0"), Some(false));
+    assert_eq!(condition("2 > 1 AND 3 <> 3"), Some(false));
+    assert_eq!(condition("NOT 2 > 3"), Some(true));
+    let run = time_warp_core::testing::run_program("U:A=(2>1)\nU:B=5 AND 3\nC:SQR(-1)", &[]);
+    assert_eq!((run.interp.variables["A"], run.interp.variables["B"]), (1.0, 1.0));
+    assert_eq!(run.interp.stored_condition, Some(false));
+    assert_eq!(run.interp.diagnostics.iter().map(|d| d.kind).collect::<Vec<_>>(), [WarningKind::NanCondition]);
+    run!("U:X=3\nY:X\nT:nonzero holds\nN:X - 3\nT:zero fails" => ["nonzero holds", "zero fails"]);
+}
+
+/// `(2>1)` is 1 in Logo (Logo has no IF to test)
+#[test]
+fn test_truth_convention_in_logo() {
+    let turtle = run_turtle!("FORWARD (2>1) * 10\nFORWARD (5 AND 3) * 10");
+    assert!((turtle.y + 20.0).abs() < 1e-3, "{}", turtle.y);
+}